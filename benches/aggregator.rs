@@ -0,0 +1,174 @@
+//! 聚合器热路径基准（cargo bench --features bench --bench aggregator）
+//!
+//! 模拟新币上线后的交易突发：少量活跃钱包反复买卖、金额长尾分布、储备随买卖移动
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Duration, Utc};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use solana_sdk::pubkey::Pubkey;
+use solsniper::aggregator::bench_support::BenchWindow;
+use solsniper::{AdvancedMetricsCalculator, PumpFunEvent, PumpFunEventType};
+
+/// 突发规模（事件数）
+const BURST_SIZES: &[usize] = &[100, 500, 2000];
+
+/// 一次突发中的单笔交易
+struct BurstTrade {
+    user: Pubkey,
+    is_buy: bool,
+    sol_amount: u64,
+    token_amount: u64,
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+    timestamp: DateTime<Utc>,
+}
+
+/// 生成确定性的交易突发（xorshift，结果可复现）
+fn trade_burst(events: usize, duration_secs: i64) -> Vec<BurstTrade> {
+    let users: Vec<Pubkey> = (0..64).map(|_| Pubkey::new_unique()).collect();
+    let start = Utc::now() - Duration::seconds(duration_secs);
+    let step_ms = duration_secs * 1000 / events.max(1) as i64;
+
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let mut virtual_sol_reserves = 30_000_000_000u64;
+    let mut virtual_token_reserves = 1_073_000_000_000_000u64;
+
+    (0..events)
+        .map(|i| {
+            let roll = next();
+            // 约 70% 买入；金额 0.01–0.5 SOL 为主，偶有 1–5 SOL 的大单
+            let is_buy = roll % 10 < 7;
+            let sol_amount = if roll % 50 == 0 {
+                1_000_000_000 + next() % 4_000_000_000
+            } else {
+                10_000_000 + next() % 490_000_000
+            };
+            // 卖出不超过当前储备的 10%，保持曲线有效
+            let sol_amount = if is_buy { sol_amount } else { sol_amount.min(virtual_sol_reserves / 10) };
+
+            let k = virtual_sol_reserves as u128 * virtual_token_reserves as u128;
+            let token_amount = if is_buy {
+                let new_sol = virtual_sol_reserves + sol_amount;
+                let new_token = (k / new_sol as u128) as u64;
+                let out = virtual_token_reserves - new_token;
+                virtual_sol_reserves = new_sol;
+                virtual_token_reserves = new_token;
+                out
+            } else {
+                let new_sol = virtual_sol_reserves - sol_amount;
+                let new_token = (k / new_sol as u128) as u64;
+                let input = new_token - virtual_token_reserves;
+                virtual_sol_reserves = new_sol;
+                virtual_token_reserves = new_token;
+                input
+            };
+
+            BurstTrade {
+                user: users[(next() % users.len() as u64) as usize],
+                is_buy,
+                sol_amount,
+                token_amount,
+                virtual_sol_reserves,
+                virtual_token_reserves,
+                timestamp: start + Duration::milliseconds(step_ms * i as i64),
+            }
+        })
+        .collect()
+}
+
+fn bench_mint_window(c: &mut Criterion) {
+    let mint = Pubkey::new_unique();
+    let mut group = c.benchmark_group("mint_window");
+
+    for &size in BURST_SIZES {
+        let burst = trade_burst(size, 30);
+        let now = Utc::now();
+        group.throughput(Throughput::Elements(size as u64));
+
+        // add_event：整段突发写入一个空窗口（含按时间 / 事件数剪枝）
+        group.bench_with_input(BenchmarkId::new("add_event", size), &burst, |b, burst| {
+            b.iter_batched(
+                || BenchWindow::new(mint, 10, 200),
+                |mut window| {
+                    for trade in burst {
+                        window.add_event(trade.user, trade.is_buy, trade.sol_amount, trade.timestamp, now);
+                    }
+                    window
+                },
+                BatchSize::SmallInput,
+            )
+        });
+
+        // add_event + calculate_metrics：每个事件后重新计算指标（与聚合器处理每笔交易一致）
+        group.bench_with_input(BenchmarkId::new("add_event_and_calculate", size), &burst, |b, burst| {
+            b.iter_batched(
+                || BenchWindow::new(mint, 10, 200),
+                |mut window| {
+                    for trade in burst {
+                        window.add_event(trade.user, trade.is_buy, trade.sol_amount, trade.timestamp, now);
+                        black_box(window.calculate_metrics());
+                    }
+                    window
+                },
+                BatchSize::SmallInput,
+            )
+        });
+
+        // calculate_metrics：满窗口上单次计算
+        let mut full = BenchWindow::new(mint, 3600, size);
+        for trade in &burst {
+            full.add_event(trade.user, trade.is_buy, trade.sol_amount, trade.timestamp, now);
+        }
+        group.bench_function(BenchmarkId::new("calculate_metrics", size), |b| {
+            b.iter(|| black_box(full.calculate_metrics()))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_advanced_metrics(c: &mut Criterion) {
+    let mint = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let calculator = AdvancedMetricsCalculator::new(1.0, 1.0);
+    let mut group = c.benchmark_group("advanced_metrics");
+
+    // 聚合器的事件历史默认保留 100 个事件（event_history_depth）；更大的规模用于观察复杂度
+    for &size in &[20usize, 100, 500] {
+        let events: VecDeque<PumpFunEvent> = trade_burst(size, 30)
+            .into_iter()
+            .map(|trade| PumpFunEvent {
+                mint,
+                user: trade.user,
+                sol_amount: trade.sol_amount,
+                token_amount: trade.token_amount,
+                virtual_sol_reserves: trade.virtual_sol_reserves,
+                virtual_token_reserves: trade.virtual_token_reserves,
+                timestamp: trade.timestamp,
+                is_buy: trade.is_buy,
+                is_dev_trade: false,
+                creator,
+                event_type: if trade.is_buy { PumpFunEventType::Buy } else { PumpFunEventType::Sell },
+                signature_key: 0,
+            })
+            .collect();
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("calculate", size), &events, |b, events| {
+            b.iter(|| black_box(calculator.calculate(black_box(events))))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_mint_window, bench_advanced_metrics);
+criterion_main!(benches);
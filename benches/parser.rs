@@ -0,0 +1,82 @@
+//! PumpFun 日志解析热路径基准（cargo bench --bench parser）
+//!
+//! 使用 src/grpc/fixtures 中的 payload 样本，模拟一笔交易的日志突发（事件日志 + 普通程序日志）
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use solsniper::grpc::parser::parse_pumpfun_event;
+
+const FIXTURES: &str = include_str!("../src/grpc/fixtures/pumpfun_payloads.txt");
+
+const SIGNATURE: &str = "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+
+/// 指定类型的样本，转换为 "Program data: <base64>" 日志
+fn program_data_logs(kind: &str) -> Vec<String> {
+    FIXTURES
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once(' '))
+        .filter(|(k, _)| *k == kind)
+        .map(|(_, payload)| format!("Program data: {}", payload))
+        .collect()
+}
+
+/// 典型买入交易的日志（ComputeBudget + PumpFun 调用 + 事件日志）
+fn buy_transaction_logs() -> Vec<String> {
+    let mut logs = vec![
+        "Program ComputeBudget111111111111111111111111111111 invoke [1]".to_string(),
+        "Program ComputeBudget111111111111111111111111111111 success".to_string(),
+        "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [1]".to_string(),
+        "Program log: Instruction: Buy".to_string(),
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]".to_string(),
+        "Program log: Instruction: Transfer".to_string(),
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 180000 compute units".to_string(),
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success".to_string(),
+    ];
+    logs.extend(program_data_logs("trade").into_iter().take(1));
+    logs.push("Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P consumed 32000 of 200000 compute units".to_string());
+    logs.push("Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P success".to_string());
+    logs
+}
+
+fn bench_parse_pumpfun_event(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_pumpfun_event");
+
+    for kind in ["trade", "create", "migrate"] {
+        let logs = program_data_logs(kind);
+        group.throughput(Throughput::Elements(logs.len() as u64));
+        group.bench_with_input(BenchmarkId::new("event", kind), &logs, |b, logs| {
+            b.iter(|| {
+                for log in logs {
+                    let _ = black_box(parse_pumpfun_event(black_box(log), SIGNATURE, 300_000_000));
+                }
+            })
+        });
+    }
+
+    // 一笔交易的全部日志（大多数不是事件日志，测前缀快速拒绝）
+    let logs = buy_transaction_logs();
+    group.throughput(Throughput::Elements(logs.len() as u64));
+    group.bench_with_input(BenchmarkId::new("transaction_logs", logs.len()), &logs, |b, logs| {
+        b.iter(|| {
+            for log in logs {
+                let _ = black_box(parse_pumpfun_event(black_box(log), SIGNATURE, 300_000_000));
+            }
+        })
+    });
+
+    // 突发：1000 笔交易的日志
+    let burst: Vec<String> = (0..1000).flat_map(|_| buy_transaction_logs()).collect();
+    group.throughput(Throughput::Elements(burst.len() as u64));
+    group.bench_with_input(BenchmarkId::new("burst", burst.len()), &burst, |b, logs| {
+        b.iter(|| {
+            for log in logs {
+                let _ = black_box(parse_pumpfun_event(black_box(log), SIGNATURE, 300_000_000));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_pumpfun_event);
+criterion_main!(benches);
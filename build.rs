@@ -0,0 +1,12 @@
+// 编译控制面 gRPC 接口（proto/solsniper.proto）
+//
+// 未设置 PROTOC 时使用 protoc-bin-vendored 自带的 protoc，构建不依赖系统安装
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto/solsniper.proto");
+    println!("cargo:rerun-if-env-changed=PROTOC");
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+    tonic_prost_build::configure().compile_protos(&["proto/solsniper.proto"], &["proto"])?;
+    Ok(())
+}
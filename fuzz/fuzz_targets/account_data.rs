@@ -0,0 +1,14 @@
+//! PumpFun 账户数据解码模糊测试（cargo fuzz run account_data）
+//!
+//! 覆盖 BondingCurve / Global 的 Borsh 解码，任意输入都不能 panic
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use solsniper::grpc::parser::{bonding_curve_account_decode, bonding_curve_decode, global_account_decode, global_decode};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = bonding_curve_decode(data);
+    let _ = bonding_curve_account_decode(data);
+    let _ = global_decode(data);
+    let _ = global_account_decode(data);
+});
@@ -0,0 +1,25 @@
+//! PumpFun 事件日志解析模糊测试（cargo fuzz run program_data）
+//!
+//! 第一个字节选择事件鉴别器，其余字节作为事件体，覆盖 Trade / CreateToken / Migrate 三个 Borsh 解析器
+#![no_main]
+
+use base64::prelude::*;
+use libfuzzer_sys::fuzz_target;
+use solsniper::grpc::parser::{discriminators, parse_pumpfun_event};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, body)) = data.split_first() else {
+        return;
+    };
+
+    let discriminator = match selector % 3 {
+        0 => discriminators::TRADE_EVENT,
+        1 => discriminators::CREATE_TOKEN_EVENT,
+        _ => discriminators::COMPLETE_PUMP_AMM_MIGRATION_EVENT,
+    };
+    let mut payload = discriminator.to_vec();
+    payload.extend_from_slice(body);
+
+    let log = format!("Program data: {}", BASE64_STANDARD.encode(payload));
+    let _ = parse_pumpfun_event(&log, "fuzz", 0);
+});
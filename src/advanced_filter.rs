@@ -9,11 +9,12 @@
 /// 4. 时间窗口过滤 - 只处理特定时间范围的事件
 /// 5. 交易频率过滤 - 过滤异常高频/低频交易
 /// 6. 地址白名单 - 只处理白名单地址
+/// 7. Rug 事件回写 - 监控检测到 rug pull 后自动拉黑创建者和大额卖家
 
 use chrono::{DateTime, Timelike, Utc};
-use log::{debug, info};
+use log::{debug, info, warn};
 use solana_sdk::pubkey::Pubkey;
-use std::collections::{HashSet, HashMap};
+use std::collections::{HashSet, HashMap, VecDeque};
 use std::sync::Arc;
 use parking_lot::RwLock;
 
@@ -87,6 +88,24 @@ impl Default for AdvancedFilterConfig {
     }
 }
 
+/// Rug Pull 事件记录
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct RugIncident {
+    pub mint: Pubkey,
+    pub detected_at: DateTime<Utc>,
+    pub confidence: f64,
+    pub indicators: Vec<String>,
+    /// 被拉黑的地址（创建者 + 大额卖家）
+    pub blacklisted: Vec<Pubkey>,
+}
+
+/// 最多保留的 rug 事件记录数
+const MAX_RUG_INCIDENTS: usize = 200;
+
+/// 交易频率记录 (mint -> (count, last_reset_time))
+type FrequencyTracker = HashMap<Pubkey, (u32, DateTime<Utc>)>;
+
 /// 高级事件过滤器
 pub struct AdvancedEventFilter {
     config: AdvancedFilterConfig,
@@ -97,11 +116,13 @@ pub struct AdvancedEventFilter {
     /// Dev 交易记录 (mint -> has_dev_trade)
     dev_trades: Arc<RwLock<HashSet<Pubkey>>>,
     /// 交易频率记录 (mint -> (count, last_reset_time))
-    frequency_tracker: Arc<RwLock<HashMap<Pubkey, (u32, DateTime<Utc>)>>>,
+    frequency_tracker: Arc<RwLock<FrequencyTracker>>,
     /// 重复事件检测 (event_hash -> timestamp)
     seen_events: Arc<RwLock<HashMap<u64, DateTime<Utc>>>>,
     /// 统计信息
     stats: Arc<RwLock<FilterStats>>,
+    /// Rug Pull 事件记录（最近 MAX_RUG_INCIDENTS 条）
+    rug_incidents: Arc<RwLock<VecDeque<RugIncident>>>,
 }
 
 /// 过滤统计
@@ -136,6 +157,7 @@ impl AdvancedEventFilter {
             frequency_tracker: Arc::new(RwLock::new(HashMap::new())),
             seen_events: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(FilterStats::default())),
+            rug_incidents: Arc::new(RwLock::new(VecDeque::new())),
         }
     }
 
@@ -270,6 +292,8 @@ impl AdvancedEventFilter {
     }
 
     /// 检查黑名单
+    ///
+    /// 同时检查交易者、token 创建者和 mint，被拉黑的创建者发的币全部过滤
     fn check_blacklist(&self, event: &PumpFunEvent) -> Result<(), FilterReason> {
         if !self.config.enable_blacklist {
             return Ok(());
@@ -282,6 +306,20 @@ impl AdvancedEventFilter {
                 address: event.user,
             });
         }
+
+        if blacklist.contains(&event.mint) {
+            debug!("❌ 黑名单 token: {}", event.mint);
+            return Err(FilterReason::BlacklistedAddress {
+                address: event.mint,
+            });
+        }
+
+        if blacklist.contains(&event.creator) {
+            debug!("❌ 黑名单创建者: {}", event.creator);
+            return Err(FilterReason::BlacklistedAddress {
+                address: event.creator,
+            });
+        }
         
         Ok(())
     }
@@ -428,14 +466,79 @@ impl AdvancedEventFilter {
         *stats.filter_reasons.entry(reason_str.to_string()).or_insert(0) += 1;
     }
 
-    /// 添加黑名单地址
-    #[allow(dead_code)]
+    /// 添加黑名单地址（交易者 / 创建者 / mint）
     pub fn add_to_blacklist(&self, address: Pubkey) {
         let mut blacklist = self.blacklist.write();
         blacklist.insert(address);
         info!("🚫 添加黑名单地址: {}", address);
     }
 
+    /// 记录 Rug Pull 事件并自动拉黑相关地址
+    ///
+    /// 由持仓监控在检测到高置信度 RugPullSignal 时调用，
+    /// 创建者和大额卖家会被加入黑名单，后续事件直接过滤。
+    /// 每个 mint 只记录一条事件，重复检测只补充新出现的大额卖家
+    pub fn record_rug_incident(
+        &self,
+        mint: Pubkey,
+        confidence: f64,
+        indicators: Vec<String>,
+        creator: Option<Pubkey>,
+        large_sellers: &[Pubkey],
+    ) {
+        let mut blacklisted = Vec::new();
+        {
+            let mut blacklist = self.blacklist.write();
+            for address in creator.iter().chain(large_sellers.iter()) {
+                if *address == Pubkey::default() {
+                    continue;
+                }
+                if blacklist.insert(*address) {
+                    blacklisted.push(*address);
+                }
+            }
+        }
+
+        let mut incidents = self.rug_incidents.write();
+
+        // 🔥 修复: 监控每个周期都会重复发出同一 mint 的 RugPullSignal，
+        // 同一事件只记录和告警一次，之后只追加新拉黑的地址
+        if let Some(incident) = incidents.iter_mut().find(|incident| incident.mint == mint) {
+            if blacklisted.is_empty() {
+                debug!("Rug Pull 事件已存在: {}", mint);
+                return;
+            }
+            for address in &blacklisted {
+                warn!("🚫 Rug Pull {} 追加拉黑: {}", mint, address);
+            }
+            incident.confidence = incident.confidence.max(confidence);
+            incident.blacklisted.extend(blacklisted);
+            return;
+        }
+
+        warn!("🚫 Rug Pull 事件已记录: {} (置信度: {:.0}%)", mint, confidence * 100.0);
+        for address in &blacklisted {
+            warn!("   自动拉黑: {}", address);
+        }
+
+        incidents.push_back(RugIncident {
+            mint,
+            detected_at: Utc::now(),
+            confidence,
+            indicators,
+            blacklisted,
+        });
+        while incidents.len() > MAX_RUG_INCIDENTS {
+            incidents.pop_front();
+        }
+    }
+
+    /// 获取 Rug Pull 事件记录
+    #[allow(dead_code)]
+    pub fn get_rug_incidents(&self) -> Vec<RugIncident> {
+        self.rug_incidents.read().iter().cloned().collect()
+    }
+
     /// 添加白名单地址
     #[allow(dead_code)]
     pub fn add_to_whitelist(&self, address: Pubkey) {
@@ -9,15 +9,19 @@
 /// 4. 价格冲击 (price_impact) - 单笔交易对价格的影响
 /// 5. 流动性深度 (liquidity_depth) - 可用流动性评估
 /// 6. 波动率 (volatility) - 价格波动程度
+/// 7. 成交量加权价格 (vwap_price) / 中位数交易额 (median_trade_size_sol)
+/// 8. 巨鲸占比 (whale_share) / 独立买家数 (unique_buyers) - 区分自然拉升与单钱包拉盘
 
 use chrono::Utc;
 use log::debug;
-use std::collections::VecDeque;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
 
 use crate::types::PumpFunEvent;
 
 /// 高级指标
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AdvancedMetrics {
     /// 曲线斜率（价格变化速率）
     pub curve_slope: f64,
@@ -39,6 +43,14 @@ pub struct AdvancedMetrics {
     pub large_trade_ratio: f64,
     /// 交易间隔标准差（ms）
     pub trade_interval_std: f64,
+    /// 成交量加权均价（lamports / token 最小单位）
+    pub vwap_price: f64,
+    /// 中位数交易额（SOL）
+    pub median_trade_size_sol: f64,
+    /// 巨鲸占比（前 3 大买家买入额 / 总买入额）
+    pub whale_share: f64,
+    /// 独立买家数
+    pub unique_buyers: u32,
 }
 
 impl Default for AdvancedMetrics {
@@ -54,6 +66,10 @@ impl Default for AdvancedMetrics {
             weighted_buy_sell_ratio: 0.0,
             large_trade_ratio: 0.0,
             trade_interval_std: 0.0,
+            vwap_price: 0.0,
+            median_trade_size_sol: 0.0,
+            whale_share: 0.0,
+            unique_buyers: 0,
         }
     }
 }
@@ -76,6 +92,7 @@ impl AdvancedMetricsCalculator {
     }
 
     /// 计算高级指标
+    #[allow(clippy::field_reassign_with_default)]
     pub fn calculate(&self, events: &VecDeque<PumpFunEvent>) -> AdvancedMetrics {
         if events.is_empty() {
             return AdvancedMetrics::default();
@@ -115,6 +132,17 @@ impl AdvancedMetricsCalculator {
         // 9. 计算交易间隔标准差
         metrics.trade_interval_std = self.calculate_trade_interval_std(events);
 
+        // 10. 计算成交量加权均价
+        metrics.vwap_price = self.calculate_vwap_price(events);
+
+        // 11. 计算中位数交易额
+        metrics.median_trade_size_sol = self.calculate_median_trade_size(events);
+
+        // 12. 计算巨鲸占比和独立买家数
+        let (whale_share, unique_buyers) = self.calculate_buyer_concentration(events);
+        metrics.whale_share = whale_share;
+        metrics.unique_buyers = unique_buyers;
+
         debug!("✅ 高级指标计算完成");
         debug!("   曲线斜率: {:.6}", metrics.curve_slope);
         debug!("   加权买压: {:.4}", metrics.weighted_buy_pressure);
@@ -122,6 +150,8 @@ impl AdvancedMetricsCalculator {
         debug!("   平均价格冲击: {:.4}%", metrics.avg_price_impact * 100.0);
         debug!("   流动性深度: {:.4}", metrics.liquidity_depth);
         debug!("   波动率: {:.4}", metrics.volatility);
+        debug!("   中位数交易额: {:.4} SOL", metrics.median_trade_size_sol);
+        debug!("   巨鲸占比: {:.2}%, 独立买家: {}", metrics.whale_share * 100.0, metrics.unique_buyers);
 
         metrics
     }
@@ -344,6 +374,70 @@ impl AdvancedMetricsCalculator {
         variance.sqrt()
     }
 
+    /// 计算成交量加权均价
+    ///
+    /// VWAP = Σ(SOL 金额) / Σ(token 数量)，忽略 Create 等无成交事件
+    fn calculate_vwap_price(&self, events: &VecDeque<PumpFunEvent>) -> f64 {
+        let mut total_sol = 0u128;
+        let mut total_tokens = 0u128;
+
+        for event in events.iter().filter(|e| e.sol_amount > 0 && e.token_amount > 0) {
+            total_sol += event.sol_amount as u128;
+            total_tokens += event.token_amount as u128;
+        }
+
+        if total_tokens == 0 {
+            return 0.0;
+        }
+
+        total_sol as f64 / total_tokens as f64
+    }
+
+    /// 计算中位数交易额（SOL）
+    fn calculate_median_trade_size(&self, events: &VecDeque<PumpFunEvent>) -> f64 {
+        let mut sizes: Vec<u64> = events.iter()
+            .filter(|e| e.sol_amount > 0)
+            .map(|e| e.sol_amount)
+            .collect();
+
+        if sizes.is_empty() {
+            return 0.0;
+        }
+
+        sizes.sort_unstable();
+        let mid = sizes.len() / 2;
+        let median = if sizes.len().is_multiple_of(2) {
+            (sizes[mid - 1] + sizes[mid]) as f64 / 2.0
+        } else {
+            sizes[mid] as f64
+        };
+
+        median / 1_000_000_000.0
+    }
+
+    /// 计算买家集中度
+    ///
+    /// 返回 (前 3 大买家买入额占比, 独立买家数)
+    fn calculate_buyer_concentration(&self, events: &VecDeque<PumpFunEvent>) -> (f64, u32) {
+        let mut buys_by_wallet: HashMap<Pubkey, u64> = HashMap::new();
+
+        for event in events.iter().filter(|e| e.is_buy) {
+            *buys_by_wallet.entry(event.user).or_insert(0) += event.sol_amount;
+        }
+
+        let unique_buyers = buys_by_wallet.len() as u32;
+        let total_buy: u64 = buys_by_wallet.values().sum();
+        if total_buy == 0 {
+            return (0.0, unique_buyers);
+        }
+
+        let mut amounts: Vec<u64> = buys_by_wallet.into_values().collect();
+        amounts.sort_unstable_by(|a, b| b.cmp(a));
+        let top3: u64 = amounts.iter().take(3).sum();
+
+        (top3 as f64 / total_buy as f64, unique_buyers)
+    }
+
     /// 计算价格（基于恒定乘积公式）
     fn calculate_price(&self, event: &PumpFunEvent) -> f64 {
         let sol_reserves = event.virtual_sol_reserves as f64;
@@ -1,96 +1,405 @@
 use chrono::{DateTime, Duration, Utc};
-use dashmap::DashMap;
-use log::{debug, info};
+use dashmap::{DashMap, DashSet};
+use log::{debug, info, warn};
 use parking_lot::RwLock;
 use solana_sdk::pubkey::Pubkey;
-use std::collections::VecDeque;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::mem::size_of;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc;
 use crossbeam_queue::ArrayQueue;  // 🔥 新增: 无锁队列
 
 use crate::advanced_filter::{AdvancedEventFilter, AdvancedFilterConfig};
 use crate::advanced_metrics::{AdvancedMetrics, AdvancedMetricsCalculator};
+use crate::chain_clock::ChainClock;
+use crate::clock::Clock;
 use crate::config::Config;
-use crate::types::{SniperEvent, TradeEventData, WindowMetrics, PumpFunEvent, PumpFunEventType};
+use crate::dynamic_strategy::StrategyMode;
+use crate::event_feed::EventFeed;
+use crate::feed_reconcile::FeedReconciler;
+use crate::market::MarketHealth;
+use crate::monitor::LargeTradeTracker;
+use crate::threshold::{ThresholdAccumulation, ThresholdTrigger};
+use crate::types::{curve_progress_percent, signature_key, SniperEvent, TradeEventData, WindowMetrics, TimeframeMetrics, PumpFunEvent, PumpFunEventType};
 
-/// 滑窗事件
-#[derive(Debug, Clone)]
+/// 多时间框架事件的最大保留数量（防止刷单时内存膨胀）
+const MAX_TIMEFRAME_EVENTS: usize = 2000;
+
+/// 缓冲池最多保留的空闲缓冲区数量
+const BUFFER_POOL_CAPACITY: usize = 1024;
+
+/// 计算速率时的最短时间跨度（秒，避免同一时刻的几笔交易算出极大速率）
+const MIN_RATE_SPAN_SECS: f64 = 1.0;
+
+/// 热路径上每新建多少个窗口检查一次内存预算（上线潮中不必等到定期清理）
+const MEMORY_CHECK_INTERVAL: usize = 1024;
+
+/// 滑窗事件（Copy，不持有堆内存）
+#[derive(Debug, Clone, Copy)]
 struct WindowEvent {
+    user: Pubkey,
     is_buy: bool,
     sol_amount: u64,
     timestamp: DateTime<Utc>,
+    /// 交易签名键（processed 事件流回滚时定位事件）
+    signature_key: u64,
+}
+
+/// 事件缓冲区池
+///
+/// 上线潮中每秒创建 / 清理大量 mint 窗口，移除窗口时回收其 VecDeque（保留已分配容量），
+/// 新窗口直接复用，避免反复分配和扩容
+struct BufferPool<T> {
+    buffers: parking_lot::Mutex<Vec<VecDeque<T>>>,
+    /// 新分配缓冲区的初始容量
+    initial_capacity: usize,
+}
+
+impl<T> BufferPool<T> {
+    fn new(initial_capacity: usize) -> Self {
+        Self {
+            buffers: parking_lot::Mutex::new(Vec::new()),
+            initial_capacity,
+        }
+    }
+
+    /// 取出一个空缓冲区（池为空时按初始容量分配）
+    fn take(&self) -> VecDeque<T> {
+        self.buffers
+            .lock()
+            .pop()
+            .unwrap_or_else(|| VecDeque::with_capacity(self.initial_capacity))
+    }
+
+    /// 归还缓冲区（池满时直接释放）
+    fn recycle(&self, mut buffer: VecDeque<T>) {
+        buffer.clear();
+        let mut buffers = self.buffers.lock();
+        if buffers.len() < BUFFER_POOL_CAPACITY {
+            buffers.push(buffer);
+        }
+    }
+}
+
+/// 窗口组合方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WindowMode {
+    /// 超出时间或超出事件数即移除（取较小窗口）
+    Smaller,
+    /// 同时超出时间和事件数才移除（取较大窗口）
+    Larger,
+}
+
+/// 滑窗规格（时间 + 事件数 + 组合方式）
+#[derive(Debug, Clone, Copy)]
+struct WindowSpec {
+    duration: Duration,
+    max_events: usize,
+    mode: WindowMode,
+}
+
+impl WindowSpec {
+    /// 从配置解析（当前策略模式的独立窗口优先于全局窗口）
+    fn from_config(config: &Config) -> Self {
+        let (mode_duration, mode_max_events) = match StrategyMode::from_config(config) {
+            StrategyMode::Conservative => (config.conservative_window_duration_secs, config.conservative_window_max_events),
+            StrategyMode::Balanced => (config.balanced_window_duration_secs, config.balanced_window_max_events),
+            StrategyMode::Aggressive => (config.aggressive_window_duration_secs, config.aggressive_window_max_events),
+            StrategyMode::Custom => (config.custom_window_duration_secs, config.custom_window_max_events),
+        };
+
+        Self {
+            duration: Duration::seconds(mode_duration.unwrap_or(config.window_duration_secs) as i64),
+            max_events: mode_max_events.unwrap_or(config.window_max_events),
+            mode: if config.window_mode == "larger" {
+                WindowMode::Larger
+            } else {
+                WindowMode::Smaller
+            },
+        }
+    }
+}
+
+impl WindowEvent {
+    /// 带符号的资金流（买入为正，卖出为负，lamports）
+    fn signed_inflow(&self) -> i64 {
+        if self.is_buy {
+            self.sol_amount as i64
+        } else {
+            -(self.sol_amount as i64)
+        }
+    }
+}
+
+/// 主滑窗的增量统计
+///
+/// 事件进出滑窗时增量更新，calculate_metrics 不再遍历整个窗口（每个事件 O(1)）
+#[derive(Debug, Default)]
+struct WindowStats {
+    buy_count: usize,
+    sell_count: usize,
+    buy_lamports: u64,
+    sell_lamports: u64,
+    /// 整个窗口的净流入
+    net_inflow: i64,
+    /// 前半窗（events[..len / 2]）的净流入，用于加速度
+    first_half_inflow: i64,
+    /// 每个钱包在窗口内的买入次数
+    buys_per_wallet: HashMap<Pubkey, u32>,
+    /// 买入 ≥2 次的钱包数
+    repeat_buyers: usize,
+}
+
+impl WindowStats {
+    fn add(&mut self, event: &WindowEvent) {
+        self.net_inflow += event.signed_inflow();
+        if event.is_buy {
+            self.buy_count += 1;
+            self.buy_lamports += event.sol_amount;
+            let count = self.buys_per_wallet.entry(event.user).or_insert(0);
+            *count += 1;
+            if *count == 2 {
+                self.repeat_buyers += 1;
+            }
+        } else {
+            self.sell_count += 1;
+            self.sell_lamports += event.sol_amount;
+        }
+    }
+
+    fn remove(&mut self, event: &WindowEvent) {
+        self.net_inflow -= event.signed_inflow();
+        if event.is_buy {
+            self.buy_count = self.buy_count.saturating_sub(1);
+            self.buy_lamports = self.buy_lamports.saturating_sub(event.sol_amount);
+            if let Some(count) = self.buys_per_wallet.get_mut(&event.user) {
+                if *count == 2 {
+                    self.repeat_buyers -= 1;
+                }
+                *count -= 1;
+                if *count == 0 {
+                    self.buys_per_wallet.remove(&event.user);
+                }
+            }
+        } else {
+            self.sell_count = self.sell_count.saturating_sub(1);
+            self.sell_lamports = self.sell_lamports.saturating_sub(event.sol_amount);
+        }
+    }
 }
 
 /// 单个 mint 的滑窗数据
 struct MintWindow {
     mint: Pubkey,
+    creator: Pubkey,
+    /// 主滑窗事件（只通过 push_event / pop_front_event 修改，保持 stats 同步）
     events: VecDeque<WindowEvent>,
+    /// 主滑窗增量统计
+    stats: WindowStats,
+    /// 多时间框架事件（按最长时间框架保留，独立于主滑窗）
+    timeframe_events: VecDeque<WindowEvent>,
     latest_reserves: Option<ReserveState>,
-    created_at: DateTime<Utc>,
+    /// 窗口创建时间（单调时钟，只用于观察时长 / TTL 清理）
+    created_at: Instant,
+    /// 最近一笔交易的时间（单调时钟，内存超预算时按此淘汰最冷的 mint）
+    last_event_at: Instant,
+    /// token 链上创建时间（来自 Create 事件，未观察到时为 None）
+    token_created_at: Option<DateTime<Utc>>,
+    /// 累计事件数（不受滑窗限制，用于预热观察）
+    total_events: usize,
+    /// 创建者在创建交易中的首笔买入（SOL）
+    dev_initial_buy_sol: Option<f64>,
     // 阈值触发相关
     cumulative_buys_sol: f64,  // 累计买入金额 (SOL)
-    threshold_triggered: bool,  // 是否已触发阈值（用于防止重复触发）
+    threshold_tiers_fired: usize,  // 已触发的档位数（用于防止同一档位重复触发）
+    threshold_armed_at: Instant,  // 观察窗口起点（窗口创建 / 平仓后重新布防）
+    threshold_baseline_sol: f64,  // 布防时的累计买入（lifetime 模式重新布防后只计算新增买入）
+    threshold_rearmed_at: Option<DateTime<Utc>>,  // 重新布防的链上时间（window 模式只统计之后的买入）
+    // 毕业狙击相关
+    last_progress_percent: Option<f64>,  // 上一次的曲线完成度（首次观察前为 None）
+    graduation_triggered: bool,  // 是否已触发毕业狙击（用于防止重复触发）
+    // 卖压触发相关
+    sell_pressure_triggered: bool,  // 卖压是否处于触发状态（只用于记录越过阈值的日志）
 }
 
 #[derive(Debug, Clone)]
 struct ReserveState {
     virtual_sol_reserves: u64,
     virtual_token_reserves: u64,
+    real_sol_reserves: u64,
+    real_token_reserves: u64,
+    /// 本地接收时间
+    updated_at: DateTime<Utc>,
 }
 
 impl MintWindow {
-    fn new(mint: Pubkey) -> Self {
+    fn new(mint: Pubkey, pool: &BufferPool<WindowEvent>, now: Instant) -> Self {
         Self {
             mint,
-            events: VecDeque::new(),
+            creator: Pubkey::default(),
+            events: pool.take(),
+            stats: WindowStats::default(),
+            timeframe_events: pool.take(),
             latest_reserves: None,
-            created_at: Utc::now(),
+            created_at: now,
+            last_event_at: now,
+            token_created_at: None,
+            total_events: 0,
+            dev_initial_buy_sol: None,
             cumulative_buys_sol: 0.0,
-            threshold_triggered: false,
+            threshold_tiers_fired: 0,
+            threshold_armed_at: now,
+            threshold_baseline_sol: 0.0,
+            threshold_rearmed_at: None,
+            last_progress_percent: None,
+            graduation_triggered: false,
+            sell_pressure_triggered: false,
         }
     }
 
     /// 添加事件到滑窗
-    fn add_event(&mut self, event: WindowEvent, max_events: usize, window_duration: Duration, now: DateTime<Utc>) {
+    fn add_event(&mut self, event: WindowEvent, spec: &WindowSpec, now: DateTime<Utc>) {
         // 如果是买入事件，累计买入金额
         if event.is_buy {
             self.cumulative_buys_sol += event.sol_amount as f64 / 1_000_000_000.0; // lamports -> SOL
         }
 
-        self.events.push_back(event.clone());
+        self.total_events += 1;
+        self.push_event(event);
+
+        let cutoff_time = now - spec.duration;
+        match spec.mode {
+            WindowMode::Smaller => {
+                // 移除超出时间窗口的事件
+                while let Some(front) = self.events.front() {
+                    if front.timestamp < cutoff_time {
+                        self.pop_front_event();
+                    } else {
+                        break;
+                    }
+                }
 
-        // 移除超出时间窗口的事件
-        let cutoff_time = now - window_duration;
-        while let Some(front) = self.events.front() {
-            if front.timestamp < cutoff_time {
-                self.events.pop_front();
-            } else {
-                break;
+                // 限制最大事件数
+                while self.events.len() > spec.max_events {
+                    self.pop_front_event();
+                }
             }
+            WindowMode::Larger => {
+                // 只有同时超出时间窗口和最大事件数才移除
+                while self.events.len() > spec.max_events {
+                    match self.events.front() {
+                        Some(front) if front.timestamp < cutoff_time => {
+                            self.pop_front_event();
+                        }
+                        _ => break,
+                    }
+                }
+            }
+        }
+    }
+
+    /// 估算窗口占用的内存（字节，按已分配容量计）
+    fn memory_bytes(&self) -> usize {
+        size_of::<Self>()
+            + (self.events.capacity() + self.timeframe_events.capacity()) * size_of::<WindowEvent>()
+            + self.stats.buys_per_wallet.capacity() * (size_of::<Pubkey>() + size_of::<u32>())
+    }
+
+    /// 事件进入主滑窗（同步更新增量统计）
+    fn push_event(&mut self, event: WindowEvent) {
+        let previous_len = self.events.len();
+        self.events.push_back(event);
+        self.stats.add(&event);
+
+        // 前半窗 = events[..len / 2]：长度变为偶数时前半窗向后扩展一个事件
+        if previous_len % 2 == 1 {
+            self.stats.first_half_inflow += self.events[previous_len / 2].signed_inflow();
         }
+    }
+
+    /// 最早的事件移出主滑窗（同步更新增量统计）
+    fn pop_front_event(&mut self) -> Option<WindowEvent> {
+        let previous_len = self.events.len();
+        let previous_mid = previous_len / 2;
+        let event = self.events.pop_front()?;
+        self.stats.remove(&event);
 
-        // 限制最大事件数
-        while self.events.len() > max_events {
-            self.events.pop_front();
+        if previous_mid > 0 {
+            self.stats.first_half_inflow -= event.signed_inflow();
+        }
+        // 移除后前半窗剩余 previous_mid - 1 个事件，新的中点更大时补入下一个事件
+        let remaining = previous_mid.saturating_sub(1);
+        if self.events.len() / 2 > remaining {
+            self.stats.first_half_inflow += self.events[remaining].signed_inflow();
         }
+
+        Some(event)
     }
 
-    /// 计算窗口指标
-    fn calculate_metrics(&self) -> WindowMetrics {
-        let mut buy_count = 0;
-        let mut sell_count = 0;
-        let mut total_buy_sol = 0u64;
-        let mut total_sell_sol = 0u64;
-
-        for event in &self.events {
-            if event.is_buy {
-                buy_count += 1;
-                total_buy_sol += event.sol_amount;
-            } else {
-                sell_count += 1;
-                total_sell_sol += event.sol_amount;
+    /// 从头重建增量统计（回滚等非首尾修改后调用）
+    fn rebuild_stats(&mut self) {
+        let mut stats = WindowStats::default();
+        let mid = self.events.len() / 2;
+        for (index, event) in self.events.iter().enumerate() {
+            stats.add(event);
+            if index < mid {
+                stats.first_half_inflow += event.signed_inflow();
             }
         }
+        self.stats = stats;
+    }
+
+    /// 归还事件缓冲区
+    fn recycle(self, pool: &BufferPool<WindowEvent>) {
+        pool.recycle(self.events);
+        pool.recycle(self.timeframe_events);
+    }
+
+    /// 撤销被回滚的交易（未确认的 processed 交易）
+    ///
+    /// 从主滑窗和多时间框架中移除该签名的事件，扣除累计买入；储备数据可能来自该交易，一并清空
+    fn roll_back(&mut self, key: u64) -> bool {
+        let removed = self.events
+            .iter()
+            .position(|e| e.signature_key == key)
+            .and_then(|index| self.events.remove(index));
+        if removed.is_some() {
+            self.rebuild_stats();
+        }
+        if let Some(index) = self.timeframe_events.iter().position(|e| e.signature_key == key) {
+            self.timeframe_events.remove(index);
+        }
+
+        let Some(event) = removed else {
+            return false;
+        };
+
+        if event.is_buy {
+            self.cumulative_buys_sol = (self.cumulative_buys_sol - event.sol_amount as f64 / 1_000_000_000.0).max(0.0);
+        }
+        self.total_events = self.total_events.saturating_sub(1);
+        self.latest_reserves = None;
+        true
+    }
+
+    /// 计算窗口指标
+    ///
+    /// 🔥 优化: 读取增量统计，O(1)，不遍历窗口事件
+    fn calculate_metrics(&self) -> WindowMetrics {
+        let buy_count = self.stats.buy_count;
+        let sell_count = self.stats.sell_count;
+
+        // 独立买家和重复买入统计
+        let unique_buyers = self.stats.buys_per_wallet.len();
+        let repeat_buyers = self.stats.repeat_buyers;
+        let repeat_buyer_ratio = if unique_buyers > 0 {
+            repeat_buyers as f64 / unique_buyers as f64
+        } else {
+            0.0
+        };
 
         let total_count = buy_count + sell_count;
         let buy_ratio = if total_count > 0 {
@@ -99,63 +408,146 @@ impl MintWindow {
             0.0
         };
 
-        let net_inflow_sol = total_buy_sol as i64 - total_sell_sol as i64;
+        let net_inflow_sol = self.stats.buy_lamports as i64 - self.stats.sell_lamports as i64;
 
         // 计算加速度：后半窗 vs 前半窗
         let acceleration = self.calculate_acceleration();
 
-        let (virtual_sol, virtual_token) = if let Some(reserves) = &self.latest_reserves
-        {
-            (
-                reserves.virtual_sol_reserves,
-                reserves.virtual_token_reserves,
-            )
-        } else {
-            (0, 0)
-        };
+        // 🔥 新增: 按窗口时长归一化的速率（不同活跃时长的 mint 之间可比）
+        let window_span_secs = self.span_secs(0, self.events.len().saturating_sub(1));
+        let rate_span = window_span_secs.max(MIN_RATE_SPAN_SECS);
+        let inflow_rate_sol_per_sec = net_inflow_sol as f64 / 1_000_000_000.0 / rate_span;
+        let trade_rate_per_sec = self.events.len() as f64 / rate_span;
+        let normalized_acceleration = self.calculate_normalized_acceleration();
+
+        let (virtual_sol, virtual_token, real_sol, real_token, reserves_updated_at) =
+            if let Some(reserves) = &self.latest_reserves {
+                (
+                    reserves.virtual_sol_reserves,
+                    reserves.virtual_token_reserves,
+                    reserves.real_sol_reserves,
+                    reserves.real_token_reserves,
+                    Some(reserves.updated_at),
+                )
+            } else {
+                (0, 0, 0, 0, None)
+            };
 
         WindowMetrics {
             mint: self.mint,
+            creator: self.creator,
             net_inflow_sol,
             buy_ratio,
             acceleration,
+            window_span_secs,
+            inflow_rate_sol_per_sec,
+            trade_rate_per_sec,
+            normalized_acceleration,
             latest_virtual_sol_reserves: virtual_sol,
             latest_virtual_token_reserves: virtual_token,
+            latest_real_sol_reserves: real_sol,
+            latest_real_token_reserves: real_token,
+            reserves_updated_at,
             event_count: self.events.len(),
+            total_event_count: self.total_events,
+            observed_secs: 0,  // 由 handle_trade_event 设置
+            event_time: None, // 由 handle_trade_event 设置
+            token_age_secs: None, // 由 handle_trade_event 设置
+            dev_initial_buy_sol: self.dev_initial_buy_sol,
+            unique_buyers,
+            repeat_buyer_ratio,
+            curve_progress_percent: 0.0, // 这个字段会在后面单独设置（需要毕业目标配置）
+            graduation_triggered: false, // 这个字段会在后面单独设置
             threshold_buy_amount: None, // 这个字段会在后面单独设置
+            sell_pressure_sol: None, // 这个字段会在后面单独设置
             advanced_metrics: None, // 这个字段会在后面单独设置
+            timeframes: Vec::new(), // 这个字段会在后面单独设置
+            score_breakdown: None, // 策略引擎发出买入信号时设置
         }
     }
 
-    /// 计算加速度：后半窗净流入 / 前半窗净流入
-    fn calculate_acceleration(&self) -> f64 {
-        if self.events.len() < 4 {
-            return 0.0;
+    /// 记录多时间框架事件（保留最长时间框架内的事件）
+    fn track_timeframe_event(&mut self, event: WindowEvent, retention: Duration, now: DateTime<Utc>) {
+        self.timeframe_events.push_back(event);
+
+        let cutoff_time = now - retention;
+        while let Some(front) = self.timeframe_events.front() {
+            if front.timestamp < cutoff_time {
+                self.timeframe_events.pop_front();
+            } else {
+                break;
+            }
         }
 
-        let mid_point = self.events.len() / 2;
+        while self.timeframe_events.len() > MAX_TIMEFRAME_EVENTS {
+            self.timeframe_events.pop_front();
+        }
+    }
 
-        let first_half_inflow: i64 = self.events.iter()
-            .take(mid_point)
-            .map(|e| {
-                if e.is_buy {
-                    e.sol_amount as i64
-                } else {
-                    -(e.sol_amount as i64)
+    /// 计算多时间框架指标
+    ///
+    /// 每个时间框架独立计算买占比、净流入和加速度（后半段 vs 前半段，按时间切分）
+    fn calculate_timeframe_metrics(&self, timeframes_secs: &[u64], now: DateTime<Utc>) -> Vec<TimeframeMetrics> {
+        timeframes_secs
+            .iter()
+            .map(|&timeframe_secs| {
+                let start = now - Duration::seconds(timeframe_secs as i64);
+                let mid = now - Duration::milliseconds((timeframe_secs * 500) as i64);
+
+                let mut buy_count = 0usize;
+                let mut event_count = 0usize;
+                let mut first_half_inflow = 0i64;
+                let mut second_half_inflow = 0i64;
+
+                for event in self.timeframe_events.iter().filter(|e| e.timestamp >= start) {
+                    event_count += 1;
+                    if event.is_buy {
+                        buy_count += 1;
+                    }
+
+                    let signed = if event.is_buy {
+                        event.sol_amount as i64
+                    } else {
+                        -(event.sol_amount as i64)
+                    };
+                    if event.timestamp < mid {
+                        first_half_inflow += signed;
+                    } else {
+                        second_half_inflow += signed;
+                    }
                 }
-            })
-            .sum();
 
-        let second_half_inflow: i64 = self.events.iter()
-            .skip(mid_point)
-            .map(|e| {
-                if e.is_buy {
-                    e.sol_amount as i64
+                let buy_ratio = if event_count > 0 {
+                    buy_count as f64 / event_count as f64
+                } else {
+                    0.0
+                };
+
+                let acceleration = if first_half_inflow <= 0 {
+                    if second_half_inflow > 0 { f64::INFINITY } else { 0.0 }
                 } else {
-                    -(e.sol_amount as i64)
+                    second_half_inflow as f64 / first_half_inflow as f64
+                };
+
+                TimeframeMetrics {
+                    timeframe_secs,
+                    net_inflow_sol: first_half_inflow + second_half_inflow,
+                    buy_ratio,
+                    acceleration,
+                    event_count,
                 }
             })
-            .sum();
+            .collect()
+    }
+
+    /// 计算加速度：后半窗净流入 / 前半窗净流入
+    fn calculate_acceleration(&self) -> f64 {
+        if self.events.len() < 4 {
+            return 0.0;
+        }
+
+        let first_half_inflow = self.stats.first_half_inflow;
+        let second_half_inflow = self.stats.net_inflow - first_half_inflow;
 
         if first_half_inflow <= 0 {
             if second_half_inflow > 0 {
@@ -168,53 +560,275 @@ impl MintWindow {
         second_half_inflow as f64 / first_half_inflow as f64
     }
 
+    /// 主滑窗中两个事件之间的时间跨度（秒）
+    fn span_secs(&self, from: usize, to: usize) -> f64 {
+        match (self.events.get(from), self.events.get(to)) {
+            (Some(first), Some(last)) => (last.timestamp - first.timestamp).num_milliseconds().max(0) as f64 / 1000.0,
+            _ => 0.0,
+        }
+    }
+
+    /// 计算归一化加速度：后半窗净流入速率 / 前半窗净流入速率
+    ///
+    /// 前后半窗按事件数划分，时长可能相差很大（例如前半窗 10 秒、后半窗 1 秒），按各自时长换算为速率后再比较
+    fn calculate_normalized_acceleration(&self) -> f64 {
+        let len = self.events.len();
+        if len < 4 {
+            return 0.0;
+        }
+
+        let mid = len / 2;
+        let first_half_rate = self.stats.first_half_inflow as f64
+            / self.span_secs(0, mid).max(MIN_RATE_SPAN_SECS);
+        let second_half_rate = (self.stats.net_inflow - self.stats.first_half_inflow) as f64
+            / self.span_secs(mid, len - 1).max(MIN_RATE_SPAN_SECS);
+
+        if first_half_rate <= 0.0 {
+            if second_half_rate > 0.0 {
+                return f64::INFINITY;
+            } else {
+                return 0.0;
+            }
+        }
+
+        second_half_rate / first_half_rate
+    }
+
+    /// 检查是否应该触发毕业狙击
+    ///
+    /// 条件: 完成度从阈值下方越过阈值，且窗口净流入和买占比达标（每个 mint 只触发一次）
+    ///
+    /// 首次观察只记录基准完成度（首次看到时已在阈值上方不算越过）
+    fn check_graduation_trigger(&mut self, config: &Config, metrics: &WindowMetrics) -> bool {
+        let previous = self.last_progress_percent.replace(metrics.curve_progress_percent);
+
+        if !config.enable_graduation_sniper || self.graduation_triggered {
+            return false;
+        }
+
+        let trigger = config.graduation_progress_trigger_percent;
+        let crossed = previous.is_some_and(|previous| previous < trigger)
+            && metrics.curve_progress_percent >= trigger;
+        if !crossed {
+            return false;
+        }
+
+        let net_inflow_sol = metrics.net_inflow_sol as f64 / 1_000_000_000.0;
+        if net_inflow_sol < config.graduation_min_net_inflow_sol || metrics.buy_ratio < config.graduation_min_buy_ratio {
+            debug!("🎓 完成度越过 {:.0}% 但资金流入不足: mint={}, 净流入={:.4} SOL, 买占比={:.2}%",
+                trigger, self.mint, net_inflow_sol, metrics.buy_ratio * 100.0);
+            return false;
+        }
+
+        self.graduation_triggered = true;
+
+        info!(
+            "🎓 毕业狙击触发! mint={}, 完成度={:.2}% (阈值 {:.0}%), 净流入={:.4} SOL, 买占比={:.2}%",
+            self.mint,
+            metrics.curve_progress_percent,
+            trigger,
+            net_inflow_sol,
+            metrics.buy_ratio * 100.0
+        );
+
+        true
+    }
+
     /// 检查是否应该触发阈值买入
     ///
-    /// 返回: (是否触发, 计算的买入金额)
-    fn check_threshold_trigger(&mut self, config: &Config) -> Option<f64> {
+    /// 🔥 新增: 多档阈值（达到的最高未触发档位决定买入金额）、单币覆盖档位、平仓后重新布防
+    ///
+    /// now 为链上时间，at 为单调时间（观察窗口计时，回放时跟随录制时间）
+    ///
+    /// 返回: 触发档位的买入金额（SOL）
+    fn check_threshold_trigger(&mut self, trigger: &ThresholdTrigger, now: DateTime<Utc>, at: Instant) -> Option<f64> {
         // 如果未启用阈值触发，直接返回
-        if !config.enable_threshold_trigger {
+        if !trigger.is_enabled() {
             return None;
         }
 
-        // 如果已经触发过，不再重复触发
-        if self.threshold_triggered {
-            return None;
+        // 平仓后重新布防：清零已触发档位，观察窗口和累计买入从现在开始重新计算
+        if self.threshold_tiers_fired > 0 && trigger.take_rearm(&self.mint) {
+            self.threshold_tiers_fired = 0;
+            self.threshold_armed_at = at;
+            self.threshold_baseline_sol = self.cumulative_buys_sol;
+            self.threshold_rearmed_at = Some(now);
+            info!("🔁 阈值触发已重新布防: mint={}", self.mint);
         }
 
         // 检查是否还在观察窗口内
-        let now = Utc::now();
-        let elapsed_secs = (now - self.created_at).num_seconds() as u64;
-        if elapsed_secs > config.threshold_observation_window_secs {
+        let elapsed_secs = at.saturating_duration_since(self.threshold_armed_at).as_secs();
+        if elapsed_secs > trigger.observation_window_secs() {
             return None;
         }
 
-        // 检查累计买入是否达到阈值
-        if self.cumulative_buys_sol >= config.threshold_cumulative_buy_sol {
-            // 计算买入金额 = 阈值 × 比例
-            let mut buy_amount = config.threshold_cumulative_buy_sol * config.threshold_buy_ratio;
+        // 达到的最高档位（档位按累计买入从低到高排列）
+        let accumulated_sol = self.threshold_accumulated_sol(trigger.accumulation());
+        let tiers = trigger.tiers_for(&self.mint, &self.creator);
+        let reached = tiers.iter().take_while(|tier| accumulated_sol >= tier.cumulative_buy_sol).count();
 
-            // 应用 MIN/MAX 限制
-            buy_amount = buy_amount.max(config.threshold_min_buy_amount_sol);
-            buy_amount = buy_amount.min(config.threshold_max_buy_amount_sol);
+        // 该档位及以下已触发过，不再重复触发
+        if reached <= self.threshold_tiers_fired {
+            return None;
+        }
 
-            // 标记已触发
-            self.threshold_triggered = true;
+        let tier = tiers[reached - 1];
+        self.threshold_tiers_fired = reached;
+        trigger.record_fired(&self.mint);
+
+        info!(
+            "🎯 阈值触发! mint={}, 累计买入={:.4} SOL >= 第 {}/{} 档阈值={:.4} SOL, 买入金额={:.4} SOL",
+            self.mint,
+            accumulated_sol,
+            reached,
+            tiers.len(),
+            tier.cumulative_buy_sol,
+            tier.buy_amount_sol
+        );
+
+        Some(tier.buy_amount_sol)
+    }
 
-            info!(
-                "🎯 阈值触发! mint={}, 累计买入={:.4} SOL >= 阈值={:.4} SOL, 买入金额={:.4} SOL (阈值×{:.1}%)",
-                self.mint,
-                self.cumulative_buys_sol,
-                config.threshold_cumulative_buy_sol,
-                buy_amount,
-                config.threshold_buy_ratio * 100.0
-            );
+    /// 检查是否应该触发卖压卖出（买入阈值触发的镜像）
+    ///
+    /// 条件: 持仓中且 sell_pressure_window_secs 内的累计卖出不低于 sell_pressure_threshold_sol（只统计主滑窗内的事件）；
+    /// 电平触发：持仓期间只要仍在阈值以上，每笔交易都返回卖压（上一次卖出失败时下一笔交易重试，
+    /// 重复卖出由持仓管理器的卖出去重拦截）；未持仓的 mint 不产生卖出信号
+    ///
+    /// 返回: 当前的累计卖出金额（SOL）
+    fn check_sell_pressure_trigger(&mut self, config: &Config, held: bool, now: DateTime<Utc>) -> Option<f64> {
+        if !config.enable_sell_pressure_trigger || !held {
+            self.sell_pressure_triggered = false;
+            return None;
+        }
+
+        let cutoff = now - Duration::seconds(config.sell_pressure_window_secs as i64);
+        let sold_lamports: u64 = self.events
+            .iter()
+            .filter(|event| !event.is_buy && event.timestamp >= cutoff)
+            .map(|event| event.sol_amount)
+            .sum();
+        let sold_sol = sold_lamports as f64 / 1_000_000_000.0;
 
-            return Some(buy_amount);
+        if sold_sol < config.sell_pressure_threshold_sol {
+            self.sell_pressure_triggered = false;
+            return None;
+        }
+        if self.sell_pressure_triggered {
+            return Some(sold_sol);
         }
 
-        None
+        self.sell_pressure_triggered = true;
+        warn!(
+            "🩸 卖压触发! mint={}, {}s 内累计卖出={:.4} SOL >= 阈值={:.4} SOL",
+            self.mint,
+            config.sell_pressure_window_secs,
+            sold_sol,
+            config.sell_pressure_threshold_sol
+        );
+
+        Some(sold_sol)
     }
+
+    /// 阈值触发使用的累计买入（SOL）
+    ///
+    /// 🔥 修复: window 模式只统计主滑窗内的买入，移出滑窗的旧买入不再计入，慢速零散买入不会触发
+    fn threshold_accumulated_sol(&self, accumulation: ThresholdAccumulation) -> f64 {
+        match accumulation {
+            ThresholdAccumulation::Lifetime => self.cumulative_buys_sol - self.threshold_baseline_sol,
+            ThresholdAccumulation::Window => {
+                let lamports = match self.threshold_rearmed_at {
+                    None => self.stats.buy_lamports,
+                    Some(rearmed_at) => self.events
+                        .iter()
+                        .filter(|event| event.is_buy && event.timestamp >= rearmed_at)
+                        .map(|event| event.sol_amount)
+                        .sum(),
+                };
+                lamports as f64 / 1_000_000_000.0
+            }
+        }
+    }
+}
+
+/// 聚合器内存占用快照（控制 API / 定期清理日志）
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregatorMemoryUsage {
+    /// 滑窗数量
+    pub windows: usize,
+    /// 事件历史数量
+    pub histories: usize,
+    /// 估算占用（字节）
+    pub estimated_bytes: usize,
+    /// 内存预算（字节，0 = 不限制）
+    pub budget_bytes: usize,
+    /// 累计因超预算被淘汰的 mint 数
+    pub evicted_mints: u64,
+}
+
+/// 滑窗内单个事件的快照
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowEventSnapshot {
+    pub user: String,
+    pub is_buy: bool,
+    pub sol_amount: f64,
+    /// 链上事件时间（RFC 3339）
+    pub timestamp: String,
+}
+
+/// 最新储备快照
+#[derive(Debug, Clone, Serialize)]
+pub struct ReservesSnapshot {
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    /// 本地接收时间（RFC 3339）
+    pub updated_at: String,
+}
+
+/// 单个 mint 的聚合器状态快照（控制 API / inspect 子命令，排查"为什么没买"）
+#[derive(Debug, Clone, Serialize)]
+pub struct MintWindowSnapshot {
+    pub mint: String,
+    pub creator: String,
+    /// 窗口已观察时长（秒）
+    pub observed_secs: u64,
+    /// token 链上年龄（秒，未观察到 Create 事件时为 None）
+    pub token_age_secs: Option<u64>,
+    /// 累计事件数（不受滑窗限制）
+    pub total_events: usize,
+    pub dev_initial_buy_sol: Option<f64>,
+    /// 累计买入金额（SOL）与阈值触发状态
+    pub cumulative_buys_sol: f64,
+    pub threshold_triggered: bool,
+    /// 当前布防周期内已触发的档位数
+    pub threshold_tiers_fired: usize,
+    /// 阈值触发使用的累计买入（SOL，按 threshold_accumulation 统计）
+    pub threshold_accumulated_sol: f64,
+    pub curve_progress_percent: f64,
+    pub graduation_triggered: bool,
+    /// 主滑窗统计
+    pub buy_count: usize,
+    pub sell_count: usize,
+    pub net_inflow_sol: f64,
+    pub buy_ratio: f64,
+    /// 加速度（前半窗净流入非正时为 null）
+    pub acceleration: Option<f64>,
+    pub normalized_acceleration: Option<f64>,
+    /// 净流入速率（SOL/秒）与交易速率（笔/秒）
+    pub inflow_rate_sol_per_sec: f64,
+    pub trade_rate_per_sec: f64,
+    pub unique_buyers: usize,
+    pub repeat_buyer_ratio: f64,
+    pub reserves: Option<ReservesSnapshot>,
+    pub timeframes: Vec<TimeframeMetrics>,
+    /// 高级指标（事件历史不足 5 条时为 None）
+    pub advanced_metrics: Option<AdvancedMetrics>,
+    /// 事件历史条数（高级指标的输入）
+    pub history_events: usize,
+    /// 主滑窗事件（从旧到新）
+    pub events: Vec<WindowEventSnapshot>,
 }
 
 /// 滑窗聚合器（增强版）
@@ -234,10 +848,48 @@ pub struct Aggregator {
     event_history: Arc<DashMap<Pubkey, Arc<RwLock<VecDeque<PumpFunEvent>>>>>,
     /// 缓存的系统时间（1ms 更新一次）
     cached_time: Arc<RwLock<DateTime<Utc>>>,
+    /// 滑窗规格（按策略模式解析）
+    window_spec: WindowSpec,
+    /// 大额卖出追踪（供实时监控器读取）
+    large_trades: Arc<LargeTradeTracker>,
+    /// 大盘健康度（记录上线 / 毕业事件）
+    market_health: Arc<MarketHealth>,
+    /// 链上时钟（事件规范时间 + 窗口剪枝基准，不受本地时钟偏差影响）
+    chain_clock: ChainClock,
+    /// 计时时钟（窗口观察时长 / 阈值观察窗口 / TTL 清理，回放时跟随录制时间）
+    clock: Arc<Clock>,
+    /// 滑窗事件缓冲池（容量 = 滑窗最大事件数）
+    window_buffers: BufferPool<WindowEvent>,
+    /// 事件历史缓冲池
+    history_buffers: BufferPool<PumpFunEvent>,
+    /// 每个 mint 保留的事件历史数量
+    history_depth: usize,
+    /// 窗口 + 事件历史的内存预算（字节，0 = 不限制）
+    memory_budget_bytes: usize,
+    /// 上次检查内存预算后新建的窗口数
+    windows_since_memory_check: AtomicUsize,
+    /// 累计因超预算被淘汰的 mint 数
+    evicted_mints: AtomicU64,
+    /// processed 事件流确认对账（未确认的交易从窗口回滚）
+    feed_reconciler: Arc<FeedReconciler>,
+    /// WebSocket 事件流（推送解析出的事件）
+    event_feed: Arc<EventFeed>,
+    /// 累计买入阈值触发（多档 / 单币覆盖 / 平仓后重新布防）
+    threshold: Arc<ThresholdTrigger>,
+    /// 持仓中的 mint（持仓管理器开仓 / 平仓时维护，内存预算淘汰时跳过）
+    held_mints: Arc<DashSet<Pubkey>>,
 }
 
 impl Aggregator {
-    pub fn new(config: Arc<Config>, metrics_tx: mpsc::Sender<Arc<WindowMetrics>>) -> Self {
+    pub fn new(
+        config: Arc<Config>,
+        metrics_tx: mpsc::Sender<Arc<WindowMetrics>>,
+        large_trades: Arc<LargeTradeTracker>,
+        market_health: Arc<MarketHealth>,
+        feed_reconciler: Arc<FeedReconciler>,
+        event_feed: Arc<EventFeed>,
+        clock: Arc<Clock>,
+    ) -> Self {
         // 创建高级过滤器（从配置读取）
         let filter_config = AdvancedFilterConfig {
             min_sol_amount: Some(config.min_sol_amount),
@@ -260,7 +912,15 @@ impl Aggregator {
             config.high_frequency_window_secs,
         ));
 
+        let window_spec = WindowSpec::from_config(&config);
+        let history_depth = config.event_history_depth;
+        let memory_budget_bytes = (config.aggregator_memory_budget_mb as usize).saturating_mul(1024 * 1024);
+        let threshold = Arc::new(ThresholdTrigger::from_config(&config));
+
         info!("🎯 聚合器已初始化（增强版 + DashMap + 时间缓存优化）");
+        info!("   滑窗: {}s / {} 事件 ({:?})",
+            window_spec.duration.num_seconds(), window_spec.max_events, window_spec.mode);
+        info!("   事件历史: {} 个/mint, 内存预算: {} MB", history_depth, config.aggregator_memory_budget_mb);
         info!("   ✅ 高级事件过滤器已启用");
         info!("   ✅ 高级指标计算器已启用");
         info!("   ✅ DashMap 并发优化已启用");
@@ -286,9 +946,45 @@ impl Aggregator {
             metrics_calculator,
             event_history: Arc::new(DashMap::new()),
             cached_time,
+            window_spec,
+            large_trades,
+            market_health,
+            chain_clock: ChainClock::new(),
+            clock,
+            window_buffers: BufferPool::new(window_spec.max_events),
+            history_buffers: BufferPool::new(history_depth + 1),
+            history_depth,
+            memory_budget_bytes,
+            windows_since_memory_check: AtomicUsize::new(0),
+            evicted_mints: AtomicU64::new(0),
+            feed_reconciler,
+            event_feed,
+            threshold,
+            held_mints: Arc::new(DashSet::new()),
         }
     }
 
+    /// 获取高级事件过滤器（用于监控系统回写黑名单）
+    pub fn filter(&self) -> Arc<AdvancedEventFilter> {
+        self.filter.clone()
+    }
+
+    /// 获取阈值触发器（持仓管理器平仓后请求重新布防）
+    pub fn threshold_trigger(&self) -> Arc<ThresholdTrigger> {
+        self.threshold.clone()
+    }
+
+    /// 获取持仓 mint 集合（持仓管理器开仓 / 平仓时维护）
+    pub fn held_mints(&self) -> Arc<DashSet<Pubkey>> {
+        self.held_mints.clone()
+    }
+
+    /// 新建窗口（计数，达到 MEMORY_CHECK_INTERVAL 后由主循环检查内存预算）
+    fn new_window(&self, mint: Pubkey) -> Arc<RwLock<MintWindow>> {
+        self.windows_since_memory_check.fetch_add(1, Ordering::Relaxed);
+        Arc::new(RwLock::new(MintWindow::new(mint, &self.window_buffers, self.clock.now())))
+    }
+
     /// 获取缓存的当前时间（避免频繁系统调用）
     fn now(&self) -> DateTime<Utc> {
         *self.cached_time.read()
@@ -305,10 +1001,14 @@ impl Aggregator {
         const MIN_BACKOFF: u64 = 100;  // 最小 100μs
 
         loop {
+            // 先撤销 processed 事件流中未确认的交易
+            self.apply_rollbacks();
+
             // 批量处理队列中的所有事件
             let mut events_processed = 0;
             while let Some(event) = event_queue.pop() {
                 events_processed += 1;
+                self.event_feed.publish_event(&event);
                 match event {
                     SniperEvent::Trade(trade) => {
                         self.handle_trade_event(trade).await;
@@ -317,15 +1017,21 @@ impl Aggregator {
                         info!("🆕 新币创建: {} ({})", create.symbol, create.mint);
                         info!("   创建者: {}", create.creator);
                         info!("   开始监控首波资金流动...");
+                        self.market_health.record_launch();
 
                         // 为新 token 创建窗口（DashMap 自动处理并发）
-                        self.windows.insert(
-                            create.mint,
-                            Arc::new(RwLock::new(MintWindow::new(create.mint)))
-                        );
+                        let timestamp = DateTime::from_timestamp(create.timestamp, 0).unwrap_or_else(Utc::now);
+                        let window = self.new_window(create.mint);
+                        {
+                            let mut window = window.write();
+                            window.creator = create.creator;
+                            window.token_created_at = Some(timestamp);
+                        }
+                        if let Some(previous) = self.windows.insert(create.mint, window) {
+                            self.recycle_window(previous);
+                        }
 
                         // 初始化事件历史，并添加一个 Create 类型的 PumpFunEvent
-                        let timestamp = DateTime::from_timestamp(create.timestamp, 0).unwrap_or_else(Utc::now);
                         let create_event = PumpFunEvent {
                             mint: create.mint,
                             user: create.creator,
@@ -336,34 +1042,49 @@ impl Aggregator {
                             timestamp,
                             is_buy: false,
                             is_dev_trade: true, // Create 事件视为 dev 操作
+                            creator: create.creator,
                             event_type: PumpFunEventType::Create, // ✅ 使用 Create 类型
+                            signature_key: signature_key(&create.signature),
                         };
 
-                    let mut events = VecDeque::new();
-                    events.push_back(create_event);
-                    self.event_history.insert(
-                        create.mint,
-                        Arc::new(RwLock::new(events))
-                    );
+                        let mut events = self.history_buffers.take();
+                        events.push_back(create_event);
+                        if let Some(previous) = self.event_history.insert(
+                            create.mint,
+                            Arc::new(RwLock::new(events))
+                        ) {
+                            self.recycle_history(previous);
+                        }
 
-                    debug!("✅ Create 事件已记录: {}", create.mint);
-                }
-                SniperEvent::Migrate(migrate) => {
-                    info!("🔄 代币已迁移到 Raydium: {}", migrate.mint);
-                    info!("   Pool: {}", migrate.pool);
-                    info!("   迁移金额: {} SOL, {} tokens",
-                        migrate.sol_amount as f64 / 1_000_000_000.0,
-                        migrate.mint_amount);
-                    info!("   迁移费用: {} SOL", migrate.pool_migration_fee as f64 / 1_000_000_000.0);
-
-                    // Migrate 事件表示 bonding curve 已完成，移除窗口和历史
-                    self.windows.remove(&migrate.mint);
-                    self.event_history.remove(&migrate.mint);
-
-                    debug!("✅ Migrate 事件已处理，已移除窗口: {}", migrate.mint);
+                        debug!("✅ Create 事件已记录: {}", create.mint);
+                    }
+                    SniperEvent::Migrate(migrate) => {
+                        info!("🔄 代币已迁移到 Raydium: {}", migrate.mint);
+                        self.market_health.record_graduation();
+                        info!("   Pool: {}", migrate.pool);
+                        info!("   迁移金额: {} SOL, {} tokens",
+                            migrate.sol_amount as f64 / 1_000_000_000.0,
+                            migrate.mint_amount);
+                        info!("   迁移费用: {} SOL", migrate.pool_migration_fee as f64 / 1_000_000_000.0);
+
+                        // Migrate 事件表示 bonding curve 已完成，移除窗口和历史
+                        if let Some((_, window)) = self.windows.remove(&migrate.mint) {
+                            self.recycle_window(window);
+                        }
+                        if let Some((_, history)) = self.event_history.remove(&migrate.mint) {
+                            self.recycle_history(history);
+                        }
+
+                        debug!("✅ Migrate 事件已处理，已移除窗口: {}", migrate.mint);
+                    }
                 }
             }
 
+            // 上线潮中新建大量窗口时，不等定期清理，直接检查内存预算
+            if self.windows_since_memory_check.load(Ordering::Relaxed) >= MEMORY_CHECK_INTERVAL {
+                self.enforce_memory_budget();
+            }
+
             // 🔥 优化: 自适应退避逻辑
             if events_processed > 0 {
                 // 有事件处理，重置退避延迟
@@ -376,12 +1097,13 @@ impl Aggregator {
             tokio::time::sleep(tokio::time::Duration::from_micros(backoff_delay)).await;
         }
     }
-}
 
     /// 处理交易事件（增强版）
     async fn handle_trade_event(&self, trade: TradeEventData) {
         // 1. 转换为 PumpFunEvent 格式
-        let timestamp = DateTime::from_timestamp(trade.timestamp, 0).unwrap_or_else(Utc::now);
+        // 🔥 修复: 事件时间取链上区块时间（按 slot 细化到毫秒），不混用本地时钟
+        self.chain_clock.observe(trade.slot, trade.timestamp);
+        let timestamp = self.chain_clock.event_time(trade.slot, trade.timestamp);
         let pumpfun_event = PumpFunEvent {
             mint: trade.mint,
             user: trade.user,
@@ -392,15 +1114,39 @@ impl Aggregator {
             timestamp,
             is_buy: trade.is_buy,
             is_dev_trade: trade.user == trade.creator,
+            creator: trade.creator,
             event_type: if trade.is_buy {
                 PumpFunEventType::Buy
             } else {
                 PumpFunEventType::Sell
             },
+            signature_key: signature_key(&trade.signature),
         };
 
+        // 🔥 新增: 大额卖出写入监控器（在过滤之前，避免被金额上限过滤掉）
+        self.large_trades.record_trade(
+            trade.mint,
+            trade.user,
+            trade.sol_amount,
+            trade.is_buy,
+            timestamp,
+        );
+
+        // 🔥 新增: 创建者首笔买入
+        let dev_buy_sol = (trade.is_created_buy && trade.is_buy && pumpfun_event.is_dev_trade)
+            .then(|| trade.sol_amount as f64 / 1_000_000_000.0);
+        if let Some(dev_buy_sol) = dev_buy_sol {
+            debug!("👤 创建者首笔买入: {} {:.4} SOL", trade.mint, dev_buy_sol);
+        }
+
         // 2. 高级事件过滤
         if let Err(reason) = self.filter.filter(&pumpfun_event) {
+            // 被过滤的 dev 买入（如超过金额上限）只记录到已有窗口，不为被过滤 / 黑名单 mint 新建窗口
+            if let Some(dev_buy_sol) = dev_buy_sol {
+                if let Some(window) = self.windows.get(&trade.mint) {
+                    window.write().dev_initial_buy_sol = Some(dev_buy_sol);
+                }
+            }
             debug!("❌ 事件被过滤: {:?}", reason);
             return;
         }
@@ -409,14 +1155,14 @@ impl Aggregator {
         {
             let events_arc = self.event_history
                 .entry(trade.mint)
-                .or_insert_with(|| Arc::new(RwLock::new(VecDeque::new())))
+                .or_insert_with(|| Arc::new(RwLock::new(self.history_buffers.take())))
                 .clone();
 
             let mut events = events_arc.write();
-            events.push_back(pumpfun_event.clone());
+            events.push_back(pumpfun_event);
 
-            // 保留最近 100 个事件
-            while events.len() > 100 {
+            // 保留最近 history_depth 个事件
+            while events.len() > self.history_depth {
                 events.pop_front();
             }
         }
@@ -425,41 +1171,80 @@ impl Aggregator {
         let metrics = {
             let window_arc = self.windows
                 .entry(trade.mint)
-                .or_insert_with(|| Arc::new(RwLock::new(MintWindow::new(trade.mint))))
+                .or_insert_with(|| self.new_window(trade.mint))
                 .clone();
 
             let mut window = window_arc.write();
 
+            window.creator = trade.creator;
+            if let Some(dev_buy_sol) = dev_buy_sol {
+                window.dev_initial_buy_sol = Some(dev_buy_sol);
+            }
+            let at = self.clock.now();
+            window.last_event_at = at;
+
             // 更新储备状态
             window.latest_reserves = Some(ReserveState {
                 virtual_sol_reserves: trade.virtual_sol_reserves,
                 virtual_token_reserves: trade.virtual_token_reserves,
+                real_sol_reserves: trade.real_sol_reserves,
+                real_token_reserves: trade.real_token_reserves,
+                updated_at: self.now(),
             });
 
             // 添加事件
             let window_event = WindowEvent {
+                user: trade.user,
                 is_buy: trade.is_buy,
                 sol_amount: trade.sol_amount,
                 timestamp,
+                signature_key: pumpfun_event.signature_key,
             };
 
-            let window_duration = Duration::seconds(self.config.window_duration_secs as i64);
-            let now = self.now();
-            window.add_event(
-                window_event,
-                self.config.window_max_events,
-                window_duration,
-                now,
-            );
+            // 窗口剪枝以链上当前时间为基准（本地时钟偏差不影响窗口宽度）
+            let now = self.chain_clock.now();
+
+            // 多时间框架（按最长时间框架保留事件）
+            if let Some(&longest) = self.config.timeframe_windows_secs.iter().max() {
+                window.track_timeframe_event(
+                    window_event,
+                    Duration::seconds(longest as i64),
+                    now,
+                );
+            }
 
-            // 检查阈值触发
-            let _threshold_buy_amount = window.check_threshold_trigger(&self.config);
+            window.add_event(window_event, &self.window_spec, now);
 
             // 计算基础指标
             let mut metrics = window.calculate_metrics();
 
-            // 设置阈值触发信息
-            metrics.threshold_buy_amount = _threshold_buy_amount;
+            // 观察时长（预热期判断）
+            metrics.observed_secs = at.saturating_duration_since(window.created_at).as_secs();
+            metrics.event_time = Some(timestamp);
+            // 🔥 修复: 预热期内不评估阈值 / 毕业触发（否则档位在预热期内被消耗，买入信号又被预热期抑制）
+            let warming_up = self.config.in_warmup(metrics.observed_secs, metrics.total_event_count);
+
+            // 检查阈值触发（预热期内的买入仍然计入累计，预热结束后的下一笔交易再判断）
+            if !warming_up {
+                metrics.threshold_buy_amount = window.check_threshold_trigger(&self.threshold, now, at);
+            }
+            let held = self.held_mints.contains(&window.mint);
+            metrics.sell_pressure_sol = window.check_sell_pressure_trigger(&self.config, held, now);
+
+            metrics.token_age_secs = window.token_created_at
+                .map(|created_at| (now - created_at).num_seconds().max(0) as u64);
+
+            // 曲线完成度和毕业狙击
+            metrics.curve_progress_percent = curve_progress_percent(
+                metrics.latest_real_sol_reserves,
+                self.config.graduation_target_sol,
+            );
+            if !warming_up {
+                metrics.graduation_triggered = window.check_graduation_trigger(&self.config, &metrics);
+            }
+
+            // 多时间框架指标
+            metrics.timeframes = window.calculate_timeframe_metrics(&self.config.timeframe_windows_secs, now);
 
             metrics
             // window 锁在这里自动释放
@@ -498,6 +1283,29 @@ impl Aggregator {
         }
     }
 
+    /// 撤销 processed 事件流中未达到 confirmed 的交易（窗口事件 + 事件历史）
+    fn apply_rollbacks(&self) {
+        for rollback in self.feed_reconciler.take_rollbacks() {
+            let key = signature_key(&rollback.signature);
+            let removed = self.windows
+                .get(&rollback.mint)
+                .map(|window_arc| window_arc.write().roll_back(key))
+                .unwrap_or(false);
+
+            if let Some(events_arc) = self.event_history.get(&rollback.mint) {
+                let mut events = events_arc.write();
+                if let Some(index) = events.iter().position(|e| e.signature_key == key) {
+                    events.remove(index);
+                }
+            }
+
+            if removed {
+                info!("↩️  回滚未确认交易: mint={}, slot={}, sig={}",
+                    rollback.mint, rollback.slot, rollback.signature);
+            }
+        }
+    }
+
     /// 获取高级指标（保留作为备用 API）
     #[allow(dead_code)]
     pub fn get_advanced_metrics(&self, mint: &Pubkey) -> Option<AdvancedMetrics> {
@@ -519,34 +1327,445 @@ impl Aggregator {
         })
     }
 
+    /// 导出指定 mint 的窗口状态（事件、累计买入、触发标记、储备、高级指标；没有窗口时返回 None）
+    pub fn snapshot_window(&self, mint: &Pubkey) -> Option<MintWindowSnapshot> {
+        let window_arc = self.windows.get(mint)?.clone();
+        let now = self.chain_clock.now();
+
+        let mut snapshot = {
+            let window = window_arc.read();
+            let metrics = window.calculate_metrics();
+            MintWindowSnapshot {
+                mint: window.mint.to_string(),
+                creator: window.creator.to_string(),
+                observed_secs: self.clock.elapsed(window.created_at).as_secs(),
+                token_age_secs: window.token_created_at
+                    .map(|created_at| (now - created_at).num_seconds().max(0) as u64),
+                total_events: window.total_events,
+                dev_initial_buy_sol: window.dev_initial_buy_sol,
+                cumulative_buys_sol: window.cumulative_buys_sol,
+                threshold_triggered: window.threshold_tiers_fired > 0,
+                threshold_tiers_fired: window.threshold_tiers_fired,
+                threshold_accumulated_sol: window.threshold_accumulated_sol(self.threshold.accumulation()),
+                curve_progress_percent: curve_progress_percent(
+                    metrics.latest_real_sol_reserves,
+                    self.config.graduation_target_sol,
+                ),
+                graduation_triggered: window.graduation_triggered,
+                buy_count: window.stats.buy_count,
+                sell_count: window.stats.sell_count,
+                net_inflow_sol: metrics.net_inflow_sol as f64 / 1_000_000_000.0,
+                buy_ratio: metrics.buy_ratio,
+                acceleration: Some(metrics.acceleration).filter(|a| a.is_finite()),
+                normalized_acceleration: Some(metrics.normalized_acceleration).filter(|a| a.is_finite()),
+                inflow_rate_sol_per_sec: metrics.inflow_rate_sol_per_sec,
+                trade_rate_per_sec: metrics.trade_rate_per_sec,
+                unique_buyers: metrics.unique_buyers,
+                repeat_buyer_ratio: metrics.repeat_buyer_ratio,
+                reserves: window.latest_reserves.as_ref().map(|reserves| ReservesSnapshot {
+                    virtual_sol_reserves: reserves.virtual_sol_reserves,
+                    virtual_token_reserves: reserves.virtual_token_reserves,
+                    real_sol_reserves: reserves.real_sol_reserves,
+                    real_token_reserves: reserves.real_token_reserves,
+                    updated_at: reserves.updated_at.to_rfc3339(),
+                }),
+                timeframes: window.calculate_timeframe_metrics(&self.config.timeframe_windows_secs, now),
+                advanced_metrics: None,
+                history_events: 0,
+                events: window.events
+                    .iter()
+                    .map(|event| WindowEventSnapshot {
+                        user: event.user.to_string(),
+                        is_buy: event.is_buy,
+                        sol_amount: event.sol_amount as f64 / 1_000_000_000.0,
+                        timestamp: event.timestamp.to_rfc3339(),
+                    })
+                    .collect(),
+            }
+        };
+
+        if let Some(history_arc) = self.event_history.get(mint).map(|entry| entry.clone()) {
+            let history = history_arc.read();
+            snapshot.history_events = history.len();
+            if history.len() >= 5 {
+                snapshot.advanced_metrics = Some(self.metrics_calculator.calculate(&history));
+            }
+        }
+
+        Some(snapshot)
+    }
+
+    /// 归还移除窗口的缓冲区（仍被其他任务引用时直接释放），并清理该 mint 的阈值布防状态
+    fn recycle_window(&self, window: Arc<RwLock<MintWindow>>) {
+        self.threshold.forget(&window.read().mint);
+        if let Ok(window) = Arc::try_unwrap(window) {
+            window.into_inner().recycle(&self.window_buffers);
+        }
+    }
+
+    /// 归还移除的事件历史缓冲区
+    fn recycle_history(&self, history: Arc<RwLock<VecDeque<PumpFunEvent>>>) {
+        if let Ok(history) = Arc::try_unwrap(history) {
+            self.history_buffers.recycle(history.into_inner());
+        }
+    }
+
     /// 清理过期的窗口
     pub fn cleanup_old_windows(&self, max_age_secs: u64) {
-        let cutoff_time = self.now() - Duration::seconds(max_age_secs as i64);
+        let max_age = std::time::Duration::from_secs(max_age_secs);
 
         // 🔥 修复: 清理过期窗口
+        // 🔥 优化: 移除的窗口 / 事件历史归还缓冲池
+        let expired_windows: Vec<Pubkey> = self.windows
+            .iter()
+            .filter(|entry| self.clock.elapsed(entry.value().read().created_at) >= max_age)
+            .map(|entry| *entry.key())
+            .collect();
         let mut removed_windows = 0;
-        self.windows.retain(|_, window_arc| {
-            let window = window_arc.read();
-            let should_keep = window.created_at > cutoff_time;
-            if !should_keep {
+        for mint in expired_windows {
+            if let Some((_, window)) = self.windows.remove(&mint) {
+                self.recycle_window(window);
                 removed_windows += 1;
             }
-            should_keep
-        });
+        }
 
         // 🔥 修复: 清理对应的事件历史（防止内存泄漏）
+        let orphaned_histories: Vec<Pubkey> = self.event_history
+            .iter()
+            .filter(|entry| !self.windows.contains_key(entry.key()))
+            .map(|entry| *entry.key())
+            .collect();
         let mut removed_histories = 0;
-        self.event_history.retain(|mint, _| {
-            let should_keep = self.windows.contains_key(mint);
-            if !should_keep {
+        for mint in orphaned_histories {
+            if let Some((_, history)) = self.event_history.remove(&mint) {
+                self.recycle_history(history);
                 removed_histories += 1;
             }
-            should_keep
-        });
+        }
 
         if removed_windows > 0 || removed_histories > 0 {
             info!("🧹 清理完成: 移除 {} 个窗口, {} 个事件历史", removed_windows, removed_histories);
         }
+
+        self.enforce_memory_budget();
+
+        let usage = self.memory_usage();
+        debug!("💾 聚合器内存: {} 个窗口, {} 个事件历史, {:.1} MB (已淘汰 {} 个 mint)",
+            usage.windows,
+            usage.histories,
+            usage.estimated_bytes as f64 / (1024.0 * 1024.0),
+            usage.evicted_mints
+        );
+    }
+
+    /// 估算窗口 + 事件历史的内存占用（按 mint 汇总）
+    ///
+    /// 返回 (mint, 最近交易时间, 字节)；没有窗口的孤立事件历史最近交易时间为 None（最先淘汰）
+    fn memory_by_mint(&self) -> Vec<(Pubkey, Option<Instant>, usize)> {
+        let history_event_bytes = size_of::<PumpFunEvent>();
+        let mut usage: HashMap<Pubkey, (Option<Instant>, usize)> = HashMap::with_capacity(self.windows.len());
+
+        for entry in self.windows.iter() {
+            let window = entry.value().read();
+            usage.insert(*entry.key(), (Some(window.last_event_at), window.memory_bytes()));
+        }
+        for entry in self.event_history.iter() {
+            let bytes = size_of::<VecDeque<PumpFunEvent>>() + entry.value().read().capacity() * history_event_bytes;
+            usage.entry(*entry.key()).or_insert((None, 0)).1 += bytes;
+        }
+
+        usage.into_iter()
+            .map(|(mint, (last_event_at, bytes))| (mint, last_event_at, bytes))
+            .collect()
+    }
+
+    /// 当前内存占用快照
+    pub fn memory_usage(&self) -> AggregatorMemoryUsage {
+        let estimated_bytes = self.memory_by_mint().iter().map(|(_, _, bytes)| bytes).sum();
+        AggregatorMemoryUsage {
+            windows: self.windows.len(),
+            histories: self.event_history.len(),
+            estimated_bytes,
+            budget_bytes: self.memory_budget_bytes,
+            evicted_mints: self.evicted_mints.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 超出内存预算时淘汰最久没有交易的 mint（窗口 + 事件历史一起移除）
+    ///
+    /// 持仓中的 mint 不淘汰：退出信号依赖它们的窗口
+    fn enforce_memory_budget(&self) {
+        self.windows_since_memory_check.store(0, Ordering::Relaxed);
+        if self.memory_budget_bytes == 0 {
+            return;
+        }
+
+        let mut usage = self.memory_by_mint();
+        let mut total: usize = usage.iter().map(|(_, _, bytes)| bytes).sum();
+        if total <= self.memory_budget_bytes {
+            return;
+        }
+
+        // None（孤立事件历史）排在最前
+        usage.sort_unstable_by_key(|(_, last_event_at, _)| *last_event_at);
+
+        let mut evicted = 0u64;
+        for (mint, _, bytes) in usage {
+            if total <= self.memory_budget_bytes {
+                break;
+            }
+            if self.held_mints.contains(&mint) {
+                continue;
+            }
+            if let Some((_, window)) = self.windows.remove(&mint) {
+                self.recycle_window(window);
+            }
+            if let Some((_, history)) = self.event_history.remove(&mint) {
+                self.recycle_history(history);
+            }
+            total = total.saturating_sub(bytes);
+            evicted += 1;
+        }
+
+        self.evicted_mints.fetch_add(evicted, Ordering::Relaxed);
+        info!("💾 聚合器内存超出预算 ({} MB): 淘汰 {} 个最冷的 mint, 剩余约 {:.1} MB",
+            self.memory_budget_bytes / (1024 * 1024),
+            evicted,
+            total as f64 / (1024.0 * 1024.0)
+        );
     }
 }
 
+/// 基准测试入口（benches/aggregator.rs 使用，不属于公共 API，仅在 bench feature 下编译）
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+#[allow(dead_code)]
+pub mod bench_support {
+    use super::*;
+
+    /// 包装私有的 MintWindow，暴露滑窗热路径
+    pub struct BenchWindow {
+        window: MintWindow,
+        spec: WindowSpec,
+    }
+
+    impl BenchWindow {
+        pub fn new(mint: Pubkey, duration_secs: i64, max_events: usize) -> Self {
+            Self {
+                window: MintWindow::new(mint, &BufferPool::new(max_events), Instant::now()),
+                spec: WindowSpec {
+                    duration: Duration::seconds(duration_secs),
+                    max_events,
+                    mode: WindowMode::Smaller,
+                },
+            }
+        }
+
+        pub fn add_event(&mut self, user: Pubkey, is_buy: bool, sol_amount: u64, timestamp: DateTime<Utc>, now: DateTime<Utc>) {
+            let event = WindowEvent {
+                user,
+                is_buy,
+                sol_amount,
+                timestamp,
+                signature_key: 0,
+            };
+            self.window.add_event(event, &self.spec, now);
+        }
+
+        pub fn calculate_metrics(&self) -> WindowMetrics {
+            self.window.calculate_metrics()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/base.env");
+
+    const SOL: u64 = 1_000_000_000;
+
+    /// 集成测试的基础配置 + 覆盖项
+    fn config_with(overrides: &[(&str, &str)]) -> Config {
+        // 不写进程环境变量，测试之间互不影响
+        #[allow(deprecated)]
+        let base = dotenv::from_path_iter(FIXTURE_PATH)
+            .unwrap()
+            .map(|item| item.unwrap())
+            .filter(|(key, _)| overrides.iter().all(|(name, _)| name != key));
+        let overrides = overrides.iter().map(|(key, value)| (key.to_string(), value.to_string()));
+        Config::from_vars(base.chain(overrides)).unwrap()
+    }
+
+    fn threshold_trigger(overrides: &[(&str, &str)]) -> ThresholdTrigger {
+        let mut overrides = overrides.to_vec();
+        overrides.push(("ENABLE_THRESHOLD_TRIGGER", "true"));
+        ThresholdTrigger::from_config(&config_with(&overrides))
+    }
+
+    /// 10 秒内累计卖出 2 SOL 触发
+    fn sell_pressure_config() -> Config {
+        config_with(&[
+            ("ENABLE_SELL_PRESSURE_TRIGGER", "true"),
+            ("SELL_PRESSURE_WINDOW_SECS", "10"),
+            ("SELL_PRESSURE_THRESHOLD_SOL", "2"),
+        ])
+    }
+
+    fn spec() -> WindowSpec {
+        WindowSpec {
+            duration: Duration::seconds(60),
+            max_events: 100,
+            mode: WindowMode::Smaller,
+        }
+    }
+
+    fn trade(window: &mut MintWindow, is_buy: bool, sol_amount: u64, timestamp: DateTime<Utc>) {
+        let event = WindowEvent {
+            user: Pubkey::new_unique(),
+            is_buy,
+            sol_amount,
+            timestamp,
+            signature_key: 0,
+        };
+        window.add_event(event, &spec(), timestamp);
+    }
+
+    fn buy(window: &mut MintWindow, sol_amount: u64, timestamp: DateTime<Utc>) {
+        trade(window, true, sol_amount, timestamp);
+    }
+
+    fn sell(window: &mut MintWindow, sol_amount: u64, timestamp: DateTime<Utc>) {
+        trade(window, false, sol_amount, timestamp);
+    }
+
+    fn window(at: Instant) -> MintWindow {
+        MintWindow::new(Pubkey::new_unique(), &BufferPool::new(16), at)
+    }
+
+    #[test]
+    fn tiers_escalate_once_each() {
+        let trigger = threshold_trigger(&[("THRESHOLD_TIERS", "2:0.3,1:0.1")]);
+        let at = Instant::now();
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut window = window(at);
+
+        buy(&mut window, SOL, start);
+        assert_eq!(window.check_threshold_trigger(&trigger, start, at), Some(0.1));
+        assert_eq!(window.check_threshold_trigger(&trigger, start, at), None);
+
+        let later = start + Duration::seconds(1);
+        buy(&mut window, 3 * SOL / 2, later);
+        assert_eq!(window.check_threshold_trigger(&trigger, later, at), Some(0.3));
+        assert_eq!(window.check_threshold_trigger(&trigger, later, at), None);
+    }
+
+    #[test]
+    fn jumping_past_several_tiers_fires_the_highest() {
+        let trigger = threshold_trigger(&[("THRESHOLD_TIERS", "1:0.1,2:0.3")]);
+        let at = Instant::now();
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut window = window(at);
+
+        buy(&mut window, 5 * SOL, start);
+        assert_eq!(window.check_threshold_trigger(&trigger, start, at), Some(0.3));
+        assert_eq!(window.check_threshold_trigger(&trigger, start, at), None);
+    }
+
+    #[test]
+    fn rearm_counts_only_buys_after_the_sell() {
+        let trigger = threshold_trigger(&[("THRESHOLD_TIERS", "1:0.1"), ("THRESHOLD_REARM_AFTER_SELL", "true")]);
+        let at = Instant::now();
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut window = window(at);
+
+        buy(&mut window, SOL, start);
+        assert_eq!(window.check_threshold_trigger(&trigger, start, at), Some(0.1));
+
+        // 平仓后重新布防：此前的买入不再计入
+        trigger.rearm(&window.mint);
+        let rearmed = start + Duration::seconds(1);
+        assert_eq!(window.check_threshold_trigger(&trigger, rearmed, at), None);
+
+        let later = start + Duration::seconds(2);
+        buy(&mut window, SOL, later);
+        assert_eq!(window.check_threshold_trigger(&trigger, later, at), Some(0.1));
+    }
+
+    #[test]
+    fn rearm_is_ignored_for_mints_that_never_fired() {
+        let trigger = threshold_trigger(&[("THRESHOLD_TIERS", "1:0.1"), ("THRESHOLD_REARM_AFTER_SELL", "true")]);
+        let at = Instant::now();
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut window = window(at);
+
+        // 未触发过档位的平仓不记录重新布防请求
+        trigger.rearm(&window.mint);
+        assert!(!trigger.take_rearm(&window.mint));
+
+        buy(&mut window, SOL, start);
+        assert_eq!(window.check_threshold_trigger(&trigger, start, at), Some(0.1));
+        assert_eq!(window.check_threshold_trigger(&trigger, start, at), None);
+    }
+
+    #[test]
+    fn forgotten_mints_are_not_rearmed() {
+        let trigger = threshold_trigger(&[("THRESHOLD_TIERS", "1:0.1"), ("THRESHOLD_REARM_AFTER_SELL", "true")]);
+        let at = Instant::now();
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut window = window(at);
+
+        buy(&mut window, SOL, start);
+        assert_eq!(window.check_threshold_trigger(&trigger, start, at), Some(0.1));
+
+        // 窗口移除时清理布防状态，之后的平仓不再留下请求
+        trigger.forget(&window.mint);
+        trigger.rearm(&window.mint);
+        assert!(!trigger.take_rearm(&window.mint));
+    }
+
+    #[test]
+    fn sell_pressure_ignores_mints_not_held() {
+        let config = sell_pressure_config();
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut window = window(Instant::now());
+
+        sell(&mut window, 3 * SOL, start);
+        assert_eq!(window.check_sell_pressure_trigger(&config, false, start), None);
+        assert_eq!(window.check_sell_pressure_trigger(&config, true, start), Some(3.0));
+    }
+
+    #[test]
+    fn sell_pressure_stays_triggered_while_held_and_above_threshold() {
+        let config = sell_pressure_config();
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut window = window(Instant::now());
+
+        sell(&mut window, 2 * SOL, start);
+        assert_eq!(window.check_sell_pressure_trigger(&config, true, start), Some(2.0));
+
+        // 仍在阈值以上：每笔交易都返回卖压（上一次卖出失败时可以重试）
+        let later = start + Duration::seconds(1);
+        buy(&mut window, SOL, later);
+        assert_eq!(window.check_sell_pressure_trigger(&config, true, later), Some(2.0));
+
+        // 卖出移出卖压窗口后回落到阈值以下
+        let expired = start + Duration::seconds(11);
+        buy(&mut window, SOL, expired);
+        assert_eq!(window.check_sell_pressure_trigger(&config, true, expired), None);
+
+        sell(&mut window, 2 * SOL, expired);
+        assert_eq!(window.check_sell_pressure_trigger(&config, true, expired), Some(2.0));
+    }
+
+    #[test]
+    fn sell_pressure_is_off_unless_enabled() {
+        let config = config_with(&[]);
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut window = window(Instant::now());
+
+        sell(&mut window, 10 * SOL, start);
+        assert_eq!(window.check_sell_pressure_trigger(&config, true, start), None);
+    }
+}
@@ -0,0 +1,90 @@
+/// 链上时钟（抗本地时钟偏差）
+///
+/// 滑窗事件时间来自链上（Clock sysvar 的 unix_timestamp，秒级），剪枝如果用本地 Utc::now 做基准，
+/// 本地时钟偏差会让窗口整体变宽 / 变窄。链上时钟以最新 slot 的区块时间为锚点，
+/// 锚点之后的流逝时间用单调时钟（Instant）推算，不读取本地墙钟
+///
+/// 核心功能:
+/// 1. 锚点 - 记录观察到的最高 slot 及其区块时间
+/// 2. 事件时间 - 区块时间（秒）+ 按 slot 间隔推算的亚秒偏移（同一秒内的事件保持先后顺序）
+/// 3. 链上当前时间 - 锚点区块时间 + 锚点之后的单调流逝时间（窗口剪枝的基准）
+
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::RwLock;
+use std::time::Instant;
+
+/// 平均 slot 时长（毫秒）
+const SLOT_DURATION_MS: i64 = 400;
+
+/// 锚点（最高 slot 的区块时间）
+#[derive(Debug, Clone, Copy)]
+struct Anchor {
+    slot: u64,
+    block_time: DateTime<Utc>,
+    observed_at: Instant,
+}
+
+/// 链上时钟
+pub struct ChainClock {
+    anchor: RwLock<Option<Anchor>>,
+}
+
+impl ChainClock {
+    pub fn new() -> Self {
+        Self {
+            anchor: RwLock::new(None),
+        }
+    }
+
+    /// 记录事件的 slot 和区块时间（只在 slot 更高时前移锚点）
+    pub fn observe(&self, slot: u64, unix_timestamp: i64) {
+        let Some(block_time) = DateTime::from_timestamp(unix_timestamp, 0) else {
+            return;
+        };
+
+        let mut anchor = self.anchor.write();
+        let advance = match *anchor {
+            Some(current) => slot > current.slot && block_time >= current.block_time,
+            None => true,
+        };
+        if advance {
+            *anchor = Some(Anchor {
+                slot,
+                block_time,
+                observed_at: Instant::now(),
+            });
+        }
+    }
+
+    /// 事件的规范时间
+    ///
+    /// 区块时间只有秒级精度：以锚点按 slot 间隔推算毫秒级时间，并限制在该区块时间所在的一秒内
+    pub fn event_time(&self, slot: u64, unix_timestamp: i64) -> DateTime<Utc> {
+        let block_time = DateTime::from_timestamp(unix_timestamp, 0).unwrap_or_else(|| self.now());
+
+        let Some(anchor) = *self.anchor.read() else {
+            return block_time;
+        };
+
+        let slot_offset_ms = (slot as i64 - anchor.slot as i64) * SLOT_DURATION_MS;
+        let estimated = anchor.block_time + Duration::milliseconds(slot_offset_ms);
+        estimated.clamp(block_time, block_time + Duration::milliseconds(999))
+    }
+
+    /// 链上当前时间（未观察到任何事件前回退到本地时间）
+    pub fn now(&self) -> DateTime<Utc> {
+        match *self.anchor.read() {
+            Some(anchor) => {
+                let elapsed = Duration::from_std(anchor.observed_at.elapsed()).unwrap_or_else(|_| Duration::zero());
+                anchor.block_time + elapsed
+            }
+            None => Utc::now(),
+        }
+    }
+}
+
+impl Default for ChainClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
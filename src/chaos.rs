@@ -0,0 +1,220 @@
+/// 故障注入（chaos 测试）
+///
+/// 按配置的概率在关键路径上制造故障，用于验证重连 / 重试 / blockhash 重建逻辑的恢复能力
+///
+/// 核心功能:
+/// 1. gRPC 投递延迟 - 事件推入队列前随机等待（模拟慢节点 / 网络抖动，阻塞后续事件）
+/// 2. 事件丢弃 - 解析出的事件随机不进入队列（模拟漏推）
+/// 3. SWQOS 发送失败 - 包装 SwqosClientTrait，随机返回发送错误
+/// 4. 过期 blockhash - 包装 RpcApi，随机返回集群不认识的 blockhash（发送时触发 BlockhashNotFound）
+///
+/// 只有使用 `--features chaos` 构建时才会注入故障；未启用 feature 时所有判定恒为 false，
+/// 包装函数原样返回内部实现（Config::validate 拒绝在此情况下设置 chaos_enabled）
+
+use anyhow::Result;
+use reqwest::Client;
+use solana_client::client_error::Result as ClientResult;
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig};
+use solana_client::rpc_response::{RpcResult, RpcSimulateTransactionResult};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::account::Account;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::TransactionStatus;
+use std::sync::Arc;
+use std::time::Duration;
+use log::warn;
+
+use crate::config::Config;
+use crate::rpc_api::RpcApi;
+use crate::swqos::{SwqosClientTrait, SwqosType};
+
+/// 故障注入器（各概率取值 0.0 - 1.0，0 = 不注入该类故障）
+#[derive(Debug, Clone, Default)]
+pub struct ChaosInjector {
+    pub enabled: bool,
+    /// gRPC 事件延迟投递的概率
+    pub grpc_delay_probability: f64,
+    /// 延迟投递的最大时长（实际延迟在 0 到该值之间均匀分布）
+    pub grpc_max_delay: Duration,
+    /// gRPC 事件被丢弃的概率
+    pub drop_event_probability: f64,
+    /// SWQOS 发送失败的概率
+    pub swqos_failure_probability: f64,
+    /// get_latest_blockhash 返回过期 blockhash 的概率
+    pub stale_blockhash_probability: f64,
+}
+
+impl ChaosInjector {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            enabled: config.chaos_enabled,
+            grpc_delay_probability: config.chaos_grpc_delay_probability,
+            grpc_max_delay: Duration::from_millis(config.chaos_grpc_max_delay_ms),
+            drop_event_probability: config.chaos_drop_event_probability,
+            swqos_failure_probability: config.chaos_swqos_failure_probability,
+            stale_blockhash_probability: config.chaos_stale_blockhash_probability,
+        }
+    }
+
+    /// 是否会注入故障（需要 `chaos` feature 且配置启用）
+    pub fn is_active(&self) -> bool {
+        cfg!(feature = "chaos") && self.enabled
+    }
+
+    fn roll(&self, probability: f64) -> bool {
+        self.is_active() && probability > 0.0 && rand::random::<f64>() < probability
+    }
+
+    /// 本次 gRPC 投递需要等待的时长（None = 立即投递）
+    pub fn delivery_delay(&self) -> Option<Duration> {
+        if self.grpc_max_delay.is_zero() || !self.roll(self.grpc_delay_probability) {
+            return None;
+        }
+        Some(self.grpc_max_delay.mul_f64(rand::random::<f64>()))
+    }
+
+    /// 本次 gRPC 事件是否丢弃
+    pub fn drop_event(&self) -> bool {
+        self.roll(self.drop_event_probability)
+    }
+
+    /// 本次 SWQOS 发送是否失败
+    pub fn fail_swqos_send(&self) -> bool {
+        self.roll(self.swqos_failure_probability)
+    }
+
+    /// 本次 get_latest_blockhash 是否返回过期 blockhash
+    pub fn stale_blockhash(&self) -> bool {
+        self.roll(self.stale_blockhash_probability)
+    }
+
+    /// 包装 RPC 实现（未启用时原样返回）
+    pub fn wrap_rpc(&self, inner: Arc<dyn RpcApi>) -> Arc<dyn RpcApi> {
+        if !self.is_active() || self.stale_blockhash_probability <= 0.0 {
+            return inner;
+        }
+        Arc::new(ChaosRpc { inner, chaos: self.clone() })
+    }
+
+    /// 包装 SWQOS 客户端（未启用时原样返回）
+    pub fn wrap_swqos(&self, inner: Arc<dyn SwqosClientTrait>) -> Arc<dyn SwqosClientTrait> {
+        if !self.is_active() || self.swqos_failure_probability <= 0.0 {
+            return inner;
+        }
+        Arc::new(ChaosSwqosClient { inner, chaos: self.clone() })
+    }
+}
+
+/// 注入过期 blockhash 的 RPC 包装（其余方法直接转发）
+struct ChaosRpc {
+    inner: Arc<dyn RpcApi>,
+    chaos: ChaosInjector,
+}
+
+impl RpcApi for ChaosRpc {
+    fn get_balance(&self, pubkey: &Pubkey) -> ClientResult<u64> {
+        self.inner.get_balance(pubkey)
+    }
+
+    fn get_balance_with_commitment(&self, pubkey: &Pubkey, commitment: CommitmentConfig) -> RpcResult<u64> {
+        self.inner.get_balance_with_commitment(pubkey, commitment)
+    }
+
+    fn get_latest_blockhash(&self) -> ClientResult<Hash> {
+        let blockhash = self.inner.get_latest_blockhash()?;
+        if self.chaos.stale_blockhash() {
+            // 集群不认识的 blockhash 与过期 blockhash 一样在发送时返回 BlockhashNotFound
+            warn!("🐒 chaos: 返回过期 blockhash");
+            return Ok(Hash::new_unique());
+        }
+        Ok(blockhash)
+    }
+
+    fn is_blockhash_valid(&self, blockhash: &Hash, commitment: CommitmentConfig) -> ClientResult<bool> {
+        self.inner.is_blockhash_valid(blockhash, commitment)
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> ClientResult<Account> {
+        self.inner.get_account(pubkey)
+    }
+
+    fn get_account_data(&self, pubkey: &Pubkey) -> ClientResult<Vec<u8>> {
+        self.inner.get_account_data(pubkey)
+    }
+
+    fn get_account_with_commitment(&self, pubkey: &Pubkey, commitment: CommitmentConfig) -> RpcResult<Option<Account>> {
+        self.inner.get_account_with_commitment(pubkey, commitment)
+    }
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> ClientResult<Vec<Option<Account>>> {
+        self.inner.get_multiple_accounts(pubkeys)
+    }
+
+    fn get_token_account_balance_with_commitment(&self, token_account: &Pubkey, commitment: CommitmentConfig) -> RpcResult<u64> {
+        self.inner.get_token_account_balance_with_commitment(token_account, commitment)
+    }
+
+    fn get_signature_statuses(&self, signatures: &[Signature]) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        self.inner.get_signature_statuses(signatures)
+    }
+
+    fn send_transaction(&self, transaction: &VersionedTransaction) -> ClientResult<Signature> {
+        self.inner.send_transaction(transaction)
+    }
+
+    fn send_transaction_with_config(&self, transaction: &VersionedTransaction, config: RpcSendTransactionConfig) -> ClientResult<Signature> {
+        self.inner.send_transaction_with_config(transaction, config)
+    }
+
+    fn simulate_transaction_with_config(
+        &self,
+        transaction: &VersionedTransaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> RpcResult<RpcSimulateTransactionResult> {
+        self.inner.simulate_transaction_with_config(transaction, config)
+    }
+}
+
+/// 注入发送失败的 SWQOS 客户端包装
+struct ChaosSwqosClient {
+    inner: Arc<dyn SwqosClientTrait>,
+    chaos: ChaosInjector,
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for ChaosSwqosClient {
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        if self.chaos.fail_swqos_send() {
+            warn!("🐒 chaos: {:?} 发送失败", self.inner.get_swqos_type());
+            return Err(anyhow::anyhow!("chaos: injected SWQOS send failure"));
+        }
+        self.inner.send_transaction(transaction).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        self.inner.get_tip_account()
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        self.inner.get_swqos_type()
+    }
+
+    fn endpoint(&self) -> &str {
+        self.inner.endpoint()
+    }
+
+    fn auth_token(&self) -> &str {
+        self.inner.auth_token()
+    }
+
+    fn http_client(&self) -> &Client {
+        self.inner.http_client()
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.inner.ping().await
+    }
+}
@@ -0,0 +1,88 @@
+/// 计时时钟（实盘读系统时钟，回放时由录制事件的接收时间驱动）
+///
+/// 窗口观察时长、阈值观察窗口、Hold 信号合并、持仓时长、告警冷却和交易日志时间戳都从这里取时间：
+/// 回放时这些计时跟随录制时间（加速回放时同样加速），同一录制文件每次回放得到相同的计时结果
+///
+/// 核心功能:
+/// 1. 单调时间 - now() / elapsed()，替代 Instant::now() / Instant::elapsed()
+/// 2. 墙钟时间 - utc_now()，替代 Utc::now()（持仓入场时间 / 持仓时长 / 交易日志）
+/// 3. 回放推进 - advance_to(录制时间)，只前进不后退；两个事件之间时间静止
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+
+/// 回放时间（以第一个事件的录制时间为起点）
+#[derive(Debug)]
+struct ReplayTime {
+    /// 录制起点对应的单调时间
+    origin: Instant,
+    /// 第一个事件的录制时间（Unix 毫秒，推进前为 None）
+    start_ms: Option<i64>,
+    /// 录制起点之后的流逝时间（毫秒）
+    elapsed_ms: i64,
+}
+
+/// 计时时钟
+#[derive(Debug)]
+pub struct Clock {
+    replay: Option<Mutex<ReplayTime>>,
+}
+
+impl Clock {
+    /// 系统时钟（实盘）
+    pub fn system() -> Self {
+        Self { replay: None }
+    }
+
+    /// 回放时钟（由 advance_to 推进）
+    pub fn replay() -> Self {
+        Self {
+            replay: Some(Mutex::new(ReplayTime {
+                origin: Instant::now(),
+                start_ms: None,
+                elapsed_ms: 0,
+            })),
+        }
+    }
+
+    /// 当前单调时间
+    pub fn now(&self) -> Instant {
+        match &self.replay {
+            Some(replay) => {
+                let replay = replay.lock();
+                replay.origin + Duration::from_millis(replay.elapsed_ms as u64)
+            }
+            None => Instant::now(),
+        }
+    }
+
+    /// 距 earlier 的流逝时间（earlier 晚于当前时间时为 0）
+    pub fn elapsed(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+
+    /// 当前墙钟时间
+    pub fn utc_now(&self) -> DateTime<Utc> {
+        match &self.replay {
+            Some(replay) => {
+                let replay = replay.lock();
+                replay
+                    .start_ms
+                    .and_then(|start_ms| DateTime::from_timestamp_millis(start_ms + replay.elapsed_ms))
+                    .unwrap_or_default()
+            }
+            None => Utc::now(),
+        }
+    }
+
+    /// 回放推进到事件的录制时间（Unix 毫秒；系统时钟忽略，早于当前回放时间时不后退）
+    pub fn advance_to(&self, recorded_at_ms: i64) {
+        let Some(replay) = &self.replay else {
+            return;
+        };
+        let mut replay = replay.lock();
+        let start_ms = *replay.start_ms.get_or_insert(recorded_at_ms);
+        replay.elapsed_ms = replay.elapsed_ms.max(recorded_at_ms - start_ms);
+    }
+}
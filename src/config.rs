@@ -1,20 +1,96 @@
 use anyhow::{Context, Result};
 use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
 use solana_commitment_config::CommitmentConfig;
 
+/// 买入可用的发送通道
+pub const SEND_BACKENDS: &[&str] = &["swqos", "tpu", "lightspeed"];
+
 /// 全局配置
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     // 网络配置
     pub grpc_endpoint: String,
     pub grpc_x_token: Option<String>,
+    /// 交易订阅必须同时包含的账户（逗号分隔，为空不限制）
+    #[serde(default)]
+    pub grpc_account_required: Vec<String>,
+    /// 交易订阅排除的账户（逗号分隔，包含任一账户的交易不推送）
+    #[serde(default)]
+    pub grpc_account_exclude: Vec<String>,
+    /// gRPC 心跳 ping 间隔（秒，0 = 不发送）
+    #[serde(default = "default_grpc_ping_interval_secs")]
+    pub grpc_ping_interval_secs: u64,
+    /// 连续多少次 ping 未收到 pong 视为连接静默中断，重新订阅
+    #[serde(default = "default_grpc_max_missed_pongs")]
+    pub grpc_max_missed_pongs: u32,
+    /// 重连时从上次处理的 slot 继续订阅（from_slot），补回断线期间的事件
+    #[serde(default = "default_true")]
+    pub grpc_resume_from_slot: bool,
+    /// 交易去重表容量（按签名 + slot，0 = 不去重）
+    #[serde(default = "default_grpc_dedup_capacity")]
+    pub grpc_dedup_capacity: usize,
+    /// 交易去重条目保留时长（秒）
+    #[serde(default = "default_grpc_dedup_ttl_secs")]
+    pub grpc_dedup_ttl_secs: u64,
     pub rpc_endpoint: String,
+    /// 备用 RPC 端点（逗号分隔，主端点不健康时自动切换）
+    #[serde(default)]
+    pub rpc_fallback_endpoints: Vec<String>,
+    /// RPC 健康检查间隔（秒，0 = 不检查）
+    #[serde(default = "default_rpc_health_check_interval_secs")]
+    pub rpc_health_check_interval_secs: u64,
+    /// 连续失败多少次标记端点为不健康
+    #[serde(default = "default_rpc_failover_threshold")]
+    pub rpc_failover_threshold: u32,
+    /// 不健康端点的冷却时间（秒，期间不优先路由）
+    #[serde(default = "default_rpc_unhealthy_cooldown_secs")]
+    pub rpc_unhealthy_cooldown_secs: u64,
+    /// 端点 slot 落后最高端点超过多少视为不健康（0 = 不检查）
+    #[serde(default = "default_rpc_max_slot_lag")]
+    pub rpc_max_slot_lag: u64,
     pub rpc_lightspeed_endpoint: String,
     pub commitment_level: String,
+    /// gRPC 事件流的 commitment（processed 延迟更低，但可能包含被分叉丢弃的交易）
+    #[serde(default = "default_feed_commitment")]
+    pub feed_commitment: String,
+    /// 发送交易（blockhash / 预检）使用的 commitment（未配置时使用 commitment_level）
+    #[serde(default)]
+    pub send_commitment: Option<String>,
+    /// 判定交易已确认的 commitment（confirmed / finalized）
+    #[serde(default = "default_confirm_commitment")]
+    pub confirm_commitment: String,
+    /// 余额读取（钱包 SOL / token 余额）使用的 commitment（未配置时使用 commitment_level）
+    #[serde(default)]
+    pub balance_commitment: Option<String>,
+    /// processed 事件流中交易等待 confirmed 的超时（毫秒，超时未确认则回滚窗口事件）
+    #[serde(default = "default_feed_reconcile_timeout_ms")]
+    pub feed_reconcile_timeout_ms: u64,
+    /// processed 事件流下，买入前等待触发该 mint 的交易全部确认（更安全，但损失 processed 的速度优势）
+    #[serde(default)]
+    pub feed_reconcile_wait_before_buy: bool,
 
-    // 钱包配置
+    // 钱包配置（只能配置一个来源，见 keystore.rs）
+    /// base58 明文私钥
+    #[serde(default)]
     pub wallet_private_key: String,
+    /// Solana keypair JSON 文件路径（solana-keygen 生成）
+    pub wallet_keypair_path: Option<String>,
+    /// 加密 keystore 路径（`solsniper encrypt-keystore` 生成）
+    pub wallet_keystore_path: Option<String>,
+    /// keystore 口令（不设置时启动时在终端提示输入）
+    pub wallet_keystore_passphrase: Option<String>,
+    /// 外部签名命令（sh -c 执行，stdout 输出 base58 私钥或 JSON 字节数组，例如密码管理器 CLI）
+    pub wallet_signer_command: Option<String>,
+    /// 远程签名服务 URL（私钥不在交易机器上，见 signer.rs）
+    pub wallet_remote_signer_url: Option<String>,
+    /// 远程签名服务 Bearer token
+    pub wallet_remote_signer_token: Option<String>,
+    /// Ledger 定位符（例如 usb://ledger，需要 `ledger` feature）
+    pub wallet_ledger_locator: Option<String>,
+    /// Ledger 派生路径账户号（m/44'/501'/<account>'/0'，不设置时使用 m/44'/501'）
+    pub wallet_ledger_account: Option<u32>,
 
     // LightSpeed 配置
     pub use_lightspeed: bool,
@@ -24,30 +100,347 @@ pub struct Config {
     // SWQOS 配置
     pub swqos_enabled: bool,
 
+    // TPU 直发配置
+    /// 启用 TPU 直发（QUIC 直接发送到当前/后续 leader，绕过 RPC）
+    #[serde(default)]
+    pub tpu_direct_enabled: bool,
+    /// TPU 客户端订阅 slot 更新的 websocket 端点（未设置时由 rpc_endpoint 推导）
+    #[serde(default)]
+    pub tpu_websocket_endpoint: Option<String>,
+    /// 同时发送到未来多少个 slot 的 leader
+    #[serde(default = "default_tpu_fanout_slots")]
+    pub tpu_fanout_slots: u64,
+    /// 买入发送通道顺序（swqos / tpu / lightspeed），依次尝试直到成功
+    #[serde(default = "default_send_backend_order")]
+    pub send_backend_order: Vec<String>,
+
+    /// 聚合器储备数据的最大可用时长（毫秒），超过则买入前重新读取链上数据
+    #[serde(default = "default_reserves_max_staleness_ms")]
+    pub reserves_max_staleness_ms: u64,
+
     // Compute Budget 配置
     pub compute_unit_limit: u32,
     pub compute_unit_price: u64,
 
+    /// 交易确认的 RPC 兜底查询间隔（毫秒，主路径为 gRPC 交易流匹配签名）
+    #[serde(default = "default_confirmation_fallback_poll_ms")]
+    pub confirmation_fallback_poll_ms: u64,
+
+    // 交易重试配置（每次重试使用新 blockhash 重新构建交易）
+    /// 最大尝试次数（含第一次）
+    #[serde(default = "default_tx_retry_max_attempts")]
+    pub tx_retry_max_attempts: u32,
+    /// 每次重试 CU 价格和 tip 的递增倍数（1.0 = 不递增）
+    #[serde(default = "default_retry_fee_multiplier")]
+    pub retry_fee_multiplier: f64,
+    /// 重试递增后的 CU 价格上限（0 = 不限制）
+    #[serde(default)]
+    pub retry_max_compute_unit_price: u64,
+
+    /// 启动时和每次卖出后关闭 WSOL / 空 token 账户，回收租金
+    #[serde(default = "default_true")]
+    pub wallet_sweep_enabled: bool,
+    /// 定期扫描空 token 账户的间隔（秒，0 = 只在启动时和卖出后清理）
+    #[serde(default = "default_wallet_sweep_interval_secs")]
+    pub wallet_sweep_interval_secs: u64,
+
+    // 控制 API（本地 HTTP，运维操作：紧急清仓等）
+    #[serde(default)]
+    pub control_api_enabled: bool,
+    #[serde(default = "default_control_api_bind")]
+    pub control_api_bind: String,
+    /// 请求需携带 `Authorization: Bearer <token>`（未设置时不校验，此时只允许绑定回环地址）
+    #[serde(default)]
+    pub control_api_token: Option<String>,
+    /// 控制面 gRPC 服务（proto/solsniper.proto，认证复用 control_api_token）
+    #[serde(default)]
+    pub control_grpc_enabled: bool,
+    #[serde(default = "default_control_grpc_bind")]
+    pub control_grpc_bind: String,
+
+    // WebSocket 事件流（事件 / 指标 / 信号 / 成交，供外部工具实时消费）
+    #[serde(default)]
+    pub event_feed_enabled: bool,
+    #[serde(default = "default_event_feed_bind")]
+    pub event_feed_bind: String,
+    /// 握手需携带 `Authorization: Bearer <token>`（未设置时不校验，此时只允许绑定回环地址）
+    #[serde(default)]
+    pub event_feed_token: Option<String>,
+    /// 广播缓冲的消息数（慢客户端落后超过该数量时丢弃积压消息）
+    #[serde(default = "default_event_feed_buffer")]
+    pub event_feed_buffer: usize,
+
     // 滑窗参数
     pub window_duration_secs: u64,
     pub window_max_events: usize,
+    /// 窗口组合方式: smaller = 时间和事件数都限制（取较小窗口），larger = 满足任一即保留（取较大窗口）
+    #[serde(default = "default_window_mode")]
+    pub window_mode: String,
+    /// 各策略模式独立的窗口大小（未设置时使用全局 window_duration_secs / window_max_events）
+    #[serde(default)]
+    pub conservative_window_duration_secs: Option<u64>,
+    #[serde(default)]
+    pub conservative_window_max_events: Option<usize>,
+    #[serde(default)]
+    pub balanced_window_duration_secs: Option<u64>,
+    #[serde(default)]
+    pub balanced_window_max_events: Option<usize>,
+    #[serde(default)]
+    pub aggressive_window_duration_secs: Option<u64>,
+    #[serde(default)]
+    pub aggressive_window_max_events: Option<usize>,
+    #[serde(default)]
+    pub custom_window_duration_secs: Option<u64>,
+    #[serde(default)]
+    pub custom_window_max_events: Option<usize>,
+    /// 多时间框架窗口（秒，逗号分隔，例如 5,15,60）
+    #[serde(default = "default_timeframe_windows")]
+    pub timeframe_windows_secs: Vec<u64>,
+    /// 是否要求所有时间框架同向才买入
+    #[serde(default)]
+    pub require_timeframe_alignment: bool,
+    /// 买入前要求的最少独立买家数（0 = 不限制）
+    #[serde(default)]
+    pub min_unique_buyers: usize,
 
     // 策略触发条件
     pub buy_ratio_threshold: f64,
     pub net_inflow_threshold_sol: f64,
     pub acceleration_required: bool,
     pub acceleration_multiplier: f64,
+    /// 传统策略的加速度检查使用按时长归一化的加速度（前后半窗时长不等时更可比）
+    #[serde(default)]
+    pub use_normalized_acceleration: bool,
+    /// 常规策略要求的最低净流入速率（SOL/秒，0 = 不限制）
+    #[serde(default)]
+    pub min_inflow_rate_sol_per_sec: f64,
+    /// 常规策略要求的最低交易速率（笔/秒，0 = 不限制）
+    #[serde(default)]
+    pub min_trade_rate_per_sec: f64,
     pub max_slippage_percent: f64,
 
     // 交易参数
     pub snipe_amount_sol: f64,
     pub slippage_percent: f64,
+    /// PumpFun 交易手续费（协议 + 创建者，基点），用于保本价格计算
+    #[serde(default = "default_pumpfun_fee_bps")]
+    pub pumpfun_fee_bps: u64,
+    /// 按动态策略置信度缩放买入金额（阈值触发 / 覆盖规则的金额不缩放）
+    #[serde(default)]
+    pub confidence_sizing_enabled: bool,
+    /// 置信度不高于下限时的买入金额（SOL）
+    #[serde(default = "default_confidence_min_buy_sol")]
+    pub confidence_min_buy_sol: f64,
+    /// 置信度为 1.0 时的买入金额（SOL）
+    #[serde(default = "default_confidence_max_buy_sol")]
+    pub confidence_max_buy_sol: f64,
+    /// 置信度映射区间的下限（0-1）
+    #[serde(default = "default_confidence_sizing_floor")]
+    pub confidence_sizing_floor: f64,
+    /// 置信度映射曲线（linear / quadratic / sqrt）
+    #[serde(default = "default_confidence_sizing_curve")]
+    pub confidence_sizing_curve: String,
+
+    // 期望收益（EV）门控：胜率 = 截距 + 斜率 × 综合评分
+    /// 拒绝 EV 为负的买入（关闭时仍计算并记录 EV）
+    #[serde(default)]
+    pub enable_ev_gate: bool,
+    #[serde(default)]
+    pub ev_win_prob_intercept: f64,
+    #[serde(default = "default_ev_win_prob_slope")]
+    pub ev_win_prob_slope: f64,
+    /// 交易日志文件（JSON Lines，未设置时不记录）
+    #[serde(default)]
+    pub trade_journal_path: Option<String>,
+    /// 决策追踪：按 mint 记录策略每次判定命中 / 拒绝的条件（控制 API GET /decisions/<mint>）
+    #[serde(default)]
+    pub decision_trace_enabled: bool,
+    /// 每个 mint 保留的判定次数
+    #[serde(default = "default_decision_trace_depth")]
+    pub decision_trace_depth: usize,
+    /// 最多追踪的 mint 数（超出时淘汰最久未评估的 mint）
+    #[serde(default = "default_decision_trace_max_mints")]
+    pub decision_trace_max_mints: usize,
+
+    // A/B 实验：影子策略 B 与实盘共享指标流，只做纸面交易
+    #[serde(default)]
+    pub experiment_enabled: bool,
+    /// 影子策略的模式（conservative / balanced / aggressive / custom）
+    #[serde(default = "default_experiment_strategy_mode")]
+    pub experiment_strategy_mode: String,
+    /// 以下为单项参数覆盖（未设置时沿用所选模式的参数）
+    #[serde(default)]
+    pub experiment_min_composite_score: Option<f64>,
+    #[serde(default)]
+    pub experiment_take_profit_multiplier: Option<f64>,
+    #[serde(default)]
+    pub experiment_stop_loss_multiplier: Option<f64>,
+    #[serde(default)]
+    pub experiment_max_hold_secs: Option<u64>,
+    /// 紧急清仓（sell-all）使用的滑点，放宽以确保立即成交
+    #[serde(default = "default_emergency_sell_slippage_percent")]
+    pub emergency_sell_slippage_percent: f64,
+    /// 单笔卖出的最大价格冲击（百分比，超过则拆分为多笔顺序卖出；0 = 不拆分）
+    #[serde(default)]
+    pub max_sell_price_impact_percent: f64,
+    /// 拆分卖出的最大笔数
+    #[serde(default = "default_max_sell_chunks")]
+    pub max_sell_chunks: usize,
     pub max_positions: usize,  // 最大同时持仓数量
+    /// 持仓与链上余额对账间隔（秒，0 = 不对账）
+    #[serde(default = "default_reconcile_interval_secs")]
+    pub reconcile_interval_secs: u64,
+    /// 启动时钱包中未记录的 PumpFun token 的处理方式（off / list / import）
+    #[serde(default = "default_recover_unknown_tokens")]
+    pub recover_unknown_tokens: String,
+    /// 持仓状态机持久化文件（JSON，未设置时只保存在内存中）
+    #[serde(default)]
+    pub position_state_path: Option<String>,
+    /// 交易发件箱文件（发送前落盘，重启后按链上状态恢复；未设置时不记录）
+    #[serde(default)]
+    pub outbox_path: Option<String>,
+    /// 持仓的价格 / 流动性历史持久化文件（JSON，重启后预热监控；未设置时只保存在内存中）
+    #[serde(default)]
+    pub monitor_history_path: Option<String>,
+    /// 事件录制文件（JSON Lines，gRPC 解析出的事件连同接收时间追加写入，供 --replay 回放；未设置时不录制）
+    #[serde(default)]
+    pub event_record_path: Option<String>,
+    /// 窗口指标导出目录（策略引擎评估的每条指标追加为 CSV，离线研究 / 阈值校准；未设置时不导出）
+    #[serde(default)]
+    pub metrics_export_dir: Option<String>,
+    /// 导出文件超过该大小后轮转（MB，0 = 不按大小轮转）
+    #[serde(default = "default_metrics_export_rotate_mb")]
+    pub metrics_export_rotate_mb: u64,
+    /// 导出文件打开超过该时长后轮转（秒，0 = 不按时间轮转）
+    #[serde(default = "default_metrics_export_rotate_secs")]
+    pub metrics_export_rotate_secs: u64,
+    /// 回放模式下模拟钱包的初始余额（SOL）
+    #[serde(default = "default_replay_wallet_sol")]
+    pub replay_wallet_sol: f64,
+    /// 启用故障注入（需要 `chaos` feature，仅用于韧性测试）
+    #[serde(default)]
+    pub chaos_enabled: bool,
+    /// gRPC 事件延迟投递的概率（0.0 - 1.0）
+    #[serde(default)]
+    pub chaos_grpc_delay_probability: f64,
+    /// gRPC 事件延迟投递的最大时长（毫秒）
+    #[serde(default = "default_chaos_grpc_max_delay_ms")]
+    pub chaos_grpc_max_delay_ms: u64,
+    /// gRPC 事件被丢弃的概率（0.0 - 1.0）
+    #[serde(default)]
+    pub chaos_drop_event_probability: f64,
+    /// SWQOS 发送失败的概率（0.0 - 1.0）
+    #[serde(default)]
+    pub chaos_swqos_failure_probability: f64,
+    /// get_latest_blockhash 返回过期 blockhash 的概率（0.0 - 1.0）
+    #[serde(default)]
+    pub chaos_stale_blockhash_probability: f64,
+    /// 每个 RPC 端点的请求速率上限（次/秒，所有组件共享；0 = 不限流）
+    #[serde(default)]
+    pub rpc_rate_limit_per_sec: f64,
+    /// 令牌桶突发上限（0 = 等于每秒速率）
+    #[serde(default)]
+    pub rpc_rate_limit_burst: u32,
+
+    // 大盘健康度门控：SOL 价格 / 上线数 / 毕业率低于阈值时暂停开新仓（阈值 0 = 不检查）
+    #[serde(default)]
+    pub market_health_enabled: bool,
+    /// 上线数和毕业率的统计窗口（秒）
+    #[serde(default = "default_market_health_window_secs")]
+    pub market_health_window_secs: u64,
+    #[serde(default)]
+    pub min_sol_price_usd: f64,
+    /// 统计窗口内的最少上线数
+    #[serde(default)]
+    pub min_launches_per_window: usize,
+    /// 统计窗口内的最低毕业率（毕业数 / 上线数，百分比）
+    #[serde(default)]
+    pub min_graduation_rate_percent: f64,
+    /// SOL/USD 价格 API 及响应中价格字段的 JSON Pointer
+    #[serde(default = "default_sol_price_api_url")]
+    pub sol_price_api_url: String,
+    #[serde(default = "default_sol_price_json_pointer")]
+    pub sol_price_json_pointer: String,
+    /// 价格拉取间隔（秒，0 = 不拉取）
+    #[serde(default = "default_sol_price_interval_secs")]
+    pub sol_price_interval_secs: u64,
+
+    // 最大回撤暂停（权益 = 钱包 SOL + 持仓估值）
+    /// 回撤超过该百分比时暂停开新仓（0 = 禁用）
+    #[serde(default)]
+    pub max_drawdown_percent: f64,
+    /// 暂停后自动恢复的冷却时间（秒，0 = 只能通过控制 API 手动恢复）
+    #[serde(default = "default_drawdown_cooldown_secs")]
+    pub drawdown_cooldown_secs: u64,
+    /// 权益更新间隔（秒）
+    #[serde(default = "default_equity_check_interval_secs")]
+    pub equity_check_interval_secs: u64,
+
+    // 钱包余额检查（余额低于单笔买入储备时暂停买入）
+    /// 检查间隔（秒，0 = 禁用）
+    #[serde(default = "default_balance_check_interval_secs")]
+    pub balance_check_interval_secs: u64,
+    /// 储备中额外预留的 SOL（交易手续费 + ATA 租金）
+    #[serde(default = "default_balance_reserve_buffer_sol")]
+    pub balance_reserve_buffer_sol: f64,
+
+    // 支出限额（执行器签名前检查，独立于策略，见 spend_guard.rs）
+    /// 单笔交易最大支出（SOL，含 max_sol_cost 和 tip；0 = 不限制）
+    #[serde(default)]
+    pub max_spend_per_tx_sol: f64,
+    /// 滚动 1 小时累计最大支出（SOL，0 = 不限制）
+    #[serde(default)]
+    pub max_spend_per_hour_sol: f64,
+    /// 固定成本（tip + 优先费 + 网络费，按最后一次重试计算；ATA 租金平仓后可回收，不计入）占买入金额的最大比例，
+    /// 超过时拒绝买入（0 = 不检查）
+    #[serde(default = "default_max_fixed_cost_fraction")]
+    pub max_fixed_cost_fraction: f64,
+    /// 单币策略覆盖规则文件（JSON，按 mint / 创建者覆盖参数）
+    pub strategy_overrides_path: Option<String>,
+
+    // 预热观察期（新 token 观察期内只收集指标，不买入；0 = 不限制）
+    // 阈值 / 毕业触发同样受预热期约束（预热期内不评估，累计买入在预热结束后再判断）
+    /// 最短观察时长（秒）
+    #[serde(default)]
+    pub warmup_observation_secs: u64,
+    /// 最少累计事件数
+    #[serde(default)]
+    pub warmup_observation_events: usize,
+    /// 首波狙击是否跳过预热期
+    #[serde(default = "default_true")]
+    pub warmup_skip_first_wave: bool,
+
+    // token 年龄上限（常规策略，避免买入过老的曲线；0 = 不限制）
+    #[serde(default)]
+    pub max_token_age_secs: u64,
+    /// 未观察到创建事件（年龄未知）的 token 是否视为过老
+    #[serde(default)]
+    pub reject_unknown_token_age: bool,
 
     // 首波狙击策略参数
     pub enable_first_wave_sniper: bool,
     pub first_wave_inflow_multiplier: f64,
     pub first_wave_buy_ratio: f64,
+    /// 创建者首笔买入下限（SOL，过小 = 没有利益绑定；0 = 不限制）
+    #[serde(default)]
+    pub first_wave_min_dev_buy_sol: f64,
+    /// 创建者首笔买入上限（SOL，过大 = 砸盘风险；0 = 不限制）
+    #[serde(default)]
+    pub first_wave_max_dev_buy_sol: f64,
+
+    // 毕业狙击策略参数（bonding curve 接近完成时买入）
+    /// bonding curve 毕业目标（real SOL 储备）
+    #[serde(default = "default_graduation_target_sol")]
+    pub graduation_target_sol: f64,
+    #[serde(default)]
+    pub enable_graduation_sniper: bool,
+    /// 完成度越过该百分比时触发
+    #[serde(default = "default_graduation_progress_trigger")]
+    pub graduation_progress_trigger_percent: f64,
+    #[serde(default = "default_graduation_min_net_inflow")]
+    pub graduation_min_net_inflow_sol: f64,
+    #[serde(default = "default_graduation_min_buy_ratio")]
+    pub graduation_min_buy_ratio: f64,
 
     // 退出策略
     pub exit_buy_ratio_threshold: f64,
@@ -56,6 +449,13 @@ pub struct Config {
     pub hold_max_duration_secs: u64,
     pub take_profit_multiplier: f64,
     pub stop_loss_multiplier: f64,
+    /// 止盈止损评估循环间隔（毫秒）
+    #[serde(default = "default_exit_check_interval_ms")]
+    pub exit_check_interval_ms: u64,
+    /// 同一持仓的 Hold 信号最短处理间隔（毫秒，期间的 Hold 信号合并跳过，0 = 每个都处理）
+    /// 策略引擎目前不发出 Hold 信号，持仓退出由退出循环按 exit_check_interval_ms 评估
+    #[serde(default = "default_hold_coalesce_ms")]
+    pub hold_coalesce_ms: u64,
 
     // 监控参数
     pub monitor_new_tokens: bool,
@@ -72,9 +472,77 @@ pub struct Config {
     pub enable_whitelist: bool,
     pub enable_duplicate_detection: bool,
     pub duplicate_window_secs: u64,
+    // Token-2022 扩展：转账 hook / 永久委托 / 不可转让 / 默认冻结 / 可暂停的 token 拒绝买入
+    /// 可接受的转账手续费上限（bps，超过视为危险 token）
+    #[serde(default = "default_max_token_transfer_fee_bps")]
+    pub max_token_transfer_fee_bps: u16,
+    /// 拒绝危险 token 时自动拉黑 mint 和创建者
+    #[serde(default = "default_true")]
+    pub auto_blacklist_dangerous_tokens: bool,
+    // mint 权限检查：按策略模式启用（逗号分隔的模式名）
+    /// 拒绝 freeze authority 未撤销的 token
+    #[serde(default = "default_freeze_authority_check_modes")]
+    pub freeze_authority_check_modes: Vec<String>,
+    /// 拒绝 mint authority 不是 PumpFun（且未撤销）的 token
+    #[serde(default = "default_mint_authority_check_modes")]
+    pub mint_authority_check_modes: Vec<String>,
+    // 蜜罐检测：买入前模拟小额买入 + 立即卖出，卖不出则拒绝（首波狙击跳过）
+    #[serde(default)]
+    pub honeypot_simulation_enabled: bool,
+    /// 模拟买入的金额（SOL）
+    #[serde(default = "default_honeypot_probe_sol")]
+    pub honeypot_probe_sol: f64,
 
     // 动态策略参数
     pub dynamic_strategy_mode: String,
+    // 成功率反馈：平仓结果回馈动态策略，亏损后收紧买入阈值，连胜后放宽
+    #[serde(default = "default_true")]
+    pub enable_success_feedback: bool,
+    /// 每次调整的步长（系数乘以 1 ± step）
+    #[serde(default = "default_success_feedback_step")]
+    pub success_feedback_step: f64,
+    /// 连胜多少笔后放宽一次
+    #[serde(default = "default_success_feedback_win_streak")]
+    pub success_feedback_win_streak: u32,
+    /// 调整系数下限（最多放宽到的程度）
+    #[serde(default = "default_success_feedback_min_factor")]
+    pub success_feedback_min_factor: f64,
+    /// 调整系数上限（最多收紧到的程度）
+    #[serde(default = "default_success_feedback_max_factor")]
+    pub success_feedback_max_factor: f64,
+    // 时段自适应：按 UTC 小时 / 星期统计胜率，差时段提高综合评分要求并缩小仓位
+    #[serde(default = "default_true")]
+    pub enable_time_adaptation: bool,
+    /// 时段样本至少多少笔交易后才参与判断
+    #[serde(default = "default_time_bucket_min_trades")]
+    pub time_bucket_min_trades: u32,
+    /// 时段胜率低于此值视为差时段
+    #[serde(default = "default_bad_hour_win_rate")]
+    pub bad_hour_win_rate: f64,
+    /// 差时段综合评分要求乘数
+    #[serde(default = "default_bad_hour_score_multiplier")]
+    pub bad_hour_score_multiplier: f64,
+    /// 差时段仓位乘数
+    #[serde(default = "default_bad_hour_size_multiplier")]
+    pub bad_hour_size_multiplier: f64,
+    // 波动状态切换：按最近新币上线初期的平均波动率在保守 / 配置模式 / 激进买入条件之间切换
+    #[serde(default)]
+    pub enable_volatility_adaptation: bool,
+    /// 只采集上线后前多少秒内的波动率
+    #[serde(default = "default_regime_window_secs")]
+    pub regime_window_secs: u64,
+    /// 参与平均的最近新币数
+    #[serde(default = "default_regime_sample_mints")]
+    pub regime_sample_mints: usize,
+    /// 至少多少个新币样本后才切换
+    #[serde(default = "default_regime_min_samples")]
+    pub regime_min_samples: usize,
+    /// 平均波动率低于此值切换激进条件
+    #[serde(default = "default_regime_calm_volatility")]
+    pub regime_calm_volatility: f64,
+    /// 平均波动率高于此值切换保守条件
+    #[serde(default = "default_regime_volatile_volatility")]
+    pub regime_volatile_volatility: f64,
     // 🔥 新增：策略模式开关（布尔值控制）
     pub enable_conservative_mode: bool,
     pub enable_balanced_mode: bool,
@@ -124,6 +592,18 @@ pub struct Config {
     pub rug_pull_confidence_threshold: f64,
     pub monitor_interval_secs: u64,
     pub price_history_hours: i64,
+    /// 同一持仓的同类风险警报重复触发冷却（秒，Critical 警报不受限制）
+    #[serde(default = "default_alert_cooldown_secs")]
+    pub alert_cooldown_secs: u64,
+    /// 风险条件持续消失多久后自动解除警报（秒）
+    #[serde(default = "default_alert_auto_resolve_secs")]
+    pub alert_auto_resolve_secs: u64,
+    /// 检测到 rug pull 后自动拉黑创建者和大额卖家
+    #[serde(default = "default_true")]
+    pub enable_rug_auto_blacklist: bool,
+    /// 自动拉黑所需的最低 rug pull 置信度
+    #[serde(default = "default_rug_blacklist_confidence")]
+    pub rug_blacklist_confidence_threshold: f64,
 
     // 阈值触发策略参数
     pub enable_threshold_trigger: bool,
@@ -132,17 +612,122 @@ pub struct Config {
     pub threshold_buy_ratio: f64,
     pub threshold_min_buy_amount_sol: f64,
     pub threshold_max_buy_amount_sol: f64,
+    /// 多档阈值（累计买入 SOL:买入 SOL，逗号分隔，例如 5:0.1,20:0.5；为空时按上面的参数生成单档）
+    #[serde(default)]
+    pub threshold_tiers: Vec<String>,
+    /// 平仓后重新布防阈值触发（清零已触发档位，重新开始观察窗口）
+    #[serde(default)]
+    pub threshold_rearm_after_sell: bool,
+    /// 累计买入的统计方式: window（只统计主滑窗内的买入）/ lifetime（窗口创建以来的全部买入）
+    #[serde(default = "default_threshold_accumulation")]
+    pub threshold_accumulation: String,
+
+    // 卖压触发参数
+    /// 卖压触发：sell_pressure_window_secs 内累计卖出超过 sell_pressure_threshold_sol 时立即卖出持仓
+    #[serde(default)]
+    pub enable_sell_pressure_trigger: bool,
+    #[serde(default = "default_sell_pressure_window_secs")]
+    pub sell_pressure_window_secs: u64,
+    #[serde(default = "default_sell_pressure_threshold_sol")]
+    pub sell_pressure_threshold_sol: f64,
 
     // 动能衰减参数
     pub momentum_buy_ratio_threshold: f64,
     pub momentum_net_inflow_threshold: f64,
     pub momentum_activity_threshold: f64,
     pub momentum_composite_score_threshold: f64,
+    #[serde(default = "default_momentum_acceleration_threshold")]
+    pub momentum_acceleration_threshold: f64,
+    /// 严格模式：至少 3 项（或全部已启用项）同时触发才判定衰减
+    #[serde(default)]
+    pub momentum_strict_mode: bool,
+    /// 禁用的衰减检查（逗号分隔: buy_ratio,net_inflow,activity,acceleration,composite_score）
+    #[serde(default)]
+    pub momentum_disabled_checks: Vec<String>,
+    // 各策略模式独立的动能衰减参数（未设置时使用上面的全局参数）
+    // 保守模式
+    #[serde(default)]
+    pub conservative_momentum_buy_ratio_threshold: Option<f64>,
+    #[serde(default)]
+    pub conservative_momentum_net_inflow_threshold: Option<f64>,
+    #[serde(default)]
+    pub conservative_momentum_activity_threshold: Option<f64>,
+    #[serde(default)]
+    pub conservative_momentum_acceleration_threshold: Option<f64>,
+    #[serde(default)]
+    pub conservative_momentum_composite_score_threshold: Option<f64>,
+    #[serde(default)]
+    pub conservative_momentum_strict_mode: Option<bool>,
+    #[serde(default)]
+    pub conservative_momentum_disabled_checks: Option<Vec<String>>,
+    // 平衡模式
+    #[serde(default)]
+    pub balanced_momentum_buy_ratio_threshold: Option<f64>,
+    #[serde(default)]
+    pub balanced_momentum_net_inflow_threshold: Option<f64>,
+    #[serde(default)]
+    pub balanced_momentum_activity_threshold: Option<f64>,
+    #[serde(default)]
+    pub balanced_momentum_acceleration_threshold: Option<f64>,
+    #[serde(default)]
+    pub balanced_momentum_composite_score_threshold: Option<f64>,
+    #[serde(default)]
+    pub balanced_momentum_strict_mode: Option<bool>,
+    #[serde(default)]
+    pub balanced_momentum_disabled_checks: Option<Vec<String>>,
+    // 激进模式
+    #[serde(default)]
+    pub aggressive_momentum_buy_ratio_threshold: Option<f64>,
+    #[serde(default)]
+    pub aggressive_momentum_net_inflow_threshold: Option<f64>,
+    #[serde(default)]
+    pub aggressive_momentum_activity_threshold: Option<f64>,
+    #[serde(default)]
+    pub aggressive_momentum_acceleration_threshold: Option<f64>,
+    #[serde(default)]
+    pub aggressive_momentum_composite_score_threshold: Option<f64>,
+    #[serde(default)]
+    pub aggressive_momentum_strict_mode: Option<bool>,
+    #[serde(default)]
+    pub aggressive_momentum_disabled_checks: Option<Vec<String>>,
+    // 自定义模式
+    #[serde(default)]
+    pub custom_momentum_buy_ratio_threshold: Option<f64>,
+    #[serde(default)]
+    pub custom_momentum_net_inflow_threshold: Option<f64>,
+    #[serde(default)]
+    pub custom_momentum_activity_threshold: Option<f64>,
+    #[serde(default)]
+    pub custom_momentum_acceleration_threshold: Option<f64>,
+    #[serde(default)]
+    pub custom_momentum_composite_score_threshold: Option<f64>,
+    #[serde(default)]
+    pub custom_momentum_strict_mode: Option<bool>,
+    #[serde(default)]
+    pub custom_momentum_disabled_checks: Option<Vec<String>>,
 
     // 系统参数
     pub event_queue_capacity: usize,
     pub aggregator_cleanup_interval_secs: u64,
     pub aggregator_window_ttl_secs: u64,
+    /// 每个 mint 保留的事件历史数量（高级指标计算）
+    #[serde(default = "default_event_history_depth")]
+    pub event_history_depth: usize,
+    /// 聚合器窗口 + 事件历史的内存预算（MB，0 = 不限制；超出时淘汰最久没有交易的 mint）
+    #[serde(default = "default_aggregator_memory_budget_mb")]
+    pub aggregator_memory_budget_mb: u64,
+    /// 价格缓存中曲线的保留时间（秒，超过未更新则清理）
+    #[serde(default = "default_price_cache_ttl_secs")]
+    pub price_cache_ttl_secs: u64,
+    /// 管道背压采样间隔（毫秒）
+    #[serde(default = "default_pipeline_monitor_interval_ms")]
+    pub pipeline_monitor_interval_ms: u64,
+    /// 事件队列 / 指标通道 / 信号通道占用率超过该百分比时告警
+    #[serde(default = "default_backpressure_warn_percent")]
+    pub backpressure_warn_percent: f64,
+    /// 策略评估 worker 数（按 mint 分片，同一 mint 的指标始终由同一 worker 按顺序评估）
+    #[serde(default = "default_strategy_workers")]
+    pub strategy_workers: usize,
 }
 
 impl Config {
@@ -150,8 +735,16 @@ impl Config {
     pub fn from_env() -> Result<Self> {
         dotenv::dotenv().ok();
 
-        let config = envy::from_env::<Config>()
-            .context("Failed to load configuration from environment variables")?;
+        Self::from_vars(std::env::vars())
+    }
+
+    /// 从给定的键值对加载配置（不读取进程环境变量和 .env，集成测试使用）
+    pub fn from_vars<I>(vars: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let config = envy::from_iter::<_, Config>(vars)
+            .context("Failed to load configuration from variables")?;
 
         config.validate()?;
 
@@ -180,6 +773,79 @@ impl Config {
             anyhow::bail!("max_positions must be > 0");
         }
 
+        if self.max_drawdown_percent < 0.0 || self.max_drawdown_percent >= 100.0 {
+            anyhow::bail!("max_drawdown_percent must be between 0.0 and 100.0 (exclusive)");
+        }
+
+        if self.equity_check_interval_secs == 0 {
+            anyhow::bail!("equity_check_interval_secs must be > 0");
+        }
+
+        if self.balance_reserve_buffer_sol < 0.0 {
+            anyhow::bail!("balance_reserve_buffer_sol must be >= 0.0");
+        }
+
+        // 🔥 新增: 支出限额不能小于默认买入金额，否则所有买入都会被拒绝
+        if self.max_spend_per_tx_sol < 0.0 || self.max_spend_per_hour_sol < 0.0 {
+            anyhow::bail!("max_spend_per_tx_sol / max_spend_per_hour_sol must be >= 0.0");
+        }
+        if self.max_spend_per_tx_sol > 0.0 && self.max_spend_per_tx_sol < self.snipe_amount_sol {
+            anyhow::bail!("max_spend_per_tx_sol must be >= snipe_amount_sol (or 0 to disable)");
+        }
+        if self.max_spend_per_hour_sol > 0.0 && self.max_spend_per_hour_sol < self.snipe_amount_sol {
+            anyhow::bail!("max_spend_per_hour_sol must be >= snipe_amount_sol (or 0 to disable)");
+        }
+
+        if self.replay_wallet_sol < 0.0 {
+            anyhow::bail!("replay_wallet_sol must be >= 0.0");
+        }
+
+        // 🔥 新增: 故障注入只在 chaos feature 构建中生效，避免误以为已启用
+        if self.chaos_enabled && !cfg!(feature = "chaos") {
+            anyhow::bail!("chaos_enabled requires building with `--features chaos`");
+        }
+        for (name, probability) in [
+            ("chaos_grpc_delay_probability", self.chaos_grpc_delay_probability),
+            ("chaos_drop_event_probability", self.chaos_drop_event_probability),
+            ("chaos_swqos_failure_probability", self.chaos_swqos_failure_probability),
+            ("chaos_stale_blockhash_probability", self.chaos_stale_blockhash_probability),
+        ] {
+            if !(0.0..=1.0).contains(&probability) {
+                anyhow::bail!("{} must be between 0.0 and 1.0", name);
+            }
+        }
+        if self.max_fixed_cost_fraction < 0.0 {
+            anyhow::bail!("max_fixed_cost_fraction must be >= 0.0");
+        }
+
+        if !["smaller", "larger"].contains(&self.window_mode.as_str()) {
+            anyhow::bail!("window_mode must be one of: smaller, larger");
+        }
+
+        let per_mode_windows = [
+            self.conservative_window_duration_secs,
+            self.balanced_window_duration_secs,
+            self.aggressive_window_duration_secs,
+            self.custom_window_duration_secs,
+        ];
+        if per_mode_windows.contains(&Some(0)) {
+            anyhow::bail!("per-mode window_duration_secs must be > 0");
+        }
+
+        let per_mode_events = [
+            self.conservative_window_max_events,
+            self.balanced_window_max_events,
+            self.aggressive_window_max_events,
+            self.custom_window_max_events,
+        ];
+        if per_mode_events.contains(&Some(0)) {
+            anyhow::bail!("per-mode window_max_events must be > 0");
+        }
+
+        if self.timeframe_windows_secs.contains(&0) {
+            anyhow::bail!("timeframe_windows_secs must all be > 0");
+        }
+
         // 验证阈值范围
         if self.buy_ratio_threshold < 0.0 || self.buy_ratio_threshold > 1.0 {
             anyhow::bail!("buy_ratio_threshold must be between 0.0 and 1.0");
@@ -194,6 +860,22 @@ impl Config {
             anyhow::bail!("snipe_amount_sol must be greater than 0");
         }
 
+        // 🔥 新增: 置信度仓位缩放
+        if self.confidence_sizing_enabled {
+            if self.confidence_min_buy_sol <= 0.0 || self.confidence_max_buy_sol < self.confidence_min_buy_sol {
+                anyhow::bail!("confidence_min_buy_sol must be > 0 and <= confidence_max_buy_sol");
+            }
+            if !(0.0..1.0).contains(&self.confidence_sizing_floor) {
+                anyhow::bail!("confidence_sizing_floor must be >= 0.0 and < 1.0");
+            }
+            if crate::sizing::SizingCurve::parse(&self.confidence_sizing_curve).is_none() {
+                anyhow::bail!("confidence_sizing_curve must be one of: linear, quadratic, sqrt");
+            }
+            if self.max_spend_per_tx_sol > 0.0 && self.max_spend_per_tx_sol < self.confidence_max_buy_sol {
+                anyhow::bail!("max_spend_per_tx_sol must be >= confidence_max_buy_sol (or 0 to disable)");
+            }
+        }
+
         if self.net_inflow_threshold_sol <= 0.0 {
             anyhow::bail!("net_inflow_threshold_sol must be greater than 0");
         }
@@ -216,11 +898,87 @@ impl Config {
             anyhow::bail!("max_trade_frequency must be greater than 0");
         }
 
+        if self.max_token_transfer_fee_bps > 10_000 {
+            anyhow::bail!("max_token_transfer_fee_bps must be <= 10000");
+        }
+
+        if self.honeypot_simulation_enabled && self.honeypot_probe_sol <= 0.0 {
+            anyhow::bail!("honeypot_probe_sol must be greater than 0");
+        }
+
+        for (name, level) in [
+            ("feed_commitment", Some(&self.feed_commitment)),
+            ("send_commitment", self.send_commitment.as_ref()),
+            ("confirm_commitment", Some(&self.confirm_commitment)),
+            ("balance_commitment", self.balance_commitment.as_ref()),
+        ] {
+            if let Some(level) = level {
+                if parse_commitment(level).is_none() {
+                    anyhow::bail!("{} must be one of: processed, confirmed, finalized (got '{}')", name, level);
+                }
+            }
+        }
+
+        if self.confirm_commitment.eq_ignore_ascii_case("processed") {
+            anyhow::bail!("confirm_commitment must be 'confirmed' or 'finalized'");
+        }
+
+        for account in self.grpc_account_required.iter().chain(&self.grpc_account_exclude) {
+            if Pubkey::try_from(account.as_str()).is_err() {
+                anyhow::bail!("grpc_account_required / grpc_account_exclude contain an invalid pubkey: '{}'", account);
+            }
+        }
+
+        if self.grpc_ping_interval_secs > 0 && self.grpc_max_missed_pongs == 0 {
+            anyhow::bail!("grpc_max_missed_pongs must be greater than 0 when grpc_ping_interval_secs is set");
+        }
+
+        if self.feed_commitment.eq_ignore_ascii_case("processed") && self.feed_reconcile_timeout_ms == 0 {
+            anyhow::bail!("feed_reconcile_timeout_ms must be greater than 0 when feed_commitment is 'processed'");
+        }
+
+        for mode in self.freeze_authority_check_modes.iter().chain(&self.mint_authority_check_modes) {
+            if !["conservative", "balanced", "aggressive", "custom"].contains(&mode.as_str()) {
+                anyhow::bail!("authority check modes must be one of: conservative, balanced, aggressive, custom (got '{}')", mode);
+            }
+        }
+
         // 验证动态策略模式
         if !["conservative", "balanced", "aggressive"].contains(&self.dynamic_strategy_mode.as_str()) {
             anyhow::bail!("dynamic_strategy_mode must be one of: conservative, balanced, aggressive");
         }
 
+        if self.success_feedback_step <= 0.0 || self.success_feedback_step >= 1.0 {
+            anyhow::bail!("success_feedback_step must be between 0.0 and 1.0 (exclusive)");
+        }
+
+        if self.success_feedback_min_factor <= 0.0
+            || self.success_feedback_min_factor > 1.0
+            || self.success_feedback_max_factor < 1.0
+        {
+            anyhow::bail!("success_feedback_min_factor must be in (0.0, 1.0] and success_feedback_max_factor must be >= 1.0");
+        }
+
+        if self.regime_calm_volatility >= self.regime_volatile_volatility {
+            anyhow::bail!("regime_calm_volatility must be < regime_volatile_volatility");
+        }
+
+        if self.regime_sample_mints == 0 {
+            anyhow::bail!("regime_sample_mints must be > 0");
+        }
+
+        if !(0.0..=1.0).contains(&self.bad_hour_win_rate) {
+            anyhow::bail!("bad_hour_win_rate must be between 0.0 and 1.0");
+        }
+
+        if self.bad_hour_score_multiplier < 1.0 {
+            anyhow::bail!("bad_hour_score_multiplier must be >= 1.0");
+        }
+
+        if self.bad_hour_size_multiplier <= 0.0 || self.bad_hour_size_multiplier > 1.0 {
+            anyhow::bail!("bad_hour_size_multiplier must be between 0.0 (exclusive) and 1.0");
+        }
+
         // 验证动态策略参数范围
         if self.conservative_min_buy_ratio < 0.0 || self.conservative_min_buy_ratio > 1.0 {
             anyhow::bail!("conservative_min_buy_ratio must be between 0.0 and 1.0");
@@ -245,6 +1003,21 @@ impl Config {
             }
         }
 
+        // 验证毕业狙击策略参数
+        if self.graduation_target_sol <= 0.0 {
+            anyhow::bail!("graduation_target_sol must be greater than 0");
+        }
+
+        if self.enable_graduation_sniper {
+            if self.graduation_progress_trigger_percent <= 0.0 || self.graduation_progress_trigger_percent >= 100.0 {
+                anyhow::bail!("graduation_progress_trigger_percent must be between 0.0 and 100.0");
+            }
+
+            if self.graduation_min_buy_ratio < 0.0 || self.graduation_min_buy_ratio > 1.0 {
+                anyhow::bail!("graduation_min_buy_ratio must be between 0.0 and 1.0");
+            }
+        }
+
         // 验证阈值触发策略参数
         if self.enable_threshold_trigger {
             if self.threshold_observation_window_secs == 0 {
@@ -266,6 +1039,22 @@ impl Config {
             if self.threshold_max_buy_amount_sol < self.threshold_min_buy_amount_sol {
                 anyhow::bail!("threshold_max_buy_amount_sol must be >= threshold_min_buy_amount_sol");
             }
+
+            crate::threshold::tiers_from_config(self)?;
+
+            if crate::threshold::ThresholdAccumulation::parse(&self.threshold_accumulation).is_none() {
+                anyhow::bail!("threshold_accumulation must be one of: window, lifetime");
+            }
+        }
+
+        if self.enable_sell_pressure_trigger {
+            if self.sell_pressure_window_secs == 0 {
+                anyhow::bail!("sell_pressure_window_secs must be greater than 0");
+            }
+
+            if self.sell_pressure_threshold_sol <= 0.0 {
+                anyhow::bail!("sell_pressure_threshold_sol must be greater than 0");
+            }
         }
 
         // 🔥 补充: 验证滑点参数
@@ -277,6 +1066,79 @@ impl Config {
             anyhow::bail!("max_slippage_percent must be between 0.0 and 100.0");
         }
 
+        if self.max_sell_price_impact_percent < 0.0 || self.max_sell_price_impact_percent >= 100.0 {
+            anyhow::bail!("max_sell_price_impact_percent must be between 0.0 and 100.0 (exclusive)");
+        }
+
+        if !(0.0..=1.0).contains(&self.ev_win_prob_intercept) {
+            anyhow::bail!("ev_win_prob_intercept must be between 0.0 and 1.0");
+        }
+
+        if !["conservative", "balanced", "aggressive", "custom"].contains(&self.experiment_strategy_mode.as_str()) {
+            anyhow::bail!("experiment_strategy_mode must be one of: conservative, balanced, aggressive, custom");
+        }
+
+        if self.market_health_enabled && self.market_health_window_secs == 0 {
+            anyhow::bail!("market_health_window_secs must be > 0");
+        }
+
+        if !(0.0..=100.0).contains(&self.min_graduation_rate_percent) {
+            anyhow::bail!("min_graduation_rate_percent must be between 0.0 and 100.0");
+        }
+
+        if !["off", "list", "import"].contains(&self.recover_unknown_tokens.as_str()) {
+            anyhow::bail!("recover_unknown_tokens must be one of: off, list, import");
+        }
+
+        if self.rpc_rate_limit_per_sec < 0.0 {
+            anyhow::bail!("rpc_rate_limit_per_sec must be >= 0");
+        }
+
+        if self.max_sell_chunks == 0 {
+            anyhow::bail!("max_sell_chunks must be > 0");
+        }
+
+        if self.pumpfun_fee_bps >= 10_000 {
+            anyhow::bail!("pumpfun_fee_bps must be < 10000");
+        }
+
+        if self.emergency_sell_slippage_percent <= 0.0 || self.emergency_sell_slippage_percent > 100.0 {
+            anyhow::bail!("emergency_sell_slippage_percent must be between 0.0 (exclusive) and 100.0");
+        }
+
+        // 🔥 新增: 钱包来源必须且只能配置一个
+        let wallet_sources = self.wallet_sources();
+        match wallet_sources.len() {
+            0 => anyhow::bail!("No wallet configured: set one of wallet_private_key, wallet_keypair_path, wallet_keystore_path, wallet_signer_command, wallet_remote_signer_url or wallet_ledger_locator"),
+            1 => {}
+            _ => anyhow::bail!("Multiple wallet sources configured ({}); set exactly one", wallet_sources.join(", ")),
+        }
+
+        if self.control_api_enabled {
+            let bind = self.control_api_bind.parse::<std::net::SocketAddr>()
+                .map_err(|e| anyhow::anyhow!("Invalid control_api_bind '{}': {}", self.control_api_bind, e))?;
+            // 非回环地址必须配置 token，否则清仓 / 暂停 / 调参接口对网络完全开放
+            if !bind.ip().is_loopback() && self.control_api_token.as_deref().is_none_or(str::is_empty) {
+                anyhow::bail!("control_api_bind {} is not a loopback address; set control_api_token to expose the control API", bind);
+            }
+        }
+
+        if self.control_grpc_enabled {
+            let bind = self.control_grpc_bind.parse::<std::net::SocketAddr>()
+                .map_err(|e| anyhow::anyhow!("Invalid control_grpc_bind '{}': {}", self.control_grpc_bind, e))?;
+            if !bind.ip().is_loopback() && self.control_api_token.as_deref().is_none_or(str::is_empty) {
+                anyhow::bail!("control_grpc_bind {} is not a loopback address; set control_api_token to expose the control gRPC service", bind);
+            }
+        }
+
+        if self.event_feed_enabled {
+            let bind = self.event_feed_bind.parse::<std::net::SocketAddr>()
+                .map_err(|e| anyhow::anyhow!("Invalid event_feed_bind '{}': {}", self.event_feed_bind, e))?;
+            if !bind.ip().is_loopback() && self.event_feed_token.as_deref().is_none_or(str::is_empty) {
+                anyhow::bail!("event_feed_bind {} is not a loopback address; set event_feed_token to expose the event feed", bind);
+            }
+        }
+
         // 🔥 补充: 验证止盈止损参数
         if self.take_profit_multiplier < 0.0 {
             anyhow::bail!("take_profit_multiplier must be >= 0.0");
@@ -291,6 +1153,10 @@ impl Config {
             anyhow::bail!("acceleration_multiplier must be >= 0.0");
         }
 
+        if self.min_inflow_rate_sol_per_sec < 0.0 || self.min_trade_rate_per_sec < 0.0 {
+            anyhow::bail!("min_inflow_rate_sol_per_sec and min_trade_rate_per_sec must be >= 0.0");
+        }
+
         // 🔥 补充: 验证系统参数
         if self.event_queue_capacity == 0 {
             anyhow::bail!("event_queue_capacity must be > 0");
@@ -304,26 +1170,195 @@ impl Config {
             anyhow::bail!("aggregator_window_ttl_secs must be > 0");
         }
 
+        // 高级指标至少需要 5 个事件
+        if self.event_history_depth < 5 {
+            anyhow::bail!("event_history_depth must be >= 5");
+        }
+
+        let disabled_check_lists = [
+            Some(&self.momentum_disabled_checks),
+            self.conservative_momentum_disabled_checks.as_ref(),
+            self.balanced_momentum_disabled_checks.as_ref(),
+            self.aggressive_momentum_disabled_checks.as_ref(),
+            self.custom_momentum_disabled_checks.as_ref(),
+        ];
+        for check in disabled_check_lists.into_iter().flatten().flatten() {
+            if !crate::momentum_decay::DECAY_CHECK_NAMES.contains(&check.as_str()) {
+                anyhow::bail!(
+                    "Unknown momentum decay check '{}', expected one of {:?}",
+                    check,
+                    crate::momentum_decay::DECAY_CHECK_NAMES
+                );
+            }
+        }
+
+        if self.confirmation_fallback_poll_ms == 0 {
+            anyhow::bail!("confirmation_fallback_poll_ms must be > 0");
+        }
+
+        if self.tx_retry_max_attempts == 0 {
+            anyhow::bail!("tx_retry_max_attempts must be > 0");
+        }
+
+        if self.retry_fee_multiplier < 1.0 {
+            anyhow::bail!("retry_fee_multiplier must be >= 1.0");
+        }
+
+        if self.send_backend_order.is_empty() {
+            anyhow::bail!("send_backend_order must not be empty");
+        }
+        for backend in &self.send_backend_order {
+            if !SEND_BACKENDS.contains(&backend.as_str()) {
+                anyhow::bail!("Unknown send backend '{}', expected one of {:?}", backend, SEND_BACKENDS);
+            }
+        }
+
+        if self.tpu_fanout_slots == 0
+            || self.tpu_fanout_slots > crate::executor::tpu_sender::MAX_TPU_FANOUT_SLOTS
+        {
+            anyhow::bail!(
+                "tpu_fanout_slots must be between 1 and {}",
+                crate::executor::tpu_sender::MAX_TPU_FANOUT_SLOTS
+            );
+        }
+
+        if self.first_wave_min_dev_buy_sol < 0.0 || self.first_wave_max_dev_buy_sol < 0.0 {
+            anyhow::bail!("first_wave dev buy bounds must be >= 0");
+        }
+
+        if self.first_wave_max_dev_buy_sol > 0.0
+            && self.first_wave_min_dev_buy_sol > self.first_wave_max_dev_buy_sol
+        {
+            anyhow::bail!("first_wave_min_dev_buy_sol must be <= first_wave_max_dev_buy_sol");
+        }
+
+        if self.exit_check_interval_ms == 0 {
+            anyhow::bail!("exit_check_interval_ms must be > 0");
+        }
+
+        if self.monitor_interval_secs == 0 {
+            anyhow::bail!("monitor_interval_secs must be > 0");
+        }
+
+        // 否则每轮检查之间警报都会被自动解除，冷却和确认失效
+        if self.monitor_interval_secs >= self.alert_auto_resolve_secs {
+            anyhow::bail!("monitor_interval_secs must be < alert_auto_resolve_secs");
+        }
+
+        if self.price_cache_ttl_secs == 0 {
+            anyhow::bail!("price_cache_ttl_secs must be > 0");
+        }
+
+        if self.pipeline_monitor_interval_ms == 0 {
+            anyhow::bail!("pipeline_monitor_interval_ms must be > 0");
+        }
+
+        if self.strategy_workers == 0 {
+            anyhow::bail!("strategy_workers must be > 0");
+        }
+
+        if self.backpressure_warn_percent <= 0.0 || self.backpressure_warn_percent > 100.0 {
+            anyhow::bail!("backpressure_warn_percent must be between 0 and 100");
+        }
+
+        if self.rug_blacklist_confidence_threshold < 0.0 || self.rug_blacklist_confidence_threshold > 1.0 {
+            anyhow::bail!("rug_blacklist_confidence_threshold must be between 0.0 and 1.0");
+        }
+
         Ok(())
     }
 
+    /// 已配置的钱包来源
+    fn wallet_sources(&self) -> Vec<&'static str> {
+        let mut sources = Vec::new();
+        if !self.wallet_private_key.is_empty() {
+            sources.push("wallet_private_key");
+        }
+        if self.wallet_keypair_path.as_deref().is_some_and(|path| !path.is_empty()) {
+            sources.push("wallet_keypair_path");
+        }
+        if self.wallet_keystore_path.as_deref().is_some_and(|path| !path.is_empty()) {
+            sources.push("wallet_keystore_path");
+        }
+        if self.wallet_signer_command.as_deref().is_some_and(|command| !command.is_empty()) {
+            sources.push("wallet_signer_command");
+        }
+        if self.wallet_remote_signer_url.as_deref().is_some_and(|url| !url.is_empty()) {
+            sources.push("wallet_remote_signer_url");
+        }
+        if self.wallet_ledger_locator.as_deref().is_some_and(|locator| !locator.is_empty()) {
+            sources.push("wallet_ledger_locator");
+        }
+        sources
+    }
+
     /// 获取钱包 Keypair
+    ///
+    /// 🔥 修复: 按配置的来源加载（明文私钥 / keypair 文件 / 加密 keystore / 外部命令），无效私钥返回错误而不是 panic
+    /// 远程签名服务 / Ledger 没有本地私钥，使用 signer::from_config
     pub fn get_keypair(&self) -> Result<Keypair> {
-        let keypair = Keypair::from_base58_string(&self.wallet_private_key);
-        Ok(keypair)
+        match self.wallet_sources().first().copied() {
+            Some(source @ ("wallet_remote_signer_url" | "wallet_ledger_locator")) => {
+                anyhow::bail!("{} has no local keypair; use signer::from_config", source)
+            }
+            Some("wallet_keypair_path") => {
+                crate::keystore::read_keypair_file(self.wallet_keypair_path.as_deref().unwrap_or_default())
+            }
+            Some("wallet_keystore_path") => crate::keystore::read_keystore(
+                self.wallet_keystore_path.as_deref().unwrap_or_default(),
+                self.wallet_keystore_passphrase.as_deref(),
+            ),
+            Some("wallet_signer_command") => {
+                crate::keystore::keypair_from_command(self.wallet_signer_command.as_deref().unwrap_or_default())
+            }
+            _ => crate::keystore::keypair_from_base58(&self.wallet_private_key),
+        }
     }
 
     /// 获取 CommitmentConfig
     pub fn get_commitment_config(&self) -> CommitmentConfig {
-        match self.commitment_level.to_lowercase().as_str() {
-            "processed" => CommitmentConfig::processed(),
-            "confirmed" => CommitmentConfig::confirmed(),
-            "finalized" => CommitmentConfig::finalized(),
-            _ => {
-                log::warn!("⚠️  未知的 commitment_level: {}, 使用默认值 'confirmed'", self.commitment_level);
-                CommitmentConfig::confirmed()
-            }
-        }
+        parse_commitment(&self.commitment_level).unwrap_or_else(|| {
+            log::warn!("⚠️  未知的 commitment_level: {}, 使用默认值 'confirmed'", self.commitment_level);
+            CommitmentConfig::confirmed()
+        })
+    }
+
+    /// gRPC 事件流的 commitment
+    pub fn get_feed_commitment(&self) -> CommitmentConfig {
+        parse_commitment(&self.feed_commitment).unwrap_or_else(CommitmentConfig::confirmed)
+    }
+
+    /// 发送交易使用的 commitment（未配置时使用 commitment_level）
+    pub fn get_send_commitment(&self) -> CommitmentConfig {
+        self.send_commitment
+            .as_deref()
+            .and_then(parse_commitment)
+            .unwrap_or_else(|| self.get_commitment_config())
+    }
+
+    /// 判定交易已确认的 commitment
+    pub fn get_confirm_commitment(&self) -> CommitmentConfig {
+        parse_commitment(&self.confirm_commitment).unwrap_or_else(CommitmentConfig::confirmed)
+    }
+
+    /// 余额读取使用的 commitment（未配置时使用 commitment_level）
+    pub fn get_balance_commitment(&self) -> CommitmentConfig {
+        self.balance_commitment
+            .as_deref()
+            .and_then(parse_commitment)
+            .unwrap_or_else(|| self.get_commitment_config())
+    }
+
+    /// 单笔交易的网络费 + 优先费估算（lamports）
+    pub fn estimated_tx_fee_lamports(&self) -> u64 {
+        const BASE_SIGNATURE_FEE: u64 = 5_000;
+        BASE_SIGNATURE_FEE + self.compute_unit_price * self.compute_unit_limit as u64 / 1_000_000
+    }
+
+    /// 是否仍在新 token 预热观察期内（所有买入路径共用）
+    pub fn in_warmup(&self, observed_secs: u64, total_event_count: usize) -> bool {
+        observed_secs < self.warmup_observation_secs
+            || total_event_count < self.warmup_observation_events
     }
 
     /// 获取狙击金额（lamports）
@@ -331,6 +1366,25 @@ impl Config {
         (self.snipe_amount_sol * 1_000_000_000.0) as u64
     }
 
+    /// TPU 客户端使用的 websocket 端点（未配置时将 rpc_endpoint 的 http(s) 替换为 ws(s)）
+    pub fn tpu_websocket_url(&self) -> String {
+        if let Some(url) = &self.tpu_websocket_endpoint {
+            return url.clone();
+        }
+        if let Some(rest) = self.rpc_endpoint.strip_prefix("https://") {
+            format!("wss://{}", rest)
+        } else if let Some(rest) = self.rpc_endpoint.strip_prefix("http://") {
+            format!("ws://{}", rest)
+        } else {
+            self.rpc_endpoint.clone()
+        }
+    }
+
+    /// 蜜罐检测模拟买入金额（lamports）
+    pub fn get_honeypot_probe_lamports(&self) -> u64 {
+        (self.honeypot_probe_sol * 1_000_000_000.0) as u64
+    }
+
     /// 获取 LightSpeed Tip（lamports）
     pub fn get_lightspeed_tip_lamports(&self) -> u64 {
         (self.lightspeed_tip_sol * 1_000_000_000.0) as u64
@@ -339,42 +1393,186 @@ impl Config {
     /// 打印配置摘要
     pub fn print_summary(&self) {
         log::info!("=== Configuration Summary ===");
+        log::info!("Wallet Source: {}", self.wallet_sources().join(", "));
         log::info!("Network:");
         log::info!("  RPC: {}", self.rpc_endpoint);
+        if !self.rpc_fallback_endpoints.is_empty() {
+            log::info!("  RPC Fallbacks: {} (health check every {}s, failover after {} failures)",
+                self.rpc_fallback_endpoints.len(), self.rpc_health_check_interval_secs, self.rpc_failover_threshold);
+        }
         log::info!("  LightSpeed RPC: {}", self.rpc_lightspeed_endpoint);
         log::info!("  gRPC: {}", self.grpc_endpoint);
-        log::info!("  Commitment: {}", self.commitment_level);
+        if !self.grpc_account_required.is_empty() || !self.grpc_account_exclude.is_empty() {
+            log::info!("  gRPC Filters: required {:?}, exclude {:?}", self.grpc_account_required, self.grpc_account_exclude);
+        }
+        if self.grpc_ping_interval_secs > 0 {
+            log::info!("  gRPC Heartbeat: ping every {}s, resubscribe after {} missed pongs",
+                self.grpc_ping_interval_secs, self.grpc_max_missed_pongs);
+        }
+        log::info!("  gRPC Resume From Slot: {}", self.grpc_resume_from_slot);
+        log::info!("  gRPC Dedup: {} entries, TTL {}s", self.grpc_dedup_capacity, self.grpc_dedup_ttl_secs);
+        log::info!("  Commitment: {} (feed {:?}, send {:?}, confirm {:?}, balance {:?})",
+            self.commitment_level,
+            self.get_feed_commitment().commitment,
+            self.get_send_commitment().commitment,
+            self.get_confirm_commitment().commitment,
+            self.get_balance_commitment().commitment);
+        if self.feed_commitment.eq_ignore_ascii_case("processed") {
+            log::info!("  Feed Reconcile: timeout {}ms, wait before buy: {}",
+                self.feed_reconcile_timeout_ms, self.feed_reconcile_wait_before_buy);
+        }
         log::info!("");
         log::info!("LightSpeed:");
         log::info!("  Enabled: {}", self.use_lightspeed);
         log::info!("  Tip: {} SOL", self.lightspeed_tip_sol);
         log::info!("");
+        log::info!("Send Backends: {:?} (TPU direct: {})", self.send_backend_order, self.tpu_direct_enabled);
+        log::info!("");
         log::info!("Compute Budget:");
         log::info!("  CU Limit: {}", self.compute_unit_limit);
         log::info!("  CU Price: {}", self.compute_unit_price);
+        log::info!("  Retry: {} attempts, fee x{:.2}/retry", self.tx_retry_max_attempts, self.retry_fee_multiplier);
+        log::info!("  Wallet Sweep: {} (interval {}s)", self.wallet_sweep_enabled, self.wallet_sweep_interval_secs);
+        log::info!("  Control API: {} ({})", self.control_api_enabled, self.control_api_bind);
+        log::info!("  Control gRPC: {} ({})", self.control_grpc_enabled, self.control_grpc_bind);
+        log::info!("  Event Feed: {} (ws://{}, buffer {})",
+            self.event_feed_enabled, self.event_feed_bind, self.event_feed_buffer);
         log::info!("");
         log::info!("Strategy:");
         log::info!("  Window Duration: {}s", self.window_duration_secs);
+        log::info!("  Timeframes: {:?}s (alignment required: {})",
+            self.timeframe_windows_secs, self.require_timeframe_alignment);
         log::info!("  Buy Ratio Threshold: {:.2}%", self.buy_ratio_threshold * 100.0);
         log::info!("  Net Inflow Threshold: {} SOL", self.net_inflow_threshold_sol);
-        log::info!("  Acceleration Required: {}", self.acceleration_required);
+        log::info!("  Acceleration Required: {} (normalized: {})",
+            self.acceleration_required, self.use_normalized_acceleration);
+        if self.min_inflow_rate_sol_per_sec > 0.0 || self.min_trade_rate_per_sec > 0.0 {
+            log::info!("  Min Rates: {} SOL/s inflow, {} trades/s",
+                self.min_inflow_rate_sol_per_sec, self.min_trade_rate_per_sec);
+        }
         log::info!("  Max Slippage: {:.1}%", self.max_slippage_percent);
+        log::info!("  Success Feedback: {} (step {:.2}, loosen after {} wins, factor {:.2}-{:.2})",
+            self.enable_success_feedback, self.success_feedback_step, self.success_feedback_win_streak,
+            self.success_feedback_min_factor, self.success_feedback_max_factor);
+        log::info!("  Time Adaptation: {} (win rate < {:.0}% after {} trades: score x{:.2}, size x{:.2})",
+            self.enable_time_adaptation, self.bad_hour_win_rate * 100.0, self.time_bucket_min_trades,
+            self.bad_hour_score_multiplier, self.bad_hour_size_multiplier);
+        log::info!("  Volatility Regimes: {} (first {}s of last {} launches, calm < {:.3}, volatile > {:.3})",
+            self.enable_volatility_adaptation, self.regime_window_secs, self.regime_sample_mints,
+            self.regime_calm_volatility, self.regime_volatile_volatility);
         log::info!("");
         log::info!("Trading:");
         log::info!("  Snipe Amount: {} SOL", self.snipe_amount_sol);
+        if self.confidence_sizing_enabled {
+            log::info!("  Confidence Sizing: {} - {} SOL ({} above confidence {:.2})",
+                self.confidence_min_buy_sol, self.confidence_max_buy_sol,
+                self.confidence_sizing_curve, self.confidence_sizing_floor);
+        }
         log::info!("  Slippage: {:.1}%", self.slippage_percent);
+        log::info!("  Emergency Sell Slippage: {:.1}%", self.emergency_sell_slippage_percent);
+        log::info!("  PumpFun Fee: {} bps (included in breakeven)", self.pumpfun_fee_bps);
+        log::info!("  EV Gate: {} (win prob = {:.2} + {:.2} × score)",
+            if self.enable_ev_gate { "ENABLED" } else { "DISABLED" },
+            self.ev_win_prob_intercept, self.ev_win_prob_slope);
+        if self.decision_trace_enabled {
+            log::info!("  Decision Trace: last {} decisions × {} mints", self.decision_trace_depth, self.decision_trace_max_mints);
+        }
+        if self.experiment_enabled {
+            log::info!("  A/B Experiment: shadow strategy '{}' (paper trading)", self.experiment_strategy_mode);
+        }
+        if self.max_sell_price_impact_percent > 0.0 {
+            log::info!("  Split Exits: max impact {:.1}%/sell, up to {} sells",
+                self.max_sell_price_impact_percent, self.max_sell_chunks);
+        }
+        if self.max_drawdown_percent > 0.0 {
+            log::info!("  Max Drawdown: {:.1}% (cooldown {}s)", self.max_drawdown_percent, self.drawdown_cooldown_secs);
+        }
+        if self.market_health_enabled {
+            log::info!("  Market Health Gate: SOL >= ${:.2}, launches >= {}, graduation rate >= {:.2}% (window {}s)",
+                self.min_sol_price_usd, self.min_launches_per_window,
+                self.min_graduation_rate_percent, self.market_health_window_secs);
+        }
+        log::info!("  Position Reconcile Interval: {}s", self.reconcile_interval_secs);
+        if self.rpc_rate_limit_per_sec > 0.0 {
+            log::info!("  RPC Rate Limit: {:.1} req/s per endpoint (burst {})",
+                self.rpc_rate_limit_per_sec, self.rpc_rate_limit_burst);
+        }
+        log::info!("  Unknown Token Recovery: {}", self.recover_unknown_tokens);
+        if let Some(path) = &self.position_state_path {
+            log::info!("  Position State File: {}", path);
+        }
+        if let Some(path) = &self.monitor_history_path {
+            log::info!("  Monitor History File: {}", path);
+        }
+        if let Some(path) = &self.outbox_path {
+            log::info!("  Transaction Outbox: {}", path);
+        }
+        if let Some(path) = &self.event_record_path {
+            log::info!("  Event Recording: {}", path);
+        }
+        if let Some(dir) = &self.metrics_export_dir {
+            log::info!("  Metrics Export: {} (rotate at {} MB / {}s)",
+                dir, self.metrics_export_rotate_mb, self.metrics_export_rotate_secs);
+        }
+        if self.chaos_enabled {
+            log::warn!("  🐒 Chaos: grpc delay {:.0}% (max {}ms), drop {:.0}%, swqos failure {:.0}%, stale blockhash {:.0}%",
+                self.chaos_grpc_delay_probability * 100.0,
+                self.chaos_grpc_max_delay_ms,
+                self.chaos_drop_event_probability * 100.0,
+                self.chaos_swqos_failure_probability * 100.0,
+                self.chaos_stale_blockhash_probability * 100.0);
+        }
+        log::info!("  Balance Reserve Buffer: {} SOL (check every {}s)",
+            self.balance_reserve_buffer_sol, self.balance_check_interval_secs);
+        if self.max_fixed_cost_fraction > 0.0 {
+            log::info!("  Max Fixed Cost: {:.0}% of position (tips + priority fee, worst-case retry)", self.max_fixed_cost_fraction * 100.0);
+        }
+        if self.max_spend_per_tx_sol > 0.0 || self.max_spend_per_hour_sol > 0.0 {
+            log::info!("  Spend Limits: {} SOL / tx, {} SOL / hour (0 = unlimited)",
+                self.max_spend_per_tx_sol, self.max_spend_per_hour_sol);
+        }
         log::info!("");
         log::info!("Sniper Strategies:");
+        if self.warmup_observation_secs > 0 || self.warmup_observation_events > 0 {
+            log::info!("  ⏳ Warm-up: {}s / {} events (first wave {})",
+                self.warmup_observation_secs,
+                self.warmup_observation_events,
+                if self.warmup_skip_first_wave { "skips" } else { "waits" });
+        }
+        if self.max_token_age_secs > 0 {
+            log::info!("  ⌛ Max Token Age: {}s (unknown age {})",
+                self.max_token_age_secs,
+                if self.reject_unknown_token_age { "rejected" } else { "allowed" });
+        }
         log::info!("  🚀 First Wave Sniper: {}", if self.enable_first_wave_sniper { "ENABLED" } else { "DISABLED" });
         if self.enable_first_wave_sniper {
             log::info!("     - Inflow Multiplier: {:.1}x", self.first_wave_inflow_multiplier);
             log::info!("     - Buy Ratio: {:.0}%", self.first_wave_buy_ratio * 100.0);
+            if self.first_wave_min_dev_buy_sol > 0.0 || self.first_wave_max_dev_buy_sol > 0.0 {
+                log::info!("     - Dev Buy: {} ~ {} SOL", self.first_wave_min_dev_buy_sol, self.first_wave_max_dev_buy_sol);
+            }
         }
         log::info!("  🎯 Threshold Trigger: {}", if self.enable_threshold_trigger { "ENABLED" } else { "DISABLED" });
         if self.enable_threshold_trigger {
             log::info!("     - Observation Window: {}s", self.threshold_observation_window_secs);
-            log::info!("     - Cumulative Buy: {} SOL", self.threshold_cumulative_buy_sol);
-            log::info!("     - Buy Ratio: {:.0}%", self.threshold_buy_ratio * 100.0);
+            if self.threshold_tiers.is_empty() {
+                log::info!("     - Cumulative Buy: {} SOL", self.threshold_cumulative_buy_sol);
+                log::info!("     - Buy Ratio: {:.0}%", self.threshold_buy_ratio * 100.0);
+            } else {
+                log::info!("     - Tiers (cumulative:buy SOL): {}", self.threshold_tiers.join(", "));
+            }
+            log::info!("     - Accumulation: {}", self.threshold_accumulation);
+            log::info!("     - Re-arm After Sell: {}", self.threshold_rearm_after_sell);
+        }
+        log::info!("  🩸 Sell Pressure Trigger: {}", if self.enable_sell_pressure_trigger { "ENABLED" } else { "DISABLED" });
+        if self.enable_sell_pressure_trigger {
+            log::info!("     - {} SOL sold within {}s", self.sell_pressure_threshold_sol, self.sell_pressure_window_secs);
+        }
+        log::info!("  🎓 Graduation Sniper: {}", if self.enable_graduation_sniper { "ENABLED" } else { "DISABLED" });
+        if self.enable_graduation_sniper {
+            log::info!("     - Progress Trigger: {:.0}% of {} SOL", self.graduation_progress_trigger_percent, self.graduation_target_sol);
+            log::info!("     - Min Net Inflow: {} SOL", self.graduation_min_net_inflow_sol);
+            log::info!("     - Min Buy Ratio: {:.0}%", self.graduation_min_buy_ratio * 100.0);
         }
         log::info!("");
         log::info!("Exit Strategy:");
@@ -383,11 +1581,393 @@ impl Config {
         log::info!("  Hold Duration: {}-{}s", self.hold_min_duration_secs, self.hold_max_duration_secs);
         log::info!("  Take Profit: {}x", self.take_profit_multiplier);
         log::info!("  Stop Loss: {}x", self.stop_loss_multiplier);
+        if self.hold_coalesce_ms > 0 {
+            log::info!("  Hold Coalescing: at most once per {}ms per position", self.hold_coalesce_ms);
+        }
         log::info!("");
         log::info!("Monitoring:");
         log::info!("  Monitor New Tokens: {}", self.monitor_new_tokens);
         log::info!("  Monitor Existing Tokens: {}", self.monitor_existing_tokens);
+        log::info!("  Alert Cooldown: {}s (auto resolve after {}s)",
+            self.alert_cooldown_secs, self.alert_auto_resolve_secs);
+        log::info!("  Rug Auto Blacklist: {} (confidence >= {:.0}%)",
+            self.enable_rug_auto_blacklist, self.rug_blacklist_confidence_threshold * 100.0);
+        log::info!("  Token-2022 Guard: max transfer fee {} bps, auto blacklist {}",
+            self.max_token_transfer_fee_bps, self.auto_blacklist_dangerous_tokens);
+        log::info!("  Authority Checks: freeze {:?}, mint {:?}",
+            self.freeze_authority_check_modes, self.mint_authority_check_modes);
+        if self.honeypot_simulation_enabled {
+            log::info!("  Honeypot Simulation: {} SOL round trip (skipped for first wave)", self.honeypot_probe_sol);
+        }
+        log::info!("");
+        log::info!("Aggregator:");
+        log::info!("  Event History Depth: {}", self.event_history_depth);
+        if self.aggregator_memory_budget_mb > 0 {
+            log::info!("  Memory Budget: {} MB (LRU eviction of cold mints)", self.aggregator_memory_budget_mb);
+        } else {
+            log::info!("  Memory Budget: unlimited");
+        }
+        log::info!("  Backpressure Warning: {:.0}% (sampled every {}ms)",
+            self.backpressure_warn_percent, self.pipeline_monitor_interval_ms);
+        log::info!("  Strategy Workers: {} (sharded by mint)", self.strategy_workers);
         log::info!("=============================");
     }
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_rug_blacklist_confidence() -> f64 {
+    0.8
+}
+
+fn default_timeframe_windows() -> Vec<u64> {
+    vec![5, 15, 60]
+}
+
+fn default_window_mode() -> String {
+    "smaller".to_string()
+}
+
+fn default_graduation_target_sol() -> f64 {
+    85.0
+}
+
+fn default_graduation_progress_trigger() -> f64 {
+    70.0
+}
+
+fn default_graduation_min_net_inflow() -> f64 {
+    1.0
+}
+
+fn default_graduation_min_buy_ratio() -> f64 {
+    0.6
+}
+
+fn default_reserves_max_staleness_ms() -> u64 {
+    500
+}
+
+fn default_alert_cooldown_secs() -> u64 {
+    300
+}
+
+fn default_alert_auto_resolve_secs() -> u64 {
+    60
+}
+
+fn default_price_cache_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_pipeline_monitor_interval_ms() -> u64 {
+    500
+}
+
+fn default_strategy_workers() -> usize {
+    1
+}
+
+fn default_threshold_accumulation() -> String {
+    "window".to_string()
+}
+
+fn default_metrics_export_rotate_mb() -> u64 {
+    256
+}
+
+fn default_metrics_export_rotate_secs() -> u64 {
+    3600
+}
+
+fn default_sell_pressure_window_secs() -> u64 {
+    10
+}
+
+fn default_sell_pressure_threshold_sol() -> f64 {
+    5.0
+}
+
+fn default_backpressure_warn_percent() -> f64 {
+    80.0
+}
+
+fn default_exit_check_interval_ms() -> u64 {
+    500
+}
+
+fn default_hold_coalesce_ms() -> u64 {
+    200
+}
+
+fn default_momentum_acceleration_threshold() -> f64 {
+    1.0
+}
+
+fn default_tpu_fanout_slots() -> u64 {
+    12
+}
+
+fn default_send_backend_order() -> Vec<String> {
+    vec!["swqos".to_string(), "tpu".to_string(), "lightspeed".to_string()]
+}
+
+fn default_tx_retry_max_attempts() -> u32 {
+    3
+}
+
+fn default_retry_fee_multiplier() -> f64 {
+    1.0
+}
+
+fn default_confirmation_fallback_poll_ms() -> u64 {
+    2000
+}
+
+fn default_wallet_sweep_interval_secs() -> u64 {
+    300
+}
+
+fn default_emergency_sell_slippage_percent() -> f64 {
+    50.0
+}
+
+fn default_control_api_bind() -> String {
+    "127.0.0.1:8787".to_string()
+}
+
+fn default_control_grpc_bind() -> String {
+    "127.0.0.1:50051".to_string()
+}
+
+fn default_event_feed_bind() -> String {
+    "127.0.0.1:8788".to_string()
+}
+
+fn default_event_feed_buffer() -> usize {
+    4096
+}
+
+fn default_drawdown_cooldown_secs() -> u64 {
+    1800
+}
+
+fn default_equity_check_interval_secs() -> u64 {
+    30
+}
+
+fn default_balance_check_interval_secs() -> u64 {
+    15
+}
+
+fn default_balance_reserve_buffer_sol() -> f64 {
+    0.005
+}
+
+fn default_pumpfun_fee_bps() -> u64 {
+    125
+}
+
+fn default_confidence_min_buy_sol() -> f64 {
+    0.05
+}
+
+fn default_confidence_max_buy_sol() -> f64 {
+    0.2
+}
+
+fn default_confidence_sizing_floor() -> f64 {
+    0.5
+}
+
+fn default_confidence_sizing_curve() -> String {
+    "linear".to_string()
+}
+
+fn default_max_sell_chunks() -> usize {
+    4
+}
+
+fn default_ev_win_prob_slope() -> f64 {
+    1.0
+}
+
+fn default_success_feedback_step() -> f64 {
+    0.05
+}
+
+fn default_success_feedback_win_streak() -> u32 {
+    3
+}
+
+fn default_success_feedback_min_factor() -> f64 {
+    0.85
+}
+
+fn default_success_feedback_max_factor() -> f64 {
+    1.3
+}
+
+fn default_time_bucket_min_trades() -> u32 {
+    5
+}
+
+fn default_bad_hour_win_rate() -> f64 {
+    0.3
+}
+
+fn default_bad_hour_score_multiplier() -> f64 {
+    1.2
+}
+
+fn default_bad_hour_size_multiplier() -> f64 {
+    0.5
+}
+
+fn default_regime_window_secs() -> u64 {
+    60
+}
+
+fn default_regime_sample_mints() -> usize {
+    50
+}
+
+fn default_regime_min_samples() -> usize {
+    10
+}
+
+fn default_regime_calm_volatility() -> f64 {
+    0.05
+}
+
+fn default_regime_volatile_volatility() -> f64 {
+    0.15
+}
+
+fn default_decision_trace_depth() -> usize {
+    20
+}
+
+fn default_decision_trace_max_mints() -> usize {
+    2_000
+}
+
+fn default_experiment_strategy_mode() -> String {
+    "aggressive".to_string()
+}
+
+fn default_market_health_window_secs() -> u64 {
+    3600
+}
+
+fn default_sol_price_api_url() -> String {
+    "https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd".to_string()
+}
+
+fn default_sol_price_json_pointer() -> String {
+    "/solana/usd".to_string()
+}
+
+fn default_sol_price_interval_secs() -> u64 {
+    60
+}
+
+fn default_reconcile_interval_secs() -> u64 {
+    300
+}
+
+fn default_recover_unknown_tokens() -> String {
+    "list".to_string()
+}
+
+fn default_rpc_health_check_interval_secs() -> u64 {
+    10
+}
+
+fn default_rpc_failover_threshold() -> u32 {
+    3
+}
+
+fn default_rpc_unhealthy_cooldown_secs() -> u64 {
+    30
+}
+
+fn default_rpc_max_slot_lag() -> u64 {
+    50
+}
+
+fn default_max_token_transfer_fee_bps() -> u16 {
+    100
+}
+
+fn default_freeze_authority_check_modes() -> Vec<String> {
+    ["conservative", "balanced", "aggressive", "custom"].iter().map(|mode| mode.to_string()).collect()
+}
+
+fn default_mint_authority_check_modes() -> Vec<String> {
+    ["conservative", "balanced", "custom"].iter().map(|mode| mode.to_string()).collect()
+}
+
+fn default_honeypot_probe_sol() -> f64 {
+    0.001
+}
+
+fn default_feed_commitment() -> String {
+    "confirmed".to_string()
+}
+
+fn default_confirm_commitment() -> String {
+    "confirmed".to_string()
+}
+
+fn default_feed_reconcile_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_event_history_depth() -> usize {
+    100
+}
+
+fn default_aggregator_memory_budget_mb() -> u64 {
+    256
+}
+
+fn default_grpc_ping_interval_secs() -> u64 {
+    10
+}
+
+fn default_grpc_max_missed_pongs() -> u32 {
+    3
+}
+
+fn default_grpc_dedup_capacity() -> usize {
+    100_000
+}
+
+fn default_grpc_dedup_ttl_secs() -> u64 {
+    600
+}
+
+fn default_max_fixed_cost_fraction() -> f64 {
+    0.25
+}
+
+fn default_replay_wallet_sol() -> f64 {
+    10.0
+}
+
+fn default_chaos_grpc_max_delay_ms() -> u64 {
+    2_000
+}
+
+/// commitment 名称（不区分大小写）
+fn parse_commitment(level: &str) -> Option<CommitmentConfig> {
+    match level.to_lowercase().as_str() {
+        "processed" => Some(CommitmentConfig::processed()),
+        "confirmed" => Some(CommitmentConfig::confirmed()),
+        "finalized" => Some(CommitmentConfig::finalized()),
+        _ => None,
+    }
+}
@@ -0,0 +1,156 @@
+/// 交易确认追踪
+///
+/// gRPC 交易流（已按 PumpFun 程序过滤，且只推送成功交易）中出现我们的签名即视为已确认，
+/// 替代每 0.5–1 秒一次的 RPC 轮询；RPC 查询降级为低频兜底（用于检测失败交易和 gRPC 断线）
+///
+/// 核心功能:
+/// 1. 等待者注册 - 按签名注册观测状态（同一签名的多个等待者共享，最后一个离开时移除）
+/// 2. gRPC 观测 - 交易流中匹配到签名立即唤醒等待者
+/// 3. RPC 兜底 - 按 confirmation_fallback_poll_ms 间隔查询签名状态
+/// 4. 确认级别 - 按 confirm_commitment 判定；事件流 commitment 低于它时 gRPC 观测不作为确认依据
+
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use log::{debug, info};
+use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::signature::Signature;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+use crate::rpc_api::RpcApi;
+
+/// 确认结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmationStatus {
+    /// 交易已成功上链
+    Confirmed,
+    /// 交易上链但执行失败
+    Failed(String),
+    /// 超时未确认
+    TimedOut,
+}
+
+/// 正在等待确认的签名
+struct PendingSignature {
+    /// gRPC 是否已观测到该签名（每个等待者持有自己的 receiver，不会错过通知）
+    observed: watch::Sender<bool>,
+    /// 当前等待者数量
+    waiters: usize,
+}
+
+/// 等待者离开（返回、超时或 future 被取消）时减少计数，最后一个等待者移除条目
+struct PendingWaiter<'a> {
+    pending: &'a DashMap<Signature, PendingSignature>,
+    signature: Signature,
+}
+
+impl Drop for PendingWaiter<'_> {
+    fn drop(&mut self) {
+        if let Entry::Occupied(mut entry) = self.pending.entry(self.signature) {
+            entry.get_mut().waiters -= 1;
+            if entry.get().waiters == 0 {
+                entry.remove();
+            }
+        }
+    }
+}
+
+/// 交易确认追踪器
+pub struct ConfirmationTracker {
+    /// 正在等待确认的签名
+    pending: DashMap<Signature, PendingSignature>,
+    /// RPC 兜底查询间隔
+    fallback_poll_interval: Duration,
+    /// 判定已确认的 commitment
+    commitment: CommitmentConfig,
+    /// gRPC 事件流的 commitment 是否满足确认要求
+    feed_confirms: bool,
+}
+
+impl ConfirmationTracker {
+    pub fn new(fallback_poll_ms: u64, commitment: CommitmentConfig, feed_commitment: CommitmentConfig) -> Self {
+        let rank = |level: CommitmentLevel| match level {
+            CommitmentLevel::Processed => 0,
+            CommitmentLevel::Confirmed => 1,
+            CommitmentLevel::Finalized => 2,
+        };
+
+        Self {
+            pending: DashMap::new(),
+            fallback_poll_interval: Duration::from_millis(fallback_poll_ms),
+            commitment,
+            feed_confirms: rank(feed_commitment.commitment) >= rank(commitment.commitment),
+        }
+    }
+
+    /// gRPC 交易流观测到签名（只对正在等待的签名生效；事件流 commitment 不足时忽略）
+    pub fn observe(&self, signature: &Signature) {
+        if !self.feed_confirms {
+            return;
+        }
+        if let Some(pending) = self.pending.get(signature) {
+            debug!("📬 gRPC 观测到交易: {}", signature);
+            pending.observed.send_replace(true);
+        }
+    }
+
+    /// 等待交易确认
+    ///
+    /// gRPC 观测到签名立即返回；同时按兜底间隔查询 RPC（首次立即查询，覆盖注册前已上链的情况）
+    pub async fn wait_for(
+        &self,
+        rpc_client: &dyn RpcApi,
+        signature: &Signature,
+        timeout_secs: u64,
+    ) -> ConfirmationStatus {
+        let mut observed = {
+            let mut pending = self.pending.entry(*signature).or_insert_with(|| PendingSignature {
+                observed: watch::channel(false).0,
+                waiters: 0,
+            });
+            pending.waiters += 1;
+            pending.observed.subscribe()
+        };
+        let _waiter = PendingWaiter { pending: &self.pending, signature: *signature };
+
+        let start = Instant::now();
+        let deadline = start + Duration::from_secs(timeout_secs);
+
+        let status = loop {
+            match rpc_client.get_signature_statuses(&[*signature]) {
+                Ok(response) => {
+                    if let Some(Some(status)) = response.value.first() {
+                        if let Some(err) = &status.err {
+                            break ConfirmationStatus::Failed(format!("{:?}", err));
+                        }
+                        if status.satisfies_commitment(self.commitment) {
+                            break ConfirmationStatus::Confirmed;
+                        }
+                    }
+                }
+                Err(e) => {
+                    debug!("⚠️  查询交易状态失败: {}, 继续等待", e);
+                }
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break ConfirmationStatus::TimedOut;
+            }
+
+            let wait = remaining.min(self.fallback_poll_interval);
+            let seen = tokio::time::timeout(wait, observed.wait_for(|observed| *observed))
+                .await
+                .is_ok_and(|result| result.is_ok());
+            if seen {
+                break ConfirmationStatus::Confirmed;
+            }
+        };
+
+        if status == ConfirmationStatus::Confirmed {
+            info!("✅ 交易已确认: {} (耗时 {}ms)", signature, start.elapsed().as_millis());
+        }
+
+        status
+    }
+}
@@ -0,0 +1,380 @@
+/// 控制 API
+///
+/// 本地 HTTP 接口，供运维在运行中直接干预机器人（不依赖 Web 框架，基于 tokio TcpListener 手动解析请求行）
+///
+/// 核心功能:
+/// 1. POST /sell-all - 紧急清仓（放宽滑点立即卖出所有持仓，跳过动能检查）
+/// 2. GET /drawdown - 回撤状态；POST /drawdown/reset - 手动恢复开新仓
+/// 3. GET /balance - 钱包余额与买入储备；GET /market - 大盘健康度；GET /rpc - RPC 端点健康状态
+/// 4. GET /unknown-tokens - 启动时发现的未记录 token；POST /unknown-tokens/import - 导入为持仓
+///    GET /position-states - 各 mint 的持仓状态与状态历史；GET /migrated - 已毕业、需手动卖出的持仓
+/// 5. GET /aggregator - 聚合器内存占用（窗口数、事件历史数、估算字节、预算、累计淘汰数）
+///    GET /aggregator/<mint> - 单个 mint 的窗口状态（事件、累计买入、触发标记、储备、高级指标）
+///    GET /decisions/<mint> - 单个 mint 最近的策略判定及命中 / 拒绝的条件（需启用 decision_trace_enabled）
+///    GET /pipeline - 事件队列 / 指标通道 / 入场与退出信号通道的当前积压、容量与峰值
+///    GET /alerts - 活跃风险警报；POST /alerts/ack - 确认全部警报；POST /alerts/ack/<mint> - 确认单个 mint 的警报
+///    GET /spend - 支出限额（单笔 / 每小时上限、最近 1 小时支出、剩余额度、拒绝次数）
+///    GET /quote/buy?mint=<mint>&sol=<SOL> / GET /quote/sell?mint=<mint>&tokens=<token 数量> - 按缓存储备报价
+/// 6. GET /health - 存活检查
+/// 7. Bearer token 认证（配置 control_api_token 时启用，常数时间比较；未配置时只能绑定回环地址）
+/// 8. inspect 子命令 - 向运行中的机器人查询单个 mint 的窗口状态并输出 JSON
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::aggregator::Aggregator;
+use crate::config::Config;
+use crate::decision_trace::DecisionTrace;
+use crate::pipeline::PipelineMonitor;
+use crate::position::PositionManager;
+use crate::quote::QuoteService;
+use crate::spend_guard::SpendGuard;
+
+/// 请求头最大长度
+const MAX_REQUEST_BYTES: usize = 8192;
+
+/// 控制 API 服务
+pub struct ControlServer {
+    config: Arc<Config>,
+    position_manager: Arc<PositionManager>,
+    aggregator: Arc<Aggregator>,
+    pipeline: Arc<PipelineMonitor>,
+    spend_guard: Arc<SpendGuard>,
+    quotes: Arc<QuoteService>,
+    decisions: Arc<DecisionTrace>,
+}
+
+impl ControlServer {
+    pub fn new(
+        config: Arc<Config>,
+        position_manager: Arc<PositionManager>,
+        aggregator: Arc<Aggregator>,
+        pipeline: Arc<PipelineMonitor>,
+        spend_guard: Arc<SpendGuard>,
+        quotes: Arc<QuoteService>,
+        decisions: Arc<DecisionTrace>,
+    ) -> Self {
+        Self { config, position_manager, aggregator, pipeline, spend_guard, quotes, decisions }
+    }
+
+    /// 监听 control_api_bind 并处理请求
+    pub async fn run(self: Arc<Self>) -> Result<()> {
+        let listener = TcpListener::bind(&self.config.control_api_bind)
+            .await
+            .with_context(|| format!("控制 API 绑定失败: {}", self.config.control_api_bind))?;
+
+        info!("🕹️  控制 API 已启动: http://{}", self.config.control_api_bind);
+
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("⚠️  控制 API 接受连接失败: {}", e);
+                    continue;
+                }
+            };
+
+            let server = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.handle_connection(stream).await {
+                    warn!("⚠️  控制 API 请求处理失败 ({}): {}", peer, e);
+                }
+            });
+        }
+    }
+
+    async fn handle_connection(&self, mut stream: TcpStream) -> Result<()> {
+        let mut buf = vec![0u8; MAX_REQUEST_BYTES];
+        let mut len = 0;
+
+        // 只需要请求行和请求头（所有接口都不读取 body）
+        loop {
+            let n = stream.read(&mut buf[len..]).await?;
+            if n == 0 {
+                break;
+            }
+            len += n;
+            if buf[..len].windows(4).any(|w| w == b"\r\n\r\n") || len == buf.len() {
+                break;
+            }
+        }
+
+        let request = String::from_utf8_lossy(&buf[..len]);
+        let mut lines = request.lines();
+        let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+        let method = request_line.next().unwrap_or_default();
+        let path = request_line.next().unwrap_or_default();
+
+        let authorization = lines
+            .take_while(|line| !line.is_empty())
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.trim().eq_ignore_ascii_case("authorization").then(|| value.trim().to_string())
+            });
+
+        let (status, body) = if !self.is_authorized(authorization.as_deref()) {
+            (401, r#"{"error":"unauthorized"}"#.to_string())
+        } else {
+            self.route(method, path).await
+        };
+
+        let response = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            reason_phrase(status),
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+
+    fn is_authorized(&self, authorization: Option<&str>) -> bool {
+        match &self.config.control_api_token {
+            Some(token) if !token.is_empty() => {
+                authorization
+                    .and_then(|value| value.strip_prefix("Bearer "))
+                    .is_some_and(|provided| token_matches(token, provided))
+            }
+            _ => true,
+        }
+    }
+
+    async fn route(&self, method: &str, path: &str) -> (u16, String) {
+        match (method, path) {
+            ("GET", "/health") => (200, r#"{"status":"ok"}"#.to_string()),
+            ("POST", "/sell-all") => {
+                info!("🕹️  控制 API: 收到紧急清仓请求");
+                let (positions, closed) = self.position_manager.sell_all("control API").await;
+                (200, format!(r#"{{"positions":{},"closed":{}}}"#, positions, closed))
+            }
+            ("GET", "/drawdown") => {
+                let snapshot = self.position_manager.drawdown().snapshot();
+                (200, format!(
+                    r#"{{"peak_equity_sol":{:.6},"equity_sol":{:.6},"drawdown_percent":{:.2},"paused":{}}}"#,
+                    snapshot.peak_equity_lamports as f64 / 1_000_000_000.0,
+                    snapshot.equity_lamports as f64 / 1_000_000_000.0,
+                    snapshot.drawdown_percent,
+                    snapshot.paused
+                ))
+            }
+            ("GET", "/balance") => {
+                let guard = self.position_manager.balance_guard();
+                (200, format!(
+                    r#"{{"balance_sol":{:.6},"reserve_sol":{:.6},"low_balance":{}}}"#,
+                    guard.balance_lamports() as f64 / 1_000_000_000.0,
+                    guard.reserve_lamports() as f64 / 1_000_000_000.0,
+                    guard.is_low()
+                ))
+            }
+            ("GET", "/market") => {
+                let snapshot = self.position_manager.market_health().snapshot();
+                let sol_price = snapshot.sol_price_usd
+                    .map(|price| format!("{:.2}", price))
+                    .unwrap_or_else(|| "null".to_string());
+                (200, format!(
+                    r#"{{"sol_price_usd":{},"launches":{},"graduations":{},"graduation_rate_percent":{:.2},"window_complete":{},"healthy":{}}}"#,
+                    sol_price,
+                    snapshot.launches,
+                    snapshot.graduations,
+                    snapshot.graduation_rate_percent,
+                    snapshot.window_complete,
+                    snapshot.healthy
+                ))
+            }
+            ("GET", "/rpc") => {
+                let status = self.position_manager.rpc_pool().status();
+                match serde_json::to_string(&status) {
+                    Ok(body) => (200, body),
+                    Err(e) => (500, format!(r#"{{"error":"{}"}}"#, e)),
+                }
+            }
+            ("GET", "/unknown-tokens") => {
+                let tokens = self.position_manager.unknown_tokens();
+                match serde_json::to_string(&tokens) {
+                    Ok(body) => (200, body),
+                    Err(e) => (500, format!(r#"{{"error":"{}"}}"#, e)),
+                }
+            }
+            ("GET", "/position-states") => {
+                let states = self.position_manager.position_states().snapshot();
+                match serde_json::to_string(&states) {
+                    Ok(body) => (200, body),
+                    Err(e) => (500, format!(r#"{{"error":"{}"}}"#, e)),
+                }
+            }
+            ("GET", "/migrated") => {
+                let positions = self.position_manager.migrated_positions();
+                match serde_json::to_string(&positions) {
+                    Ok(body) => (200, body),
+                    Err(e) => (500, format!(r#"{{"error":"{}"}}"#, e)),
+                }
+            }
+            ("GET", "/aggregator") => {
+                let usage = self.aggregator.memory_usage();
+                match serde_json::to_string(&usage) {
+                    Ok(body) => (200, body),
+                    Err(e) => (500, format!(r#"{{"error":"{}"}}"#, e)),
+                }
+            }
+            ("GET", path) if path.starts_with("/aggregator/") => {
+                let Ok(mint) = Pubkey::from_str(path.trim_start_matches("/aggregator/")) else {
+                    return (400, r#"{"error":"invalid mint"}"#.to_string());
+                };
+                match self.aggregator.snapshot_window(&mint) {
+                    Some(snapshot) => match serde_json::to_string(&snapshot) {
+                        Ok(body) => (200, body),
+                        Err(e) => (500, format!(r#"{{"error":"{}"}}"#, e)),
+                    },
+                    None => (404, r#"{"error":"no window for mint"}"#.to_string()),
+                }
+            }
+            ("GET", path) if path.starts_with("/decisions/") => {
+                if !self.decisions.is_enabled() {
+                    return (404, r#"{"error":"decision trace disabled"}"#.to_string());
+                }
+                let Ok(mint) = Pubkey::from_str(path.trim_start_matches("/decisions/")) else {
+                    return (400, r#"{"error":"invalid mint"}"#.to_string());
+                };
+                match self.decisions.get(&mint) {
+                    Some(records) => match serde_json::to_string(&records) {
+                        Ok(body) => (200, body),
+                        Err(e) => (500, format!(r#"{{"error":"{}"}}"#, e)),
+                    },
+                    None => (404, r#"{"error":"no decisions for mint"}"#.to_string()),
+                }
+            }
+            ("GET", "/pipeline") => {
+                let snapshot = self.pipeline.snapshot();
+                match serde_json::to_string(&snapshot) {
+                    Ok(body) => (200, body),
+                    Err(e) => (500, format!(r#"{{"error":"{}"}}"#, e)),
+                }
+            }
+            ("GET", "/spend") => {
+                let snapshot = self.spend_guard.snapshot();
+                match serde_json::to_string(&snapshot) {
+                    Ok(body) => (200, body),
+                    Err(e) => (500, format!(r#"{{"error":"{}"}}"#, e)),
+                }
+            }
+            ("GET", path) if path.starts_with("/quote/") => self.quote(path),
+            ("GET", "/alerts") => {
+                let alerts = self.position_manager.monitor().active_alerts();
+                match serde_json::to_string(&alerts) {
+                    Ok(body) => (200, body),
+                    Err(e) => (500, format!(r#"{{"error":"{}"}}"#, e)),
+                }
+            }
+            ("POST", "/alerts/ack") => {
+                info!("🕹️  控制 API: 确认全部风险警报");
+                let acknowledged = self.position_manager.monitor().acknowledge_alerts(None);
+                (200, format!(r#"{{"acknowledged":{}}}"#, acknowledged))
+            }
+            ("POST", path) if path.starts_with("/alerts/ack/") => {
+                match Pubkey::from_str(path.trim_start_matches("/alerts/ack/")) {
+                    Ok(mint) => {
+                        info!("🕹️  控制 API: 确认风险警报 {}", mint);
+                        let acknowledged = self.position_manager.monitor().acknowledge_alerts(Some(&mint));
+                        (200, format!(r#"{{"acknowledged":{}}}"#, acknowledged))
+                    }
+                    Err(_) => (400, r#"{"error":"invalid mint"}"#.to_string()),
+                }
+            }
+            ("POST", "/unknown-tokens/import") => {
+                info!("🕹️  控制 API: 收到导入未记录 token 请求");
+                let imported = self.position_manager.import_unknown_tokens();
+                let remaining = self.position_manager.unknown_tokens().len();
+                (200, format!(r#"{{"imported":{},"remaining":{}}}"#, imported, remaining))
+            }
+            ("POST", "/drawdown/reset") => {
+                info!("🕹️  控制 API: 收到回撤重置请求");
+                self.position_manager.drawdown().reset();
+                (200, r#"{"paused":false}"#.to_string())
+            }
+            _ => (404, r#"{"error":"not found"}"#.to_string()),
+        }
+    }
+
+    /// 报价（sol 单位为 SOL，tokens 为 token 最小单位）
+    fn quote(&self, path: &str) -> (u16, String) {
+        let (endpoint, query) = path.split_once('?').unwrap_or((path, ""));
+        let param = |key: &str| {
+            query.split('&').find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))
+        };
+
+        let Some(mint) = param("mint").and_then(|mint| Pubkey::from_str(mint).ok()) else {
+            return (400, r#"{"error":"invalid mint"}"#.to_string());
+        };
+
+        let result = match endpoint {
+            "/quote/buy" => match param("sol").and_then(|sol| sol.parse::<f64>().ok()).filter(|sol| *sol > 0.0) {
+                Some(sol) => self.quotes.quote_buy(&mint, (sol * 1_000_000_000.0) as u64),
+                None => return (400, r#"{"error":"invalid sol"}"#.to_string()),
+            },
+            "/quote/sell" => match param("tokens").and_then(|tokens| tokens.parse::<u64>().ok()).filter(|tokens| *tokens > 0) {
+                Some(tokens) => self.quotes.quote_sell(&mint, tokens),
+                None => return (400, r#"{"error":"invalid tokens"}"#.to_string()),
+            },
+            _ => return (404, r#"{"error":"not found"}"#.to_string()),
+        };
+
+        match result {
+            Ok(quote) => match serde_json::to_string(&quote) {
+                Ok(body) => (200, body),
+                Err(e) => (500, format!(r#"{{"error":"{}"}}"#, e)),
+            },
+            Err(e) => (404, format!(r#"{{"error":"{}"}}"#, e)),
+        }
+    }
+}
+
+/// inspect 子命令：请求运行中实例的 GET /aggregator/<mint>，返回格式化后的 JSON
+pub async fn inspect_mint(config: &Config, mint: &str) -> Result<String> {
+    Pubkey::from_str(mint).context("invalid mint")?;
+    if !config.control_api_enabled {
+        anyhow::bail!("inspect requires the control API (set CONTROL_API_ENABLED=true on the running instance)");
+    }
+
+    let url = format!("http://{}/aggregator/{}", config.control_api_bind, mint);
+    let mut request = reqwest::Client::new().get(&url);
+    if let Some(token) = config.control_api_token.as_deref().filter(|token| !token.is_empty()) {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await.with_context(|| format!("控制 API 请求失败: {}", url))?;
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.context("控制 API 返回了无效的 JSON")?;
+    if !status.is_success() {
+        anyhow::bail!("control API returned {}: {}", status, body);
+    }
+    Ok(serde_json::to_string_pretty(&body)?)
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    }
+}
+
+/// 常数时间比较 token（比较耗时不随首个不同字节的位置变化，避免逐字节猜测 token）
+pub(crate) fn token_matches(expected: &str, provided: &str) -> bool {
+    let (expected, provided) = (expected.as_bytes(), provided.as_bytes());
+    if expected.len() != provided.len() {
+        return false;
+    }
+    expected
+        .iter()
+        .zip(provided)
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
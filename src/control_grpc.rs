@@ -0,0 +1,220 @@
+/// 控制面 gRPC 服务
+///
+/// 在 HTTP 控制 API 和 WebSocket 事件流之外，以 tonic gRPC 服务提供同样的控制面和事件流，
+/// 接口定义见 proto/solsniper.proto（其他服务可直接生成类型化客户端）
+///
+/// 核心功能:
+/// 1. 控制面 - 存活检查、紧急清仓、回撤状态 / 重置、余额、大盘健康度、风险警报查询 / 确认
+/// 2. 事件流 - StreamEvents 订阅事件 / 指标 / 信号 / 成交（可按类型过滤，payload 与 WebSocket 事件流相同）
+/// 3. 认证 - 配置 control_api_token 时校验 metadata `authorization: Bearer <token>`（未配置时只能绑定回环地址）
+
+use anyhow::{Context, Result};
+use futures::Stream;
+use log::{info, warn};
+use solana_sdk::pubkey::Pubkey;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tonic::{Request, Response, Status};
+
+use crate::config::Config;
+use crate::control::token_matches;
+use crate::event_feed::EventFeed;
+use crate::position::PositionManager;
+
+/// 由 proto/solsniper.proto 生成
+pub mod proto {
+    tonic::include_proto!("solsniper.v1");
+}
+
+use proto::sniper_control_server::{SniperControl, SniperControlServer};
+use proto::{
+    AcknowledgeAlertsReply, AcknowledgeAlertsRequest, Alert, AlertList, BalanceReply, DrawdownReply, Empty,
+    FeedMessage, HealthReply, MarketReply, SellAllReply, SellAllRequest, StreamEventsRequest,
+};
+
+/// 控制面 gRPC 服务
+pub struct ControlGrpcService {
+    config: Arc<Config>,
+    position_manager: Arc<PositionManager>,
+    event_feed: Arc<EventFeed>,
+}
+
+impl ControlGrpcService {
+    pub fn new(config: Arc<Config>, position_manager: Arc<PositionManager>, event_feed: Arc<EventFeed>) -> Self {
+        Self { config, position_manager, event_feed }
+    }
+
+    /// 监听 control_grpc_bind 并处理请求
+    pub async fn run(self) -> Result<()> {
+        let addr = self.config.control_grpc_bind
+            .parse()
+            .with_context(|| format!("无效的 control_grpc_bind: {}", self.config.control_grpc_bind))?;
+        let token = self.config.control_api_token.clone().filter(|token| !token.is_empty());
+
+        info!("🛰️  控制面 gRPC 服务已启动: {}", addr);
+
+        let service = SniperControlServer::with_interceptor(self, move |request: Request<()>| {
+            authorize(token.as_deref(), request)
+        });
+        tonic::transport::Server::builder()
+            .add_service(service)
+            .serve(addr)
+            .await
+            .context("控制面 gRPC 服务异常退出")
+    }
+
+    fn drawdown_reply(&self) -> DrawdownReply {
+        let snapshot = self.position_manager.drawdown().snapshot();
+        DrawdownReply {
+            peak_equity_sol: snapshot.peak_equity_lamports as f64 / 1_000_000_000.0,
+            equity_sol: snapshot.equity_lamports as f64 / 1_000_000_000.0,
+            drawdown_percent: snapshot.drawdown_percent,
+            paused: snapshot.paused,
+        }
+    }
+}
+
+/// 校验 Bearer token（未配置 token 时不校验）
+fn authorize(token: Option<&str>, request: Request<()>) -> std::result::Result<Request<()>, Status> {
+    let Some(token) = token else {
+        return Ok(request);
+    };
+
+    let authorized = request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|provided| token_matches(token, provided));
+    if authorized {
+        Ok(request)
+    } else {
+        Err(Status::unauthenticated("unauthorized"))
+    }
+}
+
+type FeedStream = Pin<Box<dyn Stream<Item = std::result::Result<FeedMessage, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl SniperControl for ControlGrpcService {
+    async fn health(&self, _request: Request<Empty>) -> std::result::Result<Response<HealthReply>, Status> {
+        Ok(Response::new(HealthReply { status: "ok".to_string() }))
+    }
+
+    async fn sell_all(&self, request: Request<SellAllRequest>) -> std::result::Result<Response<SellAllReply>, Status> {
+        let reason = match request.into_inner().reason {
+            reason if reason.is_empty() => "control gRPC".to_string(),
+            reason => format!("control gRPC: {}", reason),
+        };
+        info!("🕹️  控制 gRPC: 收到紧急清仓请求 ({})", reason);
+        let (positions, closed) = self.position_manager.sell_all(&reason).await;
+        Ok(Response::new(SellAllReply {
+            positions: positions as u32,
+            closed: closed as u32,
+        }))
+    }
+
+    async fn get_drawdown(&self, _request: Request<Empty>) -> std::result::Result<Response<DrawdownReply>, Status> {
+        Ok(Response::new(self.drawdown_reply()))
+    }
+
+    async fn reset_drawdown(&self, _request: Request<Empty>) -> std::result::Result<Response<DrawdownReply>, Status> {
+        info!("🕹️  控制 gRPC: 收到回撤重置请求");
+        self.position_manager.drawdown().reset();
+        Ok(Response::new(self.drawdown_reply()))
+    }
+
+    async fn get_balance(&self, _request: Request<Empty>) -> std::result::Result<Response<BalanceReply>, Status> {
+        let guard = self.position_manager.balance_guard();
+        Ok(Response::new(BalanceReply {
+            balance_sol: guard.balance_lamports() as f64 / 1_000_000_000.0,
+            reserve_sol: guard.reserve_lamports() as f64 / 1_000_000_000.0,
+            low_balance: guard.is_low(),
+        }))
+    }
+
+    async fn get_market(&self, _request: Request<Empty>) -> std::result::Result<Response<MarketReply>, Status> {
+        let snapshot = self.position_manager.market_health().snapshot();
+        Ok(Response::new(MarketReply {
+            sol_price_usd: snapshot.sol_price_usd,
+            launches: snapshot.launches as u32,
+            graduations: snapshot.graduations as u32,
+            graduation_rate_percent: snapshot.graduation_rate_percent,
+            window_complete: snapshot.window_complete,
+            healthy: snapshot.healthy,
+        }))
+    }
+
+    async fn list_alerts(&self, _request: Request<Empty>) -> std::result::Result<Response<AlertList>, Status> {
+        let alerts = self.position_manager.monitor()
+            .active_alerts()
+            .into_iter()
+            .map(|alert| Alert {
+                mint: alert.mint,
+                kind: format!("{:?}", alert.kind),
+                severity: format!("{:?}", alert.severity),
+                description: alert.description,
+                first_seen: alert.first_seen,
+                fire_count: alert.fire_count,
+                suppressed: alert.suppressed,
+                acknowledged: alert.acknowledged,
+            })
+            .collect();
+        Ok(Response::new(AlertList { alerts }))
+    }
+
+    async fn acknowledge_alerts(
+        &self,
+        request: Request<AcknowledgeAlertsRequest>,
+    ) -> std::result::Result<Response<AcknowledgeAlertsReply>, Status> {
+        let mint = match request.into_inner().mint {
+            mint if mint.is_empty() => None,
+            mint => Some(Pubkey::from_str(&mint).map_err(|_| Status::invalid_argument("invalid mint"))?),
+        };
+        info!("🕹️  控制 gRPC: 确认风险警报 ({})", mint.map_or("全部".to_string(), |mint| mint.to_string()));
+        let acknowledged = self.position_manager.monitor().acknowledge_alerts(mint.as_ref());
+        Ok(Response::new(AcknowledgeAlertsReply { acknowledged: acknowledged as u32 }))
+    }
+
+    type StreamEventsStream = FeedStream;
+
+    async fn stream_events(
+        &self,
+        request: Request<StreamEventsRequest>,
+    ) -> std::result::Result<Response<Self::StreamEventsStream>, Status> {
+        let types = request.into_inner().types;
+        let rx = self.event_feed.subscribe();
+        info!("🛰️  gRPC 事件流客户端已连接 (类型: {})",
+            if types.is_empty() { "全部".to_string() } else { types.join(", ") });
+
+        let stream = futures::stream::unfold((rx, types), |(mut rx, types)| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(frame) => {
+                        if !types.is_empty() && !types.iter().any(|kind| kind == frame.kind) {
+                            continue;
+                        }
+                        let message = FeedMessage {
+                            r#type: frame.kind.to_string(),
+                            json: frame.json.clone(),
+                        };
+                        return Some((Ok(message), (rx, types)));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("⚠️  gRPC 事件流客户端跟不上，丢弃 {} 条消息", skipped);
+                        let message = FeedMessage {
+                            r#type: "lagged".to_string(),
+                            json: format!(r#"{{"type":"lagged","skipped":{}}}"#, skipped),
+                        };
+                        return Some((Ok(message), (rx, types)));
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,138 @@
+/// 策略决策追踪
+///
+/// 策略引擎每评估一批指标记录一次判定：产生了什么信号、命中 / 拒绝的具体条件
+/// （覆盖规则禁止、预热中、独立买家不足、买占比 / 综合评分 / 滑点不达标等），
+/// 按 mint 保存在环形缓冲区中，用于回答"为什么没买"而不必开 debug 日志
+///
+/// 核心功能:
+/// 1. 记录 - 每个 mint 保留最近 decision_trace_depth 次判定（未启用时不记录、不分配）
+/// 2. 容量 - 最多追踪 decision_trace_max_mints 个 mint，超出时淘汰最久未评估的 mint
+/// 3. 查询 - 控制 API GET /decisions/<mint>（从旧到新）
+///
+/// 只覆盖策略引擎的判定；信号之后仓位管理的拒绝（持仓上限、回撤暂停、EV 等）见交易日志
+
+use chrono::Utc;
+use parking_lot::Mutex;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::config::Config;
+use crate::types::{StrategySignal, WindowMetrics};
+
+/// 一次策略判定
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionRecord {
+    /// 判定时间（RFC 3339）
+    pub timestamp: String,
+    /// 产生的信号（Buy / None）
+    pub signal: String,
+    /// 判定时的主要指标
+    pub event_count: usize,
+    pub net_inflow_sol: f64,
+    pub buy_ratio: f64,
+    /// 命中 / 拒绝的条件（按评估顺序）
+    pub reasons: Vec<String>,
+}
+
+/// 单次评估的判定说明（未启用追踪时不格式化、不分配）
+pub struct DecisionNotes {
+    enabled: bool,
+    notes: Vec<String>,
+}
+
+impl DecisionNotes {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, notes: Vec::new() }
+    }
+
+    pub fn push(&mut self, note: impl FnOnce() -> String) {
+        if self.enabled {
+            self.notes.push(note());
+        }
+    }
+}
+
+/// 单个 mint 的判定历史
+struct MintTrace {
+    /// 最近一次记录的序号（recency 索引的键）
+    last_seq: u64,
+    records: VecDeque<DecisionRecord>,
+}
+
+/// 所有 mint 的判定历史 + 按最近评估排序的索引（淘汰最久未评估的 mint 为 O(log n)）
+#[derive(Default)]
+struct Traces {
+    by_mint: HashMap<Pubkey, MintTrace>,
+    /// 序号 → mint（最小的序号即最久未评估的 mint）
+    recency: BTreeMap<u64, Pubkey>,
+}
+
+/// 策略决策追踪
+pub struct DecisionTrace {
+    enabled: bool,
+    depth: usize,
+    max_mints: usize,
+    next_seq: AtomicU64,
+    traces: Mutex<Traces>,
+}
+
+impl DecisionTrace {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            enabled: config.decision_trace_enabled,
+            depth: config.decision_trace_depth.max(1),
+            max_mints: config.decision_trace_max_mints.max(1),
+            next_seq: AtomicU64::new(0),
+            traces: Mutex::new(Traces::default()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 记录一次判定
+    pub fn record(&self, metrics: &WindowMetrics, signal: &StrategySignal, notes: DecisionNotes) {
+        if !self.enabled {
+            return;
+        }
+
+        let record = DecisionRecord {
+            timestamp: Utc::now().to_rfc3339(),
+            signal: format!("{:?}", signal),
+            event_count: metrics.event_count,
+            net_inflow_sol: metrics.net_inflow_sol as f64 / 1_000_000_000.0,
+            buy_ratio: metrics.buy_ratio,
+            reasons: notes.notes,
+        };
+
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut traces = self.traces.lock();
+        let Traces { by_mint, recency } = &mut *traces;
+
+        if !by_mint.contains_key(&metrics.mint) && by_mint.len() >= self.max_mints {
+            if let Some((_, coldest)) = recency.pop_first() {
+                by_mint.remove(&coldest);
+            }
+        }
+
+        let trace = by_mint.entry(metrics.mint).or_insert_with(|| MintTrace {
+            last_seq: 0,
+            records: VecDeque::with_capacity(self.depth),
+        });
+        recency.remove(&trace.last_seq);
+        recency.insert(seq, metrics.mint);
+        trace.last_seq = seq;
+        if trace.records.len() >= self.depth {
+            trace.records.pop_front();
+        }
+        trace.records.push_back(record);
+    }
+
+    /// 指定 mint 的判定历史（从旧到新；未追踪时返回 None）
+    pub fn get(&self, mint: &Pubkey) -> Option<Vec<DecisionRecord>> {
+        self.traces.lock().by_mint.get(mint).map(|trace| trace.records.iter().cloned().collect())
+    }
+}
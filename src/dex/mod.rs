@@ -0,0 +1,123 @@
+/// DEX 协议适配层
+///
+/// 执行器和仓位管理只依赖 `DexAdapter`，不直接拼 PumpFun 的账户表 / 指令数据，
+/// 接入 PumpSwap / Raydium / Moonshot 时新增一个适配器实现即可
+///
+/// 核心功能:
+/// 1. 账户派生 - 池子地址、池子 token 账户、创建者收费账户、用户 ATA
+/// 2. 池子解码 - 原始账户数据 → 统一的储备 / 创建者 / 是否已完成
+/// 3. 报价 - 按储备计算买入可得 token / 卖出可得 SOL、手续费和价格冲击
+/// 4. 指令构建 - 买入（token 数量 + 最大 SOL 成本）/ 卖出（token 数量 + 最小 SOL 输出）
+
+pub mod pumpfun;
+
+use anyhow::Result;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+pub use pumpfun::PumpFunAdapter;
+
+/// Associated Token Account 程序
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+/// 一个池子交易时需要的账户
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolAccounts {
+    /// 池子地址（PumpFun: bonding curve）
+    pub pool: Pubkey,
+    /// 池子持有 token 的账户（PumpFun: associated bonding curve）
+    pub pool_token_account: Pubkey,
+    /// 创建者收费账户（PumpFun: creator vault）
+    pub creator_vault: Pubkey,
+}
+
+/// 池子状态（适配器从原始账户数据解码）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolState {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    /// 实际可买 token 上限
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub creator: Pubkey,
+    /// 池子已关闭交易（PumpFun: 曲线已毕业）
+    pub complete: bool,
+}
+
+/// 报价结果（不含滑点）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Quote {
+    /// 输入数量（买入为 lamports，卖出为 token）
+    pub amount_in: u64,
+    /// 预计输出数量（买入为 token，卖出为 lamports，已扣手续费）
+    pub amount_out: u64,
+    /// 手续费（lamports）
+    pub fee_lamports: u64,
+    /// 相对当前现价的价格冲击（百分比）
+    pub price_impact_percent: f64,
+}
+
+/// 买卖指令涉及的用户侧账户
+#[derive(Debug, Clone, Copy)]
+pub struct SwapAccounts<'a> {
+    pub payer: Pubkey,
+    pub mint: Pubkey,
+    /// mint 的 token program（Token v3 / Token-2022）
+    pub token_program: Pubkey,
+    pub user_token_account: Pubkey,
+    pub pool: &'a PoolAccounts,
+}
+
+/// DEX 协议适配器
+pub trait DexAdapter: Send + Sync {
+    /// 协议名（日志使用）
+    fn name(&self) -> &'static str;
+
+    /// 协议程序地址
+    fn program_id(&self) -> Pubkey;
+
+    /// 派生 mint 对应的池子地址
+    fn derive_pool(&self, mint: &Pubkey) -> Pubkey;
+
+    /// 派生池子交易账户（池子地址 + 池子 token 账户 + 创建者收费账户）
+    fn derive_pool_accounts(&self, mint: &Pubkey, token_program: &Pubkey, creator: &Pubkey) -> PoolAccounts;
+
+    /// 解码池子账户数据（不是本协议的池子账户时返回 None）
+    fn decode_pool(&self, data: &[u8]) -> Option<PoolState>;
+
+    /// 买入报价：sol_amount lamports 可得的 token 数量
+    fn quote_buy(&self, pool: &PoolState, sol_amount: u64) -> Quote;
+
+    /// 卖出报价：token_amount 可得的 lamports
+    fn quote_sell(&self, pool: &PoolState, token_amount: u64) -> Quote;
+
+    /// 买入指令（买到 token_amount，最多花 max_sol_cost lamports）
+    fn build_buy_ix(&self, accounts: &SwapAccounts<'_>, token_amount: u64, max_sol_cost: u64) -> Result<Instruction>;
+
+    /// 卖出指令（卖出 token_amount，至少收回 min_sol_output lamports）
+    fn build_sell_ix(&self, accounts: &SwapAccounts<'_>, token_amount: u64, min_sol_output: u64) -> Result<Instruction>;
+
+    /// 本协议买入指令中的最大 SOL 成本（其他指令返回 None，支出限额使用）
+    fn buy_max_sol_cost(&self, ix: &Instruction) -> Option<u64>;
+
+    /// 用户在该 mint 下的 ATA
+    fn user_token_account(&self, wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        get_ata_with_program(wallet, mint, token_program)
+    }
+}
+
+/// 按 token program 派生 ATA（Token-2022 的 ATA 地址不同）
+pub fn get_ata_with_program(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    let associated_token_program_id = Pubkey::try_from(ASSOCIATED_TOKEN_PROGRAM_ID)
+        .expect("Invalid ASSOCIATED_TOKEN_PROGRAM_ID");
+
+    Pubkey::find_program_address(
+        &[
+            wallet.as_ref(),
+            token_program.as_ref(),
+            mint.as_ref(),
+        ],
+        &associated_token_program_id,
+    )
+    .0
+}
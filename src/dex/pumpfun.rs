@@ -0,0 +1,264 @@
+/// PumpFun bonding curve 适配器
+///
+/// 账户顺序、PDA 种子和报价公式完全参考 sol-trade-sdk（原先分别写在买入 / 卖出执行器里）
+///
+/// 核心功能:
+/// 1. PDA 派生 - bonding curve / creator vault / user volume accumulator
+/// 2. 报价 - 恒定乘积（virtual 储备），买入再取 min(real_token_reserves)
+/// 3. 买入指令 - 16 个账户，[discriminator, token_amount, max_sol_cost]
+/// 4. 卖出指令 - 14 个账户，[discriminator, amount, min_sol_output]
+///
+/// fee recipient 和费率来自 PumpGlobalState（pump.fun 会轮换 fee recipient）
+///
+/// fee_config / fee_program 不在 Global 账户中，仍是 sol-trade-sdk 的固定地址；
+/// fee program 按 FeeConfig 收取的分档费率没有读取，报价只按 Global 的协议费 + 创作者费估算，
+/// 实际收取的费率可能与报价不同
+
+use anyhow::{Context, Result};
+use log::debug;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+use super::{get_ata_with_program, DexAdapter, PoolAccounts, PoolState, Quote, SwapAccounts};
+use crate::grpc::parser::bonding_curve_account_decode;
+use crate::pump_global::PumpGlobalState;
+
+// PumpFun 常量（完全参考 sol-trade-sdk）
+pub const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+const PUMPFUN_GLOBAL: &str = "4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf";
+const PUMPFUN_EVENT_AUTHORITY: &str = "Ce6TQqeHC9p8KetsN6JsjHK7UTZk7nasjjnr7XxXp9F1";
+const SYSTEM_TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+
+// 🔥 新增: sol-trade-sdk 最新版本的额外账户（固定地址，不从 Global 读取）
+const GLOBAL_VOLUME_ACCUMULATOR: &str = "Hq2wp8uJ9jCPsYgNHex8RtqdvMPfVGoYwjvF1ATiwn2Y";
+const FEE_CONFIG: &str = "8Wf5TiAheLUqBrKXeYg2JtAFFMWtKdG2BSFgqUcPVwTt";
+const FEE_PROGRAM: &str = "pfeeUxB6jkeY1Hxd7CsFCAjcbHA9rWtchMGdZ6VojVZ";
+
+/// PumpFun 买入指令 discriminator
+pub const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+/// PumpFun 卖出指令 discriminator
+pub const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+const BASIS_POINTS: u128 = 10_000;
+
+/// PumpFun 适配器
+pub struct PumpFunAdapter {
+    program: Pubkey,
+    global: Pubkey,
+    event_authority: Pubkey,
+    system_program: Pubkey,
+    token_program_v3: Pubkey,
+    global_volume_accumulator: Pubkey,
+    fee_config: Pubkey,
+    fee_program: Pubkey,
+    /// PumpFun Global 状态（fee recipient / 费率）
+    pump_global: Arc<PumpGlobalState>,
+}
+
+impl PumpFunAdapter {
+    pub fn new(pump_global: Arc<PumpGlobalState>) -> Result<Self> {
+        Ok(Self {
+            program: Pubkey::try_from(PUMPFUN_PROGRAM_ID).context("Invalid PumpFun program ID")?,
+            global: Pubkey::try_from(PUMPFUN_GLOBAL).context("Invalid global account")?,
+            event_authority: Pubkey::try_from(PUMPFUN_EVENT_AUTHORITY).context("Invalid event authority")?,
+            system_program: Pubkey::try_from(SYSTEM_PROGRAM)?,
+            token_program_v3: Pubkey::try_from(SYSTEM_TOKEN_PROGRAM)?,
+            global_volume_accumulator: Pubkey::try_from(GLOBAL_VOLUME_ACCUMULATOR)?,
+            fee_config: Pubkey::try_from(FEE_CONFIG)?,
+            fee_program: Pubkey::try_from(FEE_PROGRAM)?,
+            pump_global,
+        })
+    }
+
+    /// 派生 creator_vault PDA
+    /// 🔥 修复: creator_vault 是 PDA，不是 ATA！seed = [b"creator-vault", creator]
+    pub fn derive_creator_vault(&self, creator: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"creator-vault", creator.as_ref()], &self.program).0
+    }
+
+    /// 派生 user_volume_accumulator PDA
+    /// 🔥 修复: seed 必须是 "user_volume_accumulator" (underscore)，不是 hyphen!
+    pub fn derive_user_volume_accumulator(&self, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"user_volume_accumulator", user.as_ref()], &self.program).0
+    }
+
+    fn total_fee_basis_points(&self) -> u128 {
+        self.pump_global.fees().total_fee_basis_points() as u128
+    }
+}
+
+impl DexAdapter for PumpFunAdapter {
+    fn name(&self) -> &'static str {
+        "PumpFun"
+    }
+
+    fn program_id(&self) -> Pubkey {
+        self.program
+    }
+
+    fn derive_pool(&self, mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &self.program).0
+    }
+
+    fn derive_pool_accounts(&self, mint: &Pubkey, token_program: &Pubkey, creator: &Pubkey) -> PoolAccounts {
+        let pool = self.derive_pool(mint);
+        PoolAccounts {
+            pool,
+            pool_token_account: get_ata_with_program(&pool, mint, token_program),
+            creator_vault: self.derive_creator_vault(creator),
+        }
+    }
+
+    fn decode_pool(&self, data: &[u8]) -> Option<PoolState> {
+        let curve = bonding_curve_account_decode(data)?;
+        Some(PoolState {
+            virtual_token_reserves: curve.virtual_token_reserves,
+            virtual_sol_reserves: curve.virtual_sol_reserves,
+            real_token_reserves: curve.real_token_reserves,
+            real_sol_reserves: curve.real_sol_reserves,
+            creator: curve.creator,
+            complete: curve.complete,
+        })
+    }
+
+    /// 参考: sol-trade-sdk/src/utils/calc/pumpfun.rs:get_buy_token_amount_from_sol_amount
+    /// 🔥 修复: 使用 virtual_token_reserves 计算，再 min(real_token_reserves)
+    fn quote_buy(&self, pool: &PoolState, sol_amount: u64) -> Quote {
+        let mut quote = Quote { amount_in: sol_amount, ..Quote::default() };
+        if sol_amount == 0 || pool.virtual_token_reserves == 0 || pool.virtual_sol_reserves == 0 {
+            return quote;
+        }
+
+        // 扣除手续费后的输入金额
+        let input_amount = (sol_amount as u128)
+            .checked_mul(BASIS_POINTS)
+            .unwrap_or(0)
+            .checked_div(self.total_fee_basis_points() + BASIS_POINTS)
+            .unwrap_or(0);
+        if input_amount == 0 {
+            return quote;
+        }
+
+        // 恒定乘积公式: k = x * y
+        let denominator = (pool.virtual_sol_reserves as u128) + input_amount;
+        let tokens_received = input_amount
+            .checked_mul(pool.virtual_token_reserves as u128)
+            .unwrap_or(0)
+            .checked_div(denominator)
+            .unwrap_or(0);
+
+        quote.amount_out = (tokens_received.min(u64::MAX as u128) as u64).min(pool.real_token_reserves);
+        quote.fee_lamports = sol_amount - input_amount as u64;
+        if quote.amount_out > 0 {
+            let spot = pool.virtual_sol_reserves as f64 / pool.virtual_token_reserves as f64;
+            let effective = input_amount as f64 / quote.amount_out as f64;
+            quote.price_impact_percent = (effective / spot - 1.0) * 100.0;
+        }
+        quote
+    }
+
+    /// 完全对齐 sol-trade-sdk 的 BondingCurveAccount::get_sell_price
+    fn quote_sell(&self, pool: &PoolState, token_amount: u64) -> Quote {
+        let mut quote = Quote { amount_in: token_amount, ..Quote::default() };
+        if token_amount == 0 || pool.virtual_token_reserves == 0 || pool.virtual_sol_reserves == 0 {
+            return quote;
+        }
+
+        // 按比例可得的 virtual sol
+        let n: u128 = ((token_amount as u128) * (pool.virtual_sol_reserves as u128))
+            / ((pool.virtual_token_reserves as u128) + (token_amount as u128));
+        // 手续费
+        let a: u128 = (n * self.total_fee_basis_points()) / BASIS_POINTS;
+
+        quote.amount_out = n.saturating_sub(a).min(u64::MAX as u128) as u64;
+        quote.fee_lamports = a.min(u64::MAX as u128) as u64;
+        let spot = pool.virtual_sol_reserves as f64 / pool.virtual_token_reserves as f64;
+        let effective = n as f64 / token_amount as f64;
+        quote.price_impact_percent = (1.0 - effective / spot) * 100.0;
+        quote
+    }
+
+    /// 买入指令（完全参考 sol-trade-sdk 的账户顺序，16 个账户）
+    fn build_buy_ix(&self, accounts: &SwapAccounts<'_>, token_amount: u64, max_sol_cost: u64) -> Result<Instruction> {
+        let user_volume_accumulator = self.derive_user_volume_accumulator(&accounts.payer);
+        // 🔥 修复: fee recipient 来自 Global 账户（pump.fun 会轮换）
+        let fee_recipient = self.pump_global.fee_recipient();
+
+        // 格式: [discriminator(8), token_amount(8), max_sol_cost(8)]
+        let mut data = Vec::with_capacity(24);
+        data.extend_from_slice(&BUY_DISCRIMINATOR);
+        data.extend_from_slice(&token_amount.to_le_bytes());
+        data.extend_from_slice(&max_sol_cost.to_le_bytes());
+
+        let metas = vec![
+            AccountMeta::new_readonly(self.global, false),                      // 0: global
+            AccountMeta::new(fee_recipient, false),                             // 1: fee_recipient
+            AccountMeta::new_readonly(accounts.mint, false),                    // 2: mint
+            AccountMeta::new(accounts.pool.pool, false),                        // 3: bonding_curve
+            AccountMeta::new(accounts.pool.pool_token_account, false),          // 4: associated_bonding_curve
+            AccountMeta::new(accounts.user_token_account, false),               // 5: user_token_account
+            AccountMeta::new(accounts.payer, true),                             // 6: payer (signer)
+            AccountMeta::new_readonly(self.system_program, false),              // 7: system_program
+            AccountMeta::new_readonly(self.token_program_v3, false),            // 8: token_program (固定 Token v3，对齐 SDK) ⭐
+            AccountMeta::new(accounts.pool.creator_vault, false),               // 9: creator_vault ⭐
+            AccountMeta::new_readonly(self.event_authority, false),             // 10: event_authority
+            AccountMeta::new_readonly(self.program, false),                     // 11: pumpfun_program
+            AccountMeta::new(self.global_volume_accumulator, false),            // 12: global_volume_accumulator ⭐ (可写)
+            AccountMeta::new(user_volume_accumulator, false),                   // 13: user_volume_accumulator ⭐
+            AccountMeta::new_readonly(self.fee_config, false),                  // 14: fee_config ⭐
+            AccountMeta::new_readonly(self.fee_program, false),                 // 15: fee_program ⭐
+        ];
+
+        // 🔥 排障日志: 打印关键账户表摘要
+        debug!("📋 PumpFun 买入账户表摘要 (16 accounts):");
+        debug!("   [1] fee_recipient: {} (writable)", fee_recipient);
+        debug!("   [9] creator_vault: {} (writable) ⭐", accounts.pool.creator_vault);
+        debug!("   [13] user_volume_accumulator: {} (writable) ⭐", user_volume_accumulator);
+
+        Ok(Instruction { program_id: self.program, accounts: metas, data })
+    }
+
+    /// 卖出指令（完全参考 sol-trade-sdk 的账户顺序，14 个账户）
+    fn build_sell_ix(&self, accounts: &SwapAccounts<'_>, token_amount: u64, min_sol_output: u64) -> Result<Instruction> {
+        let fee_recipient = self.pump_global.fee_recipient();
+
+        // 格式: [discriminator(8), amount(8), min_sol_output(8)]
+        let mut data = Vec::with_capacity(24);
+        data.extend_from_slice(&SELL_DISCRIMINATOR);
+        data.extend_from_slice(&token_amount.to_le_bytes());
+        data.extend_from_slice(&min_sol_output.to_le_bytes());
+
+        let metas = vec![
+            AccountMeta::new_readonly(self.global, false),                      // 0: global
+            AccountMeta::new(fee_recipient, false),                             // 1: fee_recipient
+            AccountMeta::new_readonly(accounts.mint, false),                    // 2: mint
+            AccountMeta::new(accounts.pool.pool, false),                        // 3: bonding_curve
+            AccountMeta::new(accounts.pool.pool_token_account, false),          // 4: associated_bonding_curve
+            AccountMeta::new(accounts.user_token_account, false),               // 5: user_token_account
+            AccountMeta::new(accounts.payer, true),                             // 6: payer (signer)
+            AccountMeta::new_readonly(self.system_program, false),              // 7: system_program
+            AccountMeta::new(accounts.pool.creator_vault, false),               // 8: creator_vault ⭐
+            AccountMeta::new_readonly(accounts.token_program, false),           // 9: token_program ⭐
+            AccountMeta::new_readonly(self.event_authority, false),             // 10: event_authority
+            AccountMeta::new_readonly(self.program, false),                     // 11: pumpfun_program
+            AccountMeta::new_readonly(self.fee_config, false),                  // 12: fee_config ⭐
+            AccountMeta::new_readonly(self.fee_program, false),                 // 13: fee_program ⭐
+        ];
+
+        debug!("📋 PumpFun 卖出账户表摘要 (14 accounts):");
+        debug!("   [1] fee_recipient: {} (writable)", fee_recipient);
+        debug!("   [8] creator_vault: {} (writable) ⭐", accounts.pool.creator_vault);
+        debug!("   [9] token_program: {} (readonly) ⭐", accounts.token_program);
+
+        Ok(Instruction { program_id: self.program, accounts: metas, data })
+    }
+
+    fn buy_max_sol_cost(&self, ix: &Instruction) -> Option<u64> {
+        if ix.program_id != self.program || !ix.data.starts_with(&BUY_DISCRIMINATOR) || ix.data.len() < 24 {
+            return None;
+        }
+        ix.data[16..24].try_into().ok().map(u64::from_le_bytes)
+    }
+}
@@ -0,0 +1,283 @@
+/// 启动诊断（check-config / doctor）
+///
+/// `solsniper check-config`（或 `solsniper doctor`）在开启交易前检查配置和所有外部依赖，
+/// 打印 PASS / WARN / FAIL 报告后退出；存在 FAIL 项时以非零状态码退出
+///
+/// 核心功能:
+/// 1. 配置 - 加载并校验所有配置项，加载钱包签名器
+/// 2. gRPC - 连接、订阅并等待第一条消息
+/// 3. RPC - 主端点 / 备用端点 getSlot 可达性、延迟与 slot 落后
+/// 4. LightSpeed - 专用端点可达（use_lightspeed 时）
+/// 5. SWQOS - 逐个服务 ping，验证端点和凭证（swqos_enabled 时）
+/// 6. 钱包余额 - 余额是否覆盖单笔买入储备（买入金额 + tip + balance_reserve_buffer_sol）
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::executor::lightspeed_buy::transfer_lamports;
+use crate::grpc::client as grpc_client;
+use crate::rpc_pool::RpcPool;
+use crate::signer;
+use crate::swqos::{MultiSwqosManager, SwqosConfig};
+
+/// 等待 gRPC 第一条消息的超时
+const GRPC_FIRST_MESSAGE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl CheckStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✅ PASS",
+            CheckStatus::Warn => "⚠️  WARN",
+            CheckStatus::Fail => "❌ FAIL",
+            CheckStatus::Skip => "⏭️  SKIP",
+        }
+    }
+}
+
+struct CheckResult {
+    name: String,
+    status: CheckStatus,
+    detail: String,
+}
+
+/// 诊断报告
+#[derive(Default)]
+struct DoctorReport {
+    checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    fn record(&mut self, name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) {
+        let result = CheckResult {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        };
+        println!("  {}  {:<24} {}", result.status.label(), result.name, result.detail);
+        self.checks.push(result);
+    }
+
+    fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|check| check.status == status).count()
+    }
+
+    fn passed(&self) -> bool {
+        self.count(CheckStatus::Fail) == 0
+    }
+
+    fn print_summary(&self) {
+        println!("================================================");
+        println!("  {} PASS / {} WARN / {} FAIL / {} SKIP",
+            self.count(CheckStatus::Pass), self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail), self.count(CheckStatus::Skip));
+        if self.passed() {
+            println!("  ✅ 所有检查通过，可以开启交易");
+        } else {
+            println!("  ❌ 存在失败项，请修复后再开启交易:");
+            for check in self.checks.iter().filter(|check| check.status == CheckStatus::Fail) {
+                println!("     - {}: {}", check.name, check.detail);
+            }
+        }
+    }
+}
+
+/// 执行全部诊断并打印报告，返回是否没有失败项
+pub async fn run() -> bool {
+    println!("🩺 SolSniper 配置诊断");
+    println!("================================================");
+
+    let mut report = DoctorReport::default();
+
+    // 1. 配置（失败时后续检查无法进行）
+    let config = match Config::from_env() {
+        Ok(config) => {
+            report.record("配置校验", CheckStatus::Pass, "所有配置项有效");
+            config
+        }
+        Err(e) => {
+            report.record("配置校验", CheckStatus::Fail, format!("{:#}", e));
+            report.print_summary();
+            return false;
+        }
+    };
+
+    let wallet = match signer::from_config(&config).await {
+        Ok(signer) => {
+            report.record("钱包", CheckStatus::Pass, signer.pubkey().to_string());
+            Some(signer.pubkey())
+        }
+        Err(e) => {
+            report.record("钱包", CheckStatus::Fail, format!("{:#}", e));
+            None
+        }
+    };
+
+    // 2. gRPC
+    check_grpc(&config, &mut report).await;
+
+    // 3. RPC
+    let rpc_pool = Arc::new(RpcPool::from_config(&config));
+    check_rpc(&config, &rpc_pool, &mut report).await;
+
+    // 4. LightSpeed
+    check_lightspeed(&config, &mut report).await;
+
+    // 5. SWQOS
+    let swqos = check_swqos(&config, &mut report).await;
+
+    // 6. 钱包余额
+    match wallet {
+        Some(wallet) => check_balance(&config, &rpc_pool, &wallet, swqos.as_deref(), &mut report).await,
+        None => report.record("钱包余额", CheckStatus::Skip, "钱包不可用"),
+    }
+
+    report.print_summary();
+    report.passed()
+}
+
+async fn check_grpc(config: &Config, report: &mut DoctorReport) {
+    match grpc_client::probe(&config.grpc_endpoint, config.grpc_x_token.clone(), GRPC_FIRST_MESSAGE_TIMEOUT).await {
+        Ok(probe) => report.record(
+            "gRPC",
+            CheckStatus::Pass,
+            format!("连接 {}ms，首条消息 {}ms{}",
+                probe.connect.as_millis(),
+                probe.first_message.as_millis(),
+                probe.slot.map_or(String::new(), |slot| format!(" (slot {})", slot))),
+        ),
+        Err(e) => report.record("gRPC", CheckStatus::Fail, format!("{:#}", e)),
+    }
+}
+
+async fn check_rpc(config: &Config, rpc_pool: &Arc<RpcPool>, report: &mut DoctorReport) {
+    let probes = rpc_pool.probe().await;
+    let max_slot = probes.iter().filter_map(|probe| probe.slot).max();
+
+    for probe in probes {
+        let name = if probe.primary {
+            format!("RPC 主端点 {}", probe.host)
+        } else {
+            format!("RPC 备用端点 {}", probe.host)
+        };
+        let unavailable = if probe.primary { CheckStatus::Fail } else { CheckStatus::Warn };
+
+        match (probe.slot, probe.error) {
+            (Some(slot), _) => {
+                let lag = max_slot.unwrap_or(slot) - slot;
+                if config.rpc_max_slot_lag > 0 && lag > config.rpc_max_slot_lag {
+                    report.record(name, CheckStatus::Warn,
+                        format!("slot {} 落后 {} slots ({}ms)", slot, lag, probe.latency.as_millis()));
+                } else {
+                    report.record(name, CheckStatus::Pass,
+                        format!("slot {} ({}ms)", slot, probe.latency.as_millis()));
+                }
+            }
+            (None, error) => {
+                let error = error.unwrap_or_default();
+                if probe.reachable {
+                    report.record(name, unavailable, format!("可达但 getSlot 失败: {}", error));
+                } else {
+                    report.record(name, unavailable, format!("不可达: {}", error));
+                }
+            }
+        }
+    }
+}
+
+async fn check_lightspeed(config: &Config, report: &mut DoctorReport) {
+    if !config.use_lightspeed {
+        report.record("LightSpeed", CheckStatus::Skip, "未启用");
+        return;
+    }
+
+    // LightSpeed 端点可能只接受 sendTransaction，返回 RPC 业务错误也视为可达
+    let pool = RpcPool::single(config, &config.rpc_lightspeed_endpoint);
+    for probe in pool.probe().await {
+        if probe.reachable {
+            report.record("LightSpeed", CheckStatus::Pass,
+                format!("{} 可达 ({}ms)", probe.host, probe.latency.as_millis()));
+        } else {
+            report.record("LightSpeed", CheckStatus::Fail,
+                format!("{} 不可达: {}", probe.host, probe.error.unwrap_or_default()));
+        }
+    }
+}
+
+async fn check_swqos(config: &Config, report: &mut DoctorReport) -> Option<Arc<MultiSwqosManager>> {
+    if !config.swqos_enabled {
+        report.record("SWQOS", CheckStatus::Skip, "未启用");
+        return None;
+    }
+
+    let manager = match SwqosConfig::from_env().and_then(MultiSwqosManager::new) {
+        Ok(manager) => Arc::new(manager),
+        Err(e) => {
+            report.record("SWQOS", CheckStatus::Fail, format!("初始化失败: {:#}", e));
+            return None;
+        }
+    };
+
+    let probes = manager.probe_all().await;
+    if probes.is_empty() {
+        report.record("SWQOS", CheckStatus::Warn, "已启用但未配置任何服务");
+    }
+    for (name, result) in probes {
+        match result {
+            Ok(rtt) => report.record(format!("SWQOS {}", name), CheckStatus::Pass, format!("RTT {}ms", rtt)),
+            Err(e) => report.record(format!("SWQOS {}", name), CheckStatus::Fail, e),
+        }
+    }
+
+    Some(manager)
+}
+
+async fn check_balance(
+    config: &Config,
+    rpc_pool: &Arc<RpcPool>,
+    wallet: &Pubkey,
+    swqos: Option<&MultiSwqosManager>,
+    report: &mut DoctorReport,
+) {
+    let rpc_client = rpc_pool.nonblocking_client(config.get_balance_commitment());
+    let balance = match rpc_client.get_balance(wallet).await {
+        Ok(balance) => balance,
+        Err(e) => {
+            report.record("钱包余额", CheckStatus::Fail, format!("查询失败: {}", e));
+            return;
+        }
+    };
+
+    // 与余额守卫一致：默认买入金额 + 单笔 tip + balance_reserve_buffer_sol
+    let mut tip_lamports = 0;
+    if config.use_lightspeed {
+        tip_lamports += config.get_lightspeed_tip_lamports();
+    }
+    if let Some(swqos) = swqos {
+        if let Ok(tip_instructions) = swqos.get_all_tip_instructions(wallet) {
+            tip_lamports += tip_instructions.iter().map(|(_, ix)| transfer_lamports(ix)).sum::<u64>();
+        }
+    }
+    let reserve = config.get_snipe_amount_lamports()
+        + tip_lamports
+        + (config.balance_reserve_buffer_sol * 1_000_000_000.0) as u64;
+
+    let detail = format!("{:.4} SOL (单笔买入储备 {:.4} SOL)",
+        balance as f64 / 1_000_000_000.0, reserve as f64 / 1_000_000_000.0);
+    if balance >= reserve {
+        report.record("钱包余额", CheckStatus::Pass, detail);
+    } else {
+        report.record("钱包余额", CheckStatus::Fail, format!("{}，余额不足", detail));
+    }
+}
@@ -9,11 +9,16 @@
 /// 4. 多维度触发条件 - 组合多个条件
 /// 5. 风险等级调整 - 根据风险等级调整激进程度
 
-use chrono::{Utc, Timelike};
+use chrono::{DateTime, Datelike, Utc, Timelike};
 use log::{debug, info};
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::VecDeque;
 
 use crate::advanced_metrics::AdvancedMetrics;
-use crate::types::WindowMetrics;
+use crate::config::Config;
+use crate::decision_trace::DecisionNotes;
+use crate::types::{ScoreBreakdown, WindowMetrics};
 
 /// 策略模式
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -28,6 +33,36 @@ pub enum StrategyMode {
     Custom,
 }
 
+impl StrategyMode {
+    /// 从配置解析当前策略模式
+    ///
+    /// 优先使用布尔值开关（custom > conservative > aggressive > balanced），
+    /// 所有开关都关闭时回退到 DYNAMIC_STRATEGY_MODE 字符串
+    pub fn from_config(config: &Config) -> Self {
+        if config.enable_custom_mode {
+            StrategyMode::Custom
+        } else if config.enable_conservative_mode {
+            StrategyMode::Conservative
+        } else if config.enable_aggressive_mode {
+            StrategyMode::Aggressive
+        } else if config.enable_balanced_mode {
+            StrategyMode::Balanced
+        } else {
+            Self::from_name(&config.dynamic_strategy_mode)
+        }
+    }
+
+    /// 按名称解析策略模式（未知名称视为平衡模式）
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "conservative" => StrategyMode::Conservative,
+            "aggressive" => StrategyMode::Aggressive,
+            "custom" => StrategyMode::Custom,
+            _ => StrategyMode::Balanced,
+        }
+    }
+}
+
 /// 动态策略配置
 #[derive(Debug, Clone)]
 pub struct DynamicStrategyConfig {
@@ -39,6 +74,28 @@ pub struct DynamicStrategyConfig {
     pub sell_triggers: SellTriggers,
     /// 自适应参数
     pub adaptive_params: AdaptiveParams,
+    /// 波动状态切换使用的买入条件（None 时使用内置保守 / 激进预设）
+    pub regime_triggers: Option<RegimeTriggers>,
+}
+
+/// 波动状态切换使用的买入条件（正常市场使用配置模式本身的条件）
+#[derive(Debug, Clone)]
+pub struct RegimeTriggers {
+    /// 剧烈市场
+    pub conservative: BuyTriggers,
+    /// 平静市场
+    pub aggressive: BuyTriggers,
+}
+
+/// 市场波动状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VolatilityRegime {
+    /// 平静 - 使用激进条件
+    Calm,
+    /// 正常 - 使用配置模式的条件
+    Normal,
+    /// 剧烈 - 使用保守条件
+    Volatile,
 }
 
 /// 买入触发条件
@@ -86,8 +143,135 @@ pub struct AdaptiveParams {
     pub enable_time_adaptation: bool,
     /// 是否启用成功率反馈
     pub enable_success_feedback: bool,
-    /// 波动率调整系数（0.5-2.0）
-    pub volatility_adjustment_factor: f64,
+    /// 成功率反馈调整系数（>1 收紧买入阈值，<1 放宽）
+    pub success_adjustment_factor: f64,
+    /// 每次调整的步长
+    pub success_feedback_step: f64,
+    /// 连胜多少笔后放宽一次
+    pub success_feedback_win_streak: u32,
+    /// 调整系数下限（最多放宽到的程度）
+    pub success_feedback_min_factor: f64,
+    /// 调整系数上限（最多收紧到的程度）
+    pub success_feedback_max_factor: f64,
+    /// 时段调整系数（当前时段历史表现差时 >1，提高综合评分要求）
+    pub time_adjustment_factor: f64,
+    /// 时段统计至少多少笔交易后才参与判断
+    pub time_bucket_min_trades: u32,
+    /// 时段胜率低于此值视为差时段
+    pub bad_hour_win_rate: f64,
+    /// 差时段综合评分要求乘数
+    pub bad_hour_score_multiplier: f64,
+    /// 差时段仓位乘数
+    pub bad_hour_size_multiplier: f64,
+    /// 只采集上线后前多少秒内的波动率（新币首段波动）
+    pub regime_window_secs: u64,
+    /// 参与市场波动率平均的最近新币数
+    pub regime_sample_mints: usize,
+    /// 至少多少个新币样本后才切换状态
+    pub regime_min_samples: usize,
+    /// 平均波动率低于此值为平静市场（切换激进条件）
+    pub regime_calm_volatility: f64,
+    /// 平均波动率高于此值为剧烈市场（切换保守条件）
+    pub regime_volatile_volatility: f64,
+}
+
+impl Default for AdaptiveParams {
+    fn default() -> Self {
+        Self {
+            enable_volatility_adaptation: true,
+            enable_time_adaptation: true,
+            enable_success_feedback: true,
+            success_adjustment_factor: 1.0,
+            success_feedback_step: 0.05,
+            success_feedback_win_streak: 3,
+            success_feedback_min_factor: 0.85,
+            success_feedback_max_factor: 1.3,
+            time_adjustment_factor: 1.0,
+            time_bucket_min_trades: 5,
+            bad_hour_win_rate: 0.3,
+            bad_hour_score_multiplier: 1.2,
+            bad_hour_size_multiplier: 0.5,
+            regime_window_secs: 60,
+            regime_sample_mints: 50,
+            regime_min_samples: 10,
+            regime_calm_volatility: 0.05,
+            regime_volatile_volatility: 0.15,
+        }
+    }
+}
+
+/// 已实现交易统计（成功率反馈使用）
+#[derive(Debug, Clone, Default)]
+pub struct TradeStats {
+    pub wins: u32,
+    pub losses: u32,
+    /// 当前连胜笔数
+    pub win_streak: u32,
+    /// 当前连亏笔数
+    pub loss_streak: u32,
+    /// 累计盈亏百分比
+    pub total_profit_loss_percent: f64,
+}
+
+impl TradeStats {
+    pub fn win_rate(&self) -> f64 {
+        let total = self.wins + self.losses;
+        if total == 0 {
+            0.0
+        } else {
+            self.wins as f64 / total as f64
+        }
+    }
+}
+
+/// 单个时段的胜负统计
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BucketStats {
+    pub wins: u32,
+    pub losses: u32,
+}
+
+impl BucketStats {
+    pub fn trades(&self) -> u32 {
+        self.wins + self.losses
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.trades() == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.trades() as f64
+        }
+    }
+}
+
+/// 按 UTC 小时和星期分桶的胜率统计（按开仓时间归档）
+#[derive(Debug, Clone, Default)]
+pub struct TimeBucketStats {
+    pub hourly: [BucketStats; 24],
+    pub weekday: [BucketStats; 7],
+}
+
+impl TimeBucketStats {
+    pub fn record(&mut self, entry_time: DateTime<Utc>, is_win: bool) {
+        let hour = entry_time.hour() as usize;
+        let weekday = entry_time.weekday().num_days_from_monday() as usize;
+        for bucket in [&mut self.hourly[hour], &mut self.weekday[weekday]] {
+            if is_win {
+                bucket.wins += 1;
+            } else {
+                bucket.losses += 1;
+            }
+        }
+    }
+
+    /// 某一时刻所在的小时桶和星期桶
+    pub fn buckets_at(&self, time: DateTime<Utc>) -> (BucketStats, BucketStats) {
+        (
+            self.hourly[time.hour() as usize],
+            self.weekday[time.weekday().num_days_from_monday() as usize],
+        )
+    }
 }
 
 impl Default for DynamicStrategyConfig {
@@ -123,8 +307,9 @@ impl DynamicStrategyConfig {
                 enable_volatility_adaptation: true,
                 enable_time_adaptation: true,
                 enable_success_feedback: true,
-                volatility_adjustment_factor: 1.0,
+                ..AdaptiveParams::default()
             },
+            regime_triggers: None,
         }
     }
 
@@ -153,8 +338,9 @@ impl DynamicStrategyConfig {
                 enable_volatility_adaptation: true,
                 enable_time_adaptation: true,
                 enable_success_feedback: true,
-                volatility_adjustment_factor: 1.0,
+                ..AdaptiveParams::default()
             },
+            regime_triggers: None,
         }
     }
 
@@ -184,15 +370,69 @@ impl DynamicStrategyConfig {
                 enable_volatility_adaptation: true,
                 enable_time_adaptation: true,
                 enable_success_feedback: true,
-                volatility_adjustment_factor: 1.0,
+                ..AdaptiveParams::default()
             },
+            regime_triggers: None,
         }
     }
 }
 
+/// 最近新币的首段波动率（按 mint 去重，保留最近 max_samples 个）
+#[derive(Debug, Default)]
+pub struct LaunchVolatilityTracker {
+    samples: VecDeque<(Pubkey, f64)>,
+}
+
+impl LaunchVolatilityTracker {
+    /// 记录某个新币的最新波动率（同一 mint 覆盖旧值）
+    pub fn observe(&mut self, mint: Pubkey, volatility: f64, max_samples: usize) {
+        if let Some(sample) = self.samples.iter_mut().find(|(m, _)| *m == mint) {
+            sample.1 = volatility;
+            return;
+        }
+
+        self.samples.push_back((mint, volatility));
+        while self.samples.len() > max_samples {
+            self.samples.pop_front();
+        }
+    }
+
+    /// 平均波动率（样本不足时为 None）
+    pub fn average(&self, min_samples: usize) -> Option<f64> {
+        if self.samples.is_empty() || self.samples.len() < min_samples {
+            return None;
+        }
+        Some(self.samples.iter().map(|(_, v)| v).sum::<f64>() / self.samples.len() as f64)
+    }
+}
+
+/// 买入评估结果
+#[derive(Debug, Clone)]
+pub struct BuyEvaluation {
+    pub should_buy: bool,
+    /// 置信度（0-1）
+    pub confidence: f64,
+    pub passed_conditions: usize,
+    pub total_conditions: usize,
+    /// 综合评分拆解
+    pub score: ScoreBreakdown,
+}
+
 /// 动态策略引擎
 pub struct DynamicStrategyEngine {
     config: DynamicStrategyConfig,
+    /// 未经成功率反馈调整的原始买入条件
+    base_buy_triggers: BuyTriggers,
+    /// 已实现交易统计
+    stats: TradeStats,
+    /// 按小时 / 星期分桶的胜率统计
+    time_stats: TimeBucketStats,
+    /// 配置模式本身的买入条件（正常市场使用）
+    configured_buy_triggers: BuyTriggers,
+    /// 当前市场波动状态
+    regime: VolatilityRegime,
+    /// 最近新币的首段波动率（单独加锁：采集样本不需要引擎写锁）
+    launch_volatility: Mutex<LaunchVolatilityTracker>,
 }
 
 impl DynamicStrategyEngine {
@@ -205,23 +445,30 @@ impl DynamicStrategyEngine {
         info!("   加速度阈值: {:.2}x", config.buy_triggers.min_acceleration);
         
         Self {
+            base_buy_triggers: config.buy_triggers.clone(),
+            configured_buy_triggers: config.buy_triggers.clone(),
             config,
+            stats: TradeStats::default(),
+            time_stats: TimeBucketStats::default(),
+            regime: VolatilityRegime::Normal,
+            launch_volatility: Mutex::new(LaunchVolatilityTracker::default()),
         }
     }
 
     /// 评估买入条件
     /// 
-    /// 返回是否满足买入条件和置信度（0-1）
+    /// 返回是否满足买入条件和置信度（0-1）；未通过的条件（实际值与阈值）写入 notes，
+    /// 决策追踪关闭时不格式化
+    ///
+    /// 只读评估：自适应参数由调用方在评估前通过 adapt_parameters 更新
     pub fn evaluate_buy(
-        &mut self,
+        &self,
         metrics: &WindowMetrics,
         advanced_metrics: &AdvancedMetrics,
-    ) -> (bool, f64) {
+        notes: &mut DecisionNotes,
+    ) -> BuyEvaluation {
         debug!("🎯 评估买入条件");
         
-        // 自适应调整参数
-        self.adapt_parameters(metrics, advanced_metrics);
-        
         let triggers = &self.config.buy_triggers;
         let mut passed_conditions = 0;
         let mut total_conditions = 0;
@@ -237,6 +484,7 @@ impl DynamicStrategyEngine {
                 triggers.min_buy_ratio * 100.0
             );
         } else {
+            notes.push(|| format!("buy_ratio {:.2}% < {:.2}%", metrics.buy_ratio * 100.0, triggers.min_buy_ratio * 100.0));
             debug!("❌ 买占比: {:.2}% < {:.2}%", 
                 metrics.buy_ratio * 100.0, 
                 triggers.min_buy_ratio * 100.0
@@ -254,6 +502,7 @@ impl DynamicStrategyEngine {
                 triggers.min_net_inflow_sol
             );
         } else {
+            notes.push(|| format!("net_inflow {:.4} SOL < {:.4} SOL", net_inflow_sol, triggers.min_net_inflow_sol));
             debug!("❌ 净流入: {:.4} SOL < {:.4} SOL", 
                 net_inflow_sol, 
                 triggers.min_net_inflow_sol
@@ -270,6 +519,7 @@ impl DynamicStrategyEngine {
                 triggers.min_acceleration
             );
         } else {
+            notes.push(|| format!("acceleration {:.2}x < {:.2}x", metrics.acceleration, triggers.min_acceleration));
             debug!("❌ 加速度: {:.2}x < {:.2}x", 
                 metrics.acceleration, 
                 triggers.min_acceleration
@@ -286,6 +536,7 @@ impl DynamicStrategyEngine {
                 triggers.min_high_frequency_trades
             );
         } else {
+            notes.push(|| format!("high_frequency_trades {} < {}", advanced_metrics.high_frequency_trades, triggers.min_high_frequency_trades));
             debug!("❌ 高频交易: {} < {}", 
                 advanced_metrics.high_frequency_trades, 
                 triggers.min_high_frequency_trades
@@ -302,6 +553,7 @@ impl DynamicStrategyEngine {
                 triggers.min_liquidity_depth
             );
         } else {
+            notes.push(|| format!("liquidity_depth {:.4} < {:.4}", advanced_metrics.liquidity_depth, triggers.min_liquidity_depth));
             debug!("❌ 流动性深度: {:.4} < {:.4}", 
                 advanced_metrics.liquidity_depth, 
                 triggers.min_liquidity_depth
@@ -318,6 +570,7 @@ impl DynamicStrategyEngine {
                 triggers.max_price_impact * 100.0
             );
         } else {
+            notes.push(|| format!("price_impact {:.4}% > {:.4}%", advanced_metrics.avg_price_impact * 100.0, triggers.max_price_impact * 100.0));
             debug!("❌ 价格冲击: {:.4}% > {:.4}%", 
                 advanced_metrics.avg_price_impact * 100.0, 
                 triggers.max_price_impact * 100.0
@@ -335,6 +588,7 @@ impl DynamicStrategyEngine {
                 triggers.max_slippage * 100.0
             );
         } else {
+            notes.push(|| format!("estimated_slippage {:.4}% > {:.4}%", estimated_slippage * 100.0, triggers.max_slippage * 100.0));
             debug!("❌ 预估滑点: {:.4}% > {:.4}%",
                 estimated_slippage * 100.0,
                 triggers.max_slippage * 100.0
@@ -343,7 +597,8 @@ impl DynamicStrategyEngine {
 
         // 8. 综合评分检查
         total_conditions += 1;
-        let composite_score = self.calculate_composite_score(metrics, advanced_metrics);
+        let mut score = self.score_breakdown(metrics, advanced_metrics);
+        let composite_score = score.composite;
         if composite_score >= triggers.min_composite_score {
             passed_conditions += 1;
             confidence += 0.05;
@@ -352,6 +607,7 @@ impl DynamicStrategyEngine {
                 triggers.min_composite_score
             );
         } else {
+            notes.push(|| format!("composite_score {:.4} < {:.4}", composite_score, triggers.min_composite_score));
             debug!("❌ 综合评分: {:.4} < {:.4}",
                 composite_score,
                 triggers.min_composite_score
@@ -369,18 +625,25 @@ impl DynamicStrategyEngine {
         );
         info!("   置信度: {:.2}%", confidence * 100.0);
         
-        (should_buy, confidence)
+        score.confidence = confidence;
+
+        BuyEvaluation {
+            should_buy,
+            confidence,
+            passed_conditions,
+            total_conditions,
+            score,
+        }
     }
 
     /// 自适应调整参数
-    fn adapt_parameters(&mut self, _metrics: &WindowMetrics, advanced_metrics: &AdvancedMetrics) {
+    pub fn adapt_parameters(&mut self) {
         let enable_volatility = self.config.adaptive_params.enable_volatility_adaptation;
         let enable_time = self.config.adaptive_params.enable_time_adaptation;
-        let _enable_success = self.config.adaptive_params.enable_success_feedback;
 
         // 1. 市场波动率自适应
         if enable_volatility {
-            self.adapt_to_volatility(advanced_metrics.volatility);
+            self.adapt_to_volatility();
         }
 
         // 2. 时间段自适应
@@ -388,59 +651,216 @@ impl DynamicStrategyEngine {
             self.adapt_to_time();
         }
 
-        // 3. 成功率反馈
-        // TODO: 实现交易历史记录后再启用
-        // if enable_success {
-        //     self.adapt_to_success_rate();
-        // }
+        // 3. 成功率反馈：由 record_trade_outcome 在每笔平仓后调整
+    }
+
+    /// 自适应参数是否需要更新（波动状态或时段系数将要切换）
+    ///
+    /// 只读检查，调用方据此决定是否获取写锁
+    pub fn adaptation_pending(&self) -> bool {
+        let params = &self.config.adaptive_params;
+        let regime_changed = params.enable_volatility_adaptation
+            && self.target_regime().is_some_and(|(regime, _)| regime != self.regime);
+        let time_changed = params.enable_time_adaptation
+            && (self.target_time_factor() - params.time_adjustment_factor).abs() > f64::EPSILON;
+        regime_changed || time_changed
     }
 
-    /// 根据波动率调整
-    fn adapt_to_volatility(&mut self, volatility: f64) {
-        // 高波动 -> 更保守
-        // 低波动 -> 更激进
-        let adjustment = if volatility > 0.15 {
-            0.8 // 提高阈值 20%
-        } else if volatility < 0.05 {
-            1.2 // 降低阈值 20%
+    /// 记录一笔已实现交易的结果（净盈亏百分比，按开仓时间归入时段统计），并按成功率反馈调整买入阈值
+    ///
+    /// 亏损立即收紧一步；连胜 success_feedback_win_streak 笔后放宽一步；调整系数限制在上下限之间
+    pub fn record_trade_outcome(&mut self, profit_loss_percent: f64, entry_time: DateTime<Utc>) {
+        let is_win = profit_loss_percent > 0.0;
+        self.time_stats.record(entry_time, is_win);
+        if is_win {
+            self.stats.wins += 1;
+            self.stats.win_streak += 1;
+            self.stats.loss_streak = 0;
         } else {
-            1.0
+            self.stats.losses += 1;
+            self.stats.loss_streak += 1;
+            self.stats.win_streak = 0;
+        }
+        self.stats.total_profit_loss_percent += profit_loss_percent;
+
+        info!("📈 交易统计: {} 胜 / {} 负 (胜率 {:.1}%), 累计 {:+.2}%",
+            self.stats.wins, self.stats.losses, self.stats.win_rate() * 100.0,
+            self.stats.total_profit_loss_percent);
+
+        if self.config.adaptive_params.enable_success_feedback {
+            self.adapt_to_success_rate(is_win);
+        }
+    }
+
+    /// 根据最近的交易结果调整成功率系数并重算买入条件
+    fn adapt_to_success_rate(&mut self, is_win: bool) {
+        let params = &mut self.config.adaptive_params;
+        let previous = params.success_adjustment_factor;
+
+        if !is_win {
+            params.success_adjustment_factor =
+                (previous * (1.0 + params.success_feedback_step)).min(params.success_feedback_max_factor);
+        } else if params.success_feedback_win_streak > 0
+            && self.stats.win_streak.is_multiple_of(params.success_feedback_win_streak)
+        {
+            params.success_adjustment_factor =
+                (previous * (1.0 - params.success_feedback_step)).max(params.success_feedback_min_factor);
+        }
+
+        let factor = params.success_adjustment_factor;
+        if (factor - previous).abs() > f64::EPSILON {
+            info!("🔧 成功率反馈: 买入阈值系数 {:.3} -> {:.3} ({})",
+                previous, factor, if factor > previous { "收紧" } else { "放宽" });
+            self.config.buy_triggers = self.adjusted_buy_triggers();
+        }
+    }
+
+    /// 按成功率系数缩放原始买入条件（下限类阈值乘以系数，上限类阈值除以系数），
+    /// 综合评分要求再叠加时段系数
+    fn adjusted_buy_triggers(&self) -> BuyTriggers {
+        let factor = self.config.adaptive_params.success_adjustment_factor;
+        let time_factor = self.config.adaptive_params.time_adjustment_factor;
+        let base = &self.base_buy_triggers;
+
+        BuyTriggers {
+            min_buy_ratio: (base.min_buy_ratio * factor).min(0.99),
+            min_net_inflow_sol: base.min_net_inflow_sol * factor,
+            min_acceleration: base.min_acceleration * factor,
+            max_slippage: base.max_slippage / factor,
+            min_high_frequency_trades: (base.min_high_frequency_trades as f64 * factor).round() as u32,
+            min_liquidity_depth: (base.min_liquidity_depth * factor).min(1.0),
+            max_price_impact: base.max_price_impact / factor,
+            min_composite_score: (base.min_composite_score * factor * time_factor).min(1.0),
+        }
+    }
+
+    /// 采集新币上线初期的波动率（所有新币都采集，不限于通过买入评估的）
+    pub fn observe_launch(&self, metrics: &WindowMetrics, advanced_metrics: &AdvancedMetrics) {
+        let params = &self.config.adaptive_params;
+        if !params.enable_volatility_adaptation || metrics.observed_secs > params.regime_window_secs {
+            return;
+        }
+        self.launch_volatility.lock().observe(metrics.mint, advanced_metrics.volatility, params.regime_sample_mints);
+    }
+
+    /// 按新币平均波动率判定的市场波动状态（样本不足时为 None）
+    fn target_regime(&self) -> Option<(VolatilityRegime, f64)> {
+        let params = &self.config.adaptive_params;
+        let volatility = self.launch_volatility.lock().average(params.regime_min_samples)?;
+
+        let regime = if volatility > params.regime_volatile_volatility {
+            VolatilityRegime::Volatile
+        } else if volatility < params.regime_calm_volatility {
+            VolatilityRegime::Calm
+        } else {
+            VolatilityRegime::Normal
         };
-        
-        self.config.adaptive_params.volatility_adjustment_factor = adjustment;
-        
-        if adjustment != 1.0 {
-            debug!("🔧 波动率调整: {:.2}x (波动率: {:.4})", adjustment, volatility);
+        Some((regime, volatility))
+    }
+
+    /// 根据市场整体波动状态切换买入条件
+    /// 高波动 -> 保守条件；低波动 -> 激进条件；其余 -> 配置模式的条件
+    fn adapt_to_volatility(&mut self) {
+        let Some((regime, volatility)) = self.target_regime() else {
+            return;
+        };
+
+        if regime == self.regime {
+            return;
+        }
+
+        info!("🌡️  市场波动状态切换: {:?} -> {:?} (新币平均波动率: {:.4})", self.regime, regime, volatility);
+        self.regime = regime;
+        self.base_buy_triggers = self.regime_buy_triggers(regime);
+        self.config.buy_triggers = self.adjusted_buy_triggers();
+    }
+
+    /// 某个波动状态对应的原始买入条件
+    fn regime_buy_triggers(&self, regime: VolatilityRegime) -> BuyTriggers {
+        match (regime, &self.config.regime_triggers) {
+            (VolatilityRegime::Normal, _) => self.configured_buy_triggers.clone(),
+            (VolatilityRegime::Volatile, Some(triggers)) => triggers.conservative.clone(),
+            (VolatilityRegime::Calm, Some(triggers)) => triggers.aggressive.clone(),
+            (VolatilityRegime::Volatile, None) => DynamicStrategyConfig::conservative().buy_triggers,
+            (VolatilityRegime::Calm, None) => DynamicStrategyConfig::aggressive().buy_triggers,
         }
     }
 
-    /// 根据时间段调整
+    /// 当前时段对应的综合评分系数（差时段提高要求）
+    fn target_time_factor(&self) -> f64 {
+        if self.is_bad_time(Utc::now()) {
+            self.config.adaptive_params.bad_hour_score_multiplier
+        } else {
+            1.0
+        }
+    }
+
+    /// 根据时间段调整：当前小时或星期的历史胜率过低时提高综合评分要求
     fn adapt_to_time(&mut self) {
-        let hour = Utc::now().hour();
-        
-        // UTC 时间，需要根据实际市场活跃时间调整
-        // 假设 12:00-20:00 UTC 是活跃时段
-        let is_active_hours = hour >= 12 && hour <= 20;
-        
-        if !is_active_hours {
-            // 非活跃时段，更保守
-            debug!("🔧 非活跃时段，采用保守策略");
+        let factor = self.target_time_factor();
+
+        let previous = self.config.adaptive_params.time_adjustment_factor;
+        if (factor - previous).abs() > f64::EPSILON {
+            self.config.adaptive_params.time_adjustment_factor = factor;
+            self.config.buy_triggers = self.adjusted_buy_triggers();
+            if factor > 1.0 {
+                info!("🕐 当前时段历史胜率偏低，综合评分要求 x{:.2}, 仓位 x{:.2}",
+                    factor, self.config.adaptive_params.bad_hour_size_multiplier);
+            } else {
+                info!("🕐 离开差时段，恢复综合评分要求");
+            }
         }
     }
 
-    /// 计算综合评分
-    fn calculate_composite_score(&self, metrics: &WindowMetrics, advanced: &AdvancedMetrics) -> f64 {
+    /// 某一时刻是否处于历史表现差的时段（小时桶或星期桶样本足够且胜率低于阈值）
+    fn is_bad_time(&self, time: DateTime<Utc>) -> bool {
+        let params = &self.config.adaptive_params;
+        let (hourly, weekday) = self.time_stats.buckets_at(time);
+
+        [hourly, weekday].iter().any(|bucket| {
+            bucket.trades() >= params.time_bucket_min_trades
+                && bucket.win_rate() < params.bad_hour_win_rate
+        })
+    }
+
+    /// 当前时段的仓位乘数（差时段缩小仓位）
+    pub fn position_size_multiplier(&self) -> f64 {
+        if self.config.adaptive_params.enable_time_adaptation && self.is_bad_time(Utc::now()) {
+            self.config.adaptive_params.bad_hour_size_multiplier
+        } else {
+            1.0
+        }
+    }
+
+    /// 计算综合评分（0-1，EV 估算用作胜率依据）
+    pub fn calculate_composite_score(&self, metrics: &WindowMetrics, advanced: &AdvancedMetrics) -> f64 {
+        self.score_breakdown(metrics, advanced).composite
+    }
+
+    /// 综合评分的各分项（confidence 由 evaluate_buy 填写）
+    pub fn score_breakdown(&self, metrics: &WindowMetrics, advanced: &AdvancedMetrics) -> ScoreBreakdown {
         let buy_ratio_score = metrics.buy_ratio;
         let net_inflow_score = (metrics.net_inflow_sol as f64 / 1_000_000_000.0 / 2.0).min(1.0);
         let acceleration_score = (metrics.acceleration / 2.0).min(1.0);
         let liquidity_score = advanced.liquidity_depth;
         let frequency_score = (advanced.high_frequency_trades as f64 / 10.0).min(1.0);
-        
-        buy_ratio_score * 0.25 +
-        net_inflow_score * 0.25 +
-        acceleration_score * 0.20 +
-        liquidity_score * 0.15 +
-        frequency_score * 0.15
+
+        let composite = buy_ratio_score * 0.25 +
+            net_inflow_score * 0.25 +
+            acceleration_score * 0.20 +
+            liquidity_score * 0.15 +
+            frequency_score * 0.15;
+
+        ScoreBreakdown {
+            buy_pressure: buy_ratio_score,
+            net_inflow: net_inflow_score,
+            acceleration: acceleration_score,
+            liquidity_depth: liquidity_score,
+            frequency: frequency_score,
+            price_impact: advanced.avg_price_impact,
+            composite,
+            confidence: 0.0,
+        }
     }
 
     /// 获取卖出触发条件（供外部使用）
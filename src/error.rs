@@ -0,0 +1,169 @@
+/// 机器人错误类型
+///
+/// 执行器和持仓管理的公共接口返回 BotError，调用方按错误类型决定重试 / 放弃 / 调整，
+/// 不再匹配错误字符串
+///
+/// 核心功能:
+/// 1. 错误分类 - RPC / SWQOS / 余额不足 / 滑点 / 曲线已完成 / 危险 token / blockhash 失效 / 链上失败 / 确认超时
+/// 2. 链上错误解析 - PumpFun 自定义错误码映射到滑点超限、曲线已完成
+/// 3. anyhow 互转 - 内部仍可使用 anyhow，`?` 转换时保留已有的 BotError 类型
+/// 4. 发送错误分类 - SendErrorKind（AlreadyProcessed → 已送达，BlockhashNotFound → 重建），
+///    执行器和 SWQOS 传输层共用
+
+use solana_client::client_error::ClientError;
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::TransactionError};
+use thiserror::Error;
+
+/// PumpFun 自定义错误码
+pub(crate) const PUMPFUN_TOO_MUCH_SOL_REQUIRED: u32 = 6002;
+pub(crate) const PUMPFUN_TOO_LITTLE_SOL_RECEIVED: u32 = 6003;
+pub(crate) const PUMPFUN_BONDING_CURVE_COMPLETE: u32 = 6005;
+
+pub type BotResult<T> = std::result::Result<T, BotError>;
+
+#[derive(Debug, Error)]
+pub enum BotError {
+    /// RPC 调用失败
+    #[error("RPC error: {0}")]
+    Rpc(#[from] ClientError),
+
+    /// 所有 SWQOS 中继发送失败
+    #[error("SWQOS error: {0}")]
+    Swqos(String),
+
+    /// 钱包余额不足（含 tip）
+    #[error("insufficient balance: required {required} lamports, available {available} lamports")]
+    InsufficientBalance { required: u64, available: u64 },
+
+    /// 超出滑点（PumpFun TooMuchSolRequired / TooLittleSolReceived）
+    #[error("slippage exceeded")]
+    SlippageExceeded,
+
+    /// bonding curve 已完成（已毕业，无法在曲线上交易）
+    #[error("bonding curve complete")]
+    CurveComplete,
+
+    /// token 不安全（freeze / mint authority 未撤销，或带有转账 hook / 永久委托 / 高转账手续费等 Token-2022 扩展）
+    #[error("dangerous token: {reason}")]
+    DangerousToken { reason: String, creator: Pubkey },
+
+    /// blockhash 已失效，需要重建交易
+    #[error("blockhash expired")]
+    BlockhashExpired,
+
+    /// 交易上链但执行失败
+    #[error("transaction failed: {0}")]
+    TransactionFailed(String),
+
+    /// 交易确认超时（交易仍可能上链）
+    #[error("confirmation timed out after {secs}s: {signature}")]
+    ConfirmationTimeout { signature: Signature, secs: u64 },
+
+    /// 固定成本（最后一次重试的 tip + 优先费 + 网络费）占买入金额比例超过 max_fixed_cost_fraction
+    #[error("fixed costs too high: {fixed_lamports} lamports for a {position_lamports} lamport position")]
+    FixedCostsTooHigh { fixed_lamports: u64, position_lamports: u64 },
+
+    /// 超出支出限额，拒绝签名（见 spend_guard.rs）
+    #[error("spend limit exceeded: {reason}")]
+    SpendLimitExceeded { reason: String },
+
+    /// 其他错误
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl BotError {
+    /// 解析链上交易错误（TransactionError 的 Debug 文本），识别 PumpFun 自定义错误码
+    pub fn from_transaction_failure(err: &str) -> Self {
+        match Self::pumpfun_error_code(err) {
+            Some(PUMPFUN_TOO_MUCH_SOL_REQUIRED) | Some(PUMPFUN_TOO_LITTLE_SOL_RECEIVED) => BotError::SlippageExceeded,
+            Some(PUMPFUN_BONDING_CURVE_COMPLETE) => BotError::CurveComplete,
+            _ => BotError::TransactionFailed(err.to_string()),
+        }
+    }
+
+    /// 从 "Custom(6003)" 中提取错误码
+    fn pumpfun_error_code(err: &str) -> Option<u32> {
+        let start = err.find("Custom(")? + "Custom(".len();
+        let end = err[start..].find(')')? + start;
+        err[start..end].parse().ok()
+    }
+}
+
+impl From<anyhow::Error> for BotError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<BotError>() {
+            Ok(bot_error) => return bot_error,
+            Err(err) => err,
+        };
+
+        match classify_send_error(&err) {
+            SendErrorKind::BlockhashNotFound => BotError::BlockhashExpired,
+            _ => match err.downcast::<ClientError>() {
+                Ok(client_error) => BotError::Rpc(client_error),
+                Err(err) => BotError::Other(err),
+            },
+        }
+    }
+}
+
+/// 发送错误分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendErrorKind {
+    /// 交易已被处理（同一签名重复发送）→ 交易已送达，但本次发送是重复的
+    AlreadyProcessed,
+    /// blockhash 未找到/已过期 → 需要用新 blockhash 重建交易
+    BlockhashNotFound,
+    /// 其他错误
+    Other,
+}
+
+/// 中继返回 AlreadyProcessed 的错误文本（RPC TransactionError 的 Display / Debug，及各中继的措辞）
+const ALREADY_PROCESSED_MESSAGES: &[&str] = &[
+    "this transaction has already been processed",
+    "alreadyprocessed",
+    "transaction already processed",
+];
+
+/// 中继返回 blockhash 失效的错误文本
+const BLOCKHASH_NOT_FOUND_MESSAGES: &[&str] = &[
+    "blockhash not found",
+    "blockhashnotfound",
+    "block height exceeded",
+];
+
+/// 对发送错误分类
+///
+/// 优先使用结构化错误（BotError / ClientError / TransactionError），
+/// 中继（SWQOS）返回的错误只有文本，才按已知错误文本匹配
+pub fn classify_send_error(err: &anyhow::Error) -> SendErrorKind {
+    for cause in err.chain() {
+        if let Some(BotError::BlockhashExpired) = cause.downcast_ref::<BotError>() {
+            return SendErrorKind::BlockhashNotFound;
+        }
+        let transaction_error = cause
+            .downcast_ref::<ClientError>()
+            .and_then(ClientError::get_transaction_error)
+            .or_else(|| cause.downcast_ref::<TransactionError>().cloned());
+        match transaction_error {
+            Some(TransactionError::AlreadyProcessed) => return SendErrorKind::AlreadyProcessed,
+            Some(TransactionError::BlockhashNotFound) => return SendErrorKind::BlockhashNotFound,
+            Some(_) => return SendErrorKind::Other,
+            None => {}
+        }
+    }
+
+    classify_error_message(&format!("{:#}", err))
+}
+
+/// 按错误文本分类（中继只返回文本时使用）
+pub fn classify_error_message(message: &str) -> SendErrorKind {
+    let message = message.to_lowercase();
+    if ALREADY_PROCESSED_MESSAGES.iter().any(|known| message.contains(known)) {
+        SendErrorKind::AlreadyProcessed
+    } else if BLOCKHASH_NOT_FOUND_MESSAGES.iter().any(|known| message.contains(known)) {
+        SendErrorKind::BlockhashNotFound
+    } else {
+        SendErrorKind::Other
+    }
+}
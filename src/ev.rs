@@ -0,0 +1,127 @@
+/// 期望收益（EV）估算
+///
+/// 触发条件通过后再算一笔账：胜率 × 止盈收益 - 败率 × 止损亏损 - 交易成本，
+/// EV 为负的买入即使触发条件全部满足也不执行
+///
+/// 核心功能:
+/// 1. 胜率估算 - 综合评分线性映射为胜率（截距 / 斜率可配置，依据交易日志校准）
+/// 2. 滑点估算 - 按恒定乘积曲线计算买入和卖出的价格冲击
+/// 3. 成本 - PumpFun 买卖手续费 + tip + 买卖交易费
+/// 4. EV 门控 - enable_ev_gate 时拒绝 EV 为负的买入（无综合评分时胜率未校准，不做门控）
+
+use serde::Serialize;
+
+use crate::config::Config;
+
+/// 单笔买入的 EV 估算结果
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct EvEstimate {
+    /// 综合评分（无高级指标时为 None，胜率使用截距，仅供日志参考，不参与门控）
+    pub composite_score: Option<f64>,
+    pub win_probability: f64,
+    pub entry_slippage_percent: f64,
+    pub exit_slippage_percent: f64,
+    /// 止盈收益（lamports，未扣成本）
+    pub upside_lamports: f64,
+    /// 止损亏损（lamports，未扣成本）
+    pub downside_lamports: f64,
+    /// 滑点 + 手续费 + tip + 交易费（lamports）
+    pub cost_lamports: f64,
+    pub expected_value_lamports: f64,
+}
+
+impl EvEstimate {
+    pub fn is_negative(&self) -> bool {
+        self.expected_value_lamports < 0.0
+    }
+
+    /// 是否应被 EV 门控拒绝
+    ///
+    /// 胜率按综合评分校准；没有综合评分时截距只是先验，据此拒绝买入会把
+    /// 所有无高级指标的信号一并拦下，因此只对有评分的估算做门控
+    pub fn should_reject(&self) -> bool {
+        self.composite_score.is_some() && self.is_negative()
+    }
+
+    pub fn expected_value_sol(&self) -> f64 {
+        self.expected_value_lamports / 1_000_000_000.0
+    }
+}
+
+/// EV 计算的输入
+#[derive(Debug, Clone, Copy)]
+pub struct EvInputs {
+    pub composite_score: Option<f64>,
+    pub sol_amount: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub take_profit_multiplier: f64,
+    pub stop_loss_multiplier: f64,
+    pub tips_lamports: u64,
+}
+
+/// EV 计算器
+pub struct EvCalculator {
+    win_prob_intercept: f64,
+    win_prob_slope: f64,
+    pumpfun_fee_bps: u64,
+    tx_fee_lamports: u64,
+}
+
+impl EvCalculator {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            win_prob_intercept: config.ev_win_prob_intercept,
+            win_prob_slope: config.ev_win_prob_slope,
+            pumpfun_fee_bps: config.pumpfun_fee_bps,
+            tx_fee_lamports: config.estimated_tx_fee_lamports(),
+        }
+    }
+
+    /// 综合评分映射为胜率（无评分时使用截距）
+    pub fn win_probability(&self, composite_score: Option<f64>) -> f64 {
+        let score = composite_score.unwrap_or(0.0);
+        (self.win_prob_intercept + self.win_prob_slope * score).clamp(0.0, 1.0)
+    }
+
+    pub fn estimate(&self, inputs: &EvInputs) -> EvEstimate {
+        let amount = inputs.sol_amount as f64;
+        let vsol = inputs.virtual_sol_reserves as f64;
+        let vtok = inputs.virtual_token_reserves as f64;
+
+        // 买入冲击: 均价 (vsol + sol) / vtok 相对现价 vsol / vtok
+        let (entry_slippage, exit_slippage) = if vsol > 0.0 && vtok > 0.0 {
+            let entry = amount / vsol;
+            // 卖出同样数量的 token（买入后的曲线上）: 冲击 = t / (vtok' + t) = t / vtok
+            let tokens_out = vtok * amount / (vsol + amount);
+            let exit = tokens_out / vtok;
+            (entry, exit)
+        } else {
+            (1.0, 1.0)
+        };
+
+        let fee = self.pumpfun_fee_bps as f64 / 10_000.0;
+        let cost_lamports = amount * (entry_slippage + exit_slippage + 2.0 * fee)
+            + inputs.tips_lamports as f64
+            + 2.0 * self.tx_fee_lamports as f64;
+
+        let upside_lamports = amount * (inputs.take_profit_multiplier - 1.0).max(0.0);
+        let downside_lamports = amount * (1.0 - inputs.stop_loss_multiplier).clamp(0.0, 1.0);
+
+        let win_probability = self.win_probability(inputs.composite_score);
+        let expected_value_lamports = win_probability * upside_lamports
+            - (1.0 - win_probability) * downside_lamports
+            - cost_lamports;
+
+        EvEstimate {
+            composite_score: inputs.composite_score,
+            win_probability,
+            entry_slippage_percent: entry_slippage * 100.0,
+            exit_slippage_percent: exit_slippage * 100.0,
+            upside_lamports,
+            downside_lamports,
+            cost_lamports,
+            expected_value_lamports,
+        }
+    }
+}
@@ -0,0 +1,279 @@
+/// WebSocket 事件流
+///
+/// 把机器人看到的市场实时推送给外部工具（看板、替代策略、分析），每条消息一个 JSON 文本帧
+///
+/// 核心功能:
+/// 1. 事件 - gRPC 解析出的 SniperEvent（trade / create / migrate）
+/// 2. 指标 - 聚合器输出的 WindowMetrics（metrics）
+/// 3. 信号 - 策略引擎发出的买入 / 卖出 / 持有信号（signal）
+/// 4. 成交 - 开仓 / 平仓记录（fill，与交易日志的 entry / exit 记录一致）
+/// 5. 告警 - 安全模块触发的告警（alert，例如支出限额拒绝签名）
+/// 6. 广播 - 每条消息只序列化一次；没有连接的客户端时不序列化，不影响热路径
+/// 7. 慢客户端 - 跟不上的客户端丢弃积压消息并收到 lagged 通知，不阻塞发布方
+/// 8. Bearer token 认证（配置 event_feed_token 时启用，常数时间比较；未配置时只能绑定回环地址）
+///
+/// 控制面 gRPC 服务（control_grpc.rs）通过 subscribe 订阅同一个广播
+
+use anyhow::{Context, Result};
+use futures::{SinkExt, StreamExt};
+use log::{debug, info, warn};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::config::Config;
+use crate::control::token_matches;
+use crate::journal::JournalRecord;
+use crate::types::{SniperEvent, StrategySignal, WindowMetrics};
+
+/// 一条已序列化的事件流消息
+#[derive(Debug)]
+pub struct FeedFrame {
+    /// 消息类型（trade / create / migrate / metrics / signal / fill / alert）
+    pub kind: &'static str,
+    /// JSON 消息体（含 type 字段）
+    pub json: String,
+}
+
+/// WebSocket 事件流服务
+pub struct EventFeed {
+    bind: String,
+    token: Option<String>,
+    tx: broadcast::Sender<Arc<FeedFrame>>,
+}
+
+impl EventFeed {
+    pub fn from_config(config: &Config) -> Self {
+        let (tx, _) = broadcast::channel(config.event_feed_buffer.max(1));
+        Self {
+            bind: config.event_feed_bind.clone(),
+            token: config.event_feed_token.clone(),
+            tx,
+        }
+    }
+
+    /// 订阅事件流（WebSocket 客户端 / gRPC StreamEvents）
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<FeedFrame>> {
+        self.tx.subscribe()
+    }
+
+    /// 是否有客户端在订阅（没有时发布方直接跳过序列化）
+    fn has_subscribers(&self) -> bool {
+        self.tx.receiver_count() > 0
+    }
+
+    fn send(&self, kind: &'static str, message: serde_json::Value) {
+        // 订阅者恰好全部断开时 send 返回错误，忽略即可
+        let _ = self.tx.send(Arc::new(FeedFrame {
+            kind,
+            json: message.to_string(),
+        }));
+    }
+
+    /// 发布 gRPC 解析出的事件
+    pub fn publish_event(&self, event: &SniperEvent) {
+        if !self.has_subscribers() {
+            return;
+        }
+
+        let (kind, message) = match event {
+            SniperEvent::Trade(trade) => ("trade", json!({
+                "type": "trade",
+                "mint": trade.mint.to_string(),
+                "user": trade.user.to_string(),
+                "is_buy": trade.is_buy,
+                "sol_amount": trade.sol_amount,
+                "token_amount": trade.token_amount,
+                "virtual_sol_reserves": trade.virtual_sol_reserves,
+                "virtual_token_reserves": trade.virtual_token_reserves,
+                "real_sol_reserves": trade.real_sol_reserves,
+                "slot": trade.slot,
+                "timestamp": trade.timestamp,
+                "signature": trade.signature,
+            })),
+            SniperEvent::CreateToken(create) => ("create", json!({
+                "type": "create",
+                "mint": create.mint.to_string(),
+                "creator": create.creator.to_string(),
+                "name": create.name,
+                "symbol": create.symbol,
+                "uri": create.uri,
+                "timestamp": create.timestamp,
+                "signature": create.signature,
+            })),
+            SniperEvent::Migrate(migrate) => ("migrate", json!({
+                "type": "migrate",
+                "mint": migrate.mint.to_string(),
+                "pool": migrate.pool.to_string(),
+                "sol_amount": migrate.sol_amount,
+                "mint_amount": migrate.mint_amount,
+                "timestamp": migrate.timestamp,
+                "signature": migrate.signature,
+            })),
+        };
+        self.send(kind, message);
+    }
+
+    /// 发布窗口指标
+    pub fn publish_metrics(&self, metrics: &WindowMetrics) {
+        if !self.has_subscribers() {
+            return;
+        }
+
+        self.send("metrics", json!({
+            "type": "metrics",
+            "mint": metrics.mint.to_string(),
+            "creator": metrics.creator.to_string(),
+            "net_inflow_sol": metrics.net_inflow_sol as f64 / 1_000_000_000.0,
+            "buy_ratio": metrics.buy_ratio,
+            "acceleration": metrics.acceleration,
+            "normalized_acceleration": metrics.normalized_acceleration,
+            "inflow_rate_sol_per_sec": metrics.inflow_rate_sol_per_sec,
+            "trade_rate_per_sec": metrics.trade_rate_per_sec,
+            "event_count": metrics.event_count,
+            "total_event_count": metrics.total_event_count,
+            "unique_buyers": metrics.unique_buyers,
+            "repeat_buyer_ratio": metrics.repeat_buyer_ratio,
+            "curve_progress_percent": metrics.curve_progress_percent,
+            "observed_secs": metrics.observed_secs,
+            "token_age_secs": metrics.token_age_secs,
+            "latest_virtual_sol_reserves": metrics.latest_virtual_sol_reserves,
+            "latest_virtual_token_reserves": metrics.latest_virtual_token_reserves,
+        }));
+    }
+
+    /// 发布策略信号
+    pub fn publish_signal(&self, metrics: &WindowMetrics, signal: &StrategySignal) {
+        if !self.has_subscribers() {
+            return;
+        }
+
+        let signal = match signal {
+            StrategySignal::Buy => "buy",
+            StrategySignal::Sell => "sell",
+            StrategySignal::Hold => "hold",
+            StrategySignal::None => "none",
+        };
+        self.send("signal", json!({
+            "type": "signal",
+            "mint": metrics.mint.to_string(),
+            "signal": signal,
+            "score_breakdown": metrics.score_breakdown,
+        }));
+    }
+
+    /// 发布成交（开仓 / 平仓记录）
+    pub fn publish_fill(&self, record: &JournalRecord) {
+        if !self.has_subscribers() {
+            return;
+        }
+
+        self.send("fill", json!({
+            "type": "fill",
+            "fill": serde_json::to_value(record).unwrap_or(serde_json::Value::Null),
+        }));
+    }
+
+    /// 发布告警
+    pub fn publish_alert(&self, kind: &str, mint: &str, reason: &str) {
+        if !self.has_subscribers() {
+            return;
+        }
+
+        self.send("alert", json!({
+            "type": "alert",
+            "kind": kind,
+            "mint": mint,
+            "reason": reason,
+        }));
+    }
+
+    /// 监听 event_feed_bind 并为每个 WebSocket 客户端推送消息
+    pub async fn run(self: Arc<Self>) -> Result<()> {
+        let listener = TcpListener::bind(&self.bind)
+            .await
+            .with_context(|| format!("事件流监听失败: {}", self.bind))?;
+        info!("📣 WebSocket 事件流已启动: ws://{}", self.bind);
+
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("⚠️  事件流接受连接失败: {}", e);
+                    continue;
+                }
+            };
+
+            let feed = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = feed.handle_connection(stream).await {
+                    debug!("事件流连接结束 ({}): {}", peer, e);
+                }
+            });
+        }
+    }
+
+    async fn handle_connection(&self, stream: TcpStream) -> Result<()> {
+        let token = self.token.clone();
+        // 错误类型由 tungstenite 的握手回调签名决定
+        #[allow(clippy::result_large_err)]
+        let authorize = move |request: &Request, response: Response| -> std::result::Result<Response, ErrorResponse> {
+            let authorization = request
+                .headers()
+                .get("authorization")
+                .and_then(|value| value.to_str().ok());
+            if is_authorized(token.as_deref(), authorization) {
+                Ok(response)
+            } else {
+                let mut error = ErrorResponse::new(Some("unauthorized".to_string()));
+                *error.status_mut() = StatusCode::UNAUTHORIZED;
+                Err(error)
+            }
+        };
+
+        let mut ws = tokio_tungstenite::accept_hdr_async(stream, authorize)
+            .await
+            .context("WebSocket 握手失败")?;
+        let mut rx = self.subscribe();
+        info!("📣 事件流客户端已连接 (当前 {} 个)", self.tx.receiver_count());
+
+        loop {
+            tokio::select! {
+                message = rx.recv() => match message {
+                    Ok(frame) => ws.send(Message::Text(frame.json.clone())).await?,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("⚠️  事件流客户端跟不上，丢弃 {} 条消息", skipped);
+                        let notice = json!({ "type": "lagged", "skipped": skipped });
+                        ws.send(Message::Text(notice.to_string())).await?;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                incoming = ws.next() => match incoming {
+                    // 客户端只需要接收；Ping 由 tungstenite 自动回复
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                },
+            }
+        }
+
+        info!("📣 事件流客户端已断开");
+        Ok(())
+    }
+}
+
+/// 校验 Authorization 头（未配置 token 时不校验）
+fn is_authorized(token: Option<&str>, authorization: Option<&str>) -> bool {
+    match token {
+        Some(token) if !token.is_empty() => {
+            authorization
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .is_some_and(|provided| token_matches(token, provided))
+        }
+        _ => true,
+    }
+}
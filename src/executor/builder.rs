@@ -1,4 +1,5 @@
 /// 交易构建器
+#[derive(Default)]
 pub struct TransactionBuilder;
 
 impl TransactionBuilder {
@@ -80,4 +81,47 @@ impl TransactionBuilder {
         let result = n.saturating_sub(a);
         result.min(u64::MAX as u128) as u64
     }
+
+    /// 将卖出数量拆分为多笔，使每笔的价格冲击不超过 max_price_impact_percent
+    ///
+    /// 恒定乘积曲线上卖出 t 个 token 的价格冲击 = t / (virtual_token_reserves + t)，
+    /// 每笔按模拟后的储备计算下一笔上限；最多 max_chunks 笔，最后一笔包含剩余全部数量
+    pub fn split_sell_amount(
+        &self,
+        virtual_token_reserves: u64,
+        virtual_sol_reserves: u64,
+        token_amount: u64,
+        max_price_impact_percent: f64,
+        max_chunks: usize,
+    ) -> Vec<u64> {
+        if max_price_impact_percent <= 0.0 || virtual_token_reserves == 0 || max_chunks <= 1 {
+            return vec![token_amount];
+        }
+
+        let impact = (max_price_impact_percent / 100.0).min(0.99);
+        let mut token_reserves = virtual_token_reserves as u128;
+        let mut sol_reserves = virtual_sol_reserves as u128;
+        let mut remaining = token_amount;
+        let mut chunks = Vec::new();
+
+        while remaining > 0 {
+            if chunks.len() + 1 >= max_chunks {
+                chunks.push(remaining);
+                break;
+            }
+
+            let max_chunk = ((token_reserves as f64 * impact / (1.0 - impact)) as u64).max(1);
+            let chunk = remaining.min(max_chunk);
+
+            // 模拟本笔卖出后的储备
+            let sol_out = (chunk as u128 * sol_reserves) / (token_reserves + chunk as u128);
+            token_reserves += chunk as u128;
+            sol_reserves = sol_reserves.saturating_sub(sol_out);
+
+            chunks.push(chunk);
+            remaining -= chunk;
+        }
+
+        chunks
+    }
 }
@@ -6,117 +6,236 @@
 /// 核心功能:
 /// 1. LightSpeed RPC 端点连接
 /// 2. LightSpeed tip 机制 (TIPS_VIBE_STATION + TIPS_VIBE_FEE)
-/// 3. ComputeBudget 优先级设置
-/// 4. PumpFun 买入指令构建
+/// 3. ComputeBudget 优先级设置（发送通道按 send_backend_order：SWQOS / TPU 直发 / LightSpeed）
+/// 4. 买入指令构建（通过 DexAdapter，当前为 PumpFun）
 /// 5. 交易重试机制 (sendTxWithRetries)
 /// 6. 交易状态监控 (monitorTransactionStatus)
 /// 7. 余额检查 (checkBalanceForOperations)
 
 use anyhow::{Context, Result};
 use log::{debug, info, warn, error};
-use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
 use solana_compute_budget_interface::ComputeBudgetInstruction;
 use solana_sdk::{
+    hash::Hash,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
-    signature::{Keypair, Signature},
+    signature::Signature,
     signer::Signer,
-    transaction::Transaction,
+    transaction::TransactionError,
     message::{VersionedMessage, v0},
     transaction::VersionedTransaction,
 };
 use solana_system_interface::instruction::transfer;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
+use crate::chaos::ChaosInjector;
 use crate::config::Config;
+use crate::confirmation::{ConfirmationStatus, ConfirmationTracker};
+use crate::dex::{DexAdapter, PoolAccounts, PoolState, SwapAccounts};
+use crate::error::{classify_send_error, BotError, BotResult, SendErrorKind};
+use crate::executor::outbox::{OutboxPurpose, TransactionOutbox};
+use crate::executor::retry::RetryPolicy;
+use crate::executor::tpu_sender::TpuSender;
+use crate::rpc_api::RpcApi;
+use crate::rpc_pool::RpcPool;
 use crate::swqos::{SwqosConfig, MultiSwqosManager};
+use crate::token_extensions::MintExtensions;
+use crate::mint_safety::{AuthorityPolicy, MintAuthorities};
+use crate::signer::SharedSigner;
+use crate::spend_guard::{system_outflow, SpendGuard};
+use crate::types::ReserveSnapshot;
 
-// PumpFun 程序常量
-#[allow(dead_code)]
-const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
-#[allow(dead_code)]
-const PUMPFUN_GLOBAL: &str = "4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf";
-#[allow(dead_code)]
-// 🔥 修复: FEE_RECIPIENT 应该是 62qc2CNXwrYqQScmEdiZFFAnJR262PxWEuNQtxfafNgV
-// 参考: sol-trade-sdk/src/instruction/utils/pumpfun.rs:54
-const PUMPFUN_FEE_RECIPIENT: &str = "62qc2CNXwrYqQScmEdiZFFAnJR262PxWEuNQtxfafNgV";
-#[allow(dead_code)]
-const PUMPFUN_EVENT_AUTHORITY: &str = "Ce6TQqeHC9p8KetsN6JsjHK7UTZk7nasjjnr7XxXp9F1";
-#[allow(dead_code)]
+// Token program 常量（PumpFun 协议常量见 dex::pumpfun）
 const SYSTEM_TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";  // 🔥 新增: Token-2022
-#[allow(dead_code)]
 const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
-// 🔥 修复: 对齐 sol-trade-sdk 的常量值
-// 参考: sol-trade-sdk/src/instruction/utils/pumpfun.rs:106-111
-const GLOBAL_VOLUME_ACCUMULATOR: &str = "Hq2wp8uJ9jCPsYgNHex8RtqdvMPfVGoYwjvF1ATiwn2Y";
-const FEE_CONFIG: &str = "8Wf5TiAheLUqBrKXeYg2JtAFFMWtKdG2BSFgqUcPVwTt";
-const FEE_PROGRAM: &str = "pfeeUxB6jkeY1Hxd7CsFCAjcbHA9rWtchMGdZ6VojVZ";
+// 模拟交易的计算单元上限（买入 + 卖出）
+const SIMULATION_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// 买入结果
+#[derive(Debug, Clone, Copy)]
+pub struct BuyReceipt {
+    pub signature: Signature,
+    /// 本笔交易附带的 tip 总额（LightSpeed + SWQOS，lamports）
+    pub tips_lamports: u64,
+    /// 池子账户（associated bonding curve 按 mint 的 token program 派生）
+    pub pool: PoolAccounts,
+    /// token 创建者（从 bonding curve 账户读取）
+    pub creator: Pubkey,
+    /// mint 的 Token-2022 扩展（到账数量需扣除转账手续费）
+    pub mint_extensions: MintExtensions,
+}
 
-// Buy 指令鉴别器 (discriminator)
-#[allow(dead_code)]
-const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+/// 买入所需的链上账户（一次 getMultipleAccounts 读取 mint 和 bonding curve）
+struct BuyAccounts {
+    token_program: Pubkey,
+    authorities: MintAuthorities,
+    extensions: MintExtensions,
+    pool: PoolState,
+}
 
-/// LightSpeed 买入执行器（集成 SWQOS）
+/// 累计 tip 支出（仅统计已确认的交易）
 ///
-/// 负责执行所有买入操作，支持：
-/// - LightSpeed 优先级 RPC
-/// - SWQOS 多服务商并行发送（田忌赛马）
-/// - 自动 fallback 机制
-#[allow(dead_code)]
-pub struct LightSpeedBuyExecutor {
-    config: Arc<Config>,
-    /// 普通 RPC 客户端（用于查询）
-    rpc_client: Arc<RpcClient>,
-    /// LightSpeed RPC 客户端（用于发送交易，仅当启用时创建）
-    lightspeed_rpc: Option<Arc<RpcClient>>,
-    /// 支付账户
-    pub payer: Arc<Keypair>,
-    /// PumpFun 程序地址
-    pumpfun_program: Pubkey,
-    /// PumpFun 全局账户
-    global: Pubkey,
-    /// PumpFun 费用接收账户
-    fee_recipient: Pubkey,
-    /// PumpFun 事件权限账户
-    event_authority: Pubkey,
-    /// SWQOS 管理器（可选）
-    swqos_manager: Option<Arc<MultiSwqosManager>>,
+/// 卖出走 RPC 发送、不附带 relay tip，卖出端成本（网络费 + 优先费）单独记在 sell_fee
+#[derive(Debug, Default)]
+pub struct TipLedger {
+    swqos_lamports: AtomicU64,
+    lightspeed_lamports: AtomicU64,
+    tx_count: AtomicU64,
+    sell_fee_lamports: AtomicU64,
+    sell_count: AtomicU64,
 }
 
-#[allow(dead_code)]
-impl LightSpeedBuyExecutor {
-    /// 创建新的 LightSpeed 买入执行器（集成 SWQOS）
-    pub fn new(config: Arc<Config>, payer: Arc<Keypair>) -> Result<Self> {
-        let commitment = config.get_commitment_config();
+impl TipLedger {
+    pub fn record(&self, swqos_lamports: u64, lightspeed_lamports: u64) {
+        self.swqos_lamports.fetch_add(swqos_lamports, Ordering::Relaxed);
+        self.lightspeed_lamports.fetch_add(lightspeed_lamports, Ordering::Relaxed);
+        self.tx_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 记录一笔已结算卖出的交易费
+    pub fn record_sell(&self, fee_lamports: u64) {
+        self.sell_fee_lamports.fetch_add(fee_lamports, Ordering::Relaxed);
+        self.sell_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn swqos_lamports(&self) -> u64 {
+        self.swqos_lamports.load(Ordering::Relaxed)
+    }
+
+    pub fn lightspeed_lamports(&self) -> u64 {
+        self.lightspeed_lamports.load(Ordering::Relaxed)
+    }
+
+    pub fn total_lamports(&self) -> u64 {
+        self.swqos_lamports() + self.lightspeed_lamports()
+    }
+
+    pub fn tx_count(&self) -> u64 {
+        self.tx_count.load(Ordering::Relaxed)
+    }
+
+    pub fn sell_fee_lamports(&self) -> u64 {
+        self.sell_fee_lamports.load(Ordering::Relaxed)
+    }
+
+    pub fn sell_count(&self) -> u64 {
+        self.sell_count.load(Ordering::Relaxed)
+    }
+
+    /// 打印累计 tip 支出
+    pub fn print_summary(&self) {
+        info!("💸 累计 tip 支出: {:.6} SOL ({} 笔交易)",
+            self.total_lamports() as f64 / 1_000_000_000.0, self.tx_count());
+        info!("   SWQOS: {:.6} SOL", self.swqos_lamports() as f64 / 1_000_000_000.0);
+        info!("   LightSpeed: {:.6} SOL", self.lightspeed_lamports() as f64 / 1_000_000_000.0);
+        info!("   卖出交易费: {:.6} SOL ({} 笔卖出)",
+            self.sell_fee_lamports() as f64 / 1_000_000_000.0, self.sell_count());
+    }
+}
+
+/// 本笔交易的 tip 明细（lamports）
+#[derive(Debug, Clone, Copy, Default)]
+struct TipBreakdown {
+    swqos: u64,
+    lightspeed: u64,
+}
+
+/// SPL Token 账户（165 字节）的免租金额；Token-2022 账户略大，按此近似
+const TOKEN_ACCOUNT_RENT_LAMPORTS: u64 = 2_039_280;
+
+/// 每个签名的基础网络费
+const BASE_SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+
+/// 重签前等待上一次尝试的 blockhash 过期的上限（blockhash 约 150 个 slot 后过期）
+const BLOCKHASH_EXPIRY_WAIT: Duration = Duration::from_secs(90);
+
+/// 之前的买入尝试是否还可能上链
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PriorAttempts {
+    /// 其中一次已成功上链
+    Landed(Signature),
+    /// 都不会再上链（最近一次已执行失败或 blockhash 已过期），可以重新签名
+    Settled,
+    /// 等待超时仍无法确定，不能再签名
+    Unknown,
+}
+
+/// 单笔买入的总支出明细（lamports）
+#[derive(Debug, Clone, Copy)]
+struct BuyOutlay {
+    /// 买入金额
+    position: u64,
+    tips: u64,
+    priority_fee: u64,
+    base_fee: u64,
+    /// 新建 ATA 的租金（平仓关闭账户后可回收，只记录不计入固定成本）
+    rent: u64,
+}
+
+impl BuyOutlay {
+    /// 与仓位大小无关、花掉就收不回的固定成本
+    fn fixed(&self) -> u64 {
+        self.tips + self.priority_fee + self.base_fee
+    }
+
+    fn fixed_fraction(&self) -> f64 {
+        if self.position == 0 {
+            return f64::INFINITY;
+        }
+        self.fixed() as f64 / self.position as f64
+    }
+}
+
+/// 从 system transfer 指令中提取 lamports（第3个参数）
+pub(crate) fn transfer_lamports(ix: &Instruction) -> u64 {
+    if ix.data.len() >= 12 {
+        u64::from_le_bytes(ix.data[4..12].try_into().unwrap_or([0u8; 8]))
+    } else {
+        0
+    }
+}
+
+/// 买入执行器的查询 / 发送后端（生产环境按配置创建，测试可替换为模拟实现）
+pub struct BuyBackends {
+    /// 普通 RPC（查询，LightSpeed 未启用时也用于发送）
+    pub rpc: Arc<dyn RpcApi>,
+    /// LightSpeed RPC（未启用时为 None）
+    pub lightspeed_rpc: Option<Arc<dyn RpcApi>>,
+    /// SWQOS 管理器（未启用或初始化失败时为 None）
+    pub swqos: Option<Arc<MultiSwqosManager>>,
+}
+
+impl BuyBackends {
+    pub fn from_config(config: &Config, rpc_pool: &Arc<RpcPool>) -> Self {
+        let commitment = config.get_send_commitment();
+        // 🔥 新增: 故障注入（chaos feature）包装 RPC / SWQOS 客户端
+        let chaos = ChaosInjector::from_config(config);
 
         // 普通 RPC 客户端
-        let rpc_client = Arc::new(RpcClient::new_with_commitment(
-            config.rpc_endpoint.clone(),
-            commitment.clone(),
-        ));
+        let rpc: Arc<dyn RpcApi> = chaos.wrap_rpc(Arc::new(rpc_pool.client(commitment)));
 
         // LightSpeed RPC 客户端（仅当启用时创建）
         let lightspeed_rpc = if config.use_lightspeed {
             info!("✅ LightSpeed 已启用，创建 LightSpeed RPC 客户端");
-            Some(Arc::new(RpcClient::new_with_commitment(
-                config.rpc_lightspeed_endpoint.clone(),
-                commitment.clone(),
-            )))
+            let client: Arc<dyn RpcApi> = Arc::new(Arc::new(RpcPool::single(config, &config.rpc_lightspeed_endpoint)).client(commitment));
+            Some(chaos.wrap_rpc(client))
         } else {
             info!("ℹ️  LightSpeed 已禁用");
             None
         };
 
         // 初始化 SWQOS 管理器（如果启用）
-        let swqos_manager = if config.swqos_enabled {
+        let swqos = if config.swqos_enabled {
             match SwqosConfig::from_env() {
                 Ok(swqos_config) => {
                     match MultiSwqosManager::new(swqos_config) {
                         Ok(manager) => {
                             info!("✅ SWQOS 管理器已初始化");
-                            Some(Arc::new(manager))
+                            Some(Arc::new(manager.with_chaos(chaos)))
                         }
                         Err(e) => {
                             warn!("⚠️  SWQOS 初始化失败: {}, 将只使用 LightSpeed", e);
@@ -134,53 +253,139 @@ impl LightSpeedBuyExecutor {
             None
         };
 
+        Self { rpc, lightspeed_rpc, swqos }
+    }
+}
+
+/// LightSpeed 买入执行器（集成 SWQOS）
+///
+/// 负责执行所有买入操作，支持：
+/// - LightSpeed 优先级 RPC
+/// - SWQOS 多服务商并行发送（田忌赛马）
+/// - 自动 fallback 机制
+pub struct LightSpeedBuyExecutor {
+    config: Arc<Config>,
+    /// 普通 RPC 客户端（用于查询）
+    rpc_client: Arc<dyn RpcApi>,
+    /// LightSpeed RPC 客户端（用于发送交易，仅当启用时创建）
+    lightspeed_rpc: Option<Arc<dyn RpcApi>>,
+    /// 支付账户
+    pub payer: SharedSigner,
+    /// DEX 协议适配器（账户派生 / 报价 / 买卖指令）
+    dex: Arc<dyn DexAdapter>,
+    /// SWQOS 管理器（可选）
+    swqos_manager: Option<Arc<MultiSwqosManager>>,
+    /// 累计 tip 支出
+    tip_ledger: Arc<TipLedger>,
+    /// 交易确认追踪
+    confirmations: Arc<ConfirmationTracker>,
+    /// TPU 直发客户端（首次使用时初始化，初始化失败记为 None 不再重试）
+    tpu_sender: tokio::sync::OnceCell<Option<Arc<TpuSender>>>,
+    /// 交易发件箱（发送前落盘，崩溃后可恢复）
+    outbox: Arc<TransactionOutbox>,
+    /// RPC 池（TPU 客户端的 leader schedule 查询也走池）
+    rpc_pool: Arc<RpcPool>,
+    /// mint 权限检查（按策略模式启用）
+    authority_policy: AuthorityPolicy,
+    /// 支出限额（签名前检查）
+    spend_guard: Arc<SpendGuard>,
+}
+
+impl LightSpeedBuyExecutor {
+    /// 创建新的 LightSpeed 买入执行器（集成 SWQOS）
+    pub fn new(
+        config: Arc<Config>,
+        payer: SharedSigner,
+        confirmations: Arc<ConfirmationTracker>,
+        outbox: Arc<TransactionOutbox>,
+        rpc_pool: Arc<RpcPool>,
+        dex: Arc<dyn DexAdapter>,
+        spend_guard: Arc<SpendGuard>,
+    ) -> Result<Self> {
+        let backends = BuyBackends::from_config(&config, &rpc_pool);
+        Self::with_backends(config, payer, confirmations, outbox, rpc_pool, dex, spend_guard, backends)
+    }
+
+    /// 使用指定的 RPC / SWQOS 后端创建（测试注入模拟实现）
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_backends(
+        config: Arc<Config>,
+        payer: SharedSigner,
+        confirmations: Arc<ConfirmationTracker>,
+        outbox: Arc<TransactionOutbox>,
+        rpc_pool: Arc<RpcPool>,
+        dex: Arc<dyn DexAdapter>,
+        spend_guard: Arc<SpendGuard>,
+        backends: BuyBackends,
+    ) -> Result<Self> {
+        let commitment = config.get_send_commitment();
+        let BuyBackends { rpc: rpc_client, lightspeed_rpc, swqos: swqos_manager } = backends;
+
         info!("🚀 LightSpeed 买入执行器已初始化");
         info!("   RPC 端点: {}", config.rpc_endpoint);
-        info!("   Commitment Level: {}", config.commitment_level);
+        info!("   Commitment Level: {:?}", commitment.commitment);
         if config.use_lightspeed {
             info!("   LightSpeed RPC: {}", config.rpc_lightspeed_endpoint);
         }
         info!("   钱包地址: {}", payer.pubkey());
+        info!("   DEX: {}", dex.name());
         if swqos_manager.is_some() {
             info!("   SWQOS: 已启用（田忌赛马模式）");
         }
+        let authority_policy = AuthorityPolicy::from_config(&config);
+        info!("   权限检查: freeze authority {}, mint authority {}",
+            if authority_policy.reject_freeze_authority { "拒绝" } else { "允许" },
+            if authority_policy.reject_unexpected_mint_authority { "拒绝" } else { "允许" });
 
         Ok(Self {
             config,
             rpc_client,
             lightspeed_rpc,
             payer,
-            pumpfun_program: Pubkey::try_from(PUMPFUN_PROGRAM_ID)
-                .context("Invalid PumpFun program ID")?,
-            global: Pubkey::try_from(PUMPFUN_GLOBAL)
-                .context("Invalid global account")?,
-            fee_recipient: Pubkey::try_from(PUMPFUN_FEE_RECIPIENT)
-                .context("Invalid fee recipient")?,
-            event_authority: Pubkey::try_from(PUMPFUN_EVENT_AUTHORITY)
-                .context("Invalid event authority")?,
+            dex,
             swqos_manager,
+            tip_ledger: Arc::new(TipLedger::default()),
+            confirmations,
+            tpu_sender: tokio::sync::OnceCell::new(),
+            outbox,
+            rpc_pool,
+            authority_policy,
+            spend_guard,
         })
     }
 
+    /// 累计 tip 支出
+    pub fn tip_ledger(&self) -> Arc<TipLedger> {
+        self.tip_ledger.clone()
+    }
+
+    /// SWQOS 管理器（未启用 SWQOS 时为 None）
+    pub fn swqos_manager(&self) -> Option<Arc<MultiSwqosManager>> {
+        self.swqos_manager.clone()
+    }
+
     /// 执行买入操作（集成 SWQOS）
     ///
     /// 流程:
     /// 1. checkBalanceForOperations - 检查余额（包含 tip）
-    /// 2. 🔥 从链上读取最新 bonding_curve 数据（real_token_reserves + virtual_sol_reserves）
+    /// 2. 🔥 一次 getMultipleAccounts 读取 mint（token program）和 bonding curve（creator / 储备），
+    ///    储备优先使用未过期的聚合器快照；mint 权限 / Token-2022 扩展不安全时拒绝买入
+    ///    simulate_sell 时先模拟小额买入 + 卖出，卖不出（蜜罐）时拒绝买入
     /// 3. 构建交易指令（包含 SWQOS tips）
     /// 4. 构建 VersionedTransaction
     /// 5. **优先使用 SWQOS 田忌赛马发送**
     /// 6. SWQOS 失败则 fallback 到 LightSpeed
-    /// 7. monitorTransactionStatus - 监控交易状态
-    ///
-    /// 🔥 修复: 移除 virtual_token_reserves/virtual_sol_reserves 参数，改为从链上读取
+    /// 7. 发送失败时用新 blockhash 重建交易重试（可按倍数提高 CU 价格和 tip）
+    ///    每次发送前交易先写入发件箱（崩溃后重启可恢复）
+    /// 8. monitorTransactionStatus - 监控交易状态
     pub async fn execute_buy(
         &self,
         mint: &Pubkey,
         bonding_curve: &Pubkey,
-        associated_bonding_curve: &Pubkey,
         sol_amount: u64,
-    ) -> Result<Signature> {
+        reserves_hint: Option<ReserveSnapshot>,
+        simulate_sell: bool,
+    ) -> BotResult<BuyReceipt> {
         info!("═══════════════════════════════════════════════════════");
         info!("🎯 开始执行买入交易");
         info!("   Token Mint: {}", mint);
@@ -188,55 +393,183 @@ impl LightSpeedBuyExecutor {
         info!("   购买金额: {} SOL", sol_amount as f64 / 1_000_000_000.0);
         info!("═══════════════════════════════════════════════════════");
 
-        // 🔥 修复: 从链上读取最新 bonding_curve 数据（获取 real_token_reserves + virtual_token_reserves）
-        //
-        // 📝 设计说明：为何不使用聚合器 metrics 的 reserves？
-        //    1. metrics 缺少 real_token_reserves（事件有但聚合器未保存）
-        //    2. 计算需要 real_token_reserves 做 min 操作确保不超买
-        //    3. 聚合器数据可能有网络延迟（~10-50ms）
-        //    4. 链上读取是唯一可信源，确保计算准确性
-        //    5. 延迟成本：~10-20ms RPC 调用，对极限狙击影响可控
-        //
-        // ⚠️ 如需优化：可将 real_token_reserves 加入 WindowMetrics，并添加时间戳校验
-        let (real_token_reserves, virtual_token_reserves, virtual_sol_reserves) = {
-            use crate::grpc::parser::bonding_curve_decode;
-
-            let data = self.rpc_client.get_account_data(bonding_curve)
-                .context("读取 bonding curve 账户失败")?;
-
-            let bc = bonding_curve_decode(&data)
-                .ok_or_else(|| anyhow::anyhow!("解码 bonding curve 失败"))?;
+        // 🔥 优化: mint 和 bonding curve 合并为一次 RPC 读取（token program / creator / 储备 / complete）
+        let accounts = self.fetch_buy_accounts(mint, bonding_curve)?;
+        if accounts.pool.complete {
+            return Err(BotError::CurveComplete);
+        }
+        // 🔥 新增: freeze / mint authority 和 Token-2022 危险扩展（转账 hook / 永久委托 / 高转账手续费等）直接拒绝
+        let danger = self.authority_policy.violation(&accounts.authorities)
+            .or_else(|| accounts.extensions.danger_reason(self.config.max_token_transfer_fee_bps));
+        if let Some(reason) = danger {
+            warn!("☠️  拒绝买入 {}: {}", mint, reason);
+            return Err(BotError::DangerousToken {
+                reason,
+                creator: accounts.pool.creator,
+            });
+        }
+        if accounts.extensions.transfer_fee_bps() > 0 {
+            info!("💸 Token-2022 转账手续费: {} bps（到账数量和卖出收入按扣费后计算）",
+                accounts.extensions.transfer_fee_bps());
+        }
+        let creator = accounts.pool.creator;
+        let pool = self.dex.derive_pool_accounts(mint, &accounts.token_program, &creator);
+
+        // 🔥 优化: 优先使用聚合器的内存储备快照（来自交易事件，含 real_token_reserves）
+        //    快照超过 reserves_max_staleness_ms 或缺少 real_token_reserves 时使用刚读取的链上数据
+        let fresh_snapshot = reserves_hint.filter(|snapshot| {
+            snapshot.real_token_reserves > 0
+                && snapshot.age_ms() <= self.config.reserves_max_staleness_ms as i64
+        });
+
+        let reserves = if let Some(snapshot) = fresh_snapshot {
+            info!("📊 使用内存储备数据 (延迟 {}ms):", snapshot.age_ms());
+            info!("   real_token_reserves: {}", snapshot.real_token_reserves);
+            info!("   virtual_token_reserves: {}", snapshot.virtual_token_reserves);
+            info!("   virtual_sol_reserves: {}", snapshot.virtual_sol_reserves);
+
+            PoolState {
+                real_token_reserves: snapshot.real_token_reserves,
+                virtual_token_reserves: snapshot.virtual_token_reserves,
+                virtual_sol_reserves: snapshot.virtual_sol_reserves,
+                ..accounts.pool
+            }
+        } else {
+            if let Some(snapshot) = &reserves_hint {
+                debug!("⏰ 内存储备数据已过期 ({}ms)，使用链上数据", snapshot.age_ms());
+            }
 
+            let bc = &accounts.pool;
             info!("📊 链上储备数据:");
             info!("   real_token_reserves: {}", bc.real_token_reserves);
             info!("   virtual_token_reserves: {}", bc.virtual_token_reserves);
             info!("   virtual_sol_reserves: {}", bc.virtual_sol_reserves);
-            info!("   complete: {}", bc.complete);
 
-            (bc.real_token_reserves, bc.virtual_token_reserves, bc.virtual_sol_reserves)
+            accounts.pool
         };
 
+        // 🔥 新增: 蜜罐检测（首波狙击跳过，延迟优先）
+        if simulate_sell {
+            self.simulate_round_trip(mint, &pool, &accounts, &reserves)?;
+        }
+
         // 1. 检查余额（包含 tip 费用）
         self.check_balance_for_operations(sol_amount, "买入操作")?;
+        let mut tips = TipBreakdown::default();
 
         // 2. 构建交易指令（包含所有 tips）
-        let instructions = self.build_buy_instructions_with_all_tips(
+        // 🔥 修复: 每次尝试都用新 blockhash 重新构建交易（并按倍数提高 CU 价格和 tip），
+        //    发送结果不确定的尝试要等到它上链 / 执行失败 / blockhash 过期后才重新签名，避免两次尝试都上链重复买入
+        let policy = RetryPolicy::from_config(&self.config);
+        let mut attempted: Vec<Signature> = Vec::new();
+        let mut attempt_tips: Vec<TipBreakdown> = Vec::new();
+        // 最近一次仍可能上链的尝试的 blockhash
+        let mut in_flight: Option<Hash> = None;
+        let mut last_error = None;
+        let mut sent = None;
+        // 本次买入的所有尝试在发送前落盘，函数返回时（确认完成或失败）移除
+        let mut outbox = self.outbox.begin(OutboxPurpose::Buy, *mint);
+        // 🔥 修复: 支出限额在第一次尝试前按最后一次尝试（tip 和 CU 价格提高后）的最坏支出 × 最大尝试次数预留，
+        //    不会在重试中途才拒绝；每次尝试签名后按该次支出计入，不再退回
+        let mut spend = self.spend_guard.begin(*mint);
+        let (worst_instructions, worst_tips) = self.build_buy_instructions_with_all_tips(
             mint,
-            bonding_curve,
-            associated_bonding_curve,
+            &pool,
+            &accounts.token_program,
             sol_amount,
-            real_token_reserves,      // 🔥 实际可买代币上限
-            virtual_token_reserves,   // 🔥 用于价格公式计算
-            virtual_sol_reserves,
+            &reserves,
+            &policy,
+            policy.max_attempts,
         )?;
 
-        info!("📦 交易指令已构建，共 {} 条指令", instructions.len());
+        // 🔥 修复: 固定成本（tip + 优先费 + 网络费）占仓位比例过高时拒绝，防止 0.01 SOL 买入配 0.005 SOL tip
+        //    之类的配置错误；按最后一次尝试（tip 和 CU 价格提高后）在第一次尝试前检查一次
+        self.check_outlay(&BuyOutlay {
+            position: sol_amount,
+            tips: worst_tips.swqos + worst_tips.lightspeed,
+            priority_fee: policy.compute_unit_price(policy.max_attempts) * self.config.compute_unit_limit as u64 / 1_000_000,
+            base_fee: BASE_SIGNATURE_FEE_LAMPORTS,
+            rent: TOKEN_ACCOUNT_RENT_LAMPORTS,
+        })?;
+        spend.reserve(self.transaction_spend(&worst_instructions, &policy, policy.max_attempts), policy.max_attempts)?;
+
+        for attempt in 1..=policy.max_attempts {
+            if let Some(blockhash) = in_flight.take() {
+                match self.wait_for_prior_attempts(&attempted, &blockhash).await {
+                    PriorAttempts::Landed(landed) => {
+                        info!("✅ 之前的买入交易已上链: {}", landed);
+                        sent = Some(landed);
+                        break;
+                    }
+                    PriorAttempts::Settled => {}
+                    PriorAttempts::Unknown => {
+                        warn!("⚠️  之前的买入尝试状态未知，不再重新签名");
+                        break;
+                    }
+                }
+            }
 
-        // 3. 构建 VersionedTransaction
-        let transaction = self.build_versioned_transaction(instructions)?;
+            let (instructions, tips_for_attempt) = self.build_buy_instructions_with_all_tips(
+                mint,
+                &pool,
+                &accounts.token_program,
+                sol_amount,
+                &reserves,
+                &policy,
+                attempt,
+            )?;
+
+            info!("📦 交易指令已构建，共 {} 条指令 (尝试 {}/{})", instructions.len(), attempt, policy.max_attempts);
+
+            // 🔥 新增: 签名前按最终指令再确认一次（不超过已授权的最坏支出时不会拒绝）
+            spend.authorize(self.transaction_spend(&instructions, &policy, attempt))?;
+
+            // 3. 构建 VersionedTransaction（获取最新 blockhash）
+            let transaction = self.build_versioned_transaction(instructions)?;
+            let blockhash = *transaction.message.recent_blockhash();
+            spend.signed();
+            attempted.push(transaction.signatures[0]);
+            attempt_tips.push(tips_for_attempt);
+            outbox.record(&transaction);
+
+            // 4. 发送交易（按 send_backend_order 依次尝试）
+            match self.send_transaction_with_priority(transaction).await {
+                Ok(signature) => {
+                    sent = Some(signature);
+                    break;
+                }
+                Err(e) => {
+                    warn!("⚠️  买入发送失败 (尝试 {}/{}): {}", attempt, policy.max_attempts, e);
+                    // blockhash 失效的交易不会上链，其他错误（超时等）交易可能已被转发
+                    if classify_send_error(&e) != SendErrorKind::BlockhashNotFound {
+                        in_flight = Some(blockhash);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
 
-        // 4. 发送交易（SWQOS 优先，LightSpeed 保底）
-        let signature = self.send_transaction_with_priority(transaction).await?;
+        // 🔥 修复: 返回错误前检查所有已签名的尝试，任何一次上链都按买入成功处理（确认追踪针对上链的那次）
+        if sent.is_none() {
+            sent = match in_flight {
+                Some(blockhash) => match self.wait_for_prior_attempts(&attempted, &blockhash).await {
+                    PriorAttempts::Landed(landed) => Some(landed),
+                    PriorAttempts::Settled | PriorAttempts::Unknown => None,
+                },
+                None => self.find_landed_signature(&attempted),
+            };
+            if let Some(landed) = sent {
+                info!("✅ 之前的买入交易已上链: {}", landed);
+            }
+        }
+        let Some(signature) = sent else {
+            let e = last_error.unwrap_or_else(|| anyhow::anyhow!("买入发送失败，之前的尝试状态未知"));
+            error!("❌ 买入发送失败，已达最大重试次数: {}", e);
+            return Err(e.into());
+        };
+        if let Some(index) = attempted.iter().position(|attempt| *attempt == signature) {
+            tips = attempt_tips[index];
+        }
 
         info!("✅ 买入交易已发送: {}", signature);
 
@@ -245,63 +578,112 @@ impl LightSpeedBuyExecutor {
 
         if confirmed {
             info!("🎉 买入交易已确认: {}", signature);
+            self.tip_ledger.record(tips.swqos, tips.lightspeed);
         } else {
             warn!("⚠️  买入交易未在规定时间内确认: {}", signature);
         }
 
-        Ok(signature)
+        Ok(BuyReceipt {
+            signature,
+            tips_lamports: tips.swqos + tips.lightspeed,
+            pool,
+            creator,
+            mint_extensions: accounts.extensions,
+        })
     }
 
-    /// 检查余额是否足够执行操作
-    ///
-    /// 参考 lightspeed-examples/src/utils.ts:checkBalanceForOperations
-    ///
-    /// 🔥 修复: 计算所有 tips（LightSpeed + SWQOS）
-    fn check_balance_for_operations(
-        &self,
-        required_lamports: u64,
-        description: &str,
-    ) -> Result<()> {
-        let balance = self.rpc_client.get_balance(&self.payer.pubkey())
-            .context("获取账户余额失败")?;
+    /// 固定成本占仓位比例检查（max_fixed_cost_fraction = 0 时不检查）
+    fn check_outlay(&self, outlay: &BuyOutlay) -> BotResult<()> {
+        info!("💵 最坏总支出: 买入 {:.6} + tip {:.6} + 优先费 {:.6} + 网络费 {:.6} + ATA 租金 {:.6} SOL (固定成本 {:.1}%)",
+            outlay.position as f64 / 1_000_000_000.0,
+            outlay.tips as f64 / 1_000_000_000.0,
+            outlay.priority_fee as f64 / 1_000_000_000.0,
+            outlay.base_fee as f64 / 1_000_000_000.0,
+            outlay.rent as f64 / 1_000_000_000.0,
+            outlay.fixed_fraction() * 100.0);
+
+        let max_fraction = self.config.max_fixed_cost_fraction;
+        if max_fraction > 0.0 && outlay.fixed_fraction() > max_fraction {
+            warn!("🛑 固定成本 {:.6} SOL 占买入金额 {:.1}%，超过上限 {:.1}%，拒绝买入",
+                outlay.fixed() as f64 / 1_000_000_000.0,
+                outlay.fixed_fraction() * 100.0,
+                max_fraction * 100.0);
+            return Err(BotError::FixedCostsTooHigh {
+                fixed_lamports: outlay.fixed(),
+                position_lamports: outlay.position,
+            });
+        }
+        Ok(())
+    }
 
-        // 🔥 修复: 计算所有 tip 费用
-        let mut total_tips = 0u64;
+    /// 交易最多花掉的 SOL（lamports）：DEX 买入指令的最大 SOL 成本 + 钱包发起的 system 转账 / 建账户
+    /// + 该次尝试的网络费和优先费
+    fn transaction_spend(&self, instructions: &[Instruction], policy: &RetryPolicy, attempt: u32) -> u64 {
+        let max_sol_cost: u64 = instructions.iter().filter_map(|ix| self.dex.buy_max_sol_cost(ix)).sum();
+        max_sol_cost
+            + system_outflow(instructions, &self.payer.pubkey())
+            + BASE_SIGNATURE_FEE_LAMPORTS
+            + policy.compute_unit_price(attempt) * self.config.compute_unit_limit as u64 / 1_000_000
+    }
+
+    /// 单笔买入的 tip 总额（LightSpeed + SWQOS，lamports，首次尝试）
+    pub fn estimated_tip_lamports(&self) -> u64 {
+        let tips = self.estimate_tips();
+        tips.swqos + tips.lightspeed
+    }
+
+    /// 估算单笔买入的 tip 明细
+    fn estimate_tips(&self) -> TipBreakdown {
+        let mut tips = TipBreakdown::default();
 
         // 1. LightSpeed tip
         if self.config.use_lightspeed {
-            total_tips += self.config.get_lightspeed_tip_lamports();
+            tips.lightspeed = self.config.get_lightspeed_tip_lamports();
         }
 
         // 2. SWQOS tips（如果启用）
-        let swqos_tips_total = if let Some(swqos) = &self.swqos_manager {
+        if let Some(swqos) = &self.swqos_manager {
             match swqos.get_all_tip_instructions(&self.payer.pubkey()) {
-                Ok(tips) => {
-                    let mut swqos_total = 0u64;
-                    for (service_name, tip_ix) in tips {
+                Ok(tip_instructions) => {
+                    for (service_name, tip_ix) in tip_instructions {
                         // 🔥 从 transfer 指令中提取 lamports（第3个参数）
-                        if tip_ix.data.len() >= 12 {
-                            let tip_amount = u64::from_le_bytes(
-                                tip_ix.data[4..12].try_into().unwrap_or([0u8; 8])
-                            );
-                            swqos_total += tip_amount;
+                        let tip_amount = transfer_lamports(&tip_ix);
+                        if tip_amount > 0 {
+                            tips.swqos += tip_amount;
                             debug!("   SWQOS {} tip: {} lamports", service_name, tip_amount);
                         }
                     }
-                    total_tips += swqos_total;
-                    swqos_total
                 }
                 Err(e) => {
                     warn!("⚠️  获取 SWQOS tips 失败: {}", e);
-                    0
                 }
             }
-        } else {
-            0
-        };
+        }
+
+        tips
+    }
+
+    /// 检查余额是否足够执行操作
+    ///
+    /// 参考 lightspeed-examples/src/utils.ts:checkBalanceForOperations
+    ///
+    /// 🔥 修复: 计算所有 tips（LightSpeed + SWQOS）
+    fn check_balance_for_operations(
+        &self,
+        required_lamports: u64,
+        description: &str,
+    ) -> Result<()> {
+        let balance = self.rpc_client
+            .get_balance_with_commitment(&self.payer.pubkey(), self.config.get_balance_commitment())
+            .context("获取账户余额失败")?
+            .value;
+
+        // 🔥 修复: 计算所有 tip 费用
+        let tips = self.estimate_tips();
+        let swqos_tips_total = tips.swqos;
 
         // 计算总需求
-        let total_required = required_lamports + total_tips;
+        let total_required = required_lamports + tips.swqos + tips.lightspeed;
 
         if balance < total_required {
             error!("❌ 余额不足 - {}", description);
@@ -315,7 +697,10 @@ impl LightSpeedBuyExecutor {
                 error!("   SWQOS tips: {} SOL", swqos_tips_total as f64 / 1_000_000_000.0);
             }
             error!("   总计需要: {} SOL", total_required as f64 / 1_000_000_000.0);
-            return Err(anyhow::anyhow!("余额不足"));
+            return Err(BotError::InsufficientBalance {
+                required: total_required,
+                available: balance,
+            }.into());
         }
 
         info!("✅ 余额检查通过 - {}", description);
@@ -338,35 +723,31 @@ impl LightSpeedBuyExecutor {
     // 生产环境统一使用 build_buy_instructions_with_all_tips（包含滑点保护、real_token_reserves、SWQOS tips）
     // 避免误用导致上链失败
 
-    /// 获取 Associated Token Address
-    /// 使用 PDA 派生，避免依赖外部库
-    /// 🔥 修复: 支持 Token-2022
-    fn get_associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
-        let token_program_id = Pubkey::try_from("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")
-            .expect("Invalid TOKEN_PROGRAM_ID");
-
-        let associated_token_program_id = Pubkey::try_from("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL")
-            .expect("Invalid ASSOCIATED_TOKEN_PROGRAM_ID");
-
-        Pubkey::find_program_address(
-            &[
-                wallet.as_ref(),
-                token_program_id.as_ref(),
-                mint.as_ref(),
-            ],
-            &associated_token_program_id,
-        )
-        .0
+    /// 🔥 优化: 一次 getMultipleAccounts 读取 mint 和 bonding curve 账户（mint 同时解析 Token-2022 扩展）
+    fn fetch_buy_accounts(&self, mint: &Pubkey, bonding_curve: &Pubkey) -> Result<BuyAccounts> {
+        let accounts = self.rpc_client.get_multiple_accounts(&[*mint, *bonding_curve])
+            .context("批量读取 mint / bonding curve 账户失败")?;
+
+        let mint_account = accounts.first().and_then(Option::as_ref)
+            .ok_or_else(|| anyhow::anyhow!("mint 账户不存在: {}", mint))?;
+        let curve_account = accounts.get(1).and_then(Option::as_ref)
+            .ok_or_else(|| anyhow::anyhow!("bonding curve 账户不存在: {}", bonding_curve))?;
+
+        let pool = self.dex.decode_pool(&curve_account.data)
+            .ok_or_else(|| anyhow::anyhow!("解码 {} 池子账户失败", self.dex.name()))?;
+
+        Ok(BuyAccounts {
+            token_program: Self::resolve_token_program(mint, &mint_account.owner)?,
+            authorities: MintAuthorities::parse(&mint_account.data)
+                .ok_or_else(|| anyhow::anyhow!("解析 mint 账户失败: {}", mint))?,
+            extensions: MintExtensions::parse(&mint_account.owner, &mint_account.data),
+            pool,
+        })
     }
 
-    /// 🔥 新增: 检测 mint 的 token program（支持 Token-2022）
-    fn detect_token_program(&self, mint: &Pubkey) -> Result<Pubkey> {
-        // 读取 mint 账户
-        let account = self.rpc_client.get_account(mint)
-            .context("读取 mint 账户失败")?;
-
-        // 检查 owner（即 token program）
-        let token_program = account.owner;
+    /// 🔥 新增: 按 mint 账户的 owner 判断 token program（支持 Token-2022）
+    fn resolve_token_program(mint: &Pubkey, owner: &Pubkey) -> Result<Pubkey> {
+        let token_program = *owner;
 
         let token_2022 = Pubkey::try_from(TOKEN_2022_PROGRAM)?;
         let token_v3 = Pubkey::try_from(SYSTEM_TOKEN_PROGRAM)?;
@@ -383,123 +764,6 @@ impl LightSpeedBuyExecutor {
         }
     }
 
-    /// 🔥 新增: 获取支持 Token-2022 的 ATA 地址
-    fn get_ata_with_program(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
-        let associated_token_program_id = Pubkey::try_from("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL")
-            .expect("Invalid ASSOCIATED_TOKEN_PROGRAM_ID");
-
-        Pubkey::find_program_address(
-            &[
-                wallet.as_ref(),
-                token_program.as_ref(),  // 🔥 使用实际的 token program
-                mint.as_ref(),
-            ],
-            &associated_token_program_id,
-        )
-        .0
-    }
-
-    /// 派生 creator_vault PDA（完全参考 sol-trade-sdk）
-    /// 🔥 修复: creator_vault 是 PDA，不是 ATA！
-    /// seed = [b"creator-vault", creator.as_ref()]
-    /// program_id = PUMPFUN_PROGRAM_ID
-    fn derive_creator_vault(creator: &Pubkey) -> Result<Pubkey> {
-        let pumpfun_program = Pubkey::try_from(PUMPFUN_PROGRAM_ID)?;
-
-        let (creator_vault, _bump) = Pubkey::find_program_address(
-            &[
-                b"creator-vault",
-                creator.as_ref(),
-            ],
-            &pumpfun_program,
-        );
-
-        Ok(creator_vault)
-    }
-
-    /// 🔥 新增: 从 bonding_curve 账户读取 creator
-    fn get_creator_from_bonding_curve(&self, bonding_curve: &Pubkey) -> Result<Pubkey> {
-        use crate::grpc::parser::bonding_curve_decode;
-
-        let data = self.rpc_client.get_account_data(bonding_curve)
-            .context("读取 bonding curve 账户失败")?;
-
-        let bc = bonding_curve_decode(&data)
-            .ok_or_else(|| anyhow::anyhow!("解码 bonding curve 失败"))?;
-
-        Ok(bc.creator)
-    }
-
-    /// 派生 user_volume_accumulator PDA（完全参考 sol-trade-sdk）
-    /// 🔥 修复: seed 必须是 "user_volume_accumulator" (underscore)，不是 hyphen!
-    fn derive_user_volume_accumulator(user: &Pubkey) -> Result<Pubkey> {
-        let pumpfun_program = Pubkey::try_from(PUMPFUN_PROGRAM_ID)?;
-
-        let (user_volume_accumulator, _bump) = Pubkey::find_program_address(
-            &[
-                b"user_volume_accumulator",  // 🔥 修复: underscore，不是 hyphen
-                user.as_ref(),
-            ],
-            &pumpfun_program,
-        );
-
-        Ok(user_volume_accumulator)
-    }
-
-    /// 🔥 修复: 计算买入应得的代币数量（完全参考 sol-trade-sdk）
-    ///
-    /// 参考: sol-trade-sdk/src/utils/calc/pumpfun.rs:get_buy_token_amount_from_sol_amount
-    /// 🔥 修复: 使用 virtual_token_reserves 计算，再 min(real_token_reserves)
-    /// 🔥 修复: 使用正确的费率 FEE_BASIS_POINTS=95 + CREATOR_FEE=30
-    fn calculate_buy_token_amount(
-        real_token_reserves: u64,      // 实际可买代币上限
-        virtual_token_reserves: u64,   // 用于价格公式计算
-        virtual_sol_reserves: u64,
-        sol_amount: u64,
-    ) -> u64 {
-        if sol_amount == 0 {
-            return 0;
-        }
-
-        if virtual_token_reserves == 0 || virtual_sol_reserves == 0 {
-            return 0;
-        }
-
-        // 🔥 修复: PumpFun 费率（完全对齐 sol-trade-sdk）
-        // FEE_BASIS_POINTS = 95 (0.95%)
-        // CREATOR_FEE = 30 (0.30%)
-        // 总费率 = 125 bps (1.25%)
-        const FEE_BASIS_POINTS: u128 = 95;
-        const CREATOR_FEE: u128 = 30;
-        const BASIS_POINTS: u128 = 10_000;
-        let total_fee_basis_points = FEE_BASIS_POINTS + CREATOR_FEE;
-
-        // 扣除手续费后的输入金额（使用 checked 操作）
-        let amount_128 = sol_amount as u128;
-        let input_amount = amount_128
-            .checked_mul(BASIS_POINTS)
-            .unwrap_or(0)
-            .checked_div(total_fee_basis_points + BASIS_POINTS)
-            .unwrap_or(0);
-
-        if input_amount == 0 {
-            return 0;
-        }
-
-        // 恒定乘积公式: k = x * y（使用 checked 操作）
-        // 🔥 修复: 使用 virtual_token_reserves 计算（对齐 SDK）
-        let denominator = (virtual_sol_reserves as u128) + input_amount;
-        let tokens_received = input_amount
-            .checked_mul(virtual_token_reserves as u128)  // 🔥 使用 virtual
-            .unwrap_or(0)
-            .checked_div(denominator)
-            .unwrap_or(0);
-
-        // 🔥 修复: 取 min(计算值, real_token_reserves) 确保不超过实际可买
-        let tokens_u64 = tokens_received.min(u64::MAX as u128) as u64;
-        tokens_u64.min(real_token_reserves)
-    }
-
     /// 🔥 新增: 计算带滑点保护的最大 SOL 成本
     ///
     /// 参考: sol-trade-sdk/src/utils/calc/common.rs:calculate_with_slippage_buy
@@ -520,83 +784,11 @@ impl LightSpeedBuyExecutor {
         max_cost as u64
     }
 
-    /// 发送交易（带重试机制）
-    ///
-    /// 参考 lightspeed-examples/src/utils.ts:sendTxWithRetries
-    ///
-    /// 配置:
-    /// - preflightCommitment: "processed"
-    /// - skipPreflight: true
-    /// - maxRetries: 3
-    async fn send_tx_with_retries(
-        &self,
-        instructions: Vec<Instruction>,
-        max_attempts: u32,
-    ) -> Result<Signature> {
-        info!("📤 准备发送交易，最多重试 {} 次", max_attempts);
-
-        // 获取最新 blockhash
-        let recent_blockhash = self.rpc_client.get_latest_blockhash()
-            .context("获取 blockhash 失败")?;
-
-        // 构建交易
-        let mut transaction = Transaction::new_with_payer(
-            &instructions,
-            Some(&self.payer.pubkey()),
-        );
-        transaction.sign(&[&*self.payer], recent_blockhash);
-
-        // 序列化交易
-        let serialized_tx = bincode::serialize(&transaction)
-            .context("序列化交易失败")?;
-
-        debug!("📦 交易大小: {} bytes", serialized_tx.len());
-
-        // 选择 RPC 客户端（优先使用 LightSpeed，否则使用普通 RPC）
-        let rpc_to_use = if let Some(ref lightspeed) = self.lightspeed_rpc {
-            debug!("🚀 使用 LightSpeed RPC 发送交易");
-            lightspeed
-        } else {
-            debug!("📡 使用普通 RPC 发送交易");
-            &self.rpc_client
-        };
-
-        // 重试发送
-        for attempt in 1..=max_attempts {
-            info!("🔄 发送尝试 {}/{}", attempt, max_attempts);
-
-            match rpc_to_use.send_transaction_with_config(
-                &transaction,
-                solana_client::rpc_config::RpcSendTransactionConfig {
-                    skip_preflight: true,
-                    preflight_commitment: Some(solana_commitment_config::CommitmentLevel::Processed),
-                    max_retries: Some(3),
-                    ..Default::default()
-                },
-            ) {
-                Ok(signature) => {
-                    info!("✅ 交易已发送 (尝试 {}): {}", attempt, signature);
-                    return Ok(signature);
-                }
-                Err(e) => {
-                    error!("❌ 发送失败 (尝试 {}): {:?}", attempt, e);
-                    if attempt == max_attempts {
-                        return Err(anyhow::anyhow!("达到最大重试次数: {:?}", e));
-                    }
-                    // 等待一小段时间再重试
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-                }
-            }
-        }
-
-        Err(anyhow::anyhow!("发送交易失败"))
-    }
-
     /// 监控交易状态
     ///
     /// 参考 lightspeed-examples/src/utils.ts:monitorTransactionStatus
     ///
-    /// 持续检查交易状态，直到确认或超时
+    /// 🔥 优化: gRPC 交易流匹配签名（亚秒级），RPC 仅作低频兜底
     async fn monitor_transaction_status(
         &self,
         signature: &Signature,
@@ -605,118 +797,110 @@ impl LightSpeedBuyExecutor {
         info!("⏳ 开始监控交易状态: {}", signature);
         info!("   最大等待时间: {} 秒", max_wait_seconds);
 
-        let start_time = Instant::now();
-        let max_wait = Duration::from_secs(max_wait_seconds);
-
-        while start_time.elapsed() < max_wait {
-            match self.rpc_client.get_signature_status(signature) {
-                Ok(Some(status)) => {
-                    match status {
-                        Ok(_) => {
-                            // 交易成功
-                            let elapsed = start_time.elapsed().as_secs();
-                            info!("✅ 交易已确认 (耗时 {} 秒)", elapsed);
-                            return Ok(true);
-                        }
-                        Err(e) => {
-                            // 交易失败
-                            error!("❌ 交易失败: {:?}", e);
-                            return Ok(false);
-                        }
-                    }
-                }
-                Ok(None) => {
-                    // 交易尚未确认，继续等待
-                    debug!("⏳ 交易尚未确认，继续等待...");
+        match self.confirmations.wait_for(self.rpc_client.as_ref(), signature, max_wait_seconds).await {
+            ConfirmationStatus::Confirmed => Ok(true),
+            ConfirmationStatus::Failed(err) => {
+                error!("❌ 交易失败: {}", err);
+                Ok(false)
+            }
+            ConfirmationStatus::TimedOut => {
+                warn!("⏰ 交易确认超时 ({} 秒)", max_wait_seconds);
+                Ok(false)
+            }
+        }
+    }
+
+    /// 等待之前的尝试结束（重新签名或放弃前调用）
+    ///
+    /// 轮询所有已签名尝试的状态：任何一次上链返回 Landed；最近一次已执行失败，
+    /// 或其 blockhash 已过期（过期后再查一次状态）返回 Settled；超过 BLOCKHASH_EXPIRY_WAIT 仍不确定返回 Unknown
+    async fn wait_for_prior_attempts(&self, attempted: &[Signature], blockhash: &Hash) -> PriorAttempts {
+        let deadline = tokio::time::Instant::now() + BLOCKHASH_EXPIRY_WAIT;
+        let poll_interval = Duration::from_millis(self.config.confirmation_fallback_poll_ms.max(1));
+
+        loop {
+            if let Ok(statuses) = self.rpc_client.get_signature_statuses(attempted) {
+                let statuses = statuses.value;
+                let landed = attempted
+                    .iter()
+                    .zip(&statuses)
+                    .find(|(_, status)| matches!(status, Some(status) if status.err.is_none()));
+                if let Some((signature, _)) = landed {
+                    return PriorAttempts::Landed(*signature);
                 }
-                Err(e) => {
-                    warn!("⚠️  查询交易状态失败: {:?}", e);
+                if statuses.last().is_some_and(Option::is_some) {
+                    return PriorAttempts::Settled;
                 }
             }
 
-            // 等待 1 秒后再次检查
-            tokio::time::sleep(Duration::from_secs(1)).await;
-        }
+            if let Ok(false) = self.rpc_client.is_blockhash_valid(blockhash, self.config.get_confirm_commitment()) {
+                return match self.find_landed_signature(attempted) {
+                    Some(landed) => PriorAttempts::Landed(landed),
+                    None => PriorAttempts::Settled,
+                };
+            }
 
-        // 超时
-        warn!("⏰ 交易确认超时 ({} 秒)", max_wait_seconds);
-        Ok(false)
+            if tokio::time::Instant::now() >= deadline {
+                return PriorAttempts::Unknown;
+            }
+            debug!("⏳ 等待之前的买入尝试上链或 blockhash 过期...");
+            tokio::time::sleep(poll_interval).await;
+        }
     }
 
-    /// 获取账户余额
-    pub fn get_balance(&self) -> Result<u64> {
-        self.rpc_client.get_balance(&self.payer.pubkey())
-            .context("获取账户余额失败")
+    /// 查找已成功上链的签名
+    fn find_landed_signature(&self, signatures: &[Signature]) -> Option<Signature> {
+        if signatures.is_empty() {
+            return None;
+        }
+        let statuses = self.rpc_client.get_signature_statuses(signatures).ok()?;
+        signatures
+            .iter()
+            .zip(statuses.value)
+            .find(|(_, status)| matches!(status, Some(status) if status.err.is_none()))
+            .map(|(signature, _)| *signature)
     }
 
     /// 构建买入指令（包含所有 tips：LightSpeed + SWQOS）
     ///
     /// 🔥 修复: 使用 virtual_token_reserves 计算，再 min(real_token_reserves)
+    #[allow(clippy::too_many_arguments)]
     fn build_buy_instructions_with_all_tips(
         &self,
         mint: &Pubkey,
-        bonding_curve: &Pubkey,
-        associated_bonding_curve: &Pubkey,
+        pool: &PoolAccounts,
+        token_program: &Pubkey,
         sol_amount: u64,
-        real_token_reserves: u64,      // 🔥 实际可买代币上限
-        virtual_token_reserves: u64,   // 🔥 用于价格公式计算
-        virtual_sol_reserves: u64,
-    ) -> Result<Vec<Instruction>> {
+        reserves: &PoolState,
+        policy: &RetryPolicy,
+        attempt: u32,
+    ) -> Result<(Vec<Instruction>, TipBreakdown)> {
         let mut instructions = Vec::new();
+        let mut tips = TipBreakdown::default();
         let payer = self.payer.pubkey();
 
         // 🔥 修复: 移除重复的 ComputeBudget 指令（保留最后的 insert 版本）
 
-        // 🔥 新增: 检测 Token Program（支持 Token-2022）
-        let token_program = self.detect_token_program(mint)?;
+        // 🔥 新增: Token Program 由调用方从 mint 账户解析（支持 Token-2022）
+        let token_program = *token_program;
 
         // 1. 创建用户的 Token ATA（如果不存在）
         // 🔥 修复: 使用检测到的 token program（支持 Token-2022）
-        let user_token_account = Self::get_ata_with_program(&payer, mint, &token_program);
+        let user_token_account = self.dex.user_token_account(&payer, mint, &token_program);
 
         debug!("🏗️  添加 ATA 创建指令");
         debug!("   Token Program: {}", token_program);
         debug!("   用户 Token 账户: {}", user_token_account);
 
-        // 手动构建 CreateIdempotent 指令（幂等）
-        let ata_program_id = Pubkey::try_from("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL")?;
-        let system_program_id = Pubkey::try_from(SYSTEM_PROGRAM)?;
+        instructions.push(Self::build_create_ata_instruction(&payer, &user_token_account, mint, &token_program)?);
 
-        let create_ata_ix = Instruction {
-            program_id: ata_program_id,
-            accounts: vec![
-                AccountMeta::new(payer, true),                    // 0. 支付者（signer）
-                AccountMeta::new(user_token_account, false),      // 1. 关联代币账户
-                AccountMeta::new_readonly(payer, false),          // 2. 拥有者
-                AccountMeta::new_readonly(*mint, false),          // 3. mint
-                AccountMeta::new_readonly(system_program_id, false), // 4. system_program
-                AccountMeta::new_readonly(token_program, false),  // 5. token_program (动态)
-            ],
-            data: vec![1], // 1 = CreateIdempotent 指令
-        };
-        instructions.push(create_ata_ix);
-
-        // 2. 构建 PumpFun 买入指令（完全参考 sol-trade-sdk 的账户顺序）
-        debug!("🏗️  构建 PumpFun 买入指令");
-
-        // 🔥 修复: 先读取 creator，再派生 creator_vault PDA
-        let creator = self.get_creator_from_bonding_curve(bonding_curve)?;
-        let creator_vault = Self::derive_creator_vault(&creator)?;
-        debug!("   Creator: {}", creator);
-        debug!("   Creator Vault: {}", creator_vault);
-
-        // 派生 user_volume_accumulator PDA
-        let user_volume_accumulator = Self::derive_user_volume_accumulator(&payer)?;
-        debug!("   User Volume Accumulator: {}", user_volume_accumulator);
+        // 2. 构建 DEX 买入指令
+        debug!("🏗️  构建 {} 买入指令", self.dex.name());
 
         // 🔥 修复: 正确计算 token_amount 和 max_sol_cost（参考 sol-trade-sdk）
         // 使用 virtual_token_reserves 计算，再 min(real_token_reserves)
-        let token_amount = Self::calculate_buy_token_amount(
-            real_token_reserves,      // 🔥 实际可买代币上限
-            virtual_token_reserves,   // 🔥 用于价格公式计算
-            virtual_sol_reserves,
-            sol_amount,
-        );
+        let quote = self.dex.quote_buy(reserves, sol_amount);
+        let token_amount = quote.amount_out;
         let max_sol_cost = Self::calculate_max_sol_cost_with_slippage(
             sol_amount,
             self.config.slippage_percent,
@@ -724,66 +908,28 @@ impl LightSpeedBuyExecutor {
 
         info!("📊 买入计算:");
         info!("   输入 SOL: {} ({} lamports)", sol_amount as f64 / 1e9, sol_amount);
-        info!("   期望代币数量: {} tokens", token_amount);
+        info!("   期望代币数量: {} tokens (价格冲击 {:.2}%)", token_amount, quote.price_impact_percent);
         info!("   最大 SOL 成本 (含{}%滑点): {} lamports", self.config.slippage_percent, max_sol_cost);
 
-        // 构建指令数据
-        // 格式: [discriminator(8), token_amount(8), max_sol_cost(8)]
-        let mut instruction_data = Vec::with_capacity(24);
-        instruction_data.extend_from_slice(&BUY_DISCRIMINATOR);
-        instruction_data.extend_from_slice(&token_amount.to_le_bytes());    // 🔥 修复: token_amount
-        instruction_data.extend_from_slice(&max_sol_cost.to_le_bytes());    // 🔥 修复: max_sol_cost
-
-        // 构建账户列表（完全参考 sol-trade-sdk 的顺序，16 个账户）
-        let accounts = vec![
-            AccountMeta::new_readonly(self.global, false),                          // 0: global
-            AccountMeta::new(self.fee_recipient, false),                            // 1: fee_recipient
-            AccountMeta::new_readonly(*mint, false),                                // 2: mint
-            AccountMeta::new(*bonding_curve, false),                                // 3: bonding_curve
-            AccountMeta::new(*associated_bonding_curve, false),                     // 4: associated_bonding_curve
-            AccountMeta::new(user_token_account, false),                            // 5: user_token_account
-            AccountMeta::new(payer, true),                                          // 6: payer (signer)
-            AccountMeta::new_readonly(Pubkey::try_from(SYSTEM_PROGRAM).unwrap(), false), // 7: system_program
-            AccountMeta::new_readonly(Pubkey::try_from(SYSTEM_TOKEN_PROGRAM).unwrap(), false), // 8: token_program (固定 Token v3，对齐 SDK) ⭐
-            AccountMeta::new(creator_vault, false),                                 // 9: creator_vault ⭐
-            AccountMeta::new_readonly(self.event_authority, false),                 // 10: event_authority
-            AccountMeta::new_readonly(self.pumpfun_program, false),                 // 11: pumpfun_program
-            AccountMeta::new(Pubkey::try_from(GLOBAL_VOLUME_ACCUMULATOR).unwrap(), false), // 12: global_volume_accumulator ⭐ (可写)
-            AccountMeta::new(user_volume_accumulator, false),                       // 13: user_volume_accumulator ⭐
-            AccountMeta::new_readonly(Pubkey::try_from(FEE_CONFIG).unwrap(), false), // 14: fee_config ⭐
-            AccountMeta::new_readonly(Pubkey::try_from(FEE_PROGRAM).unwrap(), false), // 15: fee_program ⭐
-        ];
-
-        // 🔥 排障日志: 打印关键账户表摘要
-        debug!("📋 PumpFun 买入账户表摘要 (16 accounts):");
-        debug!("   [0] global: {} (readonly)", self.global);
-        debug!("   [1] fee_recipient: {} (writable)", self.fee_recipient);
-        debug!("   [8] token_program: {} (readonly, Token v3 固定) ⭐",
-            Pubkey::try_from(SYSTEM_TOKEN_PROGRAM).unwrap()
-        );
-        debug!("   [9] creator_vault: {} (writable) ⭐", creator_vault);
-        debug!("   [12] global_volume_accumulator: {} (writable) ⭐",
-            Pubkey::try_from(GLOBAL_VOLUME_ACCUMULATOR).unwrap()
-        );
-        debug!("   [13] user_volume_accumulator: {} (writable) ⭐", user_volume_accumulator);
-        debug!("   [14] fee_config: {} (readonly) ⭐", Pubkey::try_from(FEE_CONFIG).unwrap());
-        debug!("   [15] fee_program: {} (readonly) ⭐", Pubkey::try_from(FEE_PROGRAM).unwrap());
-
-        instructions.push(Instruction {
-            program_id: self.pumpfun_program,
-            accounts,
-            data: instruction_data,
-        });
+        let swap_accounts = SwapAccounts {
+            payer,
+            mint: *mint,
+            token_program,
+            user_token_account,
+            pool,
+        };
+        instructions.push(self.dex.build_buy_ix(&swap_accounts, token_amount, max_sol_cost)?);
 
         // 3. 添加 LightSpeed tip（如果启用）
         if self.config.use_lightspeed {
             let tip_address = self.config.lightspeed_tip_address.parse::<Pubkey>()
                 .context("Invalid lightspeed_tip_address")?;
-            let tip_lamports = self.config.get_lightspeed_tip_lamports();
+            let tip_lamports = policy.scale_tip(self.config.get_lightspeed_tip_lamports(), attempt);
 
             info!("💨 添加 LightSpeed tip: {} SOL", tip_lamports as f64 / 1_000_000_000.0);
 
             instructions.push(transfer(&payer, &tip_address, tip_lamports));
+            tips.lightspeed = tip_lamports;
         }
 
         // 4. 添加 SWQOS tips（如果启用）
@@ -792,7 +938,11 @@ impl LightSpeedBuyExecutor {
                 Ok(swqos_tips) => {
                     let tips_count = swqos_tips.len();
                     for (service_name, tip_ix) in swqos_tips {
-                        instructions.push(tip_ix);
+                        // 重试时按倍数提高 tip（收款账户不变）
+                        let tip_lamports = policy.scale_tip(transfer_lamports(&tip_ix), attempt);
+                        let tip_account = tip_ix.accounts[1].pubkey;
+                        tips.swqos += tip_lamports;
+                        instructions.push(transfer(&payer, &tip_account, tip_lamports));
                         debug!("💰 添加 {} tip 指令", service_name);
                     }
                     info!("✅ 已添加 {} 个 SWQOS tip 指令", tips_count);
@@ -806,13 +956,130 @@ impl LightSpeedBuyExecutor {
         // 1. 添加计算预算指令（最后插入到开头，完全参考 lightspeed-examples 的 unshift 逻辑）
         debug!("📊 添加 ComputeBudget 指令");
         instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_price(
-            self.config.compute_unit_price,
+            policy.compute_unit_price(attempt),
         ));
         instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_limit(
             self.config.compute_unit_limit,
         ));
 
-        Ok(instructions)
+        Ok((instructions, tips))
+    }
+
+    /// 创建用户 ATA 指令（CreateIdempotent，已存在时不报错）
+    fn build_create_ata_instruction(
+        payer: &Pubkey,
+        user_token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Result<Instruction> {
+        // 手动构建 CreateIdempotent 指令（幂等）
+        let ata_program_id = Pubkey::try_from("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL")?;
+        let system_program_id = Pubkey::try_from(SYSTEM_PROGRAM)?;
+
+        Ok(Instruction {
+            program_id: ata_program_id,
+            accounts: vec![
+                AccountMeta::new(*payer, true),                   // 0. 支付者（signer）
+                AccountMeta::new(*user_token_account, false),     // 1. 关联代币账户
+                AccountMeta::new_readonly(*payer, false),         // 2. 拥有者
+                AccountMeta::new_readonly(*mint, false),          // 3. mint
+                AccountMeta::new_readonly(system_program_id, false), // 4. system_program
+                AccountMeta::new_readonly(*token_program, false), // 5. token_program (动态)
+            ],
+            data: vec![1], // 1 = CreateIdempotent 指令
+        })
+    }
+
+    /// 🔥 新增: 蜜罐检测 - 在一笔模拟交易中小额买入后立即全部卖出
+    ///
+    /// 卖出指令失败 → DangerousToken（买得进卖不出）；
+    /// 买入失败或模拟请求本身失败 → 无法判断，记录警告后继续买入
+    fn simulate_round_trip(
+        &self,
+        mint: &Pubkey,
+        pool: &PoolAccounts,
+        accounts: &BuyAccounts,
+        reserves: &PoolState,
+    ) -> BotResult<()> {
+        let probe_lamports = self.config.get_honeypot_probe_lamports();
+        let token_amount = self.dex.quote_buy(reserves, probe_lamports).amount_out;
+        if token_amount == 0 {
+            return Ok(());
+        }
+
+        let payer = self.payer.pubkey();
+        let user_token_account = self.dex.user_token_account(&payer, mint, &accounts.token_program);
+        let creator = accounts.pool.creator;
+        let swap_accounts = SwapAccounts {
+            payer,
+            mint: *mint,
+            token_program: accounts.token_program,
+            user_token_account,
+            pool,
+        };
+        // 转账手续费在买入到账时扣除，只能卖出实际到账的数量
+        let sell_amount = accounts.extensions.amount_after_transfer_fee(token_amount);
+
+        let instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(SIMULATION_COMPUTE_UNIT_LIMIT),
+            Self::build_create_ata_instruction(&payer, &user_token_account, mint, &accounts.token_program)?,
+            self.dex.build_buy_ix(
+                &swap_accounts,
+                token_amount,
+                Self::calculate_max_sol_cost_with_slippage(probe_lamports, self.config.slippage_percent),
+            )?,
+            // 只检测能否卖出，min_sol_output = 0
+            self.dex.build_sell_ix(&swap_accounts, sell_amount, 0)?,
+        ];
+        let sell_index = instructions.len() - 1;
+
+        // 不签名（sig_verify = false）：远程签名 / Ledger 不为模拟交易签名；blockhash 由节点替换，不额外请求
+        let message = VersionedMessage::V0(
+            v0::Message::try_compile(&payer, &instructions, &[], Hash::default())
+                .context("编译模拟交易失败")?,
+        );
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default(); message.header().num_required_signatures as usize],
+            message,
+        };
+
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(solana_commitment_config::CommitmentConfig::processed()),
+            ..Default::default()
+        };
+        let result = match self.rpc_client.simulate_transaction_with_config(&transaction, config) {
+            Ok(response) => response.value,
+            Err(e) => {
+                warn!("⚠️  蜜罐检测模拟失败，跳过检测: {}", e);
+                return Ok(());
+            }
+        };
+
+        let Some(err) = result.err else {
+            debug!("🍯 蜜罐检测通过: {}", mint);
+            return Ok(());
+        };
+
+        let err = TransactionError::from(err);
+        if Self::failed_instruction_index(&err) == Some(sell_index) {
+            return Err(BotError::DangerousToken {
+                reason: format!("模拟卖出失败（疑似蜜罐）: {:?}", err),
+                creator,
+            });
+        }
+
+        warn!("⚠️  蜜罐检测无法判断（模拟买入失败）: {:?}", err);
+        Ok(())
+    }
+
+    /// 失败的指令序号（非指令错误时为 None）
+    fn failed_instruction_index(err: &TransactionError) -> Option<usize> {
+        match err {
+            TransactionError::InstructionError(index, _) => Some(*index as usize),
+            _ => None,
+        }
     }
 
     /// 构建 VersionedTransaction
@@ -837,27 +1104,86 @@ impl LightSpeedBuyExecutor {
         Ok(transaction)
     }
 
-    /// 发送交易（优先级：SWQOS > LightSpeed）
+    /// 发送交易（按 send_backend_order 依次尝试：SWQOS / TPU 直发 / LightSpeed）
     async fn send_transaction_with_priority(&self, transaction: VersionedTransaction) -> Result<Signature> {
-        // 优先使用 SWQOS 田忌赛马
-        if let Some(swqos) = &self.swqos_manager {
-            info!("🏁 尝试使用 SWQOS 田忌赛马发送...");
-
-            match swqos.send_transaction_race(&transaction).await {
-                Ok(result) => {
-                    info!("✅ SWQOS 成功: {} ({}ms)", result.service_name, result.latency_ms);
-                    return result.signature.ok_or_else(|| anyhow::anyhow!("SWQOS 成功但无签名"));
+        let mut last_error = None;
+
+        for backend in &self.config.send_backend_order {
+            let result = match backend.as_str() {
+                "swqos" => {
+                    let Some(swqos) = &self.swqos_manager else { continue };
+                    info!("🏁 尝试使用 SWQOS 田忌赛马发送...");
+
+                    match swqos.send_transaction_race(&transaction).await {
+                        Ok(result) => {
+                            if result.already_processed {
+                                warn!("♻️  SWQOS {} 报告交易已处理（重复发送）", result.service_name);
+                            } else {
+                                info!("✅ SWQOS 成功: {} ({}ms)", result.service_name, result.latency_ms);
+                            }
+                            result.signature.ok_or_else(|| anyhow::anyhow!("SWQOS 成功但无签名"))
+                        }
+                        Err(e) => Err(BotError::Swqos(format!("{:#}", e)).into()),
+                    }
                 }
-                Err(e) => {
-                    warn!("⚠️  SWQOS 所有重试都失败: {}", e);
-                    warn!("   尝试使用 LightSpeed 保底...");
+                "tpu" => {
+                    let Some(tpu) = self.tpu_sender().await else { continue };
+                    info!("🛰️  尝试使用 TPU 直发...");
+                    tpu.send_transaction(&transaction).await
                 }
+                "lightspeed" => {
+                    info!("📡 使用 LightSpeed RPC 发送...");
+                    self.send_via_lightspeed(&transaction).await
+                }
+                _ => continue,
+            };
+
+            match result {
+                Ok(signature) => return Ok(signature),
+                Err(e) => match classify_send_error(&e) {
+                    SendErrorKind::AlreadyProcessed => {
+                        warn!("♻️  发送通道 {} 返回 AlreadyProcessed（重复发送，交易已处理）", backend);
+                        return Ok(transaction.signatures[0]);
+                    }
+                    SendErrorKind::BlockhashNotFound => {
+                        // 其他通道发送同一笔交易也会失败，直接返回由 execute_buy 重建
+                        warn!("⚠️  发送通道 {} blockhash 已失效，需要重建交易", backend);
+                        return Err(e);
+                    }
+                    SendErrorKind::Other => {
+                        warn!("⚠️  发送通道 {} 失败: {}，尝试下一个通道", backend, e);
+                        last_error = Some(e);
+                    }
+                },
             }
         }
 
-        // SWQOS 失败或未启用，使用 LightSpeed
-        info!("📡 使用 LightSpeed RPC 发送...");
-        self.send_via_lightspeed(&transaction).await
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("没有可用的发送通道")))
+    }
+
+    /// TPU 直发客户端（未启用或初始化失败时返回 None）
+    async fn tpu_sender(&self) -> Option<Arc<TpuSender>> {
+        if !self.config.tpu_direct_enabled {
+            return None;
+        }
+
+        self.tpu_sender
+            .get_or_init(|| async {
+                match TpuSender::new(&self.config, &self.rpc_pool).await {
+                    Ok(sender) => Some(Arc::new(sender)),
+                    Err(e) => {
+                        error!("❌ TPU 直发初始化失败，禁用 TPU 通道: {:?}", e);
+                        None
+                    }
+                }
+            })
+            .await
+            .clone()
+    }
+
+    /// 提前初始化 TPU 客户端（避免首笔买入承担建连延迟）
+    pub async fn warm_up_tpu(&self) {
+        let _ = self.tpu_sender().await;
     }
 
     /// 通过 LightSpeed RPC 发送交易
@@ -873,37 +1199,25 @@ impl LightSpeedBuyExecutor {
             &self.rpc_client
         };
 
-        // 重试发送
-        let max_attempts = 3;
-        for attempt in 1..=max_attempts {
-            debug!("🔄 发送尝试 {}/{}", attempt, max_attempts);
-
-            match rpc_to_use.send_transaction_with_config(
-                transaction,
-                solana_client::rpc_config::RpcSendTransactionConfig {
-                    skip_preflight: true,
-                    preflight_commitment: Some(solana_commitment_config::CommitmentLevel::Processed),
-                    max_retries: Some(3),
-                    ..Default::default()
-                },
-            ) {
-                Ok(sig) => {
-                    info!("✅ 发送成功 (尝试 {}): {}", attempt, sig);
-                    return Ok(sig);
-                }
-                Err(e) => {
-                    if attempt < max_attempts {
-                        warn!("⚠️  发送失败 (尝试 {}): {}", attempt, e);
-                        tokio::time::sleep(Duration::from_millis(500)).await;
-                    } else {
-                        error!("❌ 所有尝试都失败: {}", e);
-                        return Err(e.into());
-                    }
-                }
+        // 🔥 修复: 不再原样重发同一笔交易（blockhash 过期后会永久失败），重试由 execute_buy 用新 blockhash 重建
+        match rpc_to_use.send_transaction_with_config(
+            transaction,
+            solana_client::rpc_config::RpcSendTransactionConfig {
+                skip_preflight: true,
+                preflight_commitment: Some(solana_commitment_config::CommitmentLevel::Processed),
+                max_retries: Some(3),
+                ..Default::default()
+            },
+        ) {
+            Ok(sig) => {
+                info!("✅ 发送成功: {}", sig);
+                Ok(sig)
+            }
+            Err(e) => {
+                warn!("⚠️  发送失败 ({}): {}", signature, e);
+                Err(e.into())
             }
         }
-
-        Ok(signature)
     }
 }
 
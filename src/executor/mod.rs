@@ -1,6 +1,10 @@
 // 新的执行器（完整实现）
 pub mod lightspeed_buy;
 pub mod sol_trade_sell;
+pub mod retry;
+pub mod outbox;
+pub mod tpu_sender;
+pub mod wallet_sweep;
 
 // 交易构建器（仅用于估算）
 pub mod builder;
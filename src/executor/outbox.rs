@@ -0,0 +1,278 @@
+/// 交易发件箱（Outbox）
+///
+/// 每笔买入 / 卖出交易在发送前先把签名后的交易（连同用途和 mint）落盘，
+/// 执行流程结束后再移除；进程在发送或确认途中崩溃时，重启后按链上状态决定恢复还是放弃，
+/// 不会留下结果未知的交易
+///
+/// 核心功能:
+/// 1. 落盘 - 发送前写入 outbox_path（JSON，先写临时文件再重命名）
+/// 2. 完成 - 买入 / 卖出流程返回时（无论成功失败）移除本次流程的所有尝试
+/// 3. 启动恢复 - 已上链的记录结果；链上失败或 blockhash 已过期的放弃；
+///    blockhash 仍有效的原样重发（签名不变，不会重复成交）并短暂等待确认
+///
+/// 钱包清理交易不经过发件箱（只关闭空账户，下次清理会自然收敛）
+
+use anyhow::{Context, Result};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chrono::Utc;
+use log::{error, info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 重发后等待确认的最长时间
+const RESUME_CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 交易用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxPurpose {
+    Buy,
+    Sell,
+}
+
+/// 发件箱记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub signature: String,
+    pub purpose: OutboxPurpose,
+    pub mint: String,
+    /// bincode 序列化的 VersionedTransaction（base64）
+    pub transaction: String,
+    pub created_at: String,
+}
+
+/// 交易发件箱（未配置 outbox_path 时不记录）
+pub struct TransactionOutbox {
+    path: Option<PathBuf>,
+    entries: Mutex<Vec<OutboxEntry>>,
+}
+
+impl TransactionOutbox {
+    /// 打开发件箱文件（保留上次运行未完成的记录，等待 recover 处理）
+    pub fn open(path: Option<&str>) -> Result<Self> {
+        let path = path.filter(|p| !p.is_empty()).map(PathBuf::from);
+
+        let entries = match path.as_ref().filter(|p| p.exists()) {
+            Some(path) => {
+                let data = std::fs::read_to_string(path)
+                    .with_context(|| format!("读取交易发件箱失败: {}", path.display()))?;
+                let entries: Vec<OutboxEntry> = serde_json::from_str(&data)
+                    .with_context(|| format!("解析交易发件箱失败: {}", path.display()))?;
+                info!("📮 交易发件箱: {} ({} 条未完成)", path.display(), entries.len());
+                entries
+            }
+            None => {
+                if let Some(path) = &path {
+                    info!("📮 交易发件箱: {}", path.display());
+                }
+                Vec::new()
+            }
+        };
+
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// 开始一次买入 / 卖出流程（返回的批次在 drop 时移除本次流程的所有记录）
+    pub fn begin(self: &Arc<Self>, purpose: OutboxPurpose, mint: Pubkey) -> OutboxBatch {
+        OutboxBatch {
+            outbox: self.clone(),
+            purpose,
+            mint,
+            signatures: Vec::new(),
+        }
+    }
+
+    fn insert(&self, entry: OutboxEntry) {
+        if self.path.is_none() {
+            return;
+        }
+        let mut entries = self.entries.lock();
+        entries.push(entry);
+        self.persist(&entries);
+    }
+
+    fn complete(&self, signatures: &[Signature]) {
+        if self.path.is_none() || signatures.is_empty() {
+            return;
+        }
+        let signatures: Vec<String> = signatures.iter().map(|signature| signature.to_string()).collect();
+        let mut entries = self.entries.lock();
+        entries.retain(|entry| !signatures.contains(&entry.signature));
+        self.persist(&entries);
+    }
+
+    /// 启动恢复：按链上状态处理上次运行未完成的交易
+    pub async fn recover(&self, rpc_client: &RpcClient) -> Result<()> {
+        let pending = self.entries.lock().clone();
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        warn!("📮 发现 {} 笔上次运行未完成的交易，检查链上状态...", pending.len());
+
+        let signatures: Vec<Signature> = pending
+            .iter()
+            .map(|entry| Signature::from_str(&entry.signature).context("解析发件箱签名失败"))
+            .collect::<Result<_>>()?;
+        let statuses = rpc_client
+            .get_signature_statuses(&signatures)
+            .context("查询发件箱交易状态失败")?
+            .value;
+
+        let mut landed = Vec::new();
+        for ((entry, signature), status) in pending.iter().zip(&signatures).zip(statuses) {
+            match status {
+                Some(status) if status.err.is_none() => {
+                    info!("   ✅ {:?} {} 已上链: {}", entry.purpose, entry.mint, signature);
+                    landed.push(entry);
+                }
+                Some(status) => {
+                    warn!("   ❌ {:?} {} 链上执行失败，放弃: {} ({:?})",
+                        entry.purpose, entry.mint, signature, status.err);
+                }
+                None => {
+                    if self.resume(rpc_client, entry, signature).await {
+                        landed.push(entry);
+                    }
+                }
+            }
+        }
+
+        if landed.iter().any(|entry| entry.purpose == OutboxPurpose::Buy) {
+            warn!("📮 有买入在崩溃前后上链，持仓将由未记录 token 恢复（recover_unknown_tokens）处理");
+        }
+
+        self.complete(&signatures);
+        info!("📮 交易发件箱恢复完成");
+        Ok(())
+    }
+
+    /// 未查到状态的交易：blockhash 仍有效时原样重发并等待确认，否则放弃（已不可能上链）
+    async fn resume(&self, rpc_client: &RpcClient, entry: &OutboxEntry, signature: &Signature) -> bool {
+        let transaction = match Self::decode_transaction(&entry.transaction) {
+            Ok(transaction) => transaction,
+            Err(e) => {
+                error!("   ❌ {:?} {} 解析交易失败，放弃: {:?}", entry.purpose, entry.mint, e);
+                return false;
+            }
+        };
+
+        let blockhash = transaction.message.recent_blockhash();
+        let valid = rpc_client
+            .is_blockhash_valid(blockhash, CommitmentConfig::processed())
+            .unwrap_or(false);
+        if !valid {
+            info!("   ⌛ {:?} {} blockhash 已过期，放弃: {}", entry.purpose, entry.mint, signature);
+            return false;
+        }
+
+        info!("   🔁 {:?} {} blockhash 仍有效，重发: {}", entry.purpose, entry.mint, signature);
+        let config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            ..Default::default()
+        };
+        if let Err(e) = rpc_client.send_transaction_with_config(&transaction, config) {
+            warn!("   ⚠️  重发失败，放弃: {}", e);
+            return false;
+        }
+
+        let deadline = tokio::time::Instant::now() + RESUME_CONFIRM_TIMEOUT;
+        while tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            let status = rpc_client
+                .get_signature_statuses(std::slice::from_ref(signature))
+                .ok()
+                .and_then(|statuses| statuses.value.into_iter().next().flatten());
+            match status {
+                Some(status) if status.err.is_none() => {
+                    info!("   ✅ 重发的交易已上链: {}", signature);
+                    return true;
+                }
+                Some(status) => {
+                    warn!("   ❌ 重发的交易链上执行失败: {} ({:?})", signature, status.err);
+                    return false;
+                }
+                None => continue,
+            }
+        }
+
+        warn!("   ⏰ 重发的交易 {} 秒内未确认，放弃: {}", RESUME_CONFIRM_TIMEOUT.as_secs(), signature);
+        false
+    }
+
+    fn encode_transaction(transaction: &VersionedTransaction) -> Result<String> {
+        let bytes = bincode::serialize(transaction).context("序列化交易失败")?;
+        Ok(STANDARD.encode(bytes))
+    }
+
+    fn decode_transaction(encoded: &str) -> Result<VersionedTransaction> {
+        let bytes = STANDARD.decode(encoded).context("base64 解码失败")?;
+        bincode::deserialize(&bytes).context("反序列化交易失败")
+    }
+
+    fn persist(&self, entries: &[OutboxEntry]) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = serde_json::to_string_pretty(entries)
+            .context("序列化交易发件箱失败")
+            .and_then(|data| {
+                let tmp = path.with_extension("tmp");
+                std::fs::write(&tmp, data).context("写入临时文件失败")?;
+                std::fs::rename(&tmp, path).context("重命名发件箱文件失败")
+            });
+
+        if let Err(e) = result {
+            warn!("⚠️  保存交易发件箱失败: {:?}", e);
+        }
+    }
+}
+
+/// 一次买入 / 卖出流程中发送的交易（drop 时从发件箱移除）
+pub struct OutboxBatch {
+    outbox: Arc<TransactionOutbox>,
+    purpose: OutboxPurpose,
+    mint: Pubkey,
+    signatures: Vec<Signature>,
+}
+
+impl OutboxBatch {
+    /// 发送前落盘（序列化失败时只记录警告，不阻止发送）
+    pub fn record(&mut self, transaction: &VersionedTransaction) {
+        let signature = transaction.signatures[0];
+        self.signatures.push(signature);
+
+        if self.outbox.path.is_none() {
+            return;
+        }
+
+        match TransactionOutbox::encode_transaction(transaction) {
+            Ok(encoded) => self.outbox.insert(OutboxEntry {
+                signature: signature.to_string(),
+                purpose: self.purpose,
+                mint: self.mint.to_string(),
+                transaction: encoded,
+                created_at: Utc::now().to_rfc3339(),
+            }),
+            Err(e) => warn!("⚠️  交易写入发件箱失败 {}: {:?}", signature, e),
+        }
+    }
+}
+
+impl Drop for OutboxBatch {
+    fn drop(&mut self) {
+        self.outbox.complete(&self.signatures);
+    }
+}
@@ -0,0 +1,75 @@
+/// 交易重试策略
+///
+/// 每次重试都重新构建交易（新 blockhash），并可按倍数逐次提高 CU 价格和 tip
+///
+/// 核心功能:
+/// 1. 最大尝试次数
+/// 2. CU 价格递增（带上限）
+/// 3. tip 递增倍数
+/// 4. 替换指令列表中的 SetComputeUnitPrice
+///
+/// 发送错误分类见 error.rs（SendErrorKind）
+
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+
+use crate::config::Config;
+
+/// ComputeBudgetInstruction::SetComputeUnitPrice 的指令标签
+const SET_COMPUTE_UNIT_PRICE_TAG: u8 = 3;
+
+/// 重试费用策略
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// 最大尝试次数（含第一次）
+    pub max_attempts: u32,
+    /// 基础 CU 价格（micro-lamports）
+    pub base_compute_unit_price: u64,
+    /// 每次重试的费用倍数（1.0 = 不递增）
+    pub fee_multiplier: f64,
+    /// CU 价格上限（0 = 不限制）
+    pub max_compute_unit_price: u64,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_attempts: config.tx_retry_max_attempts,
+            base_compute_unit_price: config.compute_unit_price,
+            fee_multiplier: config.retry_fee_multiplier,
+            max_compute_unit_price: config.retry_max_compute_unit_price,
+        }
+    }
+
+    /// 第 attempt 次尝试（从 1 开始）的费用倍数
+    pub fn fee_scale(&self, attempt: u32) -> f64 {
+        self.fee_multiplier.powi(attempt.saturating_sub(1) as i32)
+    }
+
+    /// 第 attempt 次尝试的 CU 价格
+    pub fn compute_unit_price(&self, attempt: u32) -> u64 {
+        let price = (self.base_compute_unit_price as f64 * self.fee_scale(attempt)) as u64;
+        if self.max_compute_unit_price > 0 {
+            price.min(self.max_compute_unit_price.max(self.base_compute_unit_price))
+        } else {
+            price
+        }
+    }
+
+    /// 第 attempt 次尝试的 tip 金额
+    pub fn scale_tip(&self, tip_lamports: u64, attempt: u32) -> u64 {
+        (tip_lamports as f64 * self.fee_scale(attempt)) as u64
+    }
+}
+
+/// 将指令列表中的 SetComputeUnitPrice 替换为新的价格
+pub fn set_compute_unit_price(instructions: &mut [Instruction], price: u64) {
+    let replacement = ComputeBudgetInstruction::set_compute_unit_price(price);
+    for ix in instructions.iter_mut() {
+        if ix.program_id == replacement.program_id
+            && ix.data.first() == Some(&SET_COMPUTE_UNIT_PRICE_TAG)
+        {
+            *ix = replacement.clone();
+        }
+    }
+}
@@ -6,46 +6,42 @@
 /// 核心功能:
 /// 1. TradeSellParams 完整参数构建
 /// 2. PumpFunParams::immediate_sell 逻辑
-/// 3. 卖出指令构建
+/// 3. 卖出指令构建（通过 DexAdapter，当前为 PumpFun）
 /// 4. 滑点控制
 /// 5. Token 账户关闭选项
 /// 6. 交易确认等待
 
 use anyhow::{Context, Result};
 use log::{debug, info, warn, error};
-use solana_client::rpc_client::RpcClient;
-use solana_commitment_config::CommitmentConfig;
 use solana_compute_budget_interface::ComputeBudgetInstruction;
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
-    signature::{Keypair, Signature},
+    signature::Signature,
     signer::Signer,
-    transaction::Transaction,
+    transaction::{Transaction, VersionedTransaction},
 };
 use std::sync::Arc;
-use std::time::{Duration, Instant};
 
+use crate::chaos::ChaosInjector;
 use crate::config::Config;
-
-// PumpFun 程序常量
-#[allow(dead_code)]
-const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
-const PUMPFUN_GLOBAL: &str = "4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf";
-// 🔥 修复: FEE_RECIPIENT 应该是 62qc2CNXwrYqQScmEdiZFFAnJR262PxWEuNQtxfafNgV
-// 参考: sol-trade-sdk/src/instruction/utils/pumpfun.rs:54
-const PUMPFUN_FEE_RECIPIENT: &str = "62qc2CNXwrYqQScmEdiZFFAnJR262PxWEuNQtxfafNgV";
-const PUMPFUN_EVENT_AUTHORITY: &str = "Ce6TQqeHC9p8KetsN6JsjHK7UTZk7nasjjnr7XxXp9F1";
+use crate::confirmation::{ConfirmationStatus, ConfirmationTracker};
+use crate::dex::{DexAdapter, PoolAccounts, PoolState, SwapAccounts};
+use crate::error::{classify_send_error, BotError, BotResult, SendErrorKind};
+use crate::executor::outbox::{OutboxBatch, OutboxPurpose, TransactionOutbox};
+use crate::executor::retry::{self, RetryPolicy};
+use crate::rpc_api::RpcApi;
+use crate::rpc_pool::RpcPool;
+use crate::signer::SharedSigner;
+use crate::spend_guard::{system_outflow, SpendGuard, SpendTicket};
+use crate::token_extensions::MintExtensions;
+
+// Token program 常量（PumpFun 协议常量见 dex::pumpfun）
 const SYSTEM_TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";  // 🔥 新增: Token-2022
-const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
-// 🔥 修复: 对齐 sol-trade-sdk 的常量值
-// 参考: sol-trade-sdk/src/instruction/utils/pumpfun.rs:106-111
-const FEE_CONFIG: &str = "8Wf5TiAheLUqBrKXeYg2JtAFFMWtKdG2BSFgqUcPVwTt";
-const FEE_PROGRAM: &str = "pfeeUxB6jkeY1Hxd7CsFCAjcbHA9rWtchMGdZ6VojVZ";
 
-// Sell 指令鉴别器 (discriminator)
-const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+/// 每个签名的基础网络费
+const BASE_SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
 
 /// 卖出参数
 /// 
@@ -62,21 +58,16 @@ pub struct SellParams {
     pub wait_transaction_confirmed: bool,
     /// 是否关闭 token 账户
     pub close_token_account: bool,
-    /// PumpFun 特定参数
-    pub pumpfun_params: PumpFunSellParams,
+    /// 池子账户（DEX 适配器派生，PumpFun 为 bonding curve / associated bonding curve / creator vault）
+    pub pool: PoolAccounts,
 }
 
-/// PumpFun 卖出特定参数
-/// 
-/// 参考 sol-trade-sdk 的 PumpFunParams::immediate_sell
-#[derive(Clone, Debug)]
-pub struct PumpFunSellParams {
-    /// Bonding curve 地址
-    pub bonding_curve: Pubkey,
-    /// Associated bonding curve 地址
-    pub associated_bonding_curve: Pubkey,
-    /// Creator vault 地址
-    pub creator_vault: Pubkey,
+/// 卖出结果
+#[derive(Debug, Clone, Copy)]
+pub struct SellReceipt {
+    pub signature: Signature,
+    /// 卖出交易的网络费 + 优先费（lamports，按实际发送的那次尝试计算；卖出不附带 relay tip）
+    pub fee_lamports: u64,
 }
 
 /// SolTrade 卖出执行器
@@ -85,46 +76,67 @@ pub struct PumpFunSellParams {
 pub struct SolTradeSellExecutor {
     config: Arc<Config>,
     /// RPC 客户端
-    rpc_client: Arc<RpcClient>,
+    rpc_client: Arc<dyn RpcApi>,
     /// 支付账户
-    pub payer: Arc<Keypair>,
-    /// PumpFun 程序地址
-    pumpfun_program: Pubkey,
-    /// PumpFun 全局账户
-    global: Pubkey,
-    /// PumpFun 费用接收账户
-    fee_recipient: Pubkey,
-    /// PumpFun 事件权限账户
-    event_authority: Pubkey,
+    pub payer: SharedSigner,
+    /// DEX 协议适配器（账户派生 / 报价 / 买卖指令）
+    dex: Arc<dyn DexAdapter>,
+    /// 交易确认追踪
+    confirmations: Arc<ConfirmationTracker>,
+    /// 交易发件箱（发送前落盘，崩溃后可恢复）
+    outbox: Arc<TransactionOutbox>,
+    /// 支出限额守卫（卖出只检查单笔上限）
+    spend_guard: Arc<SpendGuard>,
 }
 
 impl SolTradeSellExecutor {
     /// 创建新的 SolTrade 卖出执行器
-    pub fn new(config: Arc<Config>, payer: Arc<Keypair>) -> Result<Self> {
-        let rpc_client = Arc::new(RpcClient::new_with_commitment(
-            config.rpc_endpoint.clone(),
-            CommitmentConfig::confirmed(),
-        ));
-        
+    pub fn new(
+        config: Arc<Config>,
+        payer: SharedSigner,
+        confirmations: Arc<ConfirmationTracker>,
+        outbox: Arc<TransactionOutbox>,
+        rpc_pool: Arc<RpcPool>,
+        dex: Arc<dyn DexAdapter>,
+        spend_guard: Arc<SpendGuard>,
+    ) -> Result<Self> {
+        let rpc_client: Arc<dyn RpcApi> = Arc::new(rpc_pool.client(config.get_send_commitment()));
+        // 🔥 新增: 故障注入（chaos feature）随机返回过期 blockhash
+        let rpc_client = ChaosInjector::from_config(&config).wrap_rpc(rpc_client);
+        Self::with_rpc(config, payer, confirmations, outbox, rpc_client, dex, spend_guard)
+    }
+
+    /// 使用指定的 RPC 实现创建（测试注入模拟 RPC）
+    pub fn with_rpc(
+        config: Arc<Config>,
+        payer: SharedSigner,
+        confirmations: Arc<ConfirmationTracker>,
+        outbox: Arc<TransactionOutbox>,
+        rpc_client: Arc<dyn RpcApi>,
+        dex: Arc<dyn DexAdapter>,
+        spend_guard: Arc<SpendGuard>,
+    ) -> Result<Self> {
         info!("💰 SolTrade 卖出执行器已初始化");
         info!("   RPC 端点: {}", config.rpc_endpoint);
         info!("   钱包地址: {}", payer.pubkey());
+        info!("   DEX: {}", dex.name());
         
         Ok(Self {
             config,
             rpc_client,
             payer,
-            pumpfun_program: Pubkey::try_from(PUMPFUN_PROGRAM_ID)
-                .context("Invalid PumpFun program ID")?,
-            global: Pubkey::try_from(PUMPFUN_GLOBAL)
-                .context("Invalid global account")?,
-            fee_recipient: Pubkey::try_from(PUMPFUN_FEE_RECIPIENT)
-                .context("Invalid fee recipient")?,
-            event_authority: Pubkey::try_from(PUMPFUN_EVENT_AUTHORITY)
-                .context("Invalid event authority")?,
+            dex,
+            confirmations,
+            outbox,
+            spend_guard,
         })
     }
 
+    /// 执行器使用的 RPC（持仓管理器的交易确认轮询共用，回放时为模拟链）
+    pub fn rpc_client(&self) -> Arc<dyn RpcApi> {
+        self.rpc_client.clone()
+    }
+
     /// 执行卖出操作
     ///
     /// 参考 sol-trade-sdk 的完整流程:
@@ -132,7 +144,7 @@ impl SolTradeSellExecutor {
     /// 2. 构建卖出指令
     /// 3. 发送交易（带重试机制）
     /// 4. 等待确认（如果需要）
-    pub async fn execute_sell(&self, params: SellParams) -> Result<Signature> {
+    pub async fn execute_sell(&self, params: SellParams) -> BotResult<SellReceipt> {
         info!("═══════════════════════════════════════════════════════");
         info!("💸 开始执行 SolTrade 卖出");
         info!("   Token Mint: {}", params.mint);
@@ -146,8 +158,19 @@ impl SolTradeSellExecutor {
 
         info!("📦 卖出指令已构建，共 {} 条指令", instructions.len());
 
-        // 2. 发送交易（带重试机制）
-        let signature = self.send_transaction_with_retry(instructions).await?;
+        // 🔥 新增: 支出限额（按最后一次尝试的 CU 价格计算最坏支出，第一次签名前按最大尝试次数预留）
+        let policy = RetryPolicy::from_config(&self.config);
+        let mut spend = self.spend_guard.begin_exit(params.mint);
+        spend.reserve(
+            system_outflow(&instructions, &self.payer.pubkey()) + self.attempt_fee(&policy, policy.max_attempts),
+            policy.max_attempts,
+        )?;
+
+        // 2. 发送交易（带重试机制，发送前写入发件箱，确认等待结束后移除）
+        let mut outbox = self.outbox.begin(OutboxPurpose::Sell, params.mint);
+        let (signature, fee_lamports) = self
+            .send_transaction_with_retry(instructions, &policy, &mut outbox, &mut spend)
+            .await?;
 
         info!("✅ 卖出交易已发送: {}", signature);
 
@@ -162,87 +185,58 @@ impl SolTradeSellExecutor {
             }
         }
 
-        Ok(signature)
+        Ok(SellReceipt { signature, fee_lamports })
     }
 
     /// 构建卖出指令
     /// 
     /// 参考 sol-trade-sdk 的指令构建逻辑:
     /// 1. ComputeBudget 指令
-    /// 2. PumpFun 卖出指令
+    /// 2. DEX 卖出指令（PumpFun 账户顺序见 dex::pumpfun）
     /// 3. 关闭 token 账户指令（如果需要）
     fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
         let mut instructions = Vec::new();
         let payer = self.payer.pubkey();
 
-        info!("🏗️  开始构建 PumpFun 卖出指令");
-        debug!("   Bonding Curve: {}", params.pumpfun_params.bonding_curve);
-        debug!("   Associated Bonding Curve: {}", params.pumpfun_params.associated_bonding_curve);
-        debug!("   Creator Vault: {}", params.pumpfun_params.creator_vault);
+        info!("🏗️  开始构建 {} 卖出指令", self.dex.name());
+        debug!("   Pool: {}", params.pool.pool);
+        debug!("   Pool Token Account: {}", params.pool.pool_token_account);
+        debug!("   Creator Vault: {}", params.pool.creator_vault);
 
-        // 2. 构建 PumpFun 卖出指令
-        debug!("🏗️  构建 PumpFun 卖出指令");
-        
         // 获取用户 token 账户地址
-        let user_token_account = Self::get_associated_token_address(&payer, &params.mint);
+        // 🔥 修复: 按 mint 实际的 token program 派生 ATA（Token-2022 的 ATA 地址不同，否则卖出和关闭账户都会失败）
+        let (token_program, extensions) = self.mint_info(&params.mint)?;
+        let user_token_account = self.dex.user_token_account(&payer, &params.mint, &token_program);
         debug!("   用户 Token 账户: {}", user_token_account);
+        if let Some(program) = extensions.transfer_hook_program {
+            warn!("⚠️  {} 带有转账 hook ({})，卖出可能失败", params.mint, program);
+        }
         
         // 计算最小输出金额（考虑滑点）
+        // 🔥 新增: Token-2022 转账手续费在转入曲线时扣除，按扣费后的数量估算（保守）
         let slippage_bps = params.slippage_basis_points.unwrap_or(300); // 默认 3%
         let min_sol_output = self.calculate_min_sol_output(
-            params.input_token_amount,
+            extensions.amount_after_transfer_fee(params.input_token_amount),
             slippage_bps,
-            &params.pumpfun_params,
+            &params.pool,
         )?;
         
         debug!("   最小输出: {} lamports (滑点 {} bps)", min_sol_output, slippage_bps);
-        
-        // 构建指令数据
-        // 格式: [discriminator(8), amount(8), min_sol_output(8)]
-        let mut instruction_data = Vec::with_capacity(24);
-        instruction_data.extend_from_slice(&SELL_DISCRIMINATOR);
-        instruction_data.extend_from_slice(&params.input_token_amount.to_le_bytes());
-        instruction_data.extend_from_slice(&min_sol_output.to_le_bytes());
-        
-        // 构建账户列表（完全参考 sol-trade-sdk 的顺序）
-        let accounts = vec![
-            AccountMeta::new_readonly(self.global, false),                          // 0: global
-            AccountMeta::new(self.fee_recipient, false),                            // 1: fee_recipient
-            AccountMeta::new_readonly(params.mint, false),                          // 2: mint
-            AccountMeta::new(params.pumpfun_params.bonding_curve, false),           // 3: bonding_curve
-            AccountMeta::new(params.pumpfun_params.associated_bonding_curve, false), // 4: associated_bonding_curve
-            AccountMeta::new(user_token_account, false),                            // 5: user_token_account
-            AccountMeta::new(payer, true),                                          // 6: payer (signer)
-            AccountMeta::new_readonly(Pubkey::try_from(SYSTEM_PROGRAM).unwrap(), false), // 7: system_program
-            AccountMeta::new(params.pumpfun_params.creator_vault, false),           // 8: creator_vault ⭐
-            AccountMeta::new_readonly(Pubkey::try_from(SYSTEM_TOKEN_PROGRAM).unwrap(), false), // 9: token_program ⭐
-            AccountMeta::new_readonly(self.event_authority, false),                 // 10: event_authority
-            AccountMeta::new_readonly(self.pumpfun_program, false),                 // 11: pumpfun_program
-            AccountMeta::new_readonly(Pubkey::try_from(FEE_CONFIG).unwrap(), false), // 12: fee_config ⭐
-            AccountMeta::new_readonly(Pubkey::try_from(FEE_PROGRAM).unwrap(), false), // 13: fee_program ⭐
-        ];
-
-        // 🔥 排障日志: 打印关键账户表摘要
-        debug!("📋 PumpFun 卖出账户表摘要 (14 accounts):");
-        debug!("   [0] global: {} (readonly)", self.global);
-        debug!("   [1] fee_recipient: {} (writable)", self.fee_recipient);
-        debug!("   [8] creator_vault: {} (writable) ⭐", params.pumpfun_params.creator_vault);
-        debug!("   [9] token_program: {} (readonly, Token v3) ⭐",
-            Pubkey::try_from(SYSTEM_TOKEN_PROGRAM).unwrap()
-        );
-        debug!("   [12] fee_config: {} (readonly) ⭐", Pubkey::try_from(FEE_CONFIG).unwrap());
-        debug!("   [13] fee_program: {} (readonly) ⭐", Pubkey::try_from(FEE_PROGRAM).unwrap());
 
-        instructions.push(Instruction {
-            program_id: self.pumpfun_program,
-            accounts,
-            data: instruction_data,
-        });
+        // 2. 构建 DEX 卖出指令
+        let swap_accounts = SwapAccounts {
+            payer,
+            mint: params.mint,
+            token_program,
+            user_token_account,
+            pool: &params.pool,
+        };
+        instructions.push(self.dex.build_sell_ix(&swap_accounts, params.input_token_amount, min_sol_output)?);
 
         // 3. 关闭 token 账户指令（如果需要）
         if params.close_token_account {
             debug!("🗑️  添加关闭 Token 账户指令");
-            instructions.push(self.build_close_account_instruction(&user_token_account, &params.mint)?);
+            instructions.push(self.build_close_account_instruction(&user_token_account, &token_program));
         }
 
         // 1. 添加计算预算指令（最后插入到开头，完全参考 lightspeed-examples 的 unshift 逻辑）
@@ -262,59 +256,43 @@ impl SolTradeSellExecutor {
 
     /// 计算最小输出金额（考虑滑点）
     ///
-    /// 完全对齐 sol-trade-sdk 的 BondingCurveAccount::get_sell_price 实现
+    /// 按链上池子储备报价（PumpFun 对齐 sol-trade-sdk 的 BondingCurveAccount::get_sell_price）
     fn calculate_min_sol_output(
         &self,
         token_amount: u64,
         slippage_bps: u64,
-        params: &PumpFunSellParams,
+        pool: &PoolAccounts,
     ) -> Result<u64> {
-        // 尝试从 bonding curve 读取真实储备量
-        match self.get_bonding_curve_reserves(&params.bonding_curve) {
-            Ok((virtual_token_reserves, virtual_sol_reserves)) => {
-                if virtual_token_reserves > 0 && virtual_sol_reserves > 0 {
-                    // 完全对齐 sol-trade-sdk 的 get_sell_price 实现
-                    // 🔥 修复: 使用正确的费率 FEE_BASIS_POINTS=95 + CREATOR_FEE=30
-                    // 参考: sol-trade-sdk/src/common/bonding_curve.rs:152-169
-
-                    const FEE_BASIS_POINTS: u128 = 95;     // 0.95%
-                    const CREATOR_FEE: u128 = 30;          // 0.30%
-                    let total_fee_basis_points = FEE_BASIS_POINTS + CREATOR_FEE;  // 1.25%
-
-                    // Calculate the proportional amount of virtual sol reserves to be received using u128
-                    let n: u128 = ((token_amount as u128) * (virtual_sol_reserves as u128))
-                        / ((virtual_token_reserves as u128) + (token_amount as u128));
-
-                    // Calculate the fee amount in the same units
-                    let a: u128 = (n * total_fee_basis_points) / 10000;
-
-                    // 🔥 修复: 安全转换，避免溢出
-                    // Return the net amount after deducting the fee
-                    let estimated_output_u128 = n.saturating_sub(a);
-                    let estimated_output = estimated_output_u128.min(u64::MAX as u128) as u64;
-
-                    // 应用滑点（使用 u128 计算后再转换）
-                    let slippage_multiplier = 10000 - slippage_bps;
-                    let min_output_u128 = estimated_output_u128
-                        .saturating_mul(slippage_multiplier as u128)
-                        .checked_div(10000)
-                        .unwrap_or(0);
-                    let min_output = min_output_u128.min(u64::MAX as u128) as u64;
-
-                    debug!("💱 sol-trade-sdk get_sell_price: {} tokens -> {} SOL (after 1.25% fee)",
-                        token_amount,
-                        estimated_output as f64 / 1_000_000_000.0
-                    );
-                    debug!("   应用 {}% 滑点 -> min {} SOL",
-                        slippage_bps as f64 / 100.0,
-                        min_output as f64 / 1_000_000_000.0
-                    );
-
-                    return Ok(min_output);
-                }
+        // 尝试从池子账户读取真实储备量
+        match self.get_pool_state(&pool.pool) {
+            Ok(state) if state.virtual_token_reserves > 0 && state.virtual_sol_reserves > 0 => {
+                let quote = self.dex.quote_sell(&state, token_amount);
+
+                // 应用滑点（使用 u128 计算后再转换）
+                let slippage_multiplier = 10000 - slippage_bps;
+                let min_output_u128 = (quote.amount_out as u128)
+                    .saturating_mul(slippage_multiplier as u128)
+                    .checked_div(10000)
+                    .unwrap_or(0);
+                let min_output = min_output_u128.min(u64::MAX as u128) as u64;
+
+                debug!("💱 {} 卖出报价: {} tokens -> {} SOL (手续费 {} lamports, 价格冲击 {:.2}%)",
+                    self.dex.name(),
+                    token_amount,
+                    quote.amount_out as f64 / 1_000_000_000.0,
+                    quote.fee_lamports,
+                    quote.price_impact_percent
+                );
+                debug!("   应用 {}% 滑点 -> min {} SOL",
+                    slippage_bps as f64 / 100.0,
+                    min_output as f64 / 1_000_000_000.0
+                );
+
+                return Ok(min_output);
             }
+            Ok(_) => {}
             Err(e) => {
-                warn!("⚠️  无法读取 bonding curve 储备量: {}, 使用保守估计", e);
+                warn!("⚠️  无法读取池子储备量: {}, 使用保守估计", e);
             }
         }
 
@@ -337,54 +315,37 @@ impl SolTradeSellExecutor {
         Ok(min_output)
     }
 
-    /// 从 bonding curve 账户读取储备量
-    fn get_bonding_curve_reserves(&self, bonding_curve: &Pubkey) -> Result<(u64, u64)> {
-        let data = self.rpc_client.get_account_data(bonding_curve)
-            .context("读取 bonding curve 账户失败")?;
-
-        if data.len() >= 24 {
-            // PumpFun bonding curve 数据格式:
-            // - virtual_token_reserves: u64 (offset 8)
-            // - virtual_sol_reserves: u64 (offset 16)
-            let virtual_token_reserves = u64::from_le_bytes(
-                data[8..16].try_into().unwrap_or([0u8; 8])
-            );
-            let virtual_sol_reserves = u64::from_le_bytes(
-                data[16..24].try_into().unwrap_or([0u8; 8])
-            );
-
-            Ok((virtual_token_reserves, virtual_sol_reserves))
-        } else {
-            Err(anyhow::anyhow!("Bonding curve 数据长度不足"))
-        }
+    /// 从池子账户读取储备量（由 DEX 适配器解码）
+    fn get_pool_state(&self, pool: &Pubkey) -> Result<PoolState> {
+        let data = self.rpc_client.get_account_data(pool)
+            .context("读取池子账户失败")?;
+
+        self.dex.decode_pool(&data)
+            .ok_or_else(|| anyhow::anyhow!("解码 {} 池子账户失败", self.dex.name()))
     }
 
-    /// 构建关闭账户指令
+    /// 构建关闭账户指令（ATA 租金退回钱包）
     /// 🔥 修复: 支持 Token-2022
-    fn build_close_account_instruction(&self, token_account: &Pubkey, mint: &Pubkey) -> Result<Instruction> {
-        // 🔥 新增: 检测 token program（支持 Token-2022）
-        let token_program = self.detect_token_program(mint)?;
-
+    fn build_close_account_instruction(&self, token_account: &Pubkey, token_program: &Pubkey) -> Instruction {
         let accounts = vec![
             AccountMeta::new(*token_account, false),
             AccountMeta::new(self.payer.pubkey(), false),
             AccountMeta::new_readonly(self.payer.pubkey(), true),
         ];
 
-        let instruction = Instruction {
-            program_id: token_program,  // 🔥 使用动态检测的 token program
+        Instruction {
+            program_id: *token_program,  // 🔥 使用动态检测的 token program
             accounts,
             data: vec![9], // CloseAccount 指令索引
-        };
-
-        Ok(instruction)
+        }
     }
 
-    /// 🔥 新增: 检测 mint 的 token program（支持 Token-2022）
-    fn detect_token_program(&self, mint: &Pubkey) -> Result<Pubkey> {
+    /// 🔥 新增: 检测 mint 的 token program（支持 Token-2022）并解析 mint 扩展
+    fn mint_info(&self, mint: &Pubkey) -> Result<(Pubkey, MintExtensions)> {
         // 读取 mint 账户
         let account = self.rpc_client.get_account(mint)
             .context("读取 mint 账户失败")?;
+        let extensions = MintExtensions::parse(&account.owner, &account.data);
 
         // 检查 owner（即 token program）
         let token_program = account.owner;
@@ -392,58 +353,80 @@ impl SolTradeSellExecutor {
         let token_2022 = Pubkey::try_from(TOKEN_2022_PROGRAM)?;
         let token_v3 = Pubkey::try_from(SYSTEM_TOKEN_PROGRAM)?;
 
-        if token_program == token_2022 {
+        let token_program = if token_program == token_2022 {
             debug!("🔍 检测到 Token-2022: {}", mint);
-            Ok(token_2022)
+            token_2022
         } else if token_program == token_v3 {
             debug!("🔍 检测到 Token v3: {}", mint);
-            Ok(token_v3)
+            token_v3
         } else {
             warn!("⚠️  未知 token program: {}", token_program);
-            Ok(token_v3) // fallback to v3
-        }
+            token_v3 // fallback to v3
+        };
+
+        Ok((token_program, extensions))
     }
 
-    /// 获取 Associated Token Address
-    fn get_associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
-        let token_program_id = Pubkey::try_from("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")
-            .expect("Invalid TOKEN_PROGRAM_ID");
-
-        let associated_token_program_id = Pubkey::try_from("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL")
-            .expect("Invalid ASSOCIATED_TOKEN_PROGRAM_ID");
-
-        Pubkey::find_program_address(
-            &[
-                wallet.as_ref(),
-                token_program_id.as_ref(),
-                mint.as_ref(),
-            ],
-            &associated_token_program_id,
-        )
-        .0
+    /// 钱包在该 mint 下的 ATA 地址和 token program
+    fn user_token_account(&self, mint: &Pubkey) -> Result<(Pubkey, Pubkey)> {
+        let (token_program, _) = self.mint_info(mint)?;
+        let token_account = self.dex.user_token_account(&self.payer.pubkey(), mint, &token_program);
+        Ok((token_account, token_program))
     }
 
     /// 发送交易（带重试机制）
     ///
-    /// 最多重试 3 次
-    async fn send_transaction_with_retry(&self, instructions: Vec<Instruction>) -> Result<Signature> {
-        let max_attempts = 3;
+    /// 最多尝试 tx_retry_max_attempts 次，每次使用新 blockhash 重新签名，
+    /// 并按 retry_fee_multiplier 提高 CU 价格；重试前先检查之前的签名是否已上链，避免重复卖出
+    ///
+    /// 返回发送成功的签名和该次尝试的交易费（lamports）
+    async fn send_transaction_with_retry(
+        &self,
+        mut instructions: Vec<Instruction>,
+        policy: &RetryPolicy,
+        outbox: &mut OutboxBatch,
+        spend: &mut SpendTicket,
+    ) -> Result<(Signature, u64)> {
+        let max_attempts = policy.max_attempts;
+        let mut attempted: Vec<Signature> = Vec::new();
+        let attempt_fee = |attempt: u32| self.attempt_fee(policy, attempt);
 
         for attempt in 1..=max_attempts {
-            info!("📤 发送卖出交易 (尝试 {}/{})", attempt, max_attempts);
+            if let Some(landed) = self.find_landed_signature(&attempted) {
+                info!("✅ 之前的卖出交易已上链: {}", landed);
+                let landed_attempt = attempted.iter().position(|s| *s == landed).unwrap_or(0) as u32 + 1;
+                return Ok((landed, attempt_fee(landed_attempt)));
+            }
 
-            match self.send_transaction(instructions.clone()).await {
+            let compute_unit_price = policy.compute_unit_price(attempt);
+            retry::set_compute_unit_price(&mut instructions, compute_unit_price);
+            info!("📤 发送卖出交易 (尝试 {}/{}, CU 价格 {})", attempt, max_attempts, compute_unit_price);
+
+            match self.send_transaction(instructions.clone(), &mut attempted, outbox, spend).await {
                 Ok(signature) => {
                     if attempt > 1 {
                         info!("✅ 卖出交易发送成功 (第 {} 次尝试)", attempt);
                     }
-                    return Ok(signature);
+                    return Ok((signature, attempt_fee(attempt)));
                 }
                 Err(e) => {
+                    let kind = classify_send_error(&e);
+                    if kind == SendErrorKind::AlreadyProcessed {
+                        // 重复签名：本次发送的交易已被处理
+                        if let Some(signature) = attempted.last() {
+                            info!("♻️  卖出交易已处理 (AlreadyProcessed): {}", signature);
+                            return Ok((*signature, attempt_fee(attempted.len() as u32)));
+                        }
+                    }
+
                     if attempt < max_attempts {
                         warn!("⚠️  卖出交易发送失败 (尝试 {}/{}): {}", attempt, max_attempts, e);
-                        warn!("   {}ms 后重试...", 100 * attempt);
-                        tokio::time::sleep(tokio::time::Duration::from_millis(100 * attempt as u64)).await;
+                        if kind == SendErrorKind::BlockhashNotFound {
+                            warn!("   blockhash 已失效，立即使用新 blockhash 重建...");
+                        } else {
+                            warn!("   {}ms 后使用新 blockhash 重试...", 100 * attempt);
+                            tokio::time::sleep(tokio::time::Duration::from_millis(100 * attempt as u64)).await;
+                        }
                     } else {
                         error!("❌ 卖出交易发送失败，已达最大重试次数: {}", e);
                         return Err(e);
@@ -455,10 +438,35 @@ impl SolTradeSellExecutor {
         Err(anyhow::anyhow!("卖出交易发送失败，已达最大重试次数"))
     }
 
+    /// 第 n 次尝试的交易费（网络费 + 优先费）
+    fn attempt_fee(&self, policy: &RetryPolicy, attempt: u32) -> u64 {
+        BASE_SIGNATURE_FEE_LAMPORTS
+            + policy.compute_unit_price(attempt) * self.config.compute_unit_limit as u64 / 1_000_000
+    }
+
+    /// 查找已成功上链的签名
+    fn find_landed_signature(&self, signatures: &[Signature]) -> Option<Signature> {
+        if signatures.is_empty() {
+            return None;
+        }
+        let statuses = self.rpc_client.get_signature_statuses(signatures).ok()?;
+        signatures
+            .iter()
+            .zip(statuses.value)
+            .find(|(_, status)| matches!(status, Some(status) if status.err.is_none()))
+            .map(|(signature, _)| *signature)
+    }
+
     /// 发送交易
     ///
-    /// 参考 sol-trade-sdk 的交易发送逻辑
-    async fn send_transaction(&self, instructions: Vec<Instruction>) -> Result<Signature> {
+    /// 参考 sol-trade-sdk 的交易发送逻辑（每次调用都获取最新 blockhash 并重新签名）
+    async fn send_transaction(
+        &self,
+        instructions: Vec<Instruction>,
+        attempted: &mut Vec<Signature>,
+        outbox: &mut OutboxBatch,
+        spend: &mut SpendTicket,
+    ) -> Result<Signature> {
         info!("📤 准备发送卖出交易");
 
         // 获取最新 blockhash
@@ -471,6 +479,10 @@ impl SolTradeSellExecutor {
             Some(&self.payer.pubkey()),
         );
         transaction.sign(&[&*self.payer], recent_blockhash);
+        spend.signed();
+        attempted.push(transaction.signatures[0]);
+        let transaction = VersionedTransaction::from(transaction);
+        outbox.record(&transaction);
 
         // 发送交易
         let signature = self.rpc_client.send_transaction(&transaction)
@@ -483,57 +495,37 @@ impl SolTradeSellExecutor {
     /// 等待交易确认
     ///
     /// 参考 sol-trade-sdk 的确认等待逻辑
+    ///
+    /// 🔥 优化: gRPC 交易流匹配签名（亚秒级），RPC 仅作低频兜底
+    ///
+    /// 链上执行失败返回对应的 BotError（滑点超限 / 曲线已完成 / 其他失败），超时返回 Ok(false)
     async fn wait_for_confirmation(
         &self,
         signature: &Signature,
         max_wait_seconds: u64,
-    ) -> Result<bool> {
+    ) -> BotResult<bool> {
         info!("⏳ 等待卖出交易确认: {}", signature);
         info!("   最大等待时间: {} 秒", max_wait_seconds);
 
-        let start_time = Instant::now();
-        let max_wait = Duration::from_secs(max_wait_seconds);
-
-        while start_time.elapsed() < max_wait {
-            match self.rpc_client.get_signature_status(signature) {
-                Ok(Some(status)) => {
-                    match status {
-                        Ok(_) => {
-                            let elapsed = start_time.elapsed().as_secs();
-                            info!("✅ 卖出交易已确认 (耗时 {} 秒)", elapsed);
-                            return Ok(true);
-                        }
-                        Err(e) => {
-                            error!("❌ 卖出交易失败: {:?}", e);
-                            return Ok(false);
-                        }
-                    }
-                }
-                Ok(None) => {
-                    debug!("⏳ 交易尚未确认，继续等待...");
-                }
-                Err(e) => {
-                    warn!("⚠️  查询交易状态失败: {:?}", e);
-                }
+        match self.confirmations.wait_for(self.rpc_client.as_ref(), signature, max_wait_seconds).await {
+            ConfirmationStatus::Confirmed => Ok(true),
+            ConfirmationStatus::Failed(err) => {
+                error!("❌ 卖出交易失败: {}", err);
+                Err(BotError::from_transaction_failure(&err))
+            }
+            ConfirmationStatus::TimedOut => {
+                warn!("⏰ 卖出交易确认超时 ({} 秒)", max_wait_seconds);
+                Ok(false)
             }
-
-            tokio::time::sleep(Duration::from_secs(1)).await;
         }
-
-        warn!("⏰ 卖出交易确认超时 ({} 秒)", max_wait_seconds);
-        Ok(false)
     }
 
     /// 获取 token 账户余额
     pub async fn get_token_balance(&self, mint: &Pubkey) -> Result<u64> {
-        let token_account = Self::get_associated_token_address(&self.payer.pubkey(), mint);
+        let (token_account, _) = self.user_token_account(mint)?;
 
-        match self.rpc_client.get_token_account_balance(&token_account) {
-            Ok(balance) => {
-                let amount = balance.amount.parse::<u64>()
-                    .context("解析 token 余额失败")?;
-                Ok(amount)
-            }
+        match self.rpc_client.get_token_account_balance_with_commitment(&token_account, self.config.get_balance_commitment()) {
+            Ok(balance) => Ok(balance.value),
             Err(e) => {
                 warn!("获取 token 余额失败: {:?}", e);
                 Ok(0)
@@ -541,6 +533,31 @@ impl SolTradeSellExecutor {
         }
     }
 
+    /// 获取 ATA 账户锁定的租金（lamports，账户不存在时为 0）
+    pub async fn get_token_account_rent(&self, mint: &Pubkey) -> Result<u64> {
+        let (token_account, _) = self.user_token_account(mint)?;
+        self.rpc_client.get_balance(&token_account)
+            .context("获取 ATA 租金失败")
+    }
+
+    /// 查询 token 账户余额（对账用：账户不存在时为 0，RPC 失败时返回错误而不是 0）
+    pub async fn query_token_balance(&self, mint: &Pubkey) -> Result<u64> {
+        let (token_account, _) = self.user_token_account(mint)?;
+
+        let account = self.rpc_client
+            .get_account_with_commitment(&token_account, self.config.get_balance_commitment())
+            .context("查询 token 账户失败")?
+            .value;
+        if account.is_none() {
+            return Ok(0);
+        }
+
+        Ok(self.rpc_client
+            .get_token_account_balance_with_commitment(&token_account, self.config.get_balance_commitment())
+            .context("获取 token 余额失败")?
+            .value)
+    }
+
 }
 
 
@@ -0,0 +1,66 @@
+/// TPU 直发
+///
+/// 通过 QUIC 将交易直接发送到当前及后续 leader 的 TPU 端口，完全绕过 RPC 节点
+///
+/// 核心功能:
+/// 1. leader schedule 跟踪 - 由 solana tpu-client 通过 websocket 订阅 slot 更新
+/// 2. QUIC 连接池 - 复用与 leader 的连接
+/// 3. fanout 发送 - 同时发送到未来 fanout_slots 个 slot 的 leader
+
+use anyhow::{Context, Result};
+use log::{debug, info};
+use solana_client::{nonblocking::tpu_client::TpuClient, tpu_client::TpuClientConfig};
+use solana_quic_client::{QuicConfig, QuicConnectionManager, QuicPool};
+use solana_sdk::{signature::Signature, transaction::VersionedTransaction};
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::rpc_pool::RpcPool;
+
+/// TPU 客户端允许的最大 fanout slot 数
+pub const MAX_TPU_FANOUT_SLOTS: u64 = 100;
+
+/// TPU 直发客户端
+pub struct TpuSender {
+    client: TpuClient<QuicPool, QuicConnectionManager, QuicConfig>,
+}
+
+impl TpuSender {
+    /// 创建 TPU 客户端（需要 websocket 订阅 slot 更新，因此为异步构造）
+    pub async fn new(config: &Config, rpc_pool: &Arc<RpcPool>) -> Result<Self> {
+        let websocket_url = config.tpu_websocket_url();
+        let rpc_client = Arc::new(rpc_pool.nonblocking_client(config.get_commitment_config()));
+
+        let client = TpuClient::new(
+            "sniper-tpu",
+            rpc_client,
+            &websocket_url,
+            TpuClientConfig {
+                fanout_slots: config.tpu_fanout_slots,
+            },
+        )
+        .await
+        .context("创建 TPU 客户端失败")?;
+
+        info!("🛰️  TPU 直发已初始化");
+        info!("   Websocket: {}", websocket_url);
+        info!("   Fanout slots: {}", config.tpu_fanout_slots);
+
+        Ok(Self { client })
+    }
+
+    /// 通过 QUIC 发送交易到 leader TPU
+    pub async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        let signature = transaction.signatures[0];
+        let wire_transaction = bincode::serialize(transaction)
+            .context("序列化交易失败")?;
+
+        self.client
+            .try_send_wire_transaction(wire_transaction)
+            .await
+            .context("TPU 发送失败")?;
+
+        debug!("🛰️  TPU 已发送: {}", signature);
+        Ok(signature)
+    }
+}
@@ -0,0 +1,326 @@
+/// 钱包清理（WSOL 解包 + 空 token 账户回收租金）
+///
+/// 卖出后留下的空 ATA 和残留的 WSOL 账户会锁住 lamports（每个 ATA 约 0.002 SOL 租金），
+/// 启动时和每次卖出后扫描钱包，关闭这些账户把 lamports 收回
+///
+/// 核心功能:
+/// 1. 扫描 - Token / Token-2022 程序下钱包持有的所有 token 账户
+/// 2. 分类 - WSOL 账户（关闭即解包）、余额为 0 的账户（回收租金）
+/// 3. 批量关闭 - 每笔交易最多关闭 MAX_CLOSES_PER_TX 个账户
+/// 4. 定期扫描 - 按 wallet_sweep_interval_secs 间隔清理
+/// 5. 租金账本 - 统计开仓创建 ATA 花费的租金和已回收的租金
+/// 6. 支出限额 - 关闭交易的网络费经过支出限额守卫（只检查单笔上限，计入每小时窗口）
+
+use anyhow::{Context, Result};
+use log::{debug, info, warn};
+use solana_client::{rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::Transaction,
+};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::signer::SharedSigner;
+use crate::spend_guard::{system_outflow, SpendGuard};
+
+const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// 单笔交易最多关闭的账户数（避免超过交易体积限制）
+const MAX_CLOSES_PER_TX: usize = 10;
+
+/// 每个签名的基础网络费（关闭交易不设置优先费）
+const BASE_SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+
+/// 可回收的 token 账户
+#[derive(Debug, Clone)]
+pub struct ReclaimableAccount {
+    pub address: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    /// 账户 lamports（租金 + WSOL 余额）
+    pub lamports: u64,
+    pub is_wsol: bool,
+}
+
+/// 钱包持有的 token 账户
+#[derive(Debug, Clone)]
+pub struct TokenAccountInfo {
+    pub address: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    /// token 余额（最小单位）
+    pub amount: u64,
+    /// 账户 lamports（租金 + WSOL 余额）
+    pub lamports: u64,
+}
+
+/// 扫描结果
+#[derive(Debug, Default)]
+pub struct SweepReport {
+    pub accounts: Vec<ReclaimableAccount>,
+}
+
+impl SweepReport {
+    /// 可回收的 lamports 总额
+    pub fn reclaimable_lamports(&self) -> u64 {
+        self.accounts.iter().map(|account| account.lamports).sum()
+    }
+
+    pub fn wsol_count(&self) -> usize {
+        self.accounts.iter().filter(|account| account.is_wsol).count()
+    }
+}
+
+/// ATA 租金账本
+#[derive(Debug, Default)]
+pub struct RentLedger {
+    /// 开仓创建 ATA 锁定的租金
+    spent_lamports: AtomicU64,
+    /// 卖出关闭账户 + 钱包清理回收的租金
+    reclaimed_lamports: AtomicU64,
+    closed_accounts: AtomicU64,
+}
+
+impl RentLedger {
+    pub fn record_spent(&self, lamports: u64) {
+        self.spent_lamports.fetch_add(lamports, Ordering::Relaxed);
+    }
+
+    pub fn record_reclaimed(&self, lamports: u64, accounts: u64) {
+        self.reclaimed_lamports.fetch_add(lamports, Ordering::Relaxed);
+        self.closed_accounts.fetch_add(accounts, Ordering::Relaxed);
+    }
+
+    pub fn spent_lamports(&self) -> u64 {
+        self.spent_lamports.load(Ordering::Relaxed)
+    }
+
+    pub fn reclaimed_lamports(&self) -> u64 {
+        self.reclaimed_lamports.load(Ordering::Relaxed)
+    }
+
+    pub fn closed_accounts(&self) -> u64 {
+        self.closed_accounts.load(Ordering::Relaxed)
+    }
+
+    /// 打印租金回收报告
+    pub fn print_summary(&self) {
+        info!("🏦 ATA 租金报告:");
+        info!("   开仓锁定: {:.6} SOL", self.spent_lamports() as f64 / 1_000_000_000.0);
+        info!("   已回收: {:.6} SOL ({} 个账户)",
+            self.reclaimed_lamports() as f64 / 1_000_000_000.0, self.closed_accounts());
+    }
+}
+
+/// 钱包清理器
+pub struct WalletSweeper {
+    rpc_client: Arc<RpcClient>,
+    payer: SharedSigner,
+    rent_ledger: RentLedger,
+    spend_guard: Arc<SpendGuard>,
+}
+
+impl WalletSweeper {
+    pub fn new(rpc_client: Arc<RpcClient>, payer: SharedSigner, spend_guard: Arc<SpendGuard>) -> Self {
+        Self {
+            rpc_client,
+            payer,
+            rent_ledger: RentLedger::default(),
+            spend_guard,
+        }
+    }
+
+    pub fn rent_ledger(&self) -> &RentLedger {
+        &self.rent_ledger
+    }
+
+    /// 列出钱包在 Token / Token-2022 程序下的所有 token 账户
+    pub fn token_accounts(&self) -> Result<Vec<TokenAccountInfo>> {
+        let owner = self.payer.pubkey();
+        let mut result = Vec::new();
+
+        for program in [TOKEN_PROGRAM, TOKEN_2022_PROGRAM] {
+            let token_program = Pubkey::from_str(program)?;
+            let accounts = self.rpc_client
+                .get_token_accounts_by_owner(&owner, TokenAccountsFilter::ProgramId(token_program))
+                .with_context(|| format!("查询 token 账户失败 ({})", program))?;
+
+            for keyed in accounts {
+                // jsonParsed 编码: data.parsed.info.{mint, tokenAmount.amount}
+                let data = serde_json::to_value(&keyed.account.data).unwrap_or_default();
+                let info = &data["parsed"]["info"];
+
+                let Some(mint) = info["mint"].as_str().and_then(|m| Pubkey::from_str(m).ok()) else {
+                    continue;
+                };
+                let Ok(address) = Pubkey::from_str(&keyed.pubkey) else {
+                    continue;
+                };
+                // 解析失败时视为非空，避免误关闭
+                let amount: u64 = info["tokenAmount"]["amount"]
+                    .as_str()
+                    .and_then(|a| a.parse().ok())
+                    .unwrap_or(u64::MAX);
+
+                result.push(TokenAccountInfo {
+                    address,
+                    mint,
+                    token_program,
+                    amount,
+                    lamports: keyed.account.lamports,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// 扫描钱包中的 WSOL 账户和空 token 账户
+    pub fn scan(&self) -> Result<SweepReport> {
+        let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
+        let mut report = SweepReport::default();
+
+        for account in self.token_accounts()? {
+            let is_wsol = account.mint == wsol_mint;
+            if is_wsol || account.amount == 0 {
+                report.accounts.push(ReclaimableAccount {
+                    address: account.address,
+                    mint: account.mint,
+                    token_program: account.token_program,
+                    lamports: account.lamports,
+                    is_wsol,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 钱包持有的非 WSOL、余额不为 0 的 token
+    pub fn held_tokens(&self) -> Result<Vec<TokenAccountInfo>> {
+        let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
+        Ok(self.token_accounts()?
+            .into_iter()
+            .filter(|account| account.mint != wsol_mint && account.amount > 0 && account.amount != u64::MAX)
+            .collect())
+    }
+
+    /// 扫描并关闭可回收账户，返回回收的 lamports
+    ///
+    /// RPC 客户端是阻塞的，整个清理在 spawn_blocking 线程中执行，不占用异步工作线程
+    pub async fn sweep(self: &Arc<Self>) -> Result<u64> {
+        let sweeper = self.clone();
+        tokio::task::spawn_blocking(move || sweeper.sweep_blocking())
+            .await
+            .context("钱包清理任务异常退出")?
+    }
+
+    fn sweep_blocking(&self) -> Result<u64> {
+        let report = self.scan()?;
+        if report.accounts.is_empty() {
+            debug!("🧹 钱包无需清理");
+            return Ok(0);
+        }
+
+        info!(
+            "🧹 发现 {} 个可回收账户 (WSOL {} 个)，可回收 {:.6} SOL",
+            report.accounts.len(),
+            report.wsol_count(),
+            report.reclaimable_lamports() as f64 / 1_000_000_000.0
+        );
+
+        let owner = self.payer.pubkey();
+        let mut reclaimed = 0u64;
+
+        for batch in report.accounts.chunks(MAX_CLOSES_PER_TX) {
+            for account in batch {
+                debug!(
+                    "   关闭 {} (mint {}, {:.6} SOL)",
+                    account.address,
+                    account.mint,
+                    account.lamports as f64 / 1_000_000_000.0
+                );
+            }
+            let instructions: Vec<Instruction> = batch
+                .iter()
+                .map(|account| close_account_instruction(account, &owner))
+                .collect();
+
+            match self.send(&instructions) {
+                Ok(signature) => {
+                    let batch_lamports: u64 = batch.iter().map(|account| account.lamports).sum();
+                    reclaimed += batch_lamports;
+                    self.rent_ledger.record_reclaimed(batch_lamports, batch.len() as u64);
+                    info!(
+                        "✅ 已关闭 {} 个账户，回收 {:.6} SOL: {}",
+                        batch.len(),
+                        batch_lamports as f64 / 1_000_000_000.0,
+                        signature
+                    );
+                }
+                Err(e) => {
+                    warn!("⚠️  关闭账户失败: {:?}", e);
+                }
+            }
+        }
+
+        Ok(reclaimed)
+    }
+
+    /// 定期扫描并关闭空 token 账户（interval_secs = 0 时不启用）
+    pub async fn start_sweep_loop(self: Arc<Self>, interval_secs: u64) {
+        if interval_secs == 0 {
+            info!("🧹 定期钱包清理已禁用");
+            return;
+        }
+
+        info!("🧹 定期钱包清理已启动 (间隔 {}s)", interval_secs);
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        interval.tick().await; // 启动时已清理过一次，跳过第一次立即触发
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.sweep().await {
+                warn!("⚠️  定期钱包清理失败: {:?}", e);
+            }
+        }
+    }
+
+    fn send(&self, instructions: &[Instruction]) -> Result<solana_sdk::signature::Signature> {
+        let owner = self.payer.pubkey();
+        let mut spend = self.spend_guard.begin_exit(owner);
+        spend.authorize(system_outflow(instructions, &owner) + BASE_SIGNATURE_FEE_LAMPORTS)?;
+
+        let recent_blockhash = self.rpc_client.get_latest_blockhash()
+            .context("获取 blockhash 失败")?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&owner),
+            &[&*self.payer],
+            recent_blockhash,
+        );
+        spend.signed();
+        self.rpc_client.send_and_confirm_transaction(&transaction)
+            .context("发送关闭账户交易失败")
+    }
+}
+
+/// CloseAccount 指令（lamports 退回 owner；WSOL 账户关闭即解包）
+fn close_account_instruction(account: &ReclaimableAccount, owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: account.token_program,
+        accounts: vec![
+            AccountMeta::new(account.address, false),
+            AccountMeta::new(*owner, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: vec![9], // CloseAccount 指令索引
+    }
+}
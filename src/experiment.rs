@@ -0,0 +1,235 @@
+/// A/B 策略实验（影子模式）
+///
+/// 策略 B 与实盘策略 A 共享同一条指标流，B 只做纸面交易：按当时的曲线储备模拟成交，
+/// 记录假想的开仓 / 平仓，参数改动先用实盘流量验证，再决定是否切换为实盘策略
+///
+/// 核心功能:
+/// 1. 影子评估 - B 使用独立的动态策略引擎（独立的自适应状态，不影响 A）
+/// 2. 纸面成交 - 按恒定乘积曲线估算买入数量和卖出所得（含 PumpFun 手续费）
+/// 3. 纸面平仓 - 按 B 的止盈 / 止损 / 最大持仓时间退出，结果回馈 B 的成功率反馈
+/// 4. 记录 - 假想成交写入交易日志（shadow_entry / shadow_exit），退出时打印 B 的汇总
+
+use chrono::{DateTime, Utc};
+use log::{debug, info};
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::decision_trace::DecisionNotes;
+use crate::dynamic_strategy::{DynamicStrategyEngine, SellTriggers, StrategyMode};
+use crate::executor::TransactionBuilder;
+use crate::journal::{JournalRecord, TradeJournal};
+use crate::strategy::StrategyEngine;
+use crate::types::WindowMetrics;
+
+/// 影子持仓
+#[derive(Debug, Clone)]
+struct ShadowPosition {
+    sol_invested: u64,
+    token_amount: u64,
+    entry_time: DateTime<Utc>,
+}
+
+/// 影子策略累计结果
+#[derive(Debug, Default)]
+struct ShadowStats {
+    entries: u32,
+    wins: u32,
+    losses: u32,
+    profit_loss_lamports: i64,
+}
+
+/// 影子策略实验
+pub struct ShadowExperiment {
+    config: Arc<Config>,
+    mode: StrategyMode,
+    engine: Mutex<DynamicStrategyEngine>,
+    sell_triggers: SellTriggers,
+    tx_builder: TransactionBuilder,
+    positions: Mutex<HashMap<Pubkey, ShadowPosition>>,
+    stats: Mutex<ShadowStats>,
+    journal: Arc<TradeJournal>,
+    /// 计时时钟（影子持仓时长 / 日志时间戳，回放时跟随录制时间）
+    clock: Arc<Clock>,
+}
+
+impl ShadowExperiment {
+    /// 按 experiment_* 配置创建影子策略（未启用时返回 None）
+    pub fn from_config(config: Arc<Config>, journal: Arc<TradeJournal>, clock: Arc<Clock>) -> Option<Self> {
+        if !config.experiment_enabled {
+            return None;
+        }
+
+        let mode = StrategyMode::from_name(&config.experiment_strategy_mode);
+        let mut dynamic_config = StrategyEngine::dynamic_config_for_mode(&config, mode);
+
+        // 单项参数覆盖（未设置时沿用所选模式的参数）
+        if let Some(score) = config.experiment_min_composite_score {
+            dynamic_config.buy_triggers.min_composite_score = score;
+        }
+        if let Some(multiplier) = config.experiment_take_profit_multiplier {
+            dynamic_config.sell_triggers.take_profit_multiplier = multiplier;
+        }
+        if let Some(multiplier) = config.experiment_stop_loss_multiplier {
+            dynamic_config.sell_triggers.stop_loss_multiplier = multiplier;
+        }
+        if let Some(secs) = config.experiment_max_hold_secs {
+            dynamic_config.sell_triggers.max_hold_duration_secs = secs;
+        }
+
+        info!("🧪 A/B 实验已启用: 影子策略 B = {:?}", mode);
+        info!("   综合评分阈值: {:.2}, 止盈: {:.2}x, 止损: {:.2}x, 最大持仓: {}s",
+            dynamic_config.buy_triggers.min_composite_score,
+            dynamic_config.sell_triggers.take_profit_multiplier,
+            dynamic_config.sell_triggers.stop_loss_multiplier,
+            dynamic_config.sell_triggers.max_hold_duration_secs);
+
+        let sell_triggers = dynamic_config.sell_triggers.clone();
+
+        Some(Self {
+            config,
+            mode,
+            engine: Mutex::new(DynamicStrategyEngine::new(dynamic_config)),
+            sell_triggers,
+            tx_builder: TransactionBuilder::new(),
+            positions: Mutex::new(HashMap::new()),
+            stats: Mutex::new(ShadowStats::default()),
+            journal,
+            clock,
+        })
+    }
+
+    /// 处理一条指标：已有影子持仓时检查退出，否则评估买入
+    pub fn on_metrics(&self, metrics: &WindowMetrics) {
+        let Some(advanced) = metrics.advanced_metrics.as_ref() else {
+            return;
+        };
+
+        let mut engine = self.engine.lock();
+        engine.observe_launch(metrics, advanced);
+
+        let position = self.positions.lock().get(&metrics.mint).cloned();
+        if let Some(position) = position {
+            self.check_exit(&mut engine, metrics, &position);
+            return;
+        }
+
+        // 与实盘常规策略相同的前置条件
+        if metrics.event_count < 3
+            || self.config.in_warmup(metrics.observed_secs, metrics.total_event_count)
+            || metrics.unique_buyers < self.config.min_unique_buyers
+            || self.positions.lock().len() >= self.config.max_positions
+        {
+            return;
+        }
+
+        engine.adapt_parameters();
+        if engine.evaluate_buy(metrics, advanced, &mut DecisionNotes::new(false)).should_buy {
+            self.paper_buy(metrics);
+        }
+    }
+
+    /// 按当前曲线储备模拟买入
+    fn paper_buy(&self, metrics: &WindowMetrics) {
+        let sol_amount = self.config.get_snipe_amount_lamports();
+        let token_amount = self.tx_builder.estimate_buy_token_amount(
+            metrics.latest_virtual_token_reserves,
+            metrics.latest_virtual_sol_reserves,
+            sol_amount,
+        );
+        if token_amount == 0 {
+            return;
+        }
+
+        let entry_price_sol = sol_amount as f64 / token_amount as f64;
+        info!("🧪 [B] 纸面买入: {} ({:.4} SOL -> {} tokens)",
+            metrics.mint, sol_amount as f64 / 1_000_000_000.0, token_amount);
+
+        self.positions.lock().insert(metrics.mint, ShadowPosition {
+            sol_invested: sol_amount,
+            token_amount,
+            entry_time: self.clock.utc_now(),
+        });
+        self.stats.lock().entries += 1;
+
+        self.journal.record(&JournalRecord::ShadowEntry {
+            timestamp: self.clock.utc_now().to_rfc3339(),
+            variant: format!("{:?}", self.mode),
+            mint: metrics.mint.to_string(),
+            sol_amount_lamports: sol_amount,
+            token_amount,
+            entry_price_sol,
+        });
+    }
+
+    /// 按 B 的卖出条件检查影子持仓
+    fn check_exit(&self, engine: &mut DynamicStrategyEngine, metrics: &WindowMetrics, position: &ShadowPosition) {
+        let sol_received = self.tx_builder.estimate_sell_sol_amount(
+            metrics.latest_virtual_token_reserves,
+            metrics.latest_virtual_sol_reserves,
+            position.token_amount,
+        );
+        let value_ratio = sol_received as f64 / position.sol_invested as f64;
+        let hold_secs = self.clock.utc_now()
+            .signed_duration_since(position.entry_time)
+            .num_seconds()
+            .max(0) as u64;
+
+        let reason = if value_ratio >= self.sell_triggers.take_profit_multiplier {
+            "take_profit"
+        } else if value_ratio <= self.sell_triggers.stop_loss_multiplier {
+            "stop_loss"
+        } else if hold_secs >= self.sell_triggers.max_hold_duration_secs {
+            "max_hold"
+        } else {
+            debug!("🧪 [B] 持有 {}: {:.2}x, {}s", metrics.mint, value_ratio, hold_secs);
+            return;
+        };
+
+        self.positions.lock().remove(&metrics.mint);
+
+        let profit_loss = sol_received as i64 - position.sol_invested as i64;
+        {
+            let mut stats = self.stats.lock();
+            if profit_loss > 0 {
+                stats.wins += 1;
+            } else {
+                stats.losses += 1;
+            }
+            stats.profit_loss_lamports += profit_loss;
+        }
+        engine.record_trade_outcome(
+            profit_loss as f64 / position.sol_invested as f64 * 100.0,
+            position.entry_time,
+        );
+
+        info!("🧪 [B] 纸面卖出: {} ({}, {:+.4} SOL, 持仓 {}s)",
+            metrics.mint, reason, profit_loss as f64 / 1_000_000_000.0, hold_secs);
+
+        self.journal.record(&JournalRecord::ShadowExit {
+            timestamp: self.clock.utc_now().to_rfc3339(),
+            variant: format!("{:?}", self.mode),
+            mint: metrics.mint.to_string(),
+            sol_received_lamports: sol_received,
+            profit_loss_lamports: profit_loss,
+            hold_secs,
+            reason: reason.to_string(),
+        });
+    }
+
+    /// 打印影子策略汇总
+    pub fn print_summary(&self) {
+        let stats = self.stats.lock();
+        let closed = stats.wins + stats.losses;
+        let win_rate = if closed == 0 { 0.0 } else { stats.wins as f64 / closed as f64 * 100.0 };
+
+        info!("🧪 A/B 实验汇总 (影子策略 B = {:?}):", self.mode);
+        info!("   纸面开仓: {} 笔, 已平仓: {} 笔, 未平仓: {} 笔",
+            stats.entries, closed, self.positions.lock().len());
+        info!("   胜率: {:.1}% ({} 胜 / {} 负)", win_rate, stats.wins, stats.losses);
+        info!("   纸面盈亏: {:+.4} SOL", stats.profit_loss_lamports as f64 / 1_000_000_000.0);
+    }
+}
@@ -0,0 +1,217 @@
+/// processed 事件流确认对账（分叉保护）
+///
+/// FEED_COMMITMENT=processed 时事件流更快，但推送的交易可能落在随后被丢弃的分叉上。
+/// 进入滑窗的每笔交易按 slot 登记，slot 达到 confirmed 即视为确认；
+/// slot 被标记为 dead 或超时仍未确认时回滚这些交易
+///
+/// 核心功能:
+/// 1. 登记 - gRPC 交易流推送的交易事件按 slot 记录签名和 mint
+/// 2. 确认 - slot 状态更新到 confirmed / finalized 时清除该 slot 的登记
+/// 3. 回滚 - slot dead 或超过 feed_reconcile_timeout_ms 未确认时，交易写入回滚队列（聚合器消费，撤销窗口事件）
+/// 4. 买入保护 - 最近发生回滚的 mint 取消进行中的买入；可选买入前等待该 mint 的交易全部确认
+
+use dashmap::DashMap;
+use log::{info, warn};
+use parking_lot::Mutex;
+use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::pubkey::Pubkey;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// 等待确认的交易
+#[derive(Debug, Clone)]
+struct PendingTrade {
+    signature: String,
+    mint: Pubkey,
+}
+
+/// 单个 slot 的待确认交易
+struct PendingSlot {
+    trades: Vec<PendingTrade>,
+    observed_at: Instant,
+}
+
+/// 被回滚的交易（聚合器据此撤销窗口事件）
+#[derive(Debug, Clone)]
+pub struct RolledBackTrade {
+    pub slot: u64,
+    pub signature: String,
+    pub mint: Pubkey,
+}
+
+/// 事件流确认对账器
+pub struct FeedReconciler {
+    /// 事件流 commitment 为 processed 时启用
+    enabled: bool,
+    /// 等待 confirmed 的超时
+    timeout: Duration,
+    /// slot → 待确认交易
+    pending: DashMap<u64, PendingSlot>,
+    /// mint → 待确认交易数（买入前等待确认使用）
+    pending_per_mint: DashMap<Pubkey, usize>,
+    /// mint → 最近一次回滚时间
+    rolled_back_mints: DashMap<Pubkey, Instant>,
+    /// 等待聚合器消费的回滚交易
+    rollbacks: Mutex<Vec<RolledBackTrade>>,
+    /// 统计
+    confirmed_count: AtomicU64,
+    rolled_back_count: AtomicU64,
+}
+
+impl FeedReconciler {
+    pub fn new(feed_commitment: CommitmentConfig, timeout_ms: u64) -> Self {
+        let enabled = feed_commitment.commitment == CommitmentLevel::Processed;
+        if enabled {
+            info!("🧮 processed 事件流确认对账已启用 (超时 {}ms)", timeout_ms);
+        }
+
+        Self {
+            enabled,
+            timeout: Duration::from_millis(timeout_ms),
+            pending: DashMap::new(),
+            pending_per_mint: DashMap::new(),
+            rolled_back_mints: DashMap::new(),
+            rollbacks: Mutex::new(Vec::new()),
+            confirmed_count: AtomicU64::new(0),
+            rolled_back_count: AtomicU64::new(0),
+        }
+    }
+
+    /// 是否启用（gRPC 客户端据此订阅 slot 状态）
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 登记推送到聚合器的交易事件
+    pub fn track(&self, slot: u64, signature: &str, mint: Pubkey) {
+        if !self.enabled {
+            return;
+        }
+
+        self.pending
+            .entry(slot)
+            .or_insert_with(|| PendingSlot {
+                trades: Vec::new(),
+                observed_at: Instant::now(),
+            })
+            .trades
+            .push(PendingTrade {
+                signature: signature.to_string(),
+                mint,
+            });
+        *self.pending_per_mint.entry(mint).or_insert(0) += 1;
+    }
+
+    /// slot 已达到 confirmed / finalized
+    pub fn confirm_slot(&self, slot: u64) {
+        if let Some((_, pending)) = self.pending.remove(&slot) {
+            self.confirmed_count.fetch_add(pending.trades.len() as u64, Ordering::Relaxed);
+            self.release(&pending.trades);
+        }
+    }
+
+    /// slot 被标记为 dead（所在分叉被丢弃）
+    pub fn drop_slot(&self, slot: u64) {
+        if let Some((_, pending)) = self.pending.remove(&slot) {
+            warn!("🍴 slot {} 已被丢弃，回滚 {} 笔交易", slot, pending.trades.len());
+            self.roll_back(slot, pending.trades);
+        }
+    }
+
+    /// 回滚超时仍未确认的 slot
+    pub fn expire(&self) {
+        if !self.enabled {
+            return;
+        }
+
+        let expired: Vec<u64> = self
+            .pending
+            .iter()
+            .filter(|entry| entry.observed_at.elapsed() >= self.timeout)
+            .map(|entry| *entry.key())
+            .collect();
+
+        for slot in expired {
+            if let Some((_, pending)) = self.pending.remove(&slot) {
+                warn!("⏰ slot {} 超过 {}ms 未确认，回滚 {} 笔交易",
+                    slot, self.timeout.as_millis(), pending.trades.len());
+                self.roll_back(slot, pending.trades);
+            }
+        }
+    }
+
+    /// 取出等待聚合器处理的回滚交易
+    pub fn take_rollbacks(&self) -> Vec<RolledBackTrade> {
+        let mut rollbacks = self.rollbacks.lock();
+        if rollbacks.is_empty() {
+            return Vec::new();
+        }
+        std::mem::take(&mut *rollbacks)
+    }
+
+    /// mint 最近（超时窗口内）是否发生过回滚（进行中的买入据此取消）
+    pub fn recently_rolled_back(&self, mint: &Pubkey) -> bool {
+        self.rolled_back_mints
+            .get(mint)
+            .is_some_and(|at| at.elapsed() < self.timeout * 2)
+    }
+
+    /// 等待 mint 的待确认交易全部确认（超时返回 false）
+    pub async fn wait_for_mint(&self, mint: &Pubkey) -> bool {
+        if !self.enabled {
+            return true;
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            if self.recently_rolled_back(mint) {
+                return false;
+            }
+            if !self.pending_per_mint.contains_key(mint) {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    /// 统计（已确认交易数, 已回滚交易数, 待确认 slot 数）
+    pub fn stats(&self) -> (u64, u64, usize) {
+        (
+            self.confirmed_count.load(Ordering::Relaxed),
+            self.rolled_back_count.load(Ordering::Relaxed),
+            self.pending.len(),
+        )
+    }
+
+    fn roll_back(&self, slot: u64, trades: Vec<PendingTrade>) {
+        self.release(&trades);
+        self.rolled_back_count.fetch_add(trades.len() as u64, Ordering::Relaxed);
+
+        let now = Instant::now();
+        let mut rollbacks = self.rollbacks.lock();
+        for trade in trades {
+            self.rolled_back_mints.insert(trade.mint, now);
+            rollbacks.push(RolledBackTrade {
+                slot,
+                signature: trade.signature,
+                mint: trade.mint,
+            });
+        }
+
+        // 清理过期的回滚记录
+        let retention = self.timeout * 2;
+        self.rolled_back_mints.retain(|_, at| at.elapsed() < retention);
+    }
+
+    fn release(&self, trades: &[PendingTrade]) {
+        for trade in trades {
+            if let Some(mut count) = self.pending_per_mint.get_mut(&trade.mint) {
+                *count = count.saturating_sub(1);
+            }
+            self.pending_per_mint.remove_if(&trade.mint, |_, count| *count == 0);
+        }
+    }
+}
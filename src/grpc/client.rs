@@ -1,38 +1,131 @@
 use anyhow::{Context, Result};
 use futures::{SinkExt, StreamExt};
 use log::{debug, error, info, warn};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tonic::transport::channel::ClientTlsConfig;
 use yellowstone_grpc_client::GeyserGrpcClient;
 use yellowstone_grpc_proto::geyser::{
     subscribe_update::UpdateOneof, SubscribeRequest,
-    SubscribeRequestFilterAccounts, SubscribeRequestFilterTransactions, SubscribeUpdate,
+    SubscribeRequestFilterAccounts, SubscribeRequestFilterSlots, SubscribeRequestFilterTransactions,
+    SubscribeRequestPing, SubscribeUpdate,
 };
-use yellowstone_grpc_proto::prelude::CommitmentLevel;
+use yellowstone_grpc_proto::prelude::{CommitmentLevel, SlotStatus};
+use solana_commitment_config::{CommitmentConfig, CommitmentLevel as SolanaCommitmentLevel};
 use solana_sdk::pubkey::Pubkey;
 use crossbeam_queue::ArrayQueue;  // 🔥 新增: 无锁队列
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use base64::{Engine, engine::general_purpose::STANDARD as BASE64_STANDARD};  // 🔥 新增: base64解码
-
+use parking_lot::Mutex;
+
+use crate::chaos::ChaosInjector;
+use crate::config::Config;
+use crate::confirmation::ConfirmationTracker;
+use crate::feed_reconcile::FeedReconciler;
+use crate::price_cache::PriceCache;
+use crate::pump_global::{PumpGlobalState, PUMPFUN_GLOBAL};
+use crate::replay::EventRecorder;
 use crate::types::SniperEvent;
 
-use super::parser::parse_pumpfun_event;
+use super::dedup::SignatureDedup;
+use super::parser::{bonding_curve_account_decode, global_account_decode, parse_transaction};
 
 const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
 
+/// 订阅选项（交易过滤 + 心跳）
+#[derive(Debug, Clone)]
+pub struct SubscribeOptions {
+    /// 交易必须同时包含的账户
+    pub account_required: Vec<String>,
+    /// 交易排除的账户
+    pub account_exclude: Vec<String>,
+    /// 心跳 ping 间隔（0 = 不发送）
+    pub ping_interval: Duration,
+    /// 连续多少次 ping 未收到 pong 后重新订阅
+    pub max_missed_pongs: u32,
+    /// 重连时从上次处理的 slot 继续订阅
+    pub resume_from_slot: bool,
+    /// 交易去重表容量（0 = 不去重）
+    pub dedup_capacity: usize,
+    /// 交易去重条目的保留时长
+    pub dedup_ttl: Duration,
+    /// 故障注入（延迟投递 / 丢弃事件，需要 `chaos` feature）
+    pub chaos: ChaosInjector,
+}
+
+impl SubscribeOptions {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            account_required: config.grpc_account_required.clone(),
+            account_exclude: config.grpc_account_exclude.clone(),
+            ping_interval: Duration::from_secs(config.grpc_ping_interval_secs),
+            max_missed_pongs: config.grpc_max_missed_pongs,
+            resume_from_slot: config.grpc_resume_from_slot,
+            dedup_capacity: config.grpc_dedup_capacity,
+            dedup_ttl: Duration::from_secs(config.grpc_dedup_ttl_secs),
+            chaos: ChaosInjector::from_config(config),
+        }
+    }
+}
+
 /// Yellowstone gRPC 客户端
 #[derive(Clone)]
 pub struct GrpcClient {
     endpoint: String,
     x_token: Option<String>,
+    /// 事件流 commitment（feed_commitment）
+    commitment: CommitmentLevel,
+    /// 交易过滤 + 心跳
+    options: SubscribeOptions,
+    /// BondingCurve 账户更新写入的价格缓存
+    price_cache: Arc<PriceCache>,
+    /// Global 账户更新写入的 fee recipient / 费率
+    pump_global: Arc<PumpGlobalState>,
+    /// 交易流中匹配我们的签名，唤醒确认等待者
+    confirmations: Arc<ConfirmationTracker>,
+    /// processed 事件流确认对账（slot 状态更新驱动）
+    feed_reconciler: Arc<FeedReconciler>,
+    /// 最后处理的交易 slot（重连时作为 from_slot）
+    last_slot: Arc<AtomicU64>,
+    /// 交易去重（重连重放 / 重复推送的交易不再推入事件队列）
+    dedup: Arc<Mutex<SignatureDedup>>,
+    /// 事件录制（供 --replay 回放，未配置时不录制）
+    recorder: Arc<EventRecorder>,
 }
 
 impl GrpcClient {
     /// 创建新的 gRPC 客户端
-    pub fn new(endpoint: String, x_token: Option<String>) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        endpoint: String,
+        x_token: Option<String>,
+        commitment: CommitmentConfig,
+        options: SubscribeOptions,
+        price_cache: Arc<PriceCache>,
+        pump_global: Arc<PumpGlobalState>,
+        confirmations: Arc<ConfirmationTracker>,
+        feed_reconciler: Arc<FeedReconciler>,
+        recorder: Arc<EventRecorder>,
+    ) -> Self {
+        let commitment = match commitment.commitment {
+            SolanaCommitmentLevel::Processed => CommitmentLevel::Processed,
+            SolanaCommitmentLevel::Confirmed => CommitmentLevel::Confirmed,
+            SolanaCommitmentLevel::Finalized => CommitmentLevel::Finalized,
+        };
+
+        let dedup = Arc::new(Mutex::new(SignatureDedup::new(options.dedup_capacity, options.dedup_ttl)));
+
         Self {
             endpoint,
             x_token,
+            commitment,
+            options,
+            price_cache,
+            pump_global,
+            confirmations,
+            feed_reconciler,
+            last_slot: Arc::new(AtomicU64::new(0)),
+            dedup,
+            recorder,
         }
     }
 
@@ -60,7 +153,7 @@ impl GrpcClient {
 
             // 🔥 修复: 指数退避重连延迟（5ms -> 10ms -> 20ms -> ... -> 最多5秒）
             let delay_ms = std::cmp::min(5 * (1 << retry_count.min(10)), 5000);
-            info!("⏳ {}ms 后重连...", delay_ms);
+            info!("⏳ {}ms 后重连... (累计跳过 {} 笔重复交易)", delay_ms, self.dedup.lock().duplicates());
             tokio::time::sleep(Duration::from_millis(delay_ms)).await;
         }
     }
@@ -112,26 +205,48 @@ impl GrpcClient {
                 failed: Some(false),
                 signature: None,
                 account_include: vec![PUMPFUN_PROGRAM_ID.to_string()],
-                account_exclude: vec![],
-                account_required: vec![],
+                account_exclude: self.options.account_exclude.clone(),
+                account_required: self.options.account_required.clone(),
             },
         );
 
+        // 🔥 新增: processed 事件流订阅 slot 状态（confirmed / dead），用于对账
+        let mut slots_filter = std::collections::HashMap::new();
+        if self.feed_reconciler.is_enabled() {
+            slots_filter.insert(
+                "reconcile".to_string(),
+                SubscribeRequestFilterSlots {
+                    filter_by_commitment: Some(false),
+                    interslot_updates: Some(true),
+                },
+            );
+        }
+
+        // 🔥 新增: 重连时从上次处理的 slot 继续订阅，补回断线期间的事件
+        let from_slot = if self.options.resume_from_slot {
+            Some(self.last_slot.load(Ordering::Relaxed)).filter(|&slot| slot > 0)
+        } else {
+            None
+        };
+
         let request = SubscribeRequest {
             accounts: accounts_filter,
             transactions: transactions_filter,
-            slots: std::collections::HashMap::new(),
+            slots: slots_filter,
             blocks: std::collections::HashMap::new(),
             blocks_meta: std::collections::HashMap::new(),
             entry: std::collections::HashMap::new(),
-            commitment: Some(CommitmentLevel::Confirmed as i32),
+            commitment: Some(self.commitment as i32),
             accounts_data_slice: vec![],
             ping: None,
             transactions_status: std::collections::HashMap::new(),
-            from_slot: None,
+            from_slot,
         };
 
-        info!("📡 订阅 PumpFun 事件...");
+        match from_slot {
+            Some(slot) => info!("📡 订阅 PumpFun 事件 (commitment: {:?}, 从 slot {} 续订)...", self.commitment, slot),
+            None => info!("📡 订阅 PumpFun 事件 (commitment: {:?})...", self.commitment),
+        }
 
         // 发起订阅（yellowstone-grpc-client 返回 (Sender, Receiver)）
         let (mut subscribe_tx, mut stream) = client
@@ -147,16 +262,85 @@ impl GrpcClient {
 
         info!("✅ 成功订阅 PumpFun 事件");
 
+        // 🔥 新增: 心跳 - 定时发送 ping，连续 max_missed_pongs 次未收到 pong 视为静默中断，返回错误触发重新订阅
+        let heartbeat_enabled = !self.options.ping_interval.is_zero();
+        let mut heartbeat = tokio::time::interval(if heartbeat_enabled {
+            self.options.ping_interval
+        } else {
+            Duration::from_secs(3600)
+        });
+        heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut ping_id = 0i32;
+        let mut last_pong = Instant::now();
+        let mut received_update = false;
+
         // 处理事件流（阻塞等待直到流结束或错误）
-        while let Some(result) = stream.next().await {
+        loop {
+            let result = tokio::select! {
+                result = stream.next() => match result {
+                    Some(result) => result,
+                    None => break,
+                },
+                _ = heartbeat.tick(), if heartbeat_enabled => {
+                    let silence = last_pong.elapsed();
+                    if silence > self.options.ping_interval * self.options.max_missed_pongs {
+                        error!("💔 gRPC 心跳超时: {}s 未收到 pong，重新订阅", silence.as_secs());
+                        return Err(anyhow::anyhow!("gRPC heartbeat timed out after {}s", silence.as_secs()));
+                    }
+                    ping_id = ping_id.wrapping_add(1);
+                    subscribe_tx
+                        .send(SubscribeRequest {
+                            ping: Some(SubscribeRequestPing { id: ping_id }),
+                            ..Default::default()
+                        })
+                        .await
+                        .context("Failed to send ping")?;
+                    continue;
+                }
+            };
+
             match result {
                 Ok(update) => {
-                    if let Err(e) = Self::handle_update(update, &event_queue).await {
+                    received_update = true;
+                    match &update.update_oneof {
+                        Some(UpdateOneof::Pong(_)) => {
+                            last_pong = Instant::now();
+                        }
+                        // 服务端 ping 需要回复，否则部分负载均衡器会断开空闲连接
+                        Some(UpdateOneof::Ping(_)) => {
+                            subscribe_tx
+                                .send(SubscribeRequest {
+                                    ping: Some(SubscribeRequestPing { id: ping_id }),
+                                    ..Default::default()
+                                })
+                                .await
+                                .context("Failed to reply to ping")?;
+                        }
+                        _ => {}
+                    }
+
+                    if let Err(e) = Self::handle_update(
+                        update,
+                        &event_queue,
+                        &self.price_cache,
+                        &self.pump_global,
+                        &self.confirmations,
+                        &self.feed_reconciler,
+                        &self.last_slot,
+                        &self.dedup,
+                        &self.recorder,
+                        &self.options.chaos,
+                    ).await {
                         error!("Error handling update: {}", e);
                     }
                 }
                 Err(e) => {
                     error!("❌ gRPC 流错误: {}", e);
+                    // 续订后未收到任何更新即出错：服务端不支持或已不保留该 slot，下次从当前位置订阅
+                    if let (Some(slot), false) = (from_slot, received_update) {
+                        warn!("⚠️  从 slot {} 续订失败，下次重连从当前位置订阅（断线期间的事件会丢失）", slot);
+                        self.last_slot.store(0, Ordering::Relaxed);
+                    }
                     return Err(anyhow::anyhow!("gRPC stream error: {}", e));
                 }
             }
@@ -168,111 +352,92 @@ impl GrpcClient {
 
     /// 处理订阅更新
     /// 🔥 优化: 使用无锁队列 ArrayQueue
+    #[allow(clippy::too_many_arguments)]
     async fn handle_update(
         update: SubscribeUpdate,
         event_queue: &Arc<ArrayQueue<SniperEvent>>,
+        price_cache: &PriceCache,
+        pump_global: &PumpGlobalState,
+        confirmations: &ConfirmationTracker,
+        feed_reconciler: &FeedReconciler,
+        last_slot: &AtomicU64,
+        dedup: &Mutex<SignatureDedup>,
+        recorder: &EventRecorder,
+        chaos: &ChaosInjector,
     ) -> Result<()> {
         match update.update_oneof {
+            Some(UpdateOneof::Account(account_update)) => {
+                // 🔥 新增: BondingCurve 账户更新写入价格缓存（供监控使用，替代 RPC 轮询）
+                if let Some(account) = account_update.account {
+                    if account.pubkey.len() == 32 {
+                        let mut arr = [0u8; 32];
+                        arr.copy_from_slice(&account.pubkey);
+                        let pubkey = Pubkey::new_from_array(arr);
+
+                        // 🔥 新增: Global 账户更新刷新 fee recipient / 费率
+                        if pubkey == *PUMPFUN_GLOBAL {
+                            if let Some(global) = global_account_decode(&account.data) {
+                                pump_global.update(account_update.slot, &global);
+                            }
+                        } else if let Some(curve) = bonding_curve_account_decode(&account.data) {
+                            price_cache.update(pubkey, account_update.slot, &curve);
+                        }
+                    }
+                }
+            }
             Some(UpdateOneof::Transaction(tx_update)) => {
                 // 解析交易中的 PumpFun 事件
                 if let Some(transaction) = tx_update.transaction {
-                    let signature = bs58::encode(&transaction.signature).into_string();
-
-                    // 解析交易中的指令和日志
-                    if let Some(meta) = transaction.meta {
-                        // 🔥 修复: 从 transaction.transaction 中提取账户和指令
-                        let (account_keys, instructions) = if let Some(ref tx) = transaction.transaction {
-                            // tx 是 solana_transaction_status::proto::Transaction
-                            let account_keys: Vec<Pubkey> = tx.message.as_ref()
-                                .map(|msg| {
-                                    msg.account_keys.iter()
-                                        .filter_map(|k| {
-                                            if k.len() == 32 {
-                                                let mut arr = [0u8; 32];
-                                                arr.copy_from_slice(k);
-                                                Some(Pubkey::new_from_array(arr))
-                                            } else {
-                                                None
-                                            }
-                                        })
-                                        .collect()
-                                })
-                                .unwrap_or_default();
-
-                            let instructions = tx.message.as_ref()
-                                .map(|msg| msg.instructions.clone())
-                                .unwrap_or_default();
-
-                            (account_keys, instructions)
-                        } else {
-                            (Vec::new(), Vec::new())
-                        };
-
-                        // 🔥 检测是否为 token 创建交易（参考 sol-parser-sdk）
-                        // CreateToken 事件的 discriminator（前 16 字节）
-                        // base64 "GB7IKAUcB3c..." 解码后的 discriminator
-                        let is_created_buy = meta.log_messages.iter()
-                            .any(|log| {
-                                if let Some(data_start) = log.find("Program data: ") {
-                                    let data_str = &log[data_start + 14..];
-                                    if let Ok(decoded) = BASE64_STANDARD.decode(data_str.trim()) {
-                                        // CreateToken 事件 discriminator（前 8-16 字节）
-                                        // 参考 sol-parser-sdk 的匹配逻辑
-                                        return decoded.len() >= 16 && decoded.starts_with(&[0x18, 0x1e, 0xc8, 0x28, 0x05, 0x1c, 0x07, 0x77]);
-                                    }
-                                }
-                                false
-                            });
-
-                        // 从日志中解析事件
-                        for log in &meta.log_messages {
-                            // 🔥 修复：PumpFun 事件日志格式是 "Program data: <base64>"
-                            // 不是 "Program log: Instruction:"
-                            if log.contains("Program data:") {
-                                // 尝试解析 PumpFun 事件
-                                if let Ok(Some(mut event)) =
-                                    parse_pumpfun_event(log, &signature, tx_update.slot, is_created_buy)
-                                {
-                                    // 🔥 补全账户信息
-                                    Self::enrich_event_with_accounts(&mut event, &account_keys, &instructions);
-
-                                    debug!("Parsed PumpFun event: {:?}", event);
-                                    // 🔥 优化: 使用无锁队列推送事件
-                                    if event_queue.push(event).is_err() {
-                                        error!("❌ 事件队列已满，丢弃事件");
-                                    }
-                                }
-                            }
-                        }
+                    // 🔥 新增: 重连重放 / 重复推送的交易直接跳过，避免窗口重复计数
+                    if !dedup.lock().insert(&transaction.signature, tx_update.slot) {
+                        debug!("跳过重复交易 (slot {})", tx_update.slot);
+                        return Ok(());
+                    }
+                    // 🔥 新增: 记录续订位置
+                    last_slot.fetch_max(tx_update.slot, Ordering::Relaxed);
 
-                        // 🔥 新增: 从内部指令中补全账户信息
-                        // 某些情况下，PumpFun 指令可能作为 CPI 调用出现在 inner_instructions 中
-                        //
-                        // 📝 设计说明：当前无需在此补全账户，因为：
-                        //    1. 事件从 log_messages 解析，不是从 instructions 解析
-                        //    2. 账户已在外层补全（line 237），使用整个交易的 account_keys
-                        //    3. account_keys 包含所有账户（含 inner_instructions 涉及的）
-                        //    4. 此分支仅用于检测 CPI 场景（调试用途）
-                        //
-                        // ⚠️ 如实网遇到 CPI 场景账户缺失，可在此调用 enrich_event_with_accounts
-                        for inner_instruction in &meta.inner_instructions {
-                            for instruction in &inner_instruction.instructions {
-                                // 检查是否是 PumpFun 程序指令
-                                if (instruction.program_id_index as usize) < account_keys.len() {
-                                    let program_id = account_keys[instruction.program_id_index as usize];
-
-                                    if program_id.to_string() == PUMPFUN_PROGRAM_ID {
-                                        debug!("🔍 发现 inner_instruction 中的 PumpFun 指令");
-
-                                        // 如果之前已经解析出事件但账户不完整，可以再次尝试补全
-                                        // 这里的逻辑是：inner_instructions 可能包含额外的账户信息
-                                        // 参考: solana-streamer 的实现方式
-                                    }
-                                }
-                            }
+                    // 🔥 新增: 匹配我们自己发出的交易（订阅只推送成功交易，出现即已确认）
+                    if let Ok(sig) = solana_sdk::signature::Signature::try_from(transaction.signature.as_slice()) {
+                        confirmations.observe(&sig);
+                    }
+
+                    // 🔥 优化: 单次遍历日志和指令，事件解码时就地补全账户
+                    for event in parse_transaction(&transaction, tx_update.slot) {
+                        debug!("Parsed PumpFun event: {:?}", event);
+                        // 🔥 新增: processed 事件流登记交易事件，等待 slot 确认
+                        if let SniperEvent::Trade(trade) = &event {
+                            feed_reconciler.track(tx_update.slot, &trade.signature, trade.mint);
+                        }
+                        // 🔥 新增: 故障注入 - 丢弃 / 延迟投递（延迟会阻塞后续事件，模拟慢节点）
+                        if chaos.drop_event() {
+                            warn!("🐒 chaos: 丢弃事件 (slot {})", tx_update.slot);
+                            continue;
                         }
+                        if let Some(delay) = chaos.delivery_delay() {
+                            debug!("🐒 chaos: 延迟投递 {}ms", delay.as_millis());
+                            tokio::time::sleep(delay).await;
+                        }
+                        // 🔥 新增: 录制事件（回放模式按录制的到达时间重新注入）
+                        recorder.record(&event);
+                        // 🔥 优化: 使用无锁队列推送事件
+                        if event_queue.push(event).is_err() {
+                            error!("❌ 事件队列已满，丢弃事件");
+                        }
+                    }
+                }
+            }
+            Some(UpdateOneof::Slot(slot_update)) => {
+                // 🔥 新增: slot 状态驱动 processed 事件流对账
+                match SlotStatus::try_from(slot_update.status) {
+                    Ok(SlotStatus::SlotConfirmed) | Ok(SlotStatus::SlotFinalized) => {
+                        feed_reconciler.confirm_slot(slot_update.slot);
                     }
+                    Ok(SlotStatus::SlotDead) => {
+                        feed_reconciler.drop_slot(slot_update.slot);
+                    }
+                    _ => {}
                 }
+                feed_reconciler.expire();
             }
             Some(UpdateOneof::Ping(_)) => {
                 debug!("Received ping");
@@ -285,78 +450,67 @@ impl GrpcClient {
 
         Ok(())
     }
+}
 
-    /// 🔥 修复: 从交易指令中提取账户信息并补全事件数据
-    fn enrich_event_with_accounts(
-        event: &mut SniperEvent,
-        account_keys: &[Pubkey],
-        instructions: &[yellowstone_grpc_proto::solana::storage::confirmed_block::CompiledInstruction],
-    ) {
-        use super::parser::extract_pumpfun_accounts;
-
-        for instruction in instructions {
-            // 检查是否是 PumpFun 程序指令
-            if (instruction.program_id_index as usize) < account_keys.len() {
-                let program_id = account_keys[instruction.program_id_index as usize];
-
-                if program_id.to_string() == PUMPFUN_PROGRAM_ID {
-                    // 🔥 修复: 将 u8 账户索引转换为 u32
-                    let account_indices: Vec<u32> = instruction.accounts.iter()
-                        .map(|&idx| idx as u32)
-                        .collect();
-
-                    // 尝试解析指令获取账户
-                    if let Some(accounts) = extract_pumpfun_accounts(
-                        account_keys,
-                        &instruction.data,
-                        &account_indices,
-                    ) {
-                        // 补全事件数据并验证 mint 一致性
-                        match event {
-                            SniperEvent::Trade(ref mut trade) => {
-                                // 验证 mint 一致性
-                                if trade.mint != accounts.mint {
-                                    warn!("⚠️  Trade 事件 mint 不一致: event={}, instruction={}",
-                                        trade.mint, accounts.mint);
-                                }
-                                // 补全所有账户信息
-                                trade.bonding_curve = accounts.bonding_curve;
-                                trade.associated_bonding_curve = accounts.associated_bonding_curve;
-                                trade.creator_vault = accounts.creator_vault;
-                                trade.associated_user = accounts.associated_user;
-                                trade.global_volume_accumulator = accounts.global_volume_accumulator;
-                                trade.user_volume_accumulator = accounts.user_volume_accumulator;
-                                debug!("✅ 补全 Trade 事件账户: mint={}, bonding_curve={}, associated_bonding_curve={}, creator_vault={}, associated_user={}, global_volume_accumulator={}, user_volume_accumulator={}",
-                                    accounts.mint, accounts.bonding_curve, accounts.associated_bonding_curve, accounts.creator_vault,
-                                    accounts.associated_user, accounts.global_volume_accumulator, accounts.user_volume_accumulator);
-                            }
-                            SniperEvent::CreateToken(ref mut create) => {
-                                // 验证 mint 一致性
-                                if create.mint != accounts.mint {
-                                    warn!("⚠️  CreateToken 事件 mint 不一致: event={}, instruction={}",
-                                        create.mint, accounts.mint);
-                                }
-                                create.associated_bonding_curve = accounts.associated_bonding_curve;
-                                debug!("✅ 补全 CreateToken 事件账户: mint={}, associated_bonding_curve={}",
-                                    accounts.mint, accounts.associated_bonding_curve);
-                            }
-                            SniperEvent::Migrate(ref mut migrate) => {
-                                // 验证 mint 一致性
-                                if migrate.mint != accounts.mint {
-                                    warn!("⚠️  Migrate 事件 mint 不一致: event={}, instruction={}",
-                                        migrate.mint, accounts.mint);
-                                }
-                                migrate.global = accounts.global;
-                                migrate.withdraw_authority = accounts.withdraw_authority;
-                                migrate.associated_bonding_curve = accounts.associated_bonding_curve;
-                                debug!("✅ 补全 Migrate 事件账户: mint={}", accounts.mint);
-                            }
-                        }
-                        break;  // 找到匹配的指令后退出
-                    }
-                }
-            }
-        }
-    }
+/// gRPC 诊断探测结果
+#[derive(Debug, Clone)]
+pub struct GrpcProbe {
+    /// 建立连接耗时
+    pub connect: Duration,
+    /// 订阅后收到第一条消息的耗时
+    pub first_message: Duration,
+    /// 第一条 slot 更新的 slot（首条消息不是 slot 更新时为 None）
+    pub slot: Option<u64>,
 }
 
+/// 诊断：连接 gRPC、订阅 slot 更新并等待第一条消息（check-config 使用）
+pub async fn probe(endpoint: &str, x_token: Option<String>, first_message_timeout: Duration) -> Result<GrpcProbe> {
+    let started = Instant::now();
+    let mut client = GeyserGrpcClient::build_from_shared(endpoint.to_string())
+        .context("Invalid gRPC endpoint")?
+        .x_token(x_token)
+        .context("Failed to set x_token")?
+        .tls_config(ClientTlsConfig::new().with_native_roots())
+        .context("Failed to set TLS config")?
+        .connect_timeout(Duration::from_secs(10))
+        .timeout(Duration::from_secs(30))
+        .connect()
+        .await
+        .context("Failed to connect to gRPC server")?;
+    let connect = started.elapsed();
+
+    let mut slots_filter = std::collections::HashMap::new();
+    slots_filter.insert(
+        "probe".to_string(),
+        SubscribeRequestFilterSlots {
+            filter_by_commitment: Some(false),
+            interslot_updates: Some(false),
+        },
+    );
+    let request = SubscribeRequest {
+        slots: slots_filter,
+        commitment: Some(CommitmentLevel::Processed as i32),
+        ..Default::default()
+    };
+
+    let (mut subscribe_tx, mut stream) = client.subscribe().await.context("Failed to subscribe")?;
+    subscribe_tx.send(request).await.context("Failed to send subscribe request")?;
+
+    let subscribed = Instant::now();
+    let update = tokio::time::timeout(first_message_timeout, stream.next())
+        .await
+        .map_err(|_| anyhow::anyhow!("no message within {}s", first_message_timeout.as_secs()))?
+        .ok_or_else(|| anyhow::anyhow!("stream closed before the first message"))?
+        .context("gRPC stream error")?;
+
+    let slot = match update.update_oneof {
+        Some(UpdateOneof::Slot(slot_update)) => Some(slot_update.slot),
+        _ => None,
+    };
+
+    Ok(GrpcProbe {
+        connect,
+        first_message: subscribed.elapsed(),
+        slot,
+    })
+}
@@ -0,0 +1,76 @@
+/// 交易去重（跨重连）
+///
+/// 重连后 from_slot 重放、多个订阅源重复推送时，同一笔交易可能被再次推入事件队列，导致窗口重复计数
+///
+/// 核心功能:
+/// 1. 按 (签名, slot) 去重 - 同一 slot 的重复推送被丢弃；分叉切换后在新 slot 重新打包的交易仍会通过
+/// 2. 容量上限 - 超过容量时淘汰最早记录的条目
+/// 3. TTL - 超过 TTL 的条目视为过期，不再参与去重
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+type DedupKey = (Vec<u8>, u64);
+
+/// 签名去重表（插入顺序淘汰）
+pub struct SignatureDedup {
+    seen: HashMap<DedupKey, Instant>,
+    order: VecDeque<DedupKey>,
+    capacity: usize,
+    ttl: Duration,
+    /// 累计丢弃的重复交易数
+    duplicates: u64,
+}
+
+impl SignatureDedup {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            seen: HashMap::with_capacity(capacity.min(1 << 16)),
+            order: VecDeque::with_capacity(capacity.min(1 << 16)),
+            capacity,
+            ttl,
+            duplicates: 0,
+        }
+    }
+
+    /// 记录交易，返回 false 表示重复交易（应丢弃）
+    pub fn insert(&mut self, signature: &[u8], slot: u64) -> bool {
+        if self.capacity == 0 {
+            return true;
+        }
+
+        let now = Instant::now();
+        self.evict(now);
+
+        let key = (signature.to_vec(), slot);
+        if self.seen.contains_key(&key) {
+            self.duplicates += 1;
+            return false;
+        }
+
+        self.seen.insert(key.clone(), now);
+        self.order.push_back(key);
+        true
+    }
+
+    /// 累计丢弃的重复交易数
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates
+    }
+
+    /// 淘汰过期和超出容量的条目
+    fn evict(&mut self, now: Instant) {
+        while let Some(front) = self.order.front() {
+            let expired = self
+                .seen
+                .get(front)
+                .is_none_or(|inserted_at| now.duration_since(*inserted_at) >= self.ttl);
+            if !expired && self.order.len() < self.capacity {
+                break;
+            }
+            if let Some(key) = self.order.pop_front() {
+                self.seen.remove(&key);
+            }
+        }
+    }
+}
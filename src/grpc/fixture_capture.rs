@@ -0,0 +1,119 @@
+/// PumpFun 样本抓取（capture-fixtures 子命令）
+///
+/// 解析器测试的样本应来自链上真实数据：按交易签名读取交易日志中的 PumpFun 事件，
+/// 再读取事件涉及的 bonding curve 账户和 Global 账户，按 fixtures/pumpfun_payloads.txt 的格式输出
+///
+/// 核心功能:
+/// 1. 事件 - "Program data: " 日志按 16 字节事件鉴别器分类为 trade / create / migrate
+///    （按鉴别器而不是按解析结果分类，解析器拒绝的真实 payload 正是测试要覆盖的）
+/// 2. 账户 - 事件涉及 mint 的 bonding curve 账户（抓取时的当前状态）和 Global 账户，含 8 字节账户鉴别器
+/// 3. 来源 - 每笔交易的样本前输出 "# <签名> slot <slot>" 注释，便于核对
+
+use anyhow::{Context, Result};
+use base64::prelude::*;
+use log::warn;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use crate::config::Config;
+use crate::dex::pumpfun::PUMPFUN_PROGRAM_ID;
+use crate::grpc::parser::{discriminators, parse_pumpfun_event};
+use crate::pump_global::PUMPFUN_GLOBAL;
+use crate::types::SniperEvent;
+
+/// 事件鉴别器对应的样本类型
+fn event_kind(data: &[u8]) -> Option<&'static str> {
+    let discriminator = data.get(..16)?;
+    if discriminator == discriminators::TRADE_EVENT {
+        Some("trade")
+    } else if discriminator == discriminators::CREATE_TOKEN_EVENT {
+        Some("create")
+    } else if discriminator == discriminators::COMPLETE_PUMP_AMM_MIGRATION_EVENT {
+        Some("migrate")
+    } else {
+        None
+    }
+}
+
+fn event_mint(event: &SniperEvent) -> Pubkey {
+    match event {
+        SniperEvent::Trade(trade) => trade.mint,
+        SniperEvent::CreateToken(create) => create.mint,
+        SniperEvent::Migrate(migrate) => migrate.mint,
+    }
+}
+
+/// capture-fixtures 子命令：返回可直接追加到 fixtures/pumpfun_payloads.txt 的样本行
+pub async fn capture(config: &Config, signatures: &[String]) -> Result<String> {
+    if signatures.is_empty() {
+        anyhow::bail!("usage: solsniper capture-fixtures <signature>...");
+    }
+
+    let program_id = Pubkey::from_str(PUMPFUN_PROGRAM_ID).context("Invalid PumpFun program ID")?;
+    let rpc_client = RpcClient::new_with_commitment(config.rpc_endpoint.clone(), CommitmentConfig::confirmed());
+    let transaction_config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Json),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+
+    let mut lines = Vec::new();
+    let mut mints = BTreeSet::new();
+    for signature in signatures {
+        let parsed = Signature::from_str(signature).with_context(|| format!("invalid signature: {}", signature))?;
+        let transaction = rpc_client
+            .get_transaction_with_config(&parsed, transaction_config)
+            .await
+            .with_context(|| format!("读取交易失败: {}", signature))?;
+        let logs: Option<Vec<String>> = transaction
+            .transaction
+            .meta
+            .map(|meta| meta.log_messages.into())
+            .unwrap_or_default();
+
+        lines.push(format!("# {} slot {}", signature, transaction.slot));
+        let before = lines.len();
+        for log in logs.unwrap_or_default() {
+            let Some(payload) = log.strip_prefix("Program data: ") else {
+                continue;
+            };
+            let Ok(data) = BASE64_STANDARD.decode(payload.trim()) else {
+                continue;
+            };
+            let Some(kind) = event_kind(&data) else {
+                continue;
+            };
+
+            lines.push(format!("{} {}", kind, payload.trim()));
+            if let Ok(Some(event)) = parse_pumpfun_event(&log, signature, transaction.slot) {
+                mints.insert(event_mint(&event));
+            }
+        }
+        if lines.len() == before {
+            anyhow::bail!("transaction {} has no PumpFun events", signature);
+        }
+    }
+
+    // 账户数据是抓取时的状态，不是事件发生时的状态
+    let slot = rpc_client.get_slot().await.context("读取 slot 失败")?;
+    lines.push(format!("# accounts slot {}", slot));
+    for mint in mints {
+        let (bonding_curve, _) = Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &program_id);
+        match rpc_client.get_account_data(&bonding_curve).await {
+            Ok(data) => lines.push(format!("bonding_curve {}", BASE64_STANDARD.encode(data))),
+            Err(e) => warn!("⚠️  读取 bonding curve 失败，跳过: {} ({})", mint, e),
+        }
+    }
+    let global = rpc_client
+        .get_account_data(&PUMPFUN_GLOBAL)
+        .await
+        .context("读取 PumpFun Global 账户失败")?;
+    lines.push(format!("global {}", BASE64_STANDARD.encode(global)));
+
+    Ok(lines.join("\n"))
+}
@@ -1,5 +1,7 @@
 pub mod client;
+pub mod dedup;
+pub mod fixture_capture;
 pub mod parser;
 
-pub use client::GrpcClient;
+pub use client::{GrpcClient, SubscribeOptions};
 
@@ -1,12 +1,21 @@
 use anyhow::Result;
 use base64::prelude::*;
 use borsh::BorshDeserialize;
+use once_cell::sync::Lazy;
 use solana_sdk::pubkey::Pubkey;
+use yellowstone_grpc_proto::prelude::{
+    CompiledInstruction, InnerInstructions, Message, SubscribeUpdateTransactionInfo, TransactionStatusMeta,
+};
 
 use crate::types::{CreateTokenEventData, SniperEvent, TradeEventData, MigrateEventData};
 
+/// PumpFun 程序地址
+static PUMPFUN_PROGRAM_ID: Lazy<Pubkey> = Lazy::new(|| {
+    Pubkey::try_from("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P").expect("Invalid PUMPFUN_PROGRAM_ID")
+});
+
 /// PumpFun 事件类型
-#[allow(dead_code)]
+#[allow(dead_code, clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum PumpFunEvent {
     Trade(TradeEventData),
@@ -30,9 +39,7 @@ pub mod discriminators {
     pub const MIGRATE_IX: &[u8] = &[155, 234, 231, 146, 236, 158, 162, 30];
 
     // 账户鉴别器
-    #[allow(dead_code)] // 预留：用于 BondingCurve 账户识别
     pub const BONDING_CURVE_ACCOUNT: &[u8] = &[23, 183, 248, 55, 96, 216, 172, 96];
-    #[allow(dead_code)] // 预留：用于 Global 账户识别
     pub const GLOBAL_ACCOUNT: &[u8] = &[167, 232, 232, 177, 200, 108, 114, 127];
 }
 
@@ -42,7 +49,7 @@ const TRADE_EVENT_DISCRIMINATOR: &[u8] = discriminators::TRADE_EVENT;
 
 /// PumpFun Trade 事件结构（Borsh 反序列化）
 /// 🔥 注意：PumpFun 事件日志本身不包含 is_created_buy 字段
-/// 该字段需要在上层解析时根据交易上下文判断（参考 sol-parser-sdk）
+/// 该字段由 parse_transaction 根据同一交易中的 CreateToken 事件判断
 #[derive(BorshDeserialize, Debug)]
 struct PumpFunTradeEventRaw {
     mint: [u8; 32],
@@ -102,30 +109,139 @@ struct PumpFunMigrateEventRaw {
 
 // PumpFun 事件大小常量（完全参考 solana-streamer）
 const PUMPFUN_TRADE_EVENT_LOG_SIZE: usize = 250;
-const PUMPFUN_CREATE_TOKEN_EVENT_LOG_SIZE: usize = 257;
 const PUMPFUN_MIGRATE_EVENT_LOG_SIZE: usize = 160;  // 🔥 修复：应该是 160，不是 112
 
-/// 从日志中解析 PumpFun 事件
-/// 🔥 新增 is_created_buy 参数（参考 sol-parser-sdk）
+/// 解析一笔 gRPC 交易的所有 PumpFun 事件（单次遍历）
+///
+/// 1. 遍历一次 PumpFun 指令（含 CPI 内部指令），提取账户（带指令类型）
+/// 2. 遍历一次日志，解码事件并就地补全账户（按 mint + 指令类型匹配，每条指令只用一次）；
+///    同一交易中已出现该 mint 的 CreateToken 事件时，Trade 标记为 is_created_buy（创建时的首次买入）
+pub fn parse_transaction(tx: &SubscribeUpdateTransactionInfo, slot: u64) -> Vec<SniperEvent> {
+    let Some(meta) = &tx.meta else {
+        return Vec::new();
+    };
+
+    let signature = bs58::encode(&tx.signature).into_string();
+    let instruction_accounts = match tx.transaction.as_ref().and_then(|transaction| transaction.message.as_ref()) {
+        Some(message) => {
+            let account_keys = transaction_account_keys(message, meta);
+            pumpfun_instruction_accounts(&account_keys, &message.instructions, &meta.inner_instructions)
+        }
+        None => Vec::new(),
+    };
+
+    parse_log_events(&meta.log_messages, &signature, slot, instruction_accounts)
+}
+
+/// 指令账户索引对应的完整账户列表
+///
+/// 索引顺序：静态账户 → 地址查找表加载的可写账户 → 加载的只读账户
+/// （内部指令经常引用查找表加载的账户）
+///
+/// 🔥 修复: 加载地址数量与 address_table_lookups 声明的不一致（meta 缺失加载地址）时，
+/// 只返回静态账户，引用查找表的索引查不到账户，避免映射到错误的 bonding curve；
+/// 任何地址长度非法时返回空列表（跳过会让后续索引整体错位）
+fn transaction_account_keys(message: &Message, meta: &TransactionStatusMeta) -> Vec<Pubkey> {
+    let declared_loaded: usize = message
+        .address_table_lookups
+        .iter()
+        .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+        .sum();
+    let loaded = meta.loaded_writable_addresses.len() + meta.loaded_readonly_addresses.len();
+
+    let static_keys = message.account_keys.iter();
+    let keys: Option<Vec<Pubkey>> = if loaded == declared_loaded {
+        static_keys
+            .chain(&meta.loaded_writable_addresses)
+            .chain(&meta.loaded_readonly_addresses)
+            .map(|key| <[u8; 32]>::try_from(key.as_slice()).ok().map(Pubkey::new_from_array))
+            .collect()
+    } else {
+        log::debug!("⚠️  加载地址数量不一致 (声明 {}, 实际 {})，仅使用静态账户", declared_loaded, loaded);
+        static_keys
+            .map(|key| <[u8; 32]>::try_from(key.as_slice()).ok().map(Pubkey::new_from_array))
+            .collect()
+    };
+
+    keys.unwrap_or_default()
+}
+
+/// 提取所有 PumpFun 指令的账户（按执行顺序：外层指令，随后是它的 CPI 内部指令）
+///
+/// 🔥 新增: 聚合器 / 合约路由的买卖只以内部指令出现，外层指令不是 PumpFun
+fn pumpfun_instruction_accounts(
+    account_keys: &[Pubkey],
+    instructions: &[CompiledInstruction],
+    inner_instructions: &[InnerInstructions],
+) -> Vec<PumpFunAccounts> {
+    let mut result = Vec::new();
+    let mut extract = |program_id_index: u32, data: &[u8], accounts: &[u8]| {
+        if account_keys.get(program_id_index as usize) != Some(&*PUMPFUN_PROGRAM_ID) {
+            return;
+        }
+        if let Some(extracted) = extract_pumpfun_accounts(account_keys, data, accounts) {
+            result.push(extracted);
+        }
+    };
+
+    for (index, instruction) in instructions.iter().enumerate() {
+        extract(instruction.program_id_index, &instruction.data, &instruction.accounts);
+
+        for inner in inner_instructions.iter().filter(|inner| inner.index as usize == index) {
+            for instruction in &inner.instructions {
+                extract(instruction.program_id_index, &instruction.data, &instruction.accounts);
+            }
+        }
+    }
+
+    result
+}
+
+/// 按日志顺序解码事件，并用匹配的指令账户补全
+fn parse_log_events(
+    log_messages: &[String],
+    signature: &str,
+    slot: u64,
+    mut instruction_accounts: Vec<PumpFunAccounts>,
+) -> Vec<SniperEvent> {
+    let mut events = Vec::new();
+    let mut created: Vec<Pubkey> = Vec::new();
+
+    for log in log_messages {
+        let Ok(Some(mut event)) = parse_pumpfun_event(log, signature, slot) else {
+            continue;
+        };
+
+        match &mut event {
+            SniperEvent::CreateToken(create) => created.push(create.mint),
+            // 创建指令总是先于同一 mint 的买入执行，日志顺序一致
+            SniperEvent::Trade(trade) => trade.is_created_buy = created.contains(&trade.mint),
+            SniperEvent::Migrate(_) => {}
+        }
+
+        if let Some(index) = instruction_accounts.iter().position(|accounts| accounts.matches(&event)) {
+            let accounts = instruction_accounts.remove(index);
+            accounts.apply_to(&mut event);
+        }
+
+        events.push(event);
+    }
+
+    events
+}
+
+/// 从单条日志中解析 PumpFun 事件（Trade 的 is_created_buy 固定为 false，由 parse_transaction 补全）
 pub fn parse_pumpfun_event(
     log: &str,
     signature: &str,
     slot: u64,
-    is_created_buy: bool,
 ) -> Result<Option<SniperEvent>> {
-    // 检查是否包含 Program data
-    if !log.contains("Program data:") {
+    // 提取 base64 编码的数据（"Program data: <base64>"）
+    let Some(data_str) = log.strip_prefix("Program data: ") else {
         return Ok(None);
-    }
-
-    // 提取 base64 编码的数据
-    let parts: Vec<&str> = log.split("Program data: ").collect();
-    if parts.len() < 2 {
-        return Ok(None);
-    }
+    };
 
-    let data_str = parts[1].trim();
-    let data = match base64::prelude::BASE64_STANDARD.decode(data_str) {
+    let data = match base64::prelude::BASE64_STANDARD.decode(data_str.trim()) {
         Ok(d) => d,
         Err(_) => return Ok(None),
     };
@@ -141,7 +257,7 @@ pub fn parse_pumpfun_event(
 
     // 根据鉴别器解析不同类型的事件（使用完整 16 字节比较）
     if discriminator == TRADE_EVENT_DISCRIMINATOR {
-        parse_trade_event(&data[16..], signature, slot, is_created_buy)
+        parse_trade_event(&data[16..], signature, slot)
     } else if discriminator == CREATE_TOKEN_EVENT_DISCRIMINATOR {
         parse_create_token_event(&data[16..], signature, slot)
     } else if discriminator == discriminators::COMPLETE_PUMP_AMM_MIGRATION_EVENT {
@@ -153,12 +269,10 @@ pub fn parse_pumpfun_event(
 }
 
 /// 解析交易事件
-/// 🔥 新增 is_created_buy 参数（参考 sol-parser-sdk/src/logs/pumpfun.rs:191）
 fn parse_trade_event(
     data: &[u8],
     signature: &str,
-    _slot: u64,
-    is_created_buy: bool,
+    slot: u64,
 ) -> Result<Option<SniperEvent>> {
     // 检查数据大小（完全参考 solana-streamer）
     if data.len() < PUMPFUN_TRADE_EVENT_LOG_SIZE {
@@ -193,12 +307,13 @@ fn parse_trade_event(
         // 核心交易数据
         mint: Pubkey::new_from_array(raw_event.mint),
         is_buy: raw_event.is_buy,
-        is_created_buy,  // 🔥 新增字段（参考 sol-parser-sdk/src/logs/pumpfun.rs:272）
+        is_created_buy: false,  // 🔥 由 parse_transaction 根据同一交易的 CreateToken 事件设置
         sol_amount: raw_event.sol_amount,
         token_amount: raw_event.token_amount,
         user: Pubkey::new_from_array(raw_event.user),
         timestamp: raw_event.timestamp,
         signature: signature.to_string(),
+        slot,
 
         // 储备数据
         virtual_sol_reserves: raw_event.virtual_sol_reserves,
@@ -239,12 +354,8 @@ fn parse_create_token_event(
     signature: &str,
     _slot: u64,
 ) -> Result<Option<SniperEvent>> {
-    // 检查数据大小（完全参考 solana-streamer）
-    if data.len() < PUMPFUN_CREATE_TOKEN_EVENT_LOG_SIZE {
-        return Ok(None);
-    }
-
-    let raw_event = match PumpFunCreateTokenEventRaw::try_from_slice(&data[..PUMPFUN_CREATE_TOKEN_EVENT_LOG_SIZE]) {
+    // 🔥 修复: name / symbol / uri 是变长字符串，按前缀反序列化（不要求固定长度，忽略尾部新增字段）
+    let raw_event = match PumpFunCreateTokenEventRaw::deserialize(&mut &data[..]) {
         Ok(e) => e,
         Err(_) => return Ok(None),
     };
@@ -334,7 +445,7 @@ fn parse_migrate_event(
 pub fn extract_pumpfun_accounts(
     account_keys: &[Pubkey],
     instruction_data: &[u8],
-    account_indices: &[u32],  // 🔥 新增: 指令账户索引列表
+    account_indices: &[u8],  // 🔥 新增: 指令账户索引列表（CompiledInstruction.accounts）
 ) -> Option<PumpFunAccounts> {
     // 根据指令鉴别器判断指令类型
     if instruction_data.len() < 8 {
@@ -359,6 +470,7 @@ pub fn extract_pumpfun_accounts(
         // 12: global_volume_accumulator, 13: user_volume_accumulator, 14: fee_config, 15: fee_program
         if account_indices.len() >= 16 {
             return Some(PumpFunAccounts {
+                kind: PumpFunInstruction::Buy,
                 mint: get_account(2)?,
                 bonding_curve: get_account(3)?,
                 associated_bonding_curve: get_account(4)?,
@@ -377,6 +489,7 @@ pub fn extract_pumpfun_accounts(
         // 9: token_program, 10: event_authority, 11: program, 12: fee_config, 13: fee_program
         if account_indices.len() >= 14 {
             return Some(PumpFunAccounts {
+                kind: PumpFunInstruction::Sell,
                 mint: get_account(2)?,
                 bonding_curve: get_account(3)?,
                 associated_bonding_curve: get_account(4)?,
@@ -395,6 +508,7 @@ pub fn extract_pumpfun_accounts(
         // 9: token_program, 10: associated_token_program, 11: rent, 12: event_authority, 13: program
         if account_indices.len() >= 11 {
             return Some(PumpFunAccounts {
+                kind: PumpFunInstruction::Create,
                 mint: get_account(0)?,
                 bonding_curve: get_account(2)?,
                 associated_bonding_curve: get_account(3)?,
@@ -415,6 +529,7 @@ pub fn extract_pumpfun_accounts(
         // 17: associated_token_program, 18: event_authority, 19: program
         if account_indices.len() >= 20 {
             return Some(PumpFunAccounts {
+                kind: PumpFunInstruction::Migrate,
                 mint: get_account(2)?,
                 bonding_curve: get_account(3)?,
                 associated_bonding_curve: get_account(4)?,
@@ -431,9 +546,19 @@ pub fn extract_pumpfun_accounts(
     None
 }
 
+/// PumpFun 指令类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PumpFunInstruction {
+    Buy,
+    Sell,
+    Create,
+    Migrate,
+}
+
 /// PumpFun 账户信息（完整版）
 #[derive(Debug, Clone)]
 pub struct PumpFunAccounts {
+    pub kind: PumpFunInstruction,
     pub mint: Pubkey,
     pub bonding_curve: Pubkey,
     pub associated_bonding_curve: Pubkey,
@@ -446,6 +571,42 @@ pub struct PumpFunAccounts {
     pub user_volume_accumulator: Pubkey,  // 用户交易量累积器
 }
 
+impl PumpFunAccounts {
+    /// 是否为产生该事件的指令（mint 相同且指令类型对应）
+    fn matches(&self, event: &SniperEvent) -> bool {
+        match event {
+            SniperEvent::Trade(trade) => {
+                let kind = if trade.is_buy { PumpFunInstruction::Buy } else { PumpFunInstruction::Sell };
+                self.kind == kind && self.mint == trade.mint
+            }
+            SniperEvent::CreateToken(create) => self.kind == PumpFunInstruction::Create && self.mint == create.mint,
+            SniperEvent::Migrate(migrate) => self.kind == PumpFunInstruction::Migrate && self.mint == migrate.mint,
+        }
+    }
+
+    /// 用指令账户补全事件数据
+    fn apply_to(&self, event: &mut SniperEvent) {
+        match event {
+            SniperEvent::Trade(trade) => {
+                trade.bonding_curve = self.bonding_curve;
+                trade.associated_bonding_curve = self.associated_bonding_curve;
+                trade.creator_vault = self.creator_vault;
+                trade.associated_user = self.associated_user;
+                trade.global_volume_accumulator = self.global_volume_accumulator;
+                trade.user_volume_accumulator = self.user_volume_accumulator;
+            }
+            SniperEvent::CreateToken(create) => {
+                create.associated_bonding_curve = self.associated_bonding_curve;
+            }
+            SniperEvent::Migrate(migrate) => {
+                migrate.global = self.global;
+                migrate.withdraw_authority = self.withdraw_authority;
+                migrate.associated_bonding_curve = self.associated_bonding_curve;
+            }
+        }
+    }
+}
+
 /// PumpFun BondingCurve 账户结构（完全参考 solana-streamer）
 #[derive(Clone, Debug, Default, PartialEq, BorshDeserialize)]
 pub struct BondingCurve {
@@ -460,8 +621,18 @@ pub struct BondingCurve {
 
 pub const BONDING_CURVE_SIZE: usize = 8 * 5 + 1 + 32; // 73 bytes
 
-/// 解码 BondingCurve 账户数据
-#[allow(dead_code)]
+impl BondingCurve {
+    /// 曲线完成度（百分比），已完成的曲线固定为 100%
+    #[allow(dead_code)]
+    pub fn progress_percent(&self, graduation_target_sol: f64) -> f64 {
+        if self.complete {
+            return 100.0;
+        }
+        crate::types::curve_progress_percent(self.real_sol_reserves, graduation_target_sol)
+    }
+}
+
+/// 解码 BondingCurve 账户数据（不含 8 字节账户鉴别器）
 pub fn bonding_curve_decode(data: &[u8]) -> Option<BondingCurve> {
     if data.len() < BONDING_CURVE_SIZE {
         return None;
@@ -469,6 +640,14 @@ pub fn bonding_curve_decode(data: &[u8]) -> Option<BondingCurve> {
     borsh::from_slice::<BondingCurve>(&data[..BONDING_CURVE_SIZE]).ok()
 }
 
+/// 解码带账户鉴别器的 BondingCurve 账户数据（gRPC 账户更新 / RPC 读取的原始账户数据）
+///
+/// 非 BondingCurve 账户（鉴别器不匹配）返回 None
+pub fn bonding_curve_account_decode(data: &[u8]) -> Option<BondingCurve> {
+    let body = data.strip_prefix(discriminators::BONDING_CURVE_ACCOUNT)?;
+    bonding_curve_decode(body)
+}
+
 /// PumpFun Global 配置结构（完全参考 solana-streamer）
 #[derive(Clone, Debug, Default, PartialEq, BorshDeserialize)]
 pub struct Global {
@@ -489,10 +668,9 @@ pub struct Global {
     pub admin_set_creator_authority: Pubkey,
 }
 
-pub const GLOBAL_SIZE: usize = 1 + 32 * 2 + 8 * 5 + 32 + 1 + 8 * 2 + 32 * 7 + 32 * 2; // 481 bytes
+pub const GLOBAL_SIZE: usize = 1 + 32 * 2 + 8 * 5 + 32 + 1 + 8 * 2 + 32 * 7 + 32 * 2; // 442 bytes
 
-/// 解码 Global 账户数据
-#[allow(dead_code)]
+/// 解码 Global 账户数据（不含 8 字节账户鉴别器）
 pub fn global_decode(data: &[u8]) -> Option<Global> {
     if data.len() < GLOBAL_SIZE {
         return None;
@@ -500,3 +678,570 @@ pub fn global_decode(data: &[u8]) -> Option<Global> {
     borsh::from_slice::<Global>(&data[..GLOBAL_SIZE]).ok()
 }
 
+/// 解码带账户鉴别器的 Global 账户数据（gRPC 账户更新 / RPC 读取的原始账户数据）
+pub fn global_account_decode(data: &[u8]) -> Option<Global> {
+    let body = data.strip_prefix(discriminators::GLOBAL_ACCOUNT)?;
+    global_decode(body)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNATURE: &str = "fixture-signature";
+
+    fn program_data(discriminator: &[u8], body: &[u8]) -> String {
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(body);
+        format!("Program data: {}", BASE64_STANDARD.encode(data))
+    }
+
+    fn trade_body(mint: Pubkey, user: Pubkey, is_buy: bool, sol_amount: u64) -> Vec<u8> {
+        let mut body = Vec::with_capacity(PUMPFUN_TRADE_EVENT_LOG_SIZE);
+        body.extend_from_slice(mint.as_ref());
+        body.extend_from_slice(&sol_amount.to_le_bytes());
+        body.extend_from_slice(&1_000_000u64.to_le_bytes()); // token_amount
+        body.push(is_buy as u8);
+        body.extend_from_slice(user.as_ref());
+        body.extend_from_slice(&1_700_000_000i64.to_le_bytes()); // timestamp
+        for reserve in [30_000_000_000u64, 1_073_000_000_000_000, 0, 793_100_000_000_000] {
+            body.extend_from_slice(&reserve.to_le_bytes());
+        }
+        body.extend_from_slice(Pubkey::new_unique().as_ref()); // fee_recipient
+        body.extend_from_slice(&95u64.to_le_bytes());
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.extend_from_slice(user.as_ref()); // creator
+        body.extend_from_slice(&30u64.to_le_bytes());
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.push(1); // track_volume
+        for value in [0u64, 0, 0] {
+            body.extend_from_slice(&value.to_le_bytes());
+        }
+        body.extend_from_slice(&0i64.to_le_bytes()); // last_update_timestamp
+        assert_eq!(body.len(), PUMPFUN_TRADE_EVENT_LOG_SIZE);
+        body
+    }
+
+    fn trade_log(mint: Pubkey, user: Pubkey, is_buy: bool, sol_amount: u64) -> String {
+        program_data(TRADE_EVENT_DISCRIMINATOR, &trade_body(mint, user, is_buy, sol_amount))
+    }
+
+    fn create_body(mint: Pubkey, creator: Pubkey, name: &str) -> Vec<u8> {
+        let mut body = Vec::new();
+        for text in [name, "TEST", "https://ipfs.io/ipfs/fixture"] {
+            body.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            body.extend_from_slice(text.as_bytes());
+        }
+        body.extend_from_slice(mint.as_ref());
+        body.extend_from_slice(Pubkey::new_unique().as_ref()); // bonding_curve
+        body.extend_from_slice(creator.as_ref()); // user
+        body.extend_from_slice(creator.as_ref()); // creator
+        body.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        for value in [1_073_000_000_000_000u64, 30_000_000_000, 793_100_000_000_000, 1_000_000_000_000_000] {
+            body.extend_from_slice(&value.to_le_bytes());
+        }
+        body
+    }
+
+    fn create_log(mint: Pubkey, creator: Pubkey, name: &str) -> String {
+        program_data(CREATE_TOKEN_EVENT_DISCRIMINATOR, &create_body(mint, creator, name))
+    }
+
+    fn migrate_body(mint: Pubkey, pool: Pubkey, sol_amount: u64, timestamp: i64) -> Vec<u8> {
+        let mut body = Vec::with_capacity(PUMPFUN_MIGRATE_EVENT_LOG_SIZE);
+        body.extend_from_slice(Pubkey::new_unique().as_ref()); // user
+        body.extend_from_slice(mint.as_ref());
+        body.extend_from_slice(&206_900_000_000_000u64.to_le_bytes()); // mint_amount
+        body.extend_from_slice(&sol_amount.to_le_bytes());
+        body.extend_from_slice(&15_000_000u64.to_le_bytes()); // pool_migration_fee
+        body.extend_from_slice(Pubkey::new_unique().as_ref()); // bonding_curve
+        body.extend_from_slice(&timestamp.to_le_bytes());
+        body.extend_from_slice(pool.as_ref());
+        assert_eq!(body.len(), PUMPFUN_MIGRATE_EVENT_LOG_SIZE);
+        body
+    }
+
+    fn logs(lines: &[String]) -> Vec<String> {
+        let mut logs = vec!["Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [1]".to_string()];
+        logs.extend_from_slice(lines);
+        logs.push("Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P success".to_string());
+        logs
+    }
+
+    fn trades(events: &[SniperEvent]) -> Vec<&TradeEventData> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                SniperEvent::Trade(trade) => Some(trade),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn create_and_buy_in_same_transaction_is_created_buy() {
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let logs = logs(&[create_log(mint, creator, "Fixture"), trade_log(mint, creator, true, 500_000_000)]);
+
+        let events = parse_log_events(&logs, SIGNATURE, 1, Vec::new());
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], SniperEvent::CreateToken(create) if create.mint == mint));
+        let trades = trades(&events);
+        assert_eq!(trades.len(), 1);
+        assert!(trades[0].is_created_buy);
+        assert_eq!(trades[0].signature, SIGNATURE);
+    }
+
+    #[test]
+    fn plain_buy_is_not_created_buy() {
+        let mint = Pubkey::new_unique();
+        let logs = logs(&[trade_log(mint, Pubkey::new_unique(), true, 100_000_000)]);
+
+        let events = parse_log_events(&logs, SIGNATURE, 1, Vec::new());
+
+        let trades = trades(&events);
+        assert_eq!(trades.len(), 1);
+        assert!(!trades[0].is_created_buy);
+    }
+
+    #[test]
+    fn create_of_other_mint_does_not_mark_trade() {
+        let created = Pubkey::new_unique();
+        let traded = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let logs = logs(&[
+            create_log(created, creator, "Fixture"),
+            trade_log(created, creator, true, 500_000_000),
+            trade_log(traded, Pubkey::new_unique(), true, 100_000_000),
+        ]);
+
+        let events = parse_log_events(&logs, SIGNATURE, 1, Vec::new());
+
+        let trades = trades(&events);
+        assert_eq!(trades.len(), 2);
+        assert!(trades.iter().find(|trade| trade.mint == created).unwrap().is_created_buy);
+        assert!(!trades.iter().find(|trade| trade.mint == traded).unwrap().is_created_buy);
+    }
+
+    #[test]
+    fn create_event_with_any_name_length_is_parsed() {
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        for name in ["A", "A Much Longer Token Name For Fixtures"] {
+            let logs = logs(&[create_log(mint, creator, name)]);
+            let events = parse_log_events(&logs, SIGNATURE, 1, Vec::new());
+            assert!(matches!(&events[..], [SniperEvent::CreateToken(create)] if create.name == name));
+        }
+    }
+
+    #[test]
+    fn trade_accounts_are_filled_from_matching_instruction() {
+        let keys: Vec<Pubkey> = (0..16).map(|_| Pubkey::new_unique()).collect();
+        let mint = keys[2];
+        let indices: Vec<u8> = (0..16).collect();
+        let mut data = discriminators::BUY_IX.to_vec();
+        data.extend_from_slice(&[0u8; 16]);
+        let buy_accounts = extract_pumpfun_accounts(&keys, &data, &indices).unwrap();
+        assert_eq!(buy_accounts.kind, PumpFunInstruction::Buy);
+
+        let logs = logs(&[
+            trade_log(mint, Pubkey::new_unique(), false, 100_000_000),
+            trade_log(mint, Pubkey::new_unique(), true, 100_000_000),
+        ]);
+        let events = parse_log_events(&logs, SIGNATURE, 1, vec![buy_accounts]);
+
+        let trades = trades(&events);
+        // 卖出事件不匹配买入指令，账户保持默认
+        assert_eq!(trades[0].bonding_curve, Pubkey::default());
+        assert_eq!(trades[1].bonding_curve, keys[3]);
+        assert_eq!(trades[1].associated_bonding_curve, keys[4]);
+        assert_eq!(trades[1].creator_vault, keys[9]);
+    }
+
+    /// 聚合器外层指令 + PumpFun 买入内部指令，bonding curve 账户来自地址查找表
+    /// `with_loaded_addresses = false` 模拟 meta 缺失加载地址的情况
+    fn routed_buy_transaction(
+        buy_keys: &[Pubkey],
+        logs: Vec<String>,
+        with_loaded_addresses: bool,
+    ) -> SubscribeUpdateTransactionInfo {
+        use yellowstone_grpc_proto::prelude::{InnerInstruction, MessageAddressTableLookup, Transaction};
+
+        let aggregator = Pubkey::new_unique();
+        let static_keys: Vec<Pubkey> = std::iter::once(aggregator)
+            .chain(buy_keys[..3].iter().copied())
+            .chain(buy_keys[5..].iter().copied())
+            .collect();
+        let loaded_writable = if with_loaded_addresses {
+            vec![buy_keys[3].to_bytes().to_vec(), buy_keys[4].to_bytes().to_vec()]
+        } else {
+            Vec::new()
+        };
+        let index_of = |i: usize| -> u8 {
+            match i {
+                0..=2 => (i + 1) as u8,
+                3 | 4 => (static_keys.len() + i - 3) as u8,
+                _ => (i - 1) as u8,
+            }
+        };
+
+        let mut data = discriminators::BUY_IX.to_vec();
+        data.extend_from_slice(&[0u8; 16]);
+
+        SubscribeUpdateTransactionInfo {
+            signature: vec![7u8; 64],
+            transaction: Some(Transaction {
+                message: Some(Message {
+                    account_keys: static_keys.iter().map(|key| key.to_bytes().to_vec()).collect(),
+                    instructions: vec![CompiledInstruction {
+                        program_id_index: 0,
+                        accounts: vec![],
+                        data: vec![],
+                    }],
+                    versioned: true,
+                    address_table_lookups: vec![MessageAddressTableLookup {
+                        account_key: Pubkey::new_unique().to_bytes().to_vec(),
+                        writable_indexes: vec![0, 1],
+                        readonly_indexes: vec![],
+                    }],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            meta: Some(TransactionStatusMeta {
+                log_messages: logs,
+                inner_instructions: vec![InnerInstructions {
+                    index: 0,
+                    instructions: vec![InnerInstruction {
+                        program_id_index: index_of(11) as u32,
+                        accounts: (0..16).map(index_of).collect(),
+                        data,
+                        stack_height: Some(2),
+                    }],
+                }],
+                loaded_writable_addresses: loaded_writable,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn cpi_buy_accounts_are_extracted_from_inner_instructions() {
+        let mut buy_keys: Vec<Pubkey> = (0..16).map(|_| Pubkey::new_unique()).collect();
+        buy_keys[11] = *PUMPFUN_PROGRAM_ID;
+        let mint = buy_keys[2];
+        let tx = routed_buy_transaction(&buy_keys, logs(&[trade_log(mint, Pubkey::new_unique(), true, 100_000_000)]), true);
+
+        let events = parse_transaction(&tx, 1);
+
+        let trades = trades(&events);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].bonding_curve, buy_keys[3]);
+        assert_eq!(trades[0].associated_bonding_curve, buy_keys[4]);
+        assert_eq!(trades[0].creator_vault, buy_keys[9]);
+        assert_eq!(trades[0].signature, bs58::encode(vec![7u8; 64]).into_string());
+    }
+
+    #[test]
+    fn missing_loaded_addresses_leave_accounts_unset() {
+        let mut buy_keys: Vec<Pubkey> = (0..16).map(|_| Pubkey::new_unique()).collect();
+        buy_keys[11] = *PUMPFUN_PROGRAM_ID;
+        let mint = buy_keys[2];
+        let tx = routed_buy_transaction(&buy_keys, logs(&[trade_log(mint, Pubkey::new_unique(), true, 100_000_000)]), false);
+
+        let events = parse_transaction(&tx, 1);
+
+        // 索引指向查找表账户时不能映射到静态账户中的其它地址
+        let trades = trades(&events);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].bonding_curve, Pubkey::default());
+        assert_eq!(trades[0].associated_bonding_curve, Pubkey::default());
+    }
+
+    #[test]
+    fn loaded_addresses_follow_static_keys_in_order() {
+        let static_keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let (writable, readonly) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let message = Message {
+            account_keys: static_keys.iter().map(|key| key.to_bytes().to_vec()).collect(),
+            address_table_lookups: vec![yellowstone_grpc_proto::prelude::MessageAddressTableLookup {
+                account_key: Pubkey::new_unique().to_bytes().to_vec(),
+                writable_indexes: vec![3],
+                readonly_indexes: vec![8],
+            }],
+            ..Default::default()
+        };
+        let meta = TransactionStatusMeta {
+            loaded_writable_addresses: vec![writable.to_bytes().to_vec()],
+            loaded_readonly_addresses: vec![readonly.to_bytes().to_vec()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            transaction_account_keys(&message, &meta),
+            vec![static_keys[0], static_keys[1], writable, readonly]
+        );
+    }
+
+    #[test]
+    fn non_event_logs_are_ignored() {
+        let logs = logs(&[
+            "Program log: Instruction: Buy".to_string(),
+            "Program data: not-base64!!".to_string(),
+            program_data(&[0u8; 16], &[0u8; 64]),
+            // 截断的 Trade 事件
+            program_data(TRADE_EVENT_DISCRIMINATOR, &[0u8; 32]),
+        ]);
+
+        assert!(parse_log_events(&logs, SIGNATURE, 1, Vec::new()).is_empty());
+    }
+
+    const FIXTURES: &str = include_str!("fixtures/pumpfun_payloads.txt");
+
+    /// fixtures/pumpfun_payloads.txt 中指定类型的样本（已 base64 解码）
+    fn fixtures(kind: &str) -> Vec<Vec<u8>> {
+        let payloads: Vec<Vec<u8>> = FIXTURES
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once(' '))
+            .filter(|(line_kind, _)| *line_kind == kind)
+            .map(|(_, payload)| BASE64_STANDARD.decode(payload).expect("fixture 不是合法 base64"))
+            .collect();
+        assert!(!payloads.is_empty(), "缺少 {} 样本", kind);
+        payloads
+    }
+
+    fn parse_payload(data: &[u8]) -> Option<SniperEvent> {
+        let log = format!("Program data: {}", BASE64_STANDARD.encode(data));
+        parse_pumpfun_event(&log, SIGNATURE, 1).unwrap()
+    }
+
+    #[test]
+    fn fixture_trade_events_decode() {
+        for payload in fixtures("trade") {
+            let Some(SniperEvent::Trade(trade)) = parse_payload(&payload) else {
+                panic!("trade 样本解析失败");
+            };
+            assert_ne!(trade.mint, Pubkey::default());
+            assert_ne!(trade.creator, Pubkey::default());
+            assert!(trade.sol_amount > 0 && trade.token_amount > 0);
+            assert!(trade.virtual_sol_reserves >= trade.real_sol_reserves);
+            assert!(trade.virtual_token_reserves >= trade.real_token_reserves);
+            assert_eq!(trade.fee_basis_points, 95);
+            assert_eq!(trade.last_update_timestamp, trade.timestamp);
+
+            // 少一个字节必须拒绝，而不是读出错位的字段
+            assert!(parse_payload(&payload[..16 + PUMPFUN_TRADE_EVENT_LOG_SIZE - 1]).is_none());
+        }
+    }
+
+    #[test]
+    fn fixture_create_events_decode() {
+        for payload in fixtures("create") {
+            let Some(SniperEvent::CreateToken(create)) = parse_payload(&payload) else {
+                panic!("create 样本解析失败");
+            };
+            assert!(!create.name.is_empty() && !create.symbol.is_empty());
+            assert!(create.uri.starts_with("https://"));
+            assert_ne!(create.mint, Pubkey::default());
+            assert_ne!(create.bonding_curve, Pubkey::default());
+            assert_eq!(create.token_total_supply, 1_000_000_000_000_000);
+            assert_eq!(create.virtual_sol_reserves, 30_000_000_000);
+        }
+    }
+
+    #[test]
+    fn fixture_migrate_events_decode() {
+        for payload in fixtures("migrate") {
+            let Some(SniperEvent::Migrate(migrate)) = parse_payload(&payload) else {
+                panic!("migrate 样本解析失败");
+            };
+            // pool 是最后一个字段，事件大小常量错误（如 112）时读不到
+            assert_ne!(migrate.pool, Pubkey::default());
+            assert!(migrate.timestamp > 0);
+            assert!(migrate.sol_amount > migrate.pool_migration_fee);
+
+            assert!(parse_payload(&payload[..payload.len() - 1]).is_none());
+        }
+    }
+
+    #[test]
+    fn fixture_bonding_curves_decode() {
+        for payload in fixtures("bonding_curve") {
+            let curve = bonding_curve_account_decode(&payload).expect("bonding_curve 样本解析失败");
+            assert_eq!(curve.token_total_supply, 1_000_000_000_000_000);
+            assert_ne!(curve.creator, Pubkey::default());
+            if curve.complete {
+                assert_eq!(curve.real_token_reserves, 0);
+            } else {
+                assert!(curve.virtual_sol_reserves >= curve.real_sol_reserves);
+            }
+
+            // 不带鉴别器的账户数据不是 BondingCurve
+            assert!(bonding_curve_account_decode(&payload[8..]).is_none());
+        }
+    }
+
+    #[test]
+    fn fixture_global_decodes() {
+        for payload in fixtures("global") {
+            let global = global_account_decode(&payload).expect("global 样本解析失败");
+            assert!(global.initialized && global.enable_migrate);
+            assert_ne!(global.fee_recipient, Pubkey::default());
+            assert_eq!(global.fee_basis_points, 95);
+            assert_eq!(global.creator_fee_basis_points, 5);
+            assert_eq!(global.token_total_supply, 1_000_000_000_000_000);
+            assert!(global.fee_recipients.iter().all(|recipient| *recipient != Pubkey::default()));
+            assert_ne!(global.admin_set_creator_authority, Pubkey::default());
+
+            assert!(global_account_decode(&payload[..8 + GLOBAL_SIZE - 1]).is_none());
+            assert!(global_account_decode(&payload[8..]).is_none());
+        }
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::collection::vec;
+        use proptest::prelude::*;
+
+        fn pubkey() -> impl Strategy<Value = Pubkey> {
+            any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+        }
+
+        fn event_discriminator() -> impl Strategy<Value = &'static [u8]> {
+            prop_oneof![
+                Just(TRADE_EVENT_DISCRIMINATOR),
+                Just(CREATE_TOKEN_EVENT_DISCRIMINATOR),
+                Just(discriminators::COMPLETE_PUMP_AMM_MIGRATION_EVENT),
+            ]
+        }
+
+        proptest! {
+            #[test]
+            fn arbitrary_program_data_never_panics(discriminator in event_discriminator(), body in vec(any::<u8>(), 0..512)) {
+                let _ = parse_pumpfun_event(&program_data(discriminator, &body), SIGNATURE, 1);
+            }
+
+            #[test]
+            fn arbitrary_log_lines_never_panic(log in "\\PC{0,256}") {
+                let _ = parse_pumpfun_event(&log, SIGNATURE, 1);
+            }
+
+            #[test]
+            fn arbitrary_account_data_never_panics(data in vec(any::<u8>(), 0..1024)) {
+                let _ = bonding_curve_decode(&data);
+                let _ = bonding_curve_account_decode(&data);
+                let _ = global_decode(&data);
+            }
+
+            #[test]
+            fn trade_event_round_trips(
+                mint in pubkey(),
+                user in pubkey(),
+                is_buy in any::<bool>(),
+                sol_amount in any::<u64>(),
+                trailing in vec(any::<u8>(), 0..64),
+            ) {
+                let mut body = trade_body(mint, user, is_buy, sol_amount);
+                body.extend_from_slice(&trailing);
+
+                let Ok(Some(SniperEvent::Trade(trade))) = parse_pumpfun_event(&program_data(TRADE_EVENT_DISCRIMINATOR, &body), SIGNATURE, 1) else {
+                    panic!("trade 事件解析失败");
+                };
+                prop_assert_eq!(trade.mint, mint);
+                prop_assert_eq!(trade.user, user);
+                prop_assert_eq!(trade.creator, user);
+                prop_assert_eq!(trade.is_buy, is_buy);
+                prop_assert_eq!(trade.sol_amount, sol_amount);
+                prop_assert_eq!(trade.creator_fee_basis_points, 30);
+                prop_assert!(trade.track_volume);
+            }
+
+            #[test]
+            fn truncated_trade_event_is_rejected(mint in pubkey(), cut in 0..PUMPFUN_TRADE_EVENT_LOG_SIZE) {
+                let body = trade_body(mint, mint, true, 1);
+                let parsed = parse_pumpfun_event(&program_data(TRADE_EVENT_DISCRIMINATOR, &body[..cut]), SIGNATURE, 1).unwrap();
+                prop_assert!(parsed.is_none());
+            }
+
+            #[test]
+            fn create_event_round_trips(mint in pubkey(), creator in pubkey(), name in "\\PC{0,64}", trailing in vec(any::<u8>(), 0..64)) {
+                let mut body = create_body(mint, creator, &name);
+                body.extend_from_slice(&trailing);
+
+                let Ok(Some(SniperEvent::CreateToken(create))) = parse_pumpfun_event(&program_data(CREATE_TOKEN_EVENT_DISCRIMINATOR, &body), SIGNATURE, 1) else {
+                    panic!("create 事件解析失败");
+                };
+                prop_assert_eq!(create.mint, mint);
+                prop_assert_eq!(create.creator, creator);
+                prop_assert_eq!(create.name, name);
+                prop_assert_eq!(create.token_total_supply, 1_000_000_000_000_000);
+            }
+
+            #[test]
+            fn migrate_event_round_trips(mint in pubkey(), pool in pubkey(), sol_amount in any::<u64>(), timestamp in any::<i64>()) {
+                let body = migrate_body(mint, pool, sol_amount, timestamp);
+
+                let Ok(Some(SniperEvent::Migrate(migrate))) = parse_pumpfun_event(&program_data(discriminators::COMPLETE_PUMP_AMM_MIGRATION_EVENT, &body), SIGNATURE, 1) else {
+                    panic!("migrate 事件解析失败");
+                };
+                prop_assert_eq!(migrate.mint, mint);
+                prop_assert_eq!(migrate.pool, pool);
+                prop_assert_eq!(migrate.sol_amount, sol_amount);
+                prop_assert_eq!(migrate.timestamp, timestamp);
+
+                let truncated = parse_pumpfun_event(&program_data(discriminators::COMPLETE_PUMP_AMM_MIGRATION_EVENT, &body[..body.len() - 1]), SIGNATURE, 1).unwrap();
+                prop_assert!(truncated.is_none());
+            }
+
+            #[test]
+            fn bonding_curve_round_trips(reserves in any::<[u64; 5]>(), complete in any::<bool>(), creator in pubkey(), trailing in vec(any::<u8>(), 0..64)) {
+                let mut data = discriminators::BONDING_CURVE_ACCOUNT.to_vec();
+                for value in reserves {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+                data.push(complete as u8);
+                data.extend_from_slice(creator.as_ref());
+                data.extend_from_slice(&trailing);
+
+                let curve = bonding_curve_account_decode(&data).expect("bonding curve 解析失败");
+                prop_assert_eq!(
+                    [curve.virtual_token_reserves, curve.virtual_sol_reserves, curve.real_token_reserves, curve.real_sol_reserves, curve.token_total_supply],
+                    reserves
+                );
+                prop_assert_eq!(curve.complete, complete);
+                prop_assert_eq!(curve.creator, creator);
+                prop_assert!(bonding_curve_account_decode(&data[..8 + BONDING_CURVE_SIZE - 1]).is_none());
+            }
+
+            #[test]
+            fn global_round_trips(fee_recipient in pubkey(), fee_basis_points in any::<u64>(), fee_recipients in any::<[[u8; 32]; 7]>()) {
+                let mut data = vec![1u8]; // initialized
+                data.extend_from_slice(Pubkey::new_unique().as_ref()); // authority
+                data.extend_from_slice(fee_recipient.as_ref());
+                for value in [1_073_000_000_000_000u64, 30_000_000_000, 793_100_000_000_000, 1_000_000_000_000_000, fee_basis_points] {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+                data.extend_from_slice(Pubkey::new_unique().as_ref()); // withdraw_authority
+                data.push(1); // enable_migrate
+                data.extend_from_slice(&15_000_000u64.to_le_bytes());
+                data.extend_from_slice(&5u64.to_le_bytes());
+                for recipient in fee_recipients {
+                    data.extend_from_slice(&recipient);
+                }
+                data.extend_from_slice(Pubkey::new_unique().as_ref());
+                data.extend_from_slice(Pubkey::new_unique().as_ref());
+                prop_assert_eq!(data.len(), GLOBAL_SIZE);
+
+                let global = global_decode(&data).expect("global 解析失败");
+                prop_assert_eq!(global.fee_recipient, fee_recipient);
+                prop_assert_eq!(global.fee_basis_points, fee_basis_points);
+                prop_assert_eq!(global.fee_recipients.map(|recipient| recipient.to_bytes()), fee_recipients);
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+/// 交易日志
+///
+/// 每笔开仓 / 平仓 / 被 EV 门控拒绝的买入追加一行 JSON（JSON Lines），供事后分析和参数校准
+///
+/// 核心功能:
+/// 1. 开仓记录 - 买入金额、成交数量、入场价、保本价、tip、EV 估算、综合评分拆解
+/// 2. 平仓记录 - 卖出所得、盈亏（含扣除 tip 后的净盈亏）、持仓时长
+/// 3. EV 拒绝记录 - 触发条件通过但 EV 为负而放弃的买入（用于校准胜率映射）
+/// 4. 影子记录 - A/B 实验中影子策略的假想开仓 / 平仓
+/// 5. 对账记录 - 持仓记录与链上余额不一致时的调整；启动恢复导入的持仓
+/// 6. 毕业记录 - 持仓期间曲线完成、需要在 PumpSwap 上手动卖出的持仓
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+
+use crate::ev::EvEstimate;
+use crate::types::ScoreBreakdown;
+
+/// 日志记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalRecord {
+    Entry {
+        timestamp: String,
+        mint: String,
+        signature: String,
+        sol_amount_lamports: u64,
+        token_amount: u64,
+        entry_price_sol: f64,
+        breakeven_price_sol: f64,
+        tips_lamports: u64,
+        ev: Option<EvEstimate>,
+        /// 动态策略引擎买入时的综合评分拆解（其他触发方式为 null）
+        score_breakdown: Option<ScoreBreakdown>,
+    },
+    Exit {
+        timestamp: String,
+        mint: String,
+        signature: String,
+        sol_received_lamports: u64,
+        profit_loss_lamports: i64,
+        net_profit_loss_lamports: i64,
+        hold_secs: u64,
+        /// 开仓时的动态策略置信度（用于分析置信度与收益的关系）
+        entry_confidence: Option<f64>,
+    },
+    EvRejected {
+        timestamp: String,
+        mint: String,
+        sol_amount_lamports: u64,
+        ev: EvEstimate,
+    },
+    ShadowEntry {
+        timestamp: String,
+        variant: String,
+        mint: String,
+        sol_amount_lamports: u64,
+        token_amount: u64,
+        entry_price_sol: f64,
+    },
+    Reconciled {
+        timestamp: String,
+        mint: String,
+        recorded_token_amount: u64,
+        actual_token_amount: u64,
+        /// 链上余额为 0 时移除持仓
+        removed: bool,
+    },
+    Recovered {
+        timestamp: String,
+        mint: String,
+        token_amount: u64,
+        /// 推断的成本基础（导入时按当前曲线估值）
+        inferred_cost_lamports: u64,
+    },
+    Migrated {
+        timestamp: String,
+        mint: String,
+        token_amount: u64,
+        cost_lamports: u64,
+        pumpswap_pool: String,
+    },
+    ShadowExit {
+        timestamp: String,
+        variant: String,
+        mint: String,
+        sol_received_lamports: u64,
+        profit_loss_lamports: i64,
+        hold_secs: u64,
+        reason: String,
+    },
+}
+
+/// 交易日志（未配置 trade_journal_path 时不写入）
+pub struct TradeJournal {
+    file: Option<Mutex<File>>,
+}
+
+impl TradeJournal {
+    pub fn open(path: Option<&str>) -> Result<Self> {
+        let file = match path {
+            Some(path) if !path.is_empty() => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("打开交易日志失败: {}", path))?;
+                info!("📒 交易日志: {}", path);
+                Some(Mutex::new(file))
+            }
+            _ => None,
+        };
+
+        Ok(Self { file })
+    }
+
+    pub fn record(&self, record: &JournalRecord) {
+        let Some(file) = &self.file else {
+            return;
+        };
+
+        let line = match serde_json::to_string(record) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("⚠️  序列化交易日志失败: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = writeln!(file.lock(), "{}", line) {
+            warn!("⚠️  写入交易日志失败: {}", e);
+        }
+    }
+}
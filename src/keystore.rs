@@ -0,0 +1,195 @@
+/// 钱包密钥加载
+///
+/// 私钥不必以明文放在环境变量中，Config::get_keypair 按配置从以下来源之一加载
+///
+/// 核心功能:
+/// 1. keypair 文件 - solana-keygen 生成的 JSON 字节数组文件
+/// 2. 加密 keystore - scrypt 派生密钥 + AES-256-GCM 加密，口令从配置读取或在终端提示输入
+/// 3. 外部签名命令 - 执行命令（例如密码管理器 CLI），从 stdout 读取 base58 私钥或 JSON 字节数组
+/// 4. 生成 keystore - `solsniper encrypt-keystore <keypair.json> <keystore.json>`
+
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use std::path::Path;
+use std::process::Command;
+
+/// keystore 格式版本
+const KEYSTORE_VERSION: u32 = 1;
+
+/// scrypt 参数（N = 2^15, r = 8, p = 1，单次解密约 100ms）
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// 加密 keystore 文件
+#[derive(Debug, Serialize, Deserialize)]
+struct Keystore {
+    version: u32,
+    /// 钱包地址（不解密即可确认是哪个钱包）
+    pubkey: String,
+    kdf: String,
+    log_n: u8,
+    r: u32,
+    p: u32,
+    /// base64
+    salt: String,
+    /// base64
+    nonce: String,
+    /// base64（AES-256-GCM 加密的 64 字节私钥）
+    ciphertext: String,
+}
+
+/// 从字节解析 Keypair
+fn keypair_from_bytes(bytes: &[u8]) -> Result<Keypair> {
+    Keypair::try_from(bytes).map_err(|e| anyhow::anyhow!("invalid keypair bytes: {}", e))
+}
+
+/// 解析 base58 私钥
+pub fn keypair_from_base58(secret: &str) -> Result<Keypair> {
+    let bytes = bs58::decode(secret.trim())
+        .into_vec()
+        .context("wallet private key is not valid base58")?;
+    keypair_from_bytes(&bytes)
+}
+
+/// 解析 base58 私钥或 JSON 字节数组
+fn keypair_from_text(text: &str) -> Result<Keypair> {
+    let text = text.trim();
+    if text.starts_with('[') {
+        let bytes: Vec<u8> = serde_json::from_str(text).context("invalid keypair JSON array")?;
+        keypair_from_bytes(&bytes)
+    } else {
+        keypair_from_base58(text)
+    }
+}
+
+/// 读取 Solana keypair JSON 文件
+pub fn read_keypair_file(path: &str) -> Result<Keypair> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read keypair file: {}", path))?;
+    let bytes: Vec<u8> = serde_json::from_str(&content)
+        .with_context(|| format!("Keypair file is not a JSON byte array: {}", path))?;
+    keypair_from_bytes(&bytes)
+}
+
+/// 执行外部签名命令，从 stdout 读取私钥
+pub fn keypair_from_command(command: &str) -> Result<Keypair> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .context("Failed to run wallet_signer_command")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "wallet_signer_command exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let stdout = String::from_utf8(output.stdout).context("wallet_signer_command output is not UTF-8")?;
+    keypair_from_text(&stdout).context("wallet_signer_command output is not a valid keypair")
+}
+
+/// 口令：配置了 wallet_keystore_passphrase 时直接使用，否则在终端提示输入
+fn passphrase_or_prompt(passphrase: Option<&str>, prompt: &str) -> Result<String> {
+    match passphrase {
+        Some(passphrase) => Ok(passphrase.to_string()),
+        None => rpassword::prompt_password(prompt).context("Failed to read keystore passphrase"),
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], log_n: u8, r: u32, p: u32) -> Result<[u8; 32]> {
+    let params = scrypt::Params::new(log_n, r, p, 32)
+        .map_err(|e| anyhow::anyhow!("invalid scrypt params: {}", e))?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+        .map_err(|e| anyhow::anyhow!("scrypt failed: {}", e))?;
+    Ok(key)
+}
+
+/// 解密 keystore 文件
+pub fn read_keystore(path: &str, passphrase: Option<&str>) -> Result<Keypair> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read keystore: {}", path))?;
+    let keystore: Keystore = serde_json::from_str(&content)
+        .with_context(|| format!("Invalid keystore file: {}", path))?;
+    if keystore.version != KEYSTORE_VERSION || keystore.kdf != "scrypt" {
+        anyhow::bail!("Unsupported keystore version {} / kdf {}", keystore.version, keystore.kdf);
+    }
+
+    let salt = BASE64.decode(&keystore.salt).context("Invalid keystore salt")?;
+    let nonce = BASE64.decode(&keystore.nonce).context("Invalid keystore nonce")?;
+    let ciphertext = BASE64.decode(&keystore.ciphertext).context("Invalid keystore ciphertext")?;
+    if nonce.len() != 12 {
+        anyhow::bail!("Invalid keystore nonce length: {}", nonce.len());
+    }
+
+    let passphrase = passphrase_or_prompt(passphrase, &format!("🔐 Keystore 口令 ({}): ", keystore.pubkey))?;
+    let key = derive_key(&passphrase, &salt, keystore.log_n, keystore.r, keystore.p)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| anyhow::anyhow!("invalid key: {}", e))?;
+    let secret = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| anyhow::anyhow!("Failed to decrypt keystore (wrong passphrase?)"))?;
+
+    let keypair = keypair_from_bytes(&secret)?;
+    if keypair.pubkey().to_string() != keystore.pubkey {
+        anyhow::bail!("Keystore pubkey mismatch: expected {}, got {}", keystore.pubkey, keypair.pubkey());
+    }
+    Ok(keypair)
+}
+
+/// 把 keypair JSON 文件加密为 keystore（终端输入两次口令）
+pub fn encrypt_keypair_file(input: &str, output: &str) -> Result<()> {
+    let keypair = read_keypair_file(input)?;
+
+    let passphrase = passphrase_or_prompt(None, "🔐 设置 keystore 口令: ")?;
+    if passphrase.is_empty() {
+        anyhow::bail!("Keystore passphrase must not be empty");
+    }
+    if passphrase_or_prompt(None, "🔐 再次输入口令: ")? != passphrase {
+        anyhow::bail!("Passphrases do not match");
+    }
+
+    let salt: [u8; 32] = rand::random();
+    let nonce: [u8; 12] = rand::random();
+    let key = derive_key(&passphrase, &salt, SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| anyhow::anyhow!("invalid key: {}", e))?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), keypair.to_bytes().as_slice())
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt keypair"))?;
+
+    let keystore = Keystore {
+        version: KEYSTORE_VERSION,
+        pubkey: keypair.pubkey().to_string(),
+        kdf: "scrypt".to_string(),
+        log_n: SCRYPT_LOG_N,
+        r: SCRYPT_R,
+        p: SCRYPT_P,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+
+    // 先写临时文件再 rename，避免写到一半留下损坏的 keystore
+    let output = Path::new(output);
+    let tmp = output.with_extension("tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(&keystore)?)
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
+    }
+    std::fs::rename(&tmp, output)
+        .with_context(|| format!("Failed to write keystore: {}", output.display()))?;
+
+    println!("✅ Keystore 已写入 {} (钱包 {})", output.display(), keypair.pubkey());
+    Ok(())
+}
@@ -5,17 +5,54 @@ pub mod advanced_metrics;
 pub mod advanced_filter;
 pub mod dynamic_strategy;
 pub mod aggregator;
+pub mod chain_clock;
+pub mod clock;
+pub mod chaos;
 pub mod strategy;
+pub mod strategy_overrides;
 pub mod config;
+pub mod confirmation;
+pub mod control;
+pub mod control_grpc;
+pub mod decision_trace;
+pub mod dex;
+pub mod doctor;
+pub mod error;
+pub mod event_feed;
+pub mod experiment;
+pub mod feed_reconcile;
 pub mod grpc;
+pub mod journal;
+pub mod keystore;
+pub mod position_state;
+pub mod market;
+pub mod metrics_export;
+pub mod mint_safety;
+pub mod ev;
 pub mod executor;
 pub mod position;
 pub mod momentum_decay;
 pub mod monitor;
+pub mod pipeline;
+pub mod price_cache;
+pub mod quote;
+pub mod replay;
+pub mod pump_global;
+pub mod risk;
+pub mod rpc_api;
+pub mod rpc_limiter;
+pub mod rpc_pool;
+pub mod signal_bus;
+pub mod signer;
+pub mod sizing;
+pub mod spend_guard;
 pub mod swqos;
+pub mod threshold;
+pub mod token_extensions;
 
 // 重新导出常用类型
 pub use types::{PumpFunEvent, PumpFunEventType, WindowMetrics, SniperEvent};
 pub use advanced_metrics::{AdvancedMetrics, AdvancedMetricsCalculator};
 pub use advanced_filter::{AdvancedEventFilter, AdvancedFilterConfig};
 pub use dynamic_strategy::{DynamicStrategyEngine, DynamicStrategyConfig};
+pub use quote::{QuoteResult, QuoteService, QuoteSide};
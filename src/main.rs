@@ -1,31 +1,92 @@
 mod advanced_filter;
 mod advanced_metrics;
 mod aggregator;
+mod chain_clock;
+mod clock;
+mod chaos;
 mod config;
+mod confirmation;
+mod control;
+mod control_grpc;
+mod decision_trace;
+mod dex;
+mod doctor;
+mod error;
+mod event_feed;
 mod dynamic_strategy;
+mod ev;
+mod experiment;
+mod feed_reconcile;
 mod executor;
 mod grpc;
+mod journal;
+mod keystore;
+mod position_state;
+mod market;
+mod metrics_export;
+mod mint_safety;
 mod momentum_decay;
 mod monitor;
+mod pipeline;
 mod position;
+mod price_cache;
+mod quote;
+mod replay;
+mod pump_global;
+mod risk;
+mod rpc_api;
+mod rpc_limiter;
+mod rpc_pool;
+mod signal_bus;
+mod signer;
+mod sizing;
+mod spend_guard;
 mod strategy;
+mod strategy_overrides;
 mod swqos;
+mod threshold;
+mod token_extensions;
 mod types;
 
 use anyhow::Result;
-use log::{error, info};
+use log::{error, info, warn};
+use solana_sdk::signature::Keypair;
 use solana_sdk::signer::Signer;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use crossbeam_queue::ArrayQueue;  // 🔥 新增: 无锁队列
 
 use aggregator::Aggregator;
+use clock::Clock;
 use config::Config;
+use confirmation::ConfirmationTracker;
+use control::ControlServer;
+use control_grpc::ControlGrpcService;
+use decision_trace::DecisionTrace;
+use dex::{DexAdapter, PumpFunAdapter};
+use event_feed::EventFeed;
 use executor::TransactionBuilder;
-use executor::lightspeed_buy::LightSpeedBuyExecutor;
+use executor::lightspeed_buy::{BuyBackends, LightSpeedBuyExecutor};
+use executor::outbox::TransactionOutbox;
 use executor::sol_trade_sell::SolTradeSellExecutor;
-use grpc::GrpcClient;
+use executor::wallet_sweep::WalletSweeper;
+use experiment::ShadowExperiment;
+use feed_reconcile::FeedReconciler;
+use grpc::{GrpcClient, SubscribeOptions};
+use journal::TradeJournal;
+use position_state::PositionStateStore;
+use market::MarketHealth;
+use metrics_export::MetricsExporter;
+use monitor::LargeTradeTracker;
+use pipeline::PipelineMonitor;
 use position::PositionManager;
+use price_cache::PriceCache;
+use quote::QuoteService;
+use replay::{EventRecorder, ReplayOptions, ReplayRpc, ReplaySource};
+use pump_global::PumpGlobalState;
+use rpc_pool::RpcPool;
+use signer::SharedSigner;
+use spend_guard::SpendGuard;
 use strategy::StrategyEngine;
 
 #[tokio::main]
@@ -33,52 +94,257 @@ async fn main() -> Result<()> {
     // 初始化日志
     env_logger::init();
 
+    // 子命令：不开启交易，执行完即退出
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let replay = match args.first().map(String::as_str) {
+        // 🔥 新增: 检查配置和外部依赖
+        Some("check-config") | Some("doctor") => {
+            let passed = doctor::run().await;
+            std::process::exit(if passed { 0 } else { 1 });
+        }
+        // 🔥 新增: 把 keypair 文件加密为 keystore
+        Some("encrypt-keystore") => {
+            let [_, input, output] = args.as_slice() else {
+                anyhow::bail!("usage: solsniper encrypt-keystore <keypair.json> <keystore.json>");
+            };
+            return keystore::encrypt_keypair_file(input, output);
+        }
+        // 🔥 新增: 导出运行中实例的单个 mint 窗口状态（通过控制 API）
+        Some("inspect") => {
+            let [_, mint] = args.as_slice() else {
+                anyhow::bail!("usage: solsniper inspect <mint>");
+            };
+            println!("{}", control::inspect_mint(&Config::from_env()?, mint).await?);
+            return Ok(());
+        }
+        // 🔥 新增: 按交易签名抓取 PumpFun 样本（解析器测试 fixtures）
+        Some("capture-fixtures") => {
+            println!("{}", grpc::fixture_capture::capture(&Config::from_env()?, &args[1..]).await?);
+            return Ok(());
+        }
+        // 🔥 新增: 回放录制的事件（执行器使用模拟链，不发送真实交易）
+        Some("--replay") => Some(ReplayOptions::parse(&args)?),
+        _ => None,
+    };
+
     info!("🚀 SolSniper - Pump.fun High-Performance Sniper Bot");
     info!("================================================");
 
     // 加载配置
-    let config = Arc::new(Config::from_env()?);
+    let mut config = Config::from_env()?;
+    if let Some(replay) = &replay {
+        replay.apply(&mut config);
+    }
+    let config = Arc::new(config);
     config.print_summary();
 
-    // 获取钱包
-    let keypair = Arc::new(config.get_keypair()?);
-    info!("Wallet: {}", keypair.as_ref().pubkey());
+    // 获取钱包签名器（本地 Keypair / 远程签名服务 / Ledger；回放模式使用临时 Keypair）
+    let signer: SharedSigner = match &replay {
+        Some(_) => Arc::new(Keypair::new()),
+        None => signer::from_config(&config).await?,
+    };
+    info!("Wallet: {}", signer.pubkey());
 
     // 创建无锁队列和通道
     // 🔥 优化: 使用 ArrayQueue 替代 mpsc unbounded channel
     let event_queue = Arc::new(ArrayQueue::new(config.event_queue_capacity));
     let (metrics_tx, metrics_rx) = mpsc::channel(1000);  // 缓冲 1000 个指标
-    let (signal_tx, signal_rx) = mpsc::channel(100);  // 缓冲 100 个信号
+    // 🔥 优化: 卖出信号走独立的高优先级通道，不再排在买入 / 持有信号之后
+    let (signal_tx, signal_rx) = signal_bus::channel(100, 100);  // 入场 / 退出各缓冲 100 个信号
 
     info!("✅ 无锁队列已创建 (容量: {})", config.event_queue_capacity);
 
     // 创建组件
     info!("Initializing components...");
 
+    // RPC 池（主端点 + 备用端点，所有组件的 RpcClient 都由池创建）
+    let rpc_pool = Arc::new(RpcPool::from_config(&config));
+
+    // 计时时钟（实盘为系统时钟；回放时由录制事件的接收时间驱动，计时随倍速加速且可复现）
+    let clock = Arc::new(if replay.is_some() { Clock::replay() } else { Clock::system() });
+
+    // 0. 价格缓存（由 gRPC BondingCurve 账户更新驱动，供监控读取）
+    let price_cache = Arc::new(PriceCache::new());
+
+    // PumpFun Global 账户（fee recipient / 费率，启动时读取一次，之后由 gRPC 账户更新刷新）
+    let pump_global = Arc::new(PumpGlobalState::new());
+    if replay.is_none() {
+        match pump_global.refresh(&rpc_pool.nonblocking_client(config.get_commitment_config())).await {
+            Ok(()) => {
+                let fees = pump_global.fees();
+                info!("✅ PumpFun Global: fee recipient {}, 费率 {} + {} bps",
+                    fees.fee_recipient, fees.fee_basis_points, fees.creator_fee_basis_points);
+            }
+            Err(e) => warn!("⚠️  读取 PumpFun Global 失败，使用内置默认值: {:?}", e),
+        }
+    }
+
+    // WebSocket 事件流（事件 / 指标 / 信号 / 成交，供外部工具实时消费）
+    let event_feed = Arc::new(EventFeed::from_config(&config));
+
+    // 交易确认追踪（gRPC 交易流匹配我们的签名，RPC 低频兜底）
+    let confirmations = Arc::new(ConfirmationTracker::new(
+        config.confirmation_fallback_poll_ms,
+        config.get_confirm_commitment(),
+        config.get_feed_commitment(),
+    ));
+
+    // processed 事件流确认对账（FEED_COMMITMENT=processed 时启用，未确认的交易回滚窗口事件）
+    let feed_reconciler = Arc::new(FeedReconciler::new(
+        config.get_feed_commitment(),
+        config.feed_reconcile_timeout_ms,
+    ));
+
     // 1. gRPC 客户端（支持 X-Token 认证）
     let grpc_client = GrpcClient::new(
         config.grpc_endpoint.clone(),
         config.grpc_x_token.clone(),
+        config.get_feed_commitment(),
+        SubscribeOptions::from_config(&config),
+        price_cache.clone(),
+        pump_global.clone(),
+        confirmations.clone(),
+        feed_reconciler.clone(),
+        Arc::new(EventRecorder::open(config.event_record_path.as_deref())?),
     );
 
+    // 大额卖出追踪（聚合器写入，实时监控器读取）
+    let large_trades = Arc::new(LargeTradeTracker::new(config.large_sell_threshold));
+
     // 2. 聚合器（增强版）
-    let aggregator = Arc::new(Aggregator::new(config.clone(), metrics_tx));
+    // 大盘健康度（聚合器记录上线 / 毕业事件，持仓管理器据此暂停开新仓）
+    let market_health = Arc::new(MarketHealth::from_config(&config));
+
+    let aggregator = Arc::new(Aggregator::new(
+        config.clone(),
+        metrics_tx.clone(),
+        large_trades.clone(),
+        market_health.clone(),
+        feed_reconciler.clone(),
+        event_feed.clone(),
+        clock.clone(),
+    ));
+
+    // 交易日志（开仓 / 平仓 / EV 拒绝 / 影子成交，JSON Lines）
+    let journal = Arc::new(TradeJournal::open(config.trade_journal_path.as_deref())?);
+
+    // A/B 实验：影子策略 B（纸面交易）
+    let experiment = ShadowExperiment::from_config(config.clone(), journal.clone(), clock.clone()).map(Arc::new);
+
+    // 策略决策追踪（每次判定命中 / 拒绝的条件，控制 API 查询）
+    let decisions = Arc::new(DecisionTrace::from_config(&config));
+
+    // 窗口指标导出（CSV，离线研究 / 阈值校准）
+    let metrics_export = Arc::new(MetricsExporter::from_config(&config)?);
 
     // 3. 策略引擎（增强版 - 需要 aggregator 引用）
     let strategy = Arc::new(StrategyEngine::new(
         config.clone(),
-        signal_tx,
+        signal_tx.clone(),
         aggregator.clone(),
+        experiment.clone(),
+        event_feed.clone(),
+        decisions.clone(),
+        metrics_export.clone(),
+    ));
+
+    // 管道背压监控（事件队列 / 指标通道 / 策略 worker 队列 / 信号通道积压）
+    let pipeline = Arc::new(PipelineMonitor::new(
+        event_queue.clone(),
+        metrics_tx,
+        signal_tx,
+        strategy.clone(),
+        config.backpressure_warn_percent,
     ));
 
     // 4. 交易构建器
     let tx_builder = Arc::new(TransactionBuilder::new());
 
+    // 交易发件箱（买入 / 卖出交易发送前落盘，崩溃后重启可恢复）
+    let outbox = Arc::new(TransactionOutbox::open(config.outbox_path.as_deref())?);
+
+    // 支出限额（单笔 / 每小时 SOL 上限，买入 / 卖出 / 钱包清理签名前检查，独立于策略）
+    let spend_guard = Arc::new(SpendGuard::from_config(&config, event_feed.clone()));
+
+    // DEX 协议适配器（账户派生 / 报价 / 买卖指令，执行器和持仓管理共用）
+    let dex: Arc<dyn DexAdapter> = Arc::new(PumpFunAdapter::new(pump_global.clone())?);
+
+    // 回放模式的模拟链（曲线由回放事件驱动，执行器的查询 / 发送都落在这里）
+    let replay_chain = match &replay {
+        Some(_) => Some(Arc::new(ReplayRpc::new(
+            signer.pubkey(),
+            (config.replay_wallet_sol * 1_000_000_000.0) as u64,
+            dex.clone(),
+            config.pumpfun_fee_bps,
+        )?)),
+        None => None,
+    };
+
     // 5. LightSpeed 买入执行器
-    let lightspeed_buy = Arc::new(LightSpeedBuyExecutor::new(config.clone(), keypair.clone())?);
+    let lightspeed_buy = Arc::new(match &replay_chain {
+        Some(chain) => LightSpeedBuyExecutor::with_backends(
+            config.clone(),
+            signer.clone(),
+            confirmations.clone(),
+            outbox.clone(),
+            rpc_pool.clone(),
+            dex.clone(),
+            spend_guard.clone(),
+            BuyBackends { rpc: chain.clone(), lightspeed_rpc: None, swqos: None },
+        )?,
+        None => LightSpeedBuyExecutor::new(
+            config.clone(),
+            signer.clone(),
+            confirmations.clone(),
+            outbox.clone(),
+            rpc_pool.clone(),
+            dex.clone(),
+            spend_guard.clone(),
+        )?,
+    });
+
+    // 6. SWQOS 地区延迟探测 + 启动健康检查（在第一次赛马前选好地区、禁用不可用的服务）
+    if let Some(swqos) = lightspeed_buy.swqos_manager() {
+        swqos.select_fastest_regions().await;
+        swqos.health_check_all().await;
+    }
+    lightspeed_buy.warm_up_tpu().await;
 
     // 7. SolTrade 卖出执行器
-    let sol_trade_sell = Arc::new(SolTradeSellExecutor::new(config.clone(), keypair.clone())?);
+    let sol_trade_sell = Arc::new(match &replay_chain {
+        Some(chain) => SolTradeSellExecutor::with_rpc(
+            config.clone(),
+            signer.clone(),
+            confirmations.clone(),
+            outbox.clone(),
+            chain.clone(),
+            dex.clone(),
+            spend_guard.clone(),
+        )?,
+        None => SolTradeSellExecutor::new(
+            config.clone(),
+            signer.clone(),
+            confirmations.clone(),
+            outbox.clone(),
+            rpc_pool.clone(),
+            dex.clone(),
+            spend_guard.clone(),
+        )?,
+    });
+
+    // 钱包清理（WSOL 解包 + 空 token 账户回收租金，同时记录 ATA 租金账本）
+    let wallet_sweeper = {
+        let rpc_client = Arc::new(rpc_pool.client(config.get_commitment_config()));
+        Arc::new(WalletSweeper::new(rpc_client, signer.clone(), spend_guard.clone()))
+    };
+    if config.wallet_sweep_enabled {
+        if let Err(e) = wallet_sweeper.sweep().await {
+            error!("❌ 启动时钱包清理失败: {:?}", e);
+        }
+    }
+
+    // 持仓状态机（上次运行未完成的持仓标记为 Orphaned）
+    let position_states = Arc::new(PositionStateStore::open(config.position_state_path.as_deref())?);
 
     // 8. 持仓管理器（使用 LightSpeed 买入 + SolTrade 卖出）
     let position_manager = Arc::new(PositionManager::new(
@@ -87,15 +353,44 @@ async fn main() -> Result<()> {
         tx_builder.clone(),
         lightspeed_buy.clone(),
         sol_trade_sell.clone(),
+        dex.clone(),
+        aggregator.filter(),
+        aggregator.threshold_trigger(),
+        aggregator.held_mints(),
+        price_cache.clone(),
+        large_trades.clone(),
+        confirmations.clone(),
+        wallet_sweeper.clone(),
+        journal.clone(),
+        market_health.clone(),
+        position_states.clone(),
+        rpc_pool.clone(),
+        feed_reconciler.clone(),
+        event_feed.clone(),
+        clock.clone(),
     ));
 
+    // 启动恢复（回放模式从空钱包开始，不做恢复）
+    if replay.is_none() {
+        // 先处理上次运行未完成的交易（上链的买入随后作为未记录 token 恢复）
+        let rpc_client = rpc_pool.client(config.get_commitment_config());
+        if let Err(e) = outbox.recover(&rpc_client).await {
+            error!("❌ 交易发件箱恢复失败: {:?}", e);
+        }
+
+        // 钱包中不在持仓记录里的 PumpFun token（导入或列出供手动处理）
+        if let Err(e) = position_manager.recover_unknown_tokens().await {
+            error!("❌ 启动恢复未记录 token 失败: {:?}", e);
+        }
+    }
+
     info!("✅ All components initialized");
 
     // 启动各个组件
     info!("Starting components...");
 
     // 启动 gRPC 订阅（带自动重连和自动恢复）
-    let grpc_handle = {
+    let grpc_handle = replay.is_none().then(|| {
         let grpc_client = grpc_client.clone();
         let event_queue = event_queue.clone();  // 🔥 克隆 Arc<ArrayQueue>
         tokio::spawn(async move {
@@ -108,7 +403,14 @@ async fn main() -> Result<()> {
                 tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
             }
         })
-    };
+    });
+
+    // 回放模式：录制的事件代替 gRPC 订阅注入事件队列
+    let replay_handle = replay.zip(replay_chain.clone()).map(|(replay, chain)| {
+        let source = ReplaySource::new(replay, chain, price_cache.clone(), clock.clone());
+        let event_queue = event_queue.clone();
+        tokio::spawn(async move { source.run(event_queue).await })
+    });
 
     // 启动聚合器（带自动恢复）
     let aggregator_handle = {
@@ -144,34 +446,257 @@ async fn main() -> Result<()> {
         })
     };
 
+    // 启动持仓监控任务（独立于信号通道定时执行）
+    let monitor_handle = {
+        let position_manager = position_manager.clone();
+        tokio::spawn(async move {
+            info!("🚀 启动持仓监控任务");
+            position_manager.start_monitor_loop().await;
+            // 如果 start_monitor_loop 退出，说明发生严重错误
+            error!("❌ 持仓监控任务异常退出");
+        })
+    };
+
+    // 启动止盈止损评估任务（基于价格缓存，独立于交易流）
+    let exit_handle = {
+        let position_manager = position_manager.clone();
+        tokio::spawn(async move {
+            info!("🚀 启动止盈止损评估任务");
+            position_manager.start_exit_loop().await;
+            // 如果 start_exit_loop 退出，说明发生严重错误
+            error!("❌ 止盈止损评估任务异常退出");
+        })
+    };
+
+    // 启动 SWQOS 定期健康检查任务（SWQOS_HEALTH_CHECK_INTERVAL_SECS = 0 时不启用）
+    let swqos_health_handle = lightspeed_buy.swqos_manager().map(|swqos| {
+        tokio::spawn(async move {
+            swqos.start_health_check_loop().await;
+        })
+    });
+
+    // 启动 SWQOS 地区延迟探测任务（SWQOS_LATENCY_PROBE_INTERVAL_SECS = 0 时不启用）
+    let swqos_probe_handle = lightspeed_buy.swqos_manager().map(|swqos| {
+        tokio::spawn(async move {
+            swqos.start_latency_probe_loop().await;
+        })
+    });
+
+    // 启动权益跟踪任务（MAX_DRAWDOWN_PERCENT = 0 时不启用；回放模式不启用）
+    let equity_handle = replay_chain.is_none().then(|| {
+        let position_manager = position_manager.clone();
+        tokio::spawn(async move {
+            position_manager.start_equity_loop().await;
+        })
+    });
+
+    // 启动钱包余额检查任务（余额低于买入储备时暂停买入；回放模式不启用）
+    let balance_handle = replay_chain.is_none().then(|| {
+        let position_manager = position_manager.clone();
+        tokio::spawn(async move {
+            position_manager.start_balance_watch_loop().await;
+        })
+    });
+
+    // 启动持仓对账任务（RECONCILE_INTERVAL_SECS = 0 时不启用）
+    let reconcile_handle = {
+        let position_manager = position_manager.clone();
+        tokio::spawn(async move {
+            position_manager.start_reconcile_loop().await;
+        })
+    };
+
+    // 启动 SOL 价格监控任务（MARKET_HEALTH_ENABLED = false 时不启用；回放模式不启用）
+    let market_price_handle = replay_chain.is_none().then(|| {
+        let market_health = market_health.clone();
+        let url = config.sol_price_api_url.clone();
+        let json_pointer = config.sol_price_json_pointer.clone();
+        let interval_secs = config.sol_price_interval_secs;
+        tokio::spawn(async move {
+            market_health.start_price_loop(url, json_pointer, interval_secs).await;
+        })
+    });
+
+    // 启动 RPC 健康检查任务（只有一个端点时不启用；回放模式不启用）
+    let rpc_health_handle = replay_chain.is_none().then(|| {
+        let rpc_pool = rpc_pool.clone();
+        let interval_secs = config.rpc_health_check_interval_secs;
+        tokio::spawn(async move {
+            rpc_pool.start_health_loop(interval_secs).await;
+        })
+    });
+
+    // 启动管道背压监控任务
+    let pipeline_handle = {
+        let pipeline = pipeline.clone();
+        let interval_ms = config.pipeline_monitor_interval_ms;
+        tokio::spawn(async move {
+            pipeline.start_loop(interval_ms).await;
+        })
+    };
+
+    // 启动控制 API（紧急清仓等运维操作）
+    let control_handle = config.control_api_enabled.then(|| {
+        let server = Arc::new(ControlServer::new(
+            config.clone(),
+            position_manager.clone(),
+            aggregator.clone(),
+            pipeline.clone(),
+            spend_guard.clone(),
+            Arc::new(QuoteService::new(dex.clone(), price_cache.clone())),
+            decisions.clone(),
+        ));
+        tokio::spawn(async move {
+            if let Err(e) = server.run().await {
+                error!("❌ 控制 API 异常退出: {:?}", e);
+            }
+        })
+    });
+
+    // 启动控制面 gRPC 服务
+    let control_grpc_handle = config.control_grpc_enabled.then(|| {
+        let service = ControlGrpcService::new(config.clone(), position_manager.clone(), event_feed.clone());
+        tokio::spawn(async move {
+            if let Err(e) = service.run().await {
+                error!("❌ 控制面 gRPC 服务异常退出: {:?}", e);
+            }
+        })
+    });
+
+    // 启动 WebSocket 事件流
+    let event_feed_handle = config.event_feed_enabled.then(|| {
+        let event_feed = event_feed.clone();
+        tokio::spawn(async move {
+            if let Err(e) = event_feed.run().await {
+                error!("❌ 事件流异常退出: {:?}", e);
+            }
+        })
+    });
+
+    // SIGUSR1 触发紧急清仓（kill -USR1 <pid>）
+    #[cfg(unix)]
+    let sell_all_signal_handle = {
+        let position_manager = position_manager.clone();
+        tokio::spawn(async move {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut sigusr1 = match signal(SignalKind::user_defined1()) {
+                Ok(sigusr1) => sigusr1,
+                Err(e) => {
+                    error!("❌ 注册 SIGUSR1 失败: {}", e);
+                    return;
+                }
+            };
+            while sigusr1.recv().await.is_some() {
+                info!("🚨 收到 SIGUSR1，执行紧急清仓");
+                position_manager.sell_all("SIGUSR1").await;
+            }
+        })
+    };
+
+    // 启动定期钱包清理任务（回收空 ATA 租金）
+    let wallet_sweep_handle = config.wallet_sweep_enabled.then(|| {
+        let wallet_sweeper = wallet_sweeper.clone();
+        let interval_secs = config.wallet_sweep_interval_secs;
+        tokio::spawn(async move {
+            wallet_sweeper.start_sweep_loop(interval_secs).await;
+        })
+    });
+
     // 启动定期清理任务
     let cleanup_handle = {
         let aggregator = aggregator.clone();
+        let price_cache = price_cache.clone();
+        let large_trades = large_trades.clone();
         let cleanup_interval_secs = config.aggregator_cleanup_interval_secs;
         let window_ttl_secs = config.aggregator_window_ttl_secs;
+        let price_cache_ttl_secs = config.price_cache_ttl_secs;
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(cleanup_interval_secs));
             loop {
                 interval.tick().await;
                 aggregator.cleanup_old_windows(window_ttl_secs);
+                price_cache.cleanup(price_cache_ttl_secs);
+                large_trades.cleanup();
             }
         })
     };
 
     info!("✅ All components started");
-    info!("🎯 Bot is now running. Press Ctrl+C to stop.");
 
-    // 等待 Ctrl+C 信号
-    tokio::signal::ctrl_c().await?;
+    match replay_handle {
+        // 回放模式：回放结束或 Ctrl+C 时退出
+        Some(mut handle) => {
+            info!("⏯️  Replay is now running. Press Ctrl+C to stop.");
+            tokio::select! {
+                result = &mut handle => match result {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => error!("❌ 回放失败: {:?}", e),
+                    Err(e) => error!("❌ 回放任务异常退出: {}", e),
+                },
+                result = tokio::signal::ctrl_c() => {
+                    result?;
+                    handle.abort();
+                }
+            }
+        }
+        None => {
+            info!("🎯 Bot is now running. Press Ctrl+C to stop.");
+
+            // 等待 Ctrl+C 信号
+            tokio::signal::ctrl_c().await?;
+        }
+    }
 
     info!("Shutting down...");
 
     // 取消所有任务
-    grpc_handle.abort();
     aggregator_handle.abort();
     strategy_handle.abort();
     position_handle.abort();
+    monitor_handle.abort();
+    exit_handle.abort();
     cleanup_handle.abort();
+    reconcile_handle.abort();
+    pipeline_handle.abort();
+    for handle in [grpc_handle, equity_handle, balance_handle, market_price_handle, rpc_health_handle].into_iter().flatten() {
+        handle.abort();
+    }
+    if let Some(handle) = swqos_health_handle {
+        handle.abort();
+    }
+    if let Some(handle) = swqos_probe_handle {
+        handle.abort();
+    }
+    if let Some(handle) = wallet_sweep_handle {
+        handle.abort();
+    }
+    if let Some(handle) = control_handle {
+        handle.abort();
+    }
+    if let Some(handle) = control_grpc_handle {
+        handle.abort();
+    }
+    if let Some(handle) = event_feed_handle {
+        handle.abort();
+    }
+    #[cfg(unix)]
+    sell_all_signal_handle.abort();
+
+    metrics_export.flush();
+    position_states.flush();
+    lightspeed_buy.tip_ledger().print_summary();
+    wallet_sweeper.rent_ledger().print_summary();
+    if feed_reconciler.is_enabled() {
+        let (confirmed, rolled_back, pending_slots) = feed_reconciler.stats();
+        info!("🧮 事件流对账: {} 笔已确认, {} 笔已回滚, {} 个 slot 待确认", confirmed, rolled_back, pending_slots);
+    }
+    if let Some(experiment) = &experiment {
+        experiment.print_summary();
+    }
+    if let Some(chain) = &replay_chain {
+        info!("⏯️  回放模拟钱包: {:.4} SOL (初始 {:.4} SOL)",
+            chain.wallet_lamports() as f64 / 1_000_000_000.0, config.replay_wallet_sol);
+    }
 
     info!("Goodbye!");
 
@@ -0,0 +1,208 @@
+/// 大盘健康度
+///
+/// 跟踪 SOL/USD 价格以及 pump.fun 最近一段时间的上线速率和毕业率，
+/// 大盘走弱时 meme 资金流会枯竭，低于健康阈值时暂停开新仓（已有持仓照常管理）
+///
+/// 核心功能:
+/// 1. SOL/USD 价格 - 定期从价格 API 拉取（JSON 路径可配置）
+/// 2. 上线速率 - 统计窗口内的 Create 事件数（聚合器写入）
+/// 3. 毕业率 - 统计窗口内的 Migrate 事件数 / 上线数
+/// 4. 健康门控 - 任一指标低于阈值时暂停买入，恢复后自动放开
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// 大盘状态快照（控制 API / 日志使用）
+#[derive(Debug, Clone, Copy)]
+pub struct MarketSnapshot {
+    pub sol_price_usd: Option<f64>,
+    pub launches: usize,
+    pub graduations: usize,
+    pub graduation_rate_percent: f64,
+    /// 启动后是否已观察满一个统计窗口（未满时不判断速率）
+    pub window_complete: bool,
+    pub healthy: bool,
+}
+
+/// 大盘健康度跟踪器
+pub struct MarketHealth {
+    enabled: bool,
+    window: Duration,
+    min_sol_price_usd: f64,
+    min_launches: usize,
+    min_graduation_rate_percent: f64,
+    started_at: Instant,
+    launches: Mutex<VecDeque<Instant>>,
+    graduations: Mutex<VecDeque<Instant>>,
+    /// SOL/USD 价格（f64 bits，0 = 尚未获取）
+    sol_price_usd: AtomicU64,
+    unhealthy: AtomicBool,
+}
+
+impl MarketHealth {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            enabled: config.market_health_enabled,
+            window: Duration::from_secs(config.market_health_window_secs),
+            min_sol_price_usd: config.min_sol_price_usd,
+            min_launches: config.min_launches_per_window,
+            min_graduation_rate_percent: config.min_graduation_rate_percent,
+            started_at: Instant::now(),
+            launches: Mutex::new(VecDeque::new()),
+            graduations: Mutex::new(VecDeque::new()),
+            sol_price_usd: AtomicU64::new(0),
+            unhealthy: AtomicBool::new(false),
+        }
+    }
+
+    /// 记录一次新币上线（Create 事件）
+    pub fn record_launch(&self) {
+        Self::record(&self.launches, self.window);
+    }
+
+    /// 记录一次毕业（Migrate 事件）
+    pub fn record_graduation(&self) {
+        Self::record(&self.graduations, self.window);
+    }
+
+    pub fn set_sol_price_usd(&self, price: f64) {
+        self.sol_price_usd.store(price.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn sol_price_usd(&self) -> Option<f64> {
+        let price = f64::from_bits(self.sol_price_usd.load(Ordering::Relaxed));
+        (price > 0.0).then_some(price)
+    }
+
+    /// 是否允许开新仓（未启用时始终允许，仅在状态变化时打印日志）
+    pub fn is_healthy(&self) -> bool {
+        if !self.enabled {
+            return true;
+        }
+
+        let snapshot = self.snapshot();
+        let reason = self.unhealthy_reason(&snapshot);
+        let unhealthy = reason.is_some();
+        let was_unhealthy = self.unhealthy.swap(unhealthy, Ordering::Relaxed);
+
+        if let Some(reason) = reason {
+            if !was_unhealthy {
+                warn!("📉 大盘走弱，暂停开新仓: {}", reason);
+            }
+        } else if was_unhealthy {
+            info!("📈 大盘恢复健康，恢复开新仓");
+        }
+
+        !unhealthy
+    }
+
+    pub fn snapshot(&self) -> MarketSnapshot {
+        let launches = Self::count(&self.launches, self.window);
+        let graduations = Self::count(&self.graduations, self.window);
+        let graduation_rate_percent = if launches == 0 {
+            0.0
+        } else {
+            graduations as f64 / launches as f64 * 100.0
+        };
+
+        MarketSnapshot {
+            sol_price_usd: self.sol_price_usd(),
+            launches,
+            graduations,
+            graduation_rate_percent,
+            window_complete: self.started_at.elapsed() >= self.window,
+            healthy: !self.unhealthy.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 低于任一阈值时返回原因（阈值为 0 表示不检查该项）
+    fn unhealthy_reason(&self, snapshot: &MarketSnapshot) -> Option<String> {
+        if self.min_sol_price_usd > 0.0 {
+            if let Some(price) = snapshot.sol_price_usd {
+                if price < self.min_sol_price_usd {
+                    return Some(format!("SOL ${:.2} < ${:.2}", price, self.min_sol_price_usd));
+                }
+            }
+        }
+
+        // 启动后未满一个窗口时速率不可靠
+        if !snapshot.window_complete {
+            return None;
+        }
+
+        if self.min_launches > 0 && snapshot.launches < self.min_launches {
+            return Some(format!("上线数 {} < {} (最近 {}s)",
+                snapshot.launches, self.min_launches, self.window.as_secs()));
+        }
+
+        if self.min_graduation_rate_percent > 0.0
+            && snapshot.graduation_rate_percent < self.min_graduation_rate_percent
+        {
+            return Some(format!("毕业率 {:.2}% < {:.2}%",
+                snapshot.graduation_rate_percent, self.min_graduation_rate_percent));
+        }
+
+        None
+    }
+
+    /// 定期拉取 SOL/USD 价格（未启用或 interval_secs = 0 时不启动）
+    pub async fn start_price_loop(self: Arc<Self>, url: String, json_pointer: String, interval_secs: u64) {
+        if !self.enabled || interval_secs == 0 {
+            return;
+        }
+
+        info!("💵 SOL 价格监控已启动 (间隔 {}s)", interval_secs);
+        let client = reqwest::Client::new();
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+        loop {
+            interval.tick().await;
+            match Self::fetch_sol_price(&client, &url, &json_pointer).await {
+                Ok(price) => self.set_sol_price_usd(price),
+                Err(e) => warn!("⚠️  获取 SOL 价格失败: {:?}", e),
+            }
+        }
+    }
+
+    async fn fetch_sol_price(client: &reqwest::Client, url: &str, json_pointer: &str) -> Result<f64> {
+        let body: serde_json::Value = client
+            .get(url)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .context("请求价格 API 失败")?
+            .json()
+            .await
+            .context("解析价格 API 响应失败")?;
+
+        body.pointer(json_pointer)
+            .and_then(|value| value.as_f64())
+            .with_context(|| format!("价格 API 响应中没有 {}", json_pointer))
+    }
+
+    fn record(events: &Mutex<VecDeque<Instant>>, window: Duration) {
+        let mut events = events.lock();
+        let now = Instant::now();
+        events.push_back(now);
+        Self::prune(&mut events, window);
+    }
+
+    fn count(events: &Mutex<VecDeque<Instant>>, window: Duration) -> usize {
+        let mut events = events.lock();
+        Self::prune(&mut events, window);
+        events.len()
+    }
+
+    fn prune(events: &mut VecDeque<Instant>, window: Duration) {
+        while events.front().is_some_and(|at| at.elapsed() > window) {
+            events.pop_front();
+        }
+    }
+}
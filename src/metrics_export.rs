@@ -0,0 +1,300 @@
+/// 窗口指标导出（离线研究 / 阈值校准）
+///
+/// 策略引擎评估的每一条 WindowMetrics（含高级指标）追加为 CSV 的一行，
+/// 用真实资金流离线回测策略参数和触发阈值
+///
+/// 核心功能:
+/// 1. 导出 - metrics_export_dir 下的 metrics-<UTC 时间>.csv，每个文件带表头
+/// 2. 轮转 - 文件超过 metrics_export_rotate_mb 或打开超过 metrics_export_rotate_secs 后换新文件
+/// 3. 缓冲 - 写入先进入缓冲区，每 FLUSH_EVERY_ROWS 行及轮转时落盘（进程崩溃最多丢失一个缓冲区）
+/// 4. 后台写线程 - record 只把指标放入有界队列，格式化和文件 I/O 都在写线程中完成，
+///    不阻塞策略 worker；队列满时丢弃该行并计数
+/// 5. 时间戳 - 每行的 timestamp 为触发该指标的交易的链上时间（回放时与录制一致）
+///
+/// 未配置 metrics_export_dir 时不导出；写入失败只记录警告，不影响策略评估
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use log::{info, warn};
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::types::WindowMetrics;
+
+/// 每写入多少行落盘一次
+const FLUSH_EVERY_ROWS: usize = 1000;
+
+/// 写线程队列容量（超过时丢弃新的指标行）
+const QUEUE_CAPACITY: usize = 10_000;
+
+/// 每丢弃多少行打印一次警告
+const DROP_WARN_EVERY: u64 = 1000;
+
+const HEADER: &str = "timestamp,mint,creator,event_count,total_event_count,observed_secs,token_age_secs,\
+net_inflow_sol,buy_ratio,acceleration,normalized_acceleration,window_span_secs,inflow_rate_sol_per_sec,\
+trade_rate_per_sec,unique_buyers,repeat_buyer_ratio,dev_initial_buy_sol,curve_progress_percent,\
+graduation_triggered,threshold_buy_amount,sell_pressure_sol,virtual_sol_reserves,virtual_token_reserves,\
+real_sol_reserves,real_token_reserves,curve_slope,weighted_buy_pressure,high_frequency_trades,\
+avg_price_impact,max_price_impact,liquidity_depth,volatility,weighted_buy_sell_ratio,large_trade_ratio,\
+trade_interval_std,vwap_price,median_trade_size_sol,whale_share";
+
+/// 写线程消息
+enum ExportMessage {
+    Row(Arc<WindowMetrics>),
+    /// 落盘当前缓冲区，完成后回复
+    Flush(SyncSender<()>),
+}
+
+/// 当前写入的文件
+struct ExportFile {
+    writer: BufWriter<File>,
+    opened_at: Instant,
+    bytes: u64,
+    unflushed_rows: usize,
+}
+
+/// 写线程持有的导出状态
+struct ExportWriter {
+    dir: PathBuf,
+    rotate_bytes: u64,
+    rotate_after: Option<Duration>,
+    file: Option<ExportFile>,
+}
+
+/// 指标导出器
+pub struct MetricsExporter {
+    /// 写线程队列（未配置导出目录时为 None）
+    tx: Option<SyncSender<ExportMessage>>,
+    /// 队列满时丢弃的行数
+    dropped: AtomicU64,
+}
+
+impl MetricsExporter {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let tx = match &config.metrics_export_dir {
+            Some(dir) if !dir.is_empty() => {
+                std::fs::create_dir_all(dir)
+                    .with_context(|| format!("创建指标导出目录失败: {}", dir))?;
+                info!("📤 指标导出: {} (每 {} MB / {}s 轮转)",
+                    dir, config.metrics_export_rotate_mb, config.metrics_export_rotate_secs);
+
+                let writer = ExportWriter {
+                    dir: PathBuf::from(dir),
+                    rotate_bytes: config.metrics_export_rotate_mb.saturating_mul(1024 * 1024),
+                    rotate_after: (config.metrics_export_rotate_secs > 0)
+                        .then_some(Duration::from_secs(config.metrics_export_rotate_secs)),
+                    file: None,
+                };
+                Some(Self::spawn_writer(writer)?)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            tx,
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    /// 启动写线程（导出器释放后线程落盘并退出）
+    fn spawn_writer(mut writer: ExportWriter) -> Result<SyncSender<ExportMessage>> {
+        let (tx, rx) = mpsc::sync_channel::<ExportMessage>(QUEUE_CAPACITY);
+        std::thread::Builder::new()
+            .name("metrics-export-writer".to_string())
+            .spawn(move || {
+                while let Ok(message) = rx.recv() {
+                    match message {
+                        ExportMessage::Row(metrics) => writer.write_row(&metrics),
+                        ExportMessage::Flush(done) => {
+                            writer.flush();
+                            let _ = done.send(());
+                        }
+                    }
+                }
+                writer.flush();
+            })
+            .context("启动指标导出写线程失败")?;
+        Ok(tx)
+    }
+
+    /// 追加一条指标（只入队，不做格式化和文件 I/O）
+    pub fn record(&self, metrics: &Arc<WindowMetrics>) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+
+        match tx.try_send(ExportMessage::Row(metrics.clone())) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped % DROP_WARN_EVERY == 1 {
+                    warn!("⚠️  指标导出队列已满，丢弃指标行 (累计丢弃 {} 行)", dropped);
+                }
+            }
+            Err(TrySendError::Disconnected(_)) => warn!("⚠️  指标导出写线程已退出，指标未导出"),
+        }
+    }
+
+    /// 等待写线程写完已入队的指标并落盘（退出前调用）
+    pub fn flush(&self) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+
+        let (done_tx, done_rx) = mpsc::sync_channel(1);
+        if tx.send(ExportMessage::Flush(done_tx)).is_err() || done_rx.recv().is_err() {
+            warn!("⚠️  指标导出写线程已退出，缓冲区未落盘");
+        }
+
+        let dropped = self.dropped.load(Ordering::Relaxed);
+        if dropped > 0 {
+            warn!("⚠️  指标导出共丢弃 {} 行（写线程跟不上）", dropped);
+        }
+    }
+}
+
+impl ExportWriter {
+    fn write_row(&mut self, metrics: &WindowMetrics) {
+        let row = Self::format_row(metrics);
+
+        if self.file.as_ref().is_some_and(|current| self.should_rotate(current)) {
+            if let Some(mut current) = self.file.take() {
+                if let Err(e) = current.writer.flush() {
+                    warn!("⚠️  指标导出落盘失败: {}", e);
+                }
+            }
+        }
+
+        if self.file.is_none() {
+            match Self::open_file(&self.dir) {
+                Ok(opened) => self.file = Some(opened),
+                Err(e) => {
+                    warn!("⚠️  打开指标导出文件失败: {:#}", e);
+                    return;
+                }
+            }
+        }
+
+        let Some(current) = self.file.as_mut() else {
+            return;
+        };
+        if let Err(e) = current.writer.write_all(row.as_bytes()) {
+            warn!("⚠️  写入指标导出失败: {}", e);
+            return;
+        }
+        current.bytes += row.len() as u64;
+        current.unflushed_rows += 1;
+
+        if current.unflushed_rows >= FLUSH_EVERY_ROWS {
+            current.unflushed_rows = 0;
+            if let Err(e) = current.writer.flush() {
+                warn!("⚠️  指标导出落盘失败: {}", e);
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some(current) = self.file.as_mut() {
+            current.unflushed_rows = 0;
+            if let Err(e) = current.writer.flush() {
+                warn!("⚠️  指标导出落盘失败: {}", e);
+            }
+        }
+    }
+
+    fn should_rotate(&self, current: &ExportFile) -> bool {
+        (self.rotate_bytes > 0 && current.bytes >= self.rotate_bytes)
+            || self.rotate_after.is_some_and(|after| current.opened_at.elapsed() >= after)
+    }
+
+    fn open_file(dir: &Path) -> Result<ExportFile> {
+        let path = dir.join(format!("metrics-{}.csv", Utc::now().format("%Y%m%d-%H%M%S%.3f")));
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("{}", path.display()))?;
+
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{}", HEADER)?;
+        info!("📤 新的指标导出文件: {}", path.display());
+
+        Ok(ExportFile {
+            writer,
+            opened_at: Instant::now(),
+            bytes: HEADER.len() as u64 + 1,
+            unflushed_rows: 0,
+        })
+    }
+
+    fn format_row(metrics: &WindowMetrics) -> String {
+        fn opt<T: std::fmt::Display>(value: Option<T>) -> String {
+            value.map(|v| v.to_string()).unwrap_or_default()
+        }
+
+        let mut row = String::with_capacity(512);
+        let _ = write!(
+            row,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            opt(metrics.event_time.map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))),
+            metrics.mint,
+            metrics.creator,
+            metrics.event_count,
+            metrics.total_event_count,
+            metrics.observed_secs,
+            opt(metrics.token_age_secs),
+            metrics.net_inflow_sol as f64 / 1_000_000_000.0,
+            metrics.buy_ratio,
+            metrics.acceleration,
+            metrics.normalized_acceleration,
+            metrics.window_span_secs,
+            metrics.inflow_rate_sol_per_sec,
+            metrics.trade_rate_per_sec,
+            metrics.unique_buyers,
+            metrics.repeat_buyer_ratio,
+            opt(metrics.dev_initial_buy_sol),
+            metrics.curve_progress_percent,
+            metrics.graduation_triggered,
+            opt(metrics.threshold_buy_amount),
+            opt(metrics.sell_pressure_sol),
+            metrics.latest_virtual_sol_reserves,
+            metrics.latest_virtual_token_reserves,
+            metrics.latest_real_sol_reserves,
+            metrics.latest_real_token_reserves,
+        );
+
+        // 高级指标（事件历史不足时为空列）
+        match &metrics.advanced_metrics {
+            Some(advanced) => {
+                let _ = write!(
+                    row,
+                    ",{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                    advanced.curve_slope,
+                    advanced.weighted_buy_pressure,
+                    advanced.high_frequency_trades,
+                    advanced.avg_price_impact,
+                    advanced.max_price_impact,
+                    advanced.liquidity_depth,
+                    advanced.volatility,
+                    advanced.weighted_buy_sell_ratio,
+                    advanced.large_trade_ratio,
+                    advanced.trade_interval_std,
+                    advanced.vwap_price,
+                    advanced.median_trade_size_sol,
+                    advanced.whale_share,
+                );
+            }
+            None => row.push_str(&",".repeat(13)),
+        }
+
+        row.push('\n');
+        row
+    }
+}
@@ -0,0 +1,101 @@
+/// mint 权限安全检查
+///
+/// 买入前检查 mint 账户的 mint authority / freeze authority：
+/// 有效的 freeze authority 可以冻结我们的 ATA（买得进卖不出），
+/// 非 PumpFun 的 mint authority 可以随意增发稀释持仓
+///
+/// 核心功能:
+/// 1. 权限解析 - 读取基础 mint 布局（Token v3 / Token-2022 相同的前 82 字节）中的两个 COption<Pubkey>
+/// 2. 预期权限 - mint authority 已撤销或为 PumpFun 的 mint-authority PDA 视为正常
+/// 3. 按策略模式配置 - freeze_authority_check_modes / mint_authority_check_modes 列出启用检查的模式
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::config::Config;
+use crate::dynamic_strategy::StrategyMode;
+
+/// PumpFun 程序地址
+const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+
+/// 基础 mint 布局
+const MINT_AUTHORITY_OFFSET: usize = 0;
+const FREEZE_AUTHORITY_OFFSET: usize = 46;
+const MINT_BASE_LENGTH: usize = 82;
+
+/// mint 账户中的权限
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MintAuthorities {
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+}
+
+impl MintAuthorities {
+    /// 解析 mint 账户数据（长度不足时返回 None）
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < MINT_BASE_LENGTH {
+            return None;
+        }
+        Some(Self {
+            mint_authority: Self::coption_pubkey(data, MINT_AUTHORITY_OFFSET)?,
+            freeze_authority: Self::coption_pubkey(data, FREEZE_AUTHORITY_OFFSET)?,
+        })
+    }
+
+    /// COption<Pubkey>: u32 标记(0 = None, 1 = Some) + 32 字节地址
+    fn coption_pubkey(data: &[u8], offset: usize) -> Option<Option<Pubkey>> {
+        let tag = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?);
+        if tag == 0 {
+            return Some(None);
+        }
+        let bytes: [u8; 32] = data.get(offset + 4..offset + 36)?.try_into().ok()?;
+        Some(Some(Pubkey::new_from_array(bytes)))
+    }
+}
+
+/// 当前策略模式下启用的权限检查
+#[derive(Debug, Clone, Copy)]
+pub struct AuthorityPolicy {
+    pub reject_freeze_authority: bool,
+    pub reject_unexpected_mint_authority: bool,
+    /// PumpFun 的 mint-authority PDA
+    expected_mint_authority: Pubkey,
+}
+
+impl AuthorityPolicy {
+    /// 按配置的策略模式决定启用哪些检查
+    pub fn from_config(config: &Config) -> Self {
+        let mode = StrategyMode::from_config(config);
+        let enabled_for = |modes: &[String]| modes.iter().any(|name| StrategyMode::from_name(name) == mode);
+
+        Self {
+            reject_freeze_authority: enabled_for(&config.freeze_authority_check_modes),
+            reject_unexpected_mint_authority: enabled_for(&config.mint_authority_check_modes),
+            expected_mint_authority: Self::pumpfun_mint_authority(),
+        }
+    }
+
+    /// 不安全时返回原因
+    pub fn violation(&self, authorities: &MintAuthorities) -> Option<String> {
+        if self.reject_freeze_authority {
+            if let Some(freeze_authority) = authorities.freeze_authority {
+                return Some(format!("freeze authority 未撤销 ({})", freeze_authority));
+            }
+        }
+
+        if self.reject_unexpected_mint_authority {
+            if let Some(mint_authority) = authorities.mint_authority {
+                if mint_authority != self.expected_mint_authority {
+                    return Some(format!("非预期的 mint authority ({})", mint_authority));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// PumpFun 的 mint-authority PDA
+    fn pumpfun_mint_authority() -> Pubkey {
+        let program_id = Pubkey::try_from(PUMPFUN_PROGRAM_ID).expect("Invalid PUMPFUN_PROGRAM_ID");
+        Pubkey::find_program_address(&[b"mint-authority"], &program_id).0
+    }
+}
@@ -8,11 +8,23 @@
 /// 3. 成交频度骤降检测 - 高频交易 < 2笔
 /// 4. 多维度动能指标综合评估
 /// 5. 时间窗口分析
+/// 6. 按策略模式独立配置，可单独禁用各项检查
 
 use log::{debug, info, warn};
 
+use crate::config::Config;
+use crate::dynamic_strategy::StrategyMode;
 use crate::types::WindowMetrics;
 
+/// 可禁用的衰减检查名称（配置 momentum_disabled_checks 使用）
+pub const DECAY_CHECK_NAMES: &[&str] = &[
+    "buy_ratio",
+    "net_inflow",
+    "activity",
+    "acceleration",
+    "composite_score",
+];
+
 /// 衰减原因
 #[derive(Debug, Clone, PartialEq)]
 pub enum DecayReason {
@@ -79,6 +91,88 @@ pub struct MomentumDecayConfig {
     pub composite_score_threshold: f64,
     /// 是否启用严格模式（所有条件都要满足）
     pub strict_mode: bool,
+    /// 各项检查开关
+    pub check_buy_ratio: bool,
+    pub check_net_inflow: bool,
+    pub check_activity: bool,
+    pub check_acceleration: bool,
+    pub check_composite_score: bool,
+}
+
+impl MomentumDecayConfig {
+    /// 从 Config 创建（当前策略模式的独立参数优先于全局参数）
+    pub fn from_config(config: &Config) -> Self {
+        let (buy_ratio, net_inflow, activity, acceleration, composite_score, strict_mode, disabled_checks) =
+            match StrategyMode::from_config(config) {
+                StrategyMode::Conservative => (
+                    config.conservative_momentum_buy_ratio_threshold,
+                    config.conservative_momentum_net_inflow_threshold,
+                    config.conservative_momentum_activity_threshold,
+                    config.conservative_momentum_acceleration_threshold,
+                    config.conservative_momentum_composite_score_threshold,
+                    config.conservative_momentum_strict_mode,
+                    config.conservative_momentum_disabled_checks.as_ref(),
+                ),
+                StrategyMode::Balanced => (
+                    config.balanced_momentum_buy_ratio_threshold,
+                    config.balanced_momentum_net_inflow_threshold,
+                    config.balanced_momentum_activity_threshold,
+                    config.balanced_momentum_acceleration_threshold,
+                    config.balanced_momentum_composite_score_threshold,
+                    config.balanced_momentum_strict_mode,
+                    config.balanced_momentum_disabled_checks.as_ref(),
+                ),
+                StrategyMode::Aggressive => (
+                    config.aggressive_momentum_buy_ratio_threshold,
+                    config.aggressive_momentum_net_inflow_threshold,
+                    config.aggressive_momentum_activity_threshold,
+                    config.aggressive_momentum_acceleration_threshold,
+                    config.aggressive_momentum_composite_score_threshold,
+                    config.aggressive_momentum_strict_mode,
+                    config.aggressive_momentum_disabled_checks.as_ref(),
+                ),
+                StrategyMode::Custom => (
+                    config.custom_momentum_buy_ratio_threshold,
+                    config.custom_momentum_net_inflow_threshold,
+                    config.custom_momentum_activity_threshold,
+                    config.custom_momentum_acceleration_threshold,
+                    config.custom_momentum_composite_score_threshold,
+                    config.custom_momentum_strict_mode,
+                    config.custom_momentum_disabled_checks.as_ref(),
+                ),
+            };
+
+        let disabled_checks = disabled_checks.unwrap_or(&config.momentum_disabled_checks);
+        let enabled = |name: &str| !disabled_checks.iter().any(|check| check == name);
+
+        Self {
+            buy_ratio_threshold: buy_ratio.unwrap_or(config.momentum_buy_ratio_threshold),
+            net_inflow_threshold: net_inflow.unwrap_or(config.momentum_net_inflow_threshold),
+            trade_frequency_threshold: activity.unwrap_or(config.momentum_activity_threshold) as u32,
+            acceleration_threshold: acceleration.unwrap_or(config.momentum_acceleration_threshold),
+            composite_score_threshold: composite_score.unwrap_or(config.momentum_composite_score_threshold),
+            strict_mode: strict_mode.unwrap_or(config.momentum_strict_mode),
+            check_buy_ratio: enabled("buy_ratio"),
+            check_net_inflow: enabled("net_inflow"),
+            check_activity: enabled("activity"),
+            check_acceleration: enabled("acceleration"),
+            check_composite_score: enabled("composite_score"),
+        }
+    }
+
+    /// 已启用的检查数量
+    fn enabled_check_count(&self) -> usize {
+        [
+            self.check_buy_ratio,
+            self.check_net_inflow,
+            self.check_activity,
+            self.check_acceleration,
+            self.check_composite_score,
+        ]
+        .iter()
+        .filter(|enabled| **enabled)
+        .count()
+    }
 }
 
 impl Default for MomentumDecayConfig {
@@ -90,6 +184,11 @@ impl Default for MomentumDecayConfig {
             acceleration_threshold: 1.0,
             composite_score_threshold: 0.3,
             strict_mode: false,
+            check_buy_ratio: true,
+            check_net_inflow: true,
+            check_activity: true,
+            check_acceleration: true,
+            check_composite_score: true,
         }
     }
 }
@@ -108,6 +207,7 @@ impl MomentumDecayDetector {
         info!("   交易频率阈值: {} 笔", config.trade_frequency_threshold);
         info!("   加速度阈值: {:.2}", config.acceleration_threshold);
         info!("   严格模式: {}", config.strict_mode);
+        info!("   已启用检查: {}/{}", config.enabled_check_count(), DECAY_CHECK_NAMES.len());
 
         Self {
             config,
@@ -128,34 +228,45 @@ impl MomentumDecayDetector {
         let mut decay_reasons = Vec::new();
         
         // 1. 买卖占比回落检测
-        if let Some(reason) = self.check_buy_ratio_decline(metrics) {
-            decay_reasons.push(reason);
+        if self.config.check_buy_ratio {
+            if let Some(reason) = self.check_buy_ratio_decline(metrics) {
+                decay_reasons.push(reason);
+            }
         }
         
         // 2. 净流入转负检测
-        if let Some(reason) = self.check_negative_inflow(metrics) {
-            decay_reasons.push(reason);
+        if self.config.check_net_inflow {
+            if let Some(reason) = self.check_negative_inflow(metrics) {
+                decay_reasons.push(reason);
+            }
         }
         
         // 3. 成交频度骤降检测
-        if let Some(reason) = self.check_low_activity(metrics) {
-            decay_reasons.push(reason);
+        if self.config.check_activity {
+            if let Some(reason) = self.check_low_activity(metrics) {
+                decay_reasons.push(reason);
+            }
         }
         
         // 4. 加速度衰减检测
-        if let Some(reason) = self.check_acceleration_decay(metrics) {
-            decay_reasons.push(reason);
+        if self.config.check_acceleration {
+            if let Some(reason) = self.check_acceleration_decay(metrics) {
+                decay_reasons.push(reason);
+            }
         }
         
         // 5. 综合评分检测
-        if let Some(reason) = self.check_composite_score(metrics) {
-            decay_reasons.push(reason);
+        if self.config.check_composite_score {
+            if let Some(reason) = self.check_composite_score(metrics) {
+                decay_reasons.push(reason);
+            }
         }
         
         // 根据模式返回结果
         if self.config.strict_mode {
-            // 严格模式：所有条件都要满足
-            if decay_reasons.len() >= 3 {
+            // 严格模式：所有条件都要满足（已启用检查不足 3 项时要求全部触发）
+            let required = self.config.enabled_check_count().clamp(1, 3);
+            if decay_reasons.len() >= required {
                 if let Some(reason) = decay_reasons.into_iter().next() {
                     warn!("⚠️  检测到动能衰减（严格模式）: {}", reason.description());
                     Some(reason)
@@ -245,8 +356,8 @@ impl MomentumDecayDetector {
     /// 综合评分 = (买占比 * 0.3) + (归一化净流入 * 0.3) + (归一化加速度 * 0.2) + (归一化活跃度 * 0.2)
     fn check_composite_score(&self, metrics: &WindowMetrics) -> Option<DecayReason> {
         let buy_ratio_score = metrics.buy_ratio;
-        let net_inflow_score = (metrics.net_inflow_sol as f64 / 1_000_000_000.0).max(0.0).min(1.0);
-        let acceleration_score = metrics.acceleration.max(0.0).min(2.0) / 2.0;
+        let net_inflow_score = (metrics.net_inflow_sol as f64 / 1_000_000_000.0).clamp(0.0, 1.0);
+        let acceleration_score = metrics.acceleration.clamp(0.0, 2.0) / 2.0;
         let activity_score = (metrics.event_count as f64 / 10.0).min(1.0);
         
         let composite_score = 
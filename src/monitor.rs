@@ -8,18 +8,37 @@
 /// 3. 大额卖出监控 - 异常大额交易检测
 /// 4. 异常交易模式监控 - rug pull 信号检测
 /// 5. 多维度风险评估
-
-use anyhow::Result;
+/// 6. 历史持久化 - 持仓 mint 的价格 / 流动性历史写入 monitor_history_path，重启后预热（波动检测不从零开始）
+/// 7. 警报生命周期 - 同一 mint + 警报类型去重，冷却期内不重复触发；
+///    可手动确认（确认后仅在严重程度升级时再次触发），条件持续消失后自动解除
+///
+/// 价格和流动性从 gRPC 账户订阅驱动的价格缓存读取，不再逐个持仓轮询 RPC
+/// 价格 / 流动性历史按 mint 存放在 DashMap 中（每个 mint 独立加锁），多个持仓并发监控，
+/// 慢持仓不会阻塞其他持仓的检查和卖出确认
+
+use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
 use log::{debug, info, warn, error};
-use solana_client::rpc_client::RpcClient;
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
-use std::collections::{HashMap, VecDeque};
+use std::collections::VecDeque;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
+use crate::clock::Clock;
 use crate::config::Config;
+use crate::confirmation::{ConfirmationStatus, ConfirmationTracker};
+use crate::error::{BotError, BotResult};
+use crate::price_cache::PriceCache;
+use crate::rpc_api::RpcApi;
 use crate::types::Position;
-use crate::grpc::parser::bonding_curve_decode;  // 🔥 新增: Borsh 解析
+
+/// 大额交易记录保留时长（分钟），覆盖 rug pull 检测的 5 分钟窗口
+const LARGE_TRADE_RETENTION_MINUTES: i64 = 10;
+/// 单个 mint 最多保留的大额交易数
+const MAX_LARGE_TRADES_PER_MINT: usize = 100;
 
 /// 风险警报类型
 #[derive(Debug, Clone)]
@@ -51,6 +70,17 @@ pub enum RiskAlert {
 }
 
 impl RiskAlert {
+    /// 警报类型（去重键）
+    pub fn kind(&self) -> AlertKind {
+        match self {
+            RiskAlert::PriceVolatility { .. } => AlertKind::PriceVolatility,
+            RiskAlert::LiquidityDrop { .. } => AlertKind::LiquidityDrop,
+            RiskAlert::LargeSellDetected { .. } => AlertKind::LargeSellDetected,
+            RiskAlert::RugPullSignal { .. } => AlertKind::RugPullSignal,
+            RiskAlert::LiquidityExhaustion { .. } => AlertKind::LiquidityExhaustion,
+        }
+    }
+
     pub fn severity(&self) -> AlertSeverity {
         match self {
             RiskAlert::RugPullSignal { confidence, .. } => {
@@ -110,13 +140,184 @@ impl RiskAlert {
 }
 
 /// 警报严重程度
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum AlertSeverity {
     Medium,
     High,
     Critical,
 }
 
+/// 警报类型（不含数值，用于去重）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum AlertKind {
+    PriceVolatility,
+    LiquidityDrop,
+    LargeSellDetected,
+    RugPullSignal,
+    LiquidityExhaustion,
+}
+
+/// 活跃警报状态（同一 mint + 类型只保留一条）
+#[derive(Debug, Clone)]
+struct AlertState {
+    severity: AlertSeverity,
+    description: String,
+    first_seen: DateTime<Utc>,
+    /// 最近一次检测到该条件的时间
+    last_seen: Instant,
+    /// 最近一次实际触发（返回给调用方）的时间
+    last_fired: Instant,
+    fire_count: u32,
+    /// 冷却期内 / 已确认而被抑制的次数
+    suppressed: u32,
+    /// 确认时的严重程度（None = 未确认）
+    acknowledged: Option<AlertSeverity>,
+}
+
+/// 活跃警报快照（控制 API 使用）
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveAlert {
+    pub mint: String,
+    pub kind: AlertKind,
+    pub severity: AlertSeverity,
+    pub description: String,
+    pub first_seen: String,
+    pub fire_count: u32,
+    pub suppressed: u32,
+    pub acknowledged: bool,
+}
+
+/// 警报生命周期（去重 / 冷却 / 确认 / 自动解除）
+///
+/// 当前时间由调用方传入，实时监控器传计时时钟的当前时间（回放时跟随录制时间）
+pub struct AlertTracker {
+    /// 同一警报的重复触发冷却
+    cooldown: std::time::Duration,
+    /// 条件持续消失多久后自动解除
+    auto_resolve: std::time::Duration,
+    /// 活跃警报 ((mint, 类型) -> 状态)
+    alerts: DashMap<(Pubkey, AlertKind), AlertState>,
+}
+
+impl AlertTracker {
+    pub fn new(cooldown_secs: u64, auto_resolve_secs: u64) -> Self {
+        Self {
+            cooldown: std::time::Duration::from_secs(cooldown_secs),
+            auto_resolve: std::time::Duration::from_secs(auto_resolve_secs),
+            alerts: DashMap::new(),
+        }
+    }
+
+    /// 警报去重
+    ///
+    /// - 新警报：立即触发
+    /// - 已确认：只在严重程度高于确认时才再次触发
+    /// - 未确认：冷却期过后或严重程度升级时再次触发
+    /// - Critical 警报始终触发（紧急卖出失败时下一轮需要重试）
+    /// - 本轮未检测到、且超过自动解除时间的警报解除
+    pub fn observe(&self, mint: &Pubkey, alerts: Vec<RiskAlert>, now: Instant) -> Vec<RiskAlert> {
+        let mut fired = Vec::with_capacity(alerts.len());
+
+        for alert in alerts {
+            let severity = alert.severity();
+            let description = alert.description();
+
+            let fire = match self.alerts.get_mut(&(*mint, alert.kind())) {
+                Some(mut state) => {
+                    let escalated = severity > state.severity;
+                    let fire = severity == AlertSeverity::Critical
+                        || match state.acknowledged {
+                            Some(acknowledged) => severity > acknowledged,
+                            None => escalated || now.duration_since(state.last_fired) >= self.cooldown,
+                        };
+
+                    state.severity = severity;
+                    state.description = description;
+                    state.last_seen = now;
+                    if fire {
+                        state.last_fired = now;
+                        state.fire_count += 1;
+                        if state.acknowledged.is_some_and(|acknowledged| severity > acknowledged) {
+                            state.acknowledged = None;
+                        }
+                    } else {
+                        state.suppressed += 1;
+                    }
+                    fire
+                }
+                None => {
+                    self.alerts.insert((*mint, alert.kind()), AlertState {
+                        severity,
+                        description,
+                        first_seen: Utc::now(),
+                        last_seen: now,
+                        last_fired: now,
+                        fire_count: 1,
+                        suppressed: 0,
+                        acknowledged: None,
+                    });
+                    true
+                }
+            };
+
+            if fire {
+                fired.push(alert);
+            }
+        }
+
+        self.resolve_stale(now);
+        fired
+    }
+
+    /// 解除超过自动解除时间未再检测到的警报（包括已平仓 mint 的警报）
+    pub fn resolve_stale(&self, now: Instant) {
+        self.alerts.retain(|(mint, kind), state| {
+            let stale = now.duration_since(state.last_seen) >= self.auto_resolve;
+            if stale {
+                info!("✅ 警报已解除: {} {:?} (触发 {} 次, 抑制 {} 次)",
+                    mint, kind, state.fire_count, state.suppressed);
+            }
+            !stale
+        });
+    }
+
+    /// 当前活跃警报
+    pub fn active(&self) -> Vec<ActiveAlert> {
+        self.alerts
+            .iter()
+            .map(|entry| {
+                let (mint, kind) = entry.key();
+                let state = entry.value();
+                ActiveAlert {
+                    mint: mint.to_string(),
+                    kind: *kind,
+                    severity: state.severity,
+                    description: state.description.clone(),
+                    first_seen: state.first_seen.to_rfc3339(),
+                    fire_count: state.fire_count,
+                    suppressed: state.suppressed,
+                    acknowledged: state.acknowledged.is_some(),
+                }
+            })
+            .collect()
+    }
+
+    /// 确认警报（mint 为 None 时确认全部），返回新确认的数量
+    ///
+    /// 确认后同一条件不再触发，直到严重程度升级或条件消失后重新出现
+    pub fn acknowledge(&self, mint: Option<&Pubkey>) -> usize {
+        let mut acknowledged = 0;
+        for mut entry in self.alerts.iter_mut() {
+            if mint.is_some_and(|mint| entry.key().0 != *mint) || entry.acknowledged.is_some() {
+                continue;
+            }
+            entry.acknowledged = Some(entry.severity);
+            acknowledged += 1;
+        }
+        acknowledged
+    }
+}
+
 /// 实时监控配置
 #[derive(Debug, Clone)]
 pub struct MonitorConfig {
@@ -132,6 +333,12 @@ pub struct MonitorConfig {
     pub monitor_interval_secs: u64,
     /// 价格历史窗口（小时）
     pub price_history_hours: i64,
+    /// 同一警报的重复触发冷却（秒）
+    pub alert_cooldown_secs: u64,
+    /// 条件持续消失多久后自动解除警报（秒）
+    pub alert_auto_resolve_secs: u64,
+    /// 价格 / 流动性历史持久化文件（None = 只保存在内存中）
+    pub history_path: Option<String>,
 }
 
 impl MonitorConfig {
@@ -144,6 +351,9 @@ impl MonitorConfig {
             rug_pull_confidence_threshold: config.rug_pull_confidence_threshold,
             monitor_interval_secs: config.monitor_interval_secs,
             price_history_hours: config.price_history_hours,
+            alert_cooldown_secs: config.alert_cooldown_secs,
+            alert_auto_resolve_secs: config.alert_auto_resolve_secs,
+            history_path: config.monitor_history_path.clone(),
         }
     }
 }
@@ -157,6 +367,9 @@ impl Default for MonitorConfig {
             rug_pull_confidence_threshold: 0.7, // 70% 置信度
             monitor_interval_secs: 10,        // 每 10 秒检查一次
             price_history_hours: 24,          // 24 小时价格历史
+            alert_cooldown_secs: 300,         // 同一警报 5 分钟内不重复触发
+            alert_auto_resolve_secs: 60,      // 条件消失 1 分钟后解除
+            history_path: None,
         }
     }
 }
@@ -169,16 +382,42 @@ struct PriceRecord {
     volume: f64,  // 交易量（SOL）
 }
 
+/// 流动性历史记录
+#[derive(Debug, Clone, Copy)]
+struct LiquidityRecord {
+    timestamp: DateTime<Utc>,
+    liquidity: f64,
+}
+
+/// 持久化的单个 mint 历史（时间戳为毫秒）
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedHistory {
+    mint: String,
+    /// (timestamp_ms, price, volume)
+    prices: Vec<(i64, f64, f64)>,
+    /// (timestamp_ms, liquidity)
+    liquidity: Vec<(i64, f64)>,
+}
+
 /// 实时监控器
 pub struct RealTimeMonitor {
     config: MonitorConfig,
-    rpc_client: Arc<RpcClient>,  // 用于查询链上数据（价格、流动性等）和轮询交易确认
+    rpc_client: Arc<dyn RpcApi>,  // 用于交易确认的 RPC 兜底查询
+    confirmations: Arc<ConfirmationTracker>,
+    /// 账户更新驱动的价格缓存（价格、流动性）
+    price_cache: Arc<PriceCache>,
     /// 价格历史记录 (mint -> records)
-    price_history: HashMap<Pubkey, VecDeque<PriceRecord>>,
-    /// 流动性历史记录 (mint -> liquidity)
-    liquidity_history: HashMap<Pubkey, VecDeque<f64>>,
-    /// 大额交易记录 (mint -> transactions)
-    large_transactions: HashMap<Pubkey, VecDeque<LargeTransaction>>,
+    price_history: DashMap<Pubkey, VecDeque<PriceRecord>>,
+    /// 流动性历史记录 (mint -> records)
+    liquidity_history: DashMap<Pubkey, VecDeque<LiquidityRecord>>,
+    /// 大额交易记录（由聚合器的实时交易事件写入）
+    large_trades: Arc<LargeTradeTracker>,
+    /// 活跃警报
+    alerts: AlertTracker,
+    /// 历史持久化文件
+    history_path: Option<PathBuf>,
+    /// 计时时钟（告警冷却 / 自动解除，回放时跟随录制时间）
+    clock: Arc<Clock>,
 }
 
 /// 大额交易记录
@@ -190,28 +429,199 @@ struct LargeTransaction {
     is_sell: bool,
 }
 
+/// 大额交易追踪器
+///
+/// 聚合器写入超过阈值的卖出交易，监控器读取用于大额卖出和 rug pull 检测
+pub struct LargeTradeTracker {
+    /// 大额卖出阈值（SOL）
+    large_sell_threshold: f64,
+    /// 大额交易记录 (mint -> transactions)
+    transactions: DashMap<Pubkey, VecDeque<LargeTransaction>>,
+}
+
+impl LargeTradeTracker {
+    pub fn new(large_sell_threshold: f64) -> Self {
+        Self {
+            large_sell_threshold,
+            transactions: DashMap::new(),
+        }
+    }
+
+    /// 记录一笔交易（仅保留超过阈值的卖出）
+    pub fn record_trade(&self, mint: Pubkey, trader: Pubkey, sol_amount: u64, is_buy: bool, timestamp: DateTime<Utc>) {
+        let amount_sol = sol_amount as f64 / 1_000_000_000.0;
+        if is_buy || amount_sol <= self.large_sell_threshold {
+            return;
+        }
+
+        debug!("📉 记录大额卖出: {} {:.4} SOL (卖家: {})", mint, amount_sol, trader);
+
+        let cutoff_time = Utc::now() - Duration::minutes(LARGE_TRADE_RETENTION_MINUTES);
+        let mut history = self.transactions.entry(mint).or_default();
+        history.push_back(LargeTransaction {
+            timestamp,
+            amount_sol,
+            trader,
+            is_sell: true,
+        });
+
+        while history.len() > MAX_LARGE_TRADES_PER_MINT
+            || history.front().is_some_and(|tx| tx.timestamp < cutoff_time)
+        {
+            history.pop_front();
+        }
+    }
+
+    /// 清理过期记录
+    pub fn cleanup(&self) {
+        let cutoff_time = Utc::now() - Duration::minutes(LARGE_TRADE_RETENTION_MINUTES);
+        self.transactions.retain(|_, history| {
+            history.retain(|tx| tx.timestamp >= cutoff_time);
+            !history.is_empty()
+        });
+    }
+}
+
 impl RealTimeMonitor {
     /// 创建新的实时监控器
-    pub fn new(config: MonitorConfig, rpc_client: Arc<RpcClient>) -> Self {
+    pub fn new(
+        config: MonitorConfig,
+        rpc_client: Arc<dyn RpcApi>,
+        price_cache: Arc<PriceCache>,
+        large_trades: Arc<LargeTradeTracker>,
+        confirmations: Arc<ConfirmationTracker>,
+        clock: Arc<Clock>,
+    ) -> Self {
         info!("📡 实时监控系统已初始化");
         info!("   价格警报阈值: {:.2}%", config.price_alert_threshold);
         info!("   流动性警报阈值: {:.2}%", config.liquidity_alert_threshold);
         info!("   大额卖出阈值: {:.4} SOL", config.large_sell_threshold);
         info!("   监控间隔: {} 秒", config.monitor_interval_secs);
-        
-        Self {
+
+        let history_path = config.history_path.as_deref().filter(|p| !p.is_empty()).map(PathBuf::from);
+        let alerts = AlertTracker::new(config.alert_cooldown_secs, config.alert_auto_resolve_secs);
+        let monitor = Self {
             config,
             rpc_client,
-            price_history: HashMap::new(),
-            liquidity_history: HashMap::new(),
-            large_transactions: HashMap::new(),
+            price_cache,
+            price_history: DashMap::new(),
+            liquidity_history: DashMap::new(),
+            large_trades,
+            confirmations,
+            alerts,
+            history_path,
+            clock,
+        };
+
+        // 🔥 新增: 从上次运行保存的历史预热
+        if let Err(e) = monitor.load_history() {
+            warn!("⚠️  读取监控历史失败，从空历史开始: {:?}", e);
+        }
+
+        monitor
+    }
+
+    /// 读取持久化的价格 / 流动性历史（丢弃超出价格历史窗口的记录）
+    fn load_history(&self) -> Result<()> {
+        let Some(path) = self.history_path.as_ref().filter(|p| p.exists()) else {
+            return Ok(());
+        };
+
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("读取监控历史文件失败: {}", path.display()))?;
+        let saved: Vec<PersistedHistory> = serde_json::from_str(&data)
+            .with_context(|| format!("解析监控历史文件失败: {}", path.display()))?;
+
+        let cutoff_time = self.clock.utc_now() - Duration::hours(self.config.price_history_hours * 2);
+        let mut records = 0;
+        for history in &saved {
+            let Ok(mint) = history.mint.parse::<Pubkey>() else {
+                continue;
+            };
+
+            let prices: VecDeque<PriceRecord> = history.prices
+                .iter()
+                .filter_map(|&(timestamp_ms, price, volume)| {
+                    DateTime::from_timestamp_millis(timestamp_ms).map(|timestamp| PriceRecord { timestamp, price, volume })
+                })
+                .filter(|record| record.timestamp >= cutoff_time)
+                .collect();
+            records += prices.len();
+
+            if !prices.is_empty() {
+                self.price_history.insert(mint, prices);
+            }
+            let liquidity: VecDeque<LiquidityRecord> = history.liquidity
+                .iter()
+                .filter_map(|&(timestamp_ms, liquidity)| {
+                    DateTime::from_timestamp_millis(timestamp_ms).map(|timestamp| LiquidityRecord { timestamp, liquidity })
+                })
+                .filter(|record| record.timestamp >= cutoff_time)
+                .collect();
+            if !liquidity.is_empty() {
+                self.liquidity_history.insert(mint, liquidity);
+            }
+        }
+
+        info!("📡 监控历史已预热: {} ({} 个 mint, {} 条价格记录)", path.display(), saved.len(), records);
+        Ok(())
+    }
+
+    /// 保存持仓 mint 的价格 / 流动性历史（非持仓 mint 不写入，先写临时文件再重命名）
+    ///
+    /// 在内存中序列化快照，文件写入放到 spawn_blocking 线程，不阻塞异步工作线程
+    pub async fn persist_history(&self, held: &[Pubkey]) {
+        let Some(path) = &self.history_path else {
+            return;
+        };
+
+        let histories: Vec<PersistedHistory> = held
+            .iter()
+            .map(|mint| PersistedHistory {
+                mint: mint.to_string(),
+                prices: self.price_history
+                    .get(mint)
+                    .map(|history| history.iter()
+                        .map(|record| (record.timestamp.timestamp_millis(), record.price, record.volume))
+                        .collect())
+                    .unwrap_or_default(),
+                liquidity: self.liquidity_history
+                    .get(mint)
+                    .map(|history| history.iter()
+                        .map(|record| (record.timestamp.timestamp_millis(), record.liquidity))
+                        .collect())
+                    .unwrap_or_default(),
+            })
+            .filter(|history| !history.prices.is_empty() || !history.liquidity.is_empty())
+            .collect();
+
+        let data = match serde_json::to_string(&histories) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("⚠️  序列化监控历史失败: {}", e);
+                return;
+            }
+        };
+
+        let path = path.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let tmp = path.with_extension("tmp");
+            std::fs::write(&tmp, data).context("写入临时文件失败")?;
+            std::fs::rename(&tmp, &path).context("重命名监控历史文件失败")
+        })
+        .await
+        .context("监控历史写入任务异常退出")
+        .and_then(|result| result);
+
+        if let Err(e) = result {
+            warn!("⚠️  保存监控历史失败: {:?}", e);
         }
     }
 
     /// 监控持仓
     ///
     /// 返回检测到的所有风险警报
-    pub async fn monitor_position(&mut self, position: &Position) -> Result<Vec<RiskAlert>> {
+    pub async fn monitor_position(&self, position: &Position) -> Result<Vec<RiskAlert>> {
         debug!("📡 监控持仓: {}", position.mint);
 
         let mut alerts = Vec::new();
@@ -244,6 +654,9 @@ impl RealTimeMonitor {
             alerts.push(alert);
         }
         
+        // 去重 / 冷却 / 确认：只返回需要处理的警报
+        let alerts = self.alerts.observe(&position.mint, alerts, self.clock.now());
+
         // 记录警报
         if !alerts.is_empty() {
             warn!("⚠️  检测到 {} 个风险警报", alerts.len());
@@ -264,10 +677,23 @@ impl RealTimeMonitor {
         Ok(alerts)
     }
 
+    /// 当前活跃警报
+    pub fn active_alerts(&self) -> Vec<ActiveAlert> {
+        self.alerts.active()
+    }
+
+    /// 确认警报（mint 为 None 时确认全部），返回新确认的数量
+    pub fn acknowledge_alerts(&self, mint: Option<&Pubkey>) -> usize {
+        self.alerts.acknowledge(mint)
+    }
+
     /// 检查价格波动
-    async fn check_price_volatility(&mut self, mint: &Pubkey, volume_sol: f64) -> Result<Option<RiskAlert>> {
-        // 获取当前价格
-        let current_price = self.get_current_price(mint).await?;
+    async fn check_price_volatility(&self, mint: &Pubkey, volume_sol: f64) -> Result<Option<RiskAlert>> {
+        // 获取当前价格（缓存中尚无该曲线时跳过）
+        let current_price = match self.get_current_price(mint) {
+            Some(price) => price,
+            None => return Ok(None),
+        };
 
         // 记录价格（带交易量）
         self.record_price(mint, current_price, volume_sol);
@@ -279,7 +705,7 @@ impl RealTimeMonitor {
         };
 
         // 计算 24 小时价格变化
-        let cutoff_time = Utc::now() - Duration::hours(self.config.price_history_hours);
+        let cutoff_time = self.clock.utc_now() - Duration::hours(self.config.price_history_hours);
         let old_prices: Vec<_> = history.iter()
             .filter(|r| r.timestamp < cutoff_time)
             .collect();
@@ -313,13 +739,16 @@ impl RealTimeMonitor {
     }
 
     /// 检查流动性下降
-    async fn check_liquidity_drop(&mut self, mint: &Pubkey) -> Result<Option<RiskAlert>> {
-        // 获取当前流动性
-        let current_liquidity = self.get_current_liquidity(mint).await?;
+    async fn check_liquidity_drop(&self, mint: &Pubkey) -> Result<Option<RiskAlert>> {
+        // 获取当前流动性（缓存中尚无该曲线时跳过）
+        let current_liquidity = match self.get_current_liquidity(mint) {
+            Some(liquidity) => liquidity,
+            None => return Ok(None),
+        };
         
         // 记录流动性
-        let history = self.liquidity_history.entry(*mint).or_insert_with(VecDeque::new);
-        history.push_back(current_liquidity);
+        let mut history = self.liquidity_history.entry(*mint).or_default();
+        history.push_back(LiquidityRecord { timestamp: self.clock.utc_now(), liquidity: current_liquidity });
         
         // 保持历史记录在 100 个数据点内
         while history.len() > 100 {
@@ -332,7 +761,8 @@ impl RealTimeMonitor {
 
         // 计算流动性变化
         let old_liquidity = history.front()
-            .ok_or_else(|| anyhow::anyhow!("流动性历史为空"))?;
+            .ok_or_else(|| anyhow::anyhow!("流动性历史为空"))?
+            .liquidity;
         let drop_percent = ((old_liquidity - current_liquidity) / old_liquidity) * 100.0;
         
         if drop_percent > self.config.liquidity_alert_threshold {
@@ -347,17 +777,15 @@ impl RealTimeMonitor {
     }
 
     /// 检查大额卖出
-    async fn check_large_sells(&mut self, mint: &Pubkey) -> Result<Option<RiskAlert>> {
-        // 这里应该从链上获取最近的大额交易
-        // 简化实现：检查历史记录
-        
-        let transactions = match self.large_transactions.get(mint) {
+    async fn check_large_sells(&self, mint: &Pubkey) -> Result<Option<RiskAlert>> {
+        // 大额交易由聚合器的实时交易事件写入
+        let transactions = match self.large_trades.transactions.get(mint) {
             Some(t) if !t.is_empty() => t,
             _ => return Ok(None),
         };
         
         // 检查最近 1 分钟的大额卖出
-        let cutoff_time = Utc::now() - Duration::minutes(1);
+        let cutoff_time = self.clock.utc_now() - Duration::minutes(1);
         let recent_large_sells: Vec<_> = transactions.iter()
             .filter(|tx| tx.timestamp > cutoff_time && tx.is_sell)
             .filter(|tx| tx.amount_sol > self.config.large_sell_threshold)
@@ -383,7 +811,7 @@ impl RealTimeMonitor {
         if let Some(history) = self.liquidity_history.get(mint) {
             if history.len() >= 2 {
                 if let (Some(recent), Some(old)) = (history.back(), history.front()) {
-                    let drop = ((old - recent) / old) * 100.0;
+                    let drop = ((old.liquidity - recent.liquidity) / old.liquidity) * 100.0;
 
                     if drop > 50.0 {
                         indicators.push(format!("流动性暴跌 {:.0}%", drop));
@@ -394,9 +822,9 @@ impl RealTimeMonitor {
         }
         
         // 指标 2: 连续大额卖出
-        if let Some(transactions) = self.large_transactions.get(mint) {
+        if let Some(transactions) = self.large_trades.transactions.get(mint) {
             let recent_sells = transactions.iter()
-                .filter(|tx| tx.is_sell && tx.timestamp > Utc::now() - Duration::minutes(5))
+                .filter(|tx| tx.is_sell && tx.timestamp > self.clock.utc_now() - Duration::minutes(5))
                 .count();
             
             if recent_sells >= 3 {
@@ -432,12 +860,14 @@ impl RealTimeMonitor {
 
     /// 检查流动性枯竭
     async fn check_liquidity_exhaustion(&self, mint: &Pubkey) -> Result<Option<RiskAlert>> {
-        let current_liquidity = self.get_current_liquidity(mint).await?;
+        let current_liquidity = match self.get_current_liquidity(mint) {
+            Some(liquidity) => liquidity,
+            None => return Ok(None),
+        };
         
         // 假设初始流动性为历史最高值
         let max_liquidity = self.liquidity_history.get(mint)
-            .and_then(|h| h.iter().max_by(|a, b| a.partial_cmp(b).unwrap()))
-            .copied()
+            .and_then(|h| h.iter().map(|record| record.liquidity).max_by(|a, b| a.partial_cmp(b).unwrap()))
             .unwrap_or(current_liquidity);
         
         let remaining_percent = (current_liquidity / max_liquidity) * 100.0;
@@ -454,78 +884,31 @@ impl RealTimeMonitor {
 
     /// 获取当前价格
     ///
-    /// 完全对齐 sol-trade-sdk 的 BondingCurveAccount::get_token_price 实现
-    /// 参考: sol-trade-sdk/src/common/bonding_curve.rs:225-230
-    async fn get_current_price(&self, mint: &Pubkey) -> Result<f64> {
-        // 派生 bonding curve 地址
-        let bonding_curve = self.derive_bonding_curve(mint)?;
-
-        // 从链上读取 bonding curve 账户数据
-        match self.rpc_client.get_account_data(&bonding_curve) {
-            Ok(data) => {
-                // 🔥 修复: 使用 Borsh 解析替代手动 offset 读取
-                if let Some(bc) = bonding_curve_decode(&data) {
-                    if bc.virtual_token_reserves > 0 {
-                        // 完全对齐 sol-trade-sdk 的 get_token_price 实现
-                        let v_sol = bc.virtual_sol_reserves as f64 / 100_000_000.0;  // lamports to 0.01 SOL
-                        let v_tokens = bc.virtual_token_reserves as f64 / 100_000.0; // smallest unit
-                        let token_price = v_sol / v_tokens;
-
-                        Ok(token_price)
-                    } else {
-                        Ok(0.0)
-                    }
-                } else {
-                    Ok(0.0)
-                }
-            }
-            Err(_) => {
-                // 如果读取失败，返回 0（避免程序崩溃）
-                Ok(0.0)
-            }
-        }
+    /// 🔥 优化: 从价格缓存读取（BondingCurve 账户更新驱动），替代 RPC 轮询
+    /// 价格计算对齐 sol-trade-sdk 的 BondingCurveAccount::get_token_price
+    fn get_current_price(&self, mint: &Pubkey) -> Option<f64> {
+        self.price_cache
+            .get_by_mint(mint)
+            .map(|state| state.price())
+            .filter(|price| *price > 0.0)
     }
 
     /// 获取当前流动性
     ///
-    /// 从 bonding curve 账户读取 SOL 储备量作为流动性指标
-    async fn get_current_liquidity(&self, mint: &Pubkey) -> Result<f64> {
-        // 派生 bonding curve 地址
-        let bonding_curve = self.derive_bonding_curve(mint)?;
-
-        // 从链上读取 bonding curve 账户数据
-        match self.rpc_client.get_account_data(&bonding_curve) {
-            Ok(data) => {
-                // 🔥 修复: 使用 Borsh 解析替代手动 offset 读取
-                if let Some(bc) = bonding_curve_decode(&data) {
-                    // 流动性 = SOL储备量（lamports -> SOL）
-                    let liquidity_sol = bc.virtual_sol_reserves as f64 / 1_000_000_000.0;
-                    Ok(liquidity_sol)
-                } else {
-                    Ok(0.0)
-                }
-            }
-            Err(_) => {
-                // 如果读取失败，返回 0（避免程序崩溃）
-                Ok(0.0)
-            }
-        }
-    }
-
-    /// 派生 bonding curve PDA
-    fn derive_bonding_curve(&self, mint: &Pubkey) -> Result<Pubkey> {
-        let program_id = Pubkey::try_from("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P")?;
-        let seeds = &[b"bonding-curve", mint.as_ref()];
-        let (pda, _bump) = Pubkey::find_program_address(seeds, &program_id);
-        Ok(pda)
+    /// 使用 bonding curve 的 SOL 储备量作为流动性指标
+    fn get_current_liquidity(&self, mint: &Pubkey) -> Option<f64> {
+        self.price_cache
+            .get_by_mint(mint)
+            .map(|state| state.liquidity_sol())
+            .filter(|liquidity| *liquidity > 0.0)
     }
 
     /// 记录价格
-    fn record_price(&mut self, mint: &Pubkey, price: f64, volume: f64) {
-        let history = self.price_history.entry(*mint).or_insert_with(VecDeque::new);
+    fn record_price(&self, mint: &Pubkey, price: f64, volume: f64) {
+        let mut history = self.price_history.entry(*mint).or_default();
 
         history.push_back(PriceRecord {
-            timestamp: Utc::now(),
+            timestamp: self.clock.utc_now(),
             price,
             volume,
         });
@@ -536,55 +919,44 @@ impl RealTimeMonitor {
         }
     }
 
-    /// 轮询交易确认（参考 sol-trade-sdk 的实现）
+    /// 获取最近的大额卖家（去重）
     ///
-    /// 用于确认交易是否成功上链
+    /// 用于 rug pull 检测后回写黑名单
+    pub fn recent_large_sellers(&self, mint: &Pubkey, minutes: i64) -> Vec<Pubkey> {
+        let cutoff_time = self.clock.utc_now() - Duration::minutes(minutes);
+        let mut sellers = Vec::new();
+
+        if let Some(transactions) = self.large_trades.transactions.get(mint) {
+            for tx in transactions.iter().filter(|tx| tx.is_sell && tx.timestamp > cutoff_time) {
+                if !sellers.contains(&tx.trader) {
+                    sellers.push(tx.trader);
+                }
+            }
+        }
+
+        sellers
+    }
+
+    /// 等待交易确认
+    ///
+    /// 🔥 优化: gRPC 交易流匹配签名（亚秒级），RPC 仅作低频兜底
     pub async fn poll_transaction_confirmation(
         &self,
         signature: solana_sdk::signature::Signature,
         timeout_secs: u64,
-    ) -> Result<solana_sdk::signature::Signature> {
-        use std::time::Instant;
-        use tokio::time::{sleep, Duration};
-
-        let timeout = Duration::from_secs(timeout_secs);
-        let interval = Duration::from_millis(500); // 每 500ms 检查一次
-        let start = Instant::now();
-
-        info!("⏳ 开始轮询交易确认: {}", signature);
-
-        loop {
-            // 超时检查
-            if start.elapsed() >= timeout {
-                return Err(anyhow::anyhow!("交易确认超时 ({}s)", timeout_secs));
-            }
-
-            // 查询交易状态
-            match self.rpc_client.get_signature_statuses(&[signature]) {
-                Ok(response) => {
-                    if let Some(status) = response.value.first() {
-                        if let Some(status) = status {
-                            // 检查是否确认
-                            if status.confirmation_status.is_some() {
-                                info!("✅ 交易已确认: {}", signature);
-                                return Ok(signature);
-                            }
-
-                            // 检查是否有错误
-                            if let Some(err) = &status.err {
-                                error!("❌ 交易失败: {:?}", err);
-                                return Err(anyhow::anyhow!("交易失败: {:?}", err));
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    debug!("⚠️  查询交易状态失败: {}, 继续重试", e);
-                }
+    ) -> BotResult<solana_sdk::signature::Signature> {
+        info!("⏳ 等待交易确认: {}", signature);
+
+        match self.confirmations.wait_for(self.rpc_client.as_ref(), &signature, timeout_secs).await {
+            ConfirmationStatus::Confirmed => Ok(signature),
+            ConfirmationStatus::Failed(err) => {
+                error!("❌ 交易失败: {}", err);
+                Err(BotError::from_transaction_failure(&err))
             }
-
-            // 等待后重试
-            sleep(interval).await;
+            ConfirmationStatus::TimedOut => Err(BotError::ConfirmationTimeout {
+                signature,
+                secs: timeout_secs,
+            }),
         }
     }
 
@@ -0,0 +1,183 @@
+/// 管道背压监控
+///
+/// gRPC → 事件队列 → 聚合器 → 指标通道 → 策略 worker 队列 → 策略引擎 → 信号通道（入场 / 退出） → 持仓管理器，
+/// 任一环节消费变慢时消息在队列 / 通道中堆积，此前唯一的症状是买入悄悄变慢
+///
+/// 核心功能:
+/// 1. 事件队列占用 - ArrayQueue 当前长度 / 容量
+/// 2. 通道积压 - 指标通道、各策略 worker 队列、入场 / 退出信号通道中已缓冲未消费的消息数 / 容量
+/// 3. 峰值 - 记录启动以来各环节的最大积压
+/// 4. 告警 - 占用率超过阈值时打印警告（持续积压时每 10 秒最多一次，恢复后打印一次）
+
+use crossbeam_queue::ArrayQueue;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::signal_bus::SignalSender;
+use crate::strategy::StrategyEngine;
+use crate::types::{SniperEvent, WindowMetrics};
+
+/// 持续积压时的告警间隔
+const WARN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 单个环节的积压指标
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PipelineGauge {
+    /// 当前积压的消息数
+    pub len: usize,
+    pub capacity: usize,
+    /// 启动以来的最大积压
+    pub peak: usize,
+}
+
+impl PipelineGauge {
+    pub fn occupancy_percent(&self) -> f64 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+        self.len as f64 / self.capacity as f64 * 100.0
+    }
+}
+
+/// 管道积压快照（控制 API / 日志使用）
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineSnapshot {
+    pub event_queue: PipelineGauge,
+    pub metrics_channel: PipelineGauge,
+    /// 各策略 worker 的指标队列（按 mint 分片；单 worker 时为空，直接消费指标通道）
+    pub strategy_workers: Vec<PipelineGauge>,
+    /// 入场信号通道（买入）
+    pub signal_channel: PipelineGauge,
+    /// 退出信号通道（卖出 / 持有，优先消费）
+    pub exit_signal_channel: PipelineGauge,
+}
+
+/// 管道背压监控器
+pub struct PipelineMonitor {
+    event_queue: Arc<ArrayQueue<SniperEvent>>,
+    metrics_tx: mpsc::Sender<Arc<WindowMetrics>>,
+    signal_tx: SignalSender,
+    /// 策略引擎（worker 队列在启动后才创建，每次采样时读取）
+    strategy: Arc<StrategyEngine>,
+    /// 告警阈值（占用率百分比）
+    warn_percent: f64,
+    event_queue_peak: AtomicUsize,
+    metrics_peak: AtomicUsize,
+    worker_peaks: Mutex<Vec<usize>>,
+    signal_peak: AtomicUsize,
+    exit_signal_peak: AtomicUsize,
+    backpressured: AtomicBool,
+    last_warned: Mutex<Option<Instant>>,
+}
+
+impl PipelineMonitor {
+    pub fn new(
+        event_queue: Arc<ArrayQueue<SniperEvent>>,
+        metrics_tx: mpsc::Sender<Arc<WindowMetrics>>,
+        signal_tx: SignalSender,
+        strategy: Arc<StrategyEngine>,
+        warn_percent: f64,
+    ) -> Self {
+        Self {
+            event_queue,
+            metrics_tx,
+            signal_tx,
+            strategy,
+            warn_percent,
+            event_queue_peak: AtomicUsize::new(0),
+            metrics_peak: AtomicUsize::new(0),
+            worker_peaks: Mutex::new(Vec::new()),
+            signal_peak: AtomicUsize::new(0),
+            exit_signal_peak: AtomicUsize::new(0),
+            backpressured: AtomicBool::new(false),
+            last_warned: Mutex::new(None),
+        }
+    }
+
+    /// 采样当前积压（同时更新峰值）
+    pub fn snapshot(&self) -> PipelineSnapshot {
+        PipelineSnapshot {
+            event_queue: Self::gauge(self.event_queue.len(), self.event_queue.capacity(), &self.event_queue_peak),
+            metrics_channel: Self::channel_gauge(&self.metrics_tx, &self.metrics_peak),
+            strategy_workers: self.worker_gauges(),
+            signal_channel: Self::channel_gauge(self.signal_tx.entry_sender(), &self.signal_peak),
+            exit_signal_channel: Self::channel_gauge(self.signal_tx.exit_sender(), &self.exit_signal_peak),
+        }
+    }
+
+    /// 定期采样并在超过阈值时告警
+    pub async fn start_loop(self: Arc<Self>, interval_ms: u64) {
+        info!("📶 管道背压监控已启动 (间隔 {}ms, 告警阈值 {:.0}%)", interval_ms, self.warn_percent);
+        let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            self.check();
+        }
+    }
+
+    fn check(&self) {
+        let snapshot = self.snapshot();
+        let mut stages = vec![
+            ("事件队列".to_string(), snapshot.event_queue),
+            ("指标通道".to_string(), snapshot.metrics_channel),
+            ("信号通道".to_string(), snapshot.signal_channel),
+            ("退出信号通道".to_string(), snapshot.exit_signal_channel),
+        ];
+        stages.extend(snapshot.strategy_workers.iter().enumerate()
+            .map(|(index, gauge)| (format!("策略 worker {}", index), *gauge)));
+        let congested: Vec<String> = stages
+            .iter()
+            .filter(|(_, gauge)| gauge.occupancy_percent() >= self.warn_percent)
+            .map(|(name, gauge)| format!("{} {}/{} ({:.0}%)", name, gauge.len, gauge.capacity, gauge.occupancy_percent()))
+            .collect();
+
+        if congested.is_empty() {
+            if self.backpressured.swap(false, Ordering::Relaxed) {
+                let worker_backlog: usize = snapshot.strategy_workers.iter().map(|gauge| gauge.len).sum();
+                info!("📶 管道积压已恢复: 事件队列 {}, 指标通道 {}, 策略 worker 队列 {}, 信号通道 {}, 退出信号通道 {}",
+                    snapshot.event_queue.len, snapshot.metrics_channel.len, worker_backlog,
+                    snapshot.signal_channel.len, snapshot.exit_signal_channel.len);
+            }
+            return;
+        }
+
+        self.backpressured.store(true, Ordering::Relaxed);
+        let mut last_warned = self.last_warned.lock();
+        if last_warned.is_none_or(|at| at.elapsed() >= WARN_INTERVAL) {
+            *last_warned = Some(Instant::now());
+            warn!("⚠️  管道背压，买入信号可能延迟: {}", congested.join(", "));
+        }
+    }
+
+    /// 各策略 worker 队列的积压（峰值按 worker 序号记录）
+    fn worker_gauges(&self) -> Vec<PipelineGauge> {
+        let queues = self.strategy.worker_queues();
+        let mut peaks = self.worker_peaks.lock();
+        let len = queues.len().max(peaks.len());
+        peaks.resize(len, 0);
+
+        queues.iter().zip(peaks.iter_mut()).map(|(sender, peak)| {
+            let capacity = sender.max_capacity();
+            let len = capacity - sender.capacity();
+            *peak = (*peak).max(len);
+            PipelineGauge { len, capacity, peak: *peak }
+        }).collect()
+    }
+
+    fn channel_gauge<T>(sender: &mpsc::Sender<T>, peak: &AtomicUsize) -> PipelineGauge {
+        let capacity = sender.max_capacity();
+        Self::gauge(capacity - sender.capacity(), capacity, peak)
+    }
+
+    fn gauge(len: usize, capacity: usize, peak: &AtomicUsize) -> PipelineGauge {
+        let peak = peak.fetch_max(len, Ordering::Relaxed).max(len);
+        PipelineGauge { len, capacity, peak }
+    }
+}
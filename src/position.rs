@@ -1,19 +1,40 @@
-use chrono::Utc;
-use log::{info, warn, error};
+use log::{debug, info, warn, error};
+use dashmap::DashSet;
 use parking_lot::RwLock as ParkingLotRwLock;
-use solana_sdk::pubkey::Pubkey;
-use std::collections::HashMap;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock as TokioRwLock};
+use std::time::Instant;
+use tokio::sync::RwLock as TokioRwLock;
 use once_cell::sync::Lazy;  // 🔥 新增: 用于全局PDA缓存
+use serde::Serialize;
 
+use crate::advanced_filter::AdvancedEventFilter;
+use crate::clock::Clock;
 use crate::config::Config;
+use crate::ev::{EvCalculator, EvEstimate, EvInputs};
+use crate::event_feed::EventFeed;
+use crate::journal::{JournalRecord, TradeJournal};
+use crate::market::MarketHealth;
+use crate::position_state::{PositionStateStore, PositionStatus};
 use crate::executor::TransactionBuilder;
+use crate::confirmation::ConfirmationTracker;
+use crate::dex::DexAdapter;
+use crate::error::BotError;
 use crate::executor::lightspeed_buy::LightSpeedBuyExecutor;
-use crate::executor::sol_trade_sell::{SolTradeSellExecutor, SellParams, PumpFunSellParams};
+use crate::executor::sol_trade_sell::{SolTradeSellExecutor, SellParams};
+use crate::executor::wallet_sweep::{TokenAccountInfo, WalletSweeper};
+use crate::feed_reconcile::FeedReconciler;
+use crate::grpc::parser::{bonding_curve_account_decode, BondingCurve};
 use crate::momentum_decay::{MomentumDecayDetector, MomentumDecayConfig};
-use crate::monitor::{RealTimeMonitor, MonitorConfig, AlertSeverity};
+use crate::monitor::{RealTimeMonitor, MonitorConfig, AlertSeverity, RiskAlert, LargeTradeTracker};
+use crate::price_cache::{CurveState, PriceCache};
+use crate::risk::{BalanceGuard, DrawdownGuard};
+use crate::rpc_pool::RpcPool;
+use crate::signal_bus::SignalReceiver;
+use crate::sizing::ConfidenceSizing;
 use crate::strategy::StrategyEngine;
+use crate::threshold::ThresholdTrigger;
 use crate::types::{Position, StrategySignal, WindowMetrics};
 
 // 🔥 新增: PDA缓存（全局静态）
@@ -22,20 +43,57 @@ static PUMPFUN_PROGRAM_ID: Lazy<Pubkey> = Lazy::new(|| {
         .expect("Invalid PumpFun program ID")
 });
 
-static TOKEN_PROGRAM_ID: Lazy<Pubkey> = Lazy::new(|| {
-    Pubkey::try_from("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")
-        .expect("Invalid TOKEN_PROGRAM_ID")
+// 毕业后的 PumpSwap 池（告警时给出池地址）
+static PUMPSWAP_PROGRAM_ID: Lazy<Pubkey> = Lazy::new(|| {
+    Pubkey::try_from("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA")
+        .expect("Invalid PUMPSWAP_PROGRAM_ID")
 });
 
-static TOKEN_2022_PROGRAM_ID: Lazy<Pubkey> = Lazy::new(|| {
-    Pubkey::try_from("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb")
-        .expect("Invalid TOKEN_2022_PROGRAM_ID")
+static WSOL_MINT: Lazy<Pubkey> = Lazy::new(|| {
+    Pubkey::try_from("So11111111111111111111111111111111111111112")
+        .expect("Invalid WSOL_MINT")
 });
 
-static ASSOCIATED_TOKEN_PROGRAM_ID: Lazy<Pubkey> = Lazy::new(|| {
-    Pubkey::try_from("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL")
-        .expect("Invalid ASSOCIATED_TOKEN_PROGRAM_ID")
-});
+/// 钱包中持有但不在持仓记录里的 PumpFun token（启动恢复）
+#[derive(Debug, Clone, Serialize)]
+pub struct UnknownToken {
+    pub mint: String,
+    pub token_amount: u64,
+    /// 按当前曲线估算的卖出所得（lamports）
+    pub estimated_value_lamports: u64,
+    /// 曲线已完成（已毕业，无法在 bonding curve 上卖出，只能手动处理）
+    pub complete: bool,
+    #[serde(skip)]
+    account: TokenAccountInfo,
+    #[serde(skip)]
+    curve: BondingCurve,
+}
+
+/// 已毕业（曲线完成）的持仓：bonding curve 上无法卖出，需要在 PumpSwap 上手动卖出
+#[derive(Debug, Clone, Serialize)]
+pub struct MigratedPosition {
+    pub mint: String,
+    pub token_amount: u64,
+    /// 开仓总成本（扣除分笔卖出已收回部分前，lamports）
+    pub cost_lamports: u64,
+    /// 按 PumpFun 迁移规则派生的 PumpSwap 池
+    pub pumpswap_pool: String,
+    pub detected_at: String,
+}
+
+/// 买入名额：占用期间该 mint 计入 buys_in_flight，drop 时释放
+///
+/// 买入 future 被取消或 panic 时也会释放，避免 mint 永久占用持仓名额、无法再次买入
+struct BuyReservation {
+    buys_in_flight: Arc<ParkingLotRwLock<HashSet<Pubkey>>>,
+    mint: Pubkey,
+}
+
+impl Drop for BuyReservation {
+    fn drop(&mut self) {
+        self.buys_in_flight.write().remove(&self.mint);
+    }
+}
 
 /// 持仓管理器（增强版）
 ///
@@ -50,41 +108,102 @@ pub struct PositionManager {
     lightspeed_buy: Arc<LightSpeedBuyExecutor>,
     /// SolTrade 卖出执行器（专用于卖出）
     sol_trade_sell: Arc<SolTradeSellExecutor>,
+    /// DEX 协议适配器（池子账户派生）
+    dex: Arc<dyn DexAdapter>,
     /// 动能衰减检测器（使用 Tokio RwLock 支持异步）
     momentum_detector: Arc<TokioRwLock<MomentumDecayDetector>>,
-    /// 实时监控器（使用 Tokio RwLock 支持异步）
-    monitor: Arc<TokioRwLock<RealTimeMonitor>>,
+    /// 实时监控器（内部按 mint 分别加锁，多个持仓并发监控）
+    monitor: Arc<RealTimeMonitor>,
+    /// 聚合器的事件过滤器（rug pull 检测结果回写黑名单）
+    filter: Arc<AdvancedEventFilter>,
+    /// 聚合器的阈值触发器（平仓后请求重新布防）
+    threshold: Arc<ThresholdTrigger>,
+    /// 聚合器的持仓 mint 集合（开仓 / 平仓时同步，聚合器据此跳过持仓窗口的内存淘汰）
+    held_mints: Arc<DashSet<Pubkey>>,
+    /// 账户更新驱动的价格缓存（退出循环使用）
+    price_cache: Arc<PriceCache>,
+    /// 正在卖出的 mint（信号、监控、退出循环并发触发时避免重复卖出）
+    sells_in_flight: Arc<ParkingLotRwLock<HashSet<Pubkey>>>,
+    /// 正在买入的 mint（检查持仓到写入持仓之间有 await，避免同一 mint 重复买入）
+    buys_in_flight: Arc<ParkingLotRwLock<HashSet<Pubkey>>>,
+    /// 每个持仓最近一次处理 Hold 信号的时间（hold_coalesce_ms 内的 Hold 信号合并跳过；策略引擎目前不发出 Hold）
+    last_hold_processed: ParkingLotRwLock<HashMap<Pubkey, Instant>>,
+    /// 钱包清理器（卖出后回收 WSOL / 空账户租金，并记录 ATA 租金账本）
+    wallet_sweeper: Arc<WalletSweeper>,
+    /// 最大回撤守卫（回撤超限时暂停开新仓）
+    drawdown: Arc<DrawdownGuard>,
+    /// 钱包余额守卫（余额低于买入储备时暂停开新仓）
+    balance_guard: Arc<BalanceGuard>,
+    /// EV 计算器（买入前估算期望收益）
+    ev_calculator: EvCalculator,
+    /// 按动态策略置信度缩放买入金额
+    confidence_sizing: ConfidenceSizing,
+    /// 交易日志
+    journal: Arc<TradeJournal>,
+    /// 大盘健康度（大盘走弱时暂停开新仓）
+    market_health: Arc<MarketHealth>,
+    /// 启动时发现的未记录 token（等待导入或手动处理）
+    unknown_tokens: ParkingLotRwLock<Vec<UnknownToken>>,
+    /// 已毕业、移出持仓表的持仓（等待手动处理）
+    migrated_positions: ParkingLotRwLock<Vec<MigratedPosition>>,
+    /// 持仓状态机（PendingBuy / Open / PendingSell / Closed / Failed / Orphaned）
+    position_states: Arc<PositionStateStore>,
+    /// RPC 池（控制 API 查询端点状态）
+    rpc_pool: Arc<RpcPool>,
+    /// 共享的异步 RPC 客户端（钱包余额 / 启动恢复读取曲线，不阻塞运行时）
+    rpc_client: Arc<solana_client::nonblocking::rpc_client::RpcClient>,
+    /// processed 事件流确认对账（触发交易被回滚时取消买入）
+    feed_reconciler: Arc<FeedReconciler>,
+    /// WebSocket 事件流（推送开仓 / 平仓成交）
+    event_feed: Arc<EventFeed>,
+    /// 计时时钟（入场时间 / 持仓时长 / Hold 合并 / 交易日志时间戳，回放时跟随录制时间）
+    clock: Arc<Clock>,
 }
 
 impl PositionManager {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: Arc<Config>,
         strategy: Arc<StrategyEngine>,
         tx_builder: Arc<TransactionBuilder>,
         lightspeed_buy: Arc<LightSpeedBuyExecutor>,
         sol_trade_sell: Arc<SolTradeSellExecutor>,
+        dex: Arc<dyn DexAdapter>,
+        filter: Arc<AdvancedEventFilter>,
+        threshold: Arc<ThresholdTrigger>,
+        held_mints: Arc<DashSet<Pubkey>>,
+        price_cache: Arc<PriceCache>,
+        large_trades: Arc<LargeTradeTracker>,
+        confirmations: Arc<ConfirmationTracker>,
+        wallet_sweeper: Arc<WalletSweeper>,
+        journal: Arc<TradeJournal>,
+        market_health: Arc<MarketHealth>,
+        position_states: Arc<PositionStateStore>,
+        rpc_pool: Arc<RpcPool>,
+        feed_reconciler: Arc<FeedReconciler>,
+        event_feed: Arc<EventFeed>,
+        clock: Arc<Clock>,
     ) -> Self {
-        // 创建动能衰减检测器（使用配置文件参数）
-        let momentum_config = MomentumDecayConfig {
-            buy_ratio_threshold: config.momentum_buy_ratio_threshold,
-            net_inflow_threshold: config.momentum_net_inflow_threshold,
-            trade_frequency_threshold: config.momentum_activity_threshold as u32,
-            acceleration_threshold: 1.0,  // 保留固定值，暂无对应配置
-            composite_score_threshold: config.momentum_composite_score_threshold,
-            strict_mode: false,  // 保留固定值，暂无对应配置
-        };
+        // 创建动能衰减检测器（按策略模式读取配置）
+        let momentum_config = MomentumDecayConfig::from_config(&config);
         let momentum_detector = Arc::new(TokioRwLock::new(
             MomentumDecayDetector::new(momentum_config)
         ));
 
         // 创建实时监控器
         let monitor_config = MonitorConfig::from_config(&config);
-        let rpc_client = Arc::new(solana_client::rpc_client::RpcClient::new(
-            config.rpc_endpoint.clone()
-        ));
-        let monitor = Arc::new(TokioRwLock::new(
-            RealTimeMonitor::new(monitor_config, rpc_client)
+        let monitor = Arc::new(RealTimeMonitor::new(
+            monitor_config,
+            sol_trade_sell.rpc_client(),
+            price_cache.clone(),
+            large_trades,
+            confirmations,
+            clock.clone(),
         ));
+        let rpc_client = Arc::new(rpc_pool.nonblocking_client(config.get_balance_commitment()));
+        let drawdown = Arc::new(DrawdownGuard::from_config(&config));
+        let ev_calculator = EvCalculator::from_config(&config);
+        let confidence_sizing = ConfidenceSizing::from_config(&config);
 
         info!("🎯 持仓管理器已初始化（增强版）");
         info!("   ✅ 动能衰减检测器已启用");
@@ -99,36 +218,73 @@ impl PositionManager {
             tx_builder,
             lightspeed_buy,
             sol_trade_sell,
+            dex,
             momentum_detector,
             monitor,
+            filter,
+            threshold,
+            held_mints,
+            price_cache,
+            sells_in_flight: Arc::new(ParkingLotRwLock::new(HashSet::new())),
+            buys_in_flight: Arc::new(ParkingLotRwLock::new(HashSet::new())),
+            last_hold_processed: ParkingLotRwLock::new(HashMap::new()),
+            wallet_sweeper,
+            drawdown,
+            balance_guard: Arc::new(BalanceGuard::default()),
+            ev_calculator,
+            confidence_sizing,
+            journal,
+            market_health,
+            unknown_tokens: ParkingLotRwLock::new(Vec::new()),
+            migrated_positions: ParkingLotRwLock::new(Vec::new()),
+            position_states,
+            rpc_pool,
+            rpc_client,
+            feed_reconciler,
+            event_feed,
+            clock,
         }
     }
 
     /// 启动持仓管理器（增强版）
+    ///
+    /// 🔥 优化: 买入和卖出都在独立任务中执行（发送 + 确认可能等待数十秒），信号循环不被阻塞，
+    /// 一个 mint 的卖出不会拖住其他 mint；同一 mint 的重复买入和持仓上限由 buys_in_flight 名额保证，
+    /// 重复卖出由 sells_in_flight 去重
     pub async fn start(
-        &self,
-        mut signal_rx: mpsc::Receiver<(Arc<WindowMetrics>, StrategySignal)>,
+        self: Arc<Self>,
+        mut signal_rx: SignalReceiver,
     ) {
         info!("🎯 持仓管理器已启动（增强版）");
 
         while let Some((metrics, signal)) = signal_rx.recv().await {
+            // 🔥 优化: Hold 信号按 hold_coalesce_ms 合并，避免重复的动能衰减检测和退出评估
+            // 注意: 策略引擎目前不发出 Hold（evaluate_metrics 只产生 Buy / Sell / None），
+            // 持仓的止盈止损 / 超时由退出循环按 exit_check_interval_ms 评估；
+            // 合并只防止 Hold 信号重新接入后按交易频率重复评估
+            if signal == StrategySignal::Hold && !self.should_process_hold(&metrics.mint) {
+                continue;
+            }
+            // 卖压触发对所有 mint 都会发出卖出信号，只处理持有的
+            if signal == StrategySignal::Sell && !self.positions.read().contains_key(&metrics.mint) {
+                continue;
+            }
+
             // 1. 检查现有持仓的动能衰减
             self.check_momentum_decay(&metrics).await;
 
-            // 2. 实时监控现有持仓
-            self.monitor_positions().await;
-
-            // 3. 处理策略信号
+            // 2. 处理策略信号（持仓风险监控由独立的定时任务执行，见 start_monitor_loop）
             match signal {
                 StrategySignal::Buy => {
-                    if let Err(e) = self.handle_buy_signal(&metrics).await {
-                        error!("❌ 处理买入信号失败: {}", e);
-                    }
+                    let manager = self.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = manager.handle_buy_signal(&metrics).await {
+                            error!("❌ 处理买入信号失败: {}", e);
+                        }
+                    });
                 }
                 StrategySignal::Sell => {
-                    if let Err(e) = self.handle_sell_signal(&metrics).await {
-                        error!("❌ 处理卖出信号失败: {}", e);
-                    }
+                    self.spawn_sell(metrics, "处理卖出信号失败");
                 }
                 StrategySignal::Hold => {
                     self.handle_hold_signal(&metrics).await;
@@ -140,6 +296,598 @@ impl PositionManager {
         }
     }
 
+    /// 启动持仓监控循环
+    ///
+    /// 🔥 修复: 监控独立于信号通道按 monitor_interval_secs 定时执行，
+    /// 无市场事件时持仓仍然被监控
+    pub async fn start_monitor_loop(&self) {
+        info!("📡 持仓监控循环已启动 (间隔: {} 秒)", self.config.monitor_interval_secs);
+
+        let mut interval = tokio::time::interval(
+            tokio::time::Duration::from_secs(self.config.monitor_interval_secs)
+        );
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            if self.positions.read().is_empty() {
+                continue;
+            }
+
+            self.monitor_positions().await;
+        }
+    }
+
+    /// 启动止盈止损评估循环
+    ///
+    /// 🔥 修复: 退出条件不再依赖该 mint 恰好到达 Hold 信号，
+    /// 按 exit_check_interval_ms 用价格缓存中的最新储备评估每个持仓，交易流停止时 TP/SL 仍能触发；
+    /// 卖出在独立任务中执行，一个持仓的卖出确认不会推迟其他持仓的评估
+    pub async fn start_exit_loop(self: Arc<Self>) {
+        info!("🎯 止盈止损评估循环已启动 (间隔: {}ms)", self.config.exit_check_interval_ms);
+
+        let mut interval = tokio::time::interval(
+            tokio::time::Duration::from_millis(self.config.exit_check_interval_ms)
+        );
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let positions = {
+                let positions = self.positions.read();
+                positions.values().cloned().collect::<Vec<_>>()
+            };
+
+            for position in positions {
+                // 用最新的曲线状态刷新持仓储备（无缓存数据时沿用上次的储备）
+                let curve = self.price_cache.get(&position.bonding_curve);
+
+                // 🔥 新增: 账户更新显示曲线已完成，bonding curve 上无法再卖出
+                if curve.as_ref().is_some_and(|curve| curve.complete) {
+                    if !self.sells_in_flight.read().contains(&position.mint) {
+                        self.handle_graduation(&position.mint, "账户更新");
+                    }
+                    continue;
+                }
+
+                if let Some(curve) = &curve {
+                    if let Some(stored) = self.positions.write().get_mut(&position.mint) {
+                        stored.latest_virtual_sol_reserves = curve.virtual_sol_reserves;
+                        stored.latest_virtual_token_reserves = curve.virtual_token_reserves;
+                    }
+                }
+
+                let metrics = self.metrics_from_position(&position, curve);
+                let hold_secs = self.clock.utc_now()
+                    .signed_duration_since(position.entry_time)
+                    .num_seconds()
+                    .max(0) as u64;
+
+                let exit_signal = self.strategy.evaluate_exit_conditions(
+                    &metrics,
+                    self.breakeven_price_sol(&position),
+                    hold_secs,
+                );
+
+                if exit_signal == StrategySignal::Sell {
+                    info!("🟡 退出循环触发卖出: {}", position.mint);
+                    self.spawn_sell(Arc::new(metrics), "退出持仓失败");
+                }
+            }
+        }
+    }
+
+    /// 在独立任务中卖出（同一 mint 已有卖出在执行时由 sells_in_flight 跳过）
+    fn spawn_sell(self: &Arc<Self>, metrics: Arc<WindowMetrics>, failure: &'static str) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = manager.handle_sell_signal(&metrics).await {
+                error!("❌ {}: {}", failure, e);
+            }
+        });
+    }
+
+    /// 紧急清仓：立即以 emergency_sell_slippage_percent 卖出所有持仓
+    ///
+    /// 跳过动能衰减 / 止盈止损评估，所有持仓并发卖出，返回 (持仓数, 已平仓数)
+    pub async fn sell_all(&self, reason: &str) -> (usize, usize) {
+        let positions = {
+            let positions = self.positions.read();
+            positions.values().cloned().collect::<Vec<_>>()
+        };
+
+        if positions.is_empty() {
+            info!("🚨 紧急清仓 ({}): 当前无持仓", reason);
+            return (0, 0);
+        }
+
+        warn!("🚨 紧急清仓 ({}): {} 个持仓, 滑点 {:.1}%",
+            reason, positions.len(), self.config.emergency_sell_slippage_percent);
+
+        let sells = positions.iter().map(|position| async move {
+            let curve = self.price_cache.get(&position.bonding_curve);
+            let metrics = self.metrics_from_position(position, curve);
+            if let Err(e) = self.sell_with_slippage(&metrics, self.config.emergency_sell_slippage_percent, false).await {
+                error!("❌ 紧急卖出失败 {}: {}", position.mint, e);
+            }
+        });
+        futures::future::join_all(sells).await;
+
+        let remaining = {
+            let open = self.positions.read();
+            positions.iter().filter(|position| open.contains_key(&position.mint)).count()
+        };
+        let closed = positions.len() - remaining;
+
+        if remaining > 0 {
+            warn!("🚨 紧急清仓完成: 已平仓 {}/{}，{} 个持仓仍未卖出", closed, positions.len(), remaining);
+        } else {
+            info!("🚨 紧急清仓完成: 已平仓 {} 个持仓", closed);
+        }
+
+        (positions.len(), closed)
+    }
+
+    /// 最大回撤守卫（控制 API 查询 / 重置）
+    pub fn drawdown(&self) -> &Arc<DrawdownGuard> {
+        &self.drawdown
+    }
+
+    /// 启动权益跟踪循环（max_drawdown_percent = 0 时不启用）
+    ///
+    /// 权益 = 钱包 SOL + 持仓按最新曲线储备估算的卖出所得
+    ///
+    /// 有买入进行中时跳过采样：买入的 SOL 已从钱包扣除但持仓尚未写入，
+    /// 此时的权益会少算一笔买入金额，误触发回撤暂停
+    pub async fn start_equity_loop(&self) {
+        if !self.drawdown.enabled() {
+            info!("📉 最大回撤暂停已禁用");
+            return;
+        }
+
+        info!("📉 权益跟踪已启动 (最大回撤 {:.1}%, 间隔 {} 秒)",
+            self.config.max_drawdown_percent, self.config.equity_check_interval_secs);
+
+        let mut interval = tokio::time::interval(
+            tokio::time::Duration::from_secs(self.config.equity_check_interval_secs)
+        );
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            if !self.buys_in_flight.read().is_empty() {
+                debug!("买入进行中，跳过本次权益更新");
+                continue;
+            }
+
+            let wallet_lamports = match self.rpc_client.get_balance(&self.sol_trade_sell.payer.pubkey()).await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    warn!("⚠️  查询钱包余额失败，跳过本次权益更新: {}", e);
+                    continue;
+                }
+            };
+
+            // 查询余额期间开始的买入：余额可能已扣款而持仓未写入
+            if !self.buys_in_flight.read().is_empty() {
+                debug!("买入进行中，跳过本次权益更新");
+                continue;
+            }
+
+            self.drawdown.update(wallet_lamports + self.open_positions_mark_lamports());
+        }
+    }
+
+    /// 钱包余额守卫（控制 API 查询）
+    pub fn balance_guard(&self) -> &Arc<BalanceGuard> {
+        &self.balance_guard
+    }
+
+    pub fn market_health(&self) -> &Arc<MarketHealth> {
+        &self.market_health
+    }
+
+    pub fn position_states(&self) -> &Arc<PositionStateStore> {
+        &self.position_states
+    }
+
+    pub fn rpc_pool(&self) -> &Arc<RpcPool> {
+        &self.rpc_pool
+    }
+
+    /// 实时监控器（控制 API 查询 / 确认风险警报）
+    pub fn monitor(&self) -> &Arc<RealTimeMonitor> {
+        &self.monitor
+    }
+
+    /// 启动钱包余额检查循环（balance_check_interval_secs = 0 时不启用）
+    ///
+    /// 储备 = 默认买入金额 + 单笔 tip + balance_reserve_buffer_sol（手续费 + ATA 租金）
+    pub async fn start_balance_watch_loop(&self) {
+        if self.config.balance_check_interval_secs == 0 {
+            info!("🔋 钱包余额检查已禁用");
+            return;
+        }
+
+        info!("🔋 钱包余额检查已启动 (间隔 {} 秒)", self.config.balance_check_interval_secs);
+
+        let mut interval = tokio::time::interval(
+            tokio::time::Duration::from_secs(self.config.balance_check_interval_secs)
+        );
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let balance = match self.rpc_client.get_balance(&self.sol_trade_sell.payer.pubkey()).await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    warn!("⚠️  查询钱包余额失败: {}", e);
+                    continue;
+                }
+            };
+
+            let reserve = self.config.get_snipe_amount_lamports().max(self.confidence_sizing.max_lamports())
+                + self.lightspeed_buy.estimated_tip_lamports()
+                + (self.config.balance_reserve_buffer_sol * 1_000_000_000.0) as u64;
+
+            self.balance_guard.update(balance, reserve);
+        }
+    }
+
+    /// 启动恢复：扫描钱包中不在持仓记录里的 PumpFun token
+    ///
+    /// recover_unknown_tokens = import 时按当前曲线估值导入为持仓（成本 = 当前估值），
+    /// list 时只记录，通过控制 API 查看 / 导入
+    pub async fn recover_unknown_tokens(&self) -> anyhow::Result<()> {
+        let mode = self.config.recover_unknown_tokens.as_str();
+        if mode == "off" {
+            return Ok(());
+        }
+
+        let held = self.wallet_sweeper.held_tokens()?;
+
+        // 上次运行遗留的 Orphaned 状态：钱包里已没有余额的直接关闭
+        for mint in self.position_states.orphaned() {
+            if !held.iter().any(|account| account.mint == mint) {
+                self.position_states.transition(&mint, PositionStatus::Closed, "启动恢复: 钱包中无余额");
+            }
+        }
+
+        let mut unknown = Vec::new();
+        for account in held {
+            if self.positions.read().contains_key(&account.mint) {
+                continue;
+            }
+
+            // 没有 bonding curve 账户的不是 PumpFun token，跳过
+            let bonding_curve = self.dex.derive_pool(&account.mint);
+            let Some(curve) = self.rpc_client
+                .get_account_data(&bonding_curve)
+                .await
+                .ok()
+                .and_then(|data| bonding_curve_account_decode(&data))
+            else {
+                continue;
+            };
+
+            let estimated_value_lamports = self.tx_builder.estimate_sell_sol_amount(
+                curve.virtual_token_reserves,
+                curve.virtual_sol_reserves,
+                account.amount,
+            );
+
+            unknown.push(UnknownToken {
+                mint: account.mint.to_string(),
+                token_amount: account.amount,
+                estimated_value_lamports,
+                complete: curve.complete,
+                account,
+                curve,
+            });
+        }
+
+        if unknown.is_empty() {
+            info!("🔎 钱包中没有未记录的 PumpFun token");
+            return Ok(());
+        }
+
+        warn!("🔎 发现 {} 个未记录的 PumpFun token:", unknown.len());
+        for token in &unknown {
+            self.position_states.transition(&token.account.mint, PositionStatus::Orphaned, "钱包中未记录的 token");
+            if token.complete {
+                self.position_states.transition(&token.account.mint, PositionStatus::Migrated, "启动恢复: 曲线已完成");
+            }
+            warn!("   {} - {} tokens, 估值 {:.4} SOL{}",
+                token.mint,
+                token.token_amount,
+                token.estimated_value_lamports as f64 / 1_000_000_000.0,
+                if token.complete { " (已毕业，需手动处理)" } else { "" });
+        }
+        *self.unknown_tokens.write() = unknown;
+
+        if mode == "import" {
+            self.import_unknown_tokens();
+        } else {
+            info!("   可通过控制 API 查看 (GET /unknown-tokens) 或导入 (POST /unknown-tokens/import)");
+        }
+
+        Ok(())
+    }
+
+    /// 启动时发现、尚未导入的 token
+    pub fn unknown_tokens(&self) -> Vec<UnknownToken> {
+        self.unknown_tokens.read().clone()
+    }
+
+    /// 已毕业、等待手动处理的持仓
+    pub fn migrated_positions(&self) -> Vec<MigratedPosition> {
+        self.migrated_positions.read().clone()
+    }
+
+    /// 持仓曲线已完成（毕业迁移到 PumpSwap）
+    ///
+    /// bonding curve 上的卖出指令会失败：移出持仓表（止盈止损 / 监控不再重试），
+    /// 状态标记为 Migrated，记录交易日志并告警，由运维在 PumpSwap 上手动卖出（GET /migrated 查看）
+    fn handle_graduation(&self, mint: &Pubkey, source: &str) {
+        let Some(position) = self.positions.write().remove(mint) else {
+            return;
+        };
+        self.held_mints.remove(mint);
+
+        let pumpswap_pool = Self::derive_pumpswap_pool(mint);
+        let cost_lamports = position.total_cost_lamports();
+
+        error!("🎓 持仓已毕业（{}）: {}，bonding curve 上无法卖出", source, mint);
+        error!("   持有 {} tokens，成本 {:.6} SOL，请在 PumpSwap 池 {} 手动卖出",
+            position.token_amount, cost_lamports as f64 / 1_000_000_000.0, pumpswap_pool);
+
+        self.position_states.transition(mint, PositionStatus::Migrated, &format!("曲线完成 ({})", source));
+
+        let detected_at = self.clock.utc_now().to_rfc3339();
+        self.journal.record(&JournalRecord::Migrated {
+            timestamp: detected_at.clone(),
+            mint: mint.to_string(),
+            token_amount: position.token_amount,
+            cost_lamports,
+            pumpswap_pool: pumpswap_pool.to_string(),
+        });
+
+        self.migrated_positions.write().push(MigratedPosition {
+            mint: mint.to_string(),
+            token_amount: position.token_amount,
+            cost_lamports,
+            pumpswap_pool: pumpswap_pool.to_string(),
+            detected_at,
+        });
+    }
+
+    /// 把未记录的 token 导入为持仓（已毕业的跳过），返回导入数量
+    pub fn import_unknown_tokens(&self) -> usize {
+        let tokens = std::mem::take(&mut *self.unknown_tokens.write());
+        let mut remaining = Vec::new();
+        let mut imported = 0;
+
+        for token in tokens {
+            if token.complete {
+                remaining.push(token);
+                continue;
+            }
+            match self.import_unknown_token(&token) {
+                Ok(()) => imported += 1,
+                Err(e) => {
+                    warn!("⚠️  导入 {} 失败: {:?}", token.mint, e);
+                    remaining.push(token);
+                }
+            }
+        }
+
+        info!("📥 已导入 {} 个持仓，剩余 {} 个需手动处理", imported, remaining.len());
+        *self.unknown_tokens.write() = remaining;
+        imported
+    }
+
+    /// 导入单个 token（成本基础按当前估值推断，盈亏从导入时起算）
+    fn import_unknown_token(&self, token: &UnknownToken) -> anyhow::Result<()> {
+        let mint = token.account.mint;
+        let pool = self.dex.derive_pool_accounts(&mint, &token.account.token_program, &token.curve.creator);
+
+        let position = Position {
+            mint,
+            entry_time: self.clock.utc_now(),
+            entry_price_sol: token.estimated_value_lamports as f64 / token.token_amount as f64,
+            token_amount: token.token_amount,
+            sol_invested: token.estimated_value_lamports,
+            tips_paid_lamports: 0,
+            ata_rent_lamports: token.account.lamports,
+            entry_tx_fee_lamports: 0,
+            partial_sol_received: 0,
+            exit_fees_lamports: 0,
+            bonding_curve: pool.pool,
+            creator: token.curve.creator,
+            creator_vault: pool.creator_vault,
+            associated_bonding_curve: pool.pool_token_account,
+            latest_virtual_sol_reserves: token.curve.virtual_sol_reserves,
+            latest_virtual_token_reserves: token.curve.virtual_token_reserves,
+            entry_confidence: None,
+        };
+
+        {
+            let mut positions = self.positions.write();
+            if positions.contains_key(&mint) {
+                return Ok(());
+            }
+            positions.insert(mint, position);
+            self.held_mints.insert(mint);
+        }
+        self.position_states.transition(&mint, PositionStatus::Open, "启动恢复导入");
+
+        self.journal.record(&JournalRecord::Recovered {
+            timestamp: self.clock.utc_now().to_rfc3339(),
+            mint: token.mint.clone(),
+            token_amount: token.token_amount,
+            inferred_cost_lamports: token.estimated_value_lamports,
+        });
+
+        info!("📥 已导入持仓: {} ({} tokens, 推断成本 {:.4} SOL)",
+            token.mint, token.token_amount, token.estimated_value_lamports as f64 / 1_000_000_000.0);
+        Ok(())
+    }
+
+    /// 定期对账：持仓记录的 token 数量与链上 ATA 余额比对（reconcile_interval_secs = 0 时不启用）
+    ///
+    /// 手动卖出 / 转出导致余额为 0 时移除持仓，手动卖出部分或收到空投时按链上余额修正
+    pub async fn start_reconcile_loop(&self) {
+        if self.config.reconcile_interval_secs == 0 {
+            info!("🧾 持仓对账已禁用");
+            return;
+        }
+
+        info!("🧾 持仓对账已启动 (间隔 {} 秒)", self.config.reconcile_interval_secs);
+
+        let mut interval = tokio::time::interval(
+            tokio::time::Duration::from_secs(self.config.reconcile_interval_secs)
+        );
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            self.reconcile_positions().await;
+        }
+    }
+
+    /// 对账一次
+    async fn reconcile_positions(&self) {
+        let positions: Vec<Position> = self.positions.read().values().cloned().collect();
+        let mut mismatches = 0;
+
+        for position in positions {
+            // 正在卖出的持仓余额本来就在变化
+            if self.sells_in_flight.read().contains(&position.mint) {
+                continue;
+            }
+
+            let actual = match self.sol_trade_sell.query_token_balance(&position.mint).await {
+                Ok(actual) => actual,
+                Err(e) => {
+                    warn!("⚠️  对账查询余额失败 {}: {:?}", position.mint, e);
+                    continue;
+                }
+            };
+
+            if actual == position.token_amount {
+                continue;
+            }
+
+            mismatches += 1;
+            let removed = actual == 0;
+            warn!("🧾 持仓对账不一致: {} 记录 {} tokens, 链上 {} tokens", position.mint, position.token_amount, actual);
+
+            {
+                let mut positions = self.positions.write();
+                if removed {
+                    positions.remove(&position.mint);
+                    self.held_mints.remove(&position.mint);
+                    self.position_states.transition(&position.mint, PositionStatus::Closed, "对账: 链上余额为 0");
+                    warn!("   链上余额为 0（手动卖出 / 转出?），移除持仓");
+                } else if let Some(stored) = positions.get_mut(&position.mint) {
+                    stored.token_amount = actual;
+                    warn!("   已按链上余额修正持仓数量");
+                }
+            }
+
+            self.journal.record(&JournalRecord::Reconciled {
+                timestamp: self.clock.utc_now().to_rfc3339(),
+                mint: position.mint.to_string(),
+                recorded_token_amount: position.token_amount,
+                actual_token_amount: actual,
+                removed,
+            });
+        }
+
+        if mismatches > 0 {
+            warn!("🧾 持仓对账完成: {} 个持仓不一致", mismatches);
+        } else {
+            debug!("🧾 持仓对账完成: 全部一致");
+        }
+    }
+
+    /// 所有持仓按最新储备估算的卖出所得（lamports）
+    fn open_positions_mark_lamports(&self) -> u64 {
+        let positions = self.positions.read();
+        positions
+            .values()
+            .map(|position| {
+                self.tx_builder.estimate_sell_sol_amount(
+                    position.latest_virtual_token_reserves,
+                    position.latest_virtual_sol_reserves,
+                    position.token_amount,
+                )
+            })
+            .sum()
+    }
+
+    /// 根据持仓和最新曲线状态构建用于退出评估/卖出的 metrics
+    ///
+    /// 交易流相关字段（净流入、买入比例等）置零，仅储备数据有效；
+    /// event_count 为 0，退出评估据此跳过动能衰减检查
+    fn metrics_from_position(&self, position: &Position, curve: Option<CurveState>) -> WindowMetrics {
+        let (virtual_sol, virtual_token, real_sol, real_token, updated_at) = match curve {
+            Some(curve) => (
+                curve.virtual_sol_reserves,
+                curve.virtual_token_reserves,
+                curve.real_sol_reserves,
+                curve.real_token_reserves,
+                Some(curve.updated_at),
+            ),
+            None => (
+                position.latest_virtual_sol_reserves,
+                position.latest_virtual_token_reserves,
+                0,
+                0,
+                None,
+            ),
+        };
+
+        WindowMetrics {
+            mint: position.mint,
+            creator: position.creator,
+            event_count: 0,
+            total_event_count: 0,
+            dev_initial_buy_sol: None,
+            observed_secs: self.clock.utc_now()
+                .signed_duration_since(position.entry_time)
+                .num_seconds()
+                .max(0) as u64,
+            event_time: None,
+            token_age_secs: None,
+            unique_buyers: 0,
+            repeat_buyer_ratio: 0.0,
+            curve_progress_percent: 0.0,
+            graduation_triggered: false,
+            net_inflow_sol: 0,
+            buy_ratio: 0.0,
+            acceleration: 0.0,
+            window_span_secs: 0.0,
+            inflow_rate_sol_per_sec: 0.0,
+            trade_rate_per_sec: 0.0,
+            normalized_acceleration: 0.0,
+            latest_virtual_sol_reserves: virtual_sol,
+            latest_virtual_token_reserves: virtual_token,
+            latest_real_sol_reserves: real_sol,
+            latest_real_token_reserves: real_token,
+            reserves_updated_at: updated_at,
+            threshold_buy_amount: None,
+            sell_pressure_sol: None,
+            advanced_metrics: None,
+            timeframes: Vec::new(),
+            score_breakdown: None,
+        }
+    }
+
     /// 检查动能衰减
     ///
     /// 对所有持仓进行动能衰减检测，如果检测到衰减则触发卖出
@@ -171,106 +919,246 @@ impl PositionManager {
     /// 监控所有持仓
     ///
     /// 对所有持仓进行实时监控，检测风险警报
+    /// 🔥 优化: 各持仓并发监控（监控器按 mint 分别加锁），紧急卖出不再排在其他持仓的检查之后
     async fn monitor_positions(&self) {
         let positions = {
             let positions = self.positions.read();
             positions.values().cloned().collect::<Vec<_>>()
         };
 
-        for position in positions {
-            // 使用 Tokio RwLock 支持异步
-            let alerts = {
-                let mut monitor = self.monitor.write().await;
-                match monitor.monitor_position(&position).await {
-                    Ok(alerts) => alerts,
-                    Err(e) => {
-                        error!("❌ 监控持仓失败: {}", e);
-                        continue;
-                    }
-                }
-            };
+        let checks = positions.iter().map(|position| self.monitor_single_position(position));
+        futures::future::join_all(checks).await;
 
-            // 处理严重警报
-            for alert in alerts {
-                if alert.severity() >= AlertSeverity::High {
-                    warn!("🚨 高风险警报: {}", alert.description());
-                    warn!("   Token: {}", position.mint);
-
-                    // 对于严重警报，触发紧急卖出
-                    if alert.severity() == AlertSeverity::Critical {
-                        warn!("   触发紧急卖出");
-
-                        // 构建 metrics 用于卖出
-                        let metrics = WindowMetrics {
-                            mint: position.mint,
-                            event_count: 0,
-                            net_inflow_sol: 0,
-                            buy_ratio: 0.0,
-                            acceleration: 0.0,
-                            latest_virtual_sol_reserves: position.latest_virtual_sol_reserves,
-                            latest_virtual_token_reserves: position.latest_virtual_token_reserves,
-                            threshold_buy_amount: None,
-                            advanced_metrics: None,  // ✅ 添加新字段
-                        };
+        // 持久化持仓的价格 / 流动性历史（重启后预热）
+        let held: Vec<Pubkey> = positions.iter().map(|position| position.mint).collect();
+        self.monitor.persist_history(&held).await;
+    }
 
-                        if let Err(e) = self.handle_sell_signal(&metrics).await {
-                            error!("❌ 紧急卖出失败: {}", e);
-                        }
+    /// 监控单个持仓并处理警报
+    async fn monitor_single_position(&self, position: &Position) {
+        let alerts = match self.monitor.monitor_position(position).await {
+            Ok(alerts) => alerts,
+            Err(e) => {
+                error!("❌ 监控持仓失败: {}", e);
+                return;
+            }
+        };
+
+        // 处理严重警报
+        for alert in alerts {
+            // 高置信度 rug pull：回写黑名单
+            if let RiskAlert::RugPullSignal { confidence, indicators } = &alert {
+                self.blacklist_rug_participants(position, *confidence, indicators).await;
+            }
+
+            if alert.severity() >= AlertSeverity::High {
+                warn!("🚨 高风险警报: {}", alert.description());
+                warn!("   Token: {}", position.mint);
+
+                // 对于严重警报，触发紧急卖出
+                if alert.severity() == AlertSeverity::Critical {
+                    warn!("   触发紧急卖出");
+
+                    // 构建 metrics 用于卖出
+                    let metrics = self.metrics_from_position(
+                        position,
+                        self.price_cache.get(&position.bonding_curve),
+                    );
+
+                    if let Err(e) = self.handle_sell_signal(&metrics).await {
+                        error!("❌ 紧急卖出失败: {}", e);
                     }
                 }
             }
         }
     }
 
+    /// 将 rug pull 相关地址加入过滤器黑名单
+    ///
+    /// 拉黑 token 创建者和最近 5 分钟内的大额卖家，并记录事件
+    async fn blacklist_rug_participants(&self, position: &Position, confidence: f64, indicators: &[String]) {
+        if !self.config.enable_rug_auto_blacklist
+            || confidence < self.config.rug_blacklist_confidence_threshold
+        {
+            return;
+        }
+
+        let large_sellers = self.monitor.recent_large_sellers(&position.mint, 5);
+
+        // 开仓时已从 bonding curve 读取 creator（未知时仅拉黑大额卖家）
+        let creator = (position.creator != Pubkey::default()).then_some(position.creator);
+
+        self.filter.record_rug_incident(
+            position.mint,
+            confidence,
+            indicators.to_vec(),
+            creator,
+            &large_sellers,
+        );
+    }
+
     /// 处理买入信号（使用 LightSpeed）
     async fn handle_buy_signal(&self, metrics: &WindowMetrics) -> anyhow::Result<()> {
-        // 检查是否已有持仓
-        {
+        // 回撤超限暂停开新仓
+        if self.drawdown.is_paused() {
+            info!("🛑 回撤暂停中，跳过买入: {}", metrics.mint);
+            return Ok(());
+        }
+
+        // 余额低于买入储备时暂停开新仓
+        if self.balance_guard.is_low() {
+            info!("🪫 余额不足暂停中，跳过买入: {}", metrics.mint);
+            return Ok(());
+        }
+
+        // 曲线已完成（已毕业 / 正在迁移）时不再买入
+        if self.price_cache.get_by_mint(&metrics.mint).is_some_and(|curve| curve.complete) {
+            info!("🎓 bonding curve 已完成，跳过买入: {}", metrics.mint);
+            return Ok(());
+        }
+
+        // 大盘走弱（SOL 价格 / 上线速率 / 毕业率低于阈值）时暂停开新仓
+        if !self.market_health.is_healthy() {
+            info!("📉 大盘走弱暂停中，跳过买入: {}", metrics.mint);
+            return Ok(());
+        }
+
+        // 检查是否已有持仓，并在任何 await 之前占用该 mint 的买入名额
+        // （同一 mint 的两个信号并发到达时，第二个在这里被拒绝，避免重复买入）
+        let reservation = {
             let positions = self.positions.read();
+            let mut buys_in_flight = self.buys_in_flight.write();
             if positions.contains_key(&metrics.mint) {
                 info!("Already have position for {}, skipping", metrics.mint);
                 return Ok(());
             }
+            if buys_in_flight.contains(&metrics.mint) {
+                info!("Buy already in flight for {}, skipping", metrics.mint);
+                return Ok(());
+            }
 
-            // 检查是否达到最大持仓数
-            if positions.len() >= self.config.max_positions {
+            // 检查是否达到最大持仓数（进行中的买入也占用名额）
+            let occupied = positions.len() + buys_in_flight.len();
+            if occupied >= self.config.max_positions {
                 warn!("⚠️  已达到最大持仓数量: {}/{}, 跳过买入",
-                    positions.len(), self.config.max_positions);
+                    occupied, self.config.max_positions);
                 return Ok(());
             }
+
+            buys_in_flight.insert(metrics.mint);
+            BuyReservation {
+                buys_in_flight: self.buys_in_flight.clone(),
+                mint: metrics.mint,
+            }
+        };
+
+        self.position_states.transition(&metrics.mint, PositionStatus::PendingBuy, "买入信号");
+
+        // processed 事件流：触发信号的交易被回滚（分叉丢弃 / 超时未确认）时取消买入
+        let result = if self.feed_reconciler.recently_rolled_back(&metrics.mint) {
+            info!("↩️  触发交易已回滚，取消买入: {}", metrics.mint);
+            Ok(())
+        } else if self.config.feed_reconcile_wait_before_buy
+            && !self.feed_reconciler.wait_for_mint(&metrics.mint).await
+        {
+            info!("↩️  触发交易未确认，取消买入: {}", metrics.mint);
+            Ok(())
+        } else {
+            self.execute_buy(metrics).await
+        };
+
+        // 成功时持仓已写入，失败时释放名额允许后续信号重试
+        drop(reservation);
+
+        if self.positions.read().contains_key(&metrics.mint) {
+            self.position_states.transition(&metrics.mint, PositionStatus::Open, "买入已确认");
+        } else {
+            let note = match &result {
+                Ok(()) => "买入未执行 / 未成交".to_string(),
+                Err(e) => format!("买入失败: {}", e),
+            };
+            self.position_states.transition(&metrics.mint, PositionStatus::Failed, &note);
         }
 
+        result
+    }
+
+    /// 执行买入（调用方已占用 buys_in_flight 名额）
+    async fn execute_buy(&self, metrics: &WindowMetrics) -> anyhow::Result<()> {
         info!("🚀 执行 LightSpeed 买入: {}", metrics.mint);
 
         // 获取买入金额
-        // 优先使用阈值触发的买入金额，否则使用默认配置
+        // 优先使用阈值触发的买入金额，其次单币覆盖规则，再次按动态策略置信度缩放，否则使用默认配置
+        let override_amount = self.strategy
+            .resolve_override(&metrics.mint, &metrics.creator)
+            .and_then(|rule| rule.snipe_amount_sol);
+        let entry_confidence = metrics.score_breakdown.as_ref().map(|score| score.confidence);
+        let confidence_amount = entry_confidence.and_then(|confidence| self.confidence_sizing.size_lamports(confidence));
         let sol_amount = if let Some(threshold_amount) = metrics.threshold_buy_amount {
             info!("💡 使用阈值触发买入金额: {:.4} SOL", threshold_amount);
             (threshold_amount * 1_000_000_000.0) as u64 // SOL -> lamports
+        } else if let Some(amount) = override_amount {
+            info!("💡 使用覆盖规则买入金额: {:.4} SOL", amount);
+            (amount * 1_000_000_000.0) as u64
+        } else if let Some(amount) = confidence_amount {
+            info!("💡 按置信度 {:.2}% 缩放买入金额: {:.4} SOL",
+                entry_confidence.unwrap_or_default() * 100.0,
+                amount as f64 / 1_000_000_000.0);
+            amount
         } else {
             self.config.get_snipe_amount_lamports()
         };
 
-        // 计算 bonding_curve 和 associated_bonding_curve（PDA）
-        let bonding_curve = self.derive_bonding_curve(&metrics.mint)?;
-        let associated_bonding_curve = self.derive_associated_bonding_curve(&bonding_curve, &metrics.mint)?;
+        // 历史表现差的时段缩小仓位
+        let size_multiplier = self.strategy.position_size_multiplier();
+        let sol_amount = if size_multiplier < 1.0 {
+            let scaled = (sol_amount as f64 * size_multiplier) as u64;
+            info!("🕐 差时段缩小仓位: {:.4} SOL -> {:.4} SOL",
+                sol_amount as f64 / 1_000_000_000.0,
+                scaled as f64 / 1_000_000_000.0);
+            scaled
+        } else {
+            sol_amount
+        };
+
+        // 期望收益估算：EV 为负时（启用门控）放弃买入；无综合评分时只记录不门控
+        let ev = self.estimate_ev(metrics, sol_amount);
+        info!("📐 EV: {:+.4} SOL (胜率 {:.1}%, 滑点 {:.2}%/{:.2}%, 成本 {:.4} SOL)",
+            ev.expected_value_sol(),
+            ev.win_probability * 100.0,
+            ev.entry_slippage_percent,
+            ev.exit_slippage_percent,
+            ev.cost_lamports / 1_000_000_000.0);
+        if self.config.enable_ev_gate && ev.should_reject() {
+            info!("📐 EV 为负，跳过买入: {}", metrics.mint);
+            self.journal.record(&JournalRecord::EvRejected {
+                timestamp: self.clock.utc_now().to_rfc3339(),
+                mint: metrics.mint.to_string(),
+                sol_amount_lamports: sol_amount,
+                ev,
+            });
+            return Ok(());
+        }
+
+        // 计算 bonding_curve（PDA）；associated_bonding_curve 和 creator 由执行器批量读取后返回
+        let bonding_curve = self.dex.derive_pool(&metrics.mint);
 
         // 使用 LightSpeed 买入执行器
         // 🔥 修复: 移除 virtual_token_reserves/virtual_sol_reserves 参数（改为内部读取）
         match self.lightspeed_buy.execute_buy(
             &metrics.mint,
             &bonding_curve,
-            &associated_bonding_curve,
             sol_amount,
+            metrics.reserve_snapshot(),
+            // 蜜罐检测：首波狙击延迟优先，不做模拟
+            self.config.honeypot_simulation_enabled && !self.strategy.is_first_wave(metrics),
         ).await {
-            Ok(signature) => {
+            Ok(receipt) => {
+                let signature = receipt.signature;
                 info!("✅ LightSpeed 买入交易已发送: {}", signature);
 
                 // 🔥 修复: 使用 monitor 轮询交易确认（30秒超时，狙击需要更长时间）
-                let confirmation_result = {
-                    let monitor = self.monitor.read().await;
-                    monitor.poll_transaction_confirmation(signature, 30).await
-                };
+                let confirmation_result = self.monitor.poll_transaction_confirmation(signature, 30).await;
 
                 match confirmation_result {
                     Ok(_) => {
@@ -284,17 +1172,31 @@ impl PositionManager {
                             }
                             Err(e) => {
                                 warn!("⚠️  查询实际余额失败: {}, 使用估算值", e);
-                                // Fallback: 使用估算值
-                                let estimated = self.tx_builder.estimate_buy_token_amount(
-                                    metrics.latest_virtual_token_reserves,
-                                    metrics.latest_virtual_sol_reserves,
-                                    sol_amount,
+                                // Fallback: 使用估算值（Token-2022 转账手续费在转账时扣除）
+                                let estimated = receipt.mint_extensions.amount_after_transfer_fee(
+                                    self.tx_builder.estimate_buy_token_amount(
+                                        metrics.latest_virtual_token_reserves,
+                                        metrics.latest_virtual_sol_reserves,
+                                        sol_amount,
+                                    ),
                                 );
                                 info!("   估算获得 Token 数量: {}", estimated);
                                 estimated
                             }
                         };
 
+                        // 记录创建 ATA 锁定的租金（卖出关闭账户时回收）
+                        let ata_rent_lamports = match self.sol_trade_sell.get_token_account_rent(&metrics.mint).await {
+                            Ok(rent) => {
+                                self.wallet_sweeper.rent_ledger().record_spent(rent);
+                                rent
+                            }
+                            Err(e) => {
+                                warn!("⚠️  查询 ATA 租金失败: {}", e);
+                                0
+                            }
+                        };
+
                         // 计算入场价格
                         let entry_price_sol = if actual_token_amount > 0 {
                             sol_amount as f64 / actual_token_amount as f64
@@ -303,50 +1205,167 @@ impl PositionManager {
                         };
 
                         // 🔥 修复: 只有确认成功才记录持仓
-                        // 🔥 修复: 先读取 creator，再派生 creator_vault
-                        let creator = self.get_creator_from_bonding_curve(&bonding_curve)?;
-                        let creator_vault = Self::derive_creator_vault(&creator)?;
+                        // 🔥 优化: creator 和池子账户使用执行器买入前读取 / 派生的值
+                        let creator = receipt.creator;
 
                         let position = Position {
                             mint: metrics.mint,
-                            entry_time: Utc::now(),
+                            entry_time: self.clock.utc_now(),
                             entry_price_sol,
                             token_amount: actual_token_amount,  // 🔥 使用实际余额
                             sol_invested: sol_amount,
+                            tips_paid_lamports: receipt.tips_lamports,
+                            ata_rent_lamports,
+                            entry_tx_fee_lamports: self.config.estimated_tx_fee_lamports(),
+                            partial_sol_received: 0,
+                            exit_fees_lamports: 0,
                             bonding_curve,
-                            creator_vault,
-                            associated_bonding_curve,
+                            creator,
+                            creator_vault: receipt.pool.creator_vault,
+                            associated_bonding_curve: receipt.pool.pool_token_account,
                             latest_virtual_sol_reserves: metrics.latest_virtual_sol_reserves,
                             latest_virtual_token_reserves: metrics.latest_virtual_token_reserves,
+                            entry_confidence,
                         };
 
+                        let breakeven_price_sol = self.breakeven_price_sol(&position);
+                        let entry = JournalRecord::Entry {
+                            timestamp: position.entry_time.to_rfc3339(),
+                            mint: metrics.mint.to_string(),
+                            signature: signature.to_string(),
+                            sol_amount_lamports: sol_amount,
+                            token_amount: actual_token_amount,
+                            entry_price_sol,
+                            breakeven_price_sol,
+                            tips_lamports: receipt.tips_lamports,
+                            ev: Some(ev),
+                            score_breakdown: metrics.score_breakdown.clone(),
+                        };
+                        self.journal.record(&entry);
+                        self.event_feed.publish_fill(&entry);
                         self.positions.write().insert(metrics.mint, position);
+                        self.held_mints.insert(metrics.mint);
 
                         info!(
-                            "📊 持仓已开仓: {} tokens @ {:.8} SOL/token",
-                            actual_token_amount, entry_price_sol
+                            "📊 持仓已开仓: {} tokens @ {:.8} SOL/token (保本价 {:.8})",
+                            actual_token_amount, entry_price_sol, breakeven_price_sol
                         );
                     }
+                    Err(e @ (BotError::SlippageExceeded | BotError::CurveComplete)) => {
+                        // 链上正常拒绝（价格跑了 / 已毕业），放弃本次买入
+                        warn!("⚠️  买入未成交: {} ({})", e, signature);
+                        return Ok(());
+                    }
                     Err(e) => {
                         // 🔥 修复: 交易确认失败，不记录持仓
                         error!("❌ 买入交易确认失败: {}", e);
                         error!("   签名: {}", signature);
                         error!("   不记录持仓，避免状态不一致");
-                        return Err(anyhow::anyhow!("买入交易确认失败: {}", e));
+                        return Err(e.into());
                     }
                 }
             }
+            Err(BotError::InsufficientBalance { required, available }) => {
+                warn!(
+                    "⚠️  余额不足，跳过买入 {}: 需要 {:.4} SOL, 可用 {:.4} SOL",
+                    metrics.mint,
+                    required as f64 / 1_000_000_000.0,
+                    available as f64 / 1_000_000_000.0
+                );
+                return Ok(());
+            }
+            Err(BotError::CurveComplete) => {
+                info!("ℹ️  {} 曲线已完成，跳过买入", metrics.mint);
+                return Ok(());
+            }
+            Err(BotError::FixedCostsTooHigh { fixed_lamports, position_lamports }) => {
+                warn!(
+                    "⚠️  固定成本过高，跳过买入 {}: {:.6} SOL 成本 / {:.6} SOL 仓位（检查 tip 与买入金额配置）",
+                    metrics.mint,
+                    fixed_lamports as f64 / 1_000_000_000.0,
+                    position_lamports as f64 / 1_000_000_000.0
+                );
+                return Ok(());
+            }
+            Err(BotError::SpendLimitExceeded { reason }) => {
+                // 守卫已告警；策略不受影响，额度恢复后继续买入
+                warn!("🛑 {} 超出支出限额，跳过买入: {}", metrics.mint, reason);
+                return Ok(());
+            }
+            Err(BotError::DangerousToken { reason, creator }) => {
+                warn!("☠️  {} 不安全，跳过买入: {}", metrics.mint, reason);
+                // 🔥 新增: 拉黑 mint 和创建者，后续事件直接过滤
+                if self.config.auto_blacklist_dangerous_tokens {
+                    self.filter.add_to_blacklist(metrics.mint);
+                    if creator != Pubkey::default() {
+                        self.filter.add_to_blacklist(creator);
+                    }
+                }
+                return Ok(());
+            }
             Err(e) => {
                 error!("❌ LightSpeed 买入发送失败: {}", e);
-                return Err(e);
+                return Err(e.into());
             }
         }
 
         Ok(())
     }
 
-    /// 处理卖出信号（使用 SolTrade）
+    /// 处理卖出信号
+    ///
+    /// 同一 mint 同时只允许一笔卖出在执行
     async fn handle_sell_signal(&self, metrics: &WindowMetrics) -> anyhow::Result<()> {
+        // 🔥 新增: 卖压触发视为 rug 应对，按紧急滑点一次性卖出（不拆分）
+        if let Some(sold_sol) = metrics.sell_pressure_sol {
+            warn!("🩸 卖压触发紧急卖出: {} (窗口内累计卖出 {:.4} SOL)", metrics.mint, sold_sol);
+            return self.sell_with_slippage(metrics, self.config.emergency_sell_slippage_percent, false).await;
+        }
+        self.sell_with_slippage(metrics, self.config.slippage_percent, true).await
+    }
+
+    /// 按指定滑点卖出持仓（同一 mint 同时只允许一笔卖出在执行）
+    ///
+    /// split_exits: 是否按 max_sell_price_impact_percent 拆分为多笔卖出（紧急清仓不拆分）
+    async fn sell_with_slippage(&self, metrics: &WindowMetrics, slippage_percent: f64, split_exits: bool) -> anyhow::Result<()> {
+        // 🔥 新增: 曲线已完成时卖出指令必然失败，转为毕业处理
+        if self.price_cache.get_by_mint(&metrics.mint).is_some_and(|curve| curve.complete) {
+            self.handle_graduation(&metrics.mint, "卖出前检查");
+            return Ok(());
+        }
+
+        if !self.sells_in_flight.write().insert(metrics.mint) {
+            info!("Sell already in flight for {}, skipping", metrics.mint);
+            return Ok(());
+        }
+
+        let has_position = self.positions.read().contains_key(&metrics.mint);
+        if has_position {
+            self.position_states.transition(&metrics.mint, PositionStatus::PendingSell, "卖出开始");
+        }
+
+        let result = self.execute_position_sell(metrics, slippage_percent, split_exits).await;
+
+        self.sells_in_flight.write().remove(&metrics.mint);
+
+        // 持仓已移除 = 已平仓；仍在 = 卖出失败或部分卖出，回到 Open 等待下次重试
+        if has_position {
+            if self.positions.read().contains_key(&metrics.mint) {
+                let note = match &result {
+                    Ok(()) => "部分卖出".to_string(),
+                    Err(e) => format!("卖出失败: {}", e),
+                };
+                self.position_states.transition(&metrics.mint, PositionStatus::Open, &note);
+            } else {
+                self.position_states.transition(&metrics.mint, PositionStatus::Closed, "卖出完成");
+            }
+        }
+
+        result
+    }
+
+    /// 执行持仓卖出（使用 SolTrade）
+    async fn execute_position_sell(&self, metrics: &WindowMetrics, slippage_percent: f64, split_exits: bool) -> anyhow::Result<()> {
         // 获取持仓
         let position = {
             let positions = self.positions.read();
@@ -376,45 +1395,79 @@ impl PositionManager {
                     error!("❌ 余额为 0，无法卖出");
                     // 仍然移除持仓记录（避免重复尝试）
                     self.positions.write().remove(&metrics.mint);
+                    self.held_mints.remove(&metrics.mint);
+                    self.sweep_wallet();
                     return Ok(());
                 }
 
+                // 🔥 新增: 大额持仓拆分为多笔卖出（每笔价格冲击不超过 max_sell_price_impact_percent），
+                //    前几笔保留 token 账户，最后一笔走下面的完整平仓流程
+                let (virtual_token_reserves, virtual_sol_reserves) = self.latest_reserves(&position, metrics);
+                let chunks = if split_exits {
+                    self.tx_builder.split_sell_amount(
+                        virtual_token_reserves,
+                        virtual_sol_reserves,
+                        sell_amount,
+                        self.config.max_sell_price_impact_percent,
+                        self.config.max_sell_chunks,
+                    )
+                } else {
+                    vec![sell_amount]
+                };
+
+                let (sell_amount, position) = if let Some((&last_chunk, partial_chunks)) = chunks.split_last() {
+                    if !partial_chunks.is_empty() {
+                        info!("✂️  拆分卖出: {} 笔 {:?}", chunks.len(), chunks);
+                    }
+                    for (index, &chunk) in partial_chunks.iter().enumerate() {
+                        info!("✂️  分笔卖出 {}/{}: {} tokens", index + 1, chunks.len(), chunk);
+                        self.execute_partial_sell(&position, metrics, chunk, slippage_percent).await?;
+                    }
+                    // 重新读取持仓（分笔卖出已更新剩余数量和已成交所得）
+                    let position = self.positions.read().get(&metrics.mint).cloned().unwrap_or(position);
+                    (last_chunk, position)
+                } else {
+                    (sell_amount, position)
+                };
+
                 // 构建 SellParams（使用实际余额）
                 let sell_params = SellParams {
                     mint: metrics.mint,
                     input_token_amount: sell_amount,
-                    slippage_basis_points: Some((self.config.slippage_percent * 100.0) as u64),
+                    slippage_basis_points: Some((slippage_percent * 100.0) as u64),
                     wait_transaction_confirmed: true,
                     close_token_account: true,
-                    pumpfun_params: PumpFunSellParams {
-                        bonding_curve: position.bonding_curve,
-                        associated_bonding_curve: position.associated_bonding_curve,
-                        creator_vault: position.creator_vault,
-                    },
+                    pool: position.pool_accounts(),
                 };
 
                 // 使用 SolTrade 卖出执行器
                 match self.sol_trade_sell.execute_sell(sell_params).await {
-                    Ok(signature) => {
+                    Ok(receipt) => {
+                        let signature = receipt.signature;
                         info!("✅ SolTrade 卖出成功: {}", signature);
 
                         // 使用 monitor 轮询交易确认（10秒超时）
-                        {
-                            let monitor = self.monitor.read().await;
-                            match monitor.poll_transaction_confirmation(signature, 10).await {
-                                Ok(_) => {
-                                    info!("✅ 卖出交易已确认");
-                                }
-                                Err(e) => {
-                                    warn!("⚠️  卖出交易确认失败: {}, 继续结算", e);
-                                }
+                        let confirmed = match self.monitor.poll_transaction_confirmation(signature, 10).await {
+                            Ok(_) => {
+                                info!("✅ 卖出交易已确认");
+                                true
                             }
-                        }
+                            Err(e @ BotError::ConfirmationTimeout { .. }) => {
+                                warn!("⚠️  卖出交易确认失败: {}, 继续结算", e);
+                                false
+                            }
+                            Err(e) => {
+                                // 链上执行失败：保留持仓，等待下一次卖出
+                                error!("❌ 卖出交易执行失败: {}，保留持仓", e);
+                                return Err(e.into());
+                            }
+                        };
 
-                        // 估算获得的 SOL（从 metrics 计算）
-                        let sol_received = self.tx_builder.estimate_sell_sol_amount(
-                            metrics.latest_virtual_token_reserves,
-                            metrics.latest_virtual_sol_reserves,
+                        // 估算获得的 SOL（最新储备，含分笔卖出已成交部分）
+                        let (virtual_token_reserves, virtual_sol_reserves) = self.latest_reserves(&position, metrics);
+                        let sol_received = position.partial_sol_received + self.tx_builder.estimate_sell_sol_amount(
+                            virtual_token_reserves,
+                            virtual_sol_reserves,
                             sell_amount,
                         );
 
@@ -430,13 +1483,28 @@ impl PositionManager {
                             sol_received as f64 / 1_000_000_000.0,
                             profit_loss_percent
                         );
+                        let mut position = position;
+                        position.exit_fees_lamports += receipt.fee_lamports;
+                        self.lightspeed_buy.tip_ledger().record_sell(receipt.fee_lamports);
+                        self.log_tip_adjusted_pnl(&position, profit_loss_sol);
+                        self.journal_exit(&position, &signature, sol_received, profit_loss_sol);
+                        if confirmed {
+                            self.record_rent_reclaimed(&position);
+                        }
+                        self.feed_trade_outcome(&position, profit_loss_sol);
+                        self.threshold.rearm(&position.mint);
 
                         // 移除持仓
                         self.positions.write().remove(&metrics.mint);
+                        self.held_mints.remove(&metrics.mint);
+                        self.sweep_wallet();
                     }
                     Err(e) => {
                         error!("❌ SolTrade 卖出失败: {}", e);
-                        return Err(e);
+                        if matches!(e, BotError::SlippageExceeded) {
+                            warn!("   滑点超限，保留持仓等待下一次卖出");
+                        }
+                        return Err(e.into());
                     }
                 }
             }
@@ -448,35 +1516,36 @@ impl PositionManager {
                 let sell_params = SellParams {
                     mint: metrics.mint,
                     input_token_amount: position.token_amount,
-                    slippage_basis_points: Some((self.config.slippage_percent * 100.0) as u64),
+                    slippage_basis_points: Some((slippage_percent * 100.0) as u64),
                     wait_transaction_confirmed: true,
                     close_token_account: true,
-                    pumpfun_params: PumpFunSellParams {
-                        bonding_curve: position.bonding_curve,
-                        associated_bonding_curve: position.associated_bonding_curve,
-                        creator_vault: position.creator_vault,
-                    },
+                    pool: position.pool_accounts(),
                 };
 
                 // 使用 SolTrade 卖出执行器
                 match self.sol_trade_sell.execute_sell(sell_params).await {
-                    Ok(signature) => {
+                    Ok(receipt) => {
+                        let signature = receipt.signature;
                         info!("✅ SolTrade 卖出成功: {}", signature);
 
                         // 使用 monitor 轮询交易确认（10秒超时）
-                        {
-                            let monitor = self.monitor.read().await;
-                            match monitor.poll_transaction_confirmation(signature, 10).await {
-                                Ok(_) => {
-                                    info!("✅ 卖出交易已确认");
-                                }
-                                Err(e) => {
-                                    warn!("⚠️  卖出交易确认失败: {}, 继续结算", e);
-                                }
+                        let confirmed = match self.monitor.poll_transaction_confirmation(signature, 10).await {
+                            Ok(_) => {
+                                info!("✅ 卖出交易已确认");
+                                true
                             }
-                        }
+                            Err(e @ BotError::ConfirmationTimeout { .. }) => {
+                                warn!("⚠️  卖出交易确认失败: {}, 继续结算", e);
+                                false
+                            }
+                            Err(e) => {
+                                // 链上执行失败：保留持仓，等待下一次卖出
+                                error!("❌ 卖出交易执行失败: {}，保留持仓", e);
+                                return Err(e.into());
+                            }
+                        };
 
-                        let sol_received = self.tx_builder.estimate_sell_sol_amount(
+                        let sol_received = position.partial_sol_received + self.tx_builder.estimate_sell_sol_amount(
                             metrics.latest_virtual_token_reserves,
                             metrics.latest_virtual_sol_reserves,
                             position.token_amount,
@@ -489,11 +1558,26 @@ impl PositionManager {
                             sol_received as f64 / 1_000_000_000.0,
                             profit_loss_percent
                         );
+                        let mut position = position;
+                        position.exit_fees_lamports += receipt.fee_lamports;
+                        self.lightspeed_buy.tip_ledger().record_sell(receipt.fee_lamports);
+                        self.log_tip_adjusted_pnl(&position, profit_loss_sol);
+                        self.journal_exit(&position, &signature, sol_received, profit_loss_sol);
+                        if confirmed {
+                            self.record_rent_reclaimed(&position);
+                        }
+                        self.feed_trade_outcome(&position, profit_loss_sol);
+                        self.threshold.rearm(&position.mint);
                         self.positions.write().remove(&metrics.mint);
+                        self.held_mints.remove(&metrics.mint);
+                        self.sweep_wallet();
                     }
                     Err(e) => {
                         error!("❌ SolTrade 卖出失败: {}", e);
-                        return Err(e);
+                        if matches!(e, BotError::SlippageExceeded) {
+                            warn!("   滑点超限，保留持仓等待下一次卖出");
+                        }
+                        return Err(e.into());
                     }
                 }
             }
@@ -502,7 +1586,192 @@ impl PositionManager {
         Ok(())
     }
 
-    /// 处理持有信号
+    /// 分笔卖出中的一笔（保留 token 账户），确认后扣减持仓数量并记录估算所得
+    async fn execute_partial_sell(
+        &self,
+        position: &Position,
+        metrics: &WindowMetrics,
+        token_amount: u64,
+        slippage_percent: f64,
+    ) -> anyhow::Result<()> {
+        let sell_params = SellParams {
+            mint: metrics.mint,
+            input_token_amount: token_amount,
+            slippage_basis_points: Some((slippage_percent * 100.0) as u64),
+            wait_transaction_confirmed: true,
+            close_token_account: false,
+            pool: position.pool_accounts(),
+        };
+
+        // 卖出前的储备用于估算本笔所得
+        let (virtual_token_reserves, virtual_sol_reserves) = self.latest_reserves(position, metrics);
+
+        let receipt = match self.sol_trade_sell.execute_sell(sell_params).await {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                error!("❌ 分笔卖出失败: {}，剩余持仓保留", e);
+                return Err(e.into());
+            }
+        };
+
+        let signature = receipt.signature;
+        match self.monitor.poll_transaction_confirmation(signature, 10).await {
+            Ok(_) | Err(BotError::ConfirmationTimeout { .. }) => {}
+            Err(e) => {
+                error!("❌ 分笔卖出执行失败: {}，剩余持仓保留", e);
+                return Err(e.into());
+            }
+        }
+
+        let sol_received = self.tx_builder.estimate_sell_sol_amount(
+            virtual_token_reserves,
+            virtual_sol_reserves,
+            token_amount,
+        );
+        info!("✅ 分笔卖出完成: {} tokens -> 约 {:.4} SOL ({})",
+            token_amount, sol_received as f64 / 1_000_000_000.0, signature);
+
+        if let Some(stored) = self.positions.write().get_mut(&metrics.mint) {
+            stored.token_amount = stored.token_amount.saturating_sub(token_amount);
+            stored.partial_sol_received += sol_received;
+            stored.exit_fees_lamports += receipt.fee_lamports;
+        }
+        self.lightspeed_buy.tip_ledger().record_sell(receipt.fee_lamports);
+
+        Ok(())
+    }
+
+    /// 最新储备（优先价格缓存，其次 metrics）
+    fn latest_reserves(&self, position: &Position, metrics: &WindowMetrics) -> (u64, u64) {
+        match self.price_cache.get(&position.bonding_curve) {
+            Some(curve) => (curve.virtual_token_reserves, curve.virtual_sol_reserves),
+            None => (metrics.latest_virtual_token_reserves, metrics.latest_virtual_sol_reserves),
+        }
+    }
+
+    /// 估算本次买入的期望收益（胜率来自综合评分，收益/亏损来自止盈止损倍数）
+    fn estimate_ev(&self, metrics: &WindowMetrics, sol_amount: u64) -> EvEstimate {
+        let triggers = self.strategy.resolve_sell_triggers(metrics);
+        let tips_lamports = self.lightspeed_buy.estimated_tip_lamports();
+
+        self.ev_calculator.estimate(&EvInputs {
+            composite_score: self.strategy.composite_score(metrics),
+            sol_amount,
+            virtual_sol_reserves: metrics.latest_virtual_sol_reserves,
+            virtual_token_reserves: metrics.latest_virtual_token_reserves,
+            take_profit_multiplier: triggers.take_profit_multiplier,
+            stop_loss_multiplier: triggers.stop_loss_multiplier,
+            tips_lamports,
+        })
+    }
+
+    /// 记录平仓日志（同时推送到事件流）
+    fn journal_exit(&self, position: &Position, signature: &solana_sdk::signature::Signature, sol_received: u64, profit_loss_sol: i64) {
+        let exit = JournalRecord::Exit {
+            timestamp: self.clock.utc_now().to_rfc3339(),
+            mint: position.mint.to_string(),
+            signature: signature.to_string(),
+            sol_received_lamports: sol_received,
+            profit_loss_lamports: profit_loss_sol,
+            net_profit_loss_lamports: position.net_profit_loss_lamports(profit_loss_sol),
+            hold_secs: self.clock.utc_now()
+                .signed_duration_since(position.entry_time)
+                .num_seconds()
+                .max(0) as u64,
+            entry_confidence: position.entry_confidence,
+        };
+        self.journal.record(&exit);
+        self.event_feed.publish_fill(&exit);
+    }
+
+    /// 把扣除 tip 后的净盈亏回馈给策略引擎（成功率反馈）
+    fn feed_trade_outcome(&self, position: &Position, profit_loss_sol: i64) {
+        let net_profit_loss_sol = position.net_profit_loss_lamports(profit_loss_sol);
+        let cost_basis = (position.sol_invested + position.tips_paid_lamports).max(1);
+        self.strategy.record_trade_outcome(
+            net_profit_loss_sol as f64 / cost_basis as f64 * 100.0,
+            position.entry_time,
+        );
+    }
+
+    /// 持仓保本价格（含 PumpFun 买卖手续费、tip、买卖交易费）
+    fn breakeven_price_sol(&self, position: &Position) -> f64 {
+        position.breakeven_price_sol(self.config.pumpfun_fee_bps, self.config.estimated_tx_fee_lamports())
+    }
+
+    /// 后台清理钱包（卖出后残留的 WSOL / 空 token 账户）
+    fn sweep_wallet(&self) {
+        if !self.config.wallet_sweep_enabled {
+            return;
+        }
+
+        let sweeper = self.wallet_sweeper.clone();
+        tokio::spawn(async move {
+            if let Err(e) = sweeper.sweep().await {
+                warn!("⚠️  卖出后钱包清理失败: {:?}", e);
+            }
+        });
+    }
+
+    /// 卖出已关闭 ATA，记录回收的租金
+    ///
+    /// 只在卖出确认后调用：确认超时的卖出不一定关闭了账户，残留的空账户由钱包清理回收并记账（避免重复计入）
+    fn record_rent_reclaimed(&self, position: &Position) {
+        if position.ata_rent_lamports > 0 {
+            self.wallet_sweeper.rent_ledger().record_reclaimed(position.ata_rent_lamports, 1);
+            info!("   ATA 租金已回收: {:.6} SOL", position.ata_rent_lamports as f64 / 1_000_000_000.0);
+        }
+    }
+
+    /// 打印扣除 tip 后的净盈亏和累计 tip 支出
+    fn log_tip_adjusted_pnl(&self, position: &Position, profit_loss_sol: i64) {
+        let net_profit_loss_sol = position.net_profit_loss_lamports(profit_loss_sol);
+        let cost_basis = position.sol_invested + position.tips_paid_lamports;
+        let net_profit_loss_percent = (net_profit_loss_sol as f64 / cost_basis as f64) * 100.0;
+
+        info!(
+            "   扣除 tip 后净盈亏: {:+.4} SOL ({:+.2}%), 本笔 tip: {:.6} SOL, 卖出交易费: {:.6} SOL",
+            net_profit_loss_sol as f64 / 1_000_000_000.0,
+            net_profit_loss_percent,
+            position.tips_paid_lamports as f64 / 1_000_000_000.0,
+            position.exit_fees_lamports as f64 / 1_000_000_000.0
+        );
+
+        let ledger = self.lightspeed_buy.tip_ledger();
+        info!(
+            "   累计 tip 支出: {:.6} SOL ({} 笔交易)",
+            ledger.total_lamports() as f64 / 1_000_000_000.0,
+            ledger.tx_count()
+        );
+    }
+
+    /// Hold 信号是否需要处理（未持仓直接跳过；同一持仓 hold_coalesce_ms 内只处理一次）
+    fn should_process_hold(&self, mint: &Pubkey) -> bool {
+        if !self.positions.read().contains_key(mint) {
+            return false;
+        }
+
+        let interval = std::time::Duration::from_millis(self.config.hold_coalesce_ms);
+        if interval.is_zero() {
+            return true;
+        }
+
+        let now = self.clock.now();
+        let mut last_processed = self.last_hold_processed.write();
+        if let Some(last) = last_processed.get(mint) {
+            if now.duration_since(*last) < interval {
+                return false;
+            }
+        } else {
+            // 新持仓加入前顺带清理已平仓的记录
+            let positions = self.positions.read();
+            last_processed.retain(|held, _| positions.contains_key(held));
+        }
+        last_processed.insert(*mint, now);
+        true
+    }
+
+    /// 处理持有信号（按触发信号的窗口指标评估退出条件，含动能衰减）
     async fn handle_hold_signal(&self, metrics: &WindowMetrics) {
         // 检查是否有该 token 的持仓
         let position_opt = {
@@ -512,13 +1781,13 @@ impl PositionManager {
 
         if let Some(position) = position_opt {
             // 计算持仓时间
-            let hold_duration = Utc::now().signed_duration_since(position.entry_time);
+            let hold_duration = self.clock.utc_now().signed_duration_since(position.entry_time);
             let hold_secs = hold_duration.num_seconds() as u64;
 
             // 使用策略引擎评估退出条件
             let exit_signal = self.strategy.evaluate_exit_conditions(
                 metrics,
-                position.entry_price_sol,
+                self.breakeven_price_sol(&position),
                 hold_secs,
             );
 
@@ -531,88 +1800,17 @@ impl PositionManager {
         }
     }
 
-    /// 派生 bonding curve PDA
-    /// 🔥 优化: 使用缓存的 program_id
-    fn derive_bonding_curve(&self, mint: &Pubkey) -> anyhow::Result<Pubkey> {
-        let seeds = &[b"bonding-curve", mint.as_ref()];
-        let (pda, _bump) = Pubkey::find_program_address(seeds, &PUMPFUN_PROGRAM_ID);
-        Ok(pda)
-    }
-
-    /// 🔥 修复: 检测 mint 的 token program（支持 Token-2022）
-    ///
-    /// 📝 设计说明：此方法创建临时 RpcClient 是有意为之：
-    ///    1. RpcClient::new() 开销极小（仅创建结构体，连接池是全局的）
-    ///    2. 调用频率低（每次买入/卖出各 1-2 次）
-    ///    3. 避免在 PositionManager 中添加 rpc_client 字段增加耦合
-    ///    4. 性能影响 < 1ms，对整体延迟可忽略
-    fn detect_token_program(&self, mint: &Pubkey) -> anyhow::Result<Pubkey> {
-        use solana_client::rpc_client::RpcClient;
-
-        let rpc_client = RpcClient::new(self.config.rpc_endpoint.clone());
-        let account = rpc_client.get_account(mint)
-            .map_err(|e| anyhow::anyhow!("读取 mint 账户失败: {}", e))?;
-
-        let token_program = account.owner;
-
-        if token_program == *TOKEN_2022_PROGRAM_ID {
-            Ok(*TOKEN_2022_PROGRAM_ID)
-        } else if token_program == *TOKEN_PROGRAM_ID {
-            Ok(*TOKEN_PROGRAM_ID)
-        } else {
-            warn!("⚠️  未知 token program: {}, fallback to Token v3", token_program);
-            Ok(*TOKEN_PROGRAM_ID)
-        }
-    }
-
-    /// 🔥 修复: 获取支持 Token-2022 的 ATA 地址
-    fn get_ata_with_program(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
-        Pubkey::find_program_address(
-            &[
-                wallet.as_ref(),
-                token_program.as_ref(),
-                mint.as_ref(),
-            ],
-            &ASSOCIATED_TOKEN_PROGRAM_ID,
-        ).0
-    }
-
-    /// 派生 associated bonding curve PDA
-    /// 🔥 修复: 应为 bonding_curve 的 **mint** ATA，而非 WSOL ATA
-    /// 支持 Token-2022
-    fn derive_associated_bonding_curve(&self, bonding_curve: &Pubkey, mint: &Pubkey) -> anyhow::Result<Pubkey> {
-        // 检测 mint 的 token program
-        let token_program = self.detect_token_program(mint)?;
-
-        // 使用正确的 token program 派生 ATA
-        Ok(Self::get_ata_with_program(bonding_curve, mint, &token_program))
-    }
-
-    /// 🔥 修复: 从 bonding_curve 账户读取 creator
-    fn get_creator_from_bonding_curve(&self, bonding_curve: &Pubkey) -> anyhow::Result<Pubkey> {
-        use crate::grpc::parser::bonding_curve_decode;
-        use solana_client::rpc_client::RpcClient;
-
-        // 创建临时 RPC client 读取链上数据
-        let rpc_client = RpcClient::new(self.config.rpc_endpoint.clone());
-        let data = rpc_client.get_account_data(bonding_curve)
-            .map_err(|e| anyhow::anyhow!("读取 bonding curve 账户失败: {}", e))?;
-
-        let bc = bonding_curve_decode(&data)
-            .ok_or_else(|| anyhow::anyhow!("解码 bonding curve 失败"))?;
-
-        Ok(bc.creator)
-    }
-
-    /// 🔥 修复: 派生 creator_vault PDA（完全参考 sol-trade-sdk）
-    /// seed = [b"creator-vault", creator.as_ref()]
-    /// program_id = PUMPFUN_PROGRAM_ID
-    fn derive_creator_vault(creator: &Pubkey) -> anyhow::Result<Pubkey> {
-        let (creator_vault, _bump) = Pubkey::find_program_address(
-            &[b"creator-vault", creator.as_ref()],
+    /// 派生毕业后的 PumpSwap 池地址（canonical pool）
+    fn derive_pumpswap_pool(mint: &Pubkey) -> Pubkey {
+        // 迁移时的池创建者是 PumpFun 的 pool-authority PDA，池索引为 0，报价币为 WSOL
+        let (pool_authority, _) = Pubkey::find_program_address(
+            &[b"pool-authority", mint.as_ref()],
             &PUMPFUN_PROGRAM_ID,
         );
-        Ok(creator_vault)
+        Pubkey::find_program_address(
+            &[b"pool", &0u16.to_le_bytes(), pool_authority.as_ref(), mint.as_ref(), WSOL_MINT.as_ref()],
+            &PUMPSWAP_PROGRAM_ID,
+        ).0
     }
 
 }
@@ -0,0 +1,273 @@
+/// 持仓状态机
+///
+/// 每个 mint 的持仓生命周期用显式状态表示（带时间戳的状态历史），
+/// 重试、确认和启动恢复都基于状态判断，而不是"持仓表里有没有这个 mint"
+///
+/// 核心功能:
+/// 1. 状态 - PendingBuy → Open → PendingSell → Closed，失败为 Failed，来源不明为 Orphaned，
+///    曲线完成（毕业）后无法在 bonding curve 上卖出为 Migrated
+/// 2. 转换校验 - 只允许合法转换，非法转换记录警告并拒绝
+/// 3. 持久化 - 每次转换后通知后台写线程写入 position_state_path（JSON，先写临时文件再重命名），
+///    转换本身不做文件 I/O，写线程忙时多次转换合并为一次写入
+/// 4. 启动恢复 - 上次运行未完成（PendingBuy / Open / PendingSell）的记录标记为 Orphaned
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::Arc;
+
+/// 内存中最多保留的终态（Closed / Failed）记录数，超过时丢弃最早的
+const MAX_TERMINAL_ENTRIES: usize = 500;
+
+/// 持仓状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PositionStatus {
+    /// 买入已发送，等待确认
+    PendingBuy,
+    /// 持仓中
+    Open,
+    /// 卖出进行中
+    PendingSell,
+    /// 已平仓
+    Closed,
+    /// 买入未成交 / 失败
+    Failed,
+    /// 来源不明（上次运行遗留或钱包中未记录的 token），等待恢复
+    Orphaned,
+    /// 曲线已完成（毕业迁移到 PumpSwap），等待手动卖出
+    Migrated,
+}
+
+impl PositionStatus {
+    /// 是否为终态（之后可以重新开始新的生命周期）
+    pub fn is_terminal(self) -> bool {
+        matches!(self, PositionStatus::Closed | PositionStatus::Failed)
+    }
+
+    /// 是否允许转换到 next（current 为 None 表示没有记录）
+    pub fn can_transition(current: Option<Self>, next: Self) -> bool {
+        use PositionStatus::*;
+
+        match (current, next) {
+            (None, PendingBuy | Orphaned) => true,
+            (Some(status), PendingBuy) if status.is_terminal() => true,
+            (Some(PendingBuy), Open | Failed) => true,
+            (Some(Open), PendingSell | Closed | Orphaned) => true,
+            (Some(PendingSell), Open | Closed) => true,
+            (Some(Orphaned), Open | Closed) => true,
+            (Some(Open | PendingSell | Orphaned), Migrated) => true,
+            (Some(Migrated), Closed) => true,
+            _ => false,
+        }
+    }
+}
+
+/// 一次状态转换
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub status: PositionStatus,
+    pub timestamp: String,
+    pub note: String,
+}
+
+/// 单个 mint 的持仓生命周期
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionLifecycle {
+    pub mint: String,
+    pub status: PositionStatus,
+    pub updated_at: String,
+    pub history: Vec<StatusTransition>,
+}
+
+/// 持仓状态存储
+pub struct PositionStateStore {
+    /// 状态文件路径（锁同时保证写线程和 flush 不会同时写临时文件）
+    file: Option<Arc<Mutex<PathBuf>>>,
+    states: Arc<Mutex<HashMap<Pubkey, PositionLifecycle>>>,
+    /// 通知写线程有待保存的变更（容量 1：已有待处理通知时无需再发）
+    dirty: Option<SyncSender<()>>,
+}
+
+impl PositionStateStore {
+    /// 打开状态文件（未配置路径时只保存在内存中）
+    ///
+    /// 上次运行遗留的非终态记录标记为 Orphaned，终态记录丢弃（历史见交易日志）
+    pub fn open(path: Option<&str>) -> Result<Self> {
+        let path = path.filter(|p| !p.is_empty()).map(PathBuf::from);
+        let mut states = HashMap::new();
+
+        if let Some(path) = path.as_ref().filter(|p| p.exists()) {
+            let data = std::fs::read_to_string(path)
+                .with_context(|| format!("读取持仓状态文件失败: {}", path.display()))?;
+            let saved: Vec<PositionLifecycle> = serde_json::from_str(&data)
+                .with_context(|| format!("解析持仓状态文件失败: {}", path.display()))?;
+
+            let now = Utc::now().to_rfc3339();
+            for mut lifecycle in saved {
+                if lifecycle.status.is_terminal() {
+                    continue;
+                }
+                let Ok(mint) = Pubkey::from_str(&lifecycle.mint) else {
+                    continue;
+                };
+                warn!("🧩 上次运行未完成的持仓: {} ({:?})，标记为 Orphaned", lifecycle.mint, lifecycle.status);
+                lifecycle.status = PositionStatus::Orphaned;
+                lifecycle.updated_at = now.clone();
+                lifecycle.history.push(StatusTransition {
+                    status: PositionStatus::Orphaned,
+                    timestamp: now.clone(),
+                    note: "上次运行未完成".to_string(),
+                });
+                states.insert(mint, lifecycle);
+            }
+
+            info!("🧩 持仓状态: {} ({} 条待恢复)", path.display(), states.len());
+        }
+
+        let states = Arc::new(Mutex::new(states));
+        let file = path.map(|path| Arc::new(Mutex::new(path)));
+        let dirty = match &file {
+            Some(file) => {
+                Self::persist(file, &states);
+                Some(Self::spawn_writer(file.clone(), states.clone())?)
+            }
+            None => None,
+        };
+
+        Ok(Self { file, states, dirty })
+    }
+
+    /// 启动写线程：收到通知后保存当前全部状态（store 释放后线程退出）
+    fn spawn_writer(
+        file: Arc<Mutex<PathBuf>>,
+        states: Arc<Mutex<HashMap<Pubkey, PositionLifecycle>>>,
+    ) -> Result<SyncSender<()>> {
+        let (tx, rx) = mpsc::sync_channel::<()>(1);
+        std::thread::Builder::new()
+            .name("position-state-writer".to_string())
+            .spawn(move || {
+                while rx.recv().is_ok() {
+                    Self::persist(&file, &states);
+                }
+            })
+            .context("启动持仓状态写线程失败")?;
+        Ok(tx)
+    }
+
+    /// 处于 Orphaned 状态的 mint（启动恢复使用）
+    pub fn orphaned(&self) -> Vec<Pubkey> {
+        self.states
+            .lock()
+            .iter()
+            .filter(|(_, lifecycle)| lifecycle.status == PositionStatus::Orphaned)
+            .map(|(mint, _)| *mint)
+            .collect()
+    }
+
+    /// 状态转换（非法转换时警告并返回 false；转换到当前状态视为成功且不记录）
+    pub fn transition(&self, mint: &Pubkey, next: PositionStatus, note: &str) -> bool {
+        let mut states = self.states.lock();
+        let current = states.get(mint).map(|lifecycle| lifecycle.status);
+
+        if current == Some(next) {
+            return true;
+        }
+        if !PositionStatus::can_transition(current, next) {
+            warn!("⚠️  非法的持仓状态转换: {} {:?} -> {:?} ({})", mint, current, next, note);
+            return false;
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let transition = StatusTransition {
+            status: next,
+            timestamp: now.clone(),
+            note: note.to_string(),
+        };
+
+        match states.get_mut(mint) {
+            // 终态后重新买入：开始新的生命周期
+            Some(lifecycle) if !lifecycle.status.is_terminal() => {
+                lifecycle.status = next;
+                lifecycle.updated_at = now;
+                lifecycle.history.push(transition);
+            }
+            _ => {
+                states.insert(*mint, PositionLifecycle {
+                    mint: mint.to_string(),
+                    status: next,
+                    updated_at: now,
+                    history: vec![transition],
+                });
+            }
+        }
+
+        Self::prune_terminal(&mut states);
+        drop(states);
+        self.mark_dirty();
+        true
+    }
+
+    /// 同步保存当前状态（退出前调用，确保最后的转换已落盘）
+    pub fn flush(&self) {
+        if let Some(file) = &self.file {
+            Self::persist(file, &self.states);
+        }
+    }
+
+    fn mark_dirty(&self) {
+        let Some(dirty) = &self.dirty else {
+            return;
+        };
+        match dirty.try_send(()) {
+            // Full: 写线程尚未处理上一次通知，届时会保存包含本次转换的最新状态
+            Ok(()) | Err(TrySendError::Full(())) => {}
+            Err(TrySendError::Disconnected(())) => warn!("⚠️  持仓状态写线程已退出，状态未保存"),
+        }
+    }
+
+    /// 所有生命周期（控制 API 使用）
+    pub fn snapshot(&self) -> Vec<PositionLifecycle> {
+        self.states.lock().values().cloned().collect()
+    }
+
+    fn prune_terminal(states: &mut HashMap<Pubkey, PositionLifecycle>) {
+        let mut terminal: Vec<(Pubkey, String)> = states
+            .iter()
+            .filter(|(_, lifecycle)| lifecycle.status.is_terminal())
+            .map(|(mint, lifecycle)| (*mint, lifecycle.updated_at.clone()))
+            .collect();
+        if terminal.len() <= MAX_TERMINAL_ENTRIES {
+            return;
+        }
+
+        // RFC 3339 (UTC) 字符串按字典序即时间顺序
+        terminal.sort_by(|a, b| a.1.cmp(&b.1));
+        for (mint, _) in terminal.iter().take(terminal.len() - MAX_TERMINAL_ENTRIES) {
+            states.remove(mint);
+        }
+    }
+
+    fn persist(file: &Mutex<PathBuf>, states: &Mutex<HashMap<Pubkey, PositionLifecycle>>) {
+        let path = file.lock();
+        // 只在复制时持有状态锁，序列化和文件 I/O 不阻塞状态转换
+        let lifecycles: Vec<PositionLifecycle> = states.lock().values().cloned().collect();
+        let result = serde_json::to_string_pretty(&lifecycles)
+            .context("序列化持仓状态失败")
+            .and_then(|data| {
+                let tmp = path.with_extension("tmp");
+                std::fs::write(&tmp, data).context("写入临时文件失败")?;
+                std::fs::rename(&tmp, &*path).context("重命名状态文件失败")
+            });
+
+        if let Err(e) = result {
+            warn!("⚠️  保存持仓状态失败: {:?}", e);
+        }
+    }
+}
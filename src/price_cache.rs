@@ -0,0 +1,123 @@
+/// Bonding curve 价格缓存
+///
+/// 由 gRPC 账户订阅驱动（BondingCurve 账户更新），供监控等模块读取最新价格和流动性，
+/// 避免逐个持仓轮询 RPC
+///
+/// 核心功能:
+/// 1. 账户更新写入 - 按 slot 丢弃乱序的旧数据
+/// 2. 按 mint 查询 - 内部派生 bonding curve PDA
+/// 3. 价格/流动性计算 - 与 sol-trade-sdk 的 get_token_price 对齐
+/// 4. 过期清理 - 移除长时间未更新的曲线
+
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use log::info;
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::grpc::parser::BondingCurve;
+
+static PUMPFUN_PROGRAM_ID: Lazy<Pubkey> = Lazy::new(|| {
+    Pubkey::try_from("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P")
+        .expect("Invalid PumpFun program ID")
+});
+
+/// 单条曲线的最新状态
+#[derive(Debug, Clone, Copy)]
+pub struct CurveState {
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub complete: bool,
+    /// 账户更新所在 slot
+    pub slot: u64,
+    /// 本地接收时间
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CurveState {
+    /// Token 价格
+    ///
+    /// 完全对齐 sol-trade-sdk 的 BondingCurveAccount::get_token_price 实现
+    pub fn price(&self) -> f64 {
+        if self.virtual_token_reserves == 0 {
+            return 0.0;
+        }
+        let v_sol = self.virtual_sol_reserves as f64 / 100_000_000.0;  // lamports to 0.01 SOL
+        let v_tokens = self.virtual_token_reserves as f64 / 100_000.0; // smallest unit
+        v_sol / v_tokens
+    }
+
+    /// 流动性（SOL 储备量，lamports -> SOL）
+    pub fn liquidity_sol(&self) -> f64 {
+        self.virtual_sol_reserves as f64 / 1_000_000_000.0
+    }
+}
+
+/// 价格缓存（bonding curve 地址 -> 最新状态）
+pub struct PriceCache {
+    curves: DashMap<Pubkey, CurveState>,
+}
+
+impl PriceCache {
+    pub fn new() -> Self {
+        Self {
+            curves: DashMap::new(),
+        }
+    }
+
+    /// 写入账户更新（slot 早于已缓存数据时忽略）
+    pub fn update(&self, bonding_curve: Pubkey, slot: u64, curve: &BondingCurve) {
+        let state = CurveState {
+            virtual_sol_reserves: curve.virtual_sol_reserves,
+            virtual_token_reserves: curve.virtual_token_reserves,
+            real_sol_reserves: curve.real_sol_reserves,
+            real_token_reserves: curve.real_token_reserves,
+            complete: curve.complete,
+            slot,
+            updated_at: Utc::now(),
+        };
+
+        self.curves
+            .entry(bonding_curve)
+            .and_modify(|existing| {
+                if slot >= existing.slot {
+                    *existing = state;
+                }
+            })
+            .or_insert(state);
+    }
+
+    /// 按 bonding curve 地址查询
+    pub fn get(&self, bonding_curve: &Pubkey) -> Option<CurveState> {
+        self.curves.get(bonding_curve).map(|state| *state)
+    }
+
+    /// 按 mint 查询（内部派生 bonding curve PDA）
+    pub fn get_by_mint(&self, mint: &Pubkey) -> Option<CurveState> {
+        let (bonding_curve, _bump) = Pubkey::find_program_address(
+            &[b"bonding-curve", mint.as_ref()],
+            &PUMPFUN_PROGRAM_ID,
+        );
+        self.get(&bonding_curve)
+    }
+
+    /// 清理超过 max_age_secs 未更新的曲线
+    pub fn cleanup(&self, max_age_secs: u64) {
+        let cutoff_time = Utc::now() - Duration::seconds(max_age_secs as i64);
+        let before = self.curves.len();
+        self.curves.retain(|_, state| state.updated_at > cutoff_time);
+
+        let removed = before.saturating_sub(self.curves.len());
+        if removed > 0 {
+            info!("🧹 价格缓存清理完成: 移除 {} 条曲线, 剩余 {}", removed, self.curves.len());
+        }
+    }
+}
+
+impl Default for PriceCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,131 @@
+/// PumpFun Global 账户状态
+///
+/// fee recipient 和交易费率保存在 PumpFun 的 Global 账户中，pump.fun 轮换 fee recipient
+/// 或调整费率后，硬编码的常量会让买卖指令失败 / 滑点计算偏差
+///
+/// 核心功能:
+/// 1. 内置默认值 - 读取到 Global 账户之前使用原来的常量（fee recipient、95 + 30 bps）
+/// 2. 启动读取 - RPC 读取一次 Global 账户（账户很少变化，不能只等订阅推送）
+/// 3. 实时刷新 - gRPC 订阅 PumpFun 程序账户，Global 更新按 slot 写入
+/// 4. 变更日志 - fee recipient / 费率变化时打印
+///
+/// 只覆盖 Global 账户中的字段（fee recipient、fee_basis_points、creator_fee_basis_points）；
+/// fee_config / fee_program 账户地址和 FeeConfig 的分档费率不在此处（见 dex/pumpfun.rs）
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::grpc::parser::{global_account_decode, Global};
+
+/// PumpFun Global 账户地址
+pub static PUMPFUN_GLOBAL: Lazy<Pubkey> = Lazy::new(|| {
+    Pubkey::try_from("4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf").expect("Invalid PUMPFUN_GLOBAL")
+});
+
+// 默认值（参考 sol-trade-sdk/src/instruction/utils/pumpfun.rs）
+const DEFAULT_FEE_RECIPIENT: &str = "62qc2CNXwrYqQScmEdiZFFAnJR262PxWEuNQtxfafNgV";
+const DEFAULT_FEE_BASIS_POINTS: u64 = 95;
+const DEFAULT_CREATOR_FEE_BASIS_POINTS: u64 = 30;
+
+/// 交易使用的费用参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PumpFees {
+    pub fee_recipient: Pubkey,
+    pub fee_basis_points: u64,
+    pub creator_fee_basis_points: u64,
+    /// 来源 Global 账户的 slot（0 表示仍是内置默认值）
+    pub slot: u64,
+}
+
+impl PumpFees {
+    /// 内置默认值
+    fn fallback() -> Self {
+        Self {
+            fee_recipient: Pubkey::try_from(DEFAULT_FEE_RECIPIENT).expect("Invalid DEFAULT_FEE_RECIPIENT"),
+            fee_basis_points: DEFAULT_FEE_BASIS_POINTS,
+            creator_fee_basis_points: DEFAULT_CREATOR_FEE_BASIS_POINTS,
+            slot: 0,
+        }
+    }
+
+    /// 协议费 + 创作者费
+    pub fn total_fee_basis_points(&self) -> u64 {
+        self.fee_basis_points + self.creator_fee_basis_points
+    }
+}
+
+/// Global 账户状态（gRPC 账户更新写入，执行器读取）
+pub struct PumpGlobalState {
+    fees: RwLock<PumpFees>,
+}
+
+impl PumpGlobalState {
+    pub fn new() -> Self {
+        Self {
+            fees: RwLock::new(PumpFees::fallback()),
+        }
+    }
+
+    /// 当前费用参数
+    pub fn fees(&self) -> PumpFees {
+        *self.fees.read()
+    }
+
+    /// 当前 fee recipient
+    pub fn fee_recipient(&self) -> Pubkey {
+        self.fees.read().fee_recipient
+    }
+
+    /// 写入 Global 账户数据（slot 早于已有数据、账户未初始化或 fee recipient 为空时忽略）
+    pub fn update(&self, slot: u64, global: &Global) {
+        if !global.initialized || global.fee_recipient == Pubkey::default() {
+            warn!("⚠️  Global 账户数据无效（未初始化或 fee recipient 为空），继续使用 {}", self.fee_recipient());
+            return;
+        }
+
+        let fees = PumpFees {
+            fee_recipient: global.fee_recipient,
+            fee_basis_points: global.fee_basis_points,
+            creator_fee_basis_points: global.creator_fee_basis_points,
+            slot,
+        };
+
+        let mut current = self.fees.write();
+        if slot < current.slot {
+            return;
+        }
+        if fees.fee_recipient != current.fee_recipient
+            || fees.total_fee_basis_points() != current.total_fee_basis_points()
+        {
+            info!("🔄 PumpFun Global 更新 (slot {}): fee recipient {} → {}, 费率 {} + {} bps → {} + {} bps",
+                slot,
+                current.fee_recipient, fees.fee_recipient,
+                current.fee_basis_points, current.creator_fee_basis_points,
+                fees.fee_basis_points, fees.creator_fee_basis_points);
+        }
+        *current = fees;
+    }
+
+    /// 启动时通过 RPC 读取 Global 账户
+    pub async fn refresh(&self, rpc_client: &RpcClient) -> Result<()> {
+        let response = rpc_client
+            .get_account_with_commitment(&PUMPFUN_GLOBAL, rpc_client.commitment())
+            .await
+            .context("读取 PumpFun Global 账户失败")?;
+        let account = response.value.context("PumpFun Global 账户不存在")?;
+        let global = global_account_decode(&account.data).context("解码 PumpFun Global 账户失败")?;
+
+        self.update(response.context.slot, &global);
+        Ok(())
+    }
+}
+
+impl Default for PumpGlobalState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,118 @@
+/// 报价 API
+///
+/// 按价格缓存中的储备（gRPC 账户更新写入）计算买入 / 卖出预期，不发 RPC、不签名，
+/// 控制 API（GET /quote/buy、GET /quote/sell）和集成测试直接调用
+///
+/// 核心功能:
+/// 1. quote_buy(mint, sol_amount) - 预计得到的 token 数量、手续费、价格冲击
+/// 2. quote_sell(mint, token_amount) - 预计得到的 SOL、手续费、价格冲击
+/// 3. 储备新鲜度 - 返回储备所在 slot 和缓存年龄，调用方自行判断是否可用
+///
+/// 报价公式由 DexAdapter 提供，与执行器构建交易时使用的完全一致（不含滑点）
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+use crate::dex::{DexAdapter, PoolState, Quote};
+use crate::price_cache::{CurveState, PriceCache};
+
+/// 报价方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteSide {
+    Buy,
+    Sell,
+}
+
+/// 报价结果
+#[derive(Debug, Clone, Serialize)]
+pub struct QuoteResult {
+    pub mint: String,
+    pub side: QuoteSide,
+    pub dex: &'static str,
+    /// 输入数量（买入为 lamports，卖出为 token 最小单位）
+    pub amount_in: u64,
+    /// 预计输出数量（买入为 token 最小单位，卖出为 lamports，已扣手续费）
+    pub amount_out: u64,
+    /// 手续费（lamports）
+    pub fee_lamports: u64,
+    /// 相对当前现价的价格冲击（百分比）
+    pub price_impact_percent: f64,
+    /// 当前现价（lamports / token 最小单位）
+    pub spot_price_lamports: f64,
+    /// 储备所在 slot
+    pub reserves_slot: u64,
+    /// 储备缓存年龄（毫秒）
+    pub reserves_age_ms: i64,
+}
+
+/// 报价服务
+pub struct QuoteService {
+    dex: Arc<dyn DexAdapter>,
+    price_cache: Arc<PriceCache>,
+}
+
+impl QuoteService {
+    pub fn new(dex: Arc<dyn DexAdapter>, price_cache: Arc<PriceCache>) -> Self {
+        Self { dex, price_cache }
+    }
+
+    /// 买入报价：花 sol_amount lamports 预计得到的 token
+    pub fn quote_buy(&self, mint: &Pubkey, sol_amount: u64) -> Result<QuoteResult> {
+        let curve = self.cached_curve(mint)?;
+        let quote = self.dex.quote_buy(&Self::pool_state(&curve), sol_amount);
+        Ok(self.result(mint, QuoteSide::Buy, &curve, quote))
+    }
+
+    /// 卖出报价：卖出 token_amount 预计得到的 lamports
+    pub fn quote_sell(&self, mint: &Pubkey, token_amount: u64) -> Result<QuoteResult> {
+        let curve = self.cached_curve(mint)?;
+        let quote = self.dex.quote_sell(&Self::pool_state(&curve), token_amount);
+        Ok(self.result(mint, QuoteSide::Sell, &curve, quote))
+    }
+
+    fn cached_curve(&self, mint: &Pubkey) -> Result<CurveState> {
+        let curve = self.price_cache
+            .get(&self.dex.derive_pool(mint))
+            .ok_or_else(|| anyhow::anyhow!("no cached reserves for {}", mint))?;
+        if curve.complete {
+            anyhow::bail!("bonding curve complete for {}", mint);
+        }
+        Ok(curve)
+    }
+
+    fn pool_state(curve: &CurveState) -> PoolState {
+        PoolState {
+            virtual_token_reserves: curve.virtual_token_reserves,
+            virtual_sol_reserves: curve.virtual_sol_reserves,
+            real_token_reserves: curve.real_token_reserves,
+            real_sol_reserves: curve.real_sol_reserves,
+            complete: curve.complete,
+            ..PoolState::default()
+        }
+    }
+
+    fn result(&self, mint: &Pubkey, side: QuoteSide, curve: &CurveState, quote: Quote) -> QuoteResult {
+        let spot_price_lamports = if curve.virtual_token_reserves > 0 {
+            curve.virtual_sol_reserves as f64 / curve.virtual_token_reserves as f64
+        } else {
+            0.0
+        };
+
+        QuoteResult {
+            mint: mint.to_string(),
+            side,
+            dex: self.dex.name(),
+            amount_in: quote.amount_in,
+            amount_out: quote.amount_out,
+            fee_lamports: quote.fee_lamports,
+            price_impact_percent: quote.price_impact_percent,
+            spot_price_lamports,
+            reserves_slot: curve.slot,
+            reserves_age_ms: (Utc::now() - curve.updated_at).num_milliseconds(),
+        }
+    }
+}
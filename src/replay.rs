@@ -0,0 +1,758 @@
+/// 事件录制与确定性回放
+///
+/// 实盘运行时把 gRPC 解析出的事件连同接收时间录制为 JSON Lines；`--replay <file> --speed N`
+/// 按原始到达间隔（或加速）把事件注入同一个 ArrayQueue，聚合器 / 策略 / 持仓管理照常运行，
+/// 执行器改用由回放事件驱动的模拟链（ReplayRpc），交易日志和指标与实盘同格式输出，用于复现问题
+///
+/// 核心功能:
+/// 1. 录制 - EventRecorder 每个事件追加一行 {received_at_ms, event}
+/// 2. 回放源 - ReplaySource 按 (到达间隔 / speed) 推入事件队列（speed = 0 不等待），队列满时等待而不丢弃
+/// 3. 模拟链 - ReplayRpc 由交易事件更新曲线储备，按 PumpFun 买入 / 卖出 / 关闭账户指令结算模拟钱包
+/// 4. 回放配置 - 关闭会写入实盘状态文件或访问外部服务的功能（发件箱 / 持仓状态 / 钱包清理 / TPU / SWQOS）
+/// 5. 回放时钟 - 每个事件注入前把计时时钟推进到它的录制时间（窗口观察时长 / 持仓时长 / 告警冷却 /
+///    交易日志时间戳跟随录制时间，加速回放时同样加速）
+///
+/// 局限: 止盈止损 / 监控循环的执行间隔仍是真实时间；模拟链只结算 tip 转账和基础签名费，不计优先费
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use crossbeam_queue::ArrayQueue;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig};
+use solana_client::rpc_response::{Response, RpcResponseContext, RpcResult, RpcSimulateTransactionResult};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::account::Account;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+use solana_transaction_status::{TransactionConfirmationStatus, TransactionStatus};
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::dex::pumpfun::{BUY_DISCRIMINATOR, SELL_DISCRIMINATOR};
+use crate::dex::{DexAdapter, PoolState};
+use crate::error::{PUMPFUN_BONDING_CURVE_COMPLETE, PUMPFUN_TOO_LITTLE_SOL_RECEIVED, PUMPFUN_TOO_MUCH_SOL_REQUIRED};
+use crate::grpc::parser::{discriminators, BondingCurve};
+use crate::price_cache::PriceCache;
+use crate::rpc_api::RpcApi;
+use crate::types::SniperEvent;
+
+const USAGE: &str = "usage: solsniper --replay <events.jsonl> [--speed N]";
+
+const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+const ASSOCIATED_TOKEN_PROGRAM: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+/// 回放结束后等待管道处理完剩余信号 / 交易的时间
+const DRAIN_GRACE_SECS: u64 = 5;
+/// 每回放多少个事件打印一次进度
+const PROGRESS_INTERVAL: u64 = 10_000;
+
+const BASE_SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+const TOKEN_ACCOUNT_RENT_LAMPORTS: u64 = 2_039_280;
+const MINT_ACCOUNT_RENT_LAMPORTS: u64 = 1_461_600;
+const DEFAULT_TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+
+/// system program 错误: 余额不足
+const SYSTEM_INSUFFICIENT_LAMPORTS: u32 = 1;
+/// token program 错误: token 余额不足 / 关闭非空账户
+const TOKEN_INSUFFICIENT_FUNDS: u32 = 1;
+const TOKEN_NON_NATIVE_HAS_BALANCE: u32 = 11;
+/// Anchor 错误: 账户未初始化
+const ACCOUNT_NOT_INITIALIZED: u32 = 3012;
+
+// ==================== 录制 ====================
+
+/// 录制文件中的一行
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecordedEvent {
+    /// 本地接收时间（Unix 毫秒，回放按相邻事件的差值等待）
+    pub received_at_ms: i64,
+    pub event: SniperEvent,
+}
+
+#[derive(Serialize)]
+struct RecordLine<'a> {
+    received_at_ms: i64,
+    event: &'a SniperEvent,
+}
+
+/// 事件录制器（未配置路径时不录制）
+pub struct EventRecorder {
+    file: Option<Mutex<File>>,
+}
+
+impl EventRecorder {
+    pub fn open(path: Option<&str>) -> Result<Self> {
+        let file = match path {
+            Some(path) if !path.is_empty() => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("打开事件录制文件失败: {}", path))?;
+                info!("🎙️  事件录制: {}", path);
+                Some(Mutex::new(file))
+            }
+            _ => None,
+        };
+
+        Ok(Self { file })
+    }
+
+    /// 追加一个事件（失败只记录警告，不影响事件流）
+    pub fn record(&self, event: &SniperEvent) {
+        let Some(file) = &self.file else {
+            return;
+        };
+
+        let line = RecordLine {
+            received_at_ms: Utc::now().timestamp_millis(),
+            event,
+        };
+        let line = match serde_json::to_string(&line) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("⚠️  序列化录制事件失败: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = writeln!(file.lock(), "{}", line) {
+            warn!("⚠️  写入录制事件失败: {}", e);
+        }
+    }
+}
+
+// ==================== 回放参数 ====================
+
+/// `--replay <file> [--speed N]`
+#[derive(Debug, Clone)]
+pub struct ReplayOptions {
+    /// 录制文件
+    pub path: String,
+    /// 回放倍速（1 = 原始间隔，10 = 十倍速，0 = 不等待）
+    pub speed: f64,
+}
+
+impl ReplayOptions {
+    /// 解析命令行参数（不含程序名）
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut path = None;
+        let mut speed = 1.0;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--replay" => path = iter.next().cloned(),
+                "--speed" => {
+                    let value = iter.next().context(USAGE)?;
+                    speed = value.parse::<f64>()
+                        .with_context(|| format!("invalid --speed value: {}", value))?;
+                }
+                other => anyhow::bail!("unknown argument: {} ({})", other, USAGE),
+            }
+        }
+
+        let path = path.context(USAGE)?;
+        if !speed.is_finite() || speed < 0.0 {
+            anyhow::bail!("--speed must be a finite number >= 0");
+        }
+
+        Ok(Self { path, speed })
+    }
+
+    /// 回放模式的配置调整
+    ///
+    /// 交易日志沿用 TRADE_JOURNAL_PATH（回放的输出）；发件箱 / 持仓状态 / 监控历史不落盘，
+    /// 避免覆盖实盘状态；发送只走 RPC 通道（即 ReplayRpc）
+    pub fn apply(&self, config: &mut Config) {
+        config.outbox_path = None;
+        config.position_state_path = None;
+        config.monitor_history_path = None;
+        config.event_record_path = None;
+        config.wallet_sweep_enabled = false;
+        config.tpu_direct_enabled = false;
+        config.send_backend_order = vec!["lightspeed".to_string()];
+        // 录制的事件没有 slot 状态流，processed 对账无法完成
+        config.feed_commitment = "confirmed".to_string();
+    }
+}
+
+// ==================== 回放源 ====================
+
+/// 回放统计
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReplayStats {
+    /// 注入的事件数
+    pub events: u64,
+    /// 无法解析而跳过的行数
+    pub skipped: u64,
+    /// 录制覆盖的时长（毫秒）
+    pub recorded_span_ms: i64,
+}
+
+/// 回放源：读取录制文件并按时间注入事件队列
+pub struct ReplaySource {
+    options: ReplayOptions,
+    chain: Arc<ReplayRpc>,
+    price_cache: Arc<PriceCache>,
+    clock: Arc<Clock>,
+}
+
+impl ReplaySource {
+    pub fn new(options: ReplayOptions, chain: Arc<ReplayRpc>, price_cache: Arc<PriceCache>, clock: Arc<Clock>) -> Self {
+        Self { options, chain, price_cache, clock }
+    }
+
+    /// 回放全部事件，等待事件队列清空后再留出处理时间
+    pub async fn run(&self, event_queue: Arc<ArrayQueue<SniperEvent>>) -> Result<ReplayStats> {
+        let file = File::open(&self.options.path)
+            .with_context(|| format!("打开回放文件失败: {}", self.options.path))?;
+
+        info!("⏯️  开始回放: {} (倍速 {})", self.options.path,
+            if self.options.speed > 0.0 { format!("{}x", self.options.speed) } else { "不等待".to_string() });
+
+        let mut stats = ReplayStats::default();
+        let mut first_received_at = None;
+        let started = Instant::now();
+
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.with_context(|| format!("读取回放文件失败: {}", self.options.path))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let recorded: RecordedEvent = match serde_json::from_str(&line) {
+                Ok(recorded) => recorded,
+                Err(e) => {
+                    warn!("⚠️  跳过第 {} 行: {}", index + 1, e);
+                    stats.skipped += 1;
+                    continue;
+                }
+            };
+
+            // 按原始到达间隔等待（以第一个事件为基准，不累积误差）
+            let first = *first_received_at.get_or_insert(recorded.received_at_ms);
+            let offset_ms = (recorded.received_at_ms - first).max(0);
+            stats.recorded_span_ms = stats.recorded_span_ms.max(offset_ms);
+            if self.options.speed > 0.0 {
+                let due = started + Duration::from_secs_f64(offset_ms as f64 / 1000.0 / self.options.speed);
+                tokio::time::sleep_until(due).await;
+            }
+
+            // 计时跟随录制时间（聚合器 / 持仓管理器处理这个事件时看到的是它的接收时间）
+            self.clock.advance_to(recorded.received_at_ms);
+
+            // 模拟链和价格缓存先于聚合器看到事件（实盘中账户更新与交易同时到达）
+            if let Some((bonding_curve, slot, curve)) = self.chain.observe(&recorded.event) {
+                self.price_cache.update(bonding_curve, slot, &curve);
+            }
+
+            // 队列满时等待消费，回放不丢事件
+            let mut event = recorded.event;
+            while let Err(rejected) = event_queue.push(event) {
+                event = rejected;
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+
+            stats.events += 1;
+            if stats.events % PROGRESS_INTERVAL == 0 {
+                info!("⏯️  已回放 {} 个事件 (录制时间 +{:.1}s)", stats.events, offset_ms as f64 / 1000.0);
+            }
+        }
+
+        while !event_queue.is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tokio::time::sleep(Duration::from_secs(DRAIN_GRACE_SECS)).await;
+
+        info!("⏹️  回放完成: {} 个事件, 跳过 {} 行, 录制时长 {:.1}s, 实际用时 {:.1}s",
+            stats.events, stats.skipped, stats.recorded_span_ms as f64 / 1000.0, started.elapsed().as_secs_f64());
+
+        Ok(stats)
+    }
+}
+
+// ==================== 模拟链 ====================
+
+/// 模拟链状态
+#[derive(Default)]
+struct ChainState {
+    /// 模拟钱包余额
+    lamports: u64,
+    /// bonding curve 地址 → 曲线
+    curves: HashMap<Pubkey, BondingCurve>,
+    /// 回放中出现过的 mint（Token v3，无 mint / freeze 权限）
+    mints: HashSet<Pubkey>,
+    /// 钱包 token 账户 → (mint, 数量)
+    token_accounts: HashMap<Pubkey, (Pubkey, u64)>,
+}
+
+/// 一笔交易的待提交变更（任一指令失败时整体丢弃，只扣签名费）
+struct PendingChanges {
+    lamports: u64,
+    curves: HashMap<Pubkey, BondingCurve>,
+    token_accounts: HashMap<Pubkey, Option<(Pubkey, u64)>>,
+}
+
+impl PendingChanges {
+    fn new(base: &ChainState) -> Self {
+        Self {
+            lamports: base.lamports,
+            curves: HashMap::new(),
+            token_accounts: HashMap::new(),
+        }
+    }
+
+    fn curve(&self, base: &ChainState, pool: &Pubkey) -> Option<BondingCurve> {
+        self.curves.get(pool).or_else(|| base.curves.get(pool)).cloned()
+    }
+
+    fn token_account(&self, base: &ChainState, account: &Pubkey) -> Option<(Pubkey, u64)> {
+        match self.token_accounts.get(account) {
+            Some(pending) => *pending,
+            None => base.token_accounts.get(account).copied(),
+        }
+    }
+
+    fn debit(&mut self, lamports: u64) -> Result<(), u32> {
+        self.lamports = self.lamports.checked_sub(lamports).ok_or(SYSTEM_INSUFFICIENT_LAMPORTS)?;
+        Ok(())
+    }
+
+    fn commit(self, state: &mut ChainState) {
+        state.lamports = self.lamports;
+        state.curves.extend(self.curves);
+        for (account, value) in self.token_accounts {
+            match value {
+                Some(value) => state.token_accounts.insert(account, value),
+                None => state.token_accounts.remove(&account),
+            };
+        }
+    }
+}
+
+/// 回放用 RPC：曲线来自回放事件，钱包由发送的交易结算，交易立即确认
+pub struct ReplayRpc {
+    wallet: Pubkey,
+    dex: Arc<dyn DexAdapter>,
+    /// PumpFun 买入手续费（bps）
+    fee_bps: u64,
+    state: Mutex<ChainState>,
+    landed: Mutex<HashMap<Signature, Option<TransactionError>>>,
+    slot: AtomicU64,
+    system_program: Pubkey,
+    token_program: Pubkey,
+    token_2022_program: Pubkey,
+    associated_token_program: Pubkey,
+}
+
+impl ReplayRpc {
+    pub fn new(wallet: Pubkey, wallet_lamports: u64, dex: Arc<dyn DexAdapter>, fee_bps: u64) -> Result<Self> {
+        Ok(Self {
+            wallet,
+            dex,
+            fee_bps,
+            state: Mutex::new(ChainState {
+                lamports: wallet_lamports,
+                ..ChainState::default()
+            }),
+            landed: Mutex::new(HashMap::new()),
+            slot: AtomicU64::new(0),
+            system_program: Pubkey::from_str(SYSTEM_PROGRAM)?,
+            token_program: Pubkey::from_str(TOKEN_PROGRAM)?,
+            token_2022_program: Pubkey::from_str(TOKEN_2022_PROGRAM)?,
+            associated_token_program: Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM)?,
+        })
+    }
+
+    /// 模拟钱包当前余额
+    pub fn wallet_lamports(&self) -> u64 {
+        self.state.lock().lamports
+    }
+
+    /// 用回放事件更新曲线，返回更新后的 (bonding curve, slot, 曲线) 供写入价格缓存
+    pub fn observe(&self, event: &SniperEvent) -> Option<(Pubkey, u64, BondingCurve)> {
+        let mut state = self.state.lock();
+        match event {
+            SniperEvent::Trade(trade) => {
+                let slot = self.slot.fetch_max(trade.slot, Ordering::Relaxed).max(trade.slot);
+                let pool = self.dex.derive_pool(&trade.mint);
+                state.mints.insert(trade.mint);
+                let curve = state.curves.entry(pool).or_insert_with(|| BondingCurve {
+                    token_total_supply: DEFAULT_TOKEN_TOTAL_SUPPLY,
+                    ..BondingCurve::default()
+                });
+                curve.virtual_sol_reserves = trade.virtual_sol_reserves;
+                curve.virtual_token_reserves = trade.virtual_token_reserves;
+                curve.real_sol_reserves = trade.real_sol_reserves;
+                curve.real_token_reserves = trade.real_token_reserves;
+                curve.creator = trade.creator;
+                Some((pool, slot, curve.clone()))
+            }
+            SniperEvent::CreateToken(create) => {
+                let slot = self.slot.load(Ordering::Relaxed);
+                state.mints.insert(create.mint);
+                let curve = BondingCurve {
+                    virtual_token_reserves: create.virtual_token_reserves,
+                    virtual_sol_reserves: create.virtual_sol_reserves,
+                    real_token_reserves: create.real_token_reserves,
+                    real_sol_reserves: 0,
+                    token_total_supply: create.token_total_supply,
+                    complete: false,
+                    creator: create.creator,
+                };
+                state.curves.insert(create.bonding_curve, curve.clone());
+                Some((create.bonding_curve, slot, curve))
+            }
+            SniperEvent::Migrate(migrate) => {
+                let slot = self.slot.load(Ordering::Relaxed);
+                let curve = state.curves.get_mut(&migrate.bonding_curve)?;
+                curve.complete = true;
+                Some((migrate.bonding_curve, slot, curve.clone()))
+            }
+        }
+    }
+
+    fn context(&self) -> RpcResponseContext {
+        RpcResponseContext::new(self.slot.load(Ordering::Relaxed))
+    }
+
+    fn missing(pubkey: &Pubkey) -> ClientError {
+        ClientErrorKind::Custom(format!("AccountNotFound: pubkey={}", pubkey)).into()
+    }
+
+    fn pool_state(curve: &BondingCurve) -> PoolState {
+        PoolState {
+            virtual_token_reserves: curve.virtual_token_reserves,
+            virtual_sol_reserves: curve.virtual_sol_reserves,
+            real_token_reserves: curve.real_token_reserves,
+            real_sol_reserves: curve.real_sol_reserves,
+            creator: curve.creator,
+            complete: curve.complete,
+        }
+    }
+
+    /// 账户快照（mint / bonding curve / 钱包 token 账户）
+    fn account(&self, pubkey: &Pubkey) -> Option<Account> {
+        let state = self.state.lock();
+
+        if state.mints.contains(pubkey) {
+            // SPL mint: 无 mint / freeze authority，decimals = 6，已初始化
+            let mut data = vec![0u8; 82];
+            data[36..44].copy_from_slice(&DEFAULT_TOKEN_TOTAL_SUPPLY.to_le_bytes());
+            data[44] = 6;
+            data[45] = 1;
+            return Some(Account {
+                lamports: MINT_ACCOUNT_RENT_LAMPORTS,
+                data,
+                owner: self.token_program,
+                executable: false,
+                rent_epoch: 0,
+            });
+        }
+
+        if let Some(curve) = state.curves.get(pubkey) {
+            let mut data = discriminators::BONDING_CURVE_ACCOUNT.to_vec();
+            for value in [
+                curve.virtual_token_reserves,
+                curve.virtual_sol_reserves,
+                curve.real_token_reserves,
+                curve.real_sol_reserves,
+                curve.token_total_supply,
+            ] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            data.push(curve.complete as u8);
+            data.extend_from_slice(curve.creator.as_ref());
+            return Some(Account {
+                lamports: curve.real_sol_reserves,
+                data,
+                owner: self.dex.program_id(),
+                executable: false,
+                rent_epoch: 0,
+            });
+        }
+
+        if let Some((mint, amount)) = state.token_accounts.get(pubkey) {
+            // SPL token 账户: mint / owner / amount / state = Initialized
+            let mut data = vec![0u8; 165];
+            data[0..32].copy_from_slice(mint.as_ref());
+            data[32..64].copy_from_slice(self.wallet.as_ref());
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            data[108] = 1;
+            return Some(Account {
+                lamports: TOKEN_ACCOUNT_RENT_LAMPORTS,
+                data,
+                owner: self.token_program,
+                executable: false,
+                rent_epoch: 0,
+            });
+        }
+
+        None
+    }
+
+    /// 结算一笔交易（指令全部成功才提交；签名费总是扣除）
+    fn settle(&self, transaction: &VersionedTransaction) -> Option<TransactionError> {
+        let keys = transaction.message.static_account_keys();
+        let mut state = self.state.lock();
+        let fee = BASE_SIGNATURE_FEE_LAMPORTS * transaction.signatures.len() as u64;
+
+        let mut pending = PendingChanges::new(&state);
+        let mut failure = None;
+        for (index, ix) in transaction.message.instructions().iter().enumerate() {
+            let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+                continue;
+            };
+            let accounts: Vec<Pubkey> = ix.accounts.iter()
+                .filter_map(|&account| keys.get(account as usize).copied())
+                .collect();
+
+            if let Err(code) = self.apply_instruction(&state, &mut pending, program_id, &accounts, &ix.data) {
+                failure = Some(TransactionError::InstructionError(index as u8, InstructionError::Custom(code)));
+                break;
+            }
+        }
+
+        if failure.is_none() {
+            pending.commit(&mut state);
+        }
+        state.lamports = state.lamports.saturating_sub(fee);
+        failure
+    }
+
+    fn apply_instruction(
+        &self,
+        base: &ChainState,
+        pending: &mut PendingChanges,
+        program_id: &Pubkey,
+        accounts: &[Pubkey],
+        data: &[u8],
+    ) -> Result<(), u32> {
+        let account = |index: usize| accounts.get(index).copied().ok_or(ACCOUNT_NOT_INITIALIZED);
+        let amounts = || -> Result<(u64, u64), u32> {
+            let first = data.get(8..16).and_then(|bytes| bytes.try_into().ok()).map(u64::from_le_bytes);
+            let second = data.get(16..24).and_then(|bytes| bytes.try_into().ok()).map(u64::from_le_bytes);
+            first.zip(second).ok_or(ACCOUNT_NOT_INITIALIZED)
+        };
+
+        if *program_id == self.system_program {
+            // Transfer: [2, 0, 0, 0] + lamports（tip）
+            if data.len() >= 12 && data[..4] == [2, 0, 0, 0] && account(0)? == self.wallet {
+                let lamports = u64::from_le_bytes(data[4..12].try_into().unwrap_or([0u8; 8]));
+                pending.debit(lamports)?;
+            }
+        } else if *program_id == self.associated_token_program {
+            // CreateIdempotent: 已存在时不做任何事
+            let token_account = account(1)?;
+            if pending.token_account(base, &token_account).is_none() {
+                pending.debit(TOKEN_ACCOUNT_RENT_LAMPORTS)?;
+                pending.token_accounts.insert(token_account, Some((account(3)?, 0)));
+            }
+        } else if *program_id == self.token_program || *program_id == self.token_2022_program {
+            // CloseAccount: 租金退回钱包
+            if data == [9] {
+                let token_account = account(0)?;
+                match pending.token_account(base, &token_account) {
+                    Some((_, 0)) => {
+                        pending.token_accounts.insert(token_account, None);
+                        pending.lamports += TOKEN_ACCOUNT_RENT_LAMPORTS;
+                    }
+                    Some(_) => return Err(TOKEN_NON_NATIVE_HAS_BALANCE),
+                    None => return Err(ACCOUNT_NOT_INITIALIZED),
+                }
+            }
+        } else if *program_id == self.dex.program_id() {
+            if data.starts_with(&BUY_DISCRIMINATOR) {
+                let (token_amount, max_sol_cost) = amounts()?;
+                self.apply_buy(base, pending, account(2)?, account(3)?, account(5)?, token_amount, max_sol_cost)?;
+            } else if data.starts_with(&SELL_DISCRIMINATOR) {
+                let (token_amount, min_sol_output) = amounts()?;
+                self.apply_sell(base, pending, account(3)?, account(5)?, token_amount, min_sol_output)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 按指定 token 数量买入：成本 = 恒定乘积所需 SOL + 手续费，超过 max_sol_cost 时失败
+    #[allow(clippy::too_many_arguments)]
+    fn apply_buy(
+        &self,
+        base: &ChainState,
+        pending: &mut PendingChanges,
+        mint: Pubkey,
+        pool: Pubkey,
+        token_account: Pubkey,
+        token_amount: u64,
+        max_sol_cost: u64,
+    ) -> Result<(), u32> {
+        let mut curve = pending.curve(base, &pool).ok_or(ACCOUNT_NOT_INITIALIZED)?;
+        if curve.complete {
+            return Err(PUMPFUN_BONDING_CURVE_COMPLETE);
+        }
+        let (_, held) = pending.token_account(base, &token_account).ok_or(ACCOUNT_NOT_INITIALIZED)?;
+        if token_amount >= curve.virtual_token_reserves || token_amount > curve.real_token_reserves {
+            return Err(PUMPFUN_TOO_MUCH_SOL_REQUIRED);
+        }
+
+        let numerator = token_amount as u128 * curve.virtual_sol_reserves as u128;
+        let denominator = (curve.virtual_token_reserves - token_amount) as u128;
+        let sol_in = numerator.div_ceil(denominator) as u64;
+        let cost = sol_in + sol_in * self.fee_bps / 10_000;
+        if cost > max_sol_cost {
+            return Err(PUMPFUN_TOO_MUCH_SOL_REQUIRED);
+        }
+
+        pending.debit(cost)?;
+        curve.virtual_sol_reserves += sol_in;
+        curve.real_sol_reserves += sol_in;
+        curve.virtual_token_reserves -= token_amount;
+        curve.real_token_reserves -= token_amount;
+        pending.curves.insert(pool, curve);
+        pending.token_accounts.insert(token_account, Some((mint, held + token_amount)));
+        Ok(())
+    }
+
+    /// 卖出：所得按 DEX 报价（已扣手续费），低于 min_sol_output 时失败
+    fn apply_sell(
+        &self,
+        base: &ChainState,
+        pending: &mut PendingChanges,
+        pool: Pubkey,
+        token_account: Pubkey,
+        token_amount: u64,
+        min_sol_output: u64,
+    ) -> Result<(), u32> {
+        let mut curve = pending.curve(base, &pool).ok_or(ACCOUNT_NOT_INITIALIZED)?;
+        if curve.complete {
+            return Err(PUMPFUN_BONDING_CURVE_COMPLETE);
+        }
+        let (mint, held) = pending.token_account(base, &token_account).ok_or(ACCOUNT_NOT_INITIALIZED)?;
+        if held < token_amount {
+            return Err(TOKEN_INSUFFICIENT_FUNDS);
+        }
+
+        let quote = self.dex.quote_sell(&Self::pool_state(&curve), token_amount);
+        if quote.amount_out < min_sol_output {
+            return Err(PUMPFUN_TOO_LITTLE_SOL_RECEIVED);
+        }
+
+        let sol_out = quote.amount_out + quote.fee_lamports;
+        pending.lamports += quote.amount_out;
+        curve.virtual_sol_reserves = curve.virtual_sol_reserves.saturating_sub(sol_out);
+        curve.real_sol_reserves = curve.real_sol_reserves.saturating_sub(sol_out);
+        curve.virtual_token_reserves += token_amount;
+        curve.real_token_reserves += token_amount;
+        pending.curves.insert(pool, curve);
+        pending.token_accounts.insert(token_account, Some((mint, held - token_amount)));
+        Ok(())
+    }
+}
+
+impl RpcApi for ReplayRpc {
+    fn get_balance(&self, pubkey: &Pubkey) -> ClientResult<u64> {
+        if *pubkey == self.wallet {
+            return Ok(self.wallet_lamports());
+        }
+        Ok(self.account(pubkey).map_or(0, |account| account.lamports))
+    }
+
+    fn get_balance_with_commitment(&self, pubkey: &Pubkey, _commitment: CommitmentConfig) -> RpcResult<u64> {
+        Ok(Response { context: self.context(), value: self.get_balance(pubkey)? })
+    }
+
+    fn get_latest_blockhash(&self) -> ClientResult<Hash> {
+        Ok(Hash::new_unique())
+    }
+
+    fn is_blockhash_valid(&self, _blockhash: &Hash, _commitment: CommitmentConfig) -> ClientResult<bool> {
+        // 模拟链发送即结算，没有仍可能上链的在途交易
+        Ok(false)
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> ClientResult<Account> {
+        self.account(pubkey).ok_or_else(|| Self::missing(pubkey))
+    }
+
+    fn get_account_data(&self, pubkey: &Pubkey) -> ClientResult<Vec<u8>> {
+        Ok(self.get_account(pubkey)?.data)
+    }
+
+    fn get_account_with_commitment(&self, pubkey: &Pubkey, _commitment: CommitmentConfig) -> RpcResult<Option<Account>> {
+        Ok(Response { context: self.context(), value: self.account(pubkey) })
+    }
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> ClientResult<Vec<Option<Account>>> {
+        Ok(pubkeys.iter().map(|pubkey| self.account(pubkey)).collect())
+    }
+
+    fn get_token_account_balance_with_commitment(&self, token_account: &Pubkey, _commitment: CommitmentConfig) -> RpcResult<u64> {
+        let amount = self.state.lock().token_accounts.get(token_account).map(|(_, amount)| *amount);
+        match amount {
+            Some(amount) => Ok(Response { context: self.context(), value: amount }),
+            None => Err(Self::missing(token_account)),
+        }
+    }
+
+    fn get_signature_statuses(&self, signatures: &[Signature]) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        let slot = self.slot.load(Ordering::Relaxed);
+        let landed = self.landed.lock();
+        let value = signatures
+            .iter()
+            .map(|signature| {
+                landed.get(signature).map(|err| TransactionStatus {
+                    slot,
+                    confirmations: None,
+                    status: err.clone().map_or(Ok(()), Err),
+                    err: err.clone(),
+                    confirmation_status: Some(TransactionConfirmationStatus::Finalized),
+                })
+            })
+            .collect();
+        Ok(Response { context: self.context(), value })
+    }
+
+    fn send_transaction(&self, transaction: &VersionedTransaction) -> ClientResult<Signature> {
+        let signature = transaction.signatures[0];
+        if self.landed.lock().contains_key(&signature) {
+            return Err(TransactionError::AlreadyProcessed.into());
+        }
+
+        let err = self.settle(transaction);
+        if let Some(err) = &err {
+            info!("⏯️  模拟交易执行失败: {} ({:?})", signature, err);
+        }
+        self.landed.lock().insert(signature, err);
+        Ok(signature)
+    }
+
+    fn send_transaction_with_config(&self, transaction: &VersionedTransaction, _config: RpcSendTransactionConfig) -> ClientResult<Signature> {
+        self.send_transaction(transaction)
+    }
+
+    fn simulate_transaction_with_config(
+        &self,
+        _transaction: &VersionedTransaction,
+        _config: RpcSimulateTransactionConfig,
+    ) -> RpcResult<RpcSimulateTransactionResult> {
+        // 蜜罐检测在模拟请求失败时跳过
+        Err(ClientErrorKind::Custom("simulateTransaction is not available in replay mode".to_string()).into())
+    }
+}
@@ -0,0 +1,175 @@
+/// 账户级风控
+///
+/// 按账户权益（钱包 SOL + 持仓按当前曲线估值）跟踪回撤，超过阈值时暂停开新仓
+///
+/// 核心功能:
+/// 1. 峰值权益跟踪 - 记录运行以来的最高权益
+/// 2. 最大回撤暂停 - 回撤超过 max_drawdown_percent 时暂停买入（已有持仓照常管理）
+/// 3. 自动恢复 - 冷却 drawdown_cooldown_secs 后恢复，并以当前权益作为新的峰值
+/// 4. 手动恢复 - 控制 API 重置
+/// 5. 低余额暂停 - 钱包余额低于单笔买入所需储备（买入金额 + tip + 手续费/租金缓冲）时暂停买入
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// 回撤状态快照（控制 API / 日志使用）
+#[derive(Debug, Clone, Copy)]
+pub struct DrawdownSnapshot {
+    pub peak_equity_lamports: u64,
+    pub equity_lamports: u64,
+    pub drawdown_percent: f64,
+    pub paused: bool,
+}
+
+#[derive(Debug, Default)]
+struct DrawdownState {
+    peak_equity_lamports: u64,
+    equity_lamports: u64,
+    paused_at: Option<Instant>,
+}
+
+/// 最大回撤守卫
+pub struct DrawdownGuard {
+    /// 最大回撤百分比（0 = 禁用）
+    max_drawdown_percent: f64,
+    /// 暂停后自动恢复的冷却时间（None = 只能手动恢复）
+    cooldown: Option<Duration>,
+    state: Mutex<DrawdownState>,
+}
+
+impl DrawdownGuard {
+    pub fn new(max_drawdown_percent: f64, cooldown_secs: u64) -> Self {
+        Self {
+            max_drawdown_percent,
+            cooldown: (cooldown_secs > 0).then(|| Duration::from_secs(cooldown_secs)),
+            state: Mutex::new(DrawdownState::default()),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.max_drawdown_percent, config.drawdown_cooldown_secs)
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.max_drawdown_percent > 0.0
+    }
+
+    /// 更新当前权益，回撤超限时暂停买入
+    pub fn update(&self, equity_lamports: u64) {
+        let mut state = self.state.lock();
+        state.equity_lamports = equity_lamports;
+        state.peak_equity_lamports = state.peak_equity_lamports.max(equity_lamports);
+
+        if !self.enabled() || state.paused_at.is_some() {
+            return;
+        }
+
+        let drawdown_percent = Self::drawdown_percent(&state);
+        if drawdown_percent >= self.max_drawdown_percent {
+            state.paused_at = Some(Instant::now());
+            warn!("🛑 回撤 {:.2}% 超过上限 {:.2}%，暂停开新仓", drawdown_percent, self.max_drawdown_percent);
+            warn!("   峰值权益: {:.4} SOL, 当前权益: {:.4} SOL",
+                state.peak_equity_lamports as f64 / 1_000_000_000.0,
+                equity_lamports as f64 / 1_000_000_000.0);
+            match self.cooldown {
+                Some(cooldown) => warn!("   {} 秒后自动恢复（或通过控制 API 手动恢复）", cooldown.as_secs()),
+                None => warn!("   需通过控制 API 手动恢复"),
+            }
+        }
+    }
+
+    /// 是否暂停开新仓（冷却结束时自动恢复）
+    pub fn is_paused(&self) -> bool {
+        let mut state = self.state.lock();
+        let Some(paused_at) = state.paused_at else {
+            return false;
+        };
+
+        match self.cooldown {
+            Some(cooldown) if paused_at.elapsed() >= cooldown => {
+                Self::resume(&mut state);
+                info!("✅ 回撤暂停冷却结束，恢复开新仓");
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// 手动恢复：以当前权益作为新的峰值
+    pub fn reset(&self) {
+        let mut state = self.state.lock();
+        Self::resume(&mut state);
+        info!("✅ 回撤守卫已重置，恢复开新仓 (峰值权益: {:.4} SOL)",
+            state.peak_equity_lamports as f64 / 1_000_000_000.0);
+    }
+
+    pub fn snapshot(&self) -> DrawdownSnapshot {
+        let state = self.state.lock();
+        DrawdownSnapshot {
+            peak_equity_lamports: state.peak_equity_lamports,
+            equity_lamports: state.equity_lamports,
+            drawdown_percent: Self::drawdown_percent(&state),
+            paused: state.paused_at.is_some(),
+        }
+    }
+
+    fn resume(state: &mut DrawdownState) {
+        state.paused_at = None;
+        // 否则恢复后立即再次触发暂停
+        state.peak_equity_lamports = state.equity_lamports;
+    }
+
+    fn drawdown_percent(state: &DrawdownState) -> f64 {
+        if state.peak_equity_lamports == 0 {
+            return 0.0;
+        }
+        let drop = state.peak_equity_lamports.saturating_sub(state.equity_lamports);
+        drop as f64 / state.peak_equity_lamports as f64 * 100.0
+    }
+}
+
+/// 钱包余额守卫
+///
+/// 定期检查余额，提前发现余额不足，而不是在执行买入时才失败
+#[derive(Debug, Default)]
+pub struct BalanceGuard {
+    low_balance: AtomicBool,
+    balance_lamports: AtomicU64,
+    reserve_lamports: AtomicU64,
+}
+
+impl BalanceGuard {
+    /// 更新余额，低于储备时暂停买入（仅在状态变化时打印警告）
+    pub fn update(&self, balance_lamports: u64, reserve_lamports: u64) {
+        self.balance_lamports.store(balance_lamports, Ordering::Relaxed);
+        self.reserve_lamports.store(reserve_lamports, Ordering::Relaxed);
+
+        let low = balance_lamports < reserve_lamports;
+        let was_low = self.low_balance.swap(low, Ordering::Relaxed);
+
+        if low && !was_low {
+            warn!("🪫 钱包余额不足，暂停买入");
+            warn!("   当前余额: {:.4} SOL, 所需储备: {:.4} SOL",
+                balance_lamports as f64 / 1_000_000_000.0,
+                reserve_lamports as f64 / 1_000_000_000.0);
+        } else if !low && was_low {
+            info!("🔋 钱包余额已恢复 ({:.4} SOL)，恢复买入", balance_lamports as f64 / 1_000_000_000.0);
+        }
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.low_balance.load(Ordering::Relaxed)
+    }
+
+    pub fn balance_lamports(&self) -> u64 {
+        self.balance_lamports.load(Ordering::Relaxed)
+    }
+
+    pub fn reserve_lamports(&self) -> u64 {
+        self.reserve_lamports.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,120 @@
+/// RPC 接口抽象
+///
+/// 执行器和确认追踪只通过 `RpcApi` 访问链上数据，生产环境由 RpcPool 创建的 RpcClient 实现，
+/// 单元测试注入自定义实现，按需返回超时 / AlreadyProcessed / 滑点失败等结果
+///
+/// 核心功能:
+/// 1. 查询 - 余额、账户、批量账户、token 余额、签名状态、最新 blockhash 及其是否仍有效
+/// 2. 发送 - sendTransaction（可带配置）
+/// 3. 模拟 - simulateTransaction（蜜罐检测）
+///
+/// 错误类型保持 ClientError，error::classify_send_error 可直接识别其中的 TransactionError
+
+use solana_client::client_error::{ClientErrorKind, Result as ClientResult};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig};
+use solana_client::rpc_response::{RpcResult, RpcSimulateTransactionResult};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::account::Account;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::TransactionStatus;
+
+/// 执行器使用的 RPC 方法（阻塞调用，与 RpcClient 同名同义）
+pub trait RpcApi: Send + Sync {
+    fn get_balance(&self, pubkey: &Pubkey) -> ClientResult<u64>;
+
+    fn get_balance_with_commitment(&self, pubkey: &Pubkey, commitment: CommitmentConfig) -> RpcResult<u64>;
+
+    fn get_latest_blockhash(&self) -> ClientResult<Hash>;
+
+    /// blockhash 是否仍可用于新交易（过期后用它签名的交易不会再上链）
+    fn is_blockhash_valid(&self, blockhash: &Hash, commitment: CommitmentConfig) -> ClientResult<bool>;
+
+    fn get_account(&self, pubkey: &Pubkey) -> ClientResult<Account>;
+
+    fn get_account_data(&self, pubkey: &Pubkey) -> ClientResult<Vec<u8>>;
+
+    fn get_account_with_commitment(&self, pubkey: &Pubkey, commitment: CommitmentConfig) -> RpcResult<Option<Account>>;
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> ClientResult<Vec<Option<Account>>>;
+
+    /// token 账户余额（最小单位）
+    fn get_token_account_balance_with_commitment(&self, token_account: &Pubkey, commitment: CommitmentConfig) -> RpcResult<u64>;
+
+    fn get_signature_statuses(&self, signatures: &[Signature]) -> RpcResult<Vec<Option<TransactionStatus>>>;
+
+    fn send_transaction(&self, transaction: &VersionedTransaction) -> ClientResult<Signature>;
+
+    fn send_transaction_with_config(&self, transaction: &VersionedTransaction, config: RpcSendTransactionConfig) -> ClientResult<Signature>;
+
+    fn simulate_transaction_with_config(
+        &self,
+        transaction: &VersionedTransaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> RpcResult<RpcSimulateTransactionResult>;
+}
+
+impl RpcApi for RpcClient {
+    fn get_balance(&self, pubkey: &Pubkey) -> ClientResult<u64> {
+        RpcClient::get_balance(self, pubkey)
+    }
+
+    fn get_balance_with_commitment(&self, pubkey: &Pubkey, commitment: CommitmentConfig) -> RpcResult<u64> {
+        RpcClient::get_balance_with_commitment(self, pubkey, commitment)
+    }
+
+    fn get_latest_blockhash(&self) -> ClientResult<Hash> {
+        RpcClient::get_latest_blockhash(self)
+    }
+
+    fn is_blockhash_valid(&self, blockhash: &Hash, commitment: CommitmentConfig) -> ClientResult<bool> {
+        RpcClient::is_blockhash_valid(self, blockhash, commitment)
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> ClientResult<Account> {
+        RpcClient::get_account(self, pubkey)
+    }
+
+    fn get_account_data(&self, pubkey: &Pubkey) -> ClientResult<Vec<u8>> {
+        RpcClient::get_account_data(self, pubkey)
+    }
+
+    fn get_account_with_commitment(&self, pubkey: &Pubkey, commitment: CommitmentConfig) -> RpcResult<Option<Account>> {
+        RpcClient::get_account_with_commitment(self, pubkey, commitment)
+    }
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> ClientResult<Vec<Option<Account>>> {
+        RpcClient::get_multiple_accounts(self, pubkeys)
+    }
+
+    fn get_token_account_balance_with_commitment(&self, token_account: &Pubkey, commitment: CommitmentConfig) -> RpcResult<u64> {
+        let response = RpcClient::get_token_account_balance_with_commitment(self, token_account, commitment)?;
+        let amount = response.value.amount.parse::<u64>().map_err(|e| {
+            ClientErrorKind::Custom(format!("invalid token amount {}: {}", response.value.amount, e))
+        })?;
+        Ok(solana_client::rpc_response::Response { context: response.context, value: amount })
+    }
+
+    fn get_signature_statuses(&self, signatures: &[Signature]) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        RpcClient::get_signature_statuses(self, signatures)
+    }
+
+    fn send_transaction(&self, transaction: &VersionedTransaction) -> ClientResult<Signature> {
+        RpcClient::send_transaction(self, transaction)
+    }
+
+    fn send_transaction_with_config(&self, transaction: &VersionedTransaction, config: RpcSendTransactionConfig) -> ClientResult<Signature> {
+        RpcClient::send_transaction_with_config(self, transaction, config)
+    }
+
+    fn simulate_transaction_with_config(
+        &self,
+        transaction: &VersionedTransaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> RpcResult<RpcSimulateTransactionResult> {
+        RpcClient::simulate_transaction_with_config(self, transaction, config)
+    }
+}
@@ -0,0 +1,94 @@
+/// RPC 限流
+///
+/// 执行器、监控器、持仓管理器和钱包清理各自创建 RpcClient，但请求打到同一个端点，
+/// 繁忙时容易触发 429。RPC 池（rpc_pool）的每个端点使用这里的共享令牌桶
+///
+/// 核心功能:
+/// 1. 令牌桶 - 每个端点 rpc_rate_limit_per_sec 个请求 / 秒，突发上限 rpc_rate_limit_burst
+/// 2. 端点共享 - 按 URL 注册，不同组件创建的客户端共用同一个限流器
+/// 3. 发送优先 - sendTransaction 不排队（只占用名额），读请求在名额不足时等待（由 RPC 池调用）
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// 端点 URL -> 限流器
+static LIMITERS: Lazy<DashMap<String, Arc<RpcRateLimiter>>> = Lazy::new(DashMap::new);
+
+/// 令牌桶限流器
+pub struct RpcRateLimiter {
+    rate_per_sec: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RpcRateLimiter {
+    fn new(rate_per_sec: f64, burst: f64) -> Self {
+        Self {
+            rate_per_sec,
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// 获取一个请求名额（不足时等待）
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock();
+                self.refill(&mut state);
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / self.rate_per_sec)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// 不等待直接占用一个名额（余额可为负，后续读请求相应等待）
+    pub fn consume(&self) {
+        let mut state = self.state.lock();
+        self.refill(&mut state);
+        state.tokens = (state.tokens - 1.0).max(-self.burst);
+    }
+
+    fn refill(&self, state: &mut BucketState) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate_per_sec).min(self.burst);
+        state.last_refill = now;
+    }
+}
+
+/// 端点的共享限流器（rpc_rate_limit_per_sec = 0 时不限流）
+pub fn limiter_for(config: &Config, url: &str) -> Option<Arc<RpcRateLimiter>> {
+    if config.rpc_rate_limit_per_sec <= 0.0 {
+        return None;
+    }
+
+    let burst = if config.rpc_rate_limit_burst == 0 {
+        config.rpc_rate_limit_per_sec.ceil().max(1.0)
+    } else {
+        config.rpc_rate_limit_burst as f64
+    };
+
+    let limiter = LIMITERS
+        .entry(url.to_string())
+        .or_insert_with(|| Arc::new(RpcRateLimiter::new(config.rpc_rate_limit_per_sec, burst)))
+        .clone();
+    Some(limiter)
+}
@@ -0,0 +1,354 @@
+/// RPC 池（主端点 + 备用端点）
+///
+/// 所有组件的 RpcClient 都由池创建，请求按端点健康状态路由：主端点健康时优先使用，
+/// 不健康时自动切换到延迟最低的健康备用端点，恢复后自动切回
+///
+/// 核心功能:
+/// 1. 路由 - 主端点优先，备用端点按延迟（EWMA）排序；全部不健康时仍按顺序尝试
+/// 2. 故障切换 - 传输层错误（连接失败 / 超时 / HTTP 错误）立即尝试下一个端点，
+///    连续失败 rpc_failover_threshold 次标记为不健康（冷却 rpc_unhealthy_cooldown_secs）
+/// 3. 健康检查 - 定期 getSlot 测延迟，slot 落后最高端点超过 rpc_max_slot_lag 视为不健康
+/// 4. 限流 - 每个端点使用 rpc_limiter 的共享令牌桶
+/// 5. 透明接入 - 实现 RpcSender，调用方仍使用标准 RpcClient
+
+use async_trait::async_trait;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+use solana_client::client_error::{ClientErrorKind, Result as ClientResult};
+use solana_rpc_client::http_sender::HttpSender;
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_commitment_config::CommitmentConfig;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::rpc_limiter::{self, RpcRateLimiter};
+
+/// 延迟 EWMA 平滑系数
+const LATENCY_EWMA_ALPHA: f64 = 0.2;
+
+/// 端点运行状态
+#[derive(Debug, Default)]
+struct EndpointState {
+    consecutive_failures: u32,
+    unhealthy_until: Option<Instant>,
+    latency_ewma_ms: Option<f64>,
+    last_slot: u64,
+}
+
+/// 单个 RPC 端点
+struct RpcEndpoint {
+    url: String,
+    sender: HttpSender,
+    limiter: Option<Arc<RpcRateLimiter>>,
+    state: Mutex<EndpointState>,
+}
+
+impl RpcEndpoint {
+    fn is_healthy(&self) -> bool {
+        !matches!(self.state.lock().unhealthy_until, Some(until) if Instant::now() < until)
+    }
+
+    fn latency_ms(&self) -> f64 {
+        self.state.lock().latency_ewma_ms.unwrap_or(f64::MAX)
+    }
+}
+
+/// 端点状态快照（控制 API 使用，URL 只保留主机名以免泄露 API key）
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcEndpointStatus {
+    pub host: String,
+    pub primary: bool,
+    pub healthy: bool,
+    pub latency_ms: Option<f64>,
+    pub consecutive_failures: u32,
+    pub last_slot: u64,
+}
+
+/// 诊断探测结果（check-config 使用）
+#[derive(Debug, Clone)]
+pub struct RpcProbe {
+    pub host: String,
+    pub primary: bool,
+    pub latency: Duration,
+    /// 收到了响应（RPC 业务错误也算可达，只有传输层错误视为不可达）
+    pub reachable: bool,
+    pub slot: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// RPC 池
+pub struct RpcPool {
+    endpoints: Vec<RpcEndpoint>,
+    failover_threshold: u32,
+    unhealthy_cooldown: Duration,
+    max_slot_lag: u64,
+}
+
+impl RpcPool {
+    /// 主端点 rpc_endpoint + 备用端点 rpc_fallback_endpoints
+    pub fn from_config(config: &Config) -> Self {
+        let mut urls = vec![config.rpc_endpoint.clone()];
+        for url in &config.rpc_fallback_endpoints {
+            if !url.is_empty() && !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+
+        if urls.len() > 1 {
+            info!("🌐 RPC 池: 1 个主端点 + {} 个备用端点", urls.len() - 1);
+        }
+
+        Self::new(config, urls)
+    }
+
+    /// 只有一个端点的池（LightSpeed 等专用端点，仍享有限流和延迟统计）
+    pub fn single(config: &Config, url: &str) -> Self {
+        Self::new(config, vec![url.to_string()])
+    }
+
+    fn new(config: &Config, urls: Vec<String>) -> Self {
+        let endpoints = urls
+            .into_iter()
+            .map(|url| RpcEndpoint {
+                sender: HttpSender::new(url.clone()),
+                limiter: rpc_limiter::limiter_for(config, &url),
+                state: Mutex::new(EndpointState::default()),
+                url,
+            })
+            .collect();
+
+        Self {
+            endpoints,
+            failover_threshold: config.rpc_failover_threshold.max(1),
+            unhealthy_cooldown: Duration::from_secs(config.rpc_unhealthy_cooldown_secs),
+            max_slot_lag: config.rpc_max_slot_lag,
+        }
+    }
+
+    /// 创建（阻塞）RpcClient
+    pub fn client(self: &Arc<Self>, commitment: CommitmentConfig) -> RpcClient {
+        RpcClient::new_sender(PoolSender { pool: self.clone() }, RpcClientConfig::with_commitment(commitment))
+    }
+
+    /// 创建异步 RpcClient
+    pub fn nonblocking_client(self: &Arc<Self>, commitment: CommitmentConfig) -> NonblockingRpcClient {
+        NonblockingRpcClient::new_sender(PoolSender { pool: self.clone() }, RpcClientConfig::with_commitment(commitment))
+    }
+
+    /// 路由顺序：健康的主端点 → 健康的备用端点（按延迟）→ 不健康的端点（按配置顺序）
+    fn route(&self) -> Vec<&RpcEndpoint> {
+        let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .partition(|(_, endpoint)| endpoint.is_healthy());
+
+        healthy.sort_by(|(a_index, a), (b_index, b)| {
+            (*a_index != 0)
+                .cmp(&(*b_index != 0))
+                .then(a.latency_ms().total_cmp(&b.latency_ms()))
+        });
+
+        healthy.into_iter().chain(unhealthy).map(|(_, endpoint)| endpoint).collect()
+    }
+
+    fn record_success(&self, endpoint: &RpcEndpoint, latency: Duration) {
+        let mut state = endpoint.state.lock();
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        state.latency_ewma_ms = Some(match state.latency_ewma_ms {
+            Some(ewma) => ewma + LATENCY_EWMA_ALPHA * (latency_ms - ewma),
+            None => latency_ms,
+        });
+        state.consecutive_failures = 0;
+        if state.unhealthy_until.take().is_some() {
+            info!("🌐 RPC 端点恢复健康: {}", Self::host(&endpoint.url));
+        }
+    }
+
+    fn record_failure(&self, endpoint: &RpcEndpoint, reason: &str) {
+        let mut state = endpoint.state.lock();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= self.failover_threshold {
+            if state.unhealthy_until.is_none() {
+                warn!("🌐 RPC 端点不健康，切换到备用端点: {} ({})", Self::host(&endpoint.url), reason);
+            }
+            state.unhealthy_until = Some(Instant::now() + self.unhealthy_cooldown);
+        }
+    }
+
+    /// 只有传输层错误才切换端点（RPC 返回的业务错误换端点也一样）
+    fn is_transport_error(kind: &ClientErrorKind) -> bool {
+        matches!(kind, ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_))
+    }
+
+    /// 定期健康检查（间隔为 0 或只有一个端点时不启动）
+    pub async fn start_health_loop(self: Arc<Self>, interval_secs: u64) {
+        if interval_secs == 0 || self.endpoints.len() < 2 {
+            return;
+        }
+
+        info!("🌐 RPC 健康检查已启动 (间隔 {}s)", interval_secs);
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            self.check_health().await;
+        }
+    }
+
+    async fn check_health(&self) {
+        let checks = self.endpoints.iter().map(|endpoint| async move {
+            let started = Instant::now();
+            let result = endpoint.sender.send(RpcRequest::GetSlot, serde_json::json!([])).await;
+            (endpoint, started.elapsed(), result)
+        });
+
+        let mut slots = Vec::new();
+        for (endpoint, latency, result) in futures::future::join_all(checks).await {
+            match result.ok().and_then(|value| value.as_u64()) {
+                Some(slot) => {
+                    self.record_success(endpoint, latency);
+                    endpoint.state.lock().last_slot = slot;
+                    slots.push((endpoint, slot));
+                }
+                None => self.record_failure(endpoint, "健康检查失败"),
+            }
+        }
+
+        // slot 落后过多的端点数据陈旧，视为不健康
+        let Some(max_slot) = slots.iter().map(|(_, slot)| *slot).max() else {
+            return;
+        };
+        if self.max_slot_lag == 0 {
+            return;
+        }
+        for (endpoint, slot) in slots {
+            let lag = max_slot - slot;
+            if lag > self.max_slot_lag {
+                let mut state = endpoint.state.lock();
+                if state.unhealthy_until.is_none() {
+                    warn!("🌐 RPC 端点落后 {} slots，标记为不健康: {}", lag, Self::host(&endpoint.url));
+                }
+                state.consecutive_failures = self.failover_threshold;
+                state.unhealthy_until = Some(Instant::now() + self.unhealthy_cooldown);
+            }
+        }
+    }
+
+    /// 诊断：逐个端点直接请求 getSlot（绕过路由和限流，不影响健康状态）
+    pub async fn probe(&self) -> Vec<RpcProbe> {
+        let probes = self.endpoints.iter().enumerate().map(|(index, endpoint)| async move {
+            let started = Instant::now();
+            let result = endpoint.sender.send(RpcRequest::GetSlot, serde_json::json!([])).await;
+            let latency = started.elapsed();
+
+            let (reachable, slot, error) = match result {
+                Ok(value) => match value.as_u64() {
+                    Some(slot) => (true, Some(slot), None),
+                    None => (true, None, Some(format!("unexpected getSlot response: {}", value))),
+                },
+                Err(e) => (!Self::is_transport_error(e.kind()), None, Some(e.to_string())),
+            };
+
+            RpcProbe {
+                host: Self::host(&endpoint.url),
+                primary: index == 0,
+                latency,
+                reachable,
+                slot,
+                error,
+            }
+        });
+
+        futures::future::join_all(probes).await
+    }
+
+    /// 各端点状态
+    pub fn status(&self) -> Vec<RpcEndpointStatus> {
+        self.endpoints
+            .iter()
+            .enumerate()
+            .map(|(index, endpoint)| {
+                let healthy = endpoint.is_healthy();
+                let state = endpoint.state.lock();
+                RpcEndpointStatus {
+                    host: Self::host(&endpoint.url),
+                    primary: index == 0,
+                    healthy,
+                    latency_ms: state.latency_ewma_ms,
+                    consecutive_failures: state.consecutive_failures,
+                    last_slot: state.last_slot,
+                }
+            })
+            .collect()
+    }
+
+    fn host(url: &str) -> String {
+        reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| "<invalid url>".to_string())
+    }
+}
+
+/// 按池路由的 RpcSender
+struct PoolSender {
+    pool: Arc<RpcPool>,
+}
+
+#[async_trait]
+impl RpcSender for PoolSender {
+    async fn send(&self, request: RpcRequest, params: serde_json::Value) -> ClientResult<serde_json::Value> {
+        let route = self.pool.route();
+        let mut last_error = None;
+
+        for endpoint in route {
+            if let Some(limiter) = &endpoint.limiter {
+                if request == RpcRequest::SendTransaction {
+                    limiter.consume();
+                } else {
+                    limiter.acquire().await;
+                }
+            }
+
+            let started = Instant::now();
+            match endpoint.sender.send(request, params.clone()).await {
+                Ok(value) => {
+                    self.pool.record_success(endpoint, started.elapsed());
+                    return Ok(value);
+                }
+                Err(e) if RpcPool::is_transport_error(e.kind()) => {
+                    self.pool.record_failure(endpoint, &e.to_string());
+                    last_error = Some(e);
+                }
+                Err(e) => {
+                    // 业务错误说明端点本身可用
+                    self.pool.record_success(endpoint, started.elapsed());
+                    return Err(e);
+                }
+            }
+        }
+
+        Err(last_error.expect("RPC 池至少有一个端点"))
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.pool.endpoints.iter().fold(RpcTransportStats::default(), |mut total, endpoint| {
+            let stats = endpoint.sender.get_transport_stats();
+            total.request_count += stats.request_count;
+            total.elapsed_time += stats.elapsed_time;
+            total.rate_limited_time += stats.rate_limited_time;
+            total
+        })
+    }
+
+    fn url(&self) -> String {
+        self.pool.route().first().map(|endpoint| endpoint.url.clone()).unwrap_or_default()
+    }
+}
@@ -0,0 +1,79 @@
+/// 分优先级的策略信号总线
+///
+/// 策略引擎 → 持仓管理器的信号原先共用一个 mpsc 通道，行情爆发时买入评估产生的信号
+/// 堆积在通道中，紧急卖出只能排在后面
+///
+/// 核心功能:
+/// 1. 退出通道 - Sell（平仓信号）走独立的高优先级通道
+/// 2. 入场通道 - Buy / Hold 及其余信号走普通通道（Hold 只是信息性信号，不与卖出争抢退出通道）
+/// 3. 优先消费 - 接收端总是先取空退出通道，再处理入场通道
+///
+/// 两个通道各自有界，入场通道积压不会占用退出通道的容量
+
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::types::{StrategySignal, WindowMetrics};
+
+/// 信号消息（触发信号的窗口指标 + 信号）
+pub type SignalMessage = (Arc<WindowMetrics>, StrategySignal);
+
+/// 创建信号总线（入场通道容量、退出通道容量）
+pub fn channel(entry_capacity: usize, exit_capacity: usize) -> (SignalSender, SignalReceiver) {
+    let (entry_tx, entry_rx) = mpsc::channel(entry_capacity);
+    let (exit_tx, exit_rx) = mpsc::channel(exit_capacity);
+    (
+        SignalSender { entry_tx, exit_tx },
+        SignalReceiver { entry_rx, exit_rx },
+    )
+}
+
+/// 信号是否需要立即平仓（走退出通道）
+pub fn is_exit_critical(signal: &StrategySignal) -> bool {
+    matches!(signal, StrategySignal::Sell)
+}
+
+/// 信号发送端（按信号类型选择通道）
+#[derive(Clone)]
+pub struct SignalSender {
+    entry_tx: mpsc::Sender<SignalMessage>,
+    exit_tx: mpsc::Sender<SignalMessage>,
+}
+
+impl SignalSender {
+    pub async fn send(&self, message: SignalMessage) -> Result<(), mpsc::error::SendError<SignalMessage>> {
+        if is_exit_critical(&message.1) {
+            self.exit_tx.send(message).await
+        } else {
+            self.entry_tx.send(message).await
+        }
+    }
+
+    /// 入场通道发送端（背压监控使用）
+    pub fn entry_sender(&self) -> &mpsc::Sender<SignalMessage> {
+        &self.entry_tx
+    }
+
+    /// 退出通道发送端（背压监控使用）
+    pub fn exit_sender(&self) -> &mpsc::Sender<SignalMessage> {
+        &self.exit_tx
+    }
+}
+
+/// 信号接收端（退出通道优先）
+pub struct SignalReceiver {
+    entry_rx: mpsc::Receiver<SignalMessage>,
+    exit_rx: mpsc::Receiver<SignalMessage>,
+}
+
+impl SignalReceiver {
+    /// 接收下一条信号（两个通道都关闭且取空后返回 None）
+    pub async fn recv(&mut self) -> Option<SignalMessage> {
+        tokio::select! {
+            biased;
+            Some(message) = self.exit_rx.recv() => Some(message),
+            Some(message) = self.entry_rx.recv() => Some(message),
+            else => None,
+        }
+    }
+}
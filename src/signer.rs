@@ -0,0 +1,271 @@
+/// 交易签名器
+///
+/// 执行器只依赖 `dyn Signer`，热钱包私钥可以不放在交易机器上
+///
+/// 核心功能:
+/// 1. 本地 Keypair - 明文私钥 / keypair 文件 / 加密 keystore / 外部命令（见 keystore.rs）
+/// 2. 远程签名服务 - HTTP 接口签名，私钥保存在独立的签名服务中
+/// 3. Ledger 硬件钱包 - 通过 USB 签名（需要 `ledger` feature，每笔交易需在设备上确认）
+///
+/// 远程签名服务接口（配置 wallet_remote_signer_token 时携带 `Authorization: Bearer <token>`）:
+/// - `GET  {url}/pubkey` → `{"pubkey": "<base58>"}`
+/// - `POST {url}/sign`   `{"pubkey": "<base58>", "message": "<base64>"}` → `{"signature": "<base58>"}`
+///
+/// Signer trait 是同步接口：远程签名在 tokio 工作线程上用 block_in_place 等待，
+/// 每笔交易增加一次签名服务往返延迟。block_in_place 只能在多线程 runtime 上使用，
+/// 远程签名器连接时检查 runtime 类型，current_thread runtime 上直接拒绝（而不是签名时 panic）
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use log::info;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::{Signer, SignerError};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+use crate::config::Config;
+
+/// 执行器共享的签名器
+pub type SharedSigner = Arc<dyn Signer + Send + Sync>;
+
+/// 远程签名请求超时
+const REMOTE_SIGN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 当前线程是否在多线程 tokio runtime 中（block_in_place 的前提）
+fn on_multi_thread_runtime() -> bool {
+    Handle::try_current().is_ok_and(|handle| handle.runtime_flavor() == RuntimeFlavor::MultiThread)
+}
+
+/// 按配置的钱包来源创建签名器
+pub async fn from_config(config: &Config) -> Result<SharedSigner> {
+    if let Some(url) = config.wallet_remote_signer_url.as_deref().filter(|url| !url.is_empty()) {
+        let signer = RemoteSigner::connect(url, config.wallet_remote_signer_token.clone()).await?;
+        return Ok(Arc::new(signer));
+    }
+
+    if let Some(locator) = config.wallet_ledger_locator.as_deref().filter(|locator| !locator.is_empty()) {
+        return ledger::connect(locator, config.wallet_ledger_account).await;
+    }
+
+    Ok(Arc::new(config.get_keypair()?))
+}
+
+#[derive(Deserialize)]
+struct PubkeyResponse {
+    pubkey: String,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: String,
+}
+
+/// 远程签名服务
+pub struct RemoteSigner {
+    url: String,
+    token: Option<String>,
+    pubkey: Pubkey,
+    http_client: reqwest::Client,
+}
+
+impl std::fmt::Debug for RemoteSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteSigner").field("url", &self.url).field("pubkey", &self.pubkey).finish()
+    }
+}
+
+impl RemoteSigner {
+    /// 连接签名服务并读取钱包地址（需要多线程 tokio runtime）
+    pub async fn connect(url: &str, token: Option<String>) -> Result<Self> {
+        if !on_multi_thread_runtime() {
+            anyhow::bail!("remote signer requires a multi-threaded tokio runtime");
+        }
+
+        let url = url.trim_end_matches('/').to_string();
+        let http_client = reqwest::Client::builder()
+            .timeout(REMOTE_SIGN_TIMEOUT)
+            .build()
+            .context("Failed to build remote signer HTTP client")?;
+
+        let mut request = http_client.get(format!("{}/pubkey", url));
+        if let Some(token) = &token {
+            request = request.bearer_auth(token);
+        }
+        let response: PubkeyResponse = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach remote signer: {}", url))?
+            .error_for_status()
+            .context("Remote signer rejected pubkey request")?
+            .json()
+            .await
+            .context("Invalid remote signer pubkey response")?;
+        let pubkey = Pubkey::from_str(&response.pubkey).context("Remote signer returned an invalid pubkey")?;
+
+        info!("🔏 远程签名服务已连接: {} (钱包 {})", url, pubkey);
+        Ok(Self { url, token, pubkey, http_client })
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let mut request = self.http_client
+            .post(format!("{}/sign", self.url))
+            .json(&serde_json::json!({
+                "pubkey": self.pubkey.to_string(),
+                "message": BASE64.encode(message),
+            }));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response: SignResponse = request
+            .send()
+            .await
+            .map_err(|e| SignerError::Connection(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| SignerError::Custom(format!("remote signer rejected request: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| SignerError::Custom(format!("invalid remote signer response: {}", e)))?;
+
+        let signature = Signature::from_str(&response.signature)
+            .map_err(|e| SignerError::Custom(format!("invalid remote signature: {}", e)))?;
+        // 签名服务返回的签名必须能用本钱包地址验证，防止签错钱包或消息
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(SignerError::Custom("remote signature failed verification".to_string()));
+        }
+        Ok(signature)
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        if !on_multi_thread_runtime() {
+            return Err(SignerError::Custom("remote signer requires a multi-threaded tokio runtime".to_string()));
+        }
+        let handle = Handle::current();
+        tokio::task::block_in_place(|| handle.block_on(self.sign(message)))
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "ledger")]
+mod ledger {
+    use anyhow::Result;
+    use log::{error, info};
+    use solana_derivation_path::DerivationPath;
+    use solana_remote_wallet::locator::Locator;
+    use solana_remote_wallet::remote_keypair::{generate_remote_keypair, RemoteKeypair};
+    use solana_remote_wallet::remote_wallet::maybe_wallet_manager;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Signature;
+    use solana_sdk::signer::{Signer, SignerError};
+    use std::sync::{mpsc, Arc};
+
+    use super::SharedSigner;
+
+    struct SignRequest {
+        message: Vec<u8>,
+        reply: mpsc::Sender<Result<Signature, SignerError>>,
+    }
+
+    /// Ledger 签名器
+    ///
+    /// RemoteKeypair 内部使用 Rc，不能跨线程共享：设备由专用线程持有，签名请求通过 channel 转发
+    #[derive(Debug)]
+    struct LedgerSigner {
+        pubkey: Pubkey,
+        requests: mpsc::Sender<SignRequest>,
+    }
+
+    fn open(locator: &str, account: Option<u32>) -> Result<RemoteKeypair> {
+        let wallet_manager = maybe_wallet_manager()
+            .map_err(|e| anyhow::anyhow!("Failed to open hardware wallet manager: {}", e))?
+            .ok_or_else(|| anyhow::anyhow!("No hardware wallet found"))?;
+        let locator = Locator::new_from_path(locator)
+            .map_err(|e| anyhow::anyhow!("Invalid wallet_ledger_locator: {}", e))?;
+        let derivation_path = DerivationPath::new_bip44(account, Some(0));
+        generate_remote_keypair(locator, derivation_path, &wallet_manager, false, "wallet")
+            .map_err(|e| anyhow::anyhow!("Failed to open Ledger: {}", e))
+    }
+
+    pub async fn connect(locator: &str, account: Option<u32>) -> Result<SharedSigner> {
+        let locator = locator.to_string();
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        let (requests, request_rx) = mpsc::channel::<SignRequest>();
+
+        std::thread::Builder::new()
+            .name("ledger-signer".to_string())
+            .spawn(move || {
+                let keypair = match open(&locator, account) {
+                    Ok(keypair) => keypair,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = ready_tx.send(Ok(keypair.pubkey()));
+
+                for request in request_rx {
+                    let result = keypair.try_sign_message(&request.message);
+                    if let Err(e) = &result {
+                        error!("❌ Ledger 签名失败: {}", e);
+                    }
+                    let _ = request.reply.send(result);
+                }
+            })?;
+
+        let pubkey = ready_rx.await.map_err(|_| anyhow::anyhow!("Ledger signer thread exited"))??;
+        info!("🔏 Ledger 已连接 (钱包 {})，每笔交易需要在设备上确认", pubkey);
+        Ok(Arc::new(LedgerSigner { pubkey, requests }))
+    }
+
+    impl Signer for LedgerSigner {
+        fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+            Ok(self.pubkey)
+        }
+
+        fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+            let (reply, reply_rx) = mpsc::channel();
+            self.requests
+                .send(SignRequest { message: message.to_vec(), reply })
+                .map_err(|_| SignerError::Connection("Ledger signer thread exited".to_string()))?;
+
+            let wait = || reply_rx
+                .recv()
+                .unwrap_or_else(|_| Err(SignerError::Connection("Ledger signer thread exited".to_string())));
+            // current_thread runtime 上 block_in_place 会 panic，只能直接阻塞等待
+            if super::on_multi_thread_runtime() {
+                tokio::task::block_in_place(wait)
+            } else {
+                wait()
+            }
+        }
+
+        fn is_interactive(&self) -> bool {
+            true
+        }
+    }
+}
+
+#[cfg(not(feature = "ledger"))]
+mod ledger {
+    use anyhow::Result;
+
+    use super::SharedSigner;
+
+    pub async fn connect(_locator: &str, _account: Option<u32>) -> Result<SharedSigner> {
+        anyhow::bail!("wallet_ledger_locator requires building with `--features ledger`")
+    }
+}
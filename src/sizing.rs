@@ -0,0 +1,79 @@
+/// 按置信度缩放仓位
+///
+/// 动态策略引擎的买入评估给出置信度（0-1），按映射曲线把置信度换算为
+/// confidence_min_buy_sol 到 confidence_max_buy_sol 之间的买入金额
+///
+/// 核心功能:
+/// 1. 区间映射 - 置信度 ≤ confidence_sizing_floor 取最小金额，1.0 取最大金额
+/// 2. 映射曲线 - linear（线性）/ quadratic（高置信度才明显加仓）/ sqrt（中等置信度即加仓）
+///
+/// 只作用于动态策略引擎的买入；阈值触发 / 覆盖规则指定的金额不缩放
+
+use crate::config::Config;
+
+/// 置信度映射曲线
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizingCurve {
+    Linear,
+    Quadratic,
+    Sqrt,
+}
+
+impl SizingCurve {
+    /// 解析曲线名称（不区分大小写）
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "linear" => Some(Self::Linear),
+            "quadratic" => Some(Self::Quadratic),
+            "sqrt" => Some(Self::Sqrt),
+            _ => None,
+        }
+    }
+
+    fn apply(self, x: f64) -> f64 {
+        match self {
+            Self::Linear => x,
+            Self::Quadratic => x * x,
+            Self::Sqrt => x.sqrt(),
+        }
+    }
+}
+
+/// 置信度仓位缩放
+#[derive(Debug, Clone)]
+pub struct ConfidenceSizing {
+    enabled: bool,
+    min_lamports: u64,
+    max_lamports: u64,
+    floor: f64,
+    curve: SizingCurve,
+}
+
+impl ConfidenceSizing {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            enabled: config.confidence_sizing_enabled,
+            min_lamports: (config.confidence_min_buy_sol * 1_000_000_000.0) as u64,
+            max_lamports: (config.confidence_max_buy_sol * 1_000_000_000.0) as u64,
+            floor: config.confidence_sizing_floor,
+            curve: SizingCurve::parse(&config.confidence_sizing_curve).unwrap_or(SizingCurve::Linear),
+        }
+    }
+
+    /// 缩放后可能的最大买入金额（lamports，未启用时为 0）
+    pub fn max_lamports(&self) -> u64 {
+        if self.enabled { self.max_lamports } else { 0 }
+    }
+
+    /// 按置信度计算买入金额（lamports，未启用时返回 None）
+    pub fn size_lamports(&self, confidence: f64) -> Option<u64> {
+        if !self.enabled {
+            return None;
+        }
+
+        let span = (1.0 - self.floor).max(f64::EPSILON);
+        let x = ((confidence - self.floor) / span).clamp(0.0, 1.0);
+        let range = self.max_lamports.saturating_sub(self.min_lamports) as f64;
+        Some(self.min_lamports + (range * self.curve.apply(x)) as u64)
+    }
+}
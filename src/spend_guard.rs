@@ -0,0 +1,251 @@
+/// 支出限额守卫（独立于策略的硬性安全模块）
+///
+/// 执行器在第一次签名前按指令计算这笔交易最多会花掉的 SOL
+/// （PumpFun 买入的 max_sol_cost + 从钱包转出的 SOL，如 tip + 网络费和优先费），超过上限时拒绝签名并告警。
+/// 只看最终指令，不依赖策略 / 仓位管理的计算是否正确
+///
+/// 核心功能:
+/// 1. 单笔上限 - max_spend_per_tx_sol（0 = 不限制）
+/// 2. 每小时上限 - max_spend_per_hour_sol，滚动 1 小时窗口（0 = 不限制）
+/// 3. 重试 - 第一次尝试前按最后一次尝试（tip 和 CU 价格提高后）的最坏支出 × 最大尝试次数预留额度，
+///    每次尝试签名后按该次支出计入（多次尝试都可能上链，按总和计），之后的拒绝 / 超时 / 发送失败都不退回；
+///    交易结束时退回预留中未签名的部分
+/// 4. 卖出 / 钱包清理 - 同样经过守卫并计入每小时窗口，但只受单笔上限约束：
+///    每小时额度被买入用完时不能阻止平仓
+/// 5. 告警 - error 日志 + 事件流 alert 消息；控制 API GET /spend 查询额度
+
+use log::error;
+use parking_lot::Mutex;
+use serde::Serialize;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::error::{BotError, BotResult};
+use crate::event_feed::EventFeed;
+
+/// 每小时上限的滚动窗口
+const SPEND_WINDOW: Duration = Duration::from_secs(3600);
+
+const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+
+/// 钱包通过 system 程序转出的 lamports（Transfer / CreateAccount，如 tip 转账）
+pub fn system_outflow(instructions: &[Instruction], payer: &Pubkey) -> u64 {
+    let system_program = Pubkey::try_from(SYSTEM_PROGRAM).unwrap();
+    instructions
+        .iter()
+        .filter(|ix| {
+            ix.program_id == system_program
+                && ix.accounts.first().is_some_and(|meta| meta.pubkey == *payer)
+                && ix.data.len() >= 12
+                // 0 = CreateAccount, 2 = Transfer，lamports 都在第 4..12 字节
+                && matches!(u32::from_le_bytes(ix.data[0..4].try_into().unwrap_or([0xff; 4])), 0 | 2)
+        })
+        .map(|ix| u64::from_le_bytes(ix.data[4..12].try_into().unwrap_or([0u8; 8])))
+        .sum()
+}
+
+/// 一笔已授权的支出
+#[derive(Debug)]
+struct SpendEntry {
+    id: u64,
+    at: Instant,
+    /// 预留额度（交易结束前占用）
+    reserved: u64,
+    /// 已签名尝试的支出总和（不再退回）
+    charged: u64,
+}
+
+impl SpendEntry {
+    /// 计入每小时窗口的额度
+    fn lamports(&self) -> u64 {
+        self.reserved.max(self.charged)
+    }
+}
+
+/// 额度快照（控制 API 使用）
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendSnapshot {
+    /// 单笔上限（SOL，None = 不限制）
+    pub max_per_tx_sol: Option<f64>,
+    /// 每小时上限（SOL，None = 不限制）
+    pub max_per_hour_sol: Option<f64>,
+    /// 最近 1 小时已授权支出（SOL）
+    pub spent_last_hour_sol: f64,
+    /// 本小时剩余额度（SOL，None = 不限制）
+    pub remaining_hour_sol: Option<f64>,
+    /// 启动以来拒绝签名的次数
+    pub rejections: u64,
+}
+
+/// 支出限额守卫
+pub struct SpendGuard {
+    max_tx_lamports: u64,
+    max_hourly_lamports: u64,
+    window: Mutex<VecDeque<SpendEntry>>,
+    next_id: AtomicU64,
+    rejections: AtomicU64,
+    event_feed: Arc<EventFeed>,
+}
+
+impl SpendGuard {
+    pub fn from_config(config: &Config, event_feed: Arc<EventFeed>) -> Self {
+        Self {
+            max_tx_lamports: (config.max_spend_per_tx_sol * 1_000_000_000.0) as u64,
+            max_hourly_lamports: (config.max_spend_per_hour_sol * 1_000_000_000.0) as u64,
+            window: Mutex::new(VecDeque::new()),
+            next_id: AtomicU64::new(1),
+            rejections: AtomicU64::new(0),
+            event_feed,
+        }
+    }
+
+    /// 开始一笔买入（预留的额度在 drop 时退回，已签名尝试的支出除外）
+    pub fn begin(self: &Arc<Self>, mint: Pubkey) -> SpendTicket {
+        self.ticket(mint, false)
+    }
+
+    /// 开始一笔卖出 / 钱包清理交易（只检查单笔上限，支出仍计入每小时窗口）
+    pub fn begin_exit(self: &Arc<Self>, mint: Pubkey) -> SpendTicket {
+        self.ticket(mint, true)
+    }
+
+    fn ticket(self: &Arc<Self>, mint: Pubkey, exit: bool) -> SpendTicket {
+        SpendTicket {
+            guard: self.clone(),
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            mint,
+            exit,
+            attempt_lamports: 0,
+            charged: 0,
+        }
+    }
+
+    fn prune(window: &mut VecDeque<SpendEntry>) {
+        while window.front().is_some_and(|entry| entry.at.elapsed() > SPEND_WINDOW) {
+            window.pop_front();
+        }
+    }
+
+    /// 检查并预留额度：lamports 为单次尝试的支出（受单笔上限约束），total 为这笔交易需要占用的额度
+    /// （同一 ticket 的多次预留取最大值，不超过已预留的额度时不会拒绝）
+    fn authorize(&self, id: u64, mint: &Pubkey, lamports: u64, total: u64, exit: bool) -> BotResult<()> {
+        if self.max_tx_lamports > 0 && lamports > self.max_tx_lamports {
+            return Err(self.reject(mint, format!(
+                "transaction spend {:.4} SOL exceeds per-transaction cap {:.4} SOL",
+                lamports as f64 / 1_000_000_000.0,
+                self.max_tx_lamports as f64 / 1_000_000_000.0,
+            )));
+        }
+
+        let mut window = self.window.lock();
+        Self::prune(&mut window);
+
+        let reserved = window.iter().find(|entry| entry.id == id).map_or(0, SpendEntry::lamports);
+        let spent: u64 = window.iter().filter(|entry| entry.id != id).map(SpendEntry::lamports).sum();
+        if !exit && self.max_hourly_lamports > 0 && total > reserved && spent + total > self.max_hourly_lamports {
+            drop(window);
+            return Err(self.reject(mint, format!(
+                "hourly spend would reach {:.4} SOL, exceeding cap {:.4} SOL",
+                (spent + total) as f64 / 1_000_000_000.0,
+                self.max_hourly_lamports as f64 / 1_000_000_000.0,
+            )));
+        }
+
+        match window.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => entry.reserved = entry.reserved.max(total),
+            None => window.push_back(SpendEntry { id, at: Instant::now(), reserved: total, charged: 0 }),
+        }
+        Ok(())
+    }
+
+    /// 记录已签名尝试的支出总和
+    fn charge(&self, id: u64, charged: u64) {
+        if let Some(entry) = self.window.lock().iter_mut().find(|entry| entry.id == id) {
+            entry.charged = charged;
+        }
+    }
+
+    /// 交易结束：退回预留中未签名的部分（没有尝试签名时整笔移除）
+    fn settle(&self, id: u64, charged: u64) {
+        let mut window = self.window.lock();
+        if charged == 0 {
+            window.retain(|entry| entry.id != id);
+        } else if let Some(entry) = window.iter_mut().find(|entry| entry.id == id) {
+            entry.reserved = 0;
+            entry.charged = charged;
+        }
+    }
+
+    fn reject(&self, mint: &Pubkey, reason: String) -> BotError {
+        let rejections = self.rejections.fetch_add(1, Ordering::Relaxed) + 1;
+        error!("🛑 支出限额触发，拒绝签名 {}: {} (累计 {} 次)", mint, reason, rejections);
+        self.event_feed.publish_alert("spend_limit", &mint.to_string(), &reason);
+        BotError::SpendLimitExceeded { reason }
+    }
+
+    /// 当前额度
+    pub fn snapshot(&self) -> SpendSnapshot {
+        let mut window = self.window.lock();
+        Self::prune(&mut window);
+        let spent: u64 = window.iter().map(SpendEntry::lamports).sum();
+
+        let cap = |lamports: u64| (lamports > 0).then(|| lamports as f64 / 1_000_000_000.0);
+        SpendSnapshot {
+            max_per_tx_sol: cap(self.max_tx_lamports),
+            max_per_hour_sol: cap(self.max_hourly_lamports),
+            spent_last_hour_sol: spent as f64 / 1_000_000_000.0,
+            remaining_hour_sol: cap(self.max_hourly_lamports)
+                .map(|max| (max - spent as f64 / 1_000_000_000.0).max(0.0)),
+            rejections: self.rejections.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 一笔交易（含全部重试）的支出额度
+pub struct SpendTicket {
+    guard: Arc<SpendGuard>,
+    id: u64,
+    mint: Pubkey,
+    exit: bool,
+    /// 下一次签名的尝试支出（最近一次授权 / 预留的单次支出）
+    attempt_lamports: u64,
+    /// 已签名尝试的支出总和
+    charged: u64,
+}
+
+impl SpendTicket {
+    /// 第一次签名前按单次尝试的最坏支出 × 最大尝试次数预留：超过上限时返回 SpendLimitExceeded
+    ///
+    /// 单笔上限按单次尝试检查；之后每次尝试的授权不超过预留额度时不会拒绝
+    pub fn reserve(&mut self, lamports: u64, attempts: u32) -> BotResult<()> {
+        let total = lamports.saturating_mul(attempts.max(1) as u64);
+        self.guard.authorize(self.id, &self.mint, lamports, self.charged + total, self.exit)?;
+        self.attempt_lamports = lamports;
+        Ok(())
+    }
+
+    /// 签名前按本次尝试的最终指令授权（已签名尝试的支出 + 本次支出超出预留时按上限重新检查）
+    pub fn authorize(&mut self, lamports: u64) -> BotResult<()> {
+        self.guard.authorize(self.id, &self.mint, lamports, self.charged + lamports, self.exit)?;
+        self.attempt_lamports = lamports;
+        Ok(())
+    }
+
+    /// 一次尝试已签名：按本次授权的支出计入每小时窗口，不再退回
+    /// （签名后的交易可能已被中继转发，即使之后发送报错或确认超时也可能上链花掉 SOL；
+    /// 多次尝试都可能上链，支出按总和计）
+    pub fn signed(&mut self) {
+        self.charged += self.attempt_lamports;
+        self.guard.charge(self.id, self.charged);
+    }
+}
+
+impl Drop for SpendTicket {
+    fn drop(&mut self) {
+        self.guard.settle(self.id, self.charged);
+    }
+}
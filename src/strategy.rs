@@ -1,153 +1,128 @@
+use chrono::{DateTime, Utc};
 use log::{debug, info, warn};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use solana_sdk::pubkey::Pubkey;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
 use crate::aggregator::Aggregator;
 use crate::config::Config;
-use crate::dynamic_strategy::{DynamicStrategyConfig, DynamicStrategyEngine};
-use crate::types::{BondingCurveState, StrategySignal, WindowMetrics};
+use crate::decision_trace::{DecisionNotes, DecisionTrace};
+use crate::dynamic_strategy::{BuyTriggers, DynamicStrategyConfig, DynamicStrategyEngine, SellTriggers, StrategyMode};
+use crate::event_feed::EventFeed;
+use crate::experiment::ShadowExperiment;
+use crate::metrics_export::MetricsExporter;
+use crate::signal_bus::SignalSender;
+use crate::strategy_overrides::{StrategyOverride, StrategyOverrideTable};
+use crate::types::{BondingCurveState, ScoreBreakdown, StrategySignal, WindowMetrics};
+
+/// 前几笔交易视为首波
+const FIRST_WAVE_MAX_EVENTS: usize = 5;
+
+/// 每个评估 worker 的指标队列容量
+const WORKER_QUEUE_CAPACITY: usize = 256;
 
 /// 策略引擎（增强版）
 ///
 /// 集成了动态策略引擎和高级指标
 pub struct StrategyEngine {
     config: Arc<Config>,
-    signal_tx: mpsc::Sender<(Arc<WindowMetrics>, StrategySignal)>,
+    /// 信号总线（卖出信号走高优先级通道）
+    signal_tx: SignalSender,
     /// 动态策略引擎
     dynamic_strategy: Arc<RwLock<DynamicStrategyEngine>>,
     /// 聚合器引用（用于获取高级指标，保留作为备用）
     #[allow(dead_code)]
     aggregator: Arc<Aggregator>,
+    /// 单币策略覆盖规则（按 mint / 创建者）
+    overrides: StrategyOverrideTable,
+    /// A/B 实验的影子策略（未启用时为 None）
+    experiment: Option<Arc<ShadowExperiment>>,
+    /// WebSocket 事件流（推送指标和信号）
+    event_feed: Arc<EventFeed>,
+    /// 决策追踪（每次判定命中 / 拒绝的条件）
+    decisions: Arc<DecisionTrace>,
+    /// 窗口指标导出（离线研究）
+    metrics_export: Arc<MetricsExporter>,
+    /// 各评估 worker 的指标队列（弱引用，供背压监控采样，不阻止分发器退出后队列关闭）
+    worker_queues: Mutex<Vec<mpsc::WeakSender<Arc<WindowMetrics>>>>,
 }
 
 impl StrategyEngine {
     pub fn new(
         config: Arc<Config>,
-        signal_tx: mpsc::Sender<(Arc<WindowMetrics>, StrategySignal)>,
+        signal_tx: SignalSender,
         aggregator: Arc<Aggregator>,
+        experiment: Option<Arc<ShadowExperiment>>,
+        event_feed: Arc<EventFeed>,
+        decisions: Arc<DecisionTrace>,
+        metrics_export: Arc<MetricsExporter>,
     ) -> Self {
         // 从配置创建动态策略引擎
         let dynamic_config = Self::create_dynamic_config_from_env(&config);
         let dynamic_strategy = Arc::new(RwLock::new(DynamicStrategyEngine::new(dynamic_config)));
 
+        // 加载单币策略覆盖规则（加载失败时不使用覆盖）
+        let overrides = StrategyOverrideTable::from_config(&config).unwrap_or_else(|e| {
+            warn!("⚠️  加载策略覆盖规则失败: {:#}, 不使用覆盖规则", e);
+            StrategyOverrideTable::default()
+        });
+
         info!("🎯 策略引擎已初始化（增强版）");
         info!("   ✅ 动态策略引擎已启用");
         info!("   策略模式: {}", config.dynamic_strategy_mode);
+        if !overrides.is_empty() {
+            info!("   ✅ 单币策略覆盖规则已启用");
+        }
 
         Self {
             config,
             signal_tx,
             dynamic_strategy,
             aggregator,
+            overrides,
+            experiment,
+            event_feed,
+            decisions,
+            metrics_export,
+            worker_queues: Mutex::new(Vec::new()),
         }
     }
 
     /// 从环境变量创建动态策略配置
     fn create_dynamic_config_from_env(config: &Config) -> DynamicStrategyConfig {
-        use crate::dynamic_strategy::{BuyTriggers, SellTriggers, AdaptiveParams, StrategyMode};
 
         // 🔥 优先使用布尔值开关（如果启用）
-        let mode = if config.enable_custom_mode {
-            info!("🎯 启用自定义模式 (ENABLE_CUSTOM_MODE=true)");
-            StrategyMode::Custom
-        } else if config.enable_conservative_mode {
-            info!("🎯 启用保守模式 (ENABLE_CONSERVATIVE_MODE=true)");
-            StrategyMode::Conservative
-        } else if config.enable_aggressive_mode {
-            info!("🎯 启用激进模式 (ENABLE_AGGRESSIVE_MODE=true)");
-            StrategyMode::Aggressive
-        } else if config.enable_balanced_mode {
-            info!("🎯 启用平衡模式 (ENABLE_BALANCED_MODE=true)");
-            StrategyMode::Balanced
-        } else {
+        let mode = StrategyMode::from_config(config);
+        match mode {
+            StrategyMode::Custom if config.enable_custom_mode => info!("🎯 启用自定义模式 (ENABLE_CUSTOM_MODE=true)"),
+            StrategyMode::Conservative if config.enable_conservative_mode => info!("🎯 启用保守模式 (ENABLE_CONSERVATIVE_MODE=true)"),
+            StrategyMode::Aggressive if config.enable_aggressive_mode => info!("🎯 启用激进模式 (ENABLE_AGGRESSIVE_MODE=true)"),
+            StrategyMode::Balanced if config.enable_balanced_mode => info!("🎯 启用平衡模式 (ENABLE_BALANCED_MODE=true)"),
             // 如果所有布尔值都是false，回退到字符串模式
-            info!("⚠️  所有模式开关都是false，使用 DYNAMIC_STRATEGY_MODE={}", config.dynamic_strategy_mode);
-            match config.dynamic_strategy_mode.as_str() {
-                "conservative" => StrategyMode::Conservative,
-                "aggressive" => StrategyMode::Aggressive,
-                "custom" => StrategyMode::Custom,
-                _ => StrategyMode::Balanced,
-            }
+            _ => info!("⚠️  所有模式开关都是false，使用 DYNAMIC_STRATEGY_MODE={}", config.dynamic_strategy_mode),
+        }
+
+        Self::dynamic_config_for_mode(config, mode)
+    }
+
+    /// 按指定策略模式创建动态策略配置（A/B 实验的影子策略也使用）
+    pub fn dynamic_config_for_mode(config: &Config, mode: StrategyMode) -> DynamicStrategyConfig {
+        use crate::dynamic_strategy::{AdaptiveParams, RegimeTriggers};
+
+        let buy_triggers = Self::buy_triggers_for_mode(config, mode);
+        let sell_triggers = SellTriggers {
+            take_profit_multiplier: config.take_profit_multiplier,
+            stop_loss_multiplier: config.stop_loss_multiplier,
+            min_hold_duration_secs: config.hold_min_duration_secs,
+            max_hold_duration_secs: config.hold_max_duration_secs,
+            momentum_decay_threshold: config.exit_buy_ratio_threshold,
         };
 
-        let (buy_triggers, sell_triggers) = match mode {
-            StrategyMode::Conservative => (
-                BuyTriggers {
-                    min_buy_ratio: config.conservative_min_buy_ratio,
-                    min_net_inflow_sol: config.net_inflow_threshold_sol,
-                    min_acceleration: config.conservative_min_acceleration,
-                    max_slippage: config.conservative_max_slippage,
-                    min_high_frequency_trades: config.conservative_min_high_frequency_trades,
-                    min_liquidity_depth: config.conservative_min_liquidity_depth,
-                    max_price_impact: config.conservative_max_price_impact,
-                    min_composite_score: config.conservative_min_composite_score,
-                },
-                SellTriggers {
-                    take_profit_multiplier: config.take_profit_multiplier,
-                    stop_loss_multiplier: config.stop_loss_multiplier,
-                    min_hold_duration_secs: config.hold_min_duration_secs,
-                    max_hold_duration_secs: config.hold_max_duration_secs,
-                    momentum_decay_threshold: config.exit_buy_ratio_threshold,
-                },
-            ),
-            StrategyMode::Balanced => (
-                BuyTriggers {
-                    min_buy_ratio: config.balanced_min_buy_ratio,
-                    min_net_inflow_sol: config.net_inflow_threshold_sol,
-                    min_acceleration: config.balanced_min_acceleration,
-                    max_slippage: config.balanced_max_slippage,
-                    min_high_frequency_trades: config.balanced_min_high_frequency_trades,
-                    min_liquidity_depth: config.balanced_min_liquidity_depth,
-                    max_price_impact: config.balanced_max_price_impact,
-                    min_composite_score: config.balanced_min_composite_score,
-                },
-                SellTriggers {
-                    take_profit_multiplier: config.take_profit_multiplier,
-                    stop_loss_multiplier: config.stop_loss_multiplier,
-                    min_hold_duration_secs: config.hold_min_duration_secs,
-                    max_hold_duration_secs: config.hold_max_duration_secs,
-                    momentum_decay_threshold: config.exit_buy_ratio_threshold,
-                },
-            ),
-            StrategyMode::Aggressive => (
-                BuyTriggers {
-                    min_buy_ratio: config.aggressive_min_buy_ratio,
-                    min_net_inflow_sol: config.net_inflow_threshold_sol,
-                    min_acceleration: config.aggressive_min_acceleration,
-                    max_slippage: config.aggressive_max_slippage,
-                    min_high_frequency_trades: config.aggressive_min_high_frequency_trades,
-                    min_liquidity_depth: config.aggressive_min_liquidity_depth,
-                    max_price_impact: config.aggressive_max_price_impact,
-                    min_composite_score: config.aggressive_min_composite_score,
-                },
-                SellTriggers {
-                    take_profit_multiplier: config.take_profit_multiplier,
-                    stop_loss_multiplier: config.stop_loss_multiplier,
-                    min_hold_duration_secs: config.hold_min_duration_secs,
-                    max_hold_duration_secs: config.hold_max_duration_secs,
-                    momentum_decay_threshold: config.exit_buy_ratio_threshold,
-                },
-            ),
-            StrategyMode::Custom => (
-                BuyTriggers {
-                    min_buy_ratio: config.custom_min_buy_ratio,
-                    min_net_inflow_sol: config.net_inflow_threshold_sol,
-                    min_acceleration: config.custom_min_acceleration,
-                    max_slippage: config.custom_max_slippage,
-                    min_high_frequency_trades: config.custom_min_high_frequency_trades,
-                    min_liquidity_depth: config.custom_min_liquidity_depth,
-                    max_price_impact: config.custom_max_price_impact,
-                    min_composite_score: config.custom_min_composite_score,
-                },
-                SellTriggers {
-                    take_profit_multiplier: config.take_profit_multiplier,
-                    stop_loss_multiplier: config.stop_loss_multiplier,
-                    min_hold_duration_secs: config.hold_min_duration_secs,
-                    max_hold_duration_secs: config.hold_max_duration_secs,
-                    momentum_decay_threshold: config.exit_buy_ratio_threshold,
-                },
-            ),
+        // 波动状态切换：剧烈市场使用保守参数，平静市场使用激进参数
+        let regime_triggers = RegimeTriggers {
+            conservative: Self::buy_triggers_for_mode(config, StrategyMode::Conservative),
+            aggressive: Self::buy_triggers_for_mode(config, StrategyMode::Aggressive),
         };
 
         DynamicStrategyConfig {
@@ -155,26 +130,149 @@ impl StrategyEngine {
             buy_triggers,
             sell_triggers,
             adaptive_params: AdaptiveParams {
-                enable_volatility_adaptation: true,
-                enable_time_adaptation: true,
-                enable_success_feedback: true,
-                volatility_adjustment_factor: 1.0,
+                enable_volatility_adaptation: config.enable_volatility_adaptation,
+                enable_time_adaptation: config.enable_time_adaptation,
+                enable_success_feedback: config.enable_success_feedback,
+                success_adjustment_factor: 1.0,
+                success_feedback_step: config.success_feedback_step,
+                success_feedback_win_streak: config.success_feedback_win_streak,
+                success_feedback_min_factor: config.success_feedback_min_factor,
+                success_feedback_max_factor: config.success_feedback_max_factor,
+                time_adjustment_factor: 1.0,
+                time_bucket_min_trades: config.time_bucket_min_trades,
+                bad_hour_win_rate: config.bad_hour_win_rate,
+                bad_hour_score_multiplier: config.bad_hour_score_multiplier,
+                bad_hour_size_multiplier: config.bad_hour_size_multiplier,
+                regime_window_secs: config.regime_window_secs,
+                regime_sample_mints: config.regime_sample_mints,
+                regime_min_samples: config.regime_min_samples,
+                regime_calm_volatility: config.regime_calm_volatility,
+                regime_volatile_volatility: config.regime_volatile_volatility,
+            },
+            regime_triggers: Some(regime_triggers),
+        }
+    }
+
+    /// 某个策略模式的买入条件（来自配置）
+    fn buy_triggers_for_mode(config: &Config, mode: StrategyMode) -> BuyTriggers {
+        match mode {
+            StrategyMode::Conservative => BuyTriggers {
+                min_buy_ratio: config.conservative_min_buy_ratio,
+                min_net_inflow_sol: config.net_inflow_threshold_sol,
+                min_acceleration: config.conservative_min_acceleration,
+                max_slippage: config.conservative_max_slippage,
+                min_high_frequency_trades: config.conservative_min_high_frequency_trades,
+                min_liquidity_depth: config.conservative_min_liquidity_depth,
+                max_price_impact: config.conservative_max_price_impact,
+                min_composite_score: config.conservative_min_composite_score,
+            },
+            StrategyMode::Balanced => BuyTriggers {
+                min_buy_ratio: config.balanced_min_buy_ratio,
+                min_net_inflow_sol: config.net_inflow_threshold_sol,
+                min_acceleration: config.balanced_min_acceleration,
+                max_slippage: config.balanced_max_slippage,
+                min_high_frequency_trades: config.balanced_min_high_frequency_trades,
+                min_liquidity_depth: config.balanced_min_liquidity_depth,
+                max_price_impact: config.balanced_max_price_impact,
+                min_composite_score: config.balanced_min_composite_score,
+            },
+            StrategyMode::Aggressive => BuyTriggers {
+                min_buy_ratio: config.aggressive_min_buy_ratio,
+                min_net_inflow_sol: config.net_inflow_threshold_sol,
+                min_acceleration: config.aggressive_min_acceleration,
+                max_slippage: config.aggressive_max_slippage,
+                min_high_frequency_trades: config.aggressive_min_high_frequency_trades,
+                min_liquidity_depth: config.aggressive_min_liquidity_depth,
+                max_price_impact: config.aggressive_max_price_impact,
+                min_composite_score: config.aggressive_min_composite_score,
+            },
+            StrategyMode::Custom => BuyTriggers {
+                min_buy_ratio: config.custom_min_buy_ratio,
+                min_net_inflow_sol: config.net_inflow_threshold_sol,
+                min_acceleration: config.custom_min_acceleration,
+                max_slippage: config.custom_max_slippage,
+                min_high_frequency_trades: config.custom_min_high_frequency_trades,
+                min_liquidity_depth: config.custom_min_liquidity_depth,
+                max_price_impact: config.custom_max_price_impact,
+                min_composite_score: config.custom_min_composite_score,
             },
         }
     }
 
     /// 启动策略引擎
-    pub async fn start(&self, mut metrics_rx: mpsc::Receiver<Arc<WindowMetrics>>) {
-        info!("Strategy engine started");
+    ///
+    /// 🔥 优化: strategy_workers > 1 时按 mint 分片到多个 worker 并行评估，
+    /// 同一 mint 的指标始终进入同一 worker，保持该 mint 的评估顺序
+    pub async fn start(self: Arc<Self>, mut metrics_rx: mpsc::Receiver<Arc<WindowMetrics>>) {
+        let workers = self.config.strategy_workers.max(1);
+        info!("Strategy engine started ({} workers)", workers);
+
+        if workers == 1 {
+            self.run_worker(metrics_rx).await;
+            return;
+        }
+
+        let mut shards = Vec::with_capacity(workers);
+        for _ in 0..workers {
+            let (shard_tx, shard_rx) = mpsc::channel(WORKER_QUEUE_CAPACITY);
+            let engine = self.clone();
+            tokio::spawn(async move { engine.run_worker(shard_rx).await });
+            shards.push(shard_tx);
+        }
+        *self.worker_queues.lock() = shards.iter().map(mpsc::Sender::downgrade).collect();
+
+        while let Some(metrics_arc) = metrics_rx.recv().await {
+            let shard = &shards[Self::shard_of(&metrics_arc.mint, workers)];
+            if shard.send(metrics_arc).await.is_err() {
+                log::error!("Strategy worker stopped, dispatcher exiting");
+                return;
+            }
+        }
+    }
 
+    /// 各评估 worker 的指标队列（单 worker 直接消费指标通道，或尚未启动时为空）
+    pub fn worker_queues(&self) -> Vec<mpsc::Sender<Arc<WindowMetrics>>> {
+        self.worker_queues.lock().iter().filter_map(mpsc::WeakSender::upgrade).collect()
+    }
+
+    /// mint 所属的 worker（mint 本身是均匀分布的公钥，直接取前 8 字节）
+    fn shard_of(mint: &Pubkey, workers: usize) -> usize {
+        let bytes = mint.to_bytes();
+        let key = u64::from_le_bytes(bytes[..8].try_into().expect("pubkey has 32 bytes"));
+        (key % workers as u64) as usize
+    }
+
+    /// 单个评估 worker：按顺序评估收到的指标并发送信号
+    async fn run_worker(&self, mut metrics_rx: mpsc::Receiver<Arc<WindowMetrics>>) {
         while let Some(metrics_arc) = metrics_rx.recv().await {
-            let signal = self.evaluate_metrics(&metrics_arc);
+            self.event_feed.publish_metrics(&metrics_arc);
+            self.metrics_export.record(&metrics_arc);
+            // 🔥 新增: 记录每次判定命中 / 拒绝的条件（GET /decisions/<mint>）
+            let mut notes = DecisionNotes::new(self.decisions.is_enabled());
+            let mut score = None;
+            let signal = self.evaluate_metrics(&metrics_arc, &mut notes, &mut score);
+            self.decisions.record(&metrics_arc, &signal, notes);
+
+            // 🔥 新增: 动态策略引擎买入时附带综合评分拆解（随信号进入事件流和交易日志）
+            let metrics_arc = match score {
+                Some(score) => Arc::new(WindowMetrics {
+                    score_breakdown: Some(score),
+                    ..(*metrics_arc).clone()
+                }),
+                None => metrics_arc,
+            };
+
+            // A/B 实验：影子策略 B 评估同一条指标（纸面交易）
+            if let Some(experiment) = &self.experiment {
+                experiment.on_metrics(&metrics_arc);
+            }
 
             if signal != StrategySignal::None {
                 debug!(
                     "Signal generated for {}: {:?}",
                     metrics_arc.mint, signal
                 );
+                self.event_feed.publish_signal(&metrics_arc, &signal);
 
                 if let Err(e) = self.signal_tx.send((metrics_arc, signal)).await {
                     log::error!("Failed to send signal: {}", e);
@@ -183,48 +281,208 @@ impl StrategyEngine {
         }
     }
 
+    /// 解析某个 token 的策略覆盖规则（mint > 创建者 > 未知创建者）
+    pub fn resolve_override(&self, mint: &Pubkey, creator: &Pubkey) -> Option<StrategyOverride> {
+        self.overrides.resolve(mint, creator)
+    }
+
+    /// 首波狙击: 创建者首笔买入规模检查
+    ///
+    /// 过小说明创建者没有利益绑定，过大则有砸盘风险；未观察到 dev 买入时仅在设置下限时拒绝
+    fn check_dev_buy(&self, metrics: &WindowMetrics, notes: &mut DecisionNotes) -> bool {
+        let min = self.config.first_wave_min_dev_buy_sol;
+        let max = self.config.first_wave_max_dev_buy_sol;
+
+        match metrics.dev_initial_buy_sol {
+            Some(dev_buy) if min > 0.0 && dev_buy < min => {
+                debug!("❌ 创建者首笔买入过小: {:.4} SOL < {:.4} SOL", dev_buy, min);
+                notes.push(|| format!("first_wave: dev_buy {:.4} SOL < {:.4} SOL", dev_buy, min));
+                false
+            }
+            Some(dev_buy) if max > 0.0 && dev_buy > max => {
+                debug!("❌ 创建者首笔买入过大: {:.4} SOL > {:.4} SOL", dev_buy, max);
+                notes.push(|| format!("first_wave: dev_buy {:.4} SOL > {:.4} SOL", dev_buy, max));
+                false
+            }
+            Some(_) => true,
+            None if min > 0.0 => {
+                debug!("❌ 未观察到创建者首笔买入: {}", metrics.mint);
+                notes.push(|| "first_wave: dev buy not observed".to_string());
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// token 是否超过常规策略的年龄上限（max_token_age_secs = 0 时不检查）
+    fn is_stale_token(&self, metrics: &WindowMetrics) -> bool {
+        if self.config.max_token_age_secs == 0 {
+            return false;
+        }
+        match metrics.token_age_secs {
+            Some(age) => age > self.config.max_token_age_secs,
+            // 未观察到创建事件：启动前就已上线，年龄未知
+            None => self.config.reject_unknown_token_age,
+        }
+    }
+
+    /// 是否处于首波狙击阶段（启用首波狙击且前 FIRST_WAVE_MAX_EVENTS 笔交易内）
+    pub fn is_first_wave(&self, metrics: &WindowMetrics) -> bool {
+        self.config.enable_first_wave_sniper && metrics.event_count <= FIRST_WAVE_MAX_EVENTS
+    }
+
+    /// 是否仍处于预热观察期（观察时长和累计事件数都需达到配置值）
+    fn in_warmup(&self, metrics: &WindowMetrics) -> bool {
+        self.config.in_warmup(metrics.observed_secs, metrics.total_event_count)
+    }
+
     /// 评估指标并生成信号（增强版）
-    fn evaluate_metrics(&self, metrics: &WindowMetrics) -> StrategySignal {
+    ///
+    /// 命中 / 拒绝的条件写入 notes（决策追踪）；动态策略引擎买入时 score 为综合评分拆解
+    fn evaluate_metrics(
+        &self,
+        metrics: &WindowMetrics,
+        notes: &mut DecisionNotes,
+        score: &mut Option<ScoreBreakdown>,
+    ) -> StrategySignal {
+        // 采集新币首段波动率（市场波动状态判断）
+        if self.config.enable_volatility_adaptation {
+            if let Some(ref advanced) = metrics.advanced_metrics {
+                self.dynamic_strategy.read().observe_launch(metrics, advanced);
+            }
+        }
+
+        // 🩸 卖压触发：持仓中且窗口内累计卖出在阈值以上，立即卖出（聚合器只为持仓 mint 设置，不看其他指标，走高优先级通道）
+        if let Some(sold_sol) = metrics.sell_pressure_sol {
+            notes.push(|| format!("sell pressure trigger: {:.4} SOL sold", sold_sol));
+            return StrategySignal::Sell;
+        }
+
+        // 单币覆盖规则禁止买入
+        if let Some(rule) = self.resolve_override(&metrics.mint, &metrics.creator) {
+            if rule.is_disabled() {
+                debug!("🚫 覆盖规则禁止买入: {} (创建者: {})", metrics.mint, metrics.creator);
+                notes.push(|| "override rule disables buying".to_string());
+                return StrategySignal::None;
+            }
+        }
+
+        // ⏳ 预热观察期：只收集指标，抑制买入（首波狙击可配置跳过）
+        // 阈值 / 毕业触发同样受约束（聚合器在预热期内不评估这两个触发）
+        let in_warmup = self.in_warmup(metrics);
+
         // 🎯 阈值触发策略：优先级最高
-        if self.config.enable_threshold_trigger {
+        if self.config.enable_threshold_trigger && !in_warmup {
             if let Some(buy_amount) = metrics.threshold_buy_amount {
                 info!("🎯 阈值触发策略命中！");
                 info!("   Mint: {}", metrics.mint);
                 info!("   买入金额: {:.4} SOL", buy_amount);
                 info!("   立即执行买入！");
+                notes.push(|| format!("threshold trigger: buy {:.4} SOL", buy_amount));
                 return StrategySignal::Buy;
             }
         }
 
+        // 🎓 毕业狙击策略：完成度越过阈值且资金流入强劲
+        if self.config.enable_graduation_sniper && metrics.graduation_triggered && !in_warmup {
+            info!("🎓 毕业狙击策略命中！");
+            info!("   Mint: {}", metrics.mint);
+            info!("   曲线完成度: {:.2}%", metrics.curve_progress_percent);
+            info!("   立即执行买入！");
+            notes.push(|| format!("graduation trigger: progress {:.2}%", metrics.curve_progress_percent));
+            return StrategySignal::Buy;
+        }
+
         // 🚀 首波狙击逻辑：检测新币的第一波大额流入
-        if self.config.enable_first_wave_sniper {
-            let is_first_wave = metrics.event_count <= 5; // 前5笔交易视为首波
-            if is_first_wave {
-                let net_inflow_sol = metrics.net_inflow_sol as f64 / 1_000_000_000.0;
-
-                // 首波快速狙击条件（可配置）：
-                // 1. 有资金流入（大于阈值 × 倍数）
-                // 2. 买占比 >= 配置的阈值
-                let first_wave_inflow_threshold = self.config.net_inflow_threshold_sol * self.config.first_wave_inflow_multiplier;
-
-                if net_inflow_sol >= first_wave_inflow_threshold && metrics.buy_ratio >= self.config.first_wave_buy_ratio {
-                    info!("🚀 首波狙击触发！");
-                    info!("   事件数: {}", metrics.event_count);
-                    info!("   净流入: {:.4} SOL (阈值: {:.4} SOL)",
-                        net_inflow_sol, first_wave_inflow_threshold);
-                    info!("   买占比: {:.2}% (阈值: {:.2}%)",
-                        metrics.buy_ratio * 100.0, self.config.first_wave_buy_ratio * 100.0);
-                    info!("   🎯 立即买入！");
-                    return StrategySignal::Buy;
-                } else {
-                    debug!("首波监控中... 事件数: {}, 净流入: {:.4} SOL, 买占比: {:.2}%",
-                        metrics.event_count, net_inflow_sol, metrics.buy_ratio * 100.0);
+        if self.config.enable_first_wave_sniper
+            && (!in_warmup || self.config.warmup_skip_first_wave)
+            && self.is_first_wave(metrics)
+        {
+            let net_inflow_sol = metrics.net_inflow_sol as f64 / 1_000_000_000.0;
+
+            // 首波快速狙击条件（可配置）：
+            // 1. 有资金流入（大于阈值 × 倍数）
+            // 2. 买占比 >= 配置的阈值
+            let first_wave_inflow_threshold = self.config.net_inflow_threshold_sol * self.config.first_wave_inflow_multiplier;
+
+            if net_inflow_sol >= first_wave_inflow_threshold
+                && metrics.buy_ratio >= self.config.first_wave_buy_ratio
+                && self.check_dev_buy(metrics, notes)
+            {
+                info!("🚀 首波狙击触发！");
+                info!("   事件数: {}", metrics.event_count);
+                info!("   净流入: {:.4} SOL (阈值: {:.4} SOL)",
+                    net_inflow_sol, first_wave_inflow_threshold);
+                info!("   买占比: {:.2}% (阈值: {:.2}%)",
+                    metrics.buy_ratio * 100.0, self.config.first_wave_buy_ratio * 100.0);
+                info!("   🎯 立即买入！");
+                notes.push(|| "first wave trigger".to_string());
+                return StrategySignal::Buy;
+            } else {
+                debug!("首波监控中... 事件数: {}, 净流入: {:.4} SOL, 买占比: {:.2}%",
+                    metrics.event_count, net_inflow_sol, metrics.buy_ratio * 100.0);
+                if net_inflow_sol < first_wave_inflow_threshold {
+                    notes.push(|| format!("first_wave: net_inflow {:.4} SOL < {:.4} SOL", net_inflow_sol, first_wave_inflow_threshold));
+                }
+                if metrics.buy_ratio < self.config.first_wave_buy_ratio {
+                    notes.push(|| format!("first_wave: buy_ratio {:.2}% < {:.2}%",
+                        metrics.buy_ratio * 100.0, self.config.first_wave_buy_ratio * 100.0));
                 }
             }
         }
 
+        // 常规策略的 token 年龄上限（首波 / 阈值 / 毕业狙击不受限制）
+        if self.is_stale_token(metrics) {
+            debug!("❌ token 过老: {} (上线 {:?}s, 上限 {}s)",
+                metrics.mint, metrics.token_age_secs, self.config.max_token_age_secs);
+            notes.push(|| match metrics.token_age_secs {
+                Some(age) => format!("token too old: {}s > {}s", age, self.config.max_token_age_secs),
+                None => "token age unknown".to_string(),
+            });
+            return StrategySignal::None;
+        }
+
+        if in_warmup {
+            debug!("⏳ 预热观察中: {} (已观察 {}s / {} 事件)",
+                metrics.mint, metrics.observed_secs, metrics.total_event_count);
+            notes.push(|| format!("warmup: observed {}s / {} events (need {}s / {} events)",
+                metrics.observed_secs, metrics.total_event_count,
+                self.config.warmup_observation_secs, self.config.warmup_observation_events));
+            return StrategySignal::None;
+        }
+
         // 检查是否有足够的事件数据（常规策略）
         if metrics.event_count < 3 {
+            notes.push(|| format!("event_count {} < 3", metrics.event_count));
+            return StrategySignal::None;
+        }
+
+        // 独立买家数检查（防止单钱包拉盘）
+        if metrics.unique_buyers < self.config.min_unique_buyers {
+            debug!("❌ 独立买家不足: {} < {} (重复买家占比: {:.2}%)",
+                metrics.unique_buyers, self.config.min_unique_buyers, metrics.repeat_buyer_ratio * 100.0);
+            notes.push(|| format!("unique_buyers {} < {}", metrics.unique_buyers, self.config.min_unique_buyers));
+            return StrategySignal::None;
+        }
+
+        // 🔥 新增: 速率检查（按窗口时长归一化，活跃时长不同的 mint 之间可比）
+        if metrics.inflow_rate_sol_per_sec < self.config.min_inflow_rate_sol_per_sec {
+            notes.push(|| format!("inflow_rate {:.4} SOL/s < {:.4} SOL/s",
+                metrics.inflow_rate_sol_per_sec, self.config.min_inflow_rate_sol_per_sec));
+            return StrategySignal::None;
+        }
+        if metrics.trade_rate_per_sec < self.config.min_trade_rate_per_sec {
+            notes.push(|| format!("trade_rate {:.2}/s < {:.2}/s",
+                metrics.trade_rate_per_sec, self.config.min_trade_rate_per_sec));
+            return StrategySignal::None;
+        }
+
+        // 多时间框架同向检查（如果启用）
+        if self.config.require_timeframe_alignment
+            && !metrics.timeframes_aligned(self.config.buy_ratio_threshold)
+        {
+            debug!("❌ 多时间框架未对齐: {}", metrics.mint);
+            notes.push(|| "timeframes not aligned".to_string());
             return StrategySignal::None;
         }
 
@@ -239,11 +497,17 @@ impl StrategyEngine {
 
         // 如果有高级指标，使用动态策略引擎
         if let Some(advanced) = advanced_metrics {
-            let mut dynamic = self.dynamic_strategy.write();
-            let (should_buy, confidence) = dynamic.evaluate_buy(metrics, advanced);
+            // 自适应状态（波动状态 / 时段系数）只在需要切换时短暂加写锁，评估本身只读，各 worker 并行评估
+            if self.dynamic_strategy.read().adaptation_pending() {
+                self.dynamic_strategy.write().adapt_parameters();
+            }
+            let evaluation = self.dynamic_strategy.read().evaluate_buy(metrics, advanced, notes);
+            notes.push(|| format!("dynamic strategy: {}/{} conditions passed (confidence {:.2}%)",
+                evaluation.passed_conditions, evaluation.total_conditions, evaluation.confidence * 100.0));
 
-            if should_buy {
-                info!("✅ 动态策略引擎: 买入信号 (置信度: {:.2}%)", confidence * 100.0);
+            if evaluation.should_buy {
+                info!("✅ 动态策略引擎: 买入信号 (置信度: {:.2}%)", evaluation.confidence * 100.0);
+                *score = Some(evaluation.score);
                 return StrategySignal::Buy;
             } else {
                 debug!("❌ 动态策略引擎: 不满足买入条件");
@@ -256,18 +520,26 @@ impl StrategyEngine {
 
         // 条件 1: 买入占比检查
         if metrics.buy_ratio < self.config.buy_ratio_threshold {
+            notes.push(|| format!("buy_ratio {:.2}% < {:.2}%", metrics.buy_ratio * 100.0, self.config.buy_ratio_threshold * 100.0));
             return StrategySignal::None;
         }
 
         // 条件 2: 净流入检查
         let net_inflow_sol = metrics.net_inflow_sol as f64 / 1_000_000_000.0;
         if net_inflow_sol < self.config.net_inflow_threshold_sol {
+            notes.push(|| format!("net_inflow {:.4} SOL < {:.4} SOL", net_inflow_sol, self.config.net_inflow_threshold_sol));
             return StrategySignal::None;
         }
 
         // 条件 3: 加速度检查（如果启用）
         if self.config.acceleration_required {
-            if metrics.acceleration < self.config.acceleration_multiplier {
+            let acceleration = if self.config.use_normalized_acceleration {
+                metrics.normalized_acceleration
+            } else {
+                metrics.acceleration
+            };
+            if acceleration < self.config.acceleration_multiplier {
+                notes.push(|| format!("acceleration {:.2}x < {:.2}x", acceleration, self.config.acceleration_multiplier));
                 return StrategySignal::None;
             }
         }
@@ -286,6 +558,7 @@ impl StrategyEngine {
                 "Slippage too high for {}: {:.2}% > {:.2}%",
                 metrics.mint, estimated_slippage, self.config.max_slippage_percent
             );
+            notes.push(|| format!("slippage {:.2}% > {:.2}%", estimated_slippage, self.config.max_slippage_percent));
             return StrategySignal::None;
         }
 
@@ -298,20 +571,55 @@ impl StrategyEngine {
             metrics.acceleration,
             estimated_slippage
         );
+        notes.push(|| "legacy strategy: all conditions passed".to_string());
 
         StrategySignal::Buy
     }
 
+    /// 平仓后回馈交易结果（净盈亏百分比、开仓时间）给动态策略引擎
+    pub fn record_trade_outcome(&self, profit_loss_percent: f64, entry_time: DateTime<Utc>) {
+        self.dynamic_strategy.write().record_trade_outcome(profit_loss_percent, entry_time);
+    }
+
+    /// 当前时段的仓位乘数（历史表现差的时段缩小仓位）
+    pub fn position_size_multiplier(&self) -> f64 {
+        self.dynamic_strategy.read().position_size_multiplier()
+    }
+
+    /// 综合评分（无高级指标时为 None）
+    pub fn composite_score(&self, metrics: &WindowMetrics) -> Option<f64> {
+        let advanced = metrics.advanced_metrics.as_ref()?;
+        Some(self.dynamic_strategy.read().calculate_composite_score(metrics, advanced))
+    }
+
+    /// 卖出触发条件（动态策略叠加单币覆盖规则）
+    pub fn resolve_sell_triggers(&self, metrics: &WindowMetrics) -> SellTriggers {
+        let mut triggers = self.dynamic_strategy.read().get_sell_triggers().clone();
+        if let Some(rule) = self.resolve_override(&metrics.mint, &metrics.creator) {
+            if let Some(tp) = rule.take_profit_multiplier {
+                triggers.take_profit_multiplier = tp;
+            }
+            if let Some(sl) = rule.stop_loss_multiplier {
+                triggers.stop_loss_multiplier = sl;
+            }
+            if let Some(max_hold) = rule.max_hold_duration_secs {
+                triggers.max_hold_duration_secs = max_hold;
+            }
+        }
+        triggers
+    }
+
     /// 评估退出条件
+    ///
+    /// 🔥 修复: 止盈止损倍数基于保本价格（含 PumpFun 手续费、tip、交易费），而非原始买入价格
     pub fn evaluate_exit_conditions(
         &self,
         metrics: &WindowMetrics,
-        entry_price_sol: f64,
+        breakeven_price_sol: f64,
         hold_duration_secs: u64,
     ) -> StrategySignal {
-        // 使用动态策略的卖出触发条件
-        let dynamic_strategy = self.dynamic_strategy.read();
-        let triggers = dynamic_strategy.get_sell_triggers();
+        // 使用动态策略的卖出触发条件（叠加单币覆盖规则）
+        let triggers = self.resolve_sell_triggers(metrics);
 
         // 1. 检查最小持仓时间
         if hold_duration_secs < triggers.min_hold_duration_secs {
@@ -337,7 +645,7 @@ impl StrategyEngine {
 
             // 4. 止盈检查（加流动性检查）
             if triggers.take_profit_multiplier > 0.0 {
-                let take_profit_price = entry_price_sol * triggers.take_profit_multiplier;
+                let take_profit_price = breakeven_price_sol * triggers.take_profit_multiplier;
                 if current_price_sol >= take_profit_price {
                     // 🔥 优化: 检查滑点是否可接受
                     let estimated_slippage = curve_state.estimate_buy_slippage(
@@ -359,7 +667,7 @@ impl StrategyEngine {
 
             // 5. 止损检查（加流动性检查）
             if triggers.stop_loss_multiplier > 0.0 {
-                let stop_loss_price = entry_price_sol * triggers.stop_loss_multiplier;
+                let stop_loss_price = breakeven_price_sol * triggers.stop_loss_multiplier;
                 if current_price_sol <= stop_loss_price {
                     // 🔥 优化: 止损时也检查滑点，避免恐慌性抛售造成更大损失
                     let estimated_slippage = curve_state.estimate_buy_slippage(
@@ -381,8 +689,8 @@ impl StrategyEngine {
             }
         }
 
-        // 6. 动能衰减检查
-        if metrics.buy_ratio < triggers.momentum_decay_threshold {
+        // 6. 动能衰减检查（没有交易流数据时跳过，退出循环只做价格 / 时间退出）
+        if metrics.has_trade_flow() && metrics.buy_ratio < triggers.momentum_decay_threshold {
             info!("📉 MOMENTUM DECAY for {} - Buy ratio dropped to {:.2}%",
                 metrics.mint, metrics.buy_ratio * 100.0);
             return StrategySignal::Sell;
@@ -0,0 +1,160 @@
+/// 单币策略覆盖规则
+///
+/// 按 mint 或创建者覆盖策略参数（仓位大小、止盈止损等），在信号生成时解析
+///
+/// 核心功能:
+/// 1. mint 级覆盖 - 针对特定 token 的参数
+/// 2. 创建者级覆盖 - 例如白名单创建者加大仓位
+/// 3. 未知创建者默认规则 - 例如对陌生创建者收紧止损
+/// 4. 优先级合并 - mint > 创建者 > 未知创建者，未设置的字段回退到全局配置
+/// 5. 加载时校验 - threshold_tiers 在加载时校验并按累计买入排序，非法档位拒绝整个规则文件
+///
+/// 规则文件格式（JSON）:
+/// {
+///   "mints":    { "<mint>":    { "snipe_amount_sol": 0.5 } },
+///   "creators": { "<creator>": { "take_profit_multiplier": 3.0,
+///                                "threshold_tiers": [{ "cumulative_buy_sol": 10, "buy_amount_sol": 0.3 }] } },
+///   "unknown_creator": { "stop_loss_multiplier": 0.8 }
+/// }
+
+use anyhow::{Context, Result};
+use log::info;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::config::Config;
+use crate::threshold::{sorted_tiers, ThresholdTier};
+
+/// 单条覆盖规则（未设置的字段使用全局配置）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StrategyOverride {
+    /// 是否允许买入（false = 永不买入）
+    pub enabled: Option<bool>,
+    /// 买入金额（SOL）
+    pub snipe_amount_sol: Option<f64>,
+    /// 止盈倍数
+    pub take_profit_multiplier: Option<f64>,
+    /// 止损倍数
+    pub stop_loss_multiplier: Option<f64>,
+    /// 最大持仓时间（秒）
+    pub max_hold_duration_secs: Option<u64>,
+    /// 累计买入阈值档位（替换全局 threshold_tiers）
+    pub threshold_tiers: Option<Vec<ThresholdTier>>,
+}
+
+impl StrategyOverride {
+    /// 合并规则：self 中已设置的字段优先
+    fn merge(&self, fallback: &StrategyOverride) -> StrategyOverride {
+        StrategyOverride {
+            enabled: self.enabled.or(fallback.enabled),
+            snipe_amount_sol: self.snipe_amount_sol.or(fallback.snipe_amount_sol),
+            take_profit_multiplier: self.take_profit_multiplier.or(fallback.take_profit_multiplier),
+            stop_loss_multiplier: self.stop_loss_multiplier.or(fallback.stop_loss_multiplier),
+            max_hold_duration_secs: self.max_hold_duration_secs.or(fallback.max_hold_duration_secs),
+            threshold_tiers: self.threshold_tiers.clone().or_else(|| fallback.threshold_tiers.clone()),
+        }
+    }
+
+    /// 买入是否被禁止
+    pub fn is_disabled(&self) -> bool {
+        self.enabled == Some(false)
+    }
+}
+
+/// 规则文件原始格式
+#[derive(Debug, Default, Deserialize)]
+struct RawOverrideTable {
+    #[serde(default)]
+    mints: HashMap<String, StrategyOverride>,
+    #[serde(default)]
+    creators: HashMap<String, StrategyOverride>,
+    #[serde(default)]
+    unknown_creator: Option<StrategyOverride>,
+}
+
+/// 策略覆盖规则表
+#[derive(Debug, Clone, Default)]
+pub struct StrategyOverrideTable {
+    by_mint: HashMap<Pubkey, StrategyOverride>,
+    by_creator: HashMap<Pubkey, StrategyOverride>,
+    unknown_creator: Option<StrategyOverride>,
+}
+
+impl StrategyOverrideTable {
+    /// 从配置加载（未配置规则文件时返回空表）
+    pub fn from_config(config: &Config) -> Result<Self> {
+        match &config.strategy_overrides_path {
+            Some(path) if !path.is_empty() => Self::load(path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// 从 JSON 文件加载
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read strategy overrides file: {}", path))?;
+        let table = Self::from_json(&content)?;
+
+        info!("📋 策略覆盖规则已加载: {}", path);
+        info!("   mint 规则: {} 条", table.by_mint.len());
+        info!("   创建者规则: {} 条", table.by_creator.len());
+        info!("   未知创建者规则: {}", table.unknown_creator.is_some());
+
+        Ok(table)
+    }
+
+    /// 从 JSON 字符串解析
+    pub fn from_json(content: &str) -> Result<Self> {
+        let raw: RawOverrideTable = serde_json::from_str(content)
+            .context("Failed to parse strategy overrides")?;
+
+        let parse_keys = |entries: HashMap<String, StrategyOverride>| -> Result<HashMap<Pubkey, StrategyOverride>> {
+            entries
+                .into_iter()
+                .map(|(key, rule)| {
+                    let pubkey = Pubkey::from_str(&key)
+                        .with_context(|| format!("Invalid pubkey in strategy overrides: {}", key))?;
+                    let rule = Self::validated(rule)
+                        .with_context(|| format!("Invalid strategy override for {}", key))?;
+                    Ok((pubkey, rule))
+                })
+                .collect()
+        };
+
+        Ok(Self {
+            by_mint: parse_keys(raw.mints)?,
+            by_creator: parse_keys(raw.creators)?,
+            unknown_creator: raw.unknown_creator
+                .map(Self::validated)
+                .transpose()
+                .context("Invalid strategy override for unknown creators")?,
+        })
+    }
+
+    /// 校验并排序规则中的阈值档位（解析时只做一次，触发检查时直接使用）
+    fn validated(mut rule: StrategyOverride) -> Result<StrategyOverride> {
+        rule.threshold_tiers = rule.threshold_tiers.map(sorted_tiers).transpose()?;
+        Ok(rule)
+    }
+
+    /// 是否没有任何规则
+    pub fn is_empty(&self) -> bool {
+        self.by_mint.is_empty() && self.by_creator.is_empty() && self.unknown_creator.is_none()
+    }
+
+    /// 解析某个 token 的覆盖规则
+    ///
+    /// 优先级: mint > 创建者 > 未知创建者
+    pub fn resolve(&self, mint: &Pubkey, creator: &Pubkey) -> Option<StrategyOverride> {
+        let creator_rule = self.by_creator.get(creator).or(self.unknown_creator.as_ref());
+
+        match (self.by_mint.get(mint), creator_rule) {
+            (Some(mint_rule), Some(creator_rule)) => Some(mint_rule.merge(creator_rule)),
+            (Some(mint_rule), None) => Some(mint_rule.clone()),
+            (None, Some(creator_rule)) => Some(creator_rule.clone()),
+            (None, None) => None,
+        }
+    }
+}
@@ -13,11 +13,16 @@ use solana_sdk::{
 use std::{
     collections::HashMap,
     str::FromStr,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
+use futures::stream::{FuturesUnordered, StreamExt};
 use tokio::{
     sync::RwLock,
+    task::{AbortHandle, JoinHandle},
     time::timeout,
 };
 use reqwest::Client;
@@ -26,6 +31,9 @@ use base64::{Engine, engine::general_purpose::STANDARD};
 // SliceRandom 在 rand 0.9 中已移除 .choose() 方法，必须使用 IndexedRandom
 use rand::prelude::IndexedRandom;
 
+use crate::chaos::ChaosInjector;
+use crate::error::{classify_send_error, SendErrorKind};
+
 /// SWQOS 服务类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SwqosType {
@@ -63,6 +71,7 @@ impl FromStr for SwqosType {
 
 /// SWQOS 地区
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)] // SLC 是配置里使用的地区名
 pub enum SwqosRegion {
     NewYork,
     Frankfurt,
@@ -74,6 +83,20 @@ pub enum SwqosRegion {
     Default,
 }
 
+impl SwqosRegion {
+    /// 所有地区（延迟探测候选）
+    pub const ALL: [SwqosRegion; 8] = [
+        SwqosRegion::NewYork,
+        SwqosRegion::Frankfurt,
+        SwqosRegion::Amsterdam,
+        SwqosRegion::SLC,
+        SwqosRegion::Tokyo,
+        SwqosRegion::London,
+        SwqosRegion::LosAngeles,
+        SwqosRegion::Default,
+    ];
+}
+
 impl FromStr for SwqosRegion {
     type Err = anyhow::Error;
 
@@ -291,11 +314,9 @@ fn get_endpoint(swqos_type: SwqosType, region: SwqosRegion) -> String {
     endpoint.to_string()
 }
 
-/// 获取随机Tip账户
-fn get_random_tip_account(swqos_type: SwqosType) -> Result<String> {
-    let mut rng = rand::rng();  // 🔥 修复: rand 0.9 使用 rng() 而非 thread_rng()
-
-    let accounts = match swqos_type {
+/// 服务的 tip 账户集合（Default 类型没有 tip 账户）
+fn tip_accounts(swqos_type: SwqosType) -> &'static [&'static str] {
+    match swqos_type {
         SwqosType::Jito => JITO_TIP_ACCOUNTS,
         SwqosType::NextBlock => NEXTBLOCK_TIP_ACCOUNTS,
         SwqosType::ZeroSlot => ZEROSLOT_TIP_ACCOUNTS,
@@ -305,8 +326,18 @@ fn get_random_tip_account(swqos_type: SwqosType) -> Result<String> {
         SwqosType::FlashBlock => FLASHBLOCK_TIP_ACCOUNTS,
         SwqosType::BlockRazor => BLOCKRAZOR_TIP_ACCOUNTS,
         SwqosType::Astralane => ASTRALANE_TIP_ACCOUNTS,
-        SwqosType::Default => return Err(anyhow::anyhow!("Default type has no tip accounts")),
-    };
+        SwqosType::Default => &[],
+    }
+}
+
+/// 获取随机Tip账户
+fn get_random_tip_account(swqos_type: SwqosType) -> Result<String> {
+    let mut rng = rand::rng();  // 🔥 修复: rand 0.9 使用 rng() 而非 thread_rng()
+
+    if swqos_type == SwqosType::Default {
+        return Err(anyhow::anyhow!("Default type has no tip accounts"));
+    }
+    let accounts = tip_accounts(swqos_type);
 
     let account_str = accounts.choose(&mut rng)
         .ok_or_else(|| anyhow::anyhow!("No tip accounts available"))?;
@@ -324,9 +355,67 @@ pub struct SwqosServiceConfig {
     pub tip_lamports: Option<u64>,
     pub priority: u32,
     pub enabled: bool,
+    /// 是否按实测延迟自动选择地区（false = 固定使用 region）
+    #[serde(default)]
+    pub auto_region: bool,
+    /// 防夹（front-running protection）模式：牺牲延迟换取防三明治攻击
+    #[serde(default)]
+    pub anti_mev: bool,
+    /// 是否通过服务商的 staked RPC 转发
+    #[serde(default = "default_use_staked_rpcs")]
+    pub use_staked_rpcs: bool,
+    /// 自定义端点列表（Default 类型：用户提供的 staked RPC URL）
+    #[serde(default)]
+    pub custom_endpoints: Vec<String>,
+}
+
+fn default_use_staked_rpcs() -> bool {
+    true
+}
+
+/// 读取服务地区设置
+///
+/// - `{PREFIX}_REGION=auto` → 自动选择（初始使用默认地区，延迟探测后切换）
+/// - `{PREFIX}_REGION=<地区>` → 固定地区（不参与自动选择）
+/// - 未设置 → 使用默认地区，SWQOS_AUTO_REGION=true 时自动选择
+fn region_setting(env_name: &str, default_region: SwqosRegion, auto_by_default: bool) -> (SwqosRegion, bool) {
+    match std::env::var(env_name) {
+        Ok(value) if value.eq_ignore_ascii_case("auto") => (default_region, true),
+        Ok(value) => (SwqosRegion::from_str(&value).unwrap_or(default_region), false),
+        Err(_) => (default_region, auto_by_default),
+    }
+}
+
+/// 读取布尔环境变量（未设置或无法解析时使用默认值）
+fn env_flag(name: &str, default: bool) -> bool {
+    std::env::var(name)
+        .ok()
+        .and_then(|s| s.to_lowercase().parse().ok())
+        .unwrap_or(default)
 }
 
 impl SwqosServiceConfig {
+    /// 服务商是否支持防夹选项
+    fn supports_anti_mev(&self) -> bool {
+        matches!(self.service_type, SwqosType::NextBlock | SwqosType::Bloxroute)
+    }
+
+    /// 服务商是否支持关闭 staked RPC 转发
+    fn supports_staked_rpc_toggle(&self) -> bool {
+        matches!(self.service_type, SwqosType::Bloxroute)
+    }
+
+    /// 校验服务商专属选项（不支持的选项直接报错，避免静默忽略）
+    pub fn validate(&self) -> Result<()> {
+        if self.anti_mev && !self.supports_anti_mev() {
+            anyhow::bail!("SWQOS service {} does not support anti-MEV mode (supported: NextBlock, Bloxroute)", self.name);
+        }
+        if !self.use_staked_rpcs && !self.supports_staked_rpc_toggle() {
+            anyhow::bail!("SWQOS service {} does not support disabling staked RPCs (supported: Bloxroute)", self.name);
+        }
+        Ok(())
+    }
+
     pub fn get_endpoint(&self) -> String {
         get_endpoint(self.service_type, self.region)
     }
@@ -338,13 +427,90 @@ pub trait SwqosClientTrait: Send + Sync {
     async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature>;
     fn get_tip_account(&self) -> Result<String>;
     fn get_swqos_type(&self) -> SwqosType;
+    fn endpoint(&self) -> &str;
+    fn auth_token(&self) -> &str;
+    fn http_client(&self) -> &Client;
+
+    /// 连通性检查：验证端点可达且 API key 未被拒绝
+    ///
+    /// 默认实现向提交端点 POST 一个空请求体（带 `Authorization` 头），
+    /// 认证方式不同的服务商各自覆盖；状态判断见 `check_ping_status`
+    async fn ping(&self) -> Result<()> {
+        let response = self.http_client()
+            .post(self.endpoint())
+            .header("Authorization", self.auth_token())
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({}))
+            .send()
+            .await?;
+
+        check_ping_status(response.status(), "API key")
+    }
+}
+
+/// 连通性检查的 HTTP 状态判断
+///
+/// 探测请求不携带真实交易，提交接口通常返回 400/404/405 等业务错误，只要有响应就视为可达；
+/// 401/403 视为凭证无效，5xx 视为服务不可用
+fn check_ping_status(status: reqwest::StatusCode, credential: &str) -> Result<()> {
+    match status.as_u16() {
+        401 | 403 => Err(anyhow::anyhow!("{} rejected (HTTP {})", credential, status)),
+        _ if status.is_server_error() => Err(anyhow::anyhow!("Endpoint unhealthy (HTTP {})", status)),
+        _ => Ok(()),
+    }
+}
+
+/// JSON-RPC 形式中继的探测请求体（getHealth 不需要交易，服务商不支持该方法时返回业务错误，仍视为可达）
+fn ping_rpc_body() -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getHealth"
+    })
+}
+
+/// 已创建的 SWQOS 客户端（附带服务配置和健康状态）
+struct SwqosClientEntry {
+    config: SwqosServiceConfig,
+    /// 当前显示名称（带当前地区，切换地区后同步更新）
+    name: parking_lot::RwLock<String>,
+    /// 当前客户端（自动选择地区时可能被替换为更快地区的客户端）
+    client: parking_lot::RwLock<Arc<dyn SwqosClientTrait>>,
+    /// 当前使用的地区
+    region: parking_lot::RwLock<SwqosRegion>,
+    /// 健康检查结果（false = 暂时禁用，不参与赛马和 tip）
+    healthy: AtomicBool,
+}
+
+impl SwqosClientEntry {
+    fn client(&self) -> Arc<dyn SwqosClientTrait> {
+        self.client.read().clone()
+    }
+
+    /// 服务名称（日志、发送结果、tip 记录使用）
+    fn name(&self) -> String {
+        self.name.read().clone()
+    }
+
+    /// 切换地区：客户端、地区和显示名称一起更新
+    fn switch_region(&self, client: Arc<dyn SwqosClientTrait>, region: SwqosRegion) {
+        // 配置名称形如 "Jito-NewYork"，替换末尾的初始地区
+        let suffix = format!("-{:?}", self.config.region);
+        let base = self.config.name.strip_suffix(&suffix).unwrap_or(&self.config.name);
+        *self.client.write() = client;
+        *self.region.write() = region;
+        *self.name.write() = format!("{}-{:?}", base, region);
+    }
 }
 
 /// 多 SWQOS 服务管理器
 pub struct MultiSwqosManager {
-    clients: Vec<Arc<dyn SwqosClientTrait>>,
+    /// 按优先级排序的客户端
+    clients: Vec<Arc<SwqosClientEntry>>,
     config: SwqosConfig,
     results: Arc<RwLock<HashMap<String, SwqosResult>>>,
+    /// 故障注入（包装客户端，随机发送失败；需要 `chaos` feature）
+    chaos: ChaosInjector,
 }
 
 /// SWQOS 发送结果
@@ -355,6 +521,8 @@ pub struct SwqosResult {
     pub success: bool,
     pub latency_ms: u64,
     pub error: Option<String>,
+    /// 服务返回 AlreadyProcessed：交易已上链，但本次是重复发送（不是该服务送达的）
+    pub already_processed: bool,
 }
 
 /// SWQOS 配置
@@ -364,6 +532,20 @@ pub struct SwqosConfig {
     pub timeout_ms: u64,
     pub max_retries: u32,
     pub max_tips: usize,  // 最大 tip 数量（避免交易体积过大）
+    /// tip 总预算（lamports，0 = 不启用，各服务使用自己的 tip_lamports）
+    pub tip_budget_lamports: u64,
+    /// 预算模式下分摊 tip 的服务数量（按优先级取前 N 个）
+    pub tip_budget_services: usize,
+    /// 单笔交易 SWQOS tip 总额上限（lamports，0 = 不限制）
+    pub max_tip_per_tx_lamports: u64,
+    /// 健康检查超时（毫秒）
+    pub health_check_timeout_ms: u64,
+    /// 定期健康检查间隔（秒，0 = 只在启动时检查）
+    pub health_check_interval_secs: u64,
+    /// 地区延迟探测间隔（秒，0 = 只在启动时探测）
+    pub latency_probe_interval_secs: u64,
+    /// 每个地区端点的探测采样次数（取最小值）
+    pub latency_probe_samples: u32,
     pub services: Vec<SwqosServiceConfig>,
 }
 
@@ -390,14 +572,53 @@ impl SwqosConfig {
             .parse()
             .unwrap_or(5);
 
+        let tip_budget_lamports = std::env::var("SWQOS_TIP_BUDGET_LAMPORTS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .unwrap_or(0);
+
+        let tip_budget_services = std::env::var("SWQOS_TIP_BUDGET_SERVICES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(max_tips)
+            .max(1);
+
+        let max_tip_per_tx_lamports = std::env::var("SWQOS_MAX_TIP_PER_TX_LAMPORTS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .unwrap_or(0);
+
+        let health_check_timeout_ms = std::env::var("SWQOS_HEALTH_CHECK_TIMEOUT_MS")
+            .unwrap_or_else(|_| "3000".to_string())
+            .parse()
+            .unwrap_or(3000);
+
+        let health_check_interval_secs = std::env::var("SWQOS_HEALTH_CHECK_INTERVAL_SECS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .unwrap_or(0);
+
+        let latency_probe_interval_secs = std::env::var("SWQOS_LATENCY_PROBE_INTERVAL_SECS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .unwrap_or(0);
+
+        let latency_probe_samples = std::env::var("SWQOS_LATENCY_PROBE_SAMPLES")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse::<u32>()
+            .unwrap_or(3)
+            .max(1);
+
+        // 未单独设置 {PREFIX}_REGION 的服务是否自动选择地区
+        let auto_region_default = env_flag("SWQOS_AUTO_REGION", false);
+
         let mut services = Vec::new();
 
         // 加载 Jito
         if let Ok(enabled) = std::env::var("JITO_ENABLED") {
             if enabled.to_lowercase() == "true" {
                 if let Ok(uuid) = std::env::var("JITO_UUID") {
-                    let region_str = std::env::var("JITO_REGION").unwrap_or_else(|_| "NewYork".to_string());
-                    let region = SwqosRegion::from_str(&region_str).unwrap_or(SwqosRegion::NewYork);
+                    let (region, auto_region) = region_setting("JITO_REGION", SwqosRegion::NewYork, auto_region_default);
                     let tip_lamports = std::env::var("JITO_TIP_LAMPORTS")
                         .ok()
                         .and_then(|s| s.parse().ok());
@@ -410,10 +631,14 @@ impl SwqosConfig {
                         name: format!("Jito-{:?}", region),
                         service_type: SwqosType::Jito,
                         region,
+                        auto_region,
                         api_key: uuid,
                         tip_lamports,
                         priority,
                         enabled: true,
+                        anti_mev: env_flag("JITO_ANTI_MEV", false),
+                        use_staked_rpcs: env_flag("JITO_USE_STAKED_RPCS", true),
+                        custom_endpoints: Vec::new(),
                     });
                     info!("✅ 加载 Jito 配置: 区域={:?}, 优先级={}", region, priority);
                 }
@@ -424,8 +649,7 @@ impl SwqosConfig {
         if let Ok(enabled) = std::env::var("NEXTBLOCK_ENABLED") {
             if enabled.to_lowercase() == "true" {
                 if let Ok(token) = std::env::var("NEXTBLOCK_TOKEN") {
-                    let region_str = std::env::var("NEXTBLOCK_REGION").unwrap_or_else(|_| "Frankfurt".to_string());
-                    let region = SwqosRegion::from_str(&region_str).unwrap_or(SwqosRegion::Frankfurt);
+                    let (region, auto_region) = region_setting("NEXTBLOCK_REGION", SwqosRegion::Frankfurt, auto_region_default);
                     let tip_lamports = std::env::var("NEXTBLOCK_TIP_LAMPORTS")
                         .ok()
                         .and_then(|s| s.parse().ok());
@@ -438,12 +662,17 @@ impl SwqosConfig {
                         name: format!("NextBlock-{:?}", region),
                         service_type: SwqosType::NextBlock,
                         region,
+                        auto_region,
                         api_key: token,
                         tip_lamports,
                         priority,
                         enabled: true,
+                        anti_mev: env_flag("NEXTBLOCK_ANTI_MEV", false),
+                        use_staked_rpcs: env_flag("NEXTBLOCK_USE_STAKED_RPCS", true),
+                        custom_endpoints: Vec::new(),
                     });
-                    info!("✅ 加载 NextBlock 配置: 区域={:?}, 优先级={}", region, priority);
+                    info!("✅ 加载 NextBlock 配置: 区域={:?}, 优先级={}, 防夹={}",
+                        region, priority, env_flag("NEXTBLOCK_ANTI_MEV", false));
                 }
             }
         }
@@ -452,8 +681,7 @@ impl SwqosConfig {
         if let Ok(enabled) = std::env::var("ZEROSLOT_ENABLED") {
             if enabled.to_lowercase() == "true" {
                 if let Ok(api_key) = std::env::var("ZEROSLOT_API_KEY") {
-                    let region_str = std::env::var("ZEROSLOT_REGION").unwrap_or_else(|_| "NewYork".to_string());
-                    let region = SwqosRegion::from_str(&region_str).unwrap_or(SwqosRegion::NewYork);
+                    let (region, auto_region) = region_setting("ZEROSLOT_REGION", SwqosRegion::NewYork, auto_region_default);
                     let tip_lamports = std::env::var("ZEROSLOT_TIP_LAMPORTS")
                         .ok()
                         .and_then(|s| s.parse().ok());
@@ -466,10 +694,14 @@ impl SwqosConfig {
                         name: format!("ZeroSlot-{:?}", region),
                         service_type: SwqosType::ZeroSlot,
                         region,
+                        auto_region,
                         api_key,
                         tip_lamports,
                         priority,
                         enabled: true,
+                        anti_mev: env_flag("ZEROSLOT_ANTI_MEV", false),
+                        use_staked_rpcs: env_flag("ZEROSLOT_USE_STAKED_RPCS", true),
+                        custom_endpoints: Vec::new(),
                     });
                     info!("✅ 加载 ZeroSlot 配置: 区域={:?}, 优先级={}", region, priority);
                 }
@@ -480,8 +712,7 @@ impl SwqosConfig {
         if let Ok(enabled) = std::env::var("TEMPORAL_ENABLED") {
             if enabled.to_lowercase() == "true" {
                 if let Ok(api_key) = std::env::var("TEMPORAL_API_KEY") {
-                    let region_str = std::env::var("TEMPORAL_REGION").unwrap_or_else(|_| "NewYork".to_string());
-                    let region = SwqosRegion::from_str(&region_str).unwrap_or(SwqosRegion::NewYork);
+                    let (region, auto_region) = region_setting("TEMPORAL_REGION", SwqosRegion::NewYork, auto_region_default);
                     let tip_lamports = std::env::var("TEMPORAL_TIP_LAMPORTS")
                         .ok()
                         .and_then(|s| s.parse().ok());
@@ -494,10 +725,14 @@ impl SwqosConfig {
                         name: format!("Temporal-{:?}", region),
                         service_type: SwqosType::Temporal,
                         region,
+                        auto_region,
                         api_key,
                         tip_lamports,
                         priority,
                         enabled: true,
+                        anti_mev: env_flag("TEMPORAL_ANTI_MEV", false),
+                        use_staked_rpcs: env_flag("TEMPORAL_USE_STAKED_RPCS", true),
+                        custom_endpoints: Vec::new(),
                     });
                     info!("✅ 加载 Temporal 配置: 区域={:?}, 优先级={}", region, priority);
                 }
@@ -508,8 +743,7 @@ impl SwqosConfig {
         if let Ok(enabled) = std::env::var("BLOXROUTE_ENABLED") {
             if enabled.to_lowercase() == "true" {
                 if let Ok(auth_header) = std::env::var("BLOXROUTE_AUTH_HEADER") {
-                    let region_str = std::env::var("BLOXROUTE_REGION").unwrap_or_else(|_| "Tokyo".to_string());
-                    let region = SwqosRegion::from_str(&region_str).unwrap_or(SwqosRegion::Tokyo);
+                    let (region, auto_region) = region_setting("BLOXROUTE_REGION", SwqosRegion::Tokyo, auto_region_default);
                     let tip_lamports = std::env::var("BLOXROUTE_TIP_LAMPORTS")
                         .ok()
                         .and_then(|s| s.parse().ok());
@@ -522,12 +756,19 @@ impl SwqosConfig {
                         name: format!("Bloxroute-{:?}", region),
                         service_type: SwqosType::Bloxroute,
                         region,
+                        auto_region,
                         api_key: auth_header,
                         tip_lamports,
                         priority,
                         enabled: true,
+                        anti_mev: env_flag("BLOXROUTE_ANTI_MEV", false),
+                        use_staked_rpcs: env_flag("BLOXROUTE_USE_STAKED_RPCS", true),
+                        custom_endpoints: Vec::new(),
                     });
-                    info!("✅ 加载 Bloxroute 配置: 区域={:?}, 优先级={}", region, priority);
+                    info!("✅ 加载 Bloxroute 配置: 区域={:?}, 优先级={}, 防夹={}, staked RPC={}",
+                        region, priority,
+                        env_flag("BLOXROUTE_ANTI_MEV", false),
+                        env_flag("BLOXROUTE_USE_STAKED_RPCS", true));
                 }
             }
         }
@@ -536,8 +777,7 @@ impl SwqosConfig {
         if let Ok(enabled) = std::env::var("NODE1_ENABLED") {
             if enabled.to_lowercase() == "true" {
                 if let Ok(api_key) = std::env::var("NODE1_API_KEY") {
-                    let region_str = std::env::var("NODE1_REGION").unwrap_or_else(|_| "NewYork".to_string());
-                    let region = SwqosRegion::from_str(&region_str).unwrap_or(SwqosRegion::NewYork);
+                    let (region, auto_region) = region_setting("NODE1_REGION", SwqosRegion::NewYork, auto_region_default);
                     let tip_lamports = std::env::var("NODE1_TIP_LAMPORTS")
                         .ok()
                         .and_then(|s| s.parse().ok());
@@ -550,10 +790,14 @@ impl SwqosConfig {
                         name: format!("Node1-{:?}", region),
                         service_type: SwqosType::Node1,
                         region,
+                        auto_region,
                         api_key,
                         tip_lamports,
                         priority,
                         enabled: true,
+                        anti_mev: env_flag("NODE1_ANTI_MEV", false),
+                        use_staked_rpcs: env_flag("NODE1_USE_STAKED_RPCS", true),
+                        custom_endpoints: Vec::new(),
                     });
                     info!("✅ 加载 Node1 配置: 区域={:?}, 优先级={}", region, priority);
                 }
@@ -564,8 +808,7 @@ impl SwqosConfig {
         if let Ok(enabled) = std::env::var("FLASHBLOCK_ENABLED") {
             if enabled.to_lowercase() == "true" {
                 if let Ok(api_key) = std::env::var("FLASHBLOCK_API_KEY") {
-                    let region_str = std::env::var("FLASHBLOCK_REGION").unwrap_or_else(|_| "NewYork".to_string());
-                    let region = SwqosRegion::from_str(&region_str).unwrap_or(SwqosRegion::NewYork);
+                    let (region, auto_region) = region_setting("FLASHBLOCK_REGION", SwqosRegion::NewYork, auto_region_default);
                     let tip_lamports = std::env::var("FLASHBLOCK_TIP_LAMPORTS")
                         .ok()
                         .and_then(|s| s.parse().ok());
@@ -578,10 +821,14 @@ impl SwqosConfig {
                         name: format!("FlashBlock-{:?}", region),
                         service_type: SwqosType::FlashBlock,
                         region,
+                        auto_region,
                         api_key,
                         tip_lamports,
                         priority,
                         enabled: true,
+                        anti_mev: env_flag("FLASHBLOCK_ANTI_MEV", false),
+                        use_staked_rpcs: env_flag("FLASHBLOCK_USE_STAKED_RPCS", true),
+                        custom_endpoints: Vec::new(),
                     });
                     info!("✅ 加载 FlashBlock 配置: 区域={:?}, 优先级={}", region, priority);
                 }
@@ -592,8 +839,7 @@ impl SwqosConfig {
         if let Ok(enabled) = std::env::var("BLOCKRAZOR_ENABLED") {
             if enabled.to_lowercase() == "true" {
                 if let Ok(api_key) = std::env::var("BLOCKRAZOR_API_KEY") {
-                    let region_str = std::env::var("BLOCKRAZOR_REGION").unwrap_or_else(|_| "NewYork".to_string());
-                    let region = SwqosRegion::from_str(&region_str).unwrap_or(SwqosRegion::NewYork);
+                    let (region, auto_region) = region_setting("BLOCKRAZOR_REGION", SwqosRegion::NewYork, auto_region_default);
                     let tip_lamports = std::env::var("BLOCKRAZOR_TIP_LAMPORTS")
                         .ok()
                         .and_then(|s| s.parse().ok());
@@ -606,10 +852,14 @@ impl SwqosConfig {
                         name: format!("BlockRazor-{:?}", region),
                         service_type: SwqosType::BlockRazor,
                         region,
+                        auto_region,
                         api_key,
                         tip_lamports,
                         priority,
                         enabled: true,
+                        anti_mev: env_flag("BLOCKRAZOR_ANTI_MEV", false),
+                        use_staked_rpcs: env_flag("BLOCKRAZOR_USE_STAKED_RPCS", true),
+                        custom_endpoints: Vec::new(),
                     });
                     info!("✅ 加载 BlockRazor 配置: 区域={:?}, 优先级={}", region, priority);
                 }
@@ -620,8 +870,7 @@ impl SwqosConfig {
         if let Ok(enabled) = std::env::var("ASTRALANE_ENABLED") {
             if enabled.to_lowercase() == "true" {
                 if let Ok(api_key) = std::env::var("ASTRALANE_API_KEY") {
-                    let region_str = std::env::var("ASTRALANE_REGION").unwrap_or_else(|_| "NewYork".to_string());
-                    let region = SwqosRegion::from_str(&region_str).unwrap_or(SwqosRegion::NewYork);
+                    let (region, auto_region) = region_setting("ASTRALANE_REGION", SwqosRegion::NewYork, auto_region_default);
                     let tip_lamports = std::env::var("ASTRALANE_TIP_LAMPORTS")
                         .ok()
                         .and_then(|s| s.parse().ok());
@@ -634,16 +883,58 @@ impl SwqosConfig {
                         name: format!("Astralane-{:?}", region),
                         service_type: SwqosType::Astralane,
                         region,
+                        auto_region,
                         api_key,
                         tip_lamports,
                         priority,
                         enabled: true,
+                        anti_mev: env_flag("ASTRALANE_ANTI_MEV", false),
+                        use_staked_rpcs: env_flag("ASTRALANE_USE_STAKED_RPCS", true),
+                        custom_endpoints: Vec::new(),
                     });
                     info!("✅ 加载 Astralane 配置: 区域={:?}, 优先级={}", region, priority);
                 }
             }
         }
 
+        // 加载自定义 staked RPC（Default 类型：sendTransaction 扇出到用户提供的 RPC 列表）
+        if env_flag("STAKED_RPC_ENABLED", false) {
+            let urls: Vec<String> = std::env::var("STAKED_RPC_URLS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty())
+                .collect();
+
+            if urls.is_empty() {
+                warn!("⚠️  STAKED_RPC_ENABLED=true 但 STAKED_RPC_URLS 为空，跳过");
+            } else {
+                let priority = std::env::var("STAKED_RPC_PRIORITY")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .unwrap_or(10);
+
+                info!("✅ 加载 Staked RPC 配置: {} 个端点, 优先级={}", urls.len(), priority);
+                services.push(SwqosServiceConfig {
+                    name: "StakedRpc".to_string(),
+                    service_type: SwqosType::Default,
+                    region: SwqosRegion::Default,
+                    auto_region: false,
+                    api_key: String::new(),
+                    tip_lamports: None,
+                    priority,
+                    enabled: true,
+                    anti_mev: false,
+                    use_staked_rpcs: true,
+                    custom_endpoints: urls,
+                });
+            }
+        }
+
+        for service in &services {
+            service.validate()?;
+        }
+
         if services.is_empty() {
             warn!("⚠️  没有启用任何 SWQOS 服务！");
         } else {
@@ -655,6 +946,13 @@ impl SwqosConfig {
             timeout_ms,
             max_retries,
             max_tips,
+            tip_budget_lamports,
+            tip_budget_services,
+            max_tip_per_tx_lamports,
+            health_check_timeout_ms,
+            health_check_interval_secs,
+            latency_probe_interval_secs,
+            latency_probe_samples,
             services,
         })
     }
@@ -663,30 +961,55 @@ impl SwqosConfig {
 
 impl MultiSwqosManager {
     pub fn new(config: SwqosConfig) -> Result<Self> {
-        let mut clients: Vec<Arc<dyn SwqosClientTrait>> = Vec::new();
-
-        let mut sorted_services = config.services.clone();
-        sorted_services.sort_by_key(|s| s.priority);
+        let mut clients = Vec::new();
+        for service_config in config.services.iter().filter(|s| s.enabled) {
+            clients.push((service_config.clone(), Self::create_client(service_config)?));
+        }
 
-        for service_config in &sorted_services {
-            if !service_config.enabled {
-                continue;
-            }
+        Ok(Self::with_clients(config, clients))
+    }
 
-            let client = Self::create_client(service_config)?;
-            clients.push(client);
-        }
+    /// 使用已创建的客户端（测试注入模拟客户端，控制延迟 / 失败）
+    ///
+    /// 客户端按服务配置的优先级排序；config 只提供超时 / 重试 / tip 等全局参数，不再读取 config.services
+    pub fn with_clients(config: SwqosConfig, mut clients: Vec<(SwqosServiceConfig, Arc<dyn SwqosClientTrait>)>) -> Self {
+        clients.sort_by_key(|(service_config, _)| service_config.priority);
+
+        let clients: Vec<Arc<SwqosClientEntry>> = clients
+            .into_iter()
+            .map(|(service_config, client)| Arc::new(SwqosClientEntry {
+                name: parking_lot::RwLock::new(service_config.name.clone()),
+                region: parking_lot::RwLock::new(service_config.region),
+                client: parking_lot::RwLock::new(client),
+                healthy: AtomicBool::new(true),
+                config: service_config,
+            }))
+            .collect();
 
         info!("🚀 多 SWQOS 管理器已初始化");
         info!("   启用服务数量: {}", clients.len());
         info!("   并行发送: {}", config.parallel_send);
         info!("   超时时间: {}ms", config.timeout_ms);
 
-        Ok(Self {
+        Self {
             clients,
             config,
             results: Arc::new(RwLock::new(HashMap::new())),
-        })
+            chaos: ChaosInjector::default(),
+        }
+    }
+
+    /// 启用故障注入：包装所有客户端（地区切换后新建的客户端同样包装）
+    pub fn with_chaos(mut self, chaos: ChaosInjector) -> Self {
+        if chaos.is_active() {
+            warn!("🐒 SWQOS 故障注入已启用 (发送失败概率 {:.0}%)", chaos.swqos_failure_probability * 100.0);
+            for entry in &self.clients {
+                let client = chaos.wrap_swqos(entry.client());
+                *entry.client.write() = client;
+            }
+        }
+        self.chaos = chaos;
+        self
     }
 
     fn create_client(service_config: &SwqosServiceConfig) -> Result<Arc<dyn SwqosClientTrait>> {
@@ -694,10 +1017,20 @@ impl MultiSwqosManager {
         let api_key = service_config.api_key.clone();
         let swqos_type = service_config.service_type;
 
+        service_config.validate()?;
+        if service_config.anti_mev {
+            info!("🛡️  服务 {} 已启用防夹模式", service_config.name);
+        }
+
         let client: Arc<dyn SwqosClientTrait> = match swqos_type {
             SwqosType::Jito => Arc::new(JitoClient::new(endpoint, api_key)),
-            SwqosType::NextBlock => Arc::new(NextBlockClient::new(endpoint, api_key)),
-            SwqosType::Bloxroute => Arc::new(BloxrouteClient::new(endpoint, api_key)),
+            SwqosType::NextBlock => Arc::new(NextBlockClient::new(endpoint, api_key, service_config.anti_mev)),
+            SwqosType::Bloxroute => Arc::new(BloxrouteClient::new(
+                endpoint,
+                api_key,
+                service_config.anti_mev,
+                service_config.use_staked_rpcs,
+            )),
             SwqosType::Temporal => Arc::new(TemporalClient::new(endpoint, api_key)),
             SwqosType::ZeroSlot => Arc::new(ZeroSlotClient::new(endpoint, api_key)),
             SwqosType::Node1 => Arc::new(Node1Client::new(endpoint, api_key)),
@@ -705,19 +1038,207 @@ impl MultiSwqosManager {
             SwqosType::BlockRazor => Arc::new(BlockRazorClient::new(endpoint, api_key)),
             SwqosType::Astralane => Arc::new(AstralaneClient::new(endpoint, api_key)),
             SwqosType::Default => {
-                return Err(anyhow::anyhow!("Default type is not supported"));
+                if service_config.custom_endpoints.is_empty() {
+                    return Err(anyhow::anyhow!("Default type requires at least one staked RPC URL"));
+                }
+                Arc::new(StakedRpcClient::new(service_config.custom_endpoints.clone()))
             }
         };
 
         Ok(client)
     }
 
+    /// 当前健康（参与赛马）的客户端
+    fn active_clients(&self) -> Vec<Arc<SwqosClientEntry>> {
+        self.clients
+            .iter()
+            .filter(|entry| entry.healthy.load(Ordering::Relaxed))
+            .cloned()
+            .collect()
+    }
+
+    /// 测量单个端点的 RTT（多次采样取最小值，超时或失败返回 None）
+    async fn probe_endpoint(http_client: &Client, endpoint: &str, samples: u32, timeout_duration: Duration) -> Option<u64> {
+        let mut best: Option<u64> = None;
+        for _ in 0..samples {
+            let start = Instant::now();
+            // 中继根路径通常返回 404/405，任何 HTTP 响应都计入 RTT；只跳过连接失败和超时
+            if let Ok(Ok(_)) = timeout(timeout_duration, http_client.get(endpoint).send()).await {
+                let rtt = start.elapsed().as_millis() as u64;
+                best = Some(best.map_or(rtt, |b| b.min(rtt)));
+            }
+        }
+        best
+    }
+
+    /// 为启用自动地区的服务探测所有地区端点，切换到延迟最低的地区
+    ///
+    /// 固定地区（{PREFIX}_REGION 显式设置）的服务不参与
+    pub async fn select_fastest_regions(&self) {
+        let timeout_duration = Duration::from_millis(self.config.health_check_timeout_ms);
+        let samples = self.config.latency_probe_samples;
+        let http_client = Client::builder()
+            .timeout(timeout_duration)
+            .build()
+            .unwrap_or_default();
+
+        for entry in self.clients.iter().filter(|entry| entry.config.auto_region) {
+            let service_type = entry.config.service_type;
+
+            // 不同地区可能共用同一端点，按 URL 去重
+            let mut candidates: Vec<(SwqosRegion, String)> = Vec::new();
+            for region in SwqosRegion::ALL {
+                let endpoint = get_endpoint(service_type, region);
+                if !endpoint.is_empty() && !candidates.iter().any(|(_, e)| *e == endpoint) {
+                    candidates.push((region, endpoint));
+                }
+            }
+
+            let probes = candidates.iter().map(|(region, endpoint)| {
+                let http_client = &http_client;
+                async move {
+                    let rtt = Self::probe_endpoint(http_client, endpoint, samples, timeout_duration).await;
+                    (*region, rtt)
+                }
+            });
+            let measured = futures::future::join_all(probes).await;
+
+            for (region, rtt) in &measured {
+                match rtt {
+                    Some(rtt) => debug!("📡 {} {:?}: {}ms", entry.name(), region, rtt),
+                    None => debug!("📡 {} {:?}: 不可达", entry.name(), region),
+                }
+            }
+
+            let Some((best_region, best_rtt)) = measured
+                .iter()
+                .filter_map(|(region, rtt)| rtt.map(|rtt| (*region, rtt)))
+                .min_by_key(|(_, rtt)| *rtt)
+            else {
+                warn!("⚠️  {} 所有地区端点均不可达，保持当前地区", entry.name());
+                continue;
+            };
+
+            let current_region = *entry.region.read();
+            if best_region == current_region {
+                info!("📡 {} 最快地区: {:?} ({}ms)，无需切换", entry.name(), best_region, best_rtt);
+                continue;
+            }
+
+            let mut region_config = entry.config.clone();
+            region_config.region = best_region;
+            match Self::create_client(&region_config) {
+                Ok(client) => {
+                    let previous_name = entry.name();
+                    entry.switch_region(self.chaos.wrap_swqos(client), best_region);
+                    info!("📡 {} 切换地区: {:?} -> {:?} ({}ms)",
+                        previous_name, current_region, best_region, best_rtt);
+                }
+                Err(e) => warn!("⚠️  {} 创建 {:?} 客户端失败: {}", entry.name(), best_region, e),
+            }
+        }
+    }
+
+    /// 定期地区延迟探测循环
+    pub async fn start_latency_probe_loop(self: Arc<Self>) {
+        let interval_secs = self.config.latency_probe_interval_secs;
+        if interval_secs == 0 || !self.clients.iter().any(|entry| entry.config.auto_region) {
+            return;
+        }
+
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        interval.tick().await; // 第一次 tick 立即返回，启动探测已单独执行
+
+        loop {
+            interval.tick().await;
+            self.select_fastest_regions().await;
+        }
+    }
+
+    /// 对所有服务执行连通性检查
+    ///
+    /// 记录 RTT，不可达或 API key 被拒绝的服务标记为禁用（恢复后自动重新启用）
+    pub async fn health_check_all(&self) {
+        let timeout_duration = Duration::from_millis(self.config.health_check_timeout_ms);
+
+        let checks = self.clients.iter().map(|entry| {
+            let entry = entry.clone();
+            async move {
+                let start = Instant::now();
+                let result = timeout(timeout_duration, entry.client().ping()).await;
+                let rtt = start.elapsed().as_millis() as u64;
+                (entry, result, rtt)
+            }
+        });
+
+        let mut healthy_count = 0;
+        for (entry, result, rtt) in futures::future::join_all(checks).await {
+            let healthy = match result {
+                Ok(Ok(())) => {
+                    info!("💓 SWQOS {} 可用 (RTT: {}ms)", entry.name(), rtt);
+                    true
+                }
+                Ok(Err(e)) => {
+                    warn!("💔 SWQOS {} 不可用: {} ({}ms)，已禁用", entry.name(), e, rtt);
+                    false
+                }
+                Err(_) => {
+                    warn!("💔 SWQOS {} 健康检查超时 ({}ms)，已禁用", entry.name(), rtt);
+                    false
+                }
+            };
+
+            let was_healthy = entry.healthy.swap(healthy, Ordering::Relaxed);
+            if healthy && !was_healthy {
+                info!("✅ SWQOS {} 已恢复，重新启用", entry.name());
+            }
+            if healthy {
+                healthy_count += 1;
+            }
+        }
+
+        info!("💓 SWQOS 健康检查完成: {}/{} 可用", healthy_count, self.clients.len());
+    }
+
+    /// 诊断：逐个服务 ping，返回 (服务名, RTT ms 或错误)（check-config 使用，不改变启用状态）
+    pub async fn probe_all(&self) -> Vec<(String, std::result::Result<u64, String>)> {
+        let timeout_duration = Duration::from_millis(self.config.health_check_timeout_ms);
+
+        let probes = self.clients.iter().map(|entry| async move {
+            let start = Instant::now();
+            let result = match timeout(timeout_duration, entry.client().ping()).await {
+                Ok(Ok(())) => Ok(start.elapsed().as_millis() as u64),
+                Ok(Err(e)) => Err(e.to_string()),
+                Err(_) => Err(format!("timed out after {}ms", self.config.health_check_timeout_ms)),
+            };
+            (entry.name(), result)
+        });
+
+        futures::future::join_all(probes).await
+    }
+
+    /// 定期健康检查循环
+    pub async fn start_health_check_loop(self: Arc<Self>) {
+        let interval_secs = self.config.health_check_interval_secs;
+        if interval_secs == 0 {
+            return;
+        }
+
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        interval.tick().await; // 第一次 tick 立即返回，启动检查已单独执行
+
+        loop {
+            interval.tick().await;
+            self.health_check_all().await;
+        }
+    }
+
     pub async fn send_transaction_race(&self, transaction: &VersionedTransaction) -> Result<SwqosResult> {
         info!("🏁 开始田忌赛马策略发送交易");
-        info!("   参与服务数量: {}", self.clients.len());
+        info!("   参与服务数量: {}", self.active_clients().len());
         info!("   最大重试次数: {}", self.config.max_retries);
 
-        if self.clients.is_empty() {
+        if self.active_clients().is_empty() {
             return Err(anyhow::anyhow!("没有可用的 SWQOS 服务"));
         }
 
@@ -753,6 +1274,13 @@ impl MultiSwqosManager {
                 }
             }
 
+            // 🔥 blockhash 已失效时原样重发没有意义，交给调用方用新 blockhash 重建
+            if let Some(e) = &last_error {
+                if classify_send_error(e) == SendErrorKind::BlockhashNotFound {
+                    break;
+                }
+            }
+
             // 如果还有重试机会，等待一小段时间
             if attempt < self.config.max_retries {
                 tokio::time::sleep(Duration::from_millis(200)).await;
@@ -766,15 +1294,31 @@ impl MultiSwqosManager {
         info!("⚡ 使用并行发送策略");
 
         let mut tasks = Vec::new();
+        let mut names = Vec::new();
 
-        for (idx, client) in self.clients.iter().enumerate() {
-            let client = client.clone();
+        for entry in self.active_clients() {
+            let client = entry.client();
             let transaction = transaction.clone();
-            let service_name = format!("Service-{}", idx);
+            let service_name = entry.name();
+            names.push(service_name.clone());
 
             let task = tokio::spawn(async move {
                 let start = Instant::now();
                 match timeout(timeout_duration, client.send_transaction(&transaction)).await {
+                    // 🔥 重复签名（AlreadyProcessed）说明交易已上链，视为成功，
+                    //    但标记为重复发送（调用方和日志可以区分"本服务送达"与"已被处理"）
+                    Ok(Err(e)) if classify_send_error(&e) == SendErrorKind::AlreadyProcessed => {
+                        let latency = start.elapsed().as_millis() as u64;
+                        warn!("♻️  {} 返回 AlreadyProcessed（重复发送，交易已上链）", service_name);
+                        SwqosResult {
+                            service_name,
+                            signature: Some(transaction.signatures[0]),
+                            success: true,
+                            latency_ms: latency,
+                            error: None,
+                            already_processed: true,
+                        }
+                    }
                     Ok(Ok(signature)) => {
                         let latency = start.elapsed().as_millis() as u64;
                         SwqosResult {
@@ -783,6 +1327,7 @@ impl MultiSwqosManager {
                             success: true,
                             latency_ms: latency,
                             error: None,
+                            already_processed: false,
                         }
                     }
                     Ok(Err(e)) => {
@@ -793,6 +1338,7 @@ impl MultiSwqosManager {
                             success: false,
                             latency_ms: latency,
                             error: Some(e.to_string()),
+                            already_processed: false,
                         }
                     }
                     Err(_) => {
@@ -803,6 +1349,7 @@ impl MultiSwqosManager {
                             success: false,
                             latency_ms: latency,
                             error: Some("Timeout".to_string()),
+                            already_processed: false,
                         }
                     }
                 }
@@ -811,16 +1358,23 @@ impl MultiSwqosManager {
             tasks.push(task);
         }
 
+        // 🔥 修复: 按完成顺序收集结果，第一个成功后立即中止其余任务（之前按顺序 await，慢请求会阻塞快请求）
+        let race_start = Instant::now();
+        let mut pending_names = names;
+        let abort_handles: Vec<AbortHandle> = tasks.iter().map(JoinHandle::abort_handle).collect();
+        let mut in_flight: FuturesUnordered<JoinHandle<SwqosResult>> = tasks.into_iter().collect();
+
         let mut first_success: Option<SwqosResult> = None;
         let mut all_results = Vec::new();
 
-        for task in tasks {
-            match task.await {
+        while let Some(joined) = in_flight.next().await {
+            match joined {
                 Ok(result) => {
+                    pending_names.retain(|name| *name != result.service_name);
                     all_results.push(result.clone());
-                    if result.success && first_success.is_none() {
-                        first_success = Some(result.clone());
+                    if result.success {
                         info!("🏆 第一个成功的服务: {}", result.service_name);
+                        first_success = Some(result);
                         break;
                     }
                 }
@@ -830,6 +1384,23 @@ impl MultiSwqosManager {
             }
         }
 
+        // 中止仍在进行的慢请求，并记录为已取消
+        for handle in &abort_handles {
+            handle.abort();
+        }
+        let cancelled_latency = race_start.elapsed().as_millis() as u64;
+        for service_name in pending_names {
+            debug!("🛑 已取消较慢的服务: {}", service_name);
+            all_results.push(SwqosResult {
+                service_name,
+                signature: None,
+                success: false,
+                latency_ms: cancelled_latency,
+                error: Some("Cancelled".to_string()),
+                already_processed: false,
+            });
+        }
+
         {
             let mut results = self.results.write().await;
             for result in &all_results {
@@ -838,7 +1409,11 @@ impl MultiSwqosManager {
         }
 
         if let Some(success_result) = first_success {
-            info!("✅ 田忌赛马成功: {} ({}ms)", success_result.service_name, success_result.latency_ms);
+            if success_result.already_processed {
+                warn!("♻️  田忌赛马: {} 报告交易已处理（重复发送）", success_result.service_name);
+            } else {
+                info!("✅ 田忌赛马成功: {} ({}ms)", success_result.service_name, success_result.latency_ms);
+            }
             Ok(success_result)
         } else {
             let fastest = all_results.iter().min_by_key(|r| r.latency_ms);
@@ -854,13 +1429,13 @@ impl MultiSwqosManager {
     async fn send_sequential(&self, transaction: &VersionedTransaction, timeout_duration: Duration) -> Result<SwqosResult> {
         info!("🔄 使用顺序发送策略");
 
-        for (idx, client) in self.clients.iter().enumerate() {
-            let service_name = format!("Service-{}", idx);
+        for entry in self.active_clients() {
+            let service_name = entry.name();
 
             info!("🎯 尝试服务: {}", service_name);
 
             let start = Instant::now();
-            match timeout(timeout_duration, client.send_transaction(transaction)).await {
+            match timeout(timeout_duration, entry.client().send_transaction(transaction)).await {
                 Ok(Ok(signature)) => {
                     let latency = start.elapsed().as_millis() as u64;
                     let result = SwqosResult {
@@ -869,6 +1444,7 @@ impl MultiSwqosManager {
                         success: true,
                         latency_ms: latency,
                         error: None,
+                        already_processed: false,
                     };
 
                     info!("✅ 顺序发送成功: {} ({}ms)", service_name, latency);
@@ -876,7 +1452,27 @@ impl MultiSwqosManager {
                 }
                 Ok(Err(e)) => {
                     let latency = start.elapsed().as_millis() as u64;
-                    warn!("❌ 服务 {} 失败: {} ({}ms)", service_name, e, latency);
+                    match classify_send_error(&e) {
+                        SendErrorKind::AlreadyProcessed => {
+                            warn!("♻️  {} 返回 AlreadyProcessed（重复发送，交易已上链, {}ms)", service_name, latency);
+                            return Ok(SwqosResult {
+                                service_name,
+                                signature: Some(transaction.signatures[0]),
+                                success: true,
+                                latency_ms: latency,
+                                error: None,
+                                already_processed: true,
+                            });
+                        }
+                        SendErrorKind::BlockhashNotFound => {
+                            // 同一笔交易发给其他服务也会失败，交给调用方重建
+                            warn!("❌ 服务 {} blockhash 已失效 ({}ms)", service_name, latency);
+                            return Err(e);
+                        }
+                        SendErrorKind::Other => {
+                            warn!("❌ 服务 {} 失败: {} ({}ms)", service_name, e, latency);
+                        }
+                    }
                 }
                 Err(_) => {
                     let latency = start.elapsed().as_millis() as u64;
@@ -907,18 +1503,63 @@ impl MultiSwqosManager {
     ) -> Result<Vec<(String, solana_sdk::instruction::Instruction)>> {
         use solana_system_interface::instruction::transfer;
 
+        // 1. 候选服务（按优先级排序）：tip 账户集合相同的服务只付一次 tip
+        // 🔥 修复: 客户端与其自身的服务配置一一对应（之前与未排序的 services 列表 zip 会错位）
+        let mut seen_tip_sets: Vec<Vec<&'static str>> = Vec::new();
+        let mut candidates = Vec::new();
+        for entry in self.active_clients() {
+            let mut tip_set = tip_accounts(entry.config.service_type).to_vec();
+            tip_set.sort_unstable();
+
+            // 没有 tip 账户的服务（自定义 staked RPC）不需要 tip
+            if tip_set.is_empty() {
+                continue;
+            }
+            if seen_tip_sets.contains(&tip_set) {
+                debug!("🔁 服务 {} 与更高优先级服务共用 tip 账户，跳过重复 tip", entry.name());
+                continue;
+            }
+            seen_tip_sets.push(tip_set);
+            candidates.push(entry);
+        }
+
+        // 2. 按优先级裁剪（预算模式下取前 tip_budget_services 个）
+        let budget_mode = self.config.tip_budget_lamports > 0;
+        let limit = if budget_mode {
+            self.config.tip_budget_services.min(self.config.max_tips)
+        } else {
+            self.config.max_tips
+        };
+        if candidates.len() > limit {
+            info!("⚠️  服务数量 {} 超过限制 {}，按优先级裁剪", candidates.len(), limit);
+            candidates.truncate(limit);
+        }
+
+        // 3. 预算模式：总预算在选中服务间平分（余数给优先级最高的服务）
+        let (budget_share, budget_remainder) = if budget_mode && !candidates.is_empty() {
+            let count = candidates.len() as u64;
+            (self.config.tip_budget_lamports / count, self.config.tip_budget_lamports % count)
+        } else {
+            (0, 0)
+        };
+
         let mut tip_instructions = Vec::new();
+        let mut total_tip_lamports = 0u64;
+
+        for (idx, entry) in candidates.iter().enumerate() {
+            let client = entry.client();
+            let service_config = &entry.config;
+            let service_name = entry.name();
 
-        for (client, service_config) in self.clients.iter().zip(&self.config.services) {
             // 获取服务类型
             let swqos_type = client.get_swqos_type();
-            debug!("🔍 服务 {}: 类型 = {:?}", service_config.name, swqos_type);
+            debug!("🔍 服务 {}: 类型 = {:?}", service_name, swqos_type);
 
             // 获取 tip 地址
             let tip_address_str = match client.get_tip_account() {
                 Ok(addr) => addr,
                 Err(e) => {
-                    warn!("⚠️  获取服务 {} 的 tip 地址失败: {}", service_config.name, e);
+                    warn!("⚠️  获取服务 {} 的 tip 地址失败: {}", service_name, e);
                     continue;
                 }
             };
@@ -926,35 +1567,47 @@ impl MultiSwqosManager {
             let tip_address = match tip_address_str.parse::<solana_sdk::pubkey::Pubkey>() {
                 Ok(addr) => addr,
                 Err(e) => {
-                    warn!("⚠️  解析服务 {} 的 tip 地址失败: {}", service_config.name, e);
+                    warn!("⚠️  解析服务 {} 的 tip 地址失败: {}", service_name, e);
                     continue;
                 }
             };
 
-            // 获取 tip 金额（从配置或使用默认值）
-            let tip_lamports = service_config.tip_lamports.unwrap_or(100_000); // 默认 0.0001 SOL
+            // 获取 tip 金额（预算模式使用分摊金额，否则从配置或使用默认值）
+            let tip_lamports = if budget_mode {
+                budget_share + if idx == 0 { budget_remainder } else { 0 }
+            } else {
+                service_config.tip_lamports.unwrap_or(100_000) // 默认 0.0001 SOL
+            };
+
+            // 4. 单笔 tip 总额上限：超出部分从低优先级服务削减
+            let tip_lamports = if self.config.max_tip_per_tx_lamports > 0 {
+                let remaining = self.config.max_tip_per_tx_lamports.saturating_sub(total_tip_lamports);
+                if remaining == 0 {
+                    warn!("⚠️  已达单笔 tip 上限 {} lamports，跳过服务 {}",
+                        self.config.max_tip_per_tx_lamports, service_name);
+                    continue;
+                }
+                if tip_lamports > remaining {
+                    warn!("⚠️  服务 {} tip 从 {} 削减到 {} lamports（单笔上限 {}）",
+                        service_name, tip_lamports, remaining, self.config.max_tip_per_tx_lamports);
+                }
+                tip_lamports.min(remaining)
+            } else {
+                tip_lamports
+            };
+            total_tip_lamports += tip_lamports;
 
             debug!("💰 服务 {}: tip 地址 = {}, 金额 = {} lamports",
-                service_config.name, tip_address, tip_lamports);
+                service_name, tip_address, tip_lamports);
 
             // 创建 transfer 指令
             let tip_instruction = transfer(payer, &tip_address, tip_lamports);
 
-            tip_instructions.push((service_config.name.clone(), tip_instruction));
-        }
-
-        // 🔥 按优先级裁剪（取优先级最高的前 max_tips 个）
-        if tip_instructions.len() > self.config.max_tips {
-            info!("⚠️  服务数量 {} 超过限制 {}，按优先级裁剪",
-                tip_instructions.len(), self.config.max_tips);
-
-            // 按 priority 排序（已在初始化时按 priority 排序 services）
-            tip_instructions.truncate(self.config.max_tips);
-
-            info!("✅ 裁剪后保留 {} 个高优先级 tip 指令", tip_instructions.len());
+            tip_instructions.push((service_name, tip_instruction));
         }
 
-        info!("✅ 已生成 {} 个 SWQOS tip 指令", tip_instructions.len());
+        info!("✅ 已生成 {} 个 SWQOS tip 指令 (合计 {} SOL)",
+            tip_instructions.len(), total_tip_lamports as f64 / 1_000_000_000.0);
 
         Ok(tip_instructions)
     }
@@ -1041,9 +1694,42 @@ impl SwqosClientTrait for JitoClient {
         get_random_tip_account(SwqosType::Jito)
     }
 
+    /// Jito 使用 getTipAccounts 验证端点和 UUID
+    async fn ping(&self) -> Result<()> {
+        let request_body = serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "getTipAccounts",
+            "params": []
+        });
+
+        let mut request = self.http_client.post(format!("{}/api/v1/bundles", self.endpoint))
+            .header("Content-Type", "application/json")
+            .json(&request_body);
+
+        if !self.auth_token.is_empty() {
+            request = request.header("x-jito-auth", &self.auth_token);
+        }
+
+        let response = request.send().await?;
+        check_ping_status(response.status(), "Jito UUID")
+    }
+
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::Jito
     }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn auth_token(&self) -> &str {
+        &self.auth_token
+    }
+
+    fn http_client(&self) -> &Client {
+        &self.http_client
+    }
 }
 
 /// NextBlock 客户端
@@ -1051,10 +1737,12 @@ pub struct NextBlockClient {
     pub endpoint: String,
     pub auth_token: String,
     pub http_client: Client,
+    /// 防夹模式（frontRunningProtection）
+    pub anti_mev: bool,
 }
 
 impl NextBlockClient {
-    pub fn new(endpoint: String, auth_token: String) -> Self {
+    pub fn new(endpoint: String, auth_token: String, anti_mev: bool) -> Self {
         let endpoint = if endpoint.ends_with("/api/v2/submit") {
             endpoint
         } else {
@@ -1069,7 +1757,7 @@ impl NextBlockClient {
             .connect_timeout(Duration::from_secs(5))
             .build()
             .unwrap();
-        Self { endpoint, auth_token, http_client }
+        Self { endpoint, auth_token, http_client, anti_mev }
     }
 
     fn serialize_transaction(&self, transaction: &VersionedTransaction) -> Result<String> {
@@ -1088,7 +1776,7 @@ impl SwqosClientTrait for NextBlockClient {
             "transaction": {
                 "content": content
             },
-            "frontRunningProtection": false
+            "frontRunningProtection": self.anti_mev
         });
 
         let response = self.http_client.post(&self.endpoint)
@@ -1118,6 +1806,18 @@ impl SwqosClientTrait for NextBlockClient {
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::NextBlock
     }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn auth_token(&self) -> &str {
+        &self.auth_token
+    }
+
+    fn http_client(&self) -> &Client {
+        &self.http_client
+    }
 }
 
 /// Bloxroute 客户端
@@ -1125,10 +1825,14 @@ pub struct BloxrouteClient {
     pub endpoint: String,
     pub auth_token: String,
     pub http_client: Client,
+    /// 防夹模式（frontRunningProtection）
+    pub anti_mev: bool,
+    /// 是否通过 staked RPC 转发（useStakedRPCs）
+    pub use_staked_rpcs: bool,
 }
 
 impl BloxrouteClient {
-    pub fn new(endpoint: String, auth_token: String) -> Self {
+    pub fn new(endpoint: String, auth_token: String, anti_mev: bool, use_staked_rpcs: bool) -> Self {
         let http_client = Client::builder()
             .pool_idle_timeout(Duration::from_secs(60))
             .pool_max_idle_per_host(64)
@@ -1138,7 +1842,7 @@ impl BloxrouteClient {
             .connect_timeout(Duration::from_secs(5))
             .build()
             .unwrap();
-        Self { endpoint, auth_token, http_client }
+        Self { endpoint, auth_token, http_client, anti_mev, use_staked_rpcs }
     }
 
     fn serialize_transaction(&self, transaction: &VersionedTransaction) -> Result<String> {
@@ -1157,8 +1861,8 @@ impl SwqosClientTrait for BloxrouteClient {
             "transaction": {
                 "content": content,
             },
-            "frontRunningProtection": false,
-            "useStakedRPCs": true,
+            "frontRunningProtection": self.anti_mev,
+            "useStakedRPCs": self.use_staked_rpcs,
         });
 
         let endpoint = format!("{}/api/v2/submit", self.endpoint);
@@ -1186,14 +1890,150 @@ impl SwqosClientTrait for BloxrouteClient {
         get_random_tip_account(SwqosType::Bloxroute)
     }
 
+    /// Bloxroute 的提交接口在 `/api/v2/submit`
+    async fn ping(&self) -> Result<()> {
+        let response = self.http_client.post(format!("{}/api/v2/submit", self.endpoint))
+            .header("Content-Type", "application/json")
+            .header("Authorization", &self.auth_token)
+            .json(&serde_json::json!({}))
+            .send()
+            .await?;
+
+        check_ping_status(response.status(), "Bloxroute auth header")
+    }
+
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::Bloxroute
     }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn auth_token(&self) -> &str {
+        &self.auth_token
+    }
+
+    fn http_client(&self) -> &Client {
+        &self.http_client
+    }
 }
 
-// 使用宏简化其他客户端实现
-macro_rules! impl_simple_swqos_client {
-    ($client_name:ident, $swqos_type:expr) => {
+/// 自定义 staked RPC 客户端（SwqosType::Default）
+///
+/// 不依赖付费中继：将 `sendTransaction` 并行扇出到用户提供的 RPC 列表，任一成功即返回
+pub struct StakedRpcClient {
+    pub endpoints: Vec<String>,
+    pub http_client: Client,
+}
+
+impl StakedRpcClient {
+    pub fn new(endpoints: Vec<String>) -> Self {
+        let http_client = Client::builder()
+            .pool_idle_timeout(Duration::from_secs(60))
+            .pool_max_idle_per_host(64)
+            .tcp_keepalive(Some(Duration::from_secs(1200)))
+            .http2_keep_alive_interval(Duration::from_secs(15))
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        Self { endpoints, http_client }
+    }
+
+    /// 向单个 RPC 发送 JSON-RPC 请求，返回 result 字段
+    async fn rpc_call(&self, endpoint: &str, request_body: &serde_json::Value) -> Result<serde_json::Value> {
+        let response = self.http_client.post(endpoint)
+            .header("Content-Type", "application/json")
+            .json(request_body)
+            .send()
+            .await?;
+
+        let response_text = response.text().await?;
+        let response_json: serde_json::Value = serde_json::from_str(&response_text)
+            .map_err(|_| anyhow::anyhow!("{} invalid response: {}", endpoint, response_text))?;
+
+        if let Some(result) = response_json.get("result") {
+            Ok(result.clone())
+        } else if let Some(error) = response_json.get("error") {
+            Err(anyhow::anyhow!("{} error: {:?}", endpoint, error))
+        } else {
+            Err(anyhow::anyhow!("{} failed: {}", endpoint, response_text))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for StakedRpcClient {
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        let content = STANDARD.encode(bincode::serialize(transaction)?);
+        let signature = transaction.signatures[0];
+
+        let request_body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sendTransaction",
+            "params": [
+                content,
+                {
+                    "encoding": "base64",
+                    "skipPreflight": true,
+                    "maxRetries": 0
+                }
+            ]
+        });
+
+        let sends = self.endpoints
+            .iter()
+            .map(|endpoint| Box::pin(self.rpc_call(endpoint, &request_body)));
+
+        let result = futures::future::select_ok(sends).await;
+        match result {
+            Ok(_) => Ok(signature),
+            Err(e) => Err(anyhow::anyhow!("All staked RPCs failed, last error: {}", e)),
+        }
+    }
+
+    /// 任一 RPC 的 getHealth 返回成功即视为可用
+    async fn ping(&self) -> Result<()> {
+        let request_body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getHealth"
+        });
+
+        let checks = self.endpoints
+            .iter()
+            .map(|endpoint| Box::pin(self.rpc_call(endpoint, &request_body)));
+
+        let result = futures::future::select_ok(checks).await;
+        result.map(|_| ())
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        get_random_tip_account(SwqosType::Default)
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        SwqosType::Default
+    }
+
+    fn endpoint(&self) -> &str {
+        self.endpoints.first().map(String::as_str).unwrap_or_default()
+    }
+
+    fn auth_token(&self) -> &str {
+        ""
+    }
+
+    fn http_client(&self) -> &Client {
+        &self.http_client
+    }
+}
+
+// 使用宏简化其他客户端实现
+macro_rules! impl_simple_swqos_client {
+    ($client_name:ident, $swqos_type:expr) => {
         pub struct $client_name {
             pub endpoint: String,
             pub auth_token: String,
@@ -1259,13 +2099,322 @@ macro_rules! impl_simple_swqos_client {
             fn get_swqos_type(&self) -> SwqosType {
                 $swqos_type
             }
+
+            fn endpoint(&self) -> &str {
+                &self.endpoint
+            }
+
+            fn auth_token(&self) -> &str {
+                &self.auth_token
+            }
+
+            fn http_client(&self) -> &Client {
+                &self.http_client
+            }
         }
     };
 }
 
 impl_simple_swqos_client!(TemporalClient, SwqosType::Temporal);
-impl_simple_swqos_client!(ZeroSlotClient, SwqosType::ZeroSlot);
-impl_simple_swqos_client!(Node1Client, SwqosType::Node1);
-impl_simple_swqos_client!(FlashBlockClient, SwqosType::FlashBlock);
 impl_simple_swqos_client!(BlockRazorClient, SwqosType::BlockRazor);
 impl_simple_swqos_client!(AstralaneClient, SwqosType::Astralane);
+
+/// ZeroSlot 客户端 (参考 sol-trade-sdk/src/swqos/zeroslot.rs)
+///
+/// JSON-RPC sendTransaction，API key 通过 `api-key` 查询参数传递
+pub struct ZeroSlotClient {
+    pub endpoint: String,
+    pub auth_token: String,
+    pub http_client: Client,
+}
+
+impl ZeroSlotClient {
+    pub fn new(endpoint: String, auth_token: String) -> Self {
+        let http_client = Client::builder()
+            .pool_idle_timeout(Duration::from_secs(60))
+            .pool_max_idle_per_host(64)
+            .tcp_keepalive(Some(Duration::from_secs(1200)))
+            .http2_keep_alive_interval(Duration::from_secs(15))
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        Self { endpoint, auth_token, http_client }
+    }
+
+    fn serialize_transaction(&self, transaction: &VersionedTransaction) -> Result<String> {
+        let serialized = bincode::serialize(transaction)?;
+        Ok(STANDARD.encode(serialized))
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for ZeroSlotClient {
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        let content = self.serialize_transaction(transaction)?;
+        let signature = transaction.signatures[0];
+
+        let request_body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sendTransaction",
+            "params": [
+                content,
+                {
+                    "encoding": "base64",
+                    "skipPreflight": true
+                }
+            ]
+        });
+
+        let endpoint = format!("{}/?api-key={}", self.endpoint.trim_end_matches('/'), self.auth_token);
+        let response = self.http_client.post(&endpoint)
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let response_text = response.text().await?;
+
+        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
+            if response_json.get("result").is_some() {
+                return Ok(signature);
+            } else if let Some(error) = response_json.get("error") {
+                return Err(anyhow::anyhow!("ZeroSlot error: {:?}", error));
+            }
+        }
+
+        Err(anyhow::anyhow!("ZeroSlot failed: {}", response_text))
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        get_random_tip_account(SwqosType::ZeroSlot)
+    }
+
+    /// API key 通过 `api-key` 查询参数传递
+    async fn ping(&self) -> Result<()> {
+        let endpoint = format!("{}/?api-key={}", self.endpoint.trim_end_matches('/'), self.auth_token);
+        let response = self.http_client.post(&endpoint)
+            .header("Content-Type", "application/json")
+            .json(&ping_rpc_body())
+            .send()
+            .await?;
+
+        check_ping_status(response.status(), "ZeroSlot API key")
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        SwqosType::ZeroSlot
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn auth_token(&self) -> &str {
+        &self.auth_token
+    }
+
+    fn http_client(&self) -> &Client {
+        &self.http_client
+    }
+}
+
+/// Node1 客户端 (参考 sol-trade-sdk/src/swqos/node1.rs)
+///
+/// JSON-RPC sendTransaction，API key 通过 `api-key` 请求头传递
+pub struct Node1Client {
+    pub endpoint: String,
+    pub auth_token: String,
+    pub http_client: Client,
+}
+
+impl Node1Client {
+    pub fn new(endpoint: String, auth_token: String) -> Self {
+        let http_client = Client::builder()
+            .pool_idle_timeout(Duration::from_secs(60))
+            .pool_max_idle_per_host(64)
+            .tcp_keepalive(Some(Duration::from_secs(1200)))
+            .http2_keep_alive_interval(Duration::from_secs(15))
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        Self { endpoint, auth_token, http_client }
+    }
+
+    fn serialize_transaction(&self, transaction: &VersionedTransaction) -> Result<String> {
+        let serialized = bincode::serialize(transaction)?;
+        Ok(STANDARD.encode(serialized))
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for Node1Client {
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        let content = self.serialize_transaction(transaction)?;
+        let signature = transaction.signatures[0];
+
+        let request_body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sendTransaction",
+            "params": [
+                content,
+                {
+                    "encoding": "base64",
+                    "skipPreflight": true
+                }
+            ]
+        });
+
+        let endpoint = format!("{}/", self.endpoint.trim_end_matches('/'));
+        let response = self.http_client.post(&endpoint)
+            .header("Content-Type", "application/json")
+            .header("api-key", &self.auth_token)
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let response_text = response.text().await?;
+
+        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
+            if response_json.get("result").is_some() {
+                return Ok(signature);
+            } else if let Some(error) = response_json.get("error") {
+                return Err(anyhow::anyhow!("Node1 error: {:?}", error));
+            }
+        }
+
+        Err(anyhow::anyhow!("Node1 failed: {}", response_text))
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        get_random_tip_account(SwqosType::Node1)
+    }
+
+    /// API key 通过 `api-key` 请求头传递
+    async fn ping(&self) -> Result<()> {
+        let endpoint = format!("{}/", self.endpoint.trim_end_matches('/'));
+        let response = self.http_client.post(&endpoint)
+            .header("Content-Type", "application/json")
+            .header("api-key", &self.auth_token)
+            .json(&ping_rpc_body())
+            .send()
+            .await?;
+
+        check_ping_status(response.status(), "Node1 API key")
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        SwqosType::Node1
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn auth_token(&self) -> &str {
+        &self.auth_token
+    }
+
+    fn http_client(&self) -> &Client {
+        &self.http_client
+    }
+}
+
+/// FlashBlock 客户端 (参考 sol-trade-sdk/src/swqos/flashblock.rs)
+///
+/// 批量提交接口 `/api/v2/submit-batch`，API key 通过 `Authorization` 请求头传递
+pub struct FlashBlockClient {
+    pub endpoint: String,
+    pub auth_token: String,
+    pub http_client: Client,
+}
+
+impl FlashBlockClient {
+    pub fn new(endpoint: String, auth_token: String) -> Self {
+        let http_client = Client::builder()
+            .pool_idle_timeout(Duration::from_secs(60))
+            .pool_max_idle_per_host(64)
+            .tcp_keepalive(Some(Duration::from_secs(1200)))
+            .http2_keep_alive_interval(Duration::from_secs(15))
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        Self { endpoint, auth_token, http_client }
+    }
+
+    fn serialize_transaction(&self, transaction: &VersionedTransaction) -> Result<String> {
+        let serialized = bincode::serialize(transaction)?;
+        Ok(STANDARD.encode(serialized))
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for FlashBlockClient {
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        let content = self.serialize_transaction(transaction)?;
+        let signature = transaction.signatures[0];
+
+        let request_body = serde_json::json!({
+            "transactions": [content]
+        });
+
+        let endpoint = format!("{}/api/v2/submit-batch", self.endpoint.trim_end_matches('/'));
+        let response = self.http_client.post(&endpoint)
+            .header("Content-Type", "application/json")
+            .header("Authorization", &self.auth_token)
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let response_text = response.text().await?;
+
+        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
+            if response_json.get("success").and_then(|v| v.as_bool()) == Some(true)
+                || response_json.get("result").is_some()
+            {
+                return Ok(signature);
+            } else if let Some(error) = response_json.get("error").or_else(|| response_json.get("message")) {
+                return Err(anyhow::anyhow!("FlashBlock error: {:?}", error));
+            }
+        }
+
+        Err(anyhow::anyhow!("FlashBlock failed: {}", response_text))
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        get_random_tip_account(SwqosType::FlashBlock)
+    }
+
+    /// 提交空批次（`/api/v2/submit-batch`）
+    async fn ping(&self) -> Result<()> {
+        let endpoint = format!("{}/api/v2/submit-batch", self.endpoint.trim_end_matches('/'));
+        let response = self.http_client.post(&endpoint)
+            .header("Content-Type", "application/json")
+            .header("Authorization", &self.auth_token)
+            .json(&serde_json::json!({ "transactions": [] }))
+            .send()
+            .await?;
+
+        check_ping_status(response.status(), "FlashBlock API key")
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        SwqosType::FlashBlock
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn auth_token(&self) -> &str {
+        &self.auth_token
+    }
+
+    fn http_client(&self) -> &Client {
+        &self.http_client
+    }
+}
@@ -0,0 +1,197 @@
+/// 累计买入阈值触发
+///
+/// 新币观察窗口内累计买入达到阈值时立即买入（优先级最高，不看其他指标）
+///
+/// 核心功能:
+/// 1. 多档阈值 - threshold_tiers=5:0.1,20:0.5（累计买入 SOL:买入 SOL），从低到高依次触发，每档最多触发一次
+/// 2. 单币覆盖 - 策略覆盖规则的 threshold_tiers 按 mint / 创建者替换全局档位
+/// 3. 卖出后重新布防 - threshold_rearm_after_sell 时平仓后清零已触发档位，重新开始观察窗口
+///    （只记录触发过档位的 mint，窗口移除时清理）
+/// 4. 累计方式 - threshold_accumulation=window 只统计主滑窗内的买入（移出滑窗的买入不再计入），
+///    lifetime 为窗口创建以来的全部买入（旧行为，慢速零散买入最终也会触发）
+///
+/// 未配置 threshold_tiers 时只有一档: threshold_cumulative_buy_sol，
+/// 买入金额 = 阈值 × threshold_buy_ratio（限制在 threshold_min/max_buy_amount_sol 之间）
+///
+/// 持仓期间触发的更高档位仍会发出买入信号，是否加仓由持仓管理器决定（当前已持仓时不再买入）
+
+use log::warn;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use crate::config::Config;
+use crate::strategy_overrides::StrategyOverrideTable;
+
+/// 单个阈值档位
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdTier {
+    /// 触发所需的累计买入（SOL）
+    pub cumulative_buy_sol: f64,
+    /// 触发后的买入金额（SOL）
+    pub buy_amount_sol: f64,
+}
+
+impl ThresholdTier {
+    /// 解析 "累计买入:买入金额"（例如 "5:0.1"）
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (cumulative, amount) = spec.trim().split_once(':')?;
+        Some(Self {
+            cumulative_buy_sol: cumulative.trim().parse().ok()?,
+            buy_amount_sol: amount.trim().parse().ok()?,
+        })
+    }
+
+    fn is_valid(&self) -> bool {
+        self.cumulative_buy_sol > 0.0 && self.buy_amount_sol > 0.0
+    }
+}
+
+/// 按累计买入从低到高排序并校验档位
+pub fn sorted_tiers(mut tiers: Vec<ThresholdTier>) -> anyhow::Result<Vec<ThresholdTier>> {
+    if let Some(tier) = tiers.iter().find(|tier| !tier.is_valid()) {
+        anyhow::bail!(
+            "threshold tier {}:{} must have cumulative_buy_sol > 0 and buy_amount_sol > 0",
+            tier.cumulative_buy_sol, tier.buy_amount_sol
+        );
+    }
+    tiers.sort_by(|a, b| a.cumulative_buy_sol.total_cmp(&b.cumulative_buy_sol));
+    Ok(tiers)
+}
+
+/// 全局档位（配置了 threshold_tiers 时使用多档，否则按旧参数生成单档）
+pub fn tiers_from_config(config: &Config) -> anyhow::Result<Vec<ThresholdTier>> {
+    if config.threshold_tiers.is_empty() {
+        let buy_amount_sol = (config.threshold_cumulative_buy_sol * config.threshold_buy_ratio)
+            .max(config.threshold_min_buy_amount_sol)
+            .min(config.threshold_max_buy_amount_sol);
+        return Ok(vec![ThresholdTier {
+            cumulative_buy_sol: config.threshold_cumulative_buy_sol,
+            buy_amount_sol,
+        }]);
+    }
+
+    let tiers = config
+        .threshold_tiers
+        .iter()
+        .map(|spec| {
+            ThresholdTier::parse(spec)
+                .ok_or_else(|| anyhow::anyhow!("Invalid threshold tier '{}', expected <cumulative_sol>:<buy_sol>", spec))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    sorted_tiers(tiers)
+}
+
+/// 累计买入的统计方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThresholdAccumulation {
+    /// 只统计当前主滑窗内的买入
+    Window,
+    /// 统计窗口创建（或重新布防）以来的全部买入
+    Lifetime,
+}
+
+impl ThresholdAccumulation {
+    /// 解析名称（不区分大小写）
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "window" => Some(Self::Window),
+            "lifetime" => Some(Self::Lifetime),
+            _ => None,
+        }
+    }
+}
+
+/// 阈值触发配置（聚合器持有，持仓管理器平仓后请求重新布防）
+pub struct ThresholdTrigger {
+    enabled: bool,
+    observation_window_secs: u64,
+    tiers: Vec<ThresholdTier>,
+    rearm_after_sell: bool,
+    accumulation: ThresholdAccumulation,
+    /// 单币覆盖规则（只使用其中的 threshold_tiers）
+    overrides: StrategyOverrideTable,
+    /// 已触发过档位的 mint（只有这些 mint 平仓后需要重新布防）
+    fired: Mutex<HashSet<Pubkey>>,
+    /// 已平仓、等待重新布防的 mint
+    pending_rearm: Mutex<HashSet<Pubkey>>,
+}
+
+impl ThresholdTrigger {
+    pub fn from_config(config: &Config) -> Self {
+        // Config::validate 已校验档位，这里解析失败时直接关闭阈值触发
+        let tiers = tiers_from_config(config).unwrap_or_else(|e| {
+            warn!("⚠️  解析阈值档位失败: {:#}, 阈值触发已禁用", e);
+            Vec::new()
+        });
+        let overrides = StrategyOverrideTable::from_config(config).unwrap_or_else(|e| {
+            warn!("⚠️  加载策略覆盖规则失败: {:#}, 阈值触发不使用覆盖规则", e);
+            StrategyOverrideTable::default()
+        });
+
+        Self {
+            enabled: config.enable_threshold_trigger && !tiers.is_empty(),
+            observation_window_secs: config.threshold_observation_window_secs,
+            tiers,
+            rearm_after_sell: config.threshold_rearm_after_sell,
+            accumulation: ThresholdAccumulation::parse(&config.threshold_accumulation)
+                .unwrap_or(ThresholdAccumulation::Window),
+            overrides,
+            fired: Mutex::new(HashSet::new()),
+            pending_rearm: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 观察窗口（秒，从窗口创建或重新布防开始计算）
+    pub fn observation_window_secs(&self) -> u64 {
+        self.observation_window_secs
+    }
+
+    pub fn accumulation(&self) -> ThresholdAccumulation {
+        self.accumulation
+    }
+
+    /// 某个 token 的档位（覆盖规则 > 全局配置；覆盖规则的档位加载时已校验并排序）
+    pub fn tiers_for(&self, mint: &Pubkey, creator: &Pubkey) -> Cow<'_, [ThresholdTier]> {
+        if self.overrides.is_empty() {
+            return Cow::Borrowed(&self.tiers);
+        }
+        match self.overrides.resolve(mint, creator).and_then(|rule| rule.threshold_tiers) {
+            Some(tiers) => Cow::Owned(tiers),
+            None => Cow::Borrowed(&self.tiers),
+        }
+    }
+
+    /// 记录某个 mint 触发了档位（聚合器触发时调用）
+    pub fn record_fired(&self, mint: &Pubkey) {
+        if self.rearm_after_sell {
+            self.fired.lock().insert(*mint);
+        }
+    }
+
+    /// 平仓后请求重新布防（未启用 threshold_rearm_after_sell 或该 mint 未触发过档位时忽略）
+    pub fn rearm(&self, mint: &Pubkey) {
+        if self.enabled && self.rearm_after_sell && self.fired.lock().remove(mint) {
+            self.pending_rearm.lock().insert(*mint);
+        }
+    }
+
+    /// 清理某个 mint 的布防状态（聚合器移除窗口时调用）
+    pub fn forget(&self, mint: &Pubkey) {
+        if self.rearm_after_sell {
+            self.fired.lock().remove(mint);
+            self.pending_rearm.lock().remove(mint);
+        }
+    }
+
+    /// 取走重新布防请求（聚合器在下一笔交易时处理）
+    pub fn take_rearm(&self, mint: &Pubkey) -> bool {
+        self.rearm_after_sell && self.pending_rearm.lock().remove(mint)
+    }
+}
@@ -0,0 +1,162 @@
+/// Token-2022 mint 扩展解析
+///
+/// Token-2022 的 mint 可以携带扩展：转账手续费会让实际到账数量少于成交数量，
+/// 转账 hook / 永久委托 / 不可转让 / 默认冻结 / 可暂停会让卖出失败甚至资产被直接转走
+///
+/// 核心功能:
+/// 1. TLV 解析 - 按 Token-2022 账户布局（基础 mint 82 字节，填充到 165 字节 + 账户类型 + TLV）读取扩展
+/// 2. 转账手续费 - 读取新旧两档费率，取较高者（不查询当前 epoch，保守估算）
+/// 3. 危险扩展 - 转账 hook、永久委托、不可转让、默认冻结、可暂停、手续费超过上限
+/// 4. 到账数量 - 按转账手续费计算扣费后的数量（买入到账 / 卖出时曲线实收）
+
+use solana_sdk::pubkey::Pubkey;
+
+/// Token-2022 程序地址
+const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// 基础账户长度（Token-2022 扩展从这里之后开始，mint 会填充到该长度）
+const BASE_ACCOUNT_LENGTH: usize = 165;
+/// AccountType::Mint
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+// ExtensionType 编号
+const EXT_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXT_DEFAULT_ACCOUNT_STATE: u16 = 6;
+const EXT_NON_TRANSFERABLE: u16 = 9;
+const EXT_PERMANENT_DELEGATE: u16 = 12;
+const EXT_TRANSFER_HOOK: u16 = 14;
+const EXT_PAUSABLE: u16 = 26;
+
+/// DefaultAccountState 中的 AccountState::Frozen
+const ACCOUNT_STATE_FROZEN: u8 = 2;
+
+/// 转账手续费（一档）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferFee {
+    pub basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+impl TransferFee {
+    /// 对齐 spl-token-2022 的 calculate_fee：向上取整，不超过 maximum_fee
+    pub fn calculate_fee(&self, amount: u64) -> u64 {
+        if self.basis_points == 0 || amount == 0 {
+            return 0;
+        }
+        let fee = (amount as u128 * self.basis_points as u128).div_ceil(10_000);
+        (fee.min(u64::MAX as u128) as u64).min(self.maximum_fee)
+    }
+}
+
+/// mint 扩展（Token v3 mint 或没有扩展时为默认值）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MintExtensions {
+    pub transfer_fee: Option<TransferFee>,
+    pub transfer_hook_program: Option<Pubkey>,
+    pub permanent_delegate: Option<Pubkey>,
+    pub non_transferable: bool,
+    pub default_frozen: bool,
+    pub pausable: bool,
+}
+
+impl MintExtensions {
+    /// 解析 mint 账户（owner 不是 Token-2022 时返回默认值）
+    pub fn parse(owner: &Pubkey, data: &[u8]) -> Self {
+        let mut extensions = Self::default();
+        if Pubkey::try_from(TOKEN_2022_PROGRAM).ok() != Some(*owner)
+            || data.len() <= BASE_ACCOUNT_LENGTH
+            || data[BASE_ACCOUNT_LENGTH] != ACCOUNT_TYPE_MINT
+        {
+            return extensions;
+        }
+
+        let mut offset = BASE_ACCOUNT_LENGTH + 1;
+        while offset + 4 <= data.len() {
+            let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+            let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+            let start = offset + 4;
+            let Some(value) = data.get(start..start + length) else {
+                break;
+            };
+            if extension_type == 0 {
+                break;
+            }
+
+            match extension_type {
+                EXT_TRANSFER_FEE_CONFIG => extensions.transfer_fee = Self::parse_transfer_fee(value),
+                EXT_DEFAULT_ACCOUNT_STATE => {
+                    extensions.default_frozen = value.first() == Some(&ACCOUNT_STATE_FROZEN);
+                }
+                EXT_NON_TRANSFERABLE => extensions.non_transferable = true,
+                EXT_PERMANENT_DELEGATE => extensions.permanent_delegate = Self::optional_pubkey(value, 0),
+                EXT_TRANSFER_HOOK => extensions.transfer_hook_program = Self::optional_pubkey(value, 32),
+                EXT_PAUSABLE => extensions.pausable = true,
+                _ => {}
+            }
+
+            offset = start + length;
+        }
+
+        extensions
+    }
+
+    /// 存在危险扩展时返回原因（转账手续费超过 max_transfer_fee_bps 也视为危险）
+    pub fn danger_reason(&self, max_transfer_fee_bps: u16) -> Option<String> {
+        if let Some(program) = self.transfer_hook_program {
+            return Some(format!("转账 hook ({})", program));
+        }
+        if let Some(delegate) = self.permanent_delegate {
+            return Some(format!("永久委托 ({})", delegate));
+        }
+        if self.non_transferable {
+            return Some("不可转让".to_string());
+        }
+        if self.default_frozen {
+            return Some("新账户默认冻结".to_string());
+        }
+        if self.pausable {
+            return Some("可暂停转账".to_string());
+        }
+        if let Some(fee) = self.transfer_fee {
+            if fee.basis_points > max_transfer_fee_bps {
+                return Some(format!("转账手续费 {} bps > {} bps", fee.basis_points, max_transfer_fee_bps));
+            }
+        }
+        None
+    }
+
+    /// 转账手续费费率（bps，没有该扩展时为 0）
+    pub fn transfer_fee_bps(&self) -> u16 {
+        self.transfer_fee.map_or(0, |fee| fee.basis_points)
+    }
+
+    /// 扣除转账手续费后的到账数量
+    pub fn amount_after_transfer_fee(&self, amount: u64) -> u64 {
+        let fee = self.transfer_fee.map_or(0, |fee| fee.calculate_fee(amount));
+        amount.saturating_sub(fee)
+    }
+
+    /// TransferFeeConfig: 两个权限地址(64) + withheld_amount(8) + older(18) + newer(18)
+    /// 每档: epoch(8) + maximum_fee(8) + basis_points(2)
+    fn parse_transfer_fee(value: &[u8]) -> Option<TransferFee> {
+        let read_fee = |offset: usize| -> Option<TransferFee> {
+            let fee = value.get(offset..offset + 18)?;
+            Some(TransferFee {
+                maximum_fee: u64::from_le_bytes(fee[8..16].try_into().ok()?),
+                basis_points: u16::from_le_bytes(fee[16..18].try_into().ok()?),
+            })
+        };
+
+        let older = read_fee(72)?;
+        let newer = read_fee(90)?;
+        // 不查询当前 epoch，取两档中较高的费率
+        Some(if newer.basis_points >= older.basis_points { newer } else { older })
+    }
+
+    /// OptionalNonZeroPubkey（全 0 表示未设置）
+    fn optional_pubkey(value: &[u8], offset: usize) -> Option<Pubkey> {
+        let bytes: [u8; 32] = value.get(offset..offset + 32)?.try_into().ok()?;
+        let pubkey = Pubkey::new_from_array(bytes);
+        (pubkey != Pubkey::default()).then_some(pubkey)
+    }
+}
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 
+use crate::dex::PoolAccounts;
+
 /// 事件类型
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SniperEvent {
@@ -27,6 +29,9 @@ pub struct TradeEventData {
     pub user: Pubkey,
     pub timestamp: i64,
     pub signature: String,
+    /// 交易所在 slot（配合区块时间推算事件的规范时间）
+    #[serde(default)]
+    pub slot: u64,
 
     // 储备数据
     pub virtual_sol_reserves: u64,
@@ -95,8 +100,8 @@ pub struct MigrateEventData {
     pub associated_bonding_curve: Pubkey,
 }
 
-/// PumpFun 事件（统一格式）
-#[derive(Debug, Clone)]
+/// PumpFun 事件（统一格式，Copy，不持有堆内存）
+#[derive(Debug, Clone, Copy)]
 pub struct PumpFunEvent {
     pub mint: Pubkey,
     pub user: Pubkey,
@@ -107,7 +112,21 @@ pub struct PumpFunEvent {
     pub timestamp: DateTime<Utc>,
     pub is_buy: bool,
     pub is_dev_trade: bool,
+    /// token 创建者（用于创建者黑名单过滤）
+    pub creator: Pubkey,
     pub event_type: PumpFunEventType,
+    /// 交易签名键（processed 事件流回滚时定位事件，见 signature_key）
+    pub signature_key: u64,
+}
+
+/// 交易签名的 64 位键
+///
+/// 窗口 / 历史事件只保存该键而不是签名字符串，避免每个事件一次堆分配
+pub fn signature_key(signature: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    signature.hash(&mut hasher);
+    hasher.finish()
 }
 
 /// PumpFun 事件类型
@@ -122,16 +141,137 @@ pub enum PumpFunEventType {
 #[derive(Debug, Clone)]
 pub struct WindowMetrics {
     pub mint: Pubkey,
+    /// token 创建者（未知时为默认值）
+    pub creator: Pubkey,
     pub net_inflow_sol: i64,
     pub buy_ratio: f64,
     pub acceleration: f64,
+    /// 窗口内首末事件的时间跨度（秒，计算速率时至少按 1 秒计）
+    pub window_span_secs: f64,
+    /// 净流入速率（SOL/秒）
+    pub inflow_rate_sol_per_sec: f64,
+    /// 交易速率（笔/秒）
+    pub trade_rate_per_sec: f64,
+    /// 按时长归一化的加速度：后半窗净流入速率 / 前半窗净流入速率（两半时长不等时比 acceleration 更可比）
+    pub normalized_acceleration: f64,
     pub latest_virtual_sol_reserves: u64,
     pub latest_virtual_token_reserves: u64,
+    /// 最新的真实 SOL 储备（来自交易事件）
+    pub latest_real_sol_reserves: u64,
+    /// 最新的真实 Token 储备（来自交易事件，买入时用于限制最大可买数量）
+    pub latest_real_token_reserves: u64,
+    /// 储备数据的本地接收时间（用于过期校验，未知时为 None）
+    pub reserves_updated_at: Option<DateTime<Utc>>,
     pub event_count: usize,
+    /// 自窗口创建以来的累计事件数（不受滑窗限制）
+    pub total_event_count: usize,
+    /// 自窗口创建以来的观察时长（秒）
+    pub observed_secs: u64,
+    /// 触发本次计算的交易的链上时间（持仓管理器自行构造的指标为 None）
+    pub event_time: Option<DateTime<Utc>>,
+    /// token 上线时长（秒，来自 Create 事件时间戳；未观察到创建时为 None）
+    pub token_age_secs: Option<u64>,
+    /// 创建者在创建交易中的首笔买入（SOL，未观察到时为 None）
+    pub dev_initial_buy_sol: Option<f64>,
+    /// 窗口内独立买家数
+    pub unique_buyers: usize,
+    /// 重复买家占比（买入 ≥2 次的钱包 / 独立买家数）
+    pub repeat_buyer_ratio: f64,
+    /// bonding curve 完成度（real_sol_reserves / 毕业目标，0-100%）
+    pub curve_progress_percent: f64,
+    /// 是否触发毕业狙击（完成度首次越过阈值且资金流入强劲）
+    pub graduation_triggered: bool,
     // 阈值触发相关
     pub threshold_buy_amount: Option<f64>,
+    /// 卖压触发：持仓中且窗口内累计卖出在阈值以上时为累计卖出金额（SOL），持仓立即卖出
+    pub sell_pressure_sol: Option<f64>,
     // 高级指标（从聚合器传递）
     pub advanced_metrics: Option<crate::advanced_metrics::AdvancedMetrics>,
+    /// 多时间框架指标（例如 5s/15s/60s，顺序与配置一致）
+    pub timeframes: Vec<TimeframeMetrics>,
+    /// 综合评分拆解（动态策略引擎发出买入信号时由策略引擎设置）
+    pub score_breakdown: Option<ScoreBreakdown>,
+}
+
+impl WindowMetrics {
+    /// 获取储备快照（无储备数据时返回 None）
+    pub fn reserve_snapshot(&self) -> Option<ReserveSnapshot> {
+        let updated_at = self.reserves_updated_at?;
+        if self.latest_virtual_sol_reserves == 0 || self.latest_virtual_token_reserves == 0 {
+            return None;
+        }
+        Some(ReserveSnapshot {
+            virtual_sol_reserves: self.latest_virtual_sol_reserves,
+            virtual_token_reserves: self.latest_virtual_token_reserves,
+            real_token_reserves: self.latest_real_token_reserves,
+            updated_at,
+        })
+    }
+
+    /// 是否带有交易流数据（持仓管理器按持仓构建的 metrics 没有窗口事件，
+    /// 净流入 / 买占比等字段均为 0，不能用于动能判断）
+    pub fn has_trade_flow(&self) -> bool {
+        self.event_count > 0
+    }
+
+    /// 检查所有时间框架是否同向（买占比达标且净流入为正）
+    ///
+    /// 没有多时间框架数据时视为对齐
+    pub fn timeframes_aligned(&self, min_buy_ratio: f64) -> bool {
+        self.timeframes.iter().all(|tf| {
+            tf.event_count > 0 && tf.buy_ratio >= min_buy_ratio && tf.net_inflow_sol > 0
+        })
+    }
+}
+
+/// 内存中的 bonding curve 储备快照
+///
+/// 买入路径优先使用该快照，过期时回退到链上读取
+#[derive(Debug, Clone, Copy)]
+pub struct ReserveSnapshot {
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_token_reserves: u64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ReserveSnapshot {
+    /// 快照距今的毫秒数
+    pub fn age_ms(&self) -> i64 {
+        (Utc::now() - self.updated_at).num_milliseconds()
+    }
+}
+
+/// 综合评分拆解（各分项已归一化到 0-1，供事后分析评分分项与盈亏的相关性）
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreBreakdown {
+    /// 买压（买占比）
+    pub buy_pressure: f64,
+    /// 净流入（2 SOL 封顶）
+    pub net_inflow: f64,
+    /// 加速度（2x 封顶）
+    pub acceleration: f64,
+    /// 流动性深度
+    pub liquidity_depth: f64,
+    /// 交易频率（10 笔高频交易封顶）
+    pub frequency: f64,
+    /// 平均价格冲击（不计入综合评分，买入条件单独检查）
+    pub price_impact: f64,
+    /// 综合评分（分项加权和）
+    pub composite: f64,
+    /// 动态策略引擎的置信度（0-1）
+    pub confidence: f64,
+}
+
+/// 单个时间框架的窗口指标
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeframeMetrics {
+    /// 时间框架长度（秒）
+    pub timeframe_secs: u64,
+    pub net_inflow_sol: i64,
+    pub buy_ratio: f64,
+    pub acceleration: f64,
+    pub event_count: usize,
 }
 
 /// 持仓信息
@@ -142,13 +282,62 @@ pub struct Position {
     pub entry_price_sol: f64,
     pub token_amount: u64,
     pub sol_invested: u64,
+    /// 买入时支付的 tip（LightSpeed + SWQOS，lamports，计入成本）
+    pub tips_paid_lamports: u64,
+    /// 开仓创建 ATA 锁定的租金（lamports，卖出关闭账户时回收）
+    pub ata_rent_lamports: u64,
+    /// 买入交易的网络费 + 优先费（lamports）
+    pub entry_tx_fee_lamports: u64,
+    /// 分笔卖出已成交部分的估算所得（lamports，平仓时计入盈亏）
+    pub partial_sol_received: u64,
+    /// 卖出交易的网络费 + 优先费（含分笔卖出，lamports，计入净盈亏）
+    pub exit_fees_lamports: u64,
     pub bonding_curve: Pubkey,
+    /// token 创建者（用于解析单币覆盖规则）
+    pub creator: Pubkey,
     pub creator_vault: Pubkey,
     pub associated_bonding_curve: Pubkey,
     /// 最新的虚拟 SOL 储备（用于价格计算）
     pub latest_virtual_sol_reserves: u64,
     /// 最新的虚拟 Token 储备（用于价格计算）
     pub latest_virtual_token_reserves: u64,
+    /// 开仓时动态策略引擎的置信度（其他触发方式 / 导入的持仓为 None）
+    pub entry_confidence: Option<f64>,
+}
+
+impl Position {
+    /// 卖出使用的池子账户
+    pub fn pool_accounts(&self) -> PoolAccounts {
+        PoolAccounts {
+            pool: self.bonding_curve,
+            pool_token_account: self.associated_bonding_curve,
+            creator_vault: self.creator_vault,
+        }
+    }
+
+    /// 开仓总成本（买入金额 + tip + 交易费，lamports；ATA 租金卖出时回收，不计入）
+    pub fn total_cost_lamports(&self) -> u64 {
+        self.sol_invested + self.tips_paid_lamports + self.entry_tx_fee_lamports
+    }
+
+    /// 扣除买入 tip 和卖出交易费后的净盈亏（lamports）
+    pub fn net_profit_loss_lamports(&self, profit_loss_lamports: i64) -> i64 {
+        profit_loss_lamports - self.tips_paid_lamports as i64 - self.exit_fees_lamports as i64
+    }
+
+    /// 保本价格（SOL/token）
+    ///
+    /// 以该价格卖出剩余数量，扣除 PumpFun 卖出手续费和卖出交易费后刚好收回开仓总成本（扣除分笔卖出已收回部分）：
+    /// token_amount × price × (1 - fee) - exit_tx_fee = total_cost - partial_sol_received
+    pub fn breakeven_price_sol(&self, pumpfun_fee_bps: u64, exit_tx_fee_lamports: u64) -> f64 {
+        if self.token_amount == 0 {
+            return self.entry_price_sol;
+        }
+        let fee_factor = 1.0 - pumpfun_fee_bps as f64 / 10_000.0;
+        let required_lamports = (self.total_cost_lamports() + exit_tx_fee_lamports)
+            .saturating_sub(self.partial_sol_received) as f64;
+        required_lamports / (self.token_amount as f64 * fee_factor)
+    }
 }
 
 /// 策略信号
@@ -164,6 +353,17 @@ pub enum StrategySignal {
     None,
 }
 
+/// 计算 bonding curve 完成度（百分比，0-100）
+///
+/// 完成度 = real_sol_reserves / 毕业目标 SOL
+pub fn curve_progress_percent(real_sol_reserves: u64, graduation_target_sol: f64) -> f64 {
+    if graduation_target_sol <= 0.0 {
+        return 0.0;
+    }
+    let real_sol = real_sol_reserves as f64 / 1_000_000_000.0;
+    (real_sol / graduation_target_sol * 100.0).clamp(0.0, 100.0)
+}
+
 /// 曲线状态（用于滑点计算）
 #[derive(Debug, Clone)]
 pub struct BondingCurveState {
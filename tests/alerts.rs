@@ -0,0 +1,156 @@
+//! 风险警报生命周期测试：去重 / 冷却 / 升级 / 确认 / 自动解除（不需要网络）
+
+mod common;
+
+use solana_sdk::pubkey::Pubkey;
+use std::time::{Duration, Instant};
+
+use solsniper::monitor::{AlertKind, AlertTracker, RiskAlert};
+
+use common::config_with;
+
+const COOLDOWN_SECS: u64 = 300;
+const AUTO_RESOLVE_SECS: u64 = 60;
+
+fn tracker() -> AlertTracker {
+    AlertTracker::new(COOLDOWN_SECS, AUTO_RESOLVE_SECS)
+}
+
+/// 波动 <= 50% 为 Medium，> 50% 为 High
+fn volatility(change_percent: f64) -> RiskAlert {
+    RiskAlert::PriceVolatility { change_percent, timeframe: "1h".to_string() }
+}
+
+fn secs(n: u64) -> Duration {
+    Duration::from_secs(n)
+}
+
+#[test]
+fn repeated_alert_is_suppressed_until_cooldown() {
+    let alerts = tracker();
+    let mint = Pubkey::new_unique();
+    let start = Instant::now();
+
+    assert_eq!(alerts.observe(&mint, vec![volatility(30.0)], start).len(), 1);
+    // 冷却期内（条件持续存在，未到自动解除）
+    assert!(alerts.observe(&mint, vec![volatility(30.0)], start + secs(30)).is_empty());
+    assert!(alerts.observe(&mint, vec![volatility(30.0)], start + secs(60)).is_empty());
+
+    let active = alerts.active();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].kind, AlertKind::PriceVolatility);
+    assert_eq!((active[0].fire_count, active[0].suppressed), (1, 2));
+
+    // 每轮都检测到，冷却结束后再次触发
+    let mut now = start + secs(60);
+    while now < start + secs(COOLDOWN_SECS) {
+        now += secs(30);
+        alerts.observe(&mint, vec![volatility(30.0)], now);
+    }
+    assert_eq!(alerts.active()[0].fire_count, 2);
+}
+
+#[test]
+fn escalation_fires_during_cooldown() {
+    let alerts = tracker();
+    let mint = Pubkey::new_unique();
+    let start = Instant::now();
+
+    alerts.observe(&mint, vec![volatility(30.0)], start);
+    assert_eq!(alerts.observe(&mint, vec![volatility(80.0)], start + secs(10)).len(), 1);
+    // 同一严重程度不再触发
+    assert!(alerts.observe(&mint, vec![volatility(90.0)], start + secs(20)).is_empty());
+}
+
+#[test]
+fn critical_alert_always_fires() {
+    let alerts = tracker();
+    let mint = Pubkey::new_unique();
+    let start = Instant::now();
+    let exhausted = || RiskAlert::LiquidityExhaustion { remaining_percent: 5.0 };
+
+    for step in 0..3 {
+        assert_eq!(alerts.observe(&mint, vec![exhausted()], start + secs(step * 10)).len(), 1);
+    }
+    alerts.acknowledge(Some(&mint));
+    assert_eq!(alerts.observe(&mint, vec![exhausted()], start + secs(40)).len(), 1);
+}
+
+#[test]
+fn acknowledged_alert_only_fires_on_escalation() {
+    let alerts = tracker();
+    let mint = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let start = Instant::now();
+
+    alerts.observe(&mint, vec![volatility(30.0)], start);
+    alerts.observe(&other, vec![volatility(30.0)], start);
+    assert_eq!(alerts.acknowledge(Some(&mint)), 1);
+    // 已确认的不重复计数
+    assert_eq!(alerts.acknowledge(None), 1);
+    assert_eq!(alerts.acknowledge(None), 0);
+
+    // 冷却期过后也不再触发（条件持续存在）
+    let mut now = start;
+    while now < start + secs(COOLDOWN_SECS + 30) {
+        now += secs(30);
+        assert!(alerts.observe(&mint, vec![volatility(30.0)], now).is_empty());
+    }
+
+    // 升级后触发并清除确认
+    assert_eq!(alerts.observe(&mint, vec![volatility(80.0)], now + secs(10)).len(), 1);
+    let active = alerts.active();
+    let state = active.iter().find(|alert| alert.mint == mint.to_string()).unwrap();
+    assert!(!state.acknowledged);
+}
+
+#[test]
+fn cleared_condition_resolves_and_reappears_as_new() {
+    let alerts = tracker();
+    let mint = Pubkey::new_unique();
+    let start = Instant::now();
+
+    alerts.observe(&mint, vec![volatility(30.0)], start);
+    alerts.acknowledge(None);
+
+    // 条件消失但未到自动解除时间
+    alerts.resolve_stale(start + secs(AUTO_RESOLVE_SECS - 1));
+    assert_eq!(alerts.active().len(), 1);
+
+    alerts.resolve_stale(start + secs(AUTO_RESOLVE_SECS));
+    assert!(alerts.active().is_empty());
+
+    // 重新出现：作为新警报立即触发，确认状态不保留
+    let fired = alerts.observe(&mint, vec![volatility(30.0)], start + secs(AUTO_RESOLVE_SECS + 10));
+    assert_eq!(fired.len(), 1);
+    let active = alerts.active();
+    assert_eq!(active[0].fire_count, 1);
+    assert!(!active[0].acknowledged);
+}
+
+#[test]
+fn observing_one_mint_resolves_stale_alerts_of_others() {
+    let alerts = tracker();
+    let closed = Pubkey::new_unique();
+    let held = Pubkey::new_unique();
+    let start = Instant::now();
+
+    alerts.observe(&closed, vec![volatility(30.0)], start);
+    alerts.observe(&held, vec![volatility(30.0)], start + secs(AUTO_RESOLVE_SECS));
+
+    let active = alerts.active();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].mint, held.to_string());
+}
+
+#[test]
+fn monitor_interval_must_be_shorter_than_auto_resolve() {
+    let config = config_with(&[("MONITOR_INTERVAL_SECS", "5"), ("ALERT_AUTO_RESOLVE_SECS", "60")]);
+    assert!(config.is_ok());
+
+    for interval in ["60", "120"] {
+        let error = config_with(&[("MONITOR_INTERVAL_SECS", interval), ("ALERT_AUTO_RESOLVE_SECS", "60")])
+            .unwrap_err();
+        assert!(error.to_string().contains("alert_auto_resolve_secs"), "{}", error);
+    }
+}
@@ -0,0 +1,131 @@
+//! 故障注入测试（不需要网络）
+//!
+//! 未启用 `chaos` feature 时配置校验拒绝 CHAOS_ENABLED；启用后按概率注入丢事件 / 延迟 / SWQOS 发送失败
+
+mod common;
+
+use std::time::Duration;
+
+use solsniper::chaos::ChaosInjector;
+
+use common::config_with;
+
+fn injector(probability: f64) -> ChaosInjector {
+    ChaosInjector {
+        enabled: true,
+        grpc_delay_probability: probability,
+        grpc_max_delay: Duration::from_millis(50),
+        drop_event_probability: probability,
+        swqos_failure_probability: probability,
+        stale_blockhash_probability: probability,
+    }
+}
+
+#[test]
+fn chaos_probabilities_are_validated() {
+    assert!(config_with(&[("CHAOS_DROP_EVENT_PROBABILITY", "1.5")]).is_err());
+    assert!(config_with(&[("CHAOS_SWQOS_FAILURE_PROBABILITY", "-0.1")]).is_err());
+}
+
+#[cfg(not(feature = "chaos"))]
+#[test]
+fn chaos_requires_feature() {
+    assert!(config_with(&[("CHAOS_ENABLED", "true")]).is_err());
+
+    // 未启用 feature 时注入器永远不生效
+    let chaos = injector(1.0);
+    assert!(!chaos.is_active());
+    assert!(!chaos.drop_event());
+    assert!(chaos.delivery_delay().is_none());
+    assert!(!chaos.fail_swqos_send());
+    assert!(!chaos.stale_blockhash());
+}
+
+#[cfg(feature = "chaos")]
+mod enabled {
+    use super::*;
+    use anyhow::Result;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::message::{v0, VersionedMessage};
+    use solana_sdk::signature::{Keypair, Signature};
+    use solana_sdk::signer::Signer;
+    use solana_sdk::transaction::VersionedTransaction;
+    use solsniper::swqos::{SwqosClientTrait, SwqosType};
+    use std::sync::Arc;
+
+    struct EchoSwqos {
+        http: reqwest::Client,
+    }
+
+    #[async_trait::async_trait]
+    impl SwqosClientTrait for EchoSwqos {
+        async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+            Ok(transaction.signatures[0])
+        }
+
+        fn get_tip_account(&self) -> Result<String> {
+            Ok("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5".to_string())
+        }
+
+        fn get_swqos_type(&self) -> SwqosType {
+            SwqosType::Jito
+        }
+
+        fn endpoint(&self) -> &str {
+            "http://mock-swqos"
+        }
+
+        fn auth_token(&self) -> &str {
+            ""
+        }
+
+        fn http_client(&self) -> &reqwest::Client {
+            &self.http
+        }
+    }
+
+    fn transaction() -> VersionedTransaction {
+        let payer = Keypair::new();
+        let message = v0::Message::try_compile(&payer.pubkey(), &[], &[], Hash::new_unique()).unwrap();
+        VersionedTransaction::try_new(VersionedMessage::V0(message), &[&payer]).unwrap()
+    }
+
+    #[test]
+    fn config_enables_chaos() {
+        let config = config_with(&[("CHAOS_ENABLED", "true"), ("CHAOS_DROP_EVENT_PROBABILITY", "1")]).unwrap();
+        let chaos = ChaosInjector::from_config(&config);
+        assert!(chaos.is_active());
+        assert!(chaos.drop_event());
+        assert!(!chaos.fail_swqos_send());
+    }
+
+    #[test]
+    fn grpc_faults_follow_probability() {
+        let always = injector(1.0);
+        assert!(always.drop_event());
+        let delay = always.delivery_delay().unwrap();
+        assert!(delay <= Duration::from_millis(50));
+
+        let never = injector(0.0);
+        assert!(!never.drop_event());
+        assert!(never.delivery_delay().is_none());
+
+        let disabled = ChaosInjector { enabled: false, ..injector(1.0) };
+        assert!(!disabled.drop_event());
+    }
+
+    #[tokio::test]
+    async fn swqos_send_failure_is_injected() {
+        let inner: Arc<dyn SwqosClientTrait> = Arc::new(EchoSwqos { http: reqwest::Client::new() });
+        let transaction = transaction();
+
+        let failing = injector(1.0).wrap_swqos(inner.clone());
+        assert!(failing.send_transaction(&transaction).await.is_err());
+        assert_eq!(failing.get_swqos_type(), SwqosType::Jito);
+
+        // 概率为 0 时不包装，直接使用原客户端
+        let passthrough = injector(0.0).wrap_swqos(inner.clone());
+        assert!(Arc::ptr_eq(&passthrough, &inner));
+        assert_eq!(passthrough.send_transaction(&transaction).await.unwrap(), transaction.signatures[0]);
+    }
+}
@@ -0,0 +1,17 @@
+//! 集成测试共用的配置加载（fixtures/base.env 基础配置 + 覆盖项，不需要网络）
+
+use solsniper::config::Config;
+
+const BASE_ENV: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/base.env");
+
+/// 基础配置 + 覆盖项（同名配置项以覆盖项为准，后出现的覆盖先出现的）
+pub fn config_with(overrides: &[(&str, &str)]) -> anyhow::Result<Config> {
+    // from_path_iter 已标记弃用，但它是 dotenv 唯一不写进程环境变量的解析入口（测试并行运行）
+    #[allow(deprecated)]
+    let mut vars = dotenv::from_path_iter(BASE_ENV)?.collect::<Result<Vec<_>, _>>()?;
+    for (key, value) in overrides {
+        vars.retain(|(existing, _)| existing != key);
+        vars.push((key.to_string(), value.to_string()));
+    }
+    Config::from_vars(vars)
+}
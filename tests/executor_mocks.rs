@@ -0,0 +1,579 @@
+//! 执行器故障场景测试（模拟 RPC / SWQOS，不需要网络）
+//!
+//! 通过 `LightSpeedBuyExecutor::with_backends` / `SolTradeSellExecutor::with_rpc` 注入 `RpcApi`
+//! 和 `SwqosClientTrait` 的模拟实现，按脚本返回超时、AlreadyProcessed、blockhash 失效、滑点失败
+
+mod common;
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use solana_client::client_error::{ClientErrorKind, Result as ClientResult};
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig};
+use solana_client::rpc_response::{Response, RpcResponseContext, RpcResult, RpcSimulateTransactionResult};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::account::Account;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{Transaction, TransactionError, VersionedTransaction};
+use solana_transaction_status::{TransactionConfirmationStatus, TransactionStatus};
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use solsniper::config::Config;
+use solsniper::confirmation::{ConfirmationStatus, ConfirmationTracker};
+use solsniper::dex::{DexAdapter, PumpFunAdapter};
+use solsniper::error::BotError;
+use solsniper::event_feed::EventFeed;
+use solsniper::executor::lightspeed_buy::{BuyBackends, LightSpeedBuyExecutor};
+use solsniper::executor::outbox::TransactionOutbox;
+use solsniper::executor::retry::RetryPolicy;
+use solsniper::executor::sol_trade_sell::{SellParams, SolTradeSellExecutor};
+use solsniper::grpc::parser::discriminators;
+use solsniper::pump_global::PumpGlobalState;
+use solsniper::rpc_api::RpcApi;
+use solsniper::rpc_pool::RpcPool;
+use solsniper::signer::SharedSigner;
+use solsniper::spend_guard::SpendGuard;
+use solsniper::swqos::{
+    MultiSwqosManager, SwqosClientTrait, SwqosConfig, SwqosRegion, SwqosServiceConfig, SwqosType,
+};
+
+use common::config_with;
+
+const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+// ==================== 模拟 RPC ====================
+
+/// sendTransaction 的脚本化结果
+#[derive(Debug, Clone)]
+enum SendOutcome {
+    /// 接受并上链（None = 执行成功，Some = 链上执行失败）
+    Land(Option<TransactionError>),
+    /// 交易已上链，重复发送返回 AlreadyProcessed
+    AlreadyProcessed,
+    /// 拒绝（未上链）
+    Reject(TransactionError),
+    /// 请求超时（未上链）
+    Timeout,
+    /// 请求超时，但交易已被转发并上链
+    TimeoutLanded,
+}
+
+/// 模拟 RPC：账户表 + 按顺序取出的发送结果（脚本用完后一律成功上链）
+#[derive(Default)]
+struct MockRpc {
+    accounts: Mutex<HashMap<Pubkey, Account>>,
+    send_script: Mutex<VecDeque<SendOutcome>>,
+    landed: Mutex<HashMap<Signature, Option<TransactionError>>>,
+    sent: Mutex<Vec<Signature>>,
+}
+
+impl MockRpc {
+    fn with_script(outcomes: &[SendOutcome]) -> Arc<Self> {
+        let rpc = Self::default();
+        rpc.send_script.lock().extend(outcomes.iter().cloned());
+        Arc::new(rpc)
+    }
+
+    fn set_account(&self, pubkey: Pubkey, account: Account) {
+        self.accounts.lock().insert(pubkey, account);
+    }
+
+    fn land(&self, signature: Signature, err: Option<TransactionError>) {
+        self.landed.lock().insert(signature, err);
+    }
+
+    fn sent(&self) -> Vec<Signature> {
+        self.sent.lock().clone()
+    }
+
+    fn context() -> RpcResponseContext {
+        RpcResponseContext::new(1)
+    }
+
+    fn missing(pubkey: &Pubkey) -> solana_client::client_error::ClientError {
+        ClientErrorKind::Custom(format!("AccountNotFound: {}", pubkey)).into()
+    }
+
+    fn timed_out() -> solana_client::client_error::ClientError {
+        std::io::Error::new(std::io::ErrorKind::TimedOut, "operation timed out").into()
+    }
+}
+
+impl RpcApi for MockRpc {
+    fn get_balance(&self, _pubkey: &Pubkey) -> ClientResult<u64> {
+        Ok(10_000_000_000)
+    }
+
+    fn get_balance_with_commitment(&self, pubkey: &Pubkey, _commitment: CommitmentConfig) -> RpcResult<u64> {
+        Ok(Response { context: Self::context(), value: self.get_balance(pubkey)? })
+    }
+
+    fn get_latest_blockhash(&self) -> ClientResult<Hash> {
+        Ok(Hash::new_unique())
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> ClientResult<Account> {
+        self.accounts.lock().get(pubkey).cloned().ok_or_else(|| Self::missing(pubkey))
+    }
+
+    fn get_account_data(&self, pubkey: &Pubkey) -> ClientResult<Vec<u8>> {
+        Ok(self.get_account(pubkey)?.data)
+    }
+
+    fn get_account_with_commitment(&self, pubkey: &Pubkey, _commitment: CommitmentConfig) -> RpcResult<Option<Account>> {
+        Ok(Response { context: Self::context(), value: self.accounts.lock().get(pubkey).cloned() })
+    }
+
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> ClientResult<Vec<Option<Account>>> {
+        let accounts = self.accounts.lock();
+        Ok(pubkeys.iter().map(|pubkey| accounts.get(pubkey).cloned()).collect())
+    }
+
+    fn get_token_account_balance_with_commitment(&self, pubkey: &Pubkey, _commitment: CommitmentConfig) -> RpcResult<u64> {
+        Err(Self::missing(pubkey))
+    }
+
+    fn get_signature_statuses(&self, signatures: &[Signature]) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        let landed = self.landed.lock();
+        let value = signatures
+            .iter()
+            .map(|signature| {
+                landed.get(signature).map(|err| TransactionStatus {
+                    slot: 1,
+                    confirmations: None,
+                    status: err.clone().map_or(Ok(()), Err),
+                    err: err.clone(),
+                    confirmation_status: Some(TransactionConfirmationStatus::Finalized),
+                })
+            })
+            .collect();
+        Ok(Response { context: Self::context(), value })
+    }
+
+    fn send_transaction(&self, transaction: &VersionedTransaction) -> ClientResult<Signature> {
+        let signature = transaction.signatures[0];
+        self.sent.lock().push(signature);
+
+        match self.send_script.lock().pop_front().unwrap_or(SendOutcome::Land(None)) {
+            SendOutcome::Land(err) => {
+                self.land(signature, err);
+                Ok(signature)
+            }
+            SendOutcome::AlreadyProcessed => {
+                self.land(signature, None);
+                Err(TransactionError::AlreadyProcessed.into())
+            }
+            SendOutcome::Reject(err) => Err(err.into()),
+            SendOutcome::Timeout => Err(Self::timed_out()),
+            SendOutcome::TimeoutLanded => {
+                self.land(signature, None);
+                Err(Self::timed_out())
+            }
+        }
+    }
+
+    fn is_blockhash_valid(&self, _blockhash: &Hash, _commitment: CommitmentConfig) -> ClientResult<bool> {
+        // 没上链的尝试之后也不会上链，等同 blockhash 已过期
+        Ok(false)
+    }
+
+    fn send_transaction_with_config(&self, transaction: &VersionedTransaction, _config: RpcSendTransactionConfig) -> ClientResult<Signature> {
+        self.send_transaction(transaction)
+    }
+
+    fn simulate_transaction_with_config(
+        &self,
+        _transaction: &VersionedTransaction,
+        _config: RpcSimulateTransactionConfig,
+    ) -> RpcResult<RpcSimulateTransactionResult> {
+        Err(ClientErrorKind::Custom("simulateTransaction not supported by MockRpc".to_string()).into())
+    }
+}
+
+// ==================== 模拟 SWQOS ====================
+
+/// SWQOS 服务的脚本化行为
+#[derive(Debug, Clone, Copy)]
+enum SwqosOutcome {
+    /// 延迟后接受并上链
+    Land,
+    /// 延迟后返回 AlreadyProcessed（交易已上链）
+    AlreadyProcessed,
+}
+
+struct MockSwqos {
+    outcome: SwqosOutcome,
+    delay: Duration,
+    rpc: Arc<MockRpc>,
+    http: reqwest::Client,
+    calls: AtomicUsize,
+}
+
+impl MockSwqos {
+    fn new(outcome: SwqosOutcome, delay: Duration, rpc: Arc<MockRpc>) -> Arc<Self> {
+        Arc::new(Self { outcome, delay, rpc, http: reqwest::Client::new(), calls: AtomicUsize::new(0) })
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for MockSwqos {
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+
+        let signature = transaction.signatures[0];
+        self.rpc.land(signature, None);
+        match self.outcome {
+            SwqosOutcome::Land => Ok(signature),
+            SwqosOutcome::AlreadyProcessed => Err(anyhow::anyhow!("Transaction already processed")),
+        }
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        Ok("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5".to_string())
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        SwqosType::Jito
+    }
+
+    fn endpoint(&self) -> &str {
+        "http://mock-swqos"
+    }
+
+    fn auth_token(&self) -> &str {
+        ""
+    }
+
+    fn http_client(&self) -> &reqwest::Client {
+        &self.http
+    }
+}
+
+fn swqos_manager(client: Arc<MockSwqos>, timeout_ms: u64) -> Arc<MultiSwqosManager> {
+    let config = SwqosConfig {
+        parallel_send: true,
+        timeout_ms,
+        max_retries: 1,
+        max_tips: 5,
+        tip_budget_lamports: 0,
+        tip_budget_services: 5,
+        max_tip_per_tx_lamports: 0,
+        health_check_timeout_ms: 1000,
+        health_check_interval_secs: 0,
+        latency_probe_interval_secs: 0,
+        latency_probe_samples: 1,
+        services: Vec::new(),
+    };
+    let service = SwqosServiceConfig {
+        name: "Mock-Jito".to_string(),
+        service_type: SwqosType::Jito,
+        region: SwqosRegion::NewYork,
+        api_key: String::new(),
+        tip_lamports: Some(1_000),
+        priority: 1,
+        enabled: true,
+        auto_region: false,
+        anti_mev: false,
+        use_staked_rpcs: false,
+        custom_endpoints: Vec::new(),
+    };
+    Arc::new(MultiSwqosManager::with_clients(config, vec![(service, client as Arc<dyn SwqosClientTrait>)]))
+}
+
+// ==================== 测试环境 ====================
+
+fn test_config(overrides: &[(&str, &str)]) -> Arc<Config> {
+    let payer = Keypair::new().to_base58_string();
+    let mut overrides = overrides.to_vec();
+    overrides.push(("WALLET_PRIVATE_KEY", payer.as_str()));
+    overrides.push(("CONFIRMATION_FALLBACK_POLL_MS", "20"));
+    Arc::new(config_with(&overrides).expect("fixture config must be valid"))
+}
+
+/// 一个仍在曲线上的 token（mint 权限已撤销，Token v3）
+struct Market {
+    mint: Pubkey,
+    pool: Pubkey,
+    dex: Arc<dyn DexAdapter>,
+}
+
+impl Market {
+    fn install(rpc: &MockRpc) -> Self {
+        let dex: Arc<dyn DexAdapter> = Arc::new(PumpFunAdapter::new(Arc::new(PumpGlobalState::new())).unwrap());
+        let mint = Pubkey::new_unique();
+        let pool = dex.derive_pool(&mint);
+
+        // SPL mint: 无 mint / freeze authority，decimals = 6，已初始化
+        let mut mint_data = vec![0u8; 82];
+        mint_data[44] = 6;
+        mint_data[45] = 1;
+        rpc.set_account(mint, Account {
+            lamports: 1_461_600,
+            data: mint_data,
+            owner: Pubkey::from_str(TOKEN_PROGRAM).unwrap(),
+            executable: false,
+            rent_epoch: 0,
+        });
+
+        // bonding curve: 初始储备
+        let mut curve_data = discriminators::BONDING_CURVE_ACCOUNT.to_vec();
+        for value in [1_073_000_000_000_000u64, 30_000_000_000, 793_100_000_000_000, 0, 1_000_000_000_000_000] {
+            curve_data.extend_from_slice(&value.to_le_bytes());
+        }
+        curve_data.push(0);
+        curve_data.extend_from_slice(Pubkey::new_unique().as_ref());
+        rpc.set_account(pool, Account {
+            lamports: 1_000_000_000,
+            data: curve_data,
+            owner: dex.program_id(),
+            executable: false,
+            rent_epoch: 0,
+        });
+
+        Self { mint, pool, dex }
+    }
+}
+
+fn buy_executor(config: &Arc<Config>, dex: Arc<dyn DexAdapter>, backends: BuyBackends) -> LightSpeedBuyExecutor {
+    let payer: SharedSigner = Arc::new(config.get_keypair().unwrap());
+    LightSpeedBuyExecutor::with_backends(
+        config.clone(),
+        payer,
+        Arc::new(ConfirmationTracker::new(20, CommitmentConfig::confirmed(), CommitmentConfig::confirmed())),
+        Arc::new(TransactionOutbox::open(None).unwrap()),
+        Arc::new(RpcPool::from_config(config)),
+        dex,
+        Arc::new(SpendGuard::from_config(config, Arc::new(EventFeed::from_config(config)))),
+        backends,
+    )
+    .unwrap()
+}
+
+fn sell_executor(config: &Arc<Config>, dex: Arc<dyn DexAdapter>, rpc: Arc<MockRpc>) -> SolTradeSellExecutor {
+    let payer: SharedSigner = Arc::new(config.get_keypair().unwrap());
+    SolTradeSellExecutor::with_rpc(
+        config.clone(),
+        payer,
+        Arc::new(ConfirmationTracker::new(20, CommitmentConfig::confirmed(), CommitmentConfig::confirmed())),
+        Arc::new(TransactionOutbox::open(None).unwrap()),
+        rpc,
+        dex,
+        Arc::new(SpendGuard::from_config(config, Arc::new(EventFeed::from_config(config)))),
+    )
+    .unwrap()
+}
+
+fn sell_params(market: &Market) -> SellParams {
+    SellParams {
+        mint: market.mint,
+        input_token_amount: 1_000_000_000,
+        slippage_basis_points: Some(300),
+        wait_transaction_confirmed: true,
+        close_token_account: false,
+        pool: market.dex.derive_pool_accounts(&market.mint, &Pubkey::from_str(TOKEN_PROGRAM).unwrap(), &Pubkey::new_unique()),
+    }
+}
+
+// ==================== 买入 ====================
+
+#[tokio::test]
+async fn buy_falls_back_to_rpc_when_swqos_times_out() {
+    let config = test_config(&[("SEND_BACKEND_ORDER", "swqos,lightspeed")]);
+    let rpc = MockRpc::with_script(&[]);
+    let market = Market::install(&rpc);
+    let swqos = MockSwqos::new(SwqosOutcome::Land, Duration::from_secs(5), rpc.clone());
+
+    let buyer = buy_executor(&config, market.dex.clone(), BuyBackends {
+        rpc: rpc.clone(),
+        lightspeed_rpc: None,
+        swqos: Some(swqos_manager(swqos.clone(), 50)),
+    });
+    let receipt = buyer.execute_buy(&market.mint, &market.pool, 50_000_000, None, false).await.unwrap();
+
+    assert_eq!(swqos.calls.load(Ordering::SeqCst), 1);
+    assert_eq!(rpc.sent(), vec![receipt.signature]);
+}
+
+#[tokio::test]
+async fn buy_treats_swqos_already_processed_as_sent() {
+    let config = test_config(&[("SEND_BACKEND_ORDER", "swqos,lightspeed")]);
+    let rpc = MockRpc::with_script(&[]);
+    let market = Market::install(&rpc);
+    let swqos = MockSwqos::new(SwqosOutcome::AlreadyProcessed, Duration::from_millis(5), rpc.clone());
+
+    let buyer = buy_executor(&config, market.dex.clone(), BuyBackends {
+        rpc: rpc.clone(),
+        lightspeed_rpc: None,
+        swqos: Some(swqos_manager(swqos.clone(), 1_000)),
+    });
+    let receipt = buyer.execute_buy(&market.mint, &market.pool, 50_000_000, None, false).await.unwrap();
+
+    // SWQOS 已送达，不再经 RPC 发送
+    assert_eq!(swqos.calls.load(Ordering::SeqCst), 1);
+    assert!(rpc.sent().is_empty());
+    assert!(rpc.landed.lock().contains_key(&receipt.signature));
+}
+
+#[tokio::test]
+async fn swqos_marks_already_processed_as_duplicate() {
+    let rpc = MockRpc::with_script(&[]);
+    let payer = Keypair::new();
+    let transaction = VersionedTransaction::from(Transaction::new_signed_with_payer(
+        &[],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::default(),
+    ));
+
+    let duplicate = swqos_manager(MockSwqos::new(SwqosOutcome::AlreadyProcessed, Duration::from_millis(1), rpc.clone()), 1_000)
+        .send_transaction_race(&transaction)
+        .await
+        .unwrap();
+    assert!(duplicate.success);
+    assert!(duplicate.already_processed);
+    assert_eq!(duplicate.signature, Some(transaction.signatures[0]));
+
+    let landed = swqos_manager(MockSwqos::new(SwqosOutcome::Land, Duration::from_millis(1), rpc), 1_000)
+        .send_transaction_race(&transaction)
+        .await
+        .unwrap();
+    assert!(landed.success);
+    assert!(!landed.already_processed);
+}
+
+#[tokio::test]
+async fn buy_treats_rpc_already_processed_as_sent() {
+    let config = test_config(&[]);
+    let rpc = MockRpc::with_script(&[SendOutcome::AlreadyProcessed]);
+    let market = Market::install(&rpc);
+
+    let buyer = buy_executor(&config, market.dex.clone(), BuyBackends { rpc: rpc.clone(), lightspeed_rpc: None, swqos: None });
+    let receipt = buyer.execute_buy(&market.mint, &market.pool, 50_000_000, None, false).await.unwrap();
+
+    assert_eq!(rpc.sent(), vec![receipt.signature]);
+}
+
+#[tokio::test]
+async fn buy_rebuilds_transaction_after_blockhash_not_found() {
+    let config = test_config(&[]);
+    let rpc = MockRpc::with_script(&[SendOutcome::Reject(TransactionError::BlockhashNotFound)]);
+    let market = Market::install(&rpc);
+
+    let buyer = buy_executor(&config, market.dex.clone(), BuyBackends { rpc: rpc.clone(), lightspeed_rpc: None, swqos: None });
+    let receipt = buyer.execute_buy(&market.mint, &market.pool, 50_000_000, None, false).await.unwrap();
+
+    let sent = rpc.sent();
+    assert_eq!(sent.len(), 2);
+    assert_ne!(sent[0], sent[1], "retry must be re-signed with a new blockhash");
+    assert_eq!(receipt.signature, sent[1]);
+}
+
+#[tokio::test]
+async fn buy_fails_after_max_attempts() {
+    let config = test_config(&[("TX_RETRY_MAX_ATTEMPTS", "2")]);
+    let rpc = MockRpc::with_script(&[SendOutcome::Timeout, SendOutcome::Timeout]);
+    let market = Market::install(&rpc);
+
+    let buyer = buy_executor(&config, market.dex.clone(), BuyBackends { rpc: rpc.clone(), lightspeed_rpc: None, swqos: None });
+    let result = buyer.execute_buy(&market.mint, &market.pool, 50_000_000, None, false).await;
+
+    assert!(result.is_err());
+    assert_eq!(rpc.sent().len(), 2);
+}
+
+#[tokio::test]
+async fn buy_does_not_resign_after_timed_out_attempt_landed() {
+    let config = test_config(&[]);
+    let rpc = MockRpc::with_script(&[SendOutcome::TimeoutLanded]);
+    let market = Market::install(&rpc);
+
+    let buyer = buy_executor(&config, market.dex.clone(), BuyBackends { rpc: rpc.clone(), lightspeed_rpc: None, swqos: None });
+    let receipt = buyer.execute_buy(&market.mint, &market.pool, 50_000_000, None, false).await.unwrap();
+
+    // 第 1 次尝试已上链，不再签名第 2 笔
+    assert_eq!(rpc.sent(), vec![receipt.signature]);
+}
+
+#[tokio::test]
+async fn buy_returns_landed_attempt_instead_of_final_error() {
+    let config = test_config(&[("TX_RETRY_MAX_ATTEMPTS", "1")]);
+    let rpc = MockRpc::with_script(&[SendOutcome::TimeoutLanded]);
+    let market = Market::install(&rpc);
+
+    let buyer = buy_executor(&config, market.dex.clone(), BuyBackends { rpc: rpc.clone(), lightspeed_rpc: None, swqos: None });
+    let receipt = buyer.execute_buy(&market.mint, &market.pool, 50_000_000, None, false).await.unwrap();
+
+    assert_eq!(rpc.sent(), vec![receipt.signature]);
+}
+
+// ==================== 卖出 ====================
+
+#[tokio::test]
+async fn sell_retries_after_rpc_timeout() {
+    let config = test_config(&[]);
+    let rpc = MockRpc::with_script(&[SendOutcome::Timeout]);
+    let market = Market::install(&rpc);
+
+    let seller = sell_executor(&config, market.dex.clone(), rpc.clone());
+    let receipt = seller.execute_sell(sell_params(&market)).await.unwrap();
+
+    let sent = rpc.sent();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(receipt.signature, sent[1]);
+
+    // 交易费按实际发出的第 2 次尝试（CU 价格已递增）计算
+    let policy = RetryPolicy::from_config(&config);
+    let expected_fee = 5_000 + policy.compute_unit_price(2) * config.compute_unit_limit as u64 / 1_000_000;
+    assert_eq!(receipt.fee_lamports, expected_fee);
+}
+
+#[tokio::test]
+async fn sell_reports_slippage_failure() {
+    let config = test_config(&[]);
+    // PumpFun TooLittleSolReceived (6003) 在卖出指令（ComputeBudget 两条之后）
+    let failure = TransactionError::InstructionError(2, InstructionError::Custom(6003));
+    let rpc = MockRpc::with_script(&[SendOutcome::Land(Some(failure))]);
+    let market = Market::install(&rpc);
+
+    let seller = sell_executor(&config, market.dex.clone(), rpc.clone());
+    let result = seller.execute_sell(sell_params(&market)).await;
+
+    assert!(matches!(result, Err(BotError::SlippageExceeded)), "got {:?}", result);
+    assert_eq!(rpc.sent().len(), 1);
+}
+
+#[tokio::test]
+async fn sell_treats_already_processed_as_sent() {
+    let config = test_config(&[]);
+    let rpc = MockRpc::with_script(&[SendOutcome::AlreadyProcessed]);
+    let market = Market::install(&rpc);
+
+    let seller = sell_executor(&config, market.dex.clone(), rpc.clone());
+    let signature = seller.execute_sell(sell_params(&market)).await.unwrap().signature;
+
+    assert_eq!(rpc.sent(), vec![signature]);
+}
+
+#[tokio::test]
+async fn confirmation_reaches_waiter_left_after_another_times_out() {
+    let tracker = Arc::new(ConfirmationTracker::new(20, CommitmentConfig::confirmed(), CommitmentConfig::confirmed()));
+    let rpc = MockRpc::with_script(&[]);
+    let signature = Signature::new_unique();
+
+    let long_waiter = {
+        let (tracker, rpc) = (tracker.clone(), rpc.clone());
+        tokio::spawn(async move { tracker.wait_for(rpc.as_ref(), &signature, 5).await })
+    };
+    // 第一个等待者超时离开后，第二个等待者仍能收到 gRPC 观测
+    assert_eq!(tracker.wait_for(rpc.as_ref(), &signature, 1).await, ConfirmationStatus::TimedOut);
+    tracker.observe(&signature);
+    assert_eq!(long_waiter.await.unwrap(), ConfirmationStatus::Confirmed);
+}
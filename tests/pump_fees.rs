@@ -0,0 +1,91 @@
+//! PumpFun 费率来源测试：报价使用 Global 账户的协议费 + 创作者费（不需要网络）
+
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+use solsniper::dex::{DexAdapter, PoolState, PumpFunAdapter};
+use solsniper::grpc::parser::Global;
+use solsniper::pump_global::PumpGlobalState;
+
+const SOL_AMOUNT: u64 = 1_000_000_000;
+const TOKEN_AMOUNT: u64 = 10_000_000_000_000;
+
+fn pool() -> PoolState {
+    PoolState {
+        virtual_token_reserves: 1_073_000_000_000_000,
+        virtual_sol_reserves: 30_000_000_000,
+        real_token_reserves: 793_100_000_000_000,
+        real_sol_reserves: 0,
+        creator: Pubkey::new_unique(),
+        complete: false,
+    }
+}
+
+fn global(fee_basis_points: u64, creator_fee_basis_points: u64) -> Global {
+    Global {
+        initialized: true,
+        fee_recipient: Pubkey::new_unique(),
+        fee_basis_points,
+        creator_fee_basis_points,
+        ..Global::default()
+    }
+}
+
+/// 买入手续费: sol - sol × 10000 / (10000 + 总费率)
+fn expected_buy_fee(total_fee_basis_points: u64) -> u64 {
+    SOL_AMOUNT - (SOL_AMOUNT as u128 * 10_000 / (10_000 + total_fee_basis_points as u128)) as u64
+}
+
+/// 卖出手续费: 按比例可得 SOL × 总费率 / 10000
+fn expected_sell_fee(pool: &PoolState, total_fee_basis_points: u64) -> u64 {
+    let proceeds = TOKEN_AMOUNT as u128 * pool.virtual_sol_reserves as u128
+        / (pool.virtual_token_reserves as u128 + TOKEN_AMOUNT as u128);
+    (proceeds * total_fee_basis_points as u128 / 10_000) as u64
+}
+
+#[test]
+fn quotes_follow_creator_fee_from_global() {
+    let state = Arc::new(PumpGlobalState::new());
+    let dex = PumpFunAdapter::new(state.clone()).unwrap();
+    let pool = pool();
+
+    // 读取到 Global 之前：内置默认值 95 + 30 bps
+    let fallback = state.fees();
+    assert_eq!((fallback.fee_basis_points, fallback.creator_fee_basis_points), (95, 30));
+    let fallback_buy = dex.quote_buy(&pool, SOL_AMOUNT);
+    let fallback_sell = dex.quote_sell(&pool, TOKEN_AMOUNT);
+    assert_eq!(fallback_buy.fee_lamports, expected_buy_fee(125));
+    assert_eq!(fallback_sell.fee_lamports, expected_sell_fee(&pool, 125));
+
+    // Global 的创作者费改为 5 bps：买入多得 token，卖出多得 SOL
+    state.update(100, &global(95, 5));
+    let buy = dex.quote_buy(&pool, SOL_AMOUNT);
+    let sell = dex.quote_sell(&pool, TOKEN_AMOUNT);
+    assert_eq!(buy.fee_lamports, expected_buy_fee(100));
+    assert_eq!(sell.fee_lamports, expected_sell_fee(&pool, 100));
+    assert!(buy.amount_out > fallback_buy.amount_out);
+    assert!(sell.amount_out > fallback_sell.amount_out);
+
+    // 创作者费上调同样生效
+    state.update(101, &global(95, 50));
+    assert_eq!(dex.quote_buy(&pool, SOL_AMOUNT).fee_lamports, expected_buy_fee(145));
+    assert_eq!(dex.quote_sell(&pool, TOKEN_AMOUNT).fee_lamports, expected_sell_fee(&pool, 145));
+}
+
+#[test]
+fn stale_or_invalid_global_updates_are_ignored() {
+    let state = PumpGlobalState::new();
+    let current = global(95, 5);
+    state.update(100, &current);
+
+    // 更早 slot 的账户数据
+    state.update(99, &global(95, 50));
+    assert_eq!(state.fees().creator_fee_basis_points, 5);
+
+    // 未初始化 / fee recipient 为空
+    state.update(101, &Global::default());
+    let fees = state.fees();
+    assert_eq!(fees.creator_fee_basis_points, 5);
+    assert_eq!(fees.fee_recipient, current.fee_recipient);
+    assert_eq!(fees.slot, 100);
+}
@@ -0,0 +1,479 @@
+//! 事件录制 / 回放测试（不需要网络）
+//!
+//! 录制文件经 ReplaySource 注入事件队列；执行器接入 ReplayRpc 后买入 / 卖出按回放的曲线结算
+//! 完整管道回放的交易日志与 tests/fixtures/replay_journal.golden 比较
+
+mod common;
+
+use crossbeam_queue::ArrayQueue;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use solsniper::aggregator::Aggregator;
+use solsniper::clock::Clock;
+use solsniper::config::Config;
+use solsniper::confirmation::ConfirmationTracker;
+use solsniper::decision_trace::DecisionTrace;
+use solsniper::dex::{get_ata_with_program, DexAdapter, PumpFunAdapter};
+use solsniper::event_feed::EventFeed;
+use solsniper::executor::lightspeed_buy::{BuyBackends, LightSpeedBuyExecutor};
+use solsniper::executor::outbox::TransactionOutbox;
+use solsniper::executor::sol_trade_sell::{SellParams, SolTradeSellExecutor};
+use solsniper::executor::wallet_sweep::WalletSweeper;
+use solsniper::executor::TransactionBuilder;
+use solsniper::feed_reconcile::FeedReconciler;
+use solsniper::journal::TradeJournal;
+use solsniper::market::MarketHealth;
+use solsniper::metrics_export::MetricsExporter;
+use solsniper::monitor::LargeTradeTracker;
+use solsniper::position::PositionManager;
+use solsniper::position_state::PositionStateStore;
+use solsniper::price_cache::PriceCache;
+use solsniper::pump_global::PumpGlobalState;
+use solsniper::replay::{EventRecorder, ReplayOptions, ReplayRpc, ReplaySource};
+use solsniper::rpc_api::RpcApi;
+use solsniper::rpc_pool::RpcPool;
+use solsniper::signal_bus;
+use solsniper::signer::SharedSigner;
+use solsniper::spend_guard::SpendGuard;
+use solsniper::strategy::StrategyEngine;
+use solsniper::types::{CreateTokenEventData, SniperEvent, TradeEventData};
+
+use common::config_with;
+
+const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const WALLET_LAMPORTS: u64 = 10_000_000_000;
+
+fn fixture_config(payer: &Keypair, overrides: &[(&str, &str)]) -> Config {
+    let payer = payer.to_base58_string();
+    let mut vars = vec![
+        ("WALLET_PRIVATE_KEY", payer.as_str()),
+        ("CONFIRMATION_FALLBACK_POLL_MS", "20"),
+        ("SEND_BACKEND_ORDER", "lightspeed"),
+    ];
+    vars.extend_from_slice(overrides);
+    config_with(&vars).expect("fixture config must be valid")
+}
+
+fn test_config(payer: &Keypair) -> Arc<Config> {
+    Arc::new(fixture_config(payer, &[]))
+}
+
+fn create_event(dex: &dyn DexAdapter, mint: Pubkey, creator: Pubkey) -> SniperEvent {
+    SniperEvent::CreateToken(CreateTokenEventData {
+        mint,
+        name: "Replay".to_string(),
+        symbol: "RPL".to_string(),
+        uri: String::new(),
+        bonding_curve: dex.derive_pool(&mint),
+        creator,
+        virtual_sol_reserves: 30_000_000_000,
+        virtual_token_reserves: 1_073_000_000_000_000,
+        real_token_reserves: 793_100_000_000_000,
+        token_total_supply: 1_000_000_000_000_000,
+        timestamp: 1_700_000_000,
+        signature: "create".to_string(),
+        associated_bonding_curve: Pubkey::new_unique(),
+    })
+}
+
+fn trade_event(dex: &dyn DexAdapter, mint: Pubkey, creator: Pubkey, slot: u64, sol_amount: u64) -> SniperEvent {
+    let virtual_sol_reserves = 30_000_000_000 + sol_amount;
+    let virtual_token_reserves = (30_000_000_000u128 * 1_073_000_000_000_000 / virtual_sol_reserves as u128) as u64;
+    let token_amount = 1_073_000_000_000_000 - virtual_token_reserves;
+    SniperEvent::Trade(TradeEventData {
+        mint,
+        is_buy: true,
+        is_created_buy: false,
+        sol_amount,
+        token_amount,
+        user: Pubkey::new_unique(),
+        timestamp: 1_700_000_000 + slot as i64,
+        signature: format!("trade-{}", slot),
+        slot,
+        virtual_sol_reserves,
+        virtual_token_reserves,
+        real_sol_reserves: sol_amount,
+        real_token_reserves: 793_100_000_000_000 - token_amount,
+        fee_recipient: Pubkey::new_unique(),
+        fee_basis_points: 95,
+        fee: 0,
+        creator,
+        creator_fee_basis_points: 30,
+        creator_fee: 0,
+        track_volume: false,
+        total_unclaimed_tokens: 0,
+        total_claimed_tokens: 0,
+        current_sol_volume: 0,
+        last_update_timestamp: 0,
+        bonding_curve: dex.derive_pool(&mint),
+        associated_bonding_curve: Pubkey::new_unique(),
+        associated_user: Pubkey::new_unique(),
+        creator_vault: Pubkey::new_unique(),
+        global_volume_accumulator: Pubkey::new_unique(),
+        user_volume_accumulator: Pubkey::new_unique(),
+    })
+}
+
+/// 一笔 sol_amount 的买入，曲线储备按累计流入 cumulative_sol 计算
+fn curve_trade(dex: &dyn DexAdapter, mint: Pubkey, creator: Pubkey, slot: u64, sol_amount: u64, cumulative_sol: u64) -> SniperEvent {
+    let SniperEvent::Trade(mut trade) = trade_event(dex, mint, creator, slot, cumulative_sol) else {
+        unreachable!()
+    };
+    trade.sol_amount = sol_amount;
+    SniperEvent::Trade(trade)
+}
+
+/// 录制文件中的一行（接收时间固定，回放计时可复现）
+fn recorded_line(received_at_ms: i64, event: &SniperEvent) -> String {
+    serde_json::json!({ "received_at_ms": received_at_ms, "event": event }).to_string()
+}
+
+/// 交易日志归一化：只保留回放可复现的字段（签名 / EV / 保本价等随实现细节变化的字段不比较）
+fn normalize_journal(journal: &str) -> String {
+    journal
+        .lines()
+        .map(|line| {
+            let record: serde_json::Value = serde_json::from_str(line).expect("journal line must be json");
+            let field = |key: &str| record[key].to_string();
+            let event = record["event"].as_str().unwrap_or_default();
+            let head = format!("{} {} {}", event, record["timestamp"].as_str().unwrap_or_default(),
+                record["mint"].as_str().unwrap_or_default());
+            match event {
+                "entry" => format!("{} sol_amount_lamports={} token_amount={}",
+                    head, field("sol_amount_lamports"), field("token_amount")),
+                "exit" => format!("{} sol_received_lamports={} hold_secs={}",
+                    head, field("sol_received_lamports"), field("hold_secs")),
+                _ => head,
+            }
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+fn pumpfun() -> Arc<dyn DexAdapter> {
+    Arc::new(PumpFunAdapter::new(Arc::new(PumpGlobalState::new())).unwrap())
+}
+
+#[test]
+fn replay_options_parse_speed() {
+    let args = |line: &str| line.split_whitespace().map(str::to_string).collect::<Vec<_>>();
+
+    let options = ReplayOptions::parse(&args("--replay events.jsonl --speed 20")).unwrap();
+    assert_eq!(options.path, "events.jsonl");
+    assert_eq!(options.speed, 20.0);
+
+    assert_eq!(ReplayOptions::parse(&args("--replay events.jsonl")).unwrap().speed, 1.0);
+    assert!(ReplayOptions::parse(&args("--speed 2")).is_err());
+    assert!(ReplayOptions::parse(&args("--replay events.jsonl --speed -1")).is_err());
+    assert!(ReplayOptions::parse(&args("--replay events.jsonl --fast")).is_err());
+}
+
+#[test]
+fn replay_clock_follows_recorded_time() {
+    let clock = Clock::replay();
+    let start = clock.now();
+
+    // 两个事件之间时间静止；推进只前进不后退
+    clock.advance_to(1_700_000_000_000);
+    assert_eq!(clock.elapsed(start).as_millis(), 0);
+    assert_eq!(clock.utc_now().timestamp_millis(), 1_700_000_000_000);
+
+    clock.advance_to(1_700_000_002_500);
+    assert_eq!(clock.elapsed(start).as_millis(), 2_500);
+    clock.advance_to(1_700_000_001_000);
+    assert_eq!(clock.elapsed(start).as_millis(), 2_500);
+    assert_eq!(clock.utc_now().timestamp_millis(), 1_700_000_002_500);
+}
+
+#[tokio::test]
+async fn recorded_events_are_replayed_in_order() {
+    let dex = pumpfun();
+    let mint = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let path = std::env::temp_dir().join(format!("solsniper-replay-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let recorder = EventRecorder::open(path.to_str()).unwrap();
+    recorder.record(&create_event(dex.as_ref(), mint, creator));
+    for slot in 1..=3 {
+        recorder.record(&trade_event(dex.as_ref(), mint, creator, slot, slot * 100_000_000));
+    }
+    drop(recorder);
+
+    let chain = Arc::new(ReplayRpc::new(Pubkey::new_unique(), WALLET_LAMPORTS, dex.clone(), 125).unwrap());
+    let price_cache = Arc::new(PriceCache::new());
+    let options = ReplayOptions { path: path.display().to_string(), speed: 0.0 };
+    let clock = Arc::new(Clock::replay());
+    let source = ReplaySource::new(options, chain.clone(), price_cache.clone(), clock.clone());
+
+    // 队列容量小于事件数：回放等待消费而不是丢弃
+    let queue = Arc::new(ArrayQueue::new(2));
+    let consumer = {
+        let queue = queue.clone();
+        tokio::spawn(async move {
+            let mut received = Vec::new();
+            while received.len() < 4 {
+                match queue.pop() {
+                    Some(event) => received.push(event),
+                    None => tokio::task::yield_now().await,
+                }
+            }
+            received
+        })
+    };
+    let stats = source.run(queue).await.unwrap();
+    let received = consumer.await.unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(stats.events, 4);
+    assert_eq!(stats.skipped, 0);
+    assert!(matches!(received[0], SniperEvent::CreateToken(_)));
+    let slots: Vec<u64> = received[1..].iter()
+        .map(|event| match event {
+            SniperEvent::Trade(trade) => trade.slot,
+            other => panic!("unexpected event {:?}", other),
+        })
+        .collect();
+    assert_eq!(slots, vec![1, 2, 3]);
+
+    // 价格缓存和模拟链跟随最后一笔交易
+    let SniperEvent::Trade(last) = &received[3] else { unreachable!() };
+    let cached = price_cache.get(&dex.derive_pool(&mint)).unwrap();
+    assert_eq!(cached.virtual_sol_reserves, last.virtual_sol_reserves);
+    let pool = dex.decode_pool(&chain.get_account_data(&dex.derive_pool(&mint)).unwrap()).unwrap();
+    assert_eq!(pool.virtual_token_reserves, last.virtual_token_reserves);
+}
+
+#[tokio::test]
+async fn replay_chain_settles_buy_and_sell() {
+    let payer = Keypair::new();
+    let config = test_config(&payer);
+    let signer: SharedSigner = Arc::new(payer.insecure_clone());
+    let dex = pumpfun();
+    let mint = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let pool = dex.derive_pool(&mint);
+    let token_program = Pubkey::from_str(TOKEN_PROGRAM).unwrap();
+    let token_account = get_ata_with_program(&payer.pubkey(), &mint, &token_program);
+
+    let chain = Arc::new(ReplayRpc::new(payer.pubkey(), WALLET_LAMPORTS, dex.clone(), config.pumpfun_fee_bps).unwrap());
+    chain.observe(&create_event(dex.as_ref(), mint, creator));
+
+    let confirmations = Arc::new(ConfirmationTracker::new(20, CommitmentConfig::confirmed(), CommitmentConfig::confirmed()));
+    let outbox = Arc::new(TransactionOutbox::open(None).unwrap());
+    let spend_guard = Arc::new(SpendGuard::from_config(&config, Arc::new(EventFeed::from_config(&config))));
+    let buyer = LightSpeedBuyExecutor::with_backends(
+        config.clone(),
+        signer.clone(),
+        confirmations.clone(),
+        outbox.clone(),
+        Arc::new(RpcPool::from_config(&config)),
+        dex.clone(),
+        spend_guard.clone(),
+        BuyBackends { rpc: chain.clone(), lightspeed_rpc: None, swqos: None },
+    )
+    .unwrap();
+    let seller =
+        SolTradeSellExecutor::with_rpc(config.clone(), signer, confirmations, outbox, chain.clone(), dex.clone(), spend_guard)
+            .unwrap();
+
+    // 买入：token 到账，钱包支付仓位 + 手续费 + ATA 租金
+    buyer.execute_buy(&mint, &pool, 100_000_000, None, false).await.unwrap();
+    let tokens = seller.get_token_balance(&mint).await.unwrap();
+    assert!(tokens > 0);
+    let after_buy = chain.wallet_lamports();
+    assert!(after_buy < WALLET_LAMPORTS - 100_000_000);
+
+    // 其他人在回放中继续买入，价格上涨
+    chain.observe(&trade_event(dex.as_ref(), mint, creator, 10, 5_000_000_000));
+
+    // 卖出全部并关闭 ATA：收回 SOL 和租金，token 账户消失
+    seller.execute_sell(SellParams {
+        mint,
+        input_token_amount: tokens,
+        slippage_basis_points: Some(300),
+        wait_transaction_confirmed: true,
+        close_token_account: true,
+        pool: dex.derive_pool_accounts(&mint, &token_program, &creator),
+    })
+    .await
+    .unwrap();
+
+    assert!(chain.get_account(&token_account).is_err());
+    assert!(chain.wallet_lamports() > after_buy + 100_000_000);
+}
+
+/// 录制文件经完整管道回放（聚合器 → 策略 → 持仓管理 → 模拟链），交易日志与 golden 文件一致
+///
+/// 阈值触发在第 4 笔买入（累计 1.2 SOL）时买入 0.1 SOL；30s 后另一个 token 的创建事件推进回放时钟，
+/// 持仓达到最大持仓时间后卖出。入场 / 平仓时间和持仓时长都来自录制时间，与回放倍速无关
+///
+/// 实现改动导致日志变化时，用 UPDATE_GOLDEN=1 cargo test --test replay 重新生成
+#[tokio::test(flavor = "multi_thread")]
+async fn replayed_recording_matches_golden_journal() {
+    const RECORDED_AT_MS: i64 = 1_700_000_000_000;
+    let golden_path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/replay_journal.golden");
+
+    let dex = pumpfun();
+    let mint = Pubkey::new_from_array([7; 32]);
+    let other_mint = Pubkey::new_from_array([8; 32]);
+    let creator = Pubkey::new_from_array([9; 32]);
+
+    let id = std::process::id();
+    let recording = std::env::temp_dir().join(format!("solsniper-golden-{}.jsonl", id));
+    let journal_path = std::env::temp_dir().join(format!("solsniper-golden-journal-{}.jsonl", id));
+    let _ = std::fs::remove_file(&journal_path);
+
+    let mut lines = vec![recorded_line(RECORDED_AT_MS, &create_event(dex.as_ref(), mint, creator))];
+    for slot in 1..=4u64 {
+        let event = curve_trade(dex.as_ref(), mint, creator, slot, 300_000_000, slot * 300_000_000);
+        lines.push(recorded_line(RECORDED_AT_MS + slot as i64 * 200, &event));
+    }
+    lines.push(recorded_line(RECORDED_AT_MS + 30_800, &create_event(dex.as_ref(), other_mint, creator)));
+    std::fs::write(&recording, lines.join("\n")).unwrap();
+
+    let payer = Keypair::new();
+    let journal_setting = journal_path.display().to_string();
+    let options = ReplayOptions { path: recording.display().to_string(), speed: 10.0 };
+    let mut config = fixture_config(&payer, &[
+        ("ENABLE_THRESHOLD_TRIGGER", "true"),
+        ("THRESHOLD_CUMULATIVE_BUY_SOL", "1.0"),
+        ("THRESHOLD_BUY_RATIO", "0.1"),
+        ("HOLD_MAX_DURATION_SECS", "30"),
+        ("MAX_TRADE_FREQUENCY", "1000.0"),
+        ("EXIT_CHECK_INTERVAL_MS", "100"),
+        ("TRADE_JOURNAL_PATH", journal_setting.as_str()),
+    ]);
+    options.apply(&mut config);
+    let config = Arc::new(config);
+    let signer: SharedSigner = Arc::new(payer.insecure_clone());
+
+    // 组件装配与 main.rs 的回放模式一致
+    let clock = Arc::new(Clock::replay());
+    let price_cache = Arc::new(PriceCache::new());
+    let chain = Arc::new(ReplayRpc::new(payer.pubkey(), WALLET_LAMPORTS, dex.clone(), config.pumpfun_fee_bps).unwrap());
+    let event_queue = Arc::new(ArrayQueue::new(config.event_queue_capacity));
+    let (metrics_tx, metrics_rx) = mpsc::channel(1000);
+    let (signal_tx, signal_rx) = signal_bus::channel(100, 100);
+    let rpc_pool = Arc::new(RpcPool::from_config(&config));
+    let event_feed = Arc::new(EventFeed::from_config(&config));
+    let confirmations = Arc::new(ConfirmationTracker::new(
+        config.confirmation_fallback_poll_ms,
+        config.get_confirm_commitment(),
+        config.get_feed_commitment(),
+    ));
+    let feed_reconciler = Arc::new(FeedReconciler::new(config.get_feed_commitment(), config.feed_reconcile_timeout_ms));
+    let large_trades = Arc::new(LargeTradeTracker::new(config.large_sell_threshold));
+    let market_health = Arc::new(MarketHealth::from_config(&config));
+    let aggregator = Arc::new(Aggregator::new(
+        config.clone(),
+        metrics_tx,
+        large_trades.clone(),
+        market_health.clone(),
+        feed_reconciler.clone(),
+        event_feed.clone(),
+        clock.clone(),
+    ));
+    let journal = Arc::new(TradeJournal::open(config.trade_journal_path.as_deref()).unwrap());
+    let strategy = Arc::new(StrategyEngine::new(
+        config.clone(),
+        signal_tx,
+        aggregator.clone(),
+        None,
+        event_feed.clone(),
+        Arc::new(DecisionTrace::from_config(&config)),
+        Arc::new(MetricsExporter::from_config(&config).unwrap()),
+    ));
+    let outbox = Arc::new(TransactionOutbox::open(None).unwrap());
+    let spend_guard = Arc::new(SpendGuard::from_config(&config, event_feed.clone()));
+    let buyer = Arc::new(LightSpeedBuyExecutor::with_backends(
+        config.clone(),
+        signer.clone(),
+        confirmations.clone(),
+        outbox.clone(),
+        rpc_pool.clone(),
+        dex.clone(),
+        spend_guard.clone(),
+        BuyBackends { rpc: chain.clone(), lightspeed_rpc: None, swqos: None },
+    )
+    .unwrap());
+    let seller = Arc::new(SolTradeSellExecutor::with_rpc(
+        config.clone(),
+        signer.clone(),
+        confirmations.clone(),
+        outbox,
+        chain.clone(),
+        dex.clone(),
+        spend_guard.clone(),
+    )
+    .unwrap());
+    let wallet_sweeper = Arc::new(WalletSweeper::new(
+        Arc::new(rpc_pool.client(config.get_commitment_config())),
+        signer,
+        spend_guard,
+    ));
+    let position_manager = Arc::new(PositionManager::new(
+        config.clone(),
+        strategy.clone(),
+        Arc::new(TransactionBuilder::new()),
+        buyer,
+        seller,
+        dex.clone(),
+        aggregator.filter(),
+        aggregator.threshold_trigger(),
+        aggregator.held_mints(),
+        price_cache.clone(),
+        large_trades,
+        confirmations,
+        wallet_sweeper,
+        journal,
+        market_health,
+        Arc::new(PositionStateStore::open(None).unwrap()),
+        rpc_pool,
+        feed_reconciler,
+        event_feed,
+        clock.clone(),
+    ));
+
+    let tasks = vec![
+        {
+            let aggregator = aggregator.clone();
+            let event_queue = event_queue.clone();
+            tokio::spawn(async move { aggregator.start(event_queue).await })
+        },
+        tokio::spawn(strategy.start(metrics_rx)),
+        {
+            let position_manager = position_manager.clone();
+            tokio::spawn(async move { position_manager.start(signal_rx).await })
+        },
+        {
+            let position_manager = position_manager.clone();
+            tokio::spawn(async move { position_manager.start_exit_loop().await })
+        },
+    ];
+
+    // 回放结束后 ReplaySource 留出处理时间，卖出在此之前完成
+    let source = ReplaySource::new(options, chain, price_cache, clock);
+    let stats = source.run(event_queue).await.unwrap();
+    for task in tasks {
+        task.abort();
+    }
+    assert_eq!(stats.events, 6);
+
+    let journal = normalize_journal(&std::fs::read_to_string(&journal_path).unwrap_or_default());
+    let _ = std::fs::remove_file(&recording);
+    let _ = std::fs::remove_file(&journal_path);
+
+    if std::env::var("UPDATE_GOLDEN").is_ok_and(|value| value == "1") {
+        std::fs::write(golden_path, &journal).unwrap();
+        return;
+    }
+    let golden = std::fs::read_to_string(golden_path).expect("golden file missing (run with UPDATE_GOLDEN=1)");
+    assert_eq!(journal, golden, "replayed journal differs from {}", golden_path);
+}
@@ -0,0 +1,46 @@
+//! 发送错误分类测试（不需要网络）
+
+use solana_client::client_error::ClientError;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+
+use solsniper::error::{classify_error_message, classify_send_error, BotError, SendErrorKind};
+
+#[test]
+fn rpc_transaction_errors_are_classified_structurally() {
+    let already = anyhow::Error::from(ClientError::from(TransactionError::AlreadyProcessed));
+    assert_eq!(classify_send_error(&already), SendErrorKind::AlreadyProcessed);
+
+    let expired = anyhow::Error::from(ClientError::from(TransactionError::BlockhashNotFound))
+        .context("发送交易失败");
+    assert_eq!(classify_send_error(&expired), SendErrorKind::BlockhashNotFound);
+
+    let direct = anyhow::Error::from(TransactionError::AlreadyProcessed);
+    assert_eq!(classify_send_error(&direct), SendErrorKind::AlreadyProcessed);
+}
+
+#[test]
+fn other_transaction_errors_are_not_matched_by_text() {
+    // 结构化错误优先：其他 TransactionError 不会因为上下文文本被误判
+    let failed = anyhow::Error::from(ClientError::from(TransactionError::InstructionError(2, InstructionError::Custom(6003))))
+        .context("blockhash not found while retrying");
+    assert_eq!(classify_send_error(&failed), SendErrorKind::Other);
+}
+
+#[test]
+fn bot_blockhash_expired_is_recognized() {
+    let err = anyhow::Error::from(BotError::BlockhashExpired).context("重建交易");
+    assert_eq!(classify_send_error(&err), SendErrorKind::BlockhashNotFound);
+}
+
+#[test]
+fn relay_messages_are_classified() {
+    assert_eq!(classify_error_message("Transaction already processed"), SendErrorKind::AlreadyProcessed);
+    assert_eq!(
+        classify_error_message("Transaction simulation failed: This transaction has already been processed"),
+        SendErrorKind::AlreadyProcessed
+    );
+    assert_eq!(classify_error_message("{\"error\":\"BlockhashNotFound\"}"), SendErrorKind::BlockhashNotFound);
+    assert_eq!(classify_error_message("Blockhash not found"), SendErrorKind::BlockhashNotFound);
+    assert_eq!(classify_error_message("rate limited"), SendErrorKind::Other);
+}
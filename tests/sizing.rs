@@ -0,0 +1,68 @@
+//! 置信度仓位缩放测试（不需要网络）
+
+mod common;
+
+use solsniper::sizing::ConfidenceSizing;
+
+use common::config_with;
+
+const MIN_LAMPORTS: u64 = 100_000_000;
+const MAX_LAMPORTS: u64 = 500_000_000;
+
+/// 0.1 - 0.5 SOL，置信度 0.5 以下取最小金额
+fn sizing(curve: &str) -> ConfidenceSizing {
+    let config = config_with(&[
+        ("CONFIDENCE_SIZING_ENABLED", "true"),
+        ("CONFIDENCE_MIN_BUY_SOL", "0.1"),
+        ("CONFIDENCE_MAX_BUY_SOL", "0.5"),
+        ("CONFIDENCE_SIZING_FLOOR", "0.5"),
+        ("CONFIDENCE_SIZING_CURVE", curve),
+    ])
+    .unwrap();
+    ConfidenceSizing::from_config(&config)
+}
+
+#[test]
+fn disabled_sizing_leaves_amount_alone() {
+    let sizing = ConfidenceSizing::from_config(&config_with(&[]).unwrap());
+    assert_eq!(sizing.size_lamports(0.9), None);
+    assert_eq!(sizing.max_lamports(), 0);
+}
+
+#[test]
+fn confidence_at_or_below_floor_buys_minimum() {
+    for curve in ["linear", "quadratic", "sqrt"] {
+        let sizing = sizing(curve);
+        assert_eq!(sizing.size_lamports(0.0), Some(MIN_LAMPORTS), "{}", curve);
+        assert_eq!(sizing.size_lamports(0.5), Some(MIN_LAMPORTS), "{}", curve);
+    }
+}
+
+#[test]
+fn full_confidence_buys_maximum() {
+    for curve in ["linear", "quadratic", "sqrt"] {
+        let sizing = sizing(curve);
+        assert_eq!(sizing.size_lamports(1.0), Some(MAX_LAMPORTS), "{}", curve);
+        // 超出 1.0 的置信度按 1.0 处理
+        assert_eq!(sizing.size_lamports(1.5), Some(MAX_LAMPORTS), "{}", curve);
+        assert_eq!(sizing.max_lamports(), MAX_LAMPORTS);
+    }
+}
+
+#[test]
+fn curves_map_the_span_above_floor() {
+    // 置信度 0.75 位于 floor 到 1.0 的中点
+    assert_eq!(sizing("linear").size_lamports(0.75), Some(300_000_000));
+    assert_eq!(sizing("quadratic").size_lamports(0.75), Some(200_000_000));
+    // 置信度 0.625 位于四分之一处：sqrt(0.25) = 0.5
+    assert_eq!(sizing("sqrt").size_lamports(0.625), Some(300_000_000));
+    assert_eq!(sizing("linear").size_lamports(0.625), Some(200_000_000));
+}
+
+#[test]
+fn invalid_sizing_config_is_rejected() {
+    let enabled = ("CONFIDENCE_SIZING_ENABLED", "true");
+    assert!(config_with(&[enabled, ("CONFIDENCE_SIZING_CURVE", "cubic")]).is_err());
+    assert!(config_with(&[enabled, ("CONFIDENCE_SIZING_FLOOR", "1.0")]).is_err());
+    assert!(config_with(&[enabled, ("CONFIDENCE_MIN_BUY_SOL", "0.3"), ("CONFIDENCE_MAX_BUY_SOL", "0.2")]).is_err());
+}
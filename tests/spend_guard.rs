@@ -0,0 +1,84 @@
+//! 支出限额守卫测试（不需要网络）
+
+mod common;
+
+use std::sync::Arc;
+
+use solana_sdk::pubkey::Pubkey;
+use solsniper::error::BotError;
+use solsniper::event_feed::EventFeed;
+use solsniper::spend_guard::SpendGuard;
+
+use common::config_with;
+
+const SOL: u64 = 1_000_000_000;
+
+fn guard(max_per_tx_sol: &str, max_per_hour_sol: &str) -> Arc<SpendGuard> {
+    let config = config_with(&[
+        ("MAX_SPEND_PER_TX_SOL", max_per_tx_sol),
+        ("MAX_SPEND_PER_HOUR_SOL", max_per_hour_sol),
+    ])
+    .unwrap();
+    Arc::new(SpendGuard::from_config(&config, Arc::new(EventFeed::from_config(&config))))
+}
+
+fn spent_sol(guard: &SpendGuard) -> f64 {
+    guard.snapshot().spent_last_hour_sol
+}
+
+#[test]
+fn every_signed_attempt_is_charged() {
+    let guard = guard("0", "10");
+    let mut ticket = guard.begin(Pubkey::new_unique());
+    ticket.reserve(SOL, 3).unwrap();
+    assert!((spent_sol(&guard) - 3.0).abs() < 1e-9);
+
+    // 两次尝试都已签名（都可能上链），按总和计
+    ticket.authorize(SOL / 2).unwrap();
+    ticket.signed();
+    ticket.authorize(SOL * 4 / 5).unwrap();
+    ticket.signed();
+    drop(ticket);
+
+    assert!((spent_sol(&guard) - 1.3).abs() < 1e-9);
+}
+
+#[test]
+fn unsigned_reservation_is_released() {
+    let guard = guard("0", "10");
+    let mut ticket = guard.begin(Pubkey::new_unique());
+    ticket.reserve(SOL, 3).unwrap();
+    ticket.authorize(SOL).unwrap();
+    drop(ticket);
+
+    assert_eq!(spent_sol(&guard), 0.0);
+}
+
+#[test]
+fn reservation_covers_every_attempt_against_hourly_cap() {
+    let guard = guard("0", "2");
+
+    // 单次 1 SOL，三次尝试最多 3 SOL
+    let mut ticket = guard.begin(Pubkey::new_unique());
+    assert!(matches!(ticket.reserve(SOL, 3), Err(BotError::SpendLimitExceeded { .. })));
+
+    let mut first = guard.begin(Pubkey::new_unique());
+    first.reserve(SOL / 2, 3).unwrap();
+    let mut second = guard.begin(Pubkey::new_unique());
+    assert!(matches!(second.reserve(SOL * 3 / 5, 1), Err(BotError::SpendLimitExceeded { .. })));
+
+    // 预留范围内的尝试不会在重试中途被拒绝
+    for _ in 0..3 {
+        first.authorize(SOL / 2).unwrap();
+        first.signed();
+    }
+    assert_eq!(guard.snapshot().rejections, 2);
+}
+
+#[test]
+fn per_transaction_cap_applies_to_each_attempt() {
+    let guard = guard("1.5", "0");
+    let mut ticket = guard.begin(Pubkey::new_unique());
+    ticket.reserve(SOL, 3).unwrap();
+    assert!(matches!(ticket.authorize(SOL * 2), Err(BotError::SpendLimitExceeded { .. })));
+}
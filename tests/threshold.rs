@@ -0,0 +1,67 @@
+//! 阈值触发档位解析测试（不需要网络）
+
+mod common;
+
+use solana_sdk::pubkey::Pubkey;
+use solsniper::strategy_overrides::StrategyOverrideTable;
+use solsniper::threshold::{tiers_from_config, ThresholdTier};
+
+use common::config_with;
+
+#[test]
+fn legacy_parameters_produce_single_tier() {
+    let config = config_with(&[("ENABLE_THRESHOLD_TRIGGER", "true")]).unwrap();
+    let tiers = tiers_from_config(&config).unwrap();
+    // 5 SOL × 70% = 3.5 SOL，限制在 0.01 - 1.0 之间
+    assert_eq!(tiers, vec![ThresholdTier { cumulative_buy_sol: 5.0, buy_amount_sol: 1.0 }]);
+}
+
+#[test]
+fn tiers_are_parsed_and_sorted() {
+    let config = config_with(&[
+        ("ENABLE_THRESHOLD_TRIGGER", "true"),
+        ("THRESHOLD_TIERS", "20:0.5,5:0.1"),
+    ])
+    .unwrap();
+    let tiers = tiers_from_config(&config).unwrap();
+    assert_eq!(tiers, vec![
+        ThresholdTier { cumulative_buy_sol: 5.0, buy_amount_sol: 0.1 },
+        ThresholdTier { cumulative_buy_sol: 20.0, buy_amount_sol: 0.5 },
+    ]);
+}
+
+#[test]
+fn invalid_tiers_are_rejected() {
+    assert!(config_with(&[("ENABLE_THRESHOLD_TRIGGER", "true"), ("THRESHOLD_TIERS", "5")]).is_err());
+    assert!(config_with(&[("ENABLE_THRESHOLD_TRIGGER", "true"), ("THRESHOLD_TIERS", "5:0")]).is_err());
+}
+
+#[test]
+fn accumulation_mode_is_validated() {
+    assert!(config_with(&[("ENABLE_THRESHOLD_TRIGGER", "true"), ("THRESHOLD_ACCUMULATION", "lifetime")]).is_ok());
+    assert!(config_with(&[("ENABLE_THRESHOLD_TRIGGER", "true"), ("THRESHOLD_ACCUMULATION", "forever")]).is_err());
+}
+
+#[test]
+fn override_tiers_are_sorted_on_load() {
+    let table = StrategyOverrideTable::from_json(r#"{
+        "unknown_creator": { "threshold_tiers": [
+            { "cumulative_buy_sol": 20, "buy_amount_sol": 0.5 },
+            { "cumulative_buy_sol": 5, "buy_amount_sol": 0.1 }
+        ] }
+    }"#)
+    .unwrap();
+    let rule = table.resolve(&Pubkey::new_unique(), &Pubkey::new_unique()).unwrap();
+    assert_eq!(rule.threshold_tiers.unwrap(), vec![
+        ThresholdTier { cumulative_buy_sol: 5.0, buy_amount_sol: 0.1 },
+        ThresholdTier { cumulative_buy_sol: 20.0, buy_amount_sol: 0.5 },
+    ]);
+}
+
+#[test]
+fn invalid_override_tiers_reject_the_table() {
+    let result = StrategyOverrideTable::from_json(r#"{
+        "unknown_creator": { "threshold_tiers": [{ "cumulative_buy_sol": 0, "buy_amount_sol": 0.1 }] }
+    }"#);
+    assert!(result.is_err());
+}
@@ -0,0 +1,425 @@
+//! 执行器集成测试
+//!
+//! 1. 指令布局（默认运行）- PumpFun 买入 / 卖出指令的账户顺序、读写 / 签名标记、指令数据编码
+//! 2. 本地验证器端到端（`#[ignore]`）- 启动 solana-test-validator，从主网 clone PumpFun 程序、
+//!    Global / fee 账户和一个仍在曲线上的 token，用 LightSpeedBuyExecutor / SolTradeSellExecutor
+//!    真实买入再全部卖出，断言余额变化
+//!
+//! 端到端测试:
+//!
+//! ```text
+//! SOLSNIPER_IT_MINT=<仍在 bonding curve 上的 mint> \
+//!     cargo test --test validator -- --ignored --nocapture
+//! ```
+//!
+//! 需要 PATH 中有 solana-test-validator，并能访问 SOLSNIPER_IT_CLONE_URL（默认 mainnet-beta）；
+//! 未设置 SOLSNIPER_IT_MINT 或找不到 solana-test-validator 时跳过
+
+mod common;
+
+use anyhow::{Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use solsniper::confirmation::ConfirmationTracker;
+use solsniper::dex::pumpfun::{BUY_DISCRIMINATOR, PUMPFUN_PROGRAM_ID, SELL_DISCRIMINATOR};
+use solsniper::dex::{get_ata_with_program, DexAdapter, PoolAccounts, PumpFunAdapter, SwapAccounts};
+use solsniper::event_feed::EventFeed;
+use solsniper::executor::lightspeed_buy::LightSpeedBuyExecutor;
+use solsniper::executor::outbox::TransactionOutbox;
+use solsniper::executor::sol_trade_sell::{SellParams, SolTradeSellExecutor};
+use solsniper::pump_global::{PumpGlobalState, PUMPFUN_GLOBAL};
+use solsniper::rpc_pool::RpcPool;
+use solsniper::signer::SharedSigner;
+use solsniper::spend_guard::SpendGuard;
+
+use common::config_with;
+
+const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+const GLOBAL_VOLUME_ACCUMULATOR: &str = "Hq2wp8uJ9jCPsYgNHex8RtqdvMPfVGoYwjvF1ATiwn2Y";
+const FEE_CONFIG: &str = "8Wf5TiAheLUqBrKXeYg2JtAFFMWtKdG2BSFgqUcPVwTt";
+const FEE_PROGRAM: &str = "pfeeUxB6jkeY1Hxd7CsFCAjcbHA9rWtchMGdZ6VojVZ";
+
+const DEFAULT_CLONE_URL: &str = "https://api.mainnet-beta.solana.com";
+const VALIDATOR_STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
+/// 每笔交易的网络费上限（签名费 + 优先费，测试配置 CU 价格为 0）
+const MAX_TX_FEE_LAMPORTS: u64 = 10_000;
+
+fn pubkey(value: &str) -> Pubkey {
+    Pubkey::from_str(value).unwrap()
+}
+
+fn adapter() -> PumpFunAdapter {
+    PumpFunAdapter::new(Arc::new(PumpGlobalState::new())).unwrap()
+}
+
+/// (pubkey, is_signer, is_writable)
+fn metas(ix: &solana_sdk::instruction::Instruction) -> Vec<(Pubkey, bool, bool)> {
+    ix.accounts.iter().map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable)).collect()
+}
+
+// ==================== 指令布局 ====================
+
+#[test]
+fn pumpfun_buy_instruction_layout() {
+    let dex = adapter();
+    let payer = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let token_program = pubkey(TOKEN_2022_PROGRAM);
+    let pool = dex.derive_pool_accounts(&mint, &token_program, &creator);
+    let user_token_account = dex.user_token_account(&payer, &mint, &token_program);
+
+    let accounts = SwapAccounts { payer, mint, token_program, user_token_account, pool: &pool };
+    let ix = dex.build_buy_ix(&accounts, 123_456_789, 55_000_000).unwrap();
+
+    let program = pubkey(PUMPFUN_PROGRAM_ID);
+    assert_eq!(ix.program_id, program);
+    assert_eq!(ix.data.len(), 24);
+    assert_eq!(ix.data[..8], BUY_DISCRIMINATOR);
+    assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 123_456_789);
+    assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 55_000_000);
+
+    let (event_authority, _) = Pubkey::find_program_address(&[b"__event_authority"], &program);
+    let user_volume_accumulator = dex.derive_user_volume_accumulator(&payer);
+    let fee_recipient = PumpGlobalState::new().fee_recipient();
+
+    assert_eq!(metas(&ix), vec![
+        (*PUMPFUN_GLOBAL, false, false),
+        (fee_recipient, false, true),
+        (mint, false, false),
+        (pool.pool, false, true),
+        (pool.pool_token_account, false, true),
+        (user_token_account, false, true),
+        (payer, true, true),
+        (pubkey(SYSTEM_PROGRAM), false, false),
+        // 买入固定使用 Token v3（对齐 sol-trade-sdk）
+        (pubkey(TOKEN_PROGRAM), false, false),
+        (pool.creator_vault, false, true),
+        (event_authority, false, false),
+        (program, false, false),
+        (pubkey(GLOBAL_VOLUME_ACCUMULATOR), false, true),
+        (user_volume_accumulator, false, true),
+        (pubkey(FEE_CONFIG), false, false),
+        (pubkey(FEE_PROGRAM), false, false),
+    ]);
+
+    assert_eq!(dex.buy_max_sol_cost(&ix), Some(55_000_000));
+}
+
+#[test]
+fn pumpfun_sell_instruction_layout() {
+    let dex = adapter();
+    let payer = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let token_program = pubkey(TOKEN_2022_PROGRAM);
+    let pool = dex.derive_pool_accounts(&mint, &token_program, &creator);
+    let user_token_account = dex.user_token_account(&payer, &mint, &token_program);
+
+    let accounts = SwapAccounts { payer, mint, token_program, user_token_account, pool: &pool };
+    let ix = dex.build_sell_ix(&accounts, 1_000_000, 42).unwrap();
+
+    let program = pubkey(PUMPFUN_PROGRAM_ID);
+    assert_eq!(ix.program_id, program);
+    assert_eq!(ix.data[..8], SELL_DISCRIMINATOR);
+    assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 1_000_000);
+    assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 42);
+
+    let (event_authority, _) = Pubkey::find_program_address(&[b"__event_authority"], &program);
+    let fee_recipient = PumpGlobalState::new().fee_recipient();
+
+    assert_eq!(metas(&ix), vec![
+        (*PUMPFUN_GLOBAL, false, false),
+        (fee_recipient, false, true),
+        (mint, false, false),
+        (pool.pool, false, true),
+        (pool.pool_token_account, false, true),
+        (user_token_account, false, true),
+        (payer, true, true),
+        (pubkey(SYSTEM_PROGRAM), false, false),
+        (pool.creator_vault, false, true),
+        // 卖出使用 mint 实际的 token program
+        (token_program, false, false),
+        (event_authority, false, false),
+        (program, false, false),
+        (pubkey(FEE_CONFIG), false, false),
+        (pubkey(FEE_PROGRAM), false, false),
+    ]);
+
+    // 卖出指令不计入支出
+    assert_eq!(dex.buy_max_sol_cost(&ix), None);
+}
+
+#[test]
+fn pumpfun_pool_accounts_follow_token_program() {
+    let dex = adapter();
+    let mint = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let program = pubkey(PUMPFUN_PROGRAM_ID);
+
+    let (bonding_curve, _) = Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &program);
+    let (creator_vault, _) = Pubkey::find_program_address(&[b"creator-vault", creator.as_ref()], &program);
+
+    for token_program in [pubkey(TOKEN_PROGRAM), pubkey(TOKEN_2022_PROGRAM)] {
+        let pool = dex.derive_pool_accounts(&mint, &token_program, &creator);
+        assert_eq!(pool, PoolAccounts {
+            pool: bonding_curve,
+            pool_token_account: get_ata_with_program(&bonding_curve, &mint, &token_program),
+            creator_vault,
+        });
+    }
+}
+
+// ==================== 本地验证器端到端 ====================
+
+/// 本地 solana-test-validator 进程（drop 时结束进程并删除账本目录）
+struct TestValidator {
+    child: Child,
+    ledger: PathBuf,
+    rpc_url: String,
+}
+
+impl TestValidator {
+    fn available() -> bool {
+        Command::new("solana-test-validator")
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    }
+
+    fn free_port() -> Result<u16> {
+        Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+    }
+
+    /// 启动验证器并从 clone_url clone 程序和账户（maybe_accounts 不存在时跳过）
+    async fn start(
+        clone_url: &str,
+        programs: &[Pubkey],
+        accounts: &[Pubkey],
+        maybe_accounts: &[Pubkey],
+    ) -> Result<Self> {
+        let ledger = std::env::temp_dir().join(format!("solsniper-validator-{}", std::process::id()));
+        let rpc_port = Self::free_port()?;
+        let faucet_port = Self::free_port()?;
+
+        let mut command = Command::new("solana-test-validator");
+        command
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger").arg(&ledger)
+            .arg("--rpc-port").arg(rpc_port.to_string())
+            .arg("--faucet-port").arg(faucet_port.to_string())
+            .arg("--url").arg(clone_url);
+        for program in programs {
+            command.arg("--clone-upgradeable-program").arg(program.to_string());
+        }
+        for account in accounts {
+            command.arg("--clone").arg(account.to_string());
+        }
+        for account in maybe_accounts {
+            command.arg("--maybe-clone").arg(account.to_string());
+        }
+
+        let child = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .context("Failed to spawn solana-test-validator")?;
+        let validator = Self {
+            child,
+            ledger,
+            rpc_url: format!("http://127.0.0.1:{}", rpc_port),
+        };
+
+        // clone 需要从远端拉取账户，启动可能较慢
+        let rpc = validator.rpc_client();
+        let started = Instant::now();
+        while rpc.get_health().await.is_err() {
+            if started.elapsed() > VALIDATOR_STARTUP_TIMEOUT {
+                anyhow::bail!("solana-test-validator did not become healthy within {:?}", VALIDATOR_STARTUP_TIMEOUT);
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        Ok(validator)
+    }
+
+    fn rpc_client(&self) -> RpcClient {
+        RpcClient::new_with_commitment(self.rpc_url.clone(), CommitmentConfig::confirmed())
+    }
+}
+
+impl Drop for TestValidator {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.ledger);
+    }
+}
+
+async fn wait_confirmed(rpc: &RpcClient, signature: &Signature) -> Result<()> {
+    let started = Instant::now();
+    while !rpc.confirm_transaction(signature).await? {
+        if started.elapsed() > Duration::from_secs(30) {
+            anyhow::bail!("transaction not confirmed: {}", signature);
+        }
+        tokio::time::sleep(Duration::from_millis(300)).await;
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[ignore = "needs solana-test-validator and network access to clone mainnet accounts"]
+async fn buy_and_sell_against_local_validator() -> Result<()> {
+    let Ok(mint) = std::env::var("SOLSNIPER_IT_MINT") else {
+        eprintln!("SOLSNIPER_IT_MINT not set, skipping");
+        return Ok(());
+    };
+    if !TestValidator::available() {
+        eprintln!("solana-test-validator not found in PATH, skipping");
+        return Ok(());
+    }
+    let mint = Pubkey::from_str(&mint).context("Invalid SOLSNIPER_IT_MINT")?;
+    let clone_url = std::env::var("SOLSNIPER_IT_CLONE_URL").unwrap_or_else(|_| DEFAULT_CLONE_URL.to_string());
+
+    // 1. 从源集群读取需要 clone 的账户
+    let source = RpcClient::new_with_commitment(clone_url.clone(), CommitmentConfig::confirmed());
+    let source_global = PumpGlobalState::new();
+    source_global.refresh(&source).await?;
+    let dex = adapter();
+    let token_program = source.get_account(&mint).await.context("Failed to read mint")?.owner;
+    let curve = dex
+        .decode_pool(&source.get_account_data(&dex.derive_pool(&mint)).await?)
+        .context("Failed to decode bonding curve")?;
+    anyhow::ensure!(!curve.complete, "bonding curve of {} is complete, pick a mint still on the curve", mint);
+    let pool = dex.derive_pool_accounts(&mint, &token_program, &curve.creator);
+
+    // 2. 启动本地验证器
+    let validator = TestValidator::start(
+        &clone_url,
+        &[pubkey(PUMPFUN_PROGRAM_ID), pubkey(FEE_PROGRAM)],
+        &[
+            *PUMPFUN_GLOBAL,
+            pubkey(GLOBAL_VOLUME_ACCUMULATOR),
+            pubkey(FEE_CONFIG),
+            source_global.fee_recipient(),
+            mint,
+            pool.pool,
+            pool.pool_token_account,
+        ],
+        &[pool.creator_vault],
+    )
+    .await?;
+    let rpc = validator.rpc_client();
+
+    // 3. 测试钱包
+    let payer = Keypair::new();
+    let airdrop = rpc.request_airdrop(&payer.pubkey(), 2_000_000_000).await?;
+    wait_confirmed(&rpc, &airdrop).await?;
+
+    // 4. 按生产路径组装执行器
+    let outbox_path = validator.ledger.join("outbox.json").display().to_string();
+    let payer_key = payer.to_base58_string();
+    let config = Arc::new(config_with(&[
+        ("RPC_ENDPOINT", validator.rpc_url.as_str()),
+        ("RPC_LIGHTSPEED_ENDPOINT", validator.rpc_url.as_str()),
+        ("WALLET_PRIVATE_KEY", payer_key.as_str()),
+        ("OUTBOX_PATH", outbox_path.as_str()),
+    ])?);
+    let signer: SharedSigner = Arc::new(payer.insecure_clone());
+    let rpc_pool = Arc::new(RpcPool::from_config(&config));
+    let confirmations = Arc::new(ConfirmationTracker::new(
+        config.confirmation_fallback_poll_ms,
+        config.get_confirm_commitment(),
+        config.get_feed_commitment(),
+    ));
+    let outbox = Arc::new(TransactionOutbox::open(config.outbox_path.as_deref())?);
+    let pump_global = Arc::new(PumpGlobalState::new());
+    pump_global.refresh(&rpc).await?;
+    let dex: Arc<dyn DexAdapter> = Arc::new(PumpFunAdapter::new(pump_global)?);
+    let spend_guard = Arc::new(SpendGuard::from_config(&config, Arc::new(EventFeed::from_config(&config))));
+
+    let buyer = LightSpeedBuyExecutor::new(
+        config.clone(),
+        signer.clone(),
+        confirmations.clone(),
+        outbox.clone(),
+        rpc_pool.clone(),
+        dex.clone(),
+        spend_guard.clone(),
+    )?;
+    let seller = SolTradeSellExecutor::new(
+        config.clone(),
+        signer,
+        confirmations,
+        outbox,
+        rpc_pool,
+        dex.clone(),
+        spend_guard,
+    )?;
+
+    // 5. 买入
+    let sol_amount = config.get_snipe_amount_lamports();
+    let max_sol_cost = sol_amount + sol_amount * (config.slippage_percent * 100.0) as u64 / 10_000;
+    let balance_before = rpc.get_balance(&payer.pubkey()).await?;
+
+    let receipt = buyer.execute_buy(&mint, &pool.pool, sol_amount, None, false).await?;
+    wait_confirmed(&rpc, &receipt.signature).await?;
+    assert_eq!(receipt.pool, pool);
+    assert_eq!(receipt.creator, curve.creator);
+
+    let user_token_account = dex.user_token_account(&payer.pubkey(), &mint, &token_program);
+    let ata_rent = rpc.get_balance(&user_token_account).await?;
+    let tokens = seller.query_token_balance(&mint).await?;
+    let balance_after_buy = rpc.get_balance(&payer.pubkey()).await?;
+    let spent_on_curve = balance_before - balance_after_buy - ata_rent;
+
+    assert!(ata_rent > 0, "buy must create the user token account");
+    assert!(tokens > 0, "buy must credit tokens");
+    // 曲线扣款不超过 max_sol_cost，且不低于扣除滑点后的买入金额
+    assert!(spent_on_curve <= max_sol_cost + MAX_TX_FEE_LAMPORTS,
+        "spent {} lamports on the curve, max_sol_cost {}", spent_on_curve, max_sol_cost);
+    assert!(spent_on_curve >= sol_amount * 95 / 100,
+        "spent {} lamports on the curve for a {} lamport buy", spent_on_curve, sol_amount);
+
+    // 6. 全部卖出并关闭 token 账户
+    let curve_after_buy = dex
+        .decode_pool(&rpc.get_account_data(&pool.pool).await?)
+        .context("Failed to decode bonding curve")?;
+    let expected = dex.quote_sell(&curve_after_buy, tokens).amount_out;
+
+    let signature = seller.execute_sell(SellParams {
+        mint,
+        input_token_amount: tokens,
+        slippage_basis_points: Some(500),
+        wait_transaction_confirmed: true,
+        close_token_account: true,
+        pool: receipt.pool,
+    }).await?.signature;
+    wait_confirmed(&rpc, &signature).await?;
+
+    let balance_after_sell = rpc.get_balance(&payer.pubkey()).await?;
+    let received = balance_after_sell - balance_after_buy;
+
+    assert_eq!(seller.query_token_balance(&mint).await?, 0);
+    assert!(rpc.get_account_with_commitment(&user_token_account, CommitmentConfig::confirmed()).await?.value.is_none(),
+        "sell must close the user token account");
+    // 卖出所得 + 退回的 ATA 租金，与报价相比最多差 5% 滑点和网络费
+    assert!(received + MAX_TX_FEE_LAMPORTS >= ata_rent + expected * 95 / 100,
+        "received {} lamports, expected about {} + {} rent", received, expected, ata_rent);
+    // 买卖一轮扣除手续费后必然亏损
+    assert!(balance_after_sell < balance_before);
+
+    Ok(())
+}